@@ -0,0 +1,87 @@
+//! 组合条件基准测试
+//!
+//! 嵌套的 AND/OR 条件树中多处引用同一个昂贵的叶子条件，
+//! 对比共享叶子（单次请求内缓存结果）与各自独立叶子（每处都重新评估）的耗时
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use limiteron::matchers::{
+    CompositeCondition, ConditionEvaluator, LogicalOperator, MatchCondition, RequestContext,
+};
+use std::sync::Arc;
+
+/// 叶子条件被引用的次数
+const REFERENCES: usize = 8;
+
+/// 模拟昂贵的叶子条件：对请求头做多轮哈希
+fn expensive_leaf() -> Arc<dyn ConditionEvaluator> {
+    Arc::new(MatchCondition::Custom(Arc::new(
+        |context: &RequestContext| -> bool {
+            let value = context
+                .get_header("X-Token")
+                .map(String::as_str)
+                .unwrap_or("");
+            let mut hash = 0xcbf2_9ce4_8422_2325u64;
+            for _ in 0..200 {
+                for byte in value.bytes() {
+                    hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+            hash.is_multiple_of(2)
+        },
+    )))
+}
+
+/// 构建 OR(AND(leaf, NOT(user)), ...) 的嵌套树，`leaf` 为每个分支提供叶子条件
+fn tree(mut leaf: impl FnMut() -> Arc<dyn ConditionEvaluator>) -> CompositeCondition {
+    let user: Arc<dyn ConditionEvaluator> =
+        Arc::new(MatchCondition::User(vec!["admin".to_string()]));
+    let branches = (0..REFERENCES)
+        .map(|_| -> Arc<dyn ConditionEvaluator> {
+            Arc::new(CompositeCondition {
+                conditions: vec![
+                    leaf(),
+                    Arc::new(CompositeCondition {
+                        conditions: vec![user.clone()],
+                        operator: LogicalOperator::Not,
+                    }),
+                ],
+                operator: LogicalOperator::And,
+            })
+        })
+        .collect();
+    CompositeCondition {
+        conditions: branches,
+        operator: LogicalOperator::Or,
+    }
+}
+
+fn bench_shared_leaf(c: &mut Criterion) {
+    let leaf = expensive_leaf();
+    let shared = tree(|| leaf.clone());
+    let distinct = tree(expensive_leaf);
+
+    // 找一个让叶子为假的请求，迫使 OR 评估所有分支
+    let context = (0..)
+        .map(|i| {
+            RequestContext::new()
+                .with_header("X-User-Id", "user1")
+                .with_header("X-Token", &format!("token-{}", i))
+        })
+        .find(|context| !leaf.evaluate(context))
+        .unwrap();
+
+    let mut group = c.benchmark_group("composite_condition_shared_leaf");
+
+    group.bench_function("distinct_leaves", |b| {
+        b.iter(|| black_box(distinct.evaluate(black_box(&context))));
+    });
+
+    group.bench_function("shared_leaf_cached", |b| {
+        b.iter(|| black_box(shared.evaluate(black_box(&context))));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_shared_leaf);
+criterion_main!(benches);
@@ -0,0 +1,106 @@
+//! 限流器管理器并发基准测试
+//!
+//! 对比单锁映射与分片映射在 32 个并发任务访问不同 key 时的吞吐量
+
+use ahash::AHashMap;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use limiteron::{limiter_manager::LimiterManager, limiters::TokenBucketLimiter};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const TASKS: usize = 32;
+const LOOKUPS_PER_TASK: usize = 1_000;
+
+/// 单锁映射（分片前的实现方式），作为对照组
+struct SingleLockManager {
+    rate_limiters: Mutex<AHashMap<String, Arc<TokenBucketLimiter>>>,
+}
+
+impl SingleLockManager {
+    fn get_rate_limiter(
+        &self,
+        key: &str,
+        capacity: u64,
+        refill_rate: u64,
+    ) -> Arc<TokenBucketLimiter> {
+        let mut limiters = self.rate_limiters.lock();
+        if let Some(limiter) = limiters.get(key) {
+            return limiter.clone();
+        }
+        let limiter = Arc::new(TokenBucketLimiter::new(capacity, refill_rate));
+        limiters.insert(key.to_string(), limiter.clone());
+        limiter
+    }
+}
+
+/// 每个任务使用各自的 key 集合
+fn task_keys() -> Arc<Vec<Vec<String>>> {
+    Arc::new(
+        (0..TASKS)
+            .map(|task| (0..16).map(|i| format!("task{}:key{}", task, i)).collect())
+            .collect(),
+    )
+}
+
+/// 基准测试：32 个并发任务访问不同 key
+fn bench_concurrent_distinct_keys(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let keys = task_keys();
+
+    let mut group = c.benchmark_group("limiter_manager_32_tasks");
+    group.throughput(Throughput::Elements((TASKS * LOOKUPS_PER_TASK) as u64));
+
+    let single = Arc::new(SingleLockManager {
+        rate_limiters: Mutex::new(AHashMap::new()),
+    });
+    group.bench_function("single_lock", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let handles: Vec<_> = (0..TASKS)
+                    .map(|task| {
+                        let manager = single.clone();
+                        let keys = keys.clone();
+                        tokio::spawn(async move {
+                            let keys = &keys[task];
+                            for i in 0..LOOKUPS_PER_TASK {
+                                black_box(manager.get_rate_limiter(&keys[i % keys.len()], 100, 10));
+                            }
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.await.unwrap();
+                }
+            });
+        });
+    });
+
+    let sharded = Arc::new(LimiterManager::new());
+    group.bench_function("sharded", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let handles: Vec<_> = (0..TASKS)
+                    .map(|task| {
+                        let manager = sharded.clone();
+                        let keys = keys.clone();
+                        tokio::spawn(async move {
+                            let keys = &keys[task];
+                            for i in 0..LOOKUPS_PER_TASK {
+                                black_box(manager.get_rate_limiter(&keys[i % keys.len()], 100, 10));
+                            }
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.await.unwrap();
+                }
+            });
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_concurrent_distinct_keys);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! 规则匹配器基准测试
+//!
+//! 对比 1000 条 IP 范围规则下，前缀索引匹配与逐条线性评估的耗时
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use limiteron::matchers::{MatchCondition, RequestContext, Rule, RuleMatcher};
+use std::sync::Arc;
+
+const RULES: usize = 1_000;
+
+/// 每条规则覆盖一个不同的 /24 网段
+fn ip_rules() -> Vec<Rule> {
+    (0..RULES)
+        .map(|i| Rule {
+            id: format!("rule{}", i),
+            name: format!("Rule {}", i),
+            priority: (i % 100) as u16,
+            condition: Arc::new(MatchCondition::Ip(vec![format!(
+                "10.{}.{}.0/24",
+                i / 256,
+                i % 256
+            )
+            .parse()
+            .unwrap()])),
+            enabled: true,
+        })
+        .collect()
+}
+
+/// 命中、末尾命中与未命中三类请求
+fn contexts() -> Vec<RequestContext> {
+    ["10.0.5.20", "10.3.231.7", "192.168.1.1"]
+        .iter()
+        .map(|ip| RequestContext::new().with_client_ip(ip))
+        .collect()
+}
+
+fn bench_ip_rules(c: &mut Criterion) {
+    let rules = ip_rules();
+    let matcher = RuleMatcher::new(rules.clone());
+    let contexts = contexts();
+
+    let mut group = c.benchmark_group("rule_matcher_1000_ip_rules");
+
+    // 改造前的匹配方式，作为对照组
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| {
+            for context in &contexts {
+                black_box(
+                    rules
+                        .iter()
+                        .find(|rule| rule.enabled && rule.condition.evaluate(context)),
+                );
+            }
+        });
+    });
+
+    group.bench_function("prefix_index", |b| {
+        b.iter(|| {
+            for context in &contexts {
+                black_box(matcher.matches(context));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ip_rules);
+criterion_main!(benches);
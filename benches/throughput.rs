@@ -89,6 +89,7 @@ fn bench_governor_throughput(c: &mut Criterion) {
                 refill_rate: 10000,
             }],
             action: Default::default(),
+            ..Default::default()
         }],
     };
 
@@ -112,6 +113,7 @@ fn bench_governor_throughput(c: &mut Criterion) {
         method: "GET".to_string(),
         client_ip: Some("192.168.1.1".to_string()),
         query_params: ahash::AHashMap::new(),
+        ..Default::default()
     };
 
     let mut group = c.benchmark_group("governor_throughput");
@@ -0,0 +1,119 @@
+//! gRPC 拦截器示例
+//!
+//! 本示例演示如何用 `FlowGuardInterceptor` 保护 tonic 服务：
+//! 标识符从 gRPC 元数据提取，超限时返回 RESOURCE_EXHAUSTED。
+//!
+//! 运行方式: `cargo run --example grpc_interceptor --features grpc`
+
+use limiteron::{
+    config::{ActionConfig, FlowControlConfig, GlobalConfig, LimiterConfig, Matcher, Rule},
+    governor::Governor,
+    grpc::FlowGuardInterceptor,
+    storage::MemoryStorage,
+};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tonic::body::BoxBody;
+use tonic::codegen::{empty_body, http, BoxFuture, Context, InterceptedService, Poll, Service};
+
+/// 最简单的 gRPC 服务：总是返回 OK
+///
+/// 实际项目中通常是 tonic-build 生成的 `XxxServer`，
+/// 可直接使用 `XxxServer::with_interceptor(service, interceptor)`。
+#[derive(Clone)]
+struct EchoService;
+
+impl<B: Send + 'static> Service<http::Request<B>> for EchoService {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _request: http::Request<B>) -> Self::Future {
+        Box::pin(async {
+            Ok(http::Response::builder()
+                .header("grpc-status", "0")
+                .body(empty_body())
+                .unwrap())
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== gRPC 拦截器示例 ===\n");
+
+    // 每个用户 60 秒内最多 3 次调用
+    let config = FlowControlConfig {
+        version: "1.0".to_string(),
+        global: GlobalConfig {
+            storage: "memory".to_string(),
+            cache: "memory".to_string(),
+            metrics: "prometheus".to_string(),
+        },
+        rules: vec![Rule {
+            id: "grpc_rule".to_string(),
+            name: "gRPC Rule".to_string(),
+            priority: 10,
+            matchers: vec![Matcher::User {
+                user_ids: vec!["*".to_string()],
+            }],
+            limiters: vec![LimiterConfig::FixedWindow {
+                window_size: "60s".to_string(),
+                max_requests: 3,
+            }],
+            action: ActionConfig {
+                on_exceed: "reject".to_string(),
+                ban: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        }],
+    };
+
+    let governor = Arc::new(
+        Governor::new(
+            config,
+            Arc::new(MemoryStorage::new()),
+            Arc::new(MemoryStorage::new()),
+            #[cfg(feature = "monitoring")]
+            None,
+            #[cfg(feature = "telemetry")]
+            None,
+        )
+        .await?,
+    );
+
+    // 将 caller-id 元数据映射为 Governor 默认提取的 X-User-Id
+    let interceptor =
+        FlowGuardInterceptor::new(governor).with_metadata_mapping("caller-id", "x-user-id");
+    let mut service = InterceptedService::new(EchoService, interceptor);
+
+    for i in 1..=5 {
+        let request = http::Request::builder()
+            .uri("/echo.Echo/Ping")
+            .header("caller-id", "alice")
+            .body(empty_body())?;
+        let response = service.call(request).await?;
+        let status = response
+            .headers()
+            .get("grpc-status")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("0");
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("-");
+        println!(
+            "  调用 {}: grpc-status={} retry-after={}",
+            i, status, retry_after
+        );
+    }
+
+    println!("\n=== 示例完成 ===");
+    Ok(())
+}
@@ -12,6 +12,63 @@ use quote::{quote, quote_spanned};
 use syn::{parse_macro_input, ItemFn};
 
 /// 流量控制属性宏
+///
+/// # 并发限制
+///
+/// `concurrency = N` 限制同时执行的调用数。默认在并发已满时最多等待 50ms；
+/// 指定 `acquire_timeout = "100ms"`（支持 `ms`、`s`、`m`、`h`）时改为最多等待该时长，
+/// 超时返回 `ConcurrencyLimitExceeded`。
+///
+/// # 软限流
+///
+/// `on_exceed = "delay"` 时超出 `rate` 的调用不返回错误，而是等待 `delay_ms`
+/// 毫秒（默认 100）后继续执行；配额与并发限制仍按拒绝处理。
+///
+/// # 按 HTTP 方法限流
+///
+/// `methods = ["POST", "PUT"]` 只对列出的方法执行检查，`method = <表达式>` 给出当前
+/// 请求的方法（如 `method = req.method()`），两者需同时指定。方法名忽略大小写比较，
+/// 其他方法的调用不消耗额度。
+///
+/// # 作用域
+///
+/// 默认（`scope = "identifier"`）按 `identifiers` 求值结果区分限流器，每个调用方拥有独立的额度；
+/// 未指定 `identifiers` 时所有调用共享键 `default`。
+///
+/// `scope = "global"` 时限流键不含标识符，函数的所有调用无论来自哪个调用方都共享
+/// **同一个**限流器，适合保护下游服务的总吞吐（如 `rate = "1000/s", scope = "global"`）。
+/// 单个调用方即可耗尽全部额度，因此不能与 `identifiers` 或 `key_strategy` 同时使用。
+///
+/// # 标识符回退
+///
+/// 未指定 `identifiers` 时所有调用方共享键 `default`，通常并非预期。`fallback` 指定
+/// 标识符缺失（未配置或求值结果全部为空字符串）时的回退顺序：
+///
+/// - `fallback = "ip"`：依次尝试 `identifiers`、`client_ip = <表达式>` 给出的客户端 IP，
+///   最后按请求回退；需要同时指定 `client_ip`。
+/// - `fallback = "uuid"`：依次尝试 `identifiers`，最后按请求回退。
+///
+/// 按请求回退时本次调用使用一个新建的限流器，相当于以随机 UUID 为键：不与任何调用方
+/// 共享额度（只拒绝单次成本超过容量的调用），也不会缓存到 `GLOBAL_LIMITER_MANAGER` 中，
+/// 因此不会让所有调用方落入同一个桶。
+///
+/// 客户端 IP 总是按 `key_strategy = "hash"` 清洗（IPv6 地址中的 `:` 不会被直接去掉），
+/// 清洗后再加上 `ip:` 前缀；标识符清洗后不含 `:`，因此按 IP 回退得到的键不会与
+/// `identifiers` 的取值冲突。
+///
+/// # 限流键
+///
+/// 标识符经 `limiteron::limiter_manager::sanitize_key` 清洗后作为限流键，与启用
+/// `Governor::set_limiter_key_strategy` 的 Governor 使用同一实现。默认 `key_strategy = "truncate"` 截断到 128 个字符；
+/// `key_strategy = "hash"` 在标识符含非法字符或超长时附加原始值的哈希，
+/// 共享同一前缀的长标识符不会落入同一个桶。
+///
+/// # 异步与同步函数
+///
+/// - `async fn`：限流检查直接在函数体之前 `.await`，使用调用方所在的运行时。
+/// - 同步 `fn`：需要显式指定 `runtime = "current_thread"`，检查会在每个线程
+///   惰性创建的 current_thread 运行时上阻塞执行；若调用时已处于 Tokio 运行时中，
+///   函数返回错误而不是跳过检查。未指定 `runtime` 时生成 `compile_error!`。
 #[proc_macro_attribute]
 pub fn flow_control(args: TokenStream, input: TokenStream) -> TokenStream {
     let input_fn = parse_macro_input!(input as ItemFn);
@@ -36,9 +93,29 @@ struct FlowControlConfig {
     rate: Option<RateLimit>,
     quota: Option<QuotaLimit>,
     concurrency: Option<u32>,
-    identifiers: Vec<String>,
+    /// 获取并发许可的最长等待时间（毫秒）
+    acquire_timeout: Option<u64>,
+    identifiers: Vec<syn::Expr>,
     on_exceed: String,
+    /// 软限流时超出速率限制的调用等待的时长（毫秒）
+    delay_ms: Option<u64>,
     reject_message: String,
+    cost: Option<u64>,
+    cost_fn: Option<String>,
+    runtime: Option<String>,
+    on_reject: Option<String>,
+    /// 只对这些 HTTP 方法执行检查，为空时不区分方法
+    methods: Vec<String>,
+    /// 求值为当前请求 HTTP 方法的表达式
+    method: Option<syn::Expr>,
+    /// 限流键清洗策略（"truncate" 或 "hash"）
+    key_strategy: Option<String>,
+    /// 限流作用域（"identifier" 或 "global"）
+    scope: Option<String>,
+    /// 标识符缺失时的回退方式（"ip" 或 "uuid"）
+    fallback: Option<String>,
+    /// 求值为客户端 IP 的表达式，`fallback = "ip"` 时使用
+    client_ip: Option<syn::Expr>,
 }
 
 impl FlowControlConfig {
@@ -88,6 +165,14 @@ impl FlowControlConfig {
                                 }
                             }
                         }
+                        "acquire_timeout" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    config.acquire_timeout =
+                                        Some(parse_duration_millis(&lit.value())?);
+                                }
+                            }
+                        }
                         "on_exceed" => {
                             if let syn::Expr::Lit(expr_lit) = nv.value {
                                 if let syn::Lit::Str(lit) = expr_lit.lit {
@@ -95,6 +180,19 @@ impl FlowControlConfig {
                                 }
                             }
                         }
+                        "delay_ms" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Int(lit) = expr_lit.lit {
+                                    let delay_ms: u64 = lit
+                                        .base10_parse()
+                                        .map_err(|e| format!("Invalid delay_ms: {}", e))?;
+                                    if delay_ms == 0 {
+                                        return Err("delay_ms must be greater than 0".to_string());
+                                    }
+                                    config.delay_ms = Some(delay_ms);
+                                }
+                            }
+                        }
                         "reject_message" => {
                             if let syn::Expr::Lit(expr_lit) = nv.value {
                                 if let syn::Lit::Str(lit) = expr_lit.lit {
@@ -102,6 +200,147 @@ impl FlowControlConfig {
                                 }
                             }
                         }
+                        "cost" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Int(lit) = expr_lit.lit {
+                                    let cost: u64 = lit
+                                        .base10_parse()
+                                        .map_err(|e| format!("Invalid cost: {}", e))?;
+                                    if cost == 0 {
+                                        return Err("cost must be greater than 0".to_string());
+                                    }
+                                    config.cost = Some(cost);
+                                }
+                            }
+                        }
+                        "cost_fn" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    let path = lit.value();
+                                    syn::parse_str::<syn::Path>(&path).map_err(|e| {
+                                        format!("Invalid cost_fn path '{}': {}", path, e)
+                                    })?;
+                                    config.cost_fn = Some(path);
+                                }
+                            }
+                        }
+                        "on_reject" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    let path = lit.value();
+                                    syn::parse_str::<syn::Path>(&path).map_err(|e| {
+                                        format!("Invalid on_reject path '{}': {}", path, e)
+                                    })?;
+                                    config.on_reject = Some(path);
+                                }
+                            }
+                        }
+                        "methods" => match nv.value {
+                            syn::Expr::Array(array) => {
+                                for elem in array.elems {
+                                    match elem {
+                                        syn::Expr::Lit(syn::ExprLit {
+                                            lit: syn::Lit::Str(lit),
+                                            ..
+                                        }) => {
+                                            let method = lit.value();
+                                            if method.is_empty()
+                                                || !method.chars().all(|c| c.is_ascii_alphabetic())
+                                            {
+                                                return Err(format!(
+                                                    "Invalid HTTP method: '{}'",
+                                                    method
+                                                ));
+                                            }
+                                            config.methods.push(method.to_ascii_uppercase());
+                                        }
+                                        _ => {
+                                            return Err(
+                                                "methods expects string literals, e.g. methods = [\"POST\"]"
+                                                    .to_string(),
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+                            _ => {
+                                return Err("methods expects an array, e.g. methods = [\"POST\"]"
+                                    .to_string());
+                            }
+                        },
+                        "method" => {
+                            config.method = Some(nv.value);
+                        }
+                        "identifiers" => match nv.value {
+                            syn::Expr::Array(array) => {
+                                config.identifiers.extend(array.elems);
+                            }
+                            _ => {
+                                return Err(
+                                    "identifiers expects an array, e.g. identifiers = [user_id]"
+                                        .to_string(),
+                                );
+                            }
+                        },
+                        "key_strategy" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    let strategy = lit.value();
+                                    if strategy != "truncate" && strategy != "hash" {
+                                        return Err(format!(
+                                            "Invalid key_strategy: '{}', expected 'truncate' or 'hash'",
+                                            strategy
+                                        ));
+                                    }
+                                    config.key_strategy = Some(strategy);
+                                }
+                            }
+                        }
+                        "fallback" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    let fallback = lit.value();
+                                    if fallback != "ip" && fallback != "uuid" {
+                                        return Err(format!(
+                                            "Invalid fallback: '{}', expected 'ip' or 'uuid'",
+                                            fallback
+                                        ));
+                                    }
+                                    config.fallback = Some(fallback);
+                                }
+                            }
+                        }
+                        "client_ip" => {
+                            config.client_ip = Some(nv.value);
+                        }
+                        "scope" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    let scope = lit.value();
+                                    if scope != "identifier" && scope != "global" {
+                                        return Err(format!(
+                                            "Invalid scope: '{}', expected 'identifier' or 'global'",
+                                            scope
+                                        ));
+                                    }
+                                    config.scope = Some(scope);
+                                }
+                            }
+                        }
+                        "runtime" => {
+                            if let syn::Expr::Lit(expr_lit) = nv.value {
+                                if let syn::Lit::Str(lit) = expr_lit.lit {
+                                    let runtime = lit.value();
+                                    if runtime != "current_thread" {
+                                        return Err(format!(
+                                            "Invalid runtime: '{}', expected 'current_thread'",
+                                            runtime
+                                        ));
+                                    }
+                                    config.runtime = Some(runtime);
+                                }
+                            }
+                        }
                         _ => {
                             return Err(format!("Unknown attribute: {}", ident_str));
                         }
@@ -116,13 +355,11 @@ impl FlowControlConfig {
 
                     if ident_str == "identifiers" {
                         let tokens = list.tokens;
-                        let parsed = Punctuated::<syn::LitStr, Token![,]>::parse_terminated
+                        let parsed = Punctuated::<syn::Expr, Token![,]>::parse_terminated
                             .parse2(tokens)
                             .map_err(|e| format!("Failed to parse identifiers: {}", e))?;
 
-                        for lit in parsed {
-                            config.identifiers.push(lit.value());
-                        }
+                        config.identifiers.extend(parsed);
                     }
                 }
                 _ => {
@@ -131,9 +368,57 @@ impl FlowControlConfig {
             }
         }
 
+        if config.acquire_timeout.is_some() && config.concurrency.is_none() {
+            return Err("acquire_timeout requires concurrency".to_string());
+        }
+
+        if config.scope.as_deref() == Some("global") {
+            if !config.identifiers.is_empty() {
+                return Err("scope = \"global\" cannot be used with identifiers".to_string());
+            }
+            if config.key_strategy.is_some() {
+                return Err("scope = \"global\" cannot be used with key_strategy".to_string());
+            }
+            if config.fallback.is_some() {
+                return Err("scope = \"global\" cannot be used with fallback".to_string());
+            }
+        }
+
+        match (config.fallback.as_deref(), config.client_ip.is_some()) {
+            (Some("ip"), false) => {
+                return Err(
+                    "fallback = \"ip\" requires client_ip = <expr> giving the client IP"
+                        .to_string(),
+                )
+            }
+            (Some("ip"), true) => {}
+            (_, true) => return Err("client_ip requires fallback = \"ip\"".to_string()),
+            _ => {}
+        }
+
+        if config.cost.is_some() && config.cost_fn.is_some() {
+            return Err("cost and cost_fn cannot be used together".to_string());
+        }
+
+        match (config.methods.is_empty(), config.method.is_some()) {
+            (false, false) => {
+                return Err("methods requires method = <expr> giving the request method".to_string())
+            }
+            (true, true) => return Err("method requires methods".to_string()),
+            _ => {}
+        }
+
         if config.on_exceed.is_empty() {
             config.on_exceed = "reject".to_string();
         }
+        if config.on_exceed == "delay" {
+            if config.rate.is_none() {
+                return Err("on_exceed = \"delay\" requires rate".to_string());
+            }
+            config.delay_ms.get_or_insert(DEFAULT_DELAY_MS);
+        } else if config.delay_ms.is_some() {
+            return Err("delay_ms requires on_exceed = \"delay\"".to_string());
+        }
         if config.reject_message.is_empty() {
             config.reject_message = "Rate limit exceeded".to_string();
         }
@@ -142,6 +427,40 @@ impl FlowControlConfig {
     }
 }
 
+/// 软限流默认等待时长（毫秒）
+const DEFAULT_DELAY_MS: u64 = 100;
+
+/// 解析 `100ms`、`2s`、`1m`、`1h` 形式的时长，返回毫秒数
+fn parse_duration_millis(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (amount, multiplier) = if let Some(amount) = s.strip_suffix("ms") {
+        (amount, 1)
+    } else if let Some(amount) = s.strip_suffix('s') {
+        (amount, 1_000)
+    } else if let Some(amount) = s.strip_suffix('m') {
+        (amount, 60_000)
+    } else if let Some(amount) = s.strip_suffix('h') {
+        (amount, 3_600_000)
+    } else {
+        return Err(format!(
+            "Invalid duration: '{}', expected a unit of ms, s, m or h (e.g., '100ms')",
+            s
+        ));
+    };
+
+    let amount: u64 = amount
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid duration amount: '{}'", s))?;
+    let millis = amount
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Duration too large: '{}'", s))?;
+    if millis == 0 {
+        return Err("acquire_timeout must be greater than 0".to_string());
+    }
+    Ok(millis)
+}
+
 /// 速率限制配置
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -246,22 +565,107 @@ fn generate_flow_control(
 
     let reject_message = config.reject_message.clone();
 
+    // 请求成本：cost_fn 在限流检查之前求值，参数与被注解函数一致
+    let cost_expr = if let Some(ref cost_fn) = config.cost_fn {
+        let cost_fn: syn::Path = syn::parse_str(cost_fn)
+            .map_err(|e| format!("Invalid cost_fn path '{}': {}", cost_fn, e))?;
+        let args = fn_inputs.iter().filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => {
+                    let ident = &pat_ident.ident;
+                    Some(quote!(::core::clone::Clone::clone(&#ident)))
+                }
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        });
+        quote!({
+            let cost: u64 = #cost_fn(#(#args),*);
+            cost
+        })
+    } else {
+        let cost = config.cost.unwrap_or(1);
+        quote!(#cost)
+    };
+    // 仅在有速率/配额检查时才需要计算成本，避免生成未使用的变量
+    let cost_binding = if config.rate.is_some() || config.quota.is_some() {
+        quote!(let cost: u64 = #cost_expr;)
+    } else {
+        quote!()
+    };
+
+    let key_strategy = match config.key_strategy.as_deref() {
+        Some("hash") => quote!(limiteron::limiter_manager::KeyStrategy::Hash),
+        _ => quote!(limiteron::limiter_manager::KeyStrategy::Truncate),
+    };
+    let is_global = config.scope.as_deref() == Some("global");
+    // 配置回退链时标识符为 Option<String>（已清洗的键），回退链落空（None）时
+    // 本次调用使用不进入 GLOBAL_LIMITER_MANAGER 的一次性限流器
+    let optional_identifier = config.fallback.is_some();
+    // 全局作用域的键不含标识符，被注解函数的所有调用共享同一个限流器
+    let limiter_key = |kind: &str| {
+        let fn_name_str = fn_name.to_string();
+        if is_global {
+            let key = format!("{}:{}", kind, fn_name_str);
+            quote!(#key.to_string())
+        } else if optional_identifier {
+            let prefix = format!("{}:{}:", kind, fn_name_str);
+            quote!(format!("{}{}", #prefix, identifier))
+        } else {
+            let prefix = format!("{}:{}:", kind, fn_name_str);
+            quote! {
+                format!(
+                    "{}{}",
+                    #prefix,
+                    limiteron::limiter_manager::sanitize_key(&identifier, #key_strategy)
+                )
+            }
+        }
+    };
+    // 获取限流器：有标识符时从全局管理器按键获取，回退链落空时创建一次性限流器
+    let obtain_limiter = |kind: &str, cached: TokenStream2, uncached: TokenStream2| {
+        let key = limiter_key(kind);
+        if optional_identifier {
+            quote! {
+                match identifier.as_deref() {
+                    Some(identifier) => {
+                        let key = #key;
+                        #cached
+                    }
+                    None => std::sync::Arc::new(#uncached),
+                }
+            }
+        } else {
+            quote! {
+                {
+                    let key = #key;
+                    #cached
+                }
+            }
+        }
+    };
+
     let rate_check = if let Some(ref rate) = config.rate {
         let amount = rate.amount;
         let msg = reject_message.clone();
-        let fn_name_str = stringify!(#fn_name).to_string();
-        quote! {
-            let rate_key = {
-                let sanitize = |s: &str| s
-                    .chars()
-                    .filter(|c: &char| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.')
-                    .take(128)
-                    .collect::<String>();
-                format!("rate:{}:{}", #fn_name_str, sanitize(&identifier))
-            };
-            let rate_limiter = limiteron::GLOBAL_LIMITER_MANAGER.get_rate_limiter(&rate_key, #amount, 1);
-            if !rate_limiter.allow(1).await? {
+        let rate_limiter = obtain_limiter(
+            "rate",
+            quote!(limiteron::GLOBAL_LIMITER_MANAGER.get_rate_limiter(&key, #amount, 1)),
+            quote!(limiteron::limiters::TokenBucketLimiter::new(#amount, 1)),
+        );
+        // 软限流：等待固定时长后继续执行，等待期间调用被取消时随之结束
+        let on_rate_exceeded = match config.delay_ms {
+            Some(delay_ms) => quote! {
+                limiteron::macros::__private::sleep(std::time::Duration::from_millis(#delay_ms)).await;
+            },
+            None => quote! {
                 return Err(limiteron::error::FlowGuardError::RateLimitExceeded(#msg.to_string()));
+            },
+        };
+        quote! {
+            let rate_limiter = #rate_limiter;
+            if !rate_limiter.allow(cost).await? {
+                #on_rate_exceeded
             }
         }
     } else {
@@ -272,18 +676,14 @@ fn generate_flow_control(
         let max = quota.max;
         let duration = quota.to_duration();
         let msg = reject_message.clone();
-        let fn_name_str = stringify!(#fn_name).to_string();
+        let quota_limiter = obtain_limiter(
+            "quota",
+            quote!(limiteron::GLOBAL_LIMITER_MANAGER.get_quota_limiter(&key, #duration, #max)),
+            quote!(limiteron::limiters::FixedWindowLimiter::new(#duration, #max)),
+        );
         quote! {
-            let quota_key = {
-                let sanitize = |s: &str| s
-                    .chars()
-                    .filter(|c: &char| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.')
-                    .take(128)
-                    .collect::<String>();
-                format!("quota:{}:{}", #fn_name_str, sanitize(&identifier))
-            };
-            let quota_limiter = limiteron::GLOBAL_LIMITER_MANAGER.get_quota_limiter(&quota_key, #duration, #max);
-            if !quota_limiter.allow(1).await? {
+            let quota_limiter = #quota_limiter;
+            if !quota_limiter.allow(cost).await? {
                 return Err(limiteron::error::FlowGuardError::QuotaExceeded(#msg.to_string()));
             }
         }
@@ -291,26 +691,68 @@ fn generate_flow_control(
         quote!()
     };
 
-    let concurrency_check = if let Some(concurrency) = config.concurrency {
+    // 并发限制拆分为限流器获取与许可申请两部分：同步路径中许可需要在运行时之外持有
+    let (concurrency_setup, concurrency_acquire) = if let Some(concurrency) = config.concurrency {
         let msg = reject_message.clone();
-        let fn_name_str = stringify!(#fn_name).to_string();
-        quote! {
-            let concurrency_key = {
-                let sanitize = |s: &str| s
-                    .chars()
-                    .filter(|c: &char| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.')
-                    .take(128)
-                    .collect::<String>();
-                format!("concurrency:{}:{}", #fn_name_str, sanitize(&identifier))
-            };
-            let concurrency_limiter = limiteron::GLOBAL_LIMITER_MANAGER.get_concurrency_limiter(&concurrency_key, #concurrency as u64);
-            let _permit = concurrency_limiter.acquire(1).await.map_err(|_| limiteron::error::FlowGuardError::ConcurrencyLimitExceeded(#msg.to_string()))?;
-        }
+        let concurrency_limiter = obtain_limiter(
+            "concurrency",
+            quote!(limiteron::GLOBAL_LIMITER_MANAGER.get_concurrency_limiter(&key, #concurrency as u64)),
+            quote!(limiteron::limiters::ConcurrencyLimiter::with_timeout(
+                #concurrency as u64,
+                std::time::Duration::from_millis(50)
+            )),
+        );
+        let setup = quote!(let concurrency_limiter = #concurrency_limiter;);
+        let acquire = match config.acquire_timeout {
+            Some(millis) => quote! {
+                concurrency_limiter
+                    .try_acquire_timeout(1, std::time::Duration::from_millis(#millis))
+                    .await
+                    .map_err(|_| limiteron::error::FlowGuardError::ConcurrencyLimitExceeded(#msg.to_string()))
+            },
+            None => quote! {
+                concurrency_limiter.acquire(1).await.map_err(|_| limiteron::error::FlowGuardError::ConcurrencyLimitExceeded(#msg.to_string()))
+            },
+        };
+        (setup, Some(acquire))
     } else {
-        quote!()
+        (quote!(), None)
     };
 
-    let identifier_expr = if config.identifiers.is_empty() {
+    let identifier_expr = if let Some(ref fallback) = config.fallback {
+        // 回退链：identifiers → 客户端 IP（fallback = "ip"）→ None（一次性限流器）。
+        // 键在此处清洗：IP 固定按哈希策略清洗（IPv6 地址中的 `:` 会被截断策略去掉），
+        // 清洗后再加 `ip:` 前缀，清洗结果不含 `:`，因此不会与标识符的键冲突
+        let ids = &config.identifiers;
+        let ip_fallback = match (fallback.as_str(), &config.client_ip) {
+            ("ip", Some(client_ip)) => quote! {
+                let ip = format!("{}", #client_ip);
+                if !ip.is_empty() {
+                    return Some(format!(
+                        "ip:{}",
+                        limiteron::limiter_manager::sanitize_key(
+                            &ip,
+                            limiteron::limiter_manager::KeyStrategy::Hash
+                        )
+                    ));
+                }
+            },
+            _ => quote!(),
+        };
+        quote! {
+            (|| -> Option<String> {
+                let parts: Vec<String> = vec![#(format!("{}", #ids)),*];
+                if parts.iter().any(|part| !part.is_empty()) {
+                    return Some(limiteron::limiter_manager::sanitize_key(
+                        &parts.join(":"),
+                        #key_strategy,
+                    ));
+                }
+                #ip_fallback
+                None
+            })()
+        }
+    } else if config.identifiers.is_empty() {
         quote!("default")
     } else {
         let ids = &config.identifiers;
@@ -323,15 +765,90 @@ fn generate_flow_control(
         }
     };
 
+    let identifier_binding = if is_global {
+        quote!()
+    } else {
+        quote!(let identifier = #identifier_expr;)
+    };
+
     let tracing_start = quote! {
         let _span = tracing::span!(tracing::Level::INFO, "flow_control", function = stringify!(#fn_name));
         let _enter = _span.enter();
     };
 
     let metrics_record = quote! {
-        if let Some(metrics) = limiteron::telemetry::try_global() {
-            metrics.requests_total.inc();
+        limiteron::macros::__private::record_request();
+    };
+
+    let has_checks =
+        config.rate.is_some() || config.quota.is_some() || config.concurrency.is_some();
+    if is_async && config.runtime.is_some() {
+        return Err("runtime option only applies to synchronous functions".to_string());
+    }
+    if !is_async && has_checks && config.runtime.is_none() {
+        return Err(format!(
+            "flow_control on synchronous function '{}' requires runtime = \"current_thread\" \
+             (or make the function async)",
+            fn_name
+        ));
+    }
+
+    // 拒绝错误转换：指定 on_reject 时通过该函数把 FlowGuardError 转换为函数自身的错误类型
+    let map_reject = match config.on_reject {
+        Some(ref on_reject) => {
+            let on_reject: syn::Path = syn::parse_str(on_reject)
+                .map_err(|e| format!("Invalid on_reject path '{}': {}", on_reject, e))?;
+            quote!(.map_err(#on_reject))
         }
+        None => quote!(),
+    };
+
+    // 指定 methods 时，只有方法匹配的调用才执行检查
+    let method_scope = match config.method {
+        Some(ref method) if !config.methods.is_empty() => {
+            let methods = &config.methods;
+            Some(quote! {
+                {
+                    let method = format!("{}", #method);
+                    [#(#methods),*]
+                        .iter()
+                        .any(|scoped: &&str| scoped.eq_ignore_ascii_case(&method))
+                }
+            })
+        }
+        _ => None,
+    };
+
+    // 全部检查放在一个返回 Result<_, FlowGuardError> 的 async 块中，
+    // 并发许可作为块的结果返回，在函数体执行期间持有
+    let checks = if has_checks {
+        let permit = concurrency_acquire
+            .unwrap_or_else(|| quote!(Ok::<(), limiteron::error::FlowGuardError>(())));
+        let checks_block = quote! {
+            async {
+                #rate_check
+                #quota_check
+                #permit
+            }
+        };
+        let run_checks = if is_async {
+            quote!(#checks_block.await #map_reject?)
+        } else {
+            // 同步路径：在 current_thread 运行时上阻塞执行全部检查
+            quote!(limiteron::macros::__private::block_on(#checks_block) #map_reject? #map_reject?)
+        };
+        match method_scope {
+            Some(method_scope) => quote! {
+                #concurrency_setup
+                let _permit = if #method_scope { Some(#run_checks) } else { None };
+            },
+            None => quote! {
+                #concurrency_setup
+                let _permit = #run_checks;
+            },
+        }
+    } else {
+        quote!()
     };
 
     let expanded = if is_async {
@@ -340,10 +857,9 @@ fn generate_flow_control(
             #fn_vis async fn #fn_name(#fn_inputs) #fn_output {
                 use limiteron::limiters::Limiter;
                 #tracing_start
-                let identifier = #identifier_expr;
-                #rate_check
-                #quota_check
-                #concurrency_check
+                #identifier_binding
+                #cost_binding
+                #checks
                 #metrics_record
                 #fn_block
             }
@@ -354,15 +870,9 @@ fn generate_flow_control(
             #fn_vis fn #fn_name(#fn_inputs) #fn_output {
                 use limiteron::limiters::Limiter;
                 #tracing_start
-                let identifier = #identifier_expr;
-                let rt = tokio::runtime::Handle::try_current();
-                if let Ok(handle) = rt {
-                    handle.block_on(async {
-                        #rate_check
-                        #quota_check
-                        #concurrency_check
-                    });
-                }
+                #identifier_binding
+                #cost_binding
+                #checks
                 #metrics_record
                 #fn_block
             }
@@ -439,5 +949,272 @@ mod tests {
         // 注意：#[derive(Default)] 会将 String 字段默认为空字符串
         assert_eq!(config.on_exceed, "");
         assert_eq!(config.reject_message, "");
+        assert!(config.cost.is_none());
+        assert!(config.cost_fn.is_none());
+    }
+
+    #[test]
+    fn test_parse_cost() {
+        let config = FlowControlConfig::parse(&quote!(rate = "100/s", cost = 5)).unwrap();
+        assert_eq!(config.cost, Some(5));
+        assert!(config.cost_fn.is_none());
+    }
+
+    #[test]
+    fn test_parse_cost_fn() {
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "100/s", cost_fn = "compute_cost")).unwrap();
+        assert_eq!(config.cost_fn.as_deref(), Some("compute_cost"));
+        assert!(config.cost.is_none());
+    }
+
+    #[test]
+    fn test_parse_zero_cost_rejected() {
+        let err = FlowControlConfig::parse(&quote!(rate = "100/s", cost = 0)).unwrap_err();
+        assert!(err.contains("cost must be greater than 0"));
+    }
+
+    #[test]
+    fn test_parse_cost_conflict() {
+        let result =
+            FlowControlConfig::parse(&quote!(rate = "100/s", cost = 2, cost_fn = "compute_cost"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_acquire_timeout() {
+        let config =
+            FlowControlConfig::parse(&quote!(concurrency = 10, acquire_timeout = "100ms")).unwrap();
+        assert_eq!(config.acquire_timeout, Some(100));
+        let config =
+            FlowControlConfig::parse(&quote!(concurrency = 10, acquire_timeout = "2s")).unwrap();
+        assert_eq!(config.acquire_timeout, Some(2_000));
+
+        assert!(FlowControlConfig::parse(&quote!(acquire_timeout = "100ms")).is_err());
+        assert!(
+            FlowControlConfig::parse(&quote!(concurrency = 10, acquire_timeout = "100")).is_err()
+        );
+        assert!(
+            FlowControlConfig::parse(&quote!(concurrency = 10, acquire_timeout = "0ms")).is_err()
+        );
+
+        let input: ItemFn = syn::parse_quote! {
+            async fn handler() -> Result<(), FlowGuardError> {
+                Ok(())
+            }
+        };
+        let config =
+            FlowControlConfig::parse(&quote!(concurrency = 10, acquire_timeout = "100ms")).unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(tokens
+            .contains("try_acquire_timeout (1 , std :: time :: Duration :: from_millis (100u64))"));
+
+        let config = FlowControlConfig::parse(&quote!(concurrency = 10)).unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(!tokens.contains("try_acquire_timeout"));
+    }
+
+    #[test]
+    fn test_parse_delay() {
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", on_exceed = "delay", delay_ms = 250))
+                .unwrap();
+        assert_eq!(config.delay_ms, Some(250));
+        let config = FlowControlConfig::parse(&quote!(rate = "10/s", on_exceed = "delay")).unwrap();
+        assert_eq!(config.delay_ms, Some(DEFAULT_DELAY_MS));
+
+        assert!(FlowControlConfig::parse(&quote!(concurrency = 1, on_exceed = "delay")).is_err());
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", delay_ms = 100)).is_err());
+        assert!(FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            on_exceed = "delay",
+            delay_ms = 0
+        ))
+        .is_err());
+
+        let input: ItemFn = syn::parse_quote! {
+            async fn handler() -> Result<(), FlowGuardError> {
+                Ok(())
+            }
+        };
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", on_exceed = "delay", delay_ms = 250))
+                .unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(tokens.contains("sleep (std :: time :: Duration :: from_millis (250u64))"));
+        assert!(!tokens.contains("RateLimitExceeded"));
+    }
+
+    #[test]
+    fn test_parse_runtime() {
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", runtime = "current_thread")).unwrap();
+        assert_eq!(config.runtime.as_deref(), Some("current_thread"));
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", runtime = "multi")).is_err());
+    }
+
+    #[test]
+    fn test_sync_fn_requires_runtime() {
+        let input: ItemFn = syn::parse_quote! {
+            fn handler() -> Result<(), FlowGuardError> {
+                Ok(())
+            }
+        };
+        let config = FlowControlConfig::parse(&quote!(rate = "10/s")).unwrap();
+        let err = generate_flow_control(&input, &config).unwrap_err();
+        assert!(err.contains("current_thread"));
+
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", runtime = "current_thread")).unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(tokens.contains("block_on"));
+        assert!(!tokens.contains("try_current"));
+    }
+
+    #[test]
+    fn test_async_fn_rejects_runtime() {
+        let input: ItemFn = syn::parse_quote! {
+            async fn handler() -> Result<(), FlowGuardError> {
+                Ok(())
+            }
+        };
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", runtime = "current_thread")).unwrap();
+        assert!(generate_flow_control(&input, &config).is_err());
+    }
+
+    #[test]
+    fn test_on_reject_wraps_errors() {
+        let input: ItemFn = syn::parse_quote! {
+            async fn handler() -> Result<(), MyError> {
+                Ok(())
+            }
+        };
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", on_reject = "MyError::from_flow"))
+                .unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(tokens.contains("map_err (MyError :: from_flow)"));
+
+        let config = FlowControlConfig::parse(&quote!(rate = "10/s")).unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(!tokens.contains("map_err (MyError"));
+
+        assert!(FlowControlConfig::parse(&quote!(on_reject = "not a path")).is_err());
+    }
+
+    #[test]
+    fn test_parse_methods() {
+        let config = FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            methods = ["post", "PUT"],
+            method = req_method
+        ))
+        .unwrap();
+        assert_eq!(config.methods, vec!["POST", "PUT"]);
+        assert!(config.method.is_some());
+
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", methods = ["POST"])).is_err());
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", method = req_method)).is_err());
+        assert!(FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            methods = ["PO ST"],
+            method = req_method
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_key_strategy() {
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", key_strategy = "hash")).unwrap();
+        assert_eq!(config.key_strategy.as_deref(), Some("hash"));
+
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", key_strategy = "md5")).is_err());
+    }
+
+    #[test]
+    fn test_parse_scope() {
+        let config = FlowControlConfig::parse(&quote!(rate = "1000/s", scope = "global")).unwrap();
+        assert_eq!(config.scope.as_deref(), Some("global"));
+        assert!(FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            scope = "identifier",
+            identifiers = [user_id]
+        ))
+        .is_ok());
+
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", scope = "tenant")).is_err());
+        assert!(FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            scope = "global",
+            identifiers = [user_id]
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_fallback() {
+        let config = FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            fallback = "ip",
+            client_ip = req.peer_addr()
+        ))
+        .unwrap();
+        assert_eq!(config.fallback.as_deref(), Some("ip"));
+        assert!(config.client_ip.is_some());
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", fallback = "uuid")).is_ok());
+
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", fallback = "ip")).is_err());
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", client_ip = addr)).is_err());
+        assert!(FlowControlConfig::parse(&quote!(rate = "10/s", fallback = "mac")).is_err());
+        assert!(FlowControlConfig::parse(&quote!(
+            rate = "10/s",
+            scope = "global",
+            fallback = "uuid"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_identifier_exprs() {
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", identifiers = [user_id, req.tenant]))
+                .unwrap();
+        assert_eq!(config.identifiers.len(), 2);
+
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", identifiers(user_id))).unwrap();
+        assert_eq!(config.identifiers.len(), 1);
+
+        assert!(FlowControlConfig::parse(&quote!(identifiers = user_id)).is_err());
+    }
+
+    #[test]
+    fn test_identifiers_quoted_as_expressions() {
+        let input: ItemFn = syn::parse_quote! {
+            async fn handler(user_id: &str) -> Result<(), FlowGuardError> {
+                Ok(())
+            }
+        };
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "10/s", identifiers = [user_id])).unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(tokens.contains("format ! (\"{}\" , user_id)"));
+    }
+
+    #[test]
+    fn test_generate_uses_cost() {
+        let input: ItemFn = syn::parse_quote! {
+            async fn handler(user_id: String, size: u64) -> Result<(), FlowGuardError> {
+                Ok(())
+            }
+        };
+        let config =
+            FlowControlConfig::parse(&quote!(rate = "100/s", cost_fn = "compute_cost")).unwrap();
+        let tokens = generate_flow_control(&input, &config).unwrap().to_string();
+        assert!(tokens.contains("compute_cost"));
+        assert!(tokens.contains("allow (cost)"));
+        assert!(!tokens.contains("allow (1)"));
     }
 }
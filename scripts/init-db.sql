@@ -55,6 +55,17 @@ CREATE TABLE IF NOT EXISTS kv_store (
 CREATE INDEX idx_kv_expires ON kv_store(expires_at)
     WHERE expires_at IS NOT NULL;
 
+-- 创建限流计数表（主键即 (key, window_start) 索引）
+CREATE TABLE IF NOT EXISTS rate_limit_counters (
+    key VARCHAR(255) NOT NULL,
+    window_start BIGINT NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (key, window_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_expires ON rate_limit_counters(expires_at);
+
 -- 插入测试数据
 INSERT INTO kv_store (key, value) VALUES
     ('test_key', 'test_value'),
@@ -77,6 +88,6 @@ VALUES
 DO $$
 BEGIN
     RAISE NOTICE 'Limiteron test database initialized successfully';
-    RAISE NOTICE 'Created tables: quota_usage, ban_records, kv_store';
+    RAISE NOTICE 'Created tables: quota_usage, ban_records, kv_store, rate_limit_counters';
     RAISE NOTICE 'Test data inserted';
 END $$;
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- Limiteron 迁移：为 PostgreSQL 限流器创建计数表
+--
+-- 已有部署执行此脚本即可启用 PostgresStorage::fixed_window / sliding_window。
+
+CREATE TABLE IF NOT EXISTS rate_limit_counters (
+    key VARCHAR(255) NOT NULL,
+    window_start BIGINT NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (key, window_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_expires ON rate_limit_counters(expires_at);
@@ -5,6 +5,9 @@
 //! 审计日志模块
 //!
 //! 提供审计日志功能，记录决策过程、配置变更、封禁操作等。
+//!
+//! 所有事件经由同一通道交给单个后台任务按提交顺序写出，因此同一标识符的事件
+//! 按提交顺序落盘。调用 [`AuditLogger::flush`] 可等待已提交的事件写出并同步到磁盘。
 
 #[cfg(feature = "audit-log")]
 use chrono::{DateTime, Utc};
@@ -19,8 +22,17 @@ use std::time::Duration;
 #[cfg(feature = "audit-log")]
 use tracing::{error, info, trace};
 
+#[cfg(feature = "audit-log")]
+use crate::error::FlowGuardError;
 #[cfg(feature = "audit-log")]
 use tokio::sync::mpsc::{self, Sender};
+#[cfg(feature = "audit-log")]
+use tokio::sync::oneshot;
+
+#[cfg(feature = "audit-log")]
+mod sink;
+#[cfg(feature = "audit-log")]
+pub use sink::{AuditSink, JsonStdoutSink, RotatingFileSink, TracingSink};
 
 #[cfg(feature = "audit-log")]
 /// 审计事件类型
@@ -32,6 +44,9 @@ pub enum AuditEvent {
         identifier: String,
         decision: String,
         reason: String,
+        /// 产生决策的规则ID
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rule_id: Option<String>,
         request_id: Option<String>,
     },
     ConfigChange {
@@ -76,6 +91,33 @@ impl AuditEvent {
     }
 }
 
+/// 决策类别，用于审计日志采样
+#[cfg(feature = "audit-log")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecisionKind {
+    /// 允许
+    Allowed,
+    /// 拒绝（限流、配额、并发等）
+    Rejected,
+    /// 封禁
+    Banned,
+}
+
+#[cfg(feature = "audit-log")]
+impl DecisionKind {
+    /// 从决策描述得出类别：以 `allow` 开头为允许，以 `ban` 开头为封禁，其余视为拒绝
+    pub fn from_decision(decision: &str) -> Self {
+        let decision = decision.trim().to_ascii_lowercase();
+        if decision.starts_with("allow") {
+            DecisionKind::Allowed
+        } else if decision.starts_with("ban") {
+            DecisionKind::Banned
+        } else {
+            DecisionKind::Rejected
+        }
+    }
+}
+
 #[cfg(feature = "audit-log")]
 #[derive(Debug, Default)]
 pub struct AuditLogStats {
@@ -87,6 +129,7 @@ pub struct AuditLogStats {
     error_events: AtomicU64,
     batch_writes: AtomicU64,
     write_failures: AtomicU64,
+    sampled_out: AtomicU64,
 }
 
 #[cfg(feature = "audit-log")]
@@ -123,6 +166,11 @@ impl AuditLogStats {
         self.write_failures.load(Ordering::Relaxed)
     }
 
+    /// 因采样未记录的决策事件数
+    pub fn sampled_out(&self) -> u64 {
+        self.sampled_out.load(Ordering::Relaxed)
+    }
+
     pub fn reset(&self) {
         self.total_events.store(0, Ordering::Relaxed);
         self.decision_events.store(0, Ordering::Relaxed);
@@ -132,6 +180,7 @@ impl AuditLogStats {
         self.error_events.store(0, Ordering::Relaxed);
         self.batch_writes.store(0, Ordering::Relaxed);
         self.write_failures.store(0, Ordering::Relaxed);
+        self.sampled_out.store(0, Ordering::Relaxed);
     }
 }
 
@@ -198,6 +247,10 @@ pub struct AuditLogConfig {
     pub max_file_size: Option<u64>,
     /// 日志轮转：保留的文件数量
     pub max_files: Option<usize>,
+    /// 不在 `always_log` 中的决策的采样率 (0.0 - 1.0)
+    pub sample_rate: f64,
+    /// 始终记录、不参与采样的决策类别
+    pub always_log: Vec<DecisionKind>,
 }
 
 #[cfg(feature = "audit-log")]
@@ -211,6 +264,8 @@ impl Default for AuditLogConfig {
             output_path: None,
             max_file_size: Some(100 * 1024 * 1024), // 100MB
             max_files: Some(10),                    // 保留10个文件
+            sample_rate: 1.0,
+            always_log: vec![DecisionKind::Rejected, DecisionKind::Banned],
         }
     }
 }
@@ -245,20 +300,104 @@ impl AuditLogConfig {
         self.output_path = Some(path);
         self
     }
+
+    /// 设置决策采样率，超出范围时截断到 0.0 - 1.0
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 设置始终记录的决策类别
+    pub fn always_log(mut self, kinds: Vec<DecisionKind>) -> Self {
+        self.always_log = kinds;
+        self
+    }
 }
 
+/// 将请求ID映射为 [0, 1) 区间内的采样点（FNV-1a，跨进程稳定）
+#[cfg(feature = "audit-log")]
+fn sample_point(request_id: &str) -> f64 {
+    let mut hash = crate::log_redaction::fnv1a(request_id.as_bytes());
+    // 末尾混合，使相近的请求ID也能均匀分布
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^= hash >> 33;
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// 发送给后台写入任务的命令
 #[cfg(feature = "audit-log")]
 #[derive(Debug)]
+enum AuditCommand {
+    /// 记录事件
+    Event(AuditEvent),
+    /// 写出缓冲区并同步文件，完成后回复结果
+    Flush(oneshot::Sender<Result<(), String>>),
+}
+
+/// 审计日志记录器
+///
+/// Drop 时关闭通道并请求一次刷新，后台任务会尽力写出剩余事件（需要运行时仍在运行）；
+/// 需要确认写出时请在关闭前调用 [`flush`](Self::flush) 或 [`shutdown`](Self::shutdown)。
+#[cfg(feature = "audit-log")]
 pub struct AuditLogger {
-    sender: Sender<AuditEvent>,
+    sender: Sender<AuditCommand>,
     stats: Arc<AuditLogStats>,
     config: AuditLogConfig,
+    sink: Arc<dyn AuditSink>,
     write_handle: tokio::task::JoinHandle<()>,
+    /// 无请求ID时用于采样的序号
+    sample_sequence: AtomicU64,
+}
+
+#[cfg(feature = "audit-log")]
+impl std::fmt::Debug for AuditLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLogger")
+            .field("stats", &self.stats)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
 }
 
 #[cfg(feature = "audit-log")]
 impl AuditLogger {
+    /// 按配置创建审计日志记录器
+    ///
+    /// 配置了 `output_path` 时写入按 `max_file_size` / `max_files` 轮转的
+    /// [`RotatingFileSink`]，否则使用 [`TracingSink`]。
     pub async fn new(config: AuditLogConfig) -> Self {
+        let sink: Arc<dyn AuditSink> = match config.output_path {
+            Some(ref path) => {
+                let mut sink = RotatingFileSink::new(path);
+                if let Some(max_file_size) = config.max_file_size {
+                    sink = sink.max_file_size(max_file_size);
+                }
+                if let Some(max_files) = config.max_files {
+                    sink = sink.max_files(max_files);
+                }
+                Arc::new(sink)
+            }
+            None => Arc::new(TracingSink),
+        };
+        Self::with_sink(config, sink).await
+    }
+
+    /// 使用自定义输出端创建审计日志记录器，忽略配置中的 `output_path`
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::audit_log::{AuditLogConfig, AuditLogger, JsonStdoutSink};
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let logger = AuditLogger::with_sink(AuditLogConfig::new(), Arc::new(JsonStdoutSink)).await;
+    /// }
+    /// ```
+    pub async fn with_sink(config: AuditLogConfig, sink: Arc<dyn AuditSink>) -> Self {
         info!("创建审计日志记录器: enabled={}", config.enabled);
 
         let (sender, receiver) = mpsc::channel(config.channel_capacity);
@@ -268,13 +407,16 @@ impl AuditLogger {
             receiver,
             Arc::clone(&stats),
             config.clone(),
+            Arc::clone(&sink),
         ));
 
         Self {
             sender,
             stats,
             config,
+            sink,
             write_handle,
+            sample_sequence: AtomicU64::new(0),
         }
     }
 
@@ -283,18 +425,32 @@ impl AuditLogger {
     }
 
     async fn write_task(
-        mut receiver: mpsc::Receiver<AuditEvent>,
+        mut receiver: mpsc::Receiver<AuditCommand>,
         stats: Arc<AuditLogStats>,
         config: AuditLogConfig,
+        sink: Arc<dyn AuditSink>,
     ) {
         let mut batch = Vec::with_capacity(config.batch_size);
         let mut timeout = tokio::time::interval(config.batch_timeout);
+        // 自上次刷新以来写入失败的事件数
+        let mut failures = 0u64;
 
         loop {
             tokio::select! {
                 result = receiver.recv() => {
                     match result {
-                        Some(event) => {
+                        Some(AuditCommand::Flush(reply)) => {
+                            failures += Self::write_batch(&batch, sink.as_ref(), &stats).await;
+                            batch.clear();
+                            let result = if failures > 0 {
+                                Err(format!("{} audit events failed to write", failures))
+                            } else {
+                                sink.flush().await.map_err(|e| e.to_string())
+                            };
+                            failures = 0;
+                            let _ = reply.send(result);
+                        }
+                        Some(AuditCommand::Event(event)) => {
                             batch.push(event);
                             stats.total_events.fetch_add(1, Ordering::Relaxed);
 
@@ -317,23 +473,22 @@ impl AuditLogger {
                             }
 
                             if batch.len() >= config.batch_size {
-                                Self::write_batch(&batch, &config, &stats);
+                                failures += Self::write_batch(&batch, sink.as_ref(), &stats).await;
                                 batch.clear();
                             }
                         }
                         None => {
-                            if !batch.is_empty() {
-                                Self::write_batch(&batch, &config, &stats);
+                            Self::write_batch(&batch, sink.as_ref(), &stats).await;
+                            if let Err(e) = sink.flush().await {
+                                error!("同步审计日志文件失败: {}", e);
                             }
                             break;
                         }
                     }
                 }
                 _ = timeout.tick() => {
-                    if !batch.is_empty() {
-                        Self::write_batch(&batch, &config, &stats);
-                        batch.clear();
-                    }
+                    failures += Self::write_batch(&batch, sink.as_ref(), &stats).await;
+                    batch.clear();
                 }
             }
         }
@@ -341,127 +496,107 @@ impl AuditLogger {
         info!("审计日志写入任务结束");
     }
 
-    fn write_batch(batch: &[AuditEvent], config: &AuditLogConfig, stats: &AuditLogStats) {
+    /// 写出一批事件，返回写入失败的事件数
+    async fn write_batch(batch: &[AuditEvent], sink: &dyn AuditSink, stats: &AuditLogStats) -> u64 {
+        if batch.is_empty() {
+            return 0;
+        }
         stats.batch_writes.fetch_add(1, Ordering::Relaxed);
+        let mut failures = 0;
 
         for event in batch {
-            match serde_json::to_string_pretty(event) {
-                Ok(json) => {
-                    // 使用 info 级别记录日志（生产环境可见）
-                    info!("审计日志: {}", json);
-
-                    // 如果配置了输出路径，写入文件
-                    if let Some(ref path) = config.output_path {
-                        if let Err(e) = Self::write_to_file(path, &json, config) {
-                            stats.write_failures.fetch_add(1, Ordering::Relaxed);
-                            error!("写入审计日志文件失败: {}: {}", path, e);
-                        } else {
-                            trace!("成功写入审计日志文件: {}", path);
-                        }
-                    }
-                }
-                Err(e) => {
-                    stats.write_failures.fetch_add(1, Ordering::Relaxed);
-                    error!("序列化审计日志失败: {}", e);
-                }
+            if let Err(e) = sink.write(event).await {
+                failures += 1;
+                stats.write_failures.fetch_add(1, Ordering::Relaxed);
+                error!("写入审计日志失败: {}", e);
+            } else {
+                trace!("成功写入审计日志");
             }
         }
+
+        failures
     }
 
-    /// 写入审计日志到文件
+    /// 判断决策是否应被记录
     ///
-    /// # 安全说明
-    /// - 使用追加模式写入，避免覆盖已有日志
-    /// - 自动创建目录（如果不存在）
-    /// - 添加换行符分隔日志条目
-    /// - 支持日志轮转
-    fn write_to_file(path: &str, content: &str, config: &AuditLogConfig) -> std::io::Result<()> {
-        use std::fs::OpenOptions;
-        use std::io::Write;
-
-        // 检查是否需要轮转
-        if let Some(max_size) = config.max_file_size {
-            if let Ok(metadata) = std::fs::metadata(path) {
-                if metadata.len() >= max_size {
-                    // 执行日志轮转
-                    Self::rotate_log_file(path, config.max_files)?;
-                }
-            }
+    /// `always_log` 中的类别总是记录；其余按 `sample_rate` 采样。带请求ID时按请求ID的
+    /// 哈希决定，同一请求在不同组件中的采样结果一致；无请求ID时按提交序号均匀采样。
+    pub fn should_log_decision(&self, decision: &str, request_id: Option<&str>) -> bool {
+        let kind = DecisionKind::from_decision(decision);
+        if self.config.always_log.contains(&kind) || self.config.sample_rate >= 1.0 {
+            return true;
         }
-
-        // 确保目录存在
-        if let Some(parent) = std::path::Path::new(path).parent() {
-            std::fs::create_dir_all(parent)?;
+        if self.config.sample_rate <= 0.0 {
+            return false;
         }
 
-        // 以追加模式打开文件
-        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
-
-        // 写入内容并添加换行符
-        writeln!(file, "{}", content)?;
-
-        Ok(())
-    }
-
-    /// 日志轮转
-    ///
-    /// 将当前日志文件重命名，并删除旧的日志文件
-    fn rotate_log_file(path: &str, max_files: Option<usize>) -> std::io::Result<()> {
-        let path_obj = std::path::Path::new(path);
-        let parent = path_obj.parent().unwrap_or(std::path::Path::new("."));
-        let stem = path_obj
-            .file_stem()
-            .unwrap_or(std::ffi::OsStr::new("audit"));
-        let extension = path_obj
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("log");
-
-        // 删除最旧的日志文件（如果超过 max_files）
-        if let Some(max_files) = max_files {
-            let old_file = parent.join(format!(
-                "{}.{}.{}",
-                stem.to_str().unwrap(),
-                max_files,
-                extension
-            ));
-            if old_file.exists() {
-                std::fs::remove_file(&old_file)?;
+        let point = match request_id {
+            Some(request_id) => sample_point(request_id),
+            None => {
+                let sequence = self.sample_sequence.fetch_add(1, Ordering::Relaxed);
+                sample_point(&sequence.to_string())
             }
+        };
+        point < self.config.sample_rate
+    }
 
-            // 重命名中间的日志文件
-            for i in (1..max_files).rev() {
-                let old_name =
-                    parent.join(format!("{}.{}.{}", stem.to_str().unwrap(), i, extension));
-                let new_name = parent.join(format!(
-                    "{}.{}.{}",
-                    stem.to_str().unwrap(),
-                    i + 1,
-                    extension
-                ));
-                if old_name.exists() {
-                    std::fs::rename(&old_name, &new_name)?;
-                }
-            }
-        }
-
-        // 重命名当前日志文件
-        let rotated_name = parent.join(format!("{}.1.{}", stem.to_str().unwrap(), extension));
-        std::fs::rename(path, &rotated_name)?;
+    /// 记录决策事件，按 [`should_log_decision`](Self::should_log_decision) 采样
+    pub async fn log_decision(
+        &self,
+        identifier: String,
+        decision: String,
+        reason: String,
+        request_id: Option<String>,
+    ) {
+        self.log_decision_with_rule(identifier, decision, reason, None, request_id)
+            .await;
+    }
 
-        Ok(())
+    /// 记录 [`Governor::check_detailed`](crate::governor::Governor::check_detailed)
+    /// 的结果，包括产生决策的规则ID
+    pub async fn log_check_result(
+        &self,
+        result: &crate::governor::CheckResult,
+        request_id: Option<String>,
+    ) {
+        let (decision, reason) = match &result.decision {
+            crate::error::Decision::Allowed(_) => ("allowed", String::new()),
+            crate::error::Decision::Rejected(rejection) => ("rejected", rejection.message.clone()),
+            crate::error::Decision::Banned(info) => ("banned", info.reason.clone()),
+            crate::error::Decision::Delayed(delay) => ("delayed", format!("{:?}", delay)),
+        };
+        let identifier = result
+            .identifier
+            .as_ref()
+            .map(|identifier| identifier.key())
+            .unwrap_or_default();
+
+        self.log_decision_with_rule(
+            identifier,
+            decision.to_string(),
+            reason,
+            result.matched_rule.clone(),
+            request_id,
+        )
+        .await;
     }
 
-    pub async fn log_decision(
+    /// 记录决策事件并附带产生决策的规则ID
+    pub async fn log_decision_with_rule(
         &self,
         identifier: String,
         decision: String,
         reason: String,
+        rule_id: Option<String>,
         request_id: Option<String>,
     ) {
         if !self.config.enabled {
             return;
         }
+        if !self.should_log_decision(&decision, request_id.as_deref()) {
+            self.stats.sampled_out.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
 
         // 对敏感数据进行脱敏
         let sanitized_identifier = sanitize_identifier(&identifier);
@@ -471,10 +606,11 @@ impl AuditLogger {
             identifier: sanitized_identifier,
             decision,
             reason,
+            rule_id,
             request_id,
         };
 
-        if let Err(e) = self.sender.send(event).await {
+        if let Err(e) = self.sender.send(AuditCommand::Event(event)).await {
             error!("发送决策事件失败: {}", e);
             self.stats.write_failures.fetch_add(1, Ordering::Relaxed);
         }
@@ -499,7 +635,7 @@ impl AuditLogger {
             operator,
         };
 
-        if let Err(e) = self.sender.send(event).await {
+        if let Err(e) = self.sender.send(AuditCommand::Event(event)).await {
             error!("发送配置变更事件失败: {}", e);
             self.stats.write_failures.fetch_add(1, Ordering::Relaxed);
         }
@@ -526,7 +662,7 @@ impl AuditLogger {
             expires_at,
         };
 
-        if let Err(e) = self.sender.send(event).await {
+        if let Err(e) = self.sender.send(AuditCommand::Event(event)).await {
             error!("发送封禁操作事件失败: {}", e);
             self.stats.write_failures.fetch_add(1, Ordering::Relaxed);
         }
@@ -544,7 +680,7 @@ impl AuditLogger {
             details,
         };
 
-        if let Err(e) = self.sender.send(event).await {
+        if let Err(e) = self.sender.send(AuditCommand::Event(event)).await {
             error!("发送系统事件失败: {}", e);
             self.stats.write_failures.fetch_add(1, Ordering::Relaxed);
         }
@@ -567,7 +703,7 @@ impl AuditLogger {
             stack_trace,
         };
 
-        if let Err(e) = self.sender.send(event).await {
+        if let Err(e) = self.sender.send(AuditCommand::Event(event)).await {
             error!("发送错误事件失败: {}", e);
             self.stats.write_failures.fetch_add(1, Ordering::Relaxed);
         }
@@ -581,9 +717,41 @@ impl AuditLogger {
         &self.config
     }
 
+    /// 当前使用的输出端
+    pub fn sink(&self) -> &Arc<dyn AuditSink> {
+        &self.sink
+    }
+
+    /// 写出所有已提交的事件并同步到磁盘
+    ///
+    /// 在此调用之前提交的事件都会按提交顺序写出；配置了输出路径时，返回前会将文件
+    /// 同步到磁盘。
+    ///
+    /// # 返回
+    /// - `Ok(())`: 所有事件已写出
+    /// - `Err(FlowGuardError::AuditLogError)`: 自上次刷新以来有事件写入失败、
+    ///   同步失败或写入任务已停止
+    pub async fn flush(&self) -> Result<(), FlowGuardError> {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send(AuditCommand::Flush(reply))
+            .await
+            .map_err(|_| FlowGuardError::AuditLogError("审计日志写入任务已停止".to_string()))?;
+        result
+            .await
+            .map_err(|_| FlowGuardError::AuditLogError("审计日志写入任务已停止".to_string()))?
+            .map_err(FlowGuardError::AuditLogError)
+    }
+
+    /// 刷新剩余事件后停止写入任务
     pub async fn shutdown(mut self) {
         info!("停止审计日志记录器");
+        if let Err(e) = self.flush().await {
+            error!("关闭前刷新审计日志失败: {}", e);
+        }
         let handle = std::mem::replace(&mut self.write_handle, tokio::spawn(async {}));
+        // 释放发送端以关闭通道，写入任务随之退出
+        drop(self);
         let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
     }
 }
@@ -591,7 +759,9 @@ impl AuditLogger {
 #[cfg(feature = "audit-log")]
 impl Drop for AuditLogger {
     fn drop(&mut self) {
-        self.write_handle.abort();
+        // 尽力刷新：通道关闭后写入任务会写出剩余事件并退出
+        let (reply, _) = oneshot::channel();
+        let _ = self.sender.try_send(AuditCommand::Flush(reply));
     }
 }
 
@@ -632,6 +802,7 @@ mod tests {
             identifier: "test".to_string(),
             decision: "allowed".to_string(),
             reason: "test".to_string(),
+            rule_id: None,
             request_id: None,
         };
 
@@ -664,4 +835,171 @@ mod tests {
 
         assert_eq!(logger.stats().decision_events(), 1);
     }
+
+    #[tokio::test]
+    async fn test_audit_logger_log_check_result() {
+        let logger = AuditLogger::new(AuditLogConfig::default()).await;
+        let result = crate::governor::CheckResult {
+            decision: crate::error::Decision::rejected(
+                crate::error::RejectReason::RateLimit,
+                "limit exceeded",
+            ),
+            matched_rule: Some("api_rule".to_string()),
+            identifier: Some(crate::matchers::Identifier::UserId("user123".to_string())),
+            limits: None,
+            elapsed: Duration::from_millis(1),
+        };
+
+        logger.log_check_result(&result, None).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(logger.stats().decision_events(), 1);
+    }
+
+    #[test]
+    fn test_decision_kind_from_decision() {
+        assert_eq!(
+            DecisionKind::from_decision("allowed"),
+            DecisionKind::Allowed
+        );
+        assert_eq!(DecisionKind::from_decision("Allow"), DecisionKind::Allowed);
+        assert_eq!(DecisionKind::from_decision("banned"), DecisionKind::Banned);
+        assert_eq!(
+            DecisionKind::from_decision("rejected"),
+            DecisionKind::Rejected
+        );
+        assert_eq!(
+            DecisionKind::from_decision("denied"),
+            DecisionKind::Rejected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_logger_samples_allowed_decisions() {
+        let config = AuditLogConfig::new().sample_rate(0.1);
+        let logger = AuditLogger::new(config).await;
+
+        const N: usize = 10_000;
+        let logged = (0..N)
+            .filter(|i| logger.should_log_decision("allowed", Some(&format!("req-{}", i))))
+            .count();
+        assert!((800..=1200).contains(&logged), "logged {} of {}", logged, N);
+
+        // 无请求ID时同样按比例采样
+        let logged = (0..N)
+            .filter(|_| logger.should_log_decision("allowed", None))
+            .count();
+        assert!((800..=1200).contains(&logged), "logged {} of {}", logged, N);
+
+        // 同一请求ID的采样结果一致
+        for i in 0..100 {
+            let request_id = format!("req-{}", i);
+            assert_eq!(
+                logger.should_log_decision("allowed", Some(&request_id)),
+                logger.should_log_decision("allowed", Some(&request_id))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_logger_always_logs_rejections_and_bans() {
+        let config = AuditLogConfig::new().sample_rate(0.0);
+        let logger = AuditLogger::new(config).await;
+
+        for i in 0..20 {
+            let request_id = Some(format!("req-{}", i));
+            for decision in ["allowed", "rejected", "banned"] {
+                logger
+                    .log_decision(
+                        "user123".to_string(),
+                        decision.to_string(),
+                        "test".to_string(),
+                        request_id.clone(),
+                    )
+                    .await;
+            }
+        }
+        logger.flush().await.unwrap();
+
+        assert_eq!(logger.stats().decision_events(), 40);
+        assert_eq!(logger.stats().sampled_out(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_audit_logger_flush_preserves_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let config = AuditLogConfig::new()
+            .batch_size(7)
+            .batch_timeout(Duration::from_secs(3600))
+            .output_path(path.to_string_lossy().into_owned());
+        let logger = AuditLogger::new(config).await;
+
+        const N: usize = 50;
+        for i in 0..N {
+            logger
+                .log_decision(
+                    "user123".to_string(),
+                    "allowed".to_string(),
+                    format!("event-{}", i),
+                    None,
+                )
+                .await;
+        }
+        logger.flush().await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let reasons: Vec<String> = serde_json::Deserializer::from_str(&content)
+            .into_iter::<serde_json::Value>()
+            .map(|event| event.unwrap()["reason"].as_str().unwrap().to_string())
+            .collect();
+        let expected: Vec<String> = (0..N).map(|i| format!("event-{}", i)).collect();
+        assert_eq!(reasons, expected);
+        assert_eq!(logger.stats().total_events(), N as u64);
+    }
+
+    #[derive(Default)]
+    struct CollectingSink {
+        events: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AuditSink for CollectingSink {
+        async fn write(&self, event: &AuditEvent) -> Result<(), FlowGuardError> {
+            self.events.lock().push(serde_json::to_string(event)?);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_logger_custom_sink() {
+        let sink = Arc::new(CollectingSink::default());
+        let logger = AuditLogger::with_sink(AuditLogConfig::new(), sink.clone()).await;
+
+        logger
+            .log_config_change("v1".to_string(), "v2".to_string(), vec![], None)
+            .await;
+        logger.flush().await.unwrap();
+
+        let events = sink.events.lock();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].contains("\"event_type\":\"ConfigChange\""));
+    }
+
+    #[tokio::test]
+    async fn test_audit_logger_flush_reports_write_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        // 输出路径是目录，写入必然失败
+        let config = AuditLogConfig::new().output_path(dir.path().to_string_lossy().into_owned());
+        let logger = AuditLogger::new(config).await;
+
+        logger
+            .log_system_event("info".to_string(), "start".to_string(), String::new())
+            .await;
+        assert!(matches!(
+            logger.flush().await,
+            Err(FlowGuardError::AuditLogError(_))
+        ));
+        assert_eq!(logger.stats().write_failures(), 1);
+    }
 }
@@ -0,0 +1,303 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! 审计日志输出端
+//!
+//! [`AuditLogger`](super::AuditLogger) 的后台任务按提交顺序将事件交给 [`AuditSink`]。
+//! 内置三种实现：
+//!
+//! - [`TracingSink`]：通过 `tracing` 以 info 级别输出（未配置输出路径时的默认值）
+//! - [`JsonStdoutSink`]：以 JSON Lines 格式输出到标准输出
+//! - [`RotatingFileSink`]：以 JSON Lines 格式写入文件，按大小或时间轮转
+
+use super::AuditEvent;
+use crate::error::FlowGuardError;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// 审计事件输出端
+///
+/// 事件由单个后台任务依次写入，实现无需保证并发写入的顺序。
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// 写入单个事件
+    async fn write(&self, event: &AuditEvent) -> Result<(), FlowGuardError>;
+
+    /// 将已写入的事件持久化，默认无操作
+    async fn flush(&self) -> Result<(), FlowGuardError> {
+        Ok(())
+    }
+}
+
+/// 通过 `tracing` 输出审计事件
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingSink;
+
+#[async_trait]
+impl AuditSink for TracingSink {
+    async fn write(&self, event: &AuditEvent) -> Result<(), FlowGuardError> {
+        info!("审计日志: {}", serde_json::to_string(event)?);
+        Ok(())
+    }
+}
+
+/// 以 JSON Lines 格式将审计事件输出到标准输出
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonStdoutSink;
+
+#[async_trait]
+impl AuditSink for JsonStdoutSink {
+    async fn write(&self, event: &AuditEvent) -> Result<(), FlowGuardError> {
+        let line = serde_json::to_string(event)?;
+        writeln!(std::io::stdout().lock(), "{}", line)?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), FlowGuardError> {
+        std::io::stdout().lock().flush()?;
+        Ok(())
+    }
+}
+
+/// 当前打开的日志文件
+#[derive(Debug)]
+struct OpenFile {
+    file: File,
+    /// 文件当前大小（字节）
+    size: u64,
+    /// 打开时间
+    opened_at: Instant,
+}
+
+/// 以 JSON Lines 格式写入文件的审计输出端
+///
+/// 每个事件占一行。写入前检查轮转条件：文件达到 `max_file_size`，或自打开以来超过
+/// `rotation_interval` 时，将 `audit.log` 依次重命名为 `audit.1.log`、`audit.2.log`…，
+/// 超过 `max_files` 的旧文件被删除。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::audit_log::RotatingFileSink;
+/// use std::time::Duration;
+///
+/// let sink = RotatingFileSink::new("/var/log/limiteron/audit.log")
+///     .max_file_size(100 * 1024 * 1024)
+///     .rotation_interval(Duration::from_secs(24 * 3600))
+///     .max_files(10);
+/// ```
+#[derive(Debug)]
+pub struct RotatingFileSink {
+    path: PathBuf,
+    max_file_size: Option<u64>,
+    rotation_interval: Option<Duration>,
+    max_files: Option<usize>,
+    file: Mutex<Option<OpenFile>>,
+}
+
+impl RotatingFileSink {
+    /// 创建写入 `path` 的文件输出端，默认不轮转
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_file_size: None,
+            rotation_interval: None,
+            max_files: None,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// 文件达到指定大小（字节）时轮转
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// 文件打开超过指定时长时轮转
+    pub fn rotation_interval(mut self, interval: Duration) -> Self {
+        self.rotation_interval = Some(interval);
+        self
+    }
+
+    /// 保留的轮转文件数量
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    /// 输出文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 以追加模式打开输出文件，必要时创建目录
+    fn open(&self) -> std::io::Result<OpenFile> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let size = file.metadata()?.len();
+        Ok(OpenFile {
+            file,
+            size,
+            opened_at: Instant::now(),
+        })
+    }
+
+    /// 当前文件是否需要轮转
+    fn should_rotate(&self, current: &OpenFile) -> bool {
+        current.size > 0
+            && (self.max_file_size.is_some_and(|max| current.size >= max)
+                || self
+                    .rotation_interval
+                    .is_some_and(|interval| current.opened_at.elapsed() >= interval))
+    }
+
+    /// 第 `index` 个轮转文件的路径
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("audit");
+        let extension = self
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("log");
+        self.path
+            .with_file_name(format!("{}.{}.{}", stem, index, extension))
+    }
+
+    /// 日志轮转
+    ///
+    /// 将当前日志文件重命名，并删除旧的日志文件
+    fn rotate(&self) -> std::io::Result<()> {
+        if let Some(max_files) = self.max_files {
+            let oldest = self.rotated_path(max_files);
+            if oldest.exists() {
+                std::fs::remove_file(&oldest)?;
+            }
+
+            for i in (1..max_files).rev() {
+                let old_name = self.rotated_path(i);
+                if old_name.exists() {
+                    std::fs::rename(&old_name, self.rotated_path(i + 1))?;
+                }
+            }
+        }
+
+        std::fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    fn write_line(&self, line: &str) -> std::io::Result<()> {
+        let mut guard = self.file.lock();
+
+        if guard
+            .as_ref()
+            .is_some_and(|current| self.should_rotate(current))
+        {
+            *guard = None;
+            self.rotate()?;
+        }
+
+        let current = match guard.as_mut() {
+            Some(current) => current,
+            None => guard.insert(self.open()?),
+        };
+        current.file.write_all(line.as_bytes())?;
+        current.file.write_all(b"\n")?;
+        current.size += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditSink for RotatingFileSink {
+    async fn write(&self, event: &AuditEvent) -> Result<(), FlowGuardError> {
+        let line = serde_json::to_string(event)?;
+        self.write_line(&line)?;
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), FlowGuardError> {
+        if let Some(current) = self.file.lock().as_mut() {
+            current.file.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(i: usize) -> AuditEvent {
+        AuditEvent::SystemEvent {
+            timestamp: Utc::now(),
+            level: "info".to_string(),
+            name: format!("event-{}", i),
+            details: "x".repeat(64),
+        }
+    }
+
+    fn lines(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is valid JSON"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_rotating_file_sink_rotates_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let sink = RotatingFileSink::new(&path).max_file_size(512).max_files(2);
+
+        for i in 0..20 {
+            sink.write(&event(i)).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let current = lines(&path);
+        let first = lines(&sink.rotated_path(1));
+        let second = lines(&sink.rotated_path(2));
+        assert!(!sink.rotated_path(3).exists());
+        for file in [&current, &first, &second] {
+            assert!(!file.is_empty());
+        }
+        assert!(std::fs::metadata(sink.rotated_path(1)).unwrap().len() >= 512);
+
+        // 最新的事件位于当前文件末尾，轮转文件依次更旧
+        assert_eq!(current.last().unwrap()["name"], "event-19");
+        let name =
+            |v: &serde_json::Value| v["name"].as_str().unwrap()[6..].parse::<usize>().unwrap();
+        assert!(name(second.last().unwrap()) < name(&first[0]));
+        assert!(name(first.last().unwrap()) < name(&current[0]));
+    }
+
+    #[tokio::test]
+    async fn test_rotating_file_sink_rotates_by_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let sink = RotatingFileSink::new(&path).rotation_interval(Duration::from_millis(20));
+
+        sink.write(&event(0)).await.unwrap();
+        sink.write(&event(1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        sink.write(&event(2)).await.unwrap();
+
+        assert_eq!(lines(&sink.rotated_path(1)).len(), 2);
+        assert_eq!(lines(&path).len(), 1);
+    }
+}
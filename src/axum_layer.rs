@@ -0,0 +1,189 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! axum 集成模块
+//!
+//! 提供基于 [`GovernorService`] 的 tower [`Layer`] 实现 [`GovernorLayer`]：从 `http::Request` 构建
+//! [`RequestContext`]（方法、路径、查询参数、请求头、连接对端 IP），
+//! 调用 [`Governor::check_with_limits`]，允许时转发请求并附加 `X-RateLimit-*` 响应头，
+//! 拒绝时返回 `429 Too Many Requests`，封禁时返回 `403 Forbidden`，两者都带有 `Retry-After`。
+
+use crate::error::{Decision, RejectReason};
+use crate::governor::Governor;
+use crate::headers::retry_after_secs;
+use crate::limiters::RateLimitDecision;
+use crate::matchers::RequestContext;
+use crate::middleware::{
+    GovernorService, GovernorServiceLayer, GuardRejection, RequestContextBuilder, ResponseBuilder,
+};
+use axum::extract::ConnectInfo;
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tower_layer::Layer;
+
+pub use crate::headers::DEFAULT_RETRY_AFTER_SECS;
+
+/// 从请求头部构建 [`RequestContext`] 的函数
+pub type ContextExtractor = Arc<dyn Fn(&Parts) -> RequestContext + Send + Sync>;
+
+/// 从请求头部构建请求上下文
+///
+/// 写入方法、路径、查询参数和所有可读的请求头；使用
+/// `into_make_service_with_connect_info::<SocketAddr>()` 启动时，对端地址写入 `client_ip`。
+pub fn request_context_from_parts(parts: &Parts) -> RequestContext {
+    let mut context = RequestContext::new().with_path(parts.uri.path());
+    context.method = parts.method.as_str().to_string();
+
+    for (name, value) in &parts.headers {
+        if let Ok(value) = value.to_str() {
+            context = context.with_header(name.as_str(), value);
+        }
+    }
+
+    if let Some(query) = parts.uri.query() {
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            context = context.with_query_param(key, value);
+        }
+    }
+
+    if let Some(ConnectInfo(addr)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() {
+        context = context.with_client_ip(&addr.ip().to_string());
+    }
+
+    context
+}
+
+/// 使用 [`ContextExtractor`] 从 `http::Request` 的头部构建请求上下文
+#[derive(Clone)]
+pub struct PartsContextBuilder(ContextExtractor);
+
+impl<B> RequestContextBuilder<Request<B>> for PartsContextBuilder {
+    fn build(&self, request: Request<B>) -> (Request<B>, RequestContext) {
+        let (parts, body) = request.into_parts();
+        let context = (self.0)(&parts);
+        (Request::from_parts(parts, body), context)
+    }
+}
+
+/// 构造 axum 响应：允许时附加 `X-RateLimit-*` 头，拒绝时返回 429/403/401/500
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AxumResponseBuilder;
+
+impl ResponseBuilder<Response> for AxumResponseBuilder {
+    fn rejected(&self, rejection: GuardRejection) -> Response {
+        match rejection {
+            GuardRejection::Denied {
+                decision: Decision::Banned(ban),
+                ..
+            } => {
+                let mut headers = HeaderMap::new();
+                let remaining = (ban.banned_until - chrono::Utc::now())
+                    .to_std()
+                    .unwrap_or_default();
+                insert_retry_after(&mut headers, Some(remaining));
+                (StatusCode::FORBIDDEN, headers, ban.reason).into_response()
+            }
+            GuardRejection::Denied {
+                decision: Decision::Rejected(rejection),
+                ..
+            } if rejection.reason == RejectReason::NoIdentifier => {
+                (StatusCode::UNAUTHORIZED, rejection.message).into_response()
+            }
+            GuardRejection::Denied { decision, limits } => {
+                let mut headers = HeaderMap::new();
+                let retry_after = limits.and_then(|limits| limits.retry_after);
+                if let Some(limits) = limits {
+                    insert_rate_limit_headers(&mut headers, &limits);
+                }
+                insert_retry_after(&mut headers, retry_after);
+                let message = decision.message().unwrap_or_default().to_string();
+                (StatusCode::TOO_MANY_REQUESTS, headers, message).into_response()
+            }
+            GuardRejection::Error(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+
+    fn allowed(&self, response: &mut Response, limits: &RateLimitDecision) {
+        insert_rate_limit_headers(response.headers_mut(), limits);
+    }
+}
+
+/// [`GovernorLayer`] 生成的中间件服务
+pub type GovernorMiddleware<S> = GovernorService<S, PartsContextBuilder, AxumResponseBuilder>;
+
+/// Governor 的 axum 中间件层
+///
+/// 基于 [`GovernorServiceLayer`]，使用 [`PartsContextBuilder`] 与 [`AxumResponseBuilder`]。
+///
+/// # 示例
+/// ```rust,no_run
+/// use axum::{routing::get, Router};
+/// use limiteron::axum_layer::GovernorLayer;
+/// use limiteron::governor::Governor;
+/// use std::sync::Arc;
+///
+/// # fn demo(governor: Arc<Governor>) {
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "ok" }))
+///     .layer(GovernorLayer::new(governor));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GovernorLayer {
+    inner: GovernorServiceLayer<PartsContextBuilder, AxumResponseBuilder>,
+}
+
+impl GovernorLayer {
+    /// 创建新的中间件层，使用 [`request_context_from_parts`] 构建请求上下文
+    pub fn new(governor: Arc<Governor>) -> Self {
+        Self {
+            inner: GovernorServiceLayer::new(
+                governor,
+                PartsContextBuilder(Arc::new(request_context_from_parts)),
+                AxumResponseBuilder,
+            ),
+        }
+    }
+
+    /// 自定义请求上下文的构建方式
+    ///
+    /// 例如从已认证的会话中取出用户 ID 写入 `X-User-Id`，交给 Governor 的提取链识别。
+    pub fn with_context_extractor<F>(self, extractor: F) -> Self
+    where
+        F: Fn(&Parts) -> RequestContext + Send + Sync + 'static,
+    {
+        Self {
+            inner: self
+                .inner
+                .with_context_builder(PartsContextBuilder(Arc::new(extractor))),
+        }
+    }
+}
+
+impl<S> Layer<S> for GovernorLayer {
+    type Service = GovernorMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.inner.layer(inner)
+    }
+}
+
+/// 写入 `X-RateLimit-Limit` 与 `X-RateLimit-Remaining`
+fn insert_rate_limit_headers(headers: &mut HeaderMap, limits: &RateLimitDecision) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(limits.limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(limits.remaining));
+}
+
+/// 写入 `Retry-After`（秒，向上取整，至少 1 秒）
+fn insert_retry_after(headers: &mut HeaderMap, retry_after: Option<Duration>) {
+    headers.insert(
+        "retry-after",
+        HeaderValue::from(retry_after_secs(retry_after)),
+    );
+}
@@ -13,6 +13,7 @@
 //! - 封禁优先级管理（IP > User > MAC > Device > APIKey）
 //! - 自动解封定时任务
 //! - 完整的审计日志
+//! - 流式导出封禁记录（键集分页，内存占用有界）
 //! - 并行封禁检查（性能提升 50-70%）
 
 /// 第一次封禁时长（1分钟）
@@ -39,6 +40,9 @@ pub const DEFAULT_PAGINATION_LIMIT: u64 = 100;
 /// 最大分页限制
 pub const MAX_PAGINATION_LIMIT: u64 = 1000;
 
+/// 封禁过滤器支持的目标类型
+const BAN_TARGET_TYPES: [&str; 3] = ["ip", "user", "mac"];
+
 /// 最大封禁原因长度
 pub const MAX_BAN_REASON_LENGTH: usize = 500;
 
@@ -48,10 +52,14 @@ pub const MAX_USER_ID_LENGTH: usize = 100;
 /// 最大MAC地址长度
 pub const MAX_MAC_ADDRESS_LENGTH: usize = 17;
 
+use crate::clock::{Clock, RealClock};
 use crate::error::FlowGuardError;
 use crate::storage::{BanRecord, BanStorage, BanTarget};
 use chrono::{DateTime, Duration, Utc};
+use futures::stream::{self, Stream, TryStreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use std::time::Duration as StdDuration;
 use tokio::sync::RwLock;
@@ -177,6 +185,92 @@ pub struct BanFilter {
     pub limit: Option<u64>,
 }
 
+impl BanFilter {
+    /// 判断封禁记录是否满足过滤条件（不含分页）
+    pub fn matches(&self, record: &BanRecord, now: DateTime<Utc>) -> bool {
+        let (target_type, target_value) = match &record.target {
+            BanTarget::Ip(ip) => ("ip", ip.as_str()),
+            BanTarget::UserId(user_id) => ("user", user_id.as_str()),
+            BanTarget::Mac(mac) => ("mac", mac.as_str()),
+        };
+
+        if let Some(expected) = &self.target_type {
+            if !expected.eq_ignore_ascii_case(target_type) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.target_value {
+            if !target_value.contains(pattern.as_str()) {
+                return false;
+            }
+        }
+        // 手动封禁不会自动过期
+        if self.active_only && !record.is_manual && record.expires_at <= now {
+            return false;
+        }
+        if self.manual_only && !record.is_manual {
+            return false;
+        }
+        if self
+            .start_time
+            .is_some_and(|start| record.banned_at < start)
+        {
+            return false;
+        }
+        if self.end_time.is_some_and(|end| record.banned_at > end) {
+            return false;
+        }
+        true
+    }
+
+    /// 分页参数（偏移, 数量），数量不超过 [`MAX_PAGINATION_LIMIT`]
+    pub fn pagination(&self) -> (usize, usize) {
+        let limit = self
+            .limit
+            .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+            .min(MAX_PAGINATION_LIMIT);
+        (self.offset.unwrap_or(0) as usize, limit as usize)
+    }
+}
+
+/// 封禁记录的键集分页游标
+///
+/// 记录按 `(banned_at 倒序, key 正序)` 排列，游标指向上一页的最后一条记录，
+/// 下一页从它之后开始读取。`key` 由存储后端决定：PostgreSQL 使用记录 ID，
+/// 其他后端使用 [`BanCursor::target_key`]。
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "ban-manager")]
+pub struct BanCursor {
+    /// 封禁时间
+    pub banned_at: DateTime<Utc>,
+    /// 同一封禁时间内的排序键
+    pub key: String,
+}
+
+impl BanCursor {
+    /// 以封禁目标作为排序键创建游标
+    pub fn from_record(record: &BanRecord) -> Self {
+        Self {
+            banned_at: record.banned_at,
+            key: Self::target_key(&record.target),
+        }
+    }
+
+    /// 封禁目标的排序键，形如 `user:alice`
+    pub fn target_key(target: &BanTarget) -> String {
+        match target {
+            BanTarget::Ip(ip) => format!("ip:{}", ip),
+            BanTarget::UserId(user_id) => format!("user:{}", user_id),
+            BanTarget::Mac(mac) => format!("mac:{}", mac),
+        }
+    }
+
+    /// 排序位置在游标之后的记录
+    pub fn precedes(&self, banned_at: DateTime<Utc>, key: &str) -> bool {
+        banned_at < self.banned_at || (banned_at == self.banned_at && key > self.key.as_str())
+    }
+}
+
 /// 指数退避配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg(feature = "ban-manager")]
@@ -191,6 +285,15 @@ pub struct BackoffConfig {
     pub fourth_duration: u64,
     /// 最大封禁时长（秒）
     pub max_duration: u64,
+    /// 随机抖动百分比（±X%，0 表示不抖动）
+    ///
+    /// 避免同一批被封禁的客户端在封禁到期时同时重试。
+    #[serde(default)]
+    pub jitter_percent: u8,
+    /// 自适应模式：第四次之后的违规按几何级数增长直到 `max_duration`，
+    /// 而不是固定为 `fourth_duration`
+    #[serde(default)]
+    pub adaptive: bool,
 }
 
 impl Default for BackoffConfig {
@@ -201,7 +304,67 @@ impl Default for BackoffConfig {
             third_duration: THIRD_BAN_DURATION_SECS,
             fourth_duration: FOURTH_BAN_DURATION_SECS,
             max_duration: MAX_BAN_DURATION_SECS,
+            jitter_percent: 0,
+            adaptive: false,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// 计算第 `ban_times` 次违规的基础封禁时长（秒，不含抖动）
+    ///
+    /// 自适应模式下，第四次之后每次按 `fourth_duration / third_duration`
+    /// （至少为 2）的倍数增长。
+    pub fn base_duration_secs(&self, ban_times: u32) -> u64 {
+        let duration_secs = match ban_times {
+            1 => self.first_duration,
+            2 => self.second_duration,
+            3 => self.third_duration,
+            n if self.adaptive && n > 4 => {
+                let factor = self
+                    .fourth_duration
+                    .checked_div(self.third_duration)
+                    .unwrap_or(2)
+                    .max(2);
+                let mut secs = self.fourth_duration;
+                for _ in 4..n {
+                    if secs >= self.max_duration {
+                        break;
+                    }
+                    secs = secs.saturating_mul(factor);
+                }
+                secs
+            }
+            _ => self.fourth_duration,
+        };
+
+        // 不超过最大时长
+        duration_secs.min(self.max_duration)
+    }
+
+    /// 对基础时长应用抖动
+    ///
+    /// 抖动以封禁目标和违规次数为种子，同一目标的并发计算得到相同的时长。
+    pub fn apply_jitter(&self, duration_secs: u64, target: &BanTarget, ban_times: u32) -> u64 {
+        let percent = u64::from(self.jitter_percent.min(100));
+        if percent == 0 || duration_secs == 0 {
+            return duration_secs;
+        }
+
+        let span = duration_secs.saturating_mul(percent) / 100;
+        if span == 0 {
+            return duration_secs;
         }
+
+        let mut hasher = DefaultHasher::new();
+        target.hash(&mut hasher);
+        ban_times.hash(&mut hasher);
+        // 映射到 [-span, +span]
+        let offset = hasher.finish() % (span.saturating_mul(2).saturating_add(1));
+
+        (duration_secs - span)
+            .saturating_add(offset)
+            .clamp(1, self.max_duration.max(1))
     }
 }
 
@@ -227,6 +390,22 @@ impl Default for BanManagerConfig {
     }
 }
 
+/// 预封禁钩子的决策
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BanDecision {
+    /// 按默认逻辑继续封禁
+    Proceed,
+    /// 跳过本次封禁，不保存任何记录
+    Skip,
+    /// 使用指定时长封禁
+    Override(StdDuration),
+}
+
+/// 预封禁钩子
+///
+/// 在封禁次数计算完成、记录保存之前调用，参数为封禁目标和本次的封禁次数。
+pub type PreBanHook = Arc<dyn Fn(&BanTarget, u32) -> BanDecision + Send + Sync>;
+
 /// 封禁管理器
 ///
 /// 管理封禁记录的生命周期，提供CRUD接口和指数退避算法。
@@ -237,8 +416,32 @@ pub struct BanManager {
     storage: Arc<dyn BanStorage>,
     /// 配置
     config: Arc<RwLock<BanManagerConfig>>,
-    /// 自动解禁任务句柄
-    auto_unban_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    /// 自动解禁任务句柄，最后一个克隆释放时中止任务
+    auto_unban_handle: Arc<AutoUnbanTask>,
+    /// 预封禁钩子
+    pre_ban_hook: Arc<parking_lot::RwLock<Option<PreBanHook>>>,
+    /// 时间来源，决定封禁记录的开始与过期时间
+    clock: Arc<parking_lot::RwLock<Arc<dyn Clock>>>,
+}
+
+/// 自动解封任务句柄
+///
+/// 由 [`BanManager`] 的所有克隆共享，`Drop` 时中止仍在运行的任务，
+/// 避免未调用 [`BanManager::stop_auto_unban_task`] 时任务随进程一直存在。
+#[cfg(feature = "ban-manager")]
+#[derive(Default)]
+struct AutoUnbanTask {
+    handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+}
+
+#[cfg(feature = "ban-manager")]
+impl Drop for AutoUnbanTask {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.get_mut().take() {
+            handle.abort();
+            debug!("Auto-unban task aborted on drop");
+        }
+    }
 }
 
 /// 验证IP地址格式
@@ -326,6 +529,170 @@ fn validate_mac_address(mac: &str) -> Result<(), FlowGuardError> {
     Ok(())
 }
 
+/// `ban_records` 表的一行：
+/// (id, target_type, target_value, reason, ban_times, duration_secs,
+/// banned_at, expires_at, is_manual, unbanned_at, unbanned_by)
+#[cfg(feature = "postgres")]
+type BanRow = (
+    uuid::Uuid,
+    String,
+    String,
+    String,
+    i32,
+    i64,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    bool,
+    Option<DateTime<Utc>>,
+    Option<String>,
+);
+
+/// 将 `ban_records` 表的一行转换为封禁详情
+#[cfg(feature = "postgres")]
+fn ban_detail_from_row(row: BanRow) -> BanDetail {
+    let (
+        id,
+        target_type,
+        target_value,
+        reason,
+        ban_times,
+        duration_secs,
+        banned_at,
+        expires_at,
+        is_manual,
+        unbanned_at,
+        unbanned_by,
+    ) = row;
+
+    let target = match target_type.as_str() {
+        "ip" => BanTarget::Ip(target_value),
+        "user" => BanTarget::UserId(target_value),
+        "mac" => BanTarget::Mac(target_value),
+        _ => BanTarget::UserId(target_value), // 默认处理
+    };
+
+    BanDetail {
+        id: id.to_string(),
+        target,
+        ban_times: ban_times as u32,
+        duration: std::time::Duration::from_secs(duration_secs as u64),
+        banned_at,
+        expires_at,
+        is_manual,
+        reason,
+        source: if is_manual {
+            BanSource::Manual {
+                operator: unbanned_by.clone().unwrap_or_else(|| "unknown".to_string()),
+            }
+        } else {
+            BanSource::Auto
+        },
+        metadata: serde_json::json!({}),
+        created_at: banned_at,
+        updated_at: banned_at,
+        unbanned_at,
+        unbanned_by,
+    }
+}
+
+/// 读取 `after` 之后的一页封禁记录，返回每条记录及其游标
+async fn fetch_ban_page(
+    storage: &Arc<dyn BanStorage>,
+    filter: &BanFilter,
+    after: Option<&BanCursor>,
+    limit: usize,
+) -> Result<Vec<(BanCursor, BanDetail)>, FlowGuardError> {
+    if let Some(target_type) = &filter.target_type {
+        if !BAN_TARGET_TYPES.contains(&target_type.to_lowercase().as_str()) {
+            return Err(FlowGuardError::ConfigError("无效的目标类型".to_string()));
+        }
+    }
+
+    // PostgreSQL 使用记录 ID 作为排序键，同一目标的多条历史记录也能分别返回
+    #[cfg(feature = "postgres")]
+    if let Some(storage) = storage
+        .as_any()
+        .downcast_ref::<crate::postgres_storage::PostgresStorage>()
+    {
+        let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new(
+            "SELECT id, target_type, target_value, reason, ban_times, duration_secs, \
+             banned_at, expires_at, is_manual, unbanned_at, unbanned_by \
+             FROM ban_records WHERE TRUE",
+        );
+        if let Some(target_type) = &filter.target_type {
+            query
+                .push(" AND target_type = ")
+                .push_bind(target_type.to_lowercase());
+        }
+        if let Some(target_value) = &filter.target_value {
+            if target_value.len() > 255 {
+                return Err(FlowGuardError::ConfigError(
+                    "目标值长度超过限制".to_string(),
+                ));
+            }
+            let escaped_value = target_value.replace('%', "\\%").replace('_', "\\_");
+            query
+                .push(" AND target_value LIKE ")
+                .push_bind(format!("%{}%", escaped_value));
+        }
+        if filter.active_only {
+            query.push(" AND expires_at > now() AND unbanned_at IS NULL");
+        }
+        if filter.manual_only {
+            query.push(" AND is_manual = true");
+        }
+        if let Some(start) = filter.start_time {
+            query.push(" AND banned_at >= ").push_bind(start);
+        }
+        if let Some(end) = filter.end_time {
+            query.push(" AND banned_at <= ").push_bind(end);
+        }
+        if let Some(cursor) = after {
+            let id = uuid::Uuid::parse_str(&cursor.key)
+                .map_err(|e| FlowGuardError::ConfigError(format!("无效的分页游标: {}", e)))?;
+            query
+                .push(" AND (banned_at < ")
+                .push_bind(cursor.banned_at)
+                .push(" OR (banned_at = ")
+                .push_bind(cursor.banned_at)
+                .push(" AND id > ")
+                .push_bind(id)
+                .push("))");
+        }
+        query
+            .push(" ORDER BY banned_at DESC, id ASC LIMIT ")
+            .push_bind(limit as i64);
+
+        let rows = query
+            .build_query_as::<BanRow>()
+            .fetch_all(storage.pool())
+            .await
+            .map_err(|e| {
+                error!("分页查询封禁记录失败: {}", e);
+                FlowGuardError::StorageError(crate::error::StorageError::QueryError(e.to_string()))
+            })?;
+
+        return Ok(rows
+            .into_iter()
+            .map(|row| {
+                let cursor = BanCursor {
+                    banned_at: row.6,
+                    key: row.0.to_string(),
+                };
+                (cursor, ban_detail_from_row(row))
+            })
+            .collect());
+    }
+
+    // 其他存储后端通过 BanStorage::list_page 查询
+    Ok(storage
+        .list_page(filter, after, limit)
+        .await?
+        .into_iter()
+        .map(|record| (BanCursor::from_record(&record), BanDetail::from(record)))
+        .collect())
+}
+
 /// 验证封禁目标
 fn validate_ban_target(target: &BanTarget) -> Result<(), FlowGuardError> {
     match target {
@@ -390,7 +757,9 @@ impl BanManager {
         let ban_manager = Self {
             storage,
             config,
-            auto_unban_handle: Arc::new(RwLock::new(None)),
+            auto_unban_handle: Arc::new(AutoUnbanTask::default()),
+            pre_ban_hook: Arc::new(parking_lot::RwLock::new(None)),
+            clock: Arc::new(parking_lot::RwLock::new(RealClock::shared())),
         };
 
         // 启动自动解封任务
@@ -417,28 +786,22 @@ impl BanManager {
                 interval.tick().await;
                 debug!("Running auto-unban task");
 
-                // 清理过期封禁
-                #[cfg(feature = "postgres")]
-                if let Some(storage) = storage
-                    .clone()
-                    .as_any()
-                    .downcast_ref::<crate::postgres_storage::PostgresStorage>()
-                {
-                    #[cfg(feature = "postgres")]
-                    if let Err(e) = storage.cleanup_expired_bans().await {
-                        error!("Auto-unban task failed: {}", e);
-                    }
+                // 通过 BanStorage trait 清理过期封禁，适用于所有存储后端
+                match storage.cleanup_expired_bans().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("Auto-unban task removed {} expired bans", count),
+                    Err(e) => error!("Auto-unban task failed: {}", e),
                 }
             }
         });
 
-        *self.auto_unban_handle.write().await = Some(handle);
+        *self.auto_unban_handle.handle.write().await = Some(handle);
         info!("Auto-unban task started (interval: {}s)", interval_secs);
     }
 
     /// 停止自动解封任务
     pub async fn stop_auto_unban_task(&self) {
-        let mut handle_guard = self.auto_unban_handle.write().await;
+        let mut handle_guard = self.auto_unban_handle.handle.write().await;
         if let Some(handle) = handle_guard.take() {
             handle.abort();
             info!("Auto-unban task stopped");
@@ -457,20 +820,14 @@ impl BanManager {
     /// - 第一次违规：封禁1分钟
     /// - 第二次违规：封禁5分钟
     /// - 第三次违规：封禁30分钟
-    /// - 第四次及以上：封禁2小时
+    /// - 第四次及以上：封禁2小时（自适应模式下继续几何增长）
     /// - 最大封禁时长：24小时
+    ///
+    /// 该方法不含抖动，需要按目标抖动时使用 [`BanManager::calculate_ban_duration_for`]。
     #[instrument(skip(self))]
     pub async fn calculate_ban_duration(&self, ban_times: u32) -> StdDuration {
         let config = self.config.read().await;
-        let duration_secs = match ban_times {
-            1 => config.backoff.first_duration,
-            2 => config.backoff.second_duration,
-            3 => config.backoff.third_duration,
-            _ => config.backoff.fourth_duration,
-        };
-
-        // 不超过最大时长
-        let duration_secs = duration_secs.min(config.backoff.max_duration);
+        let duration_secs = config.backoff.base_duration_secs(ban_times);
 
         debug!(
             "Calculated ban duration: ban_times={}, duration={}s",
@@ -480,6 +837,73 @@ impl BanManager {
         StdDuration::from_secs(duration_secs)
     }
 
+    /// 计算指定目标的封禁时长（含抖动）
+    ///
+    /// # 参数
+    /// - `target`: 封禁目标，作为抖动种子
+    /// - `ban_times`: 封禁次数
+    #[instrument(skip(self))]
+    pub async fn calculate_ban_duration_for(
+        &self,
+        target: &BanTarget,
+        ban_times: u32,
+    ) -> StdDuration {
+        let config = self.config.read().await;
+        let base_secs = config.backoff.base_duration_secs(ban_times);
+        let duration_secs = config.backoff.apply_jitter(base_secs, target, ban_times);
+
+        debug!(
+            "Calculated ban duration: ban_times={}, base={}s, duration={}s",
+            ban_times, base_secs, duration_secs
+        );
+
+        StdDuration::from_secs(duration_secs)
+    }
+
+    /// 设置预封禁钩子
+    ///
+    /// 钩子在 [`create_ban`](Self::create_ban) 计算出封禁次数之后、保存记录之前调用，
+    /// 可用于放行内部服务（`Skip`）或调整封禁时长（`Override`）。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::ban_manager::{BanDecision, BanManager};
+    /// use limiteron::storage::{BanTarget, MockBanStorage};
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let ban_manager = BanManager::new(Arc::new(MockBanStorage), None).await.unwrap();
+    ///     ban_manager.set_pre_ban_hook(Arc::new(|target: &BanTarget, _ban_times: u32| {
+    ///         match target {
+    ///             BanTarget::Ip(ip) if ip.starts_with("10.") => BanDecision::Skip,
+    ///             _ => BanDecision::Proceed,
+    ///         }
+    ///     }));
+    /// }
+    /// ```
+    pub fn set_pre_ban_hook(&self, hook: PreBanHook) {
+        *self.pre_ban_hook.write() = Some(hook);
+    }
+
+    /// 移除预封禁钩子
+    pub fn clear_pre_ban_hook(&self) {
+        *self.pre_ban_hook.write() = None;
+    }
+
+    /// 设置时间来源
+    ///
+    /// 新建和更新的封禁记录使用该时钟计算 `banned_at` 与 `expires_at`，
+    /// 测试中可传入 [`MockClock`](crate::clock::MockClock) 控制封禁时长。
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        *self.clock.write() = clock;
+    }
+
+    /// 当前 UTC 时间
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.read().now_utc()
+    }
+
     /// 创建封禁记录
     ///
     /// # 参数
@@ -490,7 +914,8 @@ impl BanManager {
     /// - `duration`: 封禁时长（可选，不提供则自动计算）
     ///
     /// # 返回
-    /// - 封禁详情
+    /// - `Some(detail)`: 封禁详情
+    /// - `None`: 预封禁钩子返回 `Skip`，未保存任何记录
     #[instrument(skip(self, metadata))]
     pub async fn create_ban(
         &self,
@@ -499,7 +924,7 @@ impl BanManager {
         source: BanSource,
         metadata: serde_json::Value,
         duration: Option<StdDuration>,
-    ) -> Result<BanDetail, FlowGuardError> {
+    ) -> Result<Option<BanDetail>, FlowGuardError> {
         // 输入验证
         validate_ban_target(&target)?;
         validate_ban_reason(&reason)?;
@@ -513,24 +938,27 @@ impl BanManager {
         let history = self.storage.get_history(&target).await?;
         let ban_times = history.as_ref().map(|h| h.ban_times + 1).unwrap_or(1);
 
+        // 预封禁钩子（先克隆出来，避免在 await 期间持有锁）
+        let hook = self.pre_ban_hook.read().clone();
+        let decision = hook.map_or(BanDecision::Proceed, |hook| hook(&target, ban_times));
+
         // 计算封禁时长
-        let duration = match duration {
-            Some(d) => d,
-            None => {
-                // 使用默认配置计算
-                let config = self.config.read().await;
-                let duration_secs = match ban_times {
-                    1 => config.backoff.first_duration,
-                    2 => config.backoff.second_duration,
-                    3 => config.backoff.third_duration,
-                    _ => config.backoff.fourth_duration,
-                };
-                let duration_secs = duration_secs.min(config.backoff.max_duration);
-                StdDuration::from_secs(duration_secs)
+        let duration = match (decision, duration) {
+            (BanDecision::Skip, _) => {
+                info!(
+                    "Ban skipped by pre-ban hook: target={:?}, ban_times={}",
+                    target, ban_times
+                );
+                return Ok(None);
+            }
+            (BanDecision::Override(d), _) => d,
+            (BanDecision::Proceed, Some(d)) => d,
+            (BanDecision::Proceed, None) => {
+                self.calculate_ban_duration_for(&target, ban_times).await
             }
         };
 
-        let now = Utc::now();
+        let now = self.now();
         let expires_at = now + Duration::from_std(duration).unwrap();
         let is_manual = matches!(source, BanSource::Manual { .. });
 
@@ -568,7 +996,7 @@ impl BanManager {
             "Ban created successfully: id={}, ban_times={}",
             detail.id, ban_times
         );
-        Ok(detail)
+        Ok(Some(detail))
     }
 
     /// 查询封禁状态
@@ -615,7 +1043,7 @@ impl BanManager {
         }
 
         let mut record = current_record.unwrap();
-        let now = Utc::now();
+        let now = self.now();
 
         // 更新字段
         if let Some(new_reason) = reason {
@@ -724,6 +1152,13 @@ impl BanManager {
     pub async fn list_bans(&self, filter: BanFilter) -> Result<Vec<BanDetail>, FlowGuardError> {
         debug!("Listing bans with filter: {:?}", filter);
 
+        // 验证目标类型
+        if let Some(target_type) = &filter.target_type {
+            if !BAN_TARGET_TYPES.contains(&target_type.to_lowercase().as_str()) {
+                return Err(FlowGuardError::ConfigError("无效的目标类型".to_string()));
+            }
+        }
+
         // 如果是PostgreSQL存储，使用数据库查询
         #[cfg(feature = "postgres")]
         if let Some(storage) = self
@@ -736,10 +1171,6 @@ impl BanManager {
 
             // 目标类型过滤（使用参数化查询）
             if let Some(target_type) = &filter.target_type {
-                // 验证目标类型
-                if !["ip", "user", "mac"].contains(&target_type.to_lowercase().as_str()) {
-                    return Err(FlowGuardError::ConfigError("无效的目标类型".to_string()));
-                }
                 conditions.push("target_type = $1".to_string());
                 params.push(target_type.to_lowercase());
             }
@@ -794,22 +1225,7 @@ impl BanManager {
             let offset = filter.offset.unwrap_or(0);
 
             // 执行参数化查询
-            let mut query_builder = sqlx::query_as::<
-                _,
-                (
-                    uuid::Uuid,
-                    String,
-                    String,
-                    String,
-                    i32,
-                    i64,
-                    chrono::DateTime<chrono::Utc>,
-                    chrono::DateTime<chrono::Utc>,
-                    bool,
-                    Option<chrono::DateTime<chrono::Utc>>,
-                    Option<String>,
-                ),
-            >(&query);
+            let mut query_builder = sqlx::query_as::<_, BanRow>(&query);
 
             // 绑定分页参数
             query_builder = query_builder.bind(limit as i64).bind(offset as i64);
@@ -826,64 +1242,77 @@ impl BanManager {
             })?;
 
             // 转换结果
-            let bans: Vec<BanDetail> = results
-                .into_iter()
-                .map(
-                    |(
-                        id,
-                        target_type,
-                        target_value,
-                        reason,
-                        ban_times,
-                        duration_secs,
-                        banned_at,
-                        expires_at,
-                        is_manual,
-                        unbanned_at,
-                        unbanned_by,
-                    )| {
-                        let target = match target_type.as_str() {
-                            "ip" => BanTarget::Ip(target_value),
-                            "user" => BanTarget::UserId(target_value),
-                            "mac" => BanTarget::Mac(target_value),
-                            _ => BanTarget::UserId(target_value), // 默认处理
-                        };
-
-                        let unbanned_by_clone = unbanned_by.clone();
-
-                        BanDetail {
-                            id: id.to_string(),
-                            target,
-                            ban_times: ban_times as u32,
-                            duration: std::time::Duration::from_secs(duration_secs as u64),
-                            banned_at,
-                            expires_at,
-                            is_manual,
-                            reason,
-                            source: if is_manual {
-                                BanSource::Manual {
-                                    operator: unbanned_by_clone
-                                        .unwrap_or_else(|| "unknown".to_string()),
-                                }
-                            } else {
-                                BanSource::Auto
-                            },
-                            metadata: serde_json::json!({}),
-                            created_at: banned_at,
-                            updated_at: banned_at,
-                            unbanned_at,
-                            unbanned_by,
-                        }
-                    },
-                )
-                .collect();
+            let bans: Vec<BanDetail> = results.into_iter().map(ban_detail_from_row).collect();
 
             debug!("Found {} bans", bans.len());
-            Ok(bans)
-        } else {
-            // 对于内存存储，返回空列表（简化实现）
-            Ok(Vec::new())
+            return Ok(bans);
         }
+
+        // 其他存储后端通过 BanStorage::list_active 查询
+        let bans: Vec<BanDetail> = self
+            .storage
+            .list_active(&filter)
+            .await?
+            .into_iter()
+            .map(BanDetail::from)
+            .collect();
+
+        debug!("Found {} bans", bans.len());
+        Ok(bans)
+    }
+
+    /// 以流的形式逐条返回满足过滤条件的封禁记录
+    ///
+    /// 按 `(banned_at 倒序, 排序键)` 的键集分页逐页读取存储后端，任一时刻只在内存中
+    /// 保留一页记录，适合导出大量封禁。过滤器的 `limit` 作为每页读取数量，`offset` 被忽略。
+    /// 读取失败时流返回错误后结束。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use futures::TryStreamExt;
+    /// use limiteron::ban_manager::{BanFilter, BanManager};
+    /// use limiteron::storage::MemoryStorage;
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let ban_manager = BanManager::new(Arc::new(MemoryStorage::new()), None)
+    ///         .await
+    ///         .unwrap();
+    ///     let mut bans = Box::pin(ban_manager.stream_bans(BanFilter::default()));
+    ///     while let Some(ban) = bans.try_next().await.unwrap() {
+    ///         println!("{:?}", ban.target);
+    ///     }
+    /// }
+    /// ```
+    pub fn stream_bans(
+        &self,
+        filter: BanFilter,
+    ) -> impl Stream<Item = Result<BanDetail, FlowGuardError>> + Send + 'static {
+        let storage = self.storage.clone();
+        let page_size = filter.pagination().1.max(1);
+
+        // 状态为下一页的起始游标，None 表示已读完
+        stream::try_unfold(Some(None), move |after: Option<Option<BanCursor>>| {
+            let storage = storage.clone();
+            let filter = filter.clone();
+            async move {
+                let Some(after) = after else {
+                    return Ok::<_, FlowGuardError>(None);
+                };
+                let page = fetch_ban_page(&storage, &filter, after.as_ref(), page_size).await?;
+                debug!("Streamed ban page of {} records", page.len());
+
+                let next = if page.len() < page_size {
+                    None
+                } else {
+                    page.last().map(|(cursor, _)| Some(cursor.clone()))
+                };
+                let bans = stream::iter(page.into_iter().map(|(_, ban)| Ok(ban)));
+                Ok(Some((bans, next)))
+            }
+        })
+        .try_flatten()
     }
 
     /// 检查封禁优先级（并行版本，支持提前退出）
@@ -923,43 +1352,53 @@ impl BanManager {
             .map(|target| {
                 let target = target.clone();
                 let storage = storage.clone();
-                Box::pin(async move {
-                    let record = storage.is_banned(&target).await.ok()?;
-                    record.map(|r| (BanPriority::from_target(&target), BanDetail::from(r)))
-                })
+                async move {
+                    storage.is_banned(&target).await.map(|record| {
+                        record.map(|r| (BanPriority::from_target(&target), BanDetail::from(r)))
+                    })
+                }
             })
             .collect();
 
-        if check_futures.is_empty() {
-            return Ok(None);
-        }
-
-        // 使用 select! 实现提前退出
         #[cfg(feature = "parallel-checker")]
-        match futures::future::select_all(check_futures).await {
-            (Some((priority, detail)), _, _) => {
-                debug!(
-                    "Found ban with priority {:?}: target={:?}",
-                    priority, detail.target
-                );
-                Ok(Some(detail))
-            }
-            _ => Ok(None),
-        }
+        let results = futures::future::join_all(check_futures).await;
 
         #[cfg(not(feature = "parallel-checker"))]
-        {
-            // 顺序检查（当 parallel-checker 未启用时）
+        let results = {
+            // 顺序检查（当 parallel-checker 未启用时），找到封禁即停止
+            let mut results = Vec::with_capacity(check_futures.len());
             for future in check_futures {
-                if let Some((priority, detail)) = future.await {
+                let result = future.await;
+                let found = matches!(result, Ok(Some(_)));
+                results.push(result);
+                if found {
+                    break;
+                }
+            }
+            results
+        };
+
+        // 存在封禁时忽略其他目标的存储错误，否则返回第一个错误
+        let mut first_error = None;
+        for result in results {
+            match result {
+                Ok(Some((priority, detail))) => {
                     debug!(
                         "Found ban with priority {:?}: target={:?}",
                         priority, detail.target
                     );
                     return Ok(Some(detail));
                 }
+                Ok(None) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
             }
-            Ok(None)
+        }
+
+        match first_error {
+            Some(e) => Err(FlowGuardError::StorageError(e)),
+            None => Ok(None),
         }
     }
 
@@ -999,7 +1438,9 @@ impl BanManager {
                 Some(record.duration),
             )
             .await?;
-        info!("Ban added: {:?}", detail);
+        if let Some(detail) = detail {
+            info!("Ban added: {:?}", detail);
+        }
         Ok(())
     }
 
@@ -1046,6 +1487,7 @@ impl BanManager {
 mod tests {
     use super::*;
     use crate::storage::MockBanStorage;
+    use ahash::AHashSet;
 
     #[allow(dead_code)]
     fn create_test_ban_manager() -> BanManager {
@@ -1083,6 +1525,60 @@ mod tests {
         assert_eq!(config.third_duration, 1800);
         assert_eq!(config.fourth_duration, 7200);
         assert_eq!(config.max_duration, 86400);
+        assert_eq!(config.jitter_percent, 0);
+        assert!(!config.adaptive);
+    }
+
+    #[test]
+    fn test_backoff_adaptive_growth() {
+        let config = BackoffConfig {
+            adaptive: true,
+            ..Default::default()
+        };
+
+        // 前四次与固定模式一致
+        assert_eq!(config.base_duration_secs(4), 7200);
+        // 7200 / 1800 = 4 倍增长，直到最大时长
+        assert_eq!(config.base_duration_secs(5), 28800);
+        assert_eq!(config.base_duration_secs(6), 86400);
+        assert_eq!(config.base_duration_secs(100), 86400);
+
+        let fixed = BackoffConfig::default();
+        assert_eq!(fixed.base_duration_secs(5), 7200);
+        assert_eq!(fixed.base_duration_secs(100), 7200);
+    }
+
+    #[test]
+    fn test_backoff_jitter_seeded_per_target() {
+        let config = BackoffConfig {
+            jitter_percent: 20,
+            ..Default::default()
+        };
+        let target = BanTarget::Ip("192.168.1.1".to_string());
+
+        // 同一目标、同一违规次数得到相同结果
+        let first = config.apply_jitter(1000, &target, 1);
+        assert_eq!(first, config.apply_jitter(1000, &target, 1));
+        assert!((800..=1200).contains(&first));
+
+        // 不同目标分散在抖动区间内
+        let durations: AHashSet<u64> = (0..50)
+            .map(|i| {
+                let target = BanTarget::Ip(format!("10.0.0.{}", i));
+                let secs = config.apply_jitter(1000, &target, 1);
+                assert!((800..=1200).contains(&secs));
+                secs
+            })
+            .collect();
+        assert!(durations.len() > 1);
+
+        // 抖动不超过最大时长
+        assert!(config.apply_jitter(86400, &target, 1) <= 86400);
+        // 未配置抖动时保持原值
+        assert_eq!(
+            BackoffConfig::default().apply_jitter(1000, &target, 1),
+            1000
+        );
     }
 
     #[test]
@@ -1133,13 +1629,41 @@ mod tests {
             .await;
 
         assert!(result.is_ok());
-        let detail = result.unwrap();
+        let detail = result.unwrap().unwrap();
         assert_eq!(detail.target, target);
         assert_eq!(detail.reason, reason);
         assert!(!detail.is_manual);
         assert_eq!(detail.ban_times, 1);
     }
 
+    #[tokio::test]
+    async fn test_create_ban_uses_clock() {
+        use crate::clock::MockClock;
+
+        let ban_manager = BanManager::new(Arc::new(MockBanStorage), None)
+            .await
+            .unwrap();
+        let clock = Arc::new(MockClock::new());
+        clock.advance(StdDuration::from_secs(7200));
+        ban_manager.set_clock(clock.clone());
+
+        let detail = ban_manager
+            .create_ban(
+                BanTarget::UserId("user123".to_string()),
+                "Clocked".to_string(),
+                BanSource::Auto,
+                serde_json::json!({}),
+                Some(StdDuration::from_secs(60)),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(detail.banned_at, clock.now_utc());
+        assert_eq!(detail.expires_at, clock.now_utc() + Duration::seconds(60));
+        assert_eq!(detail.created_at, detail.banned_at);
+    }
+
     #[tokio::test]
     async fn test_create_ban_manual() {
         let storage = Arc::new(MockBanStorage);
@@ -1164,13 +1688,89 @@ mod tests {
             .await;
 
         assert!(result.is_ok());
-        let detail = result.unwrap();
+        let detail = result.unwrap().unwrap();
         assert_eq!(detail.target, target);
         assert_eq!(detail.reason, reason);
         assert!(detail.is_manual);
         assert_eq!(detail.duration, duration);
     }
 
+    #[tokio::test]
+    async fn test_pre_ban_hook_skip() {
+        let storage = Arc::new(crate::storage::MemoryStorage::new());
+        let ban_manager = BanManager::new(storage.clone(), None).await.unwrap();
+        ban_manager.set_pre_ban_hook(Arc::new(|target: &BanTarget, _| match target {
+            BanTarget::Ip(ip) if ip.starts_with("10.") => BanDecision::Skip,
+            _ => BanDecision::Proceed,
+        }));
+
+        let internal = BanTarget::Ip("10.0.0.8".to_string());
+        let detail = ban_manager
+            .create_ban(
+                internal.clone(),
+                "Excessive requests".to_string(),
+                BanSource::Auto,
+                serde_json::json!({}),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(detail.is_none());
+        assert!(storage.is_banned(&internal).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pre_ban_hook_override() {
+        let storage = Arc::new(crate::storage::MemoryStorage::new());
+        let ban_manager = BanManager::new(storage.clone(), None).await.unwrap();
+        ban_manager.set_pre_ban_hook(Arc::new(|_: &BanTarget, ban_times| {
+            assert_eq!(ban_times, 1);
+            BanDecision::Override(StdDuration::from_secs(5))
+        }));
+
+        let target = BanTarget::UserId("user123".to_string());
+        let detail = ban_manager
+            .create_ban(
+                target.clone(),
+                "Excessive requests".to_string(),
+                BanSource::Auto,
+                serde_json::json!({}),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(detail.duration, StdDuration::from_secs(5));
+        let record = storage.is_banned(&target).await.unwrap().unwrap();
+        assert_eq!(record.duration, StdDuration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_pre_ban_hook_proceed() {
+        let storage = Arc::new(crate::storage::MemoryStorage::new());
+        let ban_manager = BanManager::new(storage.clone(), None).await.unwrap();
+        ban_manager.set_pre_ban_hook(Arc::new(|_: &BanTarget, _| BanDecision::Proceed));
+
+        let target = BanTarget::UserId("user123".to_string());
+        let detail = ban_manager
+            .create_ban(
+                target.clone(),
+                "Excessive requests".to_string(),
+                BanSource::Auto,
+                serde_json::json!({}),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        // 第一次违规：默认封禁1分钟
+        assert_eq!(detail.duration, StdDuration::from_secs(60));
+        assert!(storage.is_banned(&target).await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_read_ban_not_found() {
         let storage = Arc::new(MockBanStorage);
@@ -1221,6 +1821,240 @@ mod tests {
         assert!(result.unwrap().is_empty());
     }
 
+    /// 创建包含多种封禁记录的内存存储
+    async fn create_listing_fixture() -> BanManager {
+        let storage = Arc::new(crate::storage::MemoryStorage::new());
+        let ban_manager = BanManager::new(storage.clone(), None).await.unwrap();
+
+        for ip in ["10.0.0.1", "10.0.0.2", "10.0.0.3"] {
+            ban_manager
+                .create_ban(
+                    BanTarget::Ip(ip.to_string()),
+                    "Excessive requests".to_string(),
+                    BanSource::Auto,
+                    serde_json::json!({}),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        ban_manager
+            .create_ban(
+                BanTarget::UserId("alice".to_string()),
+                "Abuse".to_string(),
+                BanSource::Manual {
+                    operator: "admin".to_string(),
+                },
+                serde_json::json!({}),
+                Some(StdDuration::from_secs(600)),
+            )
+            .await
+            .unwrap();
+
+        // 已过期但尚未清理的封禁
+        let now = Utc::now();
+        storage
+            .save(&BanRecord {
+                target: BanTarget::UserId("bob".to_string()),
+                ban_times: 1,
+                duration: StdDuration::from_secs(60),
+                banned_at: now - Duration::seconds(120),
+                expires_at: now - Duration::seconds(60),
+                is_manual: false,
+                reason: "Expired".to_string(),
+            })
+            .await
+            .unwrap();
+
+        ban_manager
+    }
+
+    #[tokio::test]
+    async fn test_list_bans_memory_storage() {
+        let ban_manager = create_listing_fixture().await;
+
+        let all = ban_manager.list_bans(BanFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 5);
+
+        let active = ban_manager
+            .list_bans(BanFilter {
+                active_only: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(active.len(), 4);
+        assert!(active
+            .iter()
+            .all(|ban| ban.target != BanTarget::UserId("bob".to_string())));
+
+        let users = ban_manager
+            .list_bans(BanFilter {
+                target_type: Some("user".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(users.len(), 2);
+
+        let active_users = ban_manager
+            .list_bans(BanFilter {
+                target_type: Some("USER".to_string()),
+                active_only: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(active_users.len(), 1);
+        assert!(active_users[0].is_manual);
+
+        let by_value = ban_manager
+            .list_bans(BanFilter {
+                target_value: Some("0.0.2".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(by_value.len(), 1);
+        assert_eq!(by_value[0].target, BanTarget::Ip("10.0.0.2".to_string()));
+
+        let invalid = ban_manager
+            .list_bans(BanFilter {
+                target_type: Some("device".to_string()),
+                ..Default::default()
+            })
+            .await;
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_bans_pagination() {
+        let ban_manager = create_listing_fixture().await;
+        let page = |offset, limit| BanFilter {
+            target_type: Some("ip".to_string()),
+            offset: Some(offset),
+            limit: Some(limit),
+            ..Default::default()
+        };
+
+        let first = ban_manager.list_bans(page(0, 2)).await.unwrap();
+        let second = ban_manager.list_bans(page(2, 2)).await.unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+
+        // 结果按封禁时间倒序，分页之间不重复
+        assert!(first[0].banned_at >= first[1].banned_at);
+        assert!(second
+            .iter()
+            .all(|ban| first.iter().all(|other| other.target != ban.target)));
+    }
+
+    #[tokio::test]
+    async fn test_stream_bans_yields_each_ban_once_in_order() {
+        let storage = Arc::new(crate::storage::MemoryStorage::new());
+        let ban_manager = BanManager::new(storage.clone(), None).await.unwrap();
+
+        // 每 4 条记录共享同一封禁时间，覆盖跨页的同时间记录
+        let now = Utc::now();
+        for i in 0..1050 {
+            let banned_at = now - Duration::seconds(i / 4);
+            storage
+                .save(&BanRecord {
+                    target: BanTarget::UserId(format!("user{:04}", i)),
+                    ban_times: 1,
+                    duration: StdDuration::from_secs(3600),
+                    banned_at,
+                    expires_at: banned_at + Duration::seconds(3600),
+                    is_manual: false,
+                    reason: "Export".to_string(),
+                })
+                .await
+                .unwrap();
+        }
+        for i in 0..20 {
+            storage
+                .save(&BanRecord {
+                    target: BanTarget::Ip(format!("10.0.1.{}", i)),
+                    ban_times: 1,
+                    duration: StdDuration::from_secs(3600),
+                    banned_at: now,
+                    expires_at: now + Duration::seconds(3600),
+                    is_manual: false,
+                    reason: "Export".to_string(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let filter = BanFilter {
+            target_type: Some("user".to_string()),
+            limit: Some(64),
+            ..Default::default()
+        };
+        let first: Vec<BanDetail> = ban_manager
+            .stream_bans(filter.clone())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 1050);
+
+        let keys: Vec<String> = first
+            .iter()
+            .map(|ban| BanCursor::target_key(&ban.target))
+            .collect();
+        let unique: AHashSet<&String> = keys.iter().collect();
+        assert_eq!(unique.len(), 1050);
+        for pair in first.windows(2) {
+            let previous = BanCursor {
+                banned_at: pair[0].banned_at,
+                key: BanCursor::target_key(&pair[0].target),
+            };
+            assert!(previous.precedes(pair[1].banned_at, &BanCursor::target_key(&pair[1].target)));
+        }
+
+        // 顺序稳定，与页大小无关
+        let second: Vec<BanDetail> = ban_manager
+            .stream_bans(BanFilter {
+                limit: Some(1000),
+                ..filter
+            })
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(second
+            .iter()
+            .map(|ban| BanCursor::target_key(&ban.target))
+            .eq(keys));
+    }
+
+    #[tokio::test]
+    async fn test_stream_bans_invalid_target_type() {
+        let ban_manager = create_listing_fixture().await;
+
+        let mut bans = Box::pin(ban_manager.stream_bans(BanFilter {
+            target_type: Some("device".to_string()),
+            ..Default::default()
+        }));
+        assert!(matches!(
+            bans.try_next().await,
+            Err(FlowGuardError::ConfigError(_))
+        ));
+        assert!(bans.try_next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_bans_mock_storage_empty() {
+        let ban_manager = BanManager::new(Arc::new(MockBanStorage), None)
+            .await
+            .unwrap();
+        let bans: Vec<BanDetail> = ban_manager
+            .stream_bans(BanFilter::default())
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(bans.is_empty());
+    }
+
     #[tokio::test]
     async fn test_check_ban_priority_empty() {
         let storage = Arc::new(MockBanStorage);
@@ -1275,6 +2109,62 @@ mod tests {
         ban_manager.stop_auto_unban_task().await;
     }
 
+    #[tokio::test]
+    async fn test_auto_unban_cleans_memory_storage() {
+        let storage = Arc::new(crate::storage::MemoryStorage::new());
+        let config = BanManagerConfig {
+            auto_unban_interval: 1,
+            ..Default::default()
+        };
+        let ban_manager = BanManager::new(storage.clone(), Some(config))
+            .await
+            .unwrap();
+        ban_manager.set_pre_ban_hook(Arc::new(|_: &BanTarget, _| {
+            BanDecision::Override(StdDuration::from_secs(1))
+        }));
+
+        let target = BanTarget::Ip("192.168.1.50".to_string());
+        ban_manager
+            .create_ban(
+                target.clone(),
+                "Excessive requests".to_string(),
+                BanSource::Auto,
+                serde_json::json!({}),
+                None,
+            )
+            .await
+            .unwrap();
+        // get_ban_times 直接读取记录，不触发读取时的惰性清理
+        assert_eq!(storage.get_ban_times(&target).await.unwrap(), 1);
+
+        // 等待封禁过期并再经过一个清理周期
+        tokio::time::sleep(StdDuration::from_millis(2500)).await;
+        assert_eq!(storage.get_ban_times(&target).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_auto_unban_task_aborted_when_last_clone_dropped() {
+        let storage: Arc<dyn BanStorage> = Arc::new(MockBanStorage);
+        let ban_manager = BanManager::new(storage.clone(), None).await.unwrap();
+        let clone = ban_manager.clone();
+
+        // 后台任务持有一份存储引用
+        let baseline = Arc::strong_count(&storage);
+        drop(ban_manager);
+        tokio::task::yield_now().await;
+        assert_eq!(Arc::strong_count(&storage), baseline - 1);
+
+        // 最后一个克隆释放后，任务被中止并释放存储引用
+        drop(clone);
+        for _ in 0..100 {
+            if Arc::strong_count(&storage) == 1 {
+                break;
+            }
+            tokio::time::sleep(StdDuration::from_millis(10)).await;
+        }
+        assert_eq!(Arc::strong_count(&storage), 1);
+    }
+
     #[tokio::test]
     async fn test_ban_filter_default() {
         let filter = BanFilter::default();
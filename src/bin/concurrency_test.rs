@@ -66,7 +66,9 @@ fn create_test_config() -> FlowControlConfig {
             action: ActionConfig {
                 on_exceed: "allow".to_string(),
                 ban: None,
+                ..Default::default()
             },
+            ..Default::default()
         }],
     }
 }
@@ -94,6 +96,9 @@ async fn test_concurrent_same_user(
                 Ok(Decision::Allowed(_)) => {
                     success_count.fetch_add(1, Ordering::Relaxed);
                 }
+                Ok(Decision::Delayed(_)) => {
+                    success_count.fetch_add(1, Ordering::Relaxed);
+                }
                 Ok(Decision::Banned(_)) | Ok(Decision::Rejected(_)) => {
                     reject_count.fetch_add(1, Ordering::Relaxed);
                 }
@@ -66,7 +66,9 @@ fn create_test_config() -> FlowControlConfig {
             action: ActionConfig {
                 on_exceed: "allow".to_string(),
                 ban: None,
+                ..Default::default()
             },
+            ..Default::default()
         }],
     }
 }
@@ -0,0 +1,13 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! 配置检查命令行工具
+//!
+//! 用法见 [`limiteron::cli::USAGE`]。
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = limiteron::cli::run(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(code);
+}
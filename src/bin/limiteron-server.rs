@@ -0,0 +1,117 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! 独立 HTTP 限流服务
+//!
+//! 用法：`limiteron-server <config> [--addr <host:port>] [--poll-interval <secs>] [--admin-token <token>]`
+//!
+//! 配置文件格式按扩展名识别（`json`、`yaml`/`yml`、`toml`），
+//! 文件变更按轮询间隔热加载；收到 Ctrl-C 后优雅退出。
+//!
+//! 指定 `--admin-token`（或环境变量 `LIMITERON_ADMIN_TOKEN`）后，`/ban` 端点需携带
+//! `Authorization: Bearer <token>`；未指定时封禁端点不做认证，只应监听在本机地址上。
+
+use limiteron::config::FlowControlConfig;
+use limiteron::governor::Governor;
+use limiteron::server;
+use limiteron::storage::MemoryStorage;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const USAGE: &str = "用法: limiteron-server <config> [--addr <host:port>] [--poll-interval <secs>] [--admin-token <token>]";
+
+/// 管理令牌的环境变量名
+const ADMIN_TOKEN_ENV: &str = "LIMITERON_ADMIN_TOKEN";
+
+/// 命令行参数
+struct Args {
+    config: PathBuf,
+    addr: String,
+    poll_interval: Duration,
+    admin_token: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut config = None;
+    let mut addr = "127.0.0.1:8080".to_string();
+    let mut poll_interval = Duration::from_secs(5);
+    let mut admin_token = std::env::var(ADMIN_TOKEN_ENV).ok();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().ok_or("--addr 缺少参数")?,
+            "--poll-interval" => {
+                let secs: u64 = args
+                    .next()
+                    .ok_or("--poll-interval 缺少参数")?
+                    .parse()
+                    .map_err(|_| "--poll-interval 必须是正整数秒数")?;
+                if secs == 0 {
+                    return Err("--poll-interval 必须大于 0".to_string());
+                }
+                poll_interval = Duration::from_secs(secs);
+            }
+            "--admin-token" => {
+                admin_token = Some(args.next().ok_or("--admin-token 缺少参数")?);
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if config.is_none() && !arg.starts_with('-') => config = Some(PathBuf::from(arg)),
+            _ => return Err(format!("未知参数: {}\n{}", arg, USAGE)),
+        }
+    }
+
+    Ok(Args {
+        config: config.ok_or(USAGE)?,
+        addr,
+        poll_interval,
+        admin_token: admin_token.filter(|token| !token.is_empty()),
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let config = FlowControlConfig::from_path(&args.config)?;
+    let governor = Arc::new(
+        Governor::new(
+            config,
+            Arc::new(MemoryStorage::new()),
+            Arc::new(MemoryStorage::new()),
+            #[cfg(feature = "monitoring")]
+            None,
+            #[cfg(feature = "telemetry")]
+            None,
+        )
+        .await?,
+    );
+
+    let watcher = server::config_watcher(governor.clone(), args.config, args.poll_interval);
+    watcher.start().await?;
+
+    let listener = std::net::TcpListener::bind(&args.addr)?;
+    let router = match args.admin_token {
+        Some(token) => server::router_with_admin_token(governor, token),
+        None => server::router(governor),
+    };
+    server::serve_router(listener, router, async {
+        let _ = tokio::signal::ctrl_c().await;
+    })
+    .await?;
+
+    watcher.stop().await?;
+    Ok(())
+}
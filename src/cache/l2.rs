@@ -11,7 +11,8 @@
 //! - **高性能**: 使用DashMap实现无锁并发，P99延迟 < 1ms
 //! - **TTL管理**: 自动清理过期数据
 //! - **单飞模式**: 防止缓存击穿
-//! - **LRU淘汰**: 自动淘汰最少使用的数据
+//! - **负缓存**: 以独立的较短TTL缓存"未找到"结果，避免热点未命中反复穿透到存储
+//! - **可配置淘汰**: 容量满时按 [`EvictionPolicy`]（LRU/LFU/FIFO）淘汰数据
 //! - **批量操作**: 支持批量get/set操作
 //!
 //! # 使用示例
@@ -52,6 +53,9 @@ pub const DEFAULT_CLEANUP_INTERVAL_SECS: u64 = 60;
 /// 默认LRU淘汰阈值（90%）
 pub const DEFAULT_EVICTION_THRESHOLD: f64 = 0.9;
 
+/// 默认负缓存TTL（10秒）
+pub const DEFAULT_NEGATIVE_TTL_SECS: u64 = 10;
+
 use ahash::AHashMap as HashMap;
 use dashmap::DashMap;
 use std::num::NonZeroUsize;
@@ -66,7 +70,7 @@ use crate::error::StorageError;
 /// 缓存条目
 #[derive(Debug, Clone)]
 pub struct CacheEntry {
-    /// 缓存值
+    /// 缓存值（负缓存条目为空字符串）
     pub value: String,
     /// 过期时间（None表示永不过期）
     pub expires_at: Option<Instant>,
@@ -74,6 +78,8 @@ pub struct CacheEntry {
     pub last_accessed: Instant,
     /// 访问次数
     pub access_count: u64,
+    /// 是否为负缓存条目（已确认存储中不存在）
+    not_found: bool,
 }
 
 impl CacheEntry {
@@ -85,9 +91,26 @@ impl CacheEntry {
             expires_at,
             last_accessed: Instant::now(),
             access_count: 1,
+            not_found: false,
+        }
+    }
+
+    /// 创建"未找到"的负缓存条目
+    pub fn not_found(ttl: Duration) -> Self {
+        Self {
+            value: String::new(),
+            expires_at: Some(Instant::now() + ttl),
+            last_accessed: Instant::now(),
+            access_count: 1,
+            not_found: true,
         }
     }
 
+    /// 是否为负缓存条目
+    pub fn is_not_found(&self) -> bool {
+        self.not_found
+    }
+
     /// 检查是否过期
     pub fn is_expired(&self) -> bool {
         if let Some(expires_at) = self.expires_at {
@@ -104,6 +127,21 @@ impl CacheEntry {
     }
 }
 
+/// 淘汰策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// 淘汰最近最少使用的条目
+    #[default]
+    Lru,
+    /// 淘汰访问次数最少的条目，次数相同时淘汰最久未访问的
+    ///
+    /// 适合少数热点键被反复访问的场景（如频繁触发封禁检查的标识符），
+    /// 淘汰时需要扫描全部条目。
+    Lfu,
+    /// 按写入顺序淘汰，读取和更新不影响顺序
+    Fifo,
+}
+
 /// 单飞加载器
 struct SingleFlightLoader {
     /// 加载中的任务: key -> sender
@@ -151,17 +189,14 @@ impl SingleFlightLoader {
                         }
                     }
                     Ok(Err(_)) | Err(_) => {
-                        // 超时或 channel 关闭，清理 pending 条目
-                        warn!("加载超时或 channel 关闭，清理 pending 条目: key={}", key);
-                        self.pending.remove(&key_owned);
+                        // pending 条目由加载方负责清理，这里只返回错误
+                        warn!("加载超时或 channel 关闭: key={}", key);
                         return Err(StorageError::TimeoutError(
                             "Loader timeout or channel closed".to_string(),
                         ));
                     }
                 }
 
-                // 如果走到这里，说明没有获取到结果，清理 pending 条目
-                self.pending.remove(&key_owned);
                 Err(StorageError::TimeoutError(
                     "Loader dropped without result".to_string(),
                 ))
@@ -171,21 +206,36 @@ impl SingleFlightLoader {
                 let (tx, _) = watch::channel(None);
                 entry.insert(tx.clone());
 
+                // 加载完成、失败或被取消时都清理单飞条目，后续请求重新加载
+                let _guard = PendingGuard {
+                    pending: &self.pending,
+                    key: &key_owned,
+                };
+
                 // 执行加载
                 let result = loader().await;
 
                 // 通知等待者
                 let _ = tx.send(Some(result.clone()));
 
-                // 清理单飞条目
-                self.pending.remove(&key_owned);
-
                 result
             }
         }
     }
 }
 
+/// 单飞条目清理守卫
+struct PendingGuard<'a> {
+    pending: &'a DashMap<String, watch::Sender<Option<Result<String, StorageError>>>>,
+    key: &'a str,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.pending.remove(self.key);
+    }
+}
+
 /// L2缓存配置
 #[derive(Debug, Clone)]
 pub struct L2CacheConfig {
@@ -197,6 +247,10 @@ pub struct L2CacheConfig {
     pub cleanup_interval: Duration,
     /// LRU淘汰阈值（容量使用率超过此值时触发淘汰）
     pub eviction_threshold: f64,
+    /// 负缓存TTL（None表示不缓存"未找到"结果）
+    pub negative_ttl: Option<Duration>,
+    /// 淘汰策略
+    pub eviction_policy: EvictionPolicy,
 }
 
 impl Default for L2CacheConfig {
@@ -206,6 +260,8 @@ impl Default for L2CacheConfig {
             default_ttl: Some(Duration::from_secs(DEFAULT_TTL_SECS)),
             cleanup_interval: Duration::from_secs(DEFAULT_CLEANUP_INTERVAL_SECS),
             eviction_threshold: DEFAULT_EVICTION_THRESHOLD,
+            negative_ttl: Some(Duration::from_secs(DEFAULT_NEGATIVE_TTL_SECS)),
+            eviction_policy: EvictionPolicy::default(),
         }
     }
 }
@@ -268,6 +324,23 @@ impl L2CacheConfig {
         self
     }
 
+    /// 设置负缓存TTL，为0时关闭负缓存
+    pub fn negative_ttl(mut self, ttl: Duration) -> Self {
+        if ttl.is_zero() {
+            warn!("负缓存TTL为0，将关闭负缓存");
+            self.negative_ttl = None;
+        } else {
+            self.negative_ttl = Some(ttl);
+        }
+        self
+    }
+
+    /// 设置淘汰策略
+    pub fn eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction_policy = policy;
+        self
+    }
+
     /// 验证配置
     pub fn validate(&self) -> Result<(), String> {
         if self.capacity == 0 {
@@ -281,6 +354,9 @@ impl L2CacheConfig {
                 return Err("默认TTL不能为0".to_string());
             }
         }
+        if self.negative_ttl.is_some_and(|ttl| ttl.is_zero()) {
+            return Err("负缓存TTL不能为0".to_string());
+        }
         Ok(())
     }
 }
@@ -304,6 +380,8 @@ pub struct L2Cache {
 pub struct CacheStats {
     /// 内部统计数据（使用 Mutex 保证一致性）
     inner: Arc<std::sync::Mutex<StatsData>>,
+    /// 统计所属缓存的淘汰策略
+    policy: EvictionPolicy,
 }
 
 /// 内部统计数据
@@ -323,11 +401,22 @@ struct StatsData {
 
 impl CacheStats {
     pub fn new() -> Self {
+        Self::with_policy(EvictionPolicy::default())
+    }
+
+    /// 创建指定淘汰策略的统计
+    pub fn with_policy(policy: EvictionPolicy) -> Self {
         Self {
             inner: Arc::new(std::sync::Mutex::new(StatsData::default())),
+            policy,
         }
     }
 
+    /// 统计所属缓存的淘汰策略
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
     /// 记录命中
     pub fn record_hit(&self) {
         let mut stats = self.inner.lock().unwrap();
@@ -411,7 +500,7 @@ impl L2Cache {
             panic!("L2Cache配置无效: {}", e);
         }
 
-        let stats = Arc::new(CacheStats::new());
+        let stats = Arc::new(CacheStats::with_policy(config.eviction_policy));
         let single_flight = Arc::new(SingleFlightLoader::new());
         let cleanup_handle = Self::start_cleanup_task(Arc::clone(&stats), config.cleanup_interval);
 
@@ -439,9 +528,26 @@ impl L2Cache {
     }
 
     /// 获取值
+    ///
+    /// 负缓存条目同样返回 `None`，需要区分时使用 [`get_cached`](Self::get_cached)。
     pub async fn get(&self, key: &str) -> Option<String> {
+        self.get_cached(key).await.flatten()
+    }
+
+    /// 获取值，区分未缓存与负缓存
+    ///
+    /// # 返回
+    /// - `None`: 缓存中没有该键
+    /// - `Some(None)`: 负缓存命中，已确认存储中不存在
+    /// - `Some(Some(value))`: 正缓存命中
+    pub async fn get_cached(&self, key: &str) -> Option<Option<String>> {
         let mut cache = self.data.lock().await;
-        if let Some(entry) = cache.get_mut(key) {
+        // FIFO 下读取不调整顺序
+        let entry = match self.config.eviction_policy {
+            EvictionPolicy::Fifo => cache.peek_mut(key),
+            EvictionPolicy::Lru | EvictionPolicy::Lfu => cache.get_mut(key),
+        };
+        if let Some(entry) = entry {
             // 检查是否过期
             if entry.is_expired() {
                 cache.pop(key);
@@ -452,7 +558,7 @@ impl L2Cache {
             // 更新访问信息
             entry.update_access();
             self.__stats.record_hit();
-            Some(entry.value.clone())
+            Some((!entry.is_not_found()).then(|| entry.value.clone()))
         } else {
             self.__stats.record_miss();
             None
@@ -465,30 +571,76 @@ impl L2Cache {
         let entry = CacheEntry::new(value.to_string(), ttl);
 
         let mut cache = self.data.lock().await;
+        self.insert_entry(&mut cache, key, entry);
+    }
 
-        // 检查是否需要淘汰
-        if cache.len() >= self.config.capacity {
-            // 放置新键会自动淘汰LRU，所以不需要手动evict
-            // 但为了统计信息，我们记录一次eviction
-            if cache.len() >= self.config.capacity {
-                self.__stats.record_eviction();
+    /// 写入条目，容量已满时按淘汰策略先淘汰一个条目
+    ///
+    /// 更新已有键时保留其访问次数；FIFO 下更新不调整写入顺序。
+    fn insert_entry(
+        &self,
+        cache: &mut lru::LruCache<String, CacheEntry>,
+        key: &str,
+        mut entry: CacheEntry,
+    ) {
+        if let Some(existing) = cache.peek_mut(key) {
+            entry.access_count = existing.access_count;
+            if self.config.eviction_policy == EvictionPolicy::Fifo {
+                *existing = entry;
+                self.__stats.record_write();
+                return;
             }
+        } else if cache.len() >= self.config.capacity {
+            self.evict_one(cache);
         }
 
         cache.put(key.to_string(), entry);
         self.__stats.record_write();
     }
 
-    /// 删除值
+    /// 按淘汰策略淘汰一个条目
+    fn evict_one(&self, cache: &mut lru::LruCache<String, CacheEntry>) {
+        let victim = match self.config.eviction_policy {
+            // FIFO 下读取和更新不调整顺序，最久未使用即最早写入
+            EvictionPolicy::Lru | EvictionPolicy::Fifo => cache.peek_lru().map(|(k, _)| k.clone()),
+            // 从最久未访问开始扫描，访问次数相同时淘汰较早的
+            EvictionPolicy::Lfu => cache
+                .iter()
+                .rev()
+                .min_by_key(|(_, entry)| entry.access_count)
+                .map(|(k, _)| k.clone()),
+        };
+
+        if let Some(victim) = victim {
+            cache.pop(&victim);
+            self.__stats.record_eviction();
+            trace!("淘汰缓存条目: key={}", victim);
+        }
+    }
+
+    /// 缓存"未找到"结果，使用 [`L2CacheConfig::negative_ttl`]
+    ///
+    /// 未配置负缓存TTL时不做任何操作。之后的 [`set`](Self::set) 或
+    /// [`delete`](Self::delete) 会覆盖或清除该条目。
+    pub async fn set_not_found(&self, key: &str) {
+        let Some(ttl) = self.config.negative_ttl else {
+            return;
+        };
+
+        let mut cache = self.data.lock().await;
+        self.insert_entry(&mut cache, key, CacheEntry::not_found(ttl));
+    }
+
+    /// 删除值（正缓存与负缓存条目均会清除）
     pub async fn delete(&self, key: &str) {
         let mut cache = self.data.lock().await;
         cache.pop(key);
     }
 
-    /// 检查键是否存在
+    /// 检查键是否存在（不计为访问，不影响淘汰顺序）
     pub async fn contains(&self, key: &str) -> bool {
-        let mut cache = self.data.lock().await;
-        if let Some(entry) = cache.get(key) {
+        let cache = self.data.lock().await;
+        if let Some(entry) = cache.peek(key) {
             !entry.is_expired()
         } else {
             false
@@ -517,6 +669,7 @@ impl L2Cache {
     ///
     /// 如果缓存中存在且未过期，直接返回；否则使用加载器加载。
     /// 防止缓存击穿：多个并发请求同时加载同一个key时，只有一个会实际加载。
+    /// 加载失败的结果不会被缓存，等待中的请求收到同一错误，后续请求重新加载。
     pub async fn get_or_load<F, Fut>(&self, key: &str, loader: F) -> Result<String, StorageError>
     where
         F: Fn() -> Fut,
@@ -527,13 +680,55 @@ impl L2Cache {
             return Ok(value);
         }
 
-        // 单飞模式加载
-        let value = self.single_flight.get_or_load(key, loader).await?;
+        // 单飞模式加载，在通知等待者之前写入缓存，避免后到的请求重复加载
+        self.single_flight
+            .get_or_load(key, || async {
+                let value = loader().await?;
+                self.set(key, &value, self.config.default_ttl).await;
+                Ok(value)
+            })
+            .await
+    }
 
-        // 缓存加载的值
-        self.set(key, &value, self.config.default_ttl).await;
+    /// 带负缓存的单飞模式获取或加载
+    ///
+    /// 加载器返回 `Ok(None)` 时按负缓存TTL缓存"未找到"结果，
+    /// TTL内的重复查询直接返回 `None` 而不再调用加载器。
+    pub async fn get_or_load_optional<F, Fut>(
+        &self,
+        key: &str,
+        loader: F,
+    ) -> Result<Option<String>, StorageError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<Option<String>, StorageError>>,
+    {
+        if let Some(cached) = self.get_cached(key).await {
+            return Ok(cached);
+        }
+
+        // 单飞加载器只传递字符串结果，未找到通过 NotFound 错误传递
+        let result = self
+            .single_flight
+            .get_or_load(key, || async {
+                match loader().await? {
+                    Some(value) => {
+                        self.set(key, &value, self.config.default_ttl).await;
+                        Ok(value)
+                    }
+                    None => {
+                        self.set_not_found(key).await;
+                        Err(StorageError::NotFound(key.to_string()))
+                    }
+                }
+            })
+            .await;
 
-        Ok(value)
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(StorageError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
     /// 批量获取
@@ -745,6 +940,150 @@ mod tests {
         assert_eq!(load_count.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn test_single_flight_concurrent_misses() {
+        let cache = Arc::new(L2Cache::new(100, Duration::from_secs(60)));
+        let load_count = Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+        let mut handles = vec![];
+        for _ in 0..50 {
+            let cache = Arc::clone(&cache);
+            let load_count = Arc::clone(&load_count);
+            handles.push(tokio::spawn(async move {
+                cache
+                    .get_or_load("hot", || {
+                        let load_count = Arc::clone(&load_count);
+                        async move {
+                            load_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok("loaded_value".to_string())
+                        }
+                    })
+                    .await
+            }));
+        }
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap(), "loaded_value");
+        }
+        assert_eq!(load_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_error_does_not_poison_key() {
+        let cache = L2Cache::new(100, Duration::from_secs(60));
+
+        let result = cache
+            .get_or_load("key1", || async {
+                Err(StorageError::ConnectionError("down".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(StorageError::ConnectionError(_))));
+        assert!(!cache.contains("key1").await);
+
+        let value = cache
+            .get_or_load("key1", || async { Ok("recovered".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(value, "recovered");
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_cancelled_loader_does_not_poison_key() {
+        let cache = L2Cache::new(100, Duration::from_secs(60));
+
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            cache.get_or_load("key1", || async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok("never".to_string())
+            }),
+        )
+        .await;
+        assert!(cancelled.is_err());
+
+        let value = cache
+            .get_or_load("key1", || async { Ok("loaded".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(value, "loaded");
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_loads_once_within_ttl() {
+        let cache =
+            L2Cache::with_config(L2CacheConfig::new().negative_ttl(Duration::from_millis(100)));
+
+        let load_count = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let loader = || {
+            let load_count = load_count.clone();
+            async move {
+                load_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(None)
+            }
+        };
+
+        for _ in 0..5 {
+            assert_eq!(
+                cache.get_or_load_optional("missing", loader).await.unwrap(),
+                None
+            );
+        }
+        assert_eq!(load_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(cache.get_cached("missing").await, Some(None));
+        assert_eq!(cache.get("missing").await, None);
+
+        // 负缓存过期后重新查询存储
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(
+            cache.get_or_load_optional("missing", loader).await.unwrap(),
+            None
+        );
+        assert_eq!(load_count.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_invalidation() {
+        let cache = L2Cache::new(100, Duration::from_secs(60));
+
+        cache.set_not_found("key1").await;
+        assert_eq!(cache.get_cached("key1").await, Some(None));
+
+        // 删除同时清除负缓存
+        cache.delete("key1").await;
+        assert_eq!(cache.get_cached("key1").await, None);
+
+        // 写入覆盖负缓存
+        cache.set_not_found("key1").await;
+        cache.set("key1", "value1", None).await;
+        assert_eq!(cache.get("key1").await, Some("value1".to_string()));
+
+        // 删除同时清除正缓存
+        cache.delete("key1").await;
+        assert_eq!(cache.get_cached("key1").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_distinct_from_empty_value() {
+        let cache = L2Cache::new(100, Duration::from_secs(60));
+
+        cache.set("empty", "", None).await;
+        cache.set_not_found("missing").await;
+        assert_eq!(cache.get_cached("empty").await, Some(Some(String::new())));
+        assert_eq!(cache.get_cached("missing").await, Some(None));
+
+        assert!(!CacheEntry::new(String::new(), None).is_not_found());
+        assert!(CacheEntry::not_found(Duration::from_secs(1)).is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_disabled() {
+        let cache = L2Cache::with_config(L2CacheConfig::new().negative_ttl(Duration::ZERO));
+
+        cache.set_not_found("key1").await;
+        assert_eq!(cache.get_cached("key1").await, None);
+    }
+
     #[tokio::test]
     async fn test_cache_stats() {
         let cache = L2Cache::new(100, Duration::from_secs(60));
@@ -797,6 +1136,75 @@ mod tests {
         assert!(cache.contains("key4").await);
     }
 
+    /// 两个热点键被反复访问后，连续写入一批冷键
+    async fn skewed_workload(policy: EvictionPolicy) -> L2Cache {
+        let cache = L2Cache::with_config(L2CacheConfig::new().capacity(4).eviction_policy(policy));
+
+        for hot in ["hot1", "hot2"] {
+            cache.set(hot, "v", None).await;
+            for _ in 0..10 {
+                cache.get(hot).await;
+            }
+        }
+        for i in 0..10 {
+            cache.set(&format!("cold{}", i), "v", None).await;
+        }
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_lfu_retains_hot_keys() {
+        let lfu = skewed_workload(EvictionPolicy::Lfu).await;
+        assert!(lfu.contains("hot1").await);
+        assert!(lfu.contains("hot2").await);
+        assert!(lfu.contains("cold9").await);
+        assert_eq!(lfu.len().await, 4);
+        assert_eq!(lfu.stats().evictions(), 8);
+        assert_eq!(lfu.stats().eviction_policy(), EvictionPolicy::Lfu);
+
+        let lru = skewed_workload(EvictionPolicy::Lru).await;
+        assert!(!lru.contains("hot1").await);
+        assert!(!lru.contains("hot2").await);
+        assert_eq!(lru.stats().evictions(), 8);
+        assert_eq!(lru.stats().hits(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_fifo_eviction_ignores_reads() {
+        let cache = L2Cache::with_config(
+            L2CacheConfig::new()
+                .capacity(3)
+                .eviction_policy(EvictionPolicy::Fifo),
+        );
+
+        cache.set("key1", "value1", None).await;
+        cache.set("key2", "value2", None).await;
+        cache.set("key3", "value3", None).await;
+
+        // 读取和更新 key1 不影响写入顺序
+        cache.get("key1").await;
+        cache.set("key1", "value1b", None).await;
+
+        cache.set("key4", "value4", None).await;
+
+        assert!(!cache.contains("key1").await);
+        assert!(cache.contains("key2").await);
+        assert!(cache.contains("key4").await);
+        assert_eq!(cache.stats().evictions(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_existing_key_does_not_evict() {
+        let cache = L2Cache::new(2, Duration::from_secs(60));
+
+        cache.set("key1", "value1", None).await;
+        cache.set("key2", "value2", None).await;
+        cache.set("key2", "value2b", None).await;
+
+        assert_eq!(cache.len().await, 2);
+        assert_eq!(cache.stats().evictions(), 0);
+    }
+
     #[tokio::test]
     async fn test_config_builder() {
         let config = L2CacheConfig::new()
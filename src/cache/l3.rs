@@ -57,10 +57,14 @@ pub struct L3CacheConfig {
     pub degrade_check_interval: Duration,
     /// 是否启用缓存穿透保护
     pub enable_cache_penetration_protection: bool,
-    /// 空值缓存TTL
+    /// 空值缓存TTL（L2与L3的负缓存条目均使用此TTL）
     pub null_value_ttl: Duration,
 }
 
+/// L3中表示"未找到"的哨兵值
+#[cfg(feature = "redis")]
+const NULL_SENTINEL: &str = "__NULL__";
+
 #[cfg(feature = "redis")]
 impl Default for L3CacheConfig {
     fn default() -> Self {
@@ -116,6 +120,12 @@ impl L3CacheConfig {
         self.enable_cache_penetration_protection = enable;
         self
     }
+
+    /// 设置空值缓存TTL
+    pub fn null_value_ttl(mut self, ttl: Duration) -> Self {
+        self.null_value_ttl = ttl;
+        self
+    }
 }
 
 /// L3缓存统计
@@ -251,6 +261,9 @@ impl L3Cache {
             capacity: config.l2_capacity,
             default_ttl: config.l2_default_ttl,
             cleanup_interval: config.l2_cleanup_interval,
+            negative_ttl: (config.enable_cache_penetration_protection
+                && !config.null_value_ttl.is_zero())
+            .then_some(config.null_value_ttl),
             ..Default::default()
         }));
 
@@ -352,12 +365,24 @@ impl L3Cache {
     }
 
     /// 获取值（三级缓存）
+    ///
+    /// 负缓存条目返回 `None`，需要区分时使用 [`get_cached`](Self::get_cached)。
     pub async fn get(&self, key: &str) -> Option<String> {
+        self.get_cached(key).await.flatten()
+    }
+
+    /// 获取值（三级缓存），区分未缓存与负缓存
+    ///
+    /// # 返回
+    /// - `None`: 各级缓存中都没有该键
+    /// - `Some(None)`: 负缓存命中，已确认存储中不存在
+    /// - `Some(Some(value))`: 正缓存命中
+    pub async fn get_cached(&self, key: &str) -> Option<Option<String>> {
         // L1: 快速路径（这里简化，L1可以是无锁的）
         // 暂时跳过L1，直接从L2开始
 
         // L2: 检查L2缓存
-        if let Some(value) = self.l2_cache.get(key).await {
+        if let Some(value) = self.l2_cache.get_cached(key).await {
             self.stats.l2_hits.fetch_add(1, Ordering::Relaxed);
             trace!("L2缓存命中: key={}", key);
             return Some(value);
@@ -367,6 +392,15 @@ impl L3Cache {
         if !self.degraded.load(Ordering::Relaxed) {
             if let Some(storage) = self.l3_storage.read().await.as_ref().cloned() {
                 match storage.get(key).await {
+                    Ok(Some(value)) if value == NULL_SENTINEL => {
+                        self.stats.l3_hits.fetch_add(1, Ordering::Relaxed);
+                        trace!("L3负缓存命中: key={}", key);
+
+                        // 回填到L2负缓存
+                        self.l2_cache.set_not_found(key).await;
+
+                        return Some(None);
+                    }
                     Ok(Some(value)) => {
                         self.stats.l3_hits.fetch_add(1, Ordering::Relaxed);
                         trace!("L3缓存命中: key={}", key);
@@ -376,7 +410,7 @@ impl L3Cache {
                             .set(key, &value, self.config.l2_default_ttl)
                             .await;
 
-                        return Some(value);
+                        return Some(Some(value));
                     }
                     Ok(None) => {
                         // L3未命中
@@ -441,7 +475,29 @@ impl L3Cache {
         }
     }
 
-    /// 删除值（从L2和L3删除）
+    /// 缓存"未找到"结果（写入L2和L3），使用 [`L3CacheConfig::null_value_ttl`]
+    ///
+    /// 未启用缓存穿透保护时不做任何操作。
+    pub async fn set_not_found(&self, key: &str) {
+        if !self.config.enable_cache_penetration_protection || self.config.null_value_ttl.is_zero()
+        {
+            return;
+        }
+
+        self.l2_cache.set_not_found(key).await;
+
+        if !self.degraded.load(Ordering::Relaxed) {
+            if let Some(l3_storage) = self.l3_storage.read().await.as_ref() {
+                let ttl = Some(self.config.null_value_ttl.as_secs().max(1));
+                if let Err(e) = l3_storage.as_ref().set(key, NULL_SENTINEL, ttl).await {
+                    error!("L3负缓存写入失败: key={}, error={}", key, e);
+                    self.set_degraded(true).await;
+                }
+            }
+        }
+    }
+
+    /// 删除值（从L2和L3删除，正缓存与负缓存条目均会清除）
     pub async fn delete(&self, key: &str) {
         // 从L2删除
         self.l2_cache.delete(key).await;
@@ -486,14 +542,18 @@ impl L3Cache {
     }
 
     /// 获取或加载（支持缓存穿透保护）
+    ///
+    /// 加载失败时缓存空值，空值TTL内的重复查询返回 [`StorageError::NotFound`]。
     pub async fn get_or_load<F, Fut>(&self, key: &str, loader: F) -> Result<String, StorageError>
     where
         F: Fn() -> Fut,
         Fut: std::future::Future<Output = Result<String, StorageError>>,
     {
         // 尝试从缓存获取
-        if let Some(value) = self.get(key).await {
-            return Ok(value);
+        match self.get_cached(key).await {
+            Some(Some(value)) => return Ok(value),
+            Some(None) => return Err(StorageError::NotFound(key.to_string())),
+            None => {}
         }
 
         // 缓存未命中，加载值
@@ -509,14 +569,45 @@ impl L3Cache {
                     self.stats
                         .penetration_protections
                         .fetch_add(1, Ordering::Relaxed);
-                    self.set(key, "__NULL__", Some(self.config.null_value_ttl))
-                        .await;
+                    self.set_not_found(key).await;
                 }
                 Err(e)
             }
         }
     }
 
+    /// 带负缓存的获取或加载
+    ///
+    /// 加载器返回 `Ok(None)` 时按空值TTL缓存"未找到"结果，
+    /// TTL内的重复查询直接返回 `None` 而不再调用加载器。
+    pub async fn get_or_load_optional<F, Fut>(
+        &self,
+        key: &str,
+        loader: F,
+    ) -> Result<Option<String>, StorageError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<Option<String>, StorageError>>,
+    {
+        if let Some(cached) = self.get_cached(key).await {
+            return Ok(cached);
+        }
+
+        match loader().await? {
+            Some(value) => {
+                self.set(key, &value, None).await;
+                Ok(Some(value))
+            }
+            None => {
+                self.stats
+                    .penetration_protections
+                    .fetch_add(1, Ordering::Relaxed);
+                self.set_not_found(key).await;
+                Ok(None)
+            }
+        }
+    }
+
     /// 设置降级状态
     async fn set_degraded(&self, degraded: bool) {
         let current = self.degraded.load(Ordering::Relaxed);
@@ -767,4 +858,44 @@ mod tests {
 
         cache.shutdown().await;
     }
+
+    #[tokio::test]
+    async fn test_l3_cache_negative_caching() {
+        let config = L3CacheConfig {
+            redis_config: RedisConfig::new("redis://invalid:6379"),
+            l2_capacity: 100,
+            l2_cleanup_interval: Duration::from_secs(60),
+            l2_default_ttl: Some(Duration::from_secs(300)),
+            l3_default_ttl: None,
+            degrade_check_interval: Duration::from_secs(5),
+            enable_cache_penetration_protection: true,
+            null_value_ttl: Duration::from_secs(60),
+        };
+
+        let cache = L3Cache::new(config).await.unwrap();
+
+        let load_count = Arc::new(AtomicU64::new(0));
+        let loader = || {
+            let load_count = Arc::clone(&load_count);
+            async move {
+                load_count.fetch_add(1, Ordering::SeqCst);
+                Ok(None)
+            }
+        };
+
+        for _ in 0..3 {
+            let value = cache.get_or_load_optional("missing", loader).await.unwrap();
+            assert_eq!(value, None);
+        }
+        assert_eq!(load_count.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.stats().penetration_protections(), 1);
+
+        // 删除后重新查询存储
+        cache.delete("missing").await;
+        assert_eq!(cache.get_cached("missing").await, None);
+        cache.get_or_load_optional("missing", loader).await.unwrap();
+        assert_eq!(load_count.load(Ordering::SeqCst), 2);
+
+        cache.shutdown().await;
+    }
 }
@@ -12,8 +12,9 @@ pub mod smart;
 
 // 重新导出 L2 缓存的公共 API
 pub use l2::{
-    CacheEntry, L2Cache, L2CacheConfig, DEFAULT_CACHE_CAPACITY, DEFAULT_CLEANUP_INTERVAL_SECS,
-    DEFAULT_EVICTION_THRESHOLD, DEFAULT_TTL_SECS,
+    CacheEntry, EvictionPolicy, L2Cache, L2CacheConfig, DEFAULT_CACHE_CAPACITY,
+    DEFAULT_CLEANUP_INTERVAL_SECS, DEFAULT_EVICTION_THRESHOLD, DEFAULT_NEGATIVE_TTL_SECS,
+    DEFAULT_TTL_SECS,
 };
 
 // 重新导出 L3 缓存的公共 API (仅在 redis 特性启用时)
@@ -32,4 +33,4 @@ pub mod l3_stub {
 pub use l3_stub::L3Cache;
 
 // 重新导出智能缓存的公共 API
-pub use smart::{CacheStats as SmartCacheStats, SmartCacheStrategy};
+pub use smart::{CacheStats as SmartCacheStats, SmartCacheStrategy, WritePolicy};
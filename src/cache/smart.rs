@@ -5,12 +5,81 @@
 //! 智能缓存策略
 //!
 //! 实现智能缓存失效、预取和压缩策略以提高性能。
+//!
+//! # 写策略
+//!
+//! 配置了后端存储（L3）时，写入按 [`WritePolicy`] 传播：
+//!
+//! - [`WritePolicy::WriteThrough`]：后端确认后才返回，写入失败立即暴露给调用方，
+//!   进程崩溃不会丢失已确认的写入，但每次写入都要等待一次后端往返。
+//! - [`WritePolicy::WriteBack`]：只写L2并标记为脏，按刷新间隔批量写入后端，
+//!   同一键的多次写入合并为一次。写入延迟低，但刷新前进程崩溃会丢失未落盘的写入，
+//!   刷新失败也只会记录日志并在下次刷新时重试。
+//!
+//! 写回模式下停止使用前应调用 [`SmartCacheStrategy::close`]（或
+//! [`SmartCacheStrategy::flush`]）等待脏数据写入后端；`Drop` 中的最终刷新只是尽力而为，
+//! 不等待完成，运行时关闭时可能丢失。
+//!
+//! 后端以秒为单位保存 TTL，不足一秒的部分向上取整，避免条目在后端提前过期。
 
 use crate::cache::l2::L2Cache;
+use crate::error::StorageError;
+use crate::storage::Storage;
+use ahash::AHashMap as HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// 写策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WritePolicy {
+    /// 同步写入后端，后端确认后才返回
+    #[default]
+    WriteThrough,
+    /// 异步批量写入后端
+    WriteBack {
+        /// 刷新间隔
+        flush_interval: Duration,
+    },
+}
+
+/// 后端使用的 TTL 秒数，不足一秒的部分向上取整
+fn backend_ttl(ttl: Option<Duration>) -> Option<u64> {
+    ttl.map(|ttl| ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0))
+}
+
+/// 后端存储与待刷新的脏数据
+struct WriteBackState {
+    /// 后端存储（L3）
+    backend: Arc<dyn Storage>,
+    /// 待刷新的写入，同一键只保留最新值
+    dirty: parking_lot::Mutex<HashMap<String, (String, Option<Duration>)>>,
+}
+
+impl WriteBackState {
+    /// 将脏数据写入后端，返回写入的条目数
+    ///
+    /// 写入失败时未写入的条目放回脏数据（已有更新的值除外），等待下次刷新。
+    async fn flush(&self) -> Result<usize, StorageError> {
+        let pending = std::mem::take(&mut *self.dirty.lock());
+        let mut pending = pending.into_iter();
+        let mut flushed = 0;
+
+        while let Some((key, (value, ttl))) = pending.next() {
+            if let Err(e) = self.backend.set(&key, &value, backend_ttl(ttl)).await {
+                let mut dirty = self.dirty.lock();
+                for (key, entry) in std::iter::once((key, (value, ttl))).chain(pending) {
+                    dirty.entry(key).or_insert(entry);
+                }
+                return Err(e);
+            }
+            flushed += 1;
+        }
+
+        Ok(flushed)
+    }
+}
 
 /// 智能缓存策略
 pub struct SmartCacheStrategy {
@@ -26,6 +95,15 @@ pub struct SmartCacheStrategy {
 
     /// 缓存统计
     stats: Arc<RwLock<CacheStats>>,
+
+    /// 写策略
+    write_policy: WritePolicy,
+
+    /// 后端存储与脏数据，未配置后端时为 `None`
+    write_state: Option<Arc<WriteBackState>>,
+
+    /// 写回模式的定时刷新任务
+    flush_task: Option<tokio::task::JoinHandle<()>>,
 }
 
 /// 缓存统计
@@ -58,7 +136,108 @@ impl SmartCacheStrategy {
             prefetch_threshold,
             compress_threshold,
             stats: Arc::new(RwLock::new(Default::default())),
+            write_policy: WritePolicy::WriteThrough,
+            write_state: None,
+            flush_task: None,
+        }
+    }
+
+    /// 设置后端存储（L3）及写策略
+    ///
+    /// 写回模式会启动定时刷新任务，需要在 Tokio 运行时中调用。
+    pub fn with_backend(mut self, backend: Arc<dyn Storage>, write_policy: WritePolicy) -> Self {
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
         }
+
+        let state = Arc::new(WriteBackState {
+            backend,
+            dirty: parking_lot::Mutex::new(HashMap::new()),
+        });
+
+        if let WritePolicy::WriteBack { flush_interval } = write_policy {
+            let task_state = Arc::clone(&state);
+            self.flush_task = Some(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(flush_interval);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = task_state.flush().await {
+                        warn!("写回刷新失败，将在下次刷新时重试: {}", e);
+                    }
+                }
+            }));
+        }
+
+        self.write_policy = write_policy;
+        self.write_state = Some(state);
+        self
+    }
+
+    /// 当前写策略
+    pub fn write_policy(&self) -> WritePolicy {
+        self.write_policy
+    }
+
+    /// 写入缓存
+    ///
+    /// 写穿模式下后端写入成功后才更新L2并返回，后端错误直接返回；
+    /// 写回模式下只更新L2，后端写入推迟到下次刷新。
+    pub async fn set(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Option<Duration>,
+    ) -> Result<(), StorageError> {
+        if let Some(state) = &self.write_state {
+            match self.write_policy {
+                WritePolicy::WriteThrough => {
+                    state.backend.set(key, value, backend_ttl(ttl)).await?;
+                }
+                WritePolicy::WriteBack { .. } => {
+                    state
+                        .dirty
+                        .lock()
+                        .insert(key.to_string(), (value.to_string(), ttl));
+                }
+            }
+        }
+
+        self.l2_cache.set(key, value, ttl).await;
+        Ok(())
+    }
+
+    /// 立即将写回模式下的脏数据写入后端
+    ///
+    /// # 返回
+    /// - `Ok(n)`: 写入的条目数，写穿模式或未配置后端时为 0
+    /// - `Err(_)`: 后端写入失败，未写入的条目保留到下次刷新
+    pub async fn flush(&self) -> Result<usize, StorageError> {
+        match &self.write_state {
+            Some(state) => state.flush().await,
+            None => Ok(0),
+        }
+    }
+
+    /// 停止定时刷新并等待剩余的脏数据写入后端
+    ///
+    /// 写回模式下应在停止使用前调用；`Drop` 只会在后台尽力刷新，不保证写入完成。
+    ///
+    /// # 返回
+    /// - `Ok(n)`: 最终写入的条目数
+    /// - `Err(_)`: 后端写入失败，未写入的条目在释放时再尝试一次
+    pub async fn close(mut self) -> Result<usize, StorageError> {
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
+        }
+        self.flush().await
+    }
+
+    /// 尚未写入后端的条目数
+    pub fn dirty_len(&self) -> usize {
+        self.write_state
+            .as_ref()
+            .map_or(0, |state| state.dirty.lock().len())
     }
 
     /// 智能缓存决策
@@ -141,6 +320,25 @@ impl SmartCacheStrategy {
         compressed
     }
 
+    /// 单飞模式获取或加载
+    ///
+    /// L2未命中时调用加载器；同一键的并发未命中只有一个请求实际加载，
+    /// 其余请求等待其结果。加载失败不会被缓存，后续请求重新加载。
+    pub async fn get_or_load<F, Fut>(&self, key: &str, loader: F) -> Result<String, StorageError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<String, StorageError>>,
+    {
+        if let Some(value) = self.l2_cache.get(key).await {
+            self.update_stats(true, false, false, false).await;
+            return Ok(value);
+        }
+
+        let result = self.l2_cache.get_or_load(key, loader).await;
+        self.update_stats(false, true, false, false).await;
+        result
+    }
+
     /// 触发预取
     async fn trigger_prefetch(&self, key: &str) {
         // 这里可以实现基于关联模式的预取
@@ -152,10 +350,18 @@ impl SmartCacheStrategy {
 
     /// 从存储加载数据
     async fn load_from_storage(&self, key: &str) -> Option<String> {
-        // 这里应该从实际存储（如数据库、Redis等）加载数据
-        // 这是一个框架实现，用户需要注入存储
         debug!("从存储加载数据: {}", key);
 
+        if let Some(state) = &self.write_state {
+            return match state.backend.get(key).await {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("从后端存储加载失败: {}, 错误: {}", key, e);
+                    None
+                }
+            };
+        }
+
         // 暂时返回 None，表示未实现
         // 在实际应用中，这里应该:
         // 1. 从 Redis/PostgreSQL 等存储加载数据
@@ -227,3 +433,217 @@ impl SmartCacheStrategy {
         info!("缓存统计已重置");
     }
 }
+
+impl Drop for SmartCacheStrategy {
+    fn drop(&mut self) {
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
+        }
+
+        // 写回模式下最后刷新一次脏数据（不等待完成，需要确认写入时使用 `close`）
+        let Some(state) = self.write_state.take() else {
+            return;
+        };
+        if state.dirty.lock().is_empty() {
+            return;
+        }
+        warn!(
+            "{} 条写回数据在释放时仍未刷新，将在后台尝试写入",
+            state.dirty.lock().len()
+        );
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = state.flush().await {
+                        warn!("写回最终刷新失败: {}", e);
+                    }
+                });
+            }
+            Err(_) => warn!(
+                "无可用运行时，{} 条写回数据未刷新",
+                state.dirty.lock().len()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+    /// 记录写入次数、可注入失败的后端存储
+    #[derive(Default)]
+    struct CountingBackend {
+        writes: AtomicU64,
+        fail: AtomicBool,
+        values: parking_lot::Mutex<HashMap<String, String>>,
+    }
+
+    #[async_trait]
+    impl Storage for CountingBackend {
+        async fn get(&self, key: &str) -> Result<Option<String>, StorageError> {
+            Ok(self.values.lock().get(key).cloned())
+        }
+
+        async fn set(&self, key: &str, value: &str, _ttl: Option<u64>) -> Result<(), StorageError> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(StorageError::ConnectionError("backend down".to_string()));
+            }
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            self.values
+                .lock()
+                .insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), StorageError> {
+            self.values.lock().remove(key);
+            Ok(())
+        }
+    }
+
+    fn strategy(backend: Arc<CountingBackend>, policy: WritePolicy) -> SmartCacheStrategy {
+        let l2 = Arc::new(L2Cache::new(100, Duration::from_secs(60)));
+        SmartCacheStrategy::new(l2, 10, 1024).with_backend(backend, policy)
+    }
+
+    #[tokio::test]
+    async fn test_write_through_surfaces_backend_error() {
+        let backend = Arc::new(CountingBackend::default());
+        let cache = strategy(Arc::clone(&backend), WritePolicy::WriteThrough);
+
+        cache.set("k", "v1", None).await.unwrap();
+        assert_eq!(backend.writes.load(Ordering::SeqCst), 1);
+        assert_eq!(backend.values.lock().get("k").unwrap(), "v1");
+
+        backend.fail.store(true, Ordering::SeqCst);
+        let result = cache.set("k", "v2", None).await;
+        assert!(matches!(result, Err(StorageError::ConnectionError(_))));
+        // 未确认的写入不进入L2
+        assert_eq!(cache.get_with_strategy("k").await.unwrap(), "v1");
+        assert_eq!(cache.dirty_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_write_back_coalesces_writes() {
+        let backend = Arc::new(CountingBackend::default());
+        let cache = strategy(
+            Arc::clone(&backend),
+            WritePolicy::WriteBack {
+                flush_interval: Duration::from_secs(3600),
+            },
+        );
+
+        for i in 0..10 {
+            cache.set("a", &format!("v{}", i), None).await.unwrap();
+            cache.set("b", &format!("v{}", i), None).await.unwrap();
+        }
+        assert_eq!(backend.writes.load(Ordering::SeqCst), 0);
+        assert_eq!(cache.dirty_len(), 2);
+        assert_eq!(cache.get_with_strategy("a").await.unwrap(), "v9");
+
+        assert_eq!(cache.flush().await.unwrap(), 2);
+        assert_eq!(backend.writes.load(Ordering::SeqCst), 2);
+        assert_eq!(backend.values.lock().get("a").unwrap(), "v9");
+        assert_eq!(cache.dirty_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_load_coalesces_concurrent_misses() {
+        let l2 = Arc::new(L2Cache::new(100, Duration::from_secs(60)));
+        let cache = Arc::new(SmartCacheStrategy::new(l2, 10, 1024));
+        let loads = Arc::new(AtomicU64::new(0));
+
+        let mut handles = vec![];
+        for _ in 0..50 {
+            let cache = Arc::clone(&cache);
+            let loads = Arc::clone(&loads);
+            handles.push(tokio::spawn(async move {
+                cache
+                    .get_or_load("hot", || {
+                        let loads = Arc::clone(&loads);
+                        async move {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok("v".to_string())
+                        }
+                    })
+                    .await
+            }));
+        }
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap(), "v");
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get_stats().await.total_requests, 50);
+    }
+
+    #[tokio::test]
+    async fn test_write_back_failed_flush_keeps_dirty() {
+        let backend = Arc::new(CountingBackend::default());
+        let cache = strategy(
+            Arc::clone(&backend),
+            WritePolicy::WriteBack {
+                flush_interval: Duration::from_secs(3600),
+            },
+        );
+
+        cache.set("a", "v1", None).await.unwrap();
+        backend.fail.store(true, Ordering::SeqCst);
+        assert!(cache.flush().await.is_err());
+        assert_eq!(cache.dirty_len(), 1);
+
+        backend.fail.store(false, Ordering::SeqCst);
+        assert_eq!(cache.flush().await.unwrap(), 1);
+        assert_eq!(backend.values.lock().get("a").unwrap(), "v1");
+    }
+
+    #[test]
+    fn test_backend_ttl_rounds_up() {
+        assert_eq!(backend_ttl(None), None);
+        assert_eq!(backend_ttl(Some(Duration::from_millis(500))), Some(1));
+        assert_eq!(backend_ttl(Some(Duration::from_secs(2))), Some(2));
+        assert_eq!(backend_ttl(Some(Duration::from_millis(2001))), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_write_back_close_flushes_dirty() {
+        let backend = Arc::new(CountingBackend::default());
+        let cache = strategy(
+            Arc::clone(&backend),
+            WritePolicy::WriteBack {
+                flush_interval: Duration::from_secs(3600),
+            },
+        );
+
+        cache.set("a", "v1", None).await.unwrap();
+        cache.set("b", "v1", None).await.unwrap();
+        assert_eq!(cache.close().await.unwrap(), 2);
+        assert_eq!(backend.writes.load(Ordering::SeqCst), 2);
+        assert_eq!(backend.values.lock().get("b").unwrap(), "v1");
+    }
+
+    #[tokio::test]
+    async fn test_write_back_periodic_and_final_flush() {
+        let backend = Arc::new(CountingBackend::default());
+        let cache = strategy(
+            Arc::clone(&backend),
+            WritePolicy::WriteBack {
+                flush_interval: Duration::from_millis(20),
+            },
+        );
+
+        cache.set("a", "v1", None).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(backend.writes.load(Ordering::SeqCst), 1);
+
+        // 释放时刷新剩余的脏数据
+        cache.set("b", "v1", None).await.unwrap();
+        drop(cache);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(backend.values.lock().get("b").unwrap(), "v1");
+    }
+}
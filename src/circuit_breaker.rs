@@ -9,7 +9,7 @@
 //! # 特性
 //!
 //! - **三状态**: Closed（关闭）、Open（打开）、HalfOpen（半开）
-//! - **自动熔断**: 失败次数达到阈值自动熔断
+//! - **自动熔断**: 连续失败次数或滑动窗口失败率达到阈值自动熔断
 //! - **自动恢复**: 超时后自动探测恢复
 //! - **线程安全**: 使用Arc和原子操作保证线程安全
 //! - **统计信息**: 提供详细的统计信息
@@ -27,12 +27,57 @@ pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
 pub const DEFAULT_HALF_OPEN_MAX_CALLS: u64 = 3;
 
 use crate::error::{CircuitBreakerStats, CircuitState, FlowGuardError};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 use tracing::{info, trace, warn};
 
+#[cfg(feature = "circuit-breaker")]
+/// [`CircuitBreaker::call`] 的错误
+#[derive(Debug, thiserror::Error)]
+pub enum CircuitBreakerError<E> {
+    /// 熔断器打开（或半开状态探测名额已满），请求被短路
+    #[error("熔断器打开，请求被拒绝")]
+    Open,
+    /// 被包装操作返回的错误
+    #[error("{0}")]
+    Inner(E),
+}
+
+impl From<CircuitBreakerError<FlowGuardError>> for FlowGuardError {
+    fn from(err: CircuitBreakerError<FlowGuardError>) -> Self {
+        match err {
+            CircuitBreakerError::Open => {
+                FlowGuardError::CircuitBreakerError("熔断器打开，请求被拒绝".to_string())
+            }
+            CircuitBreakerError::Inner(e) => e,
+        }
+    }
+}
+
+#[cfg(feature = "circuit-breaker")]
+/// 熔断触发策略
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TripPolicy {
+    /// 连续失败达到 [`CircuitBreakerConfig::failure_threshold`] 次时熔断
+    #[default]
+    ConsecutiveFailures,
+    /// 最近 `window` 次调用中失败比例超过 `threshold` 时熔断
+    ///
+    /// 调用数不足 `min_calls` 时不评估，避免低流量下少量失败即熔断。
+    FailureRate {
+        /// 统计窗口（最近的调用次数）
+        window: usize,
+        /// 失败率阈值（0.0 ~ 1.0）
+        threshold: f64,
+        /// 开始评估所需的最少调用次数
+        min_calls: usize,
+    },
+}
+
 #[cfg(feature = "circuit-breaker")]
 /// 熔断器配置
 #[derive(Debug, Clone)]
@@ -45,6 +90,8 @@ pub struct CircuitBreakerConfig {
     pub timeout: Duration,
     /// 半开状态的最大调用次数
     pub half_open_max_calls: u64,
+    /// 熔断触发策略（默认为连续失败 `failure_threshold` 次）
+    pub trip_policy: TripPolicy,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -54,6 +101,7 @@ impl Default for CircuitBreakerConfig {
             success_threshold: DEFAULT_SUCCESS_THRESHOLD,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             half_open_max_calls: DEFAULT_HALF_OPEN_MAX_CALLS,
+            trip_policy: TripPolicy::default(),
         }
     }
 }
@@ -66,6 +114,7 @@ impl CircuitBreakerConfig {
             success_threshold,
             timeout,
             half_open_max_calls: 3,
+            trip_policy: TripPolicy::ConsecutiveFailures,
         }
     }
 
@@ -74,6 +123,42 @@ impl CircuitBreakerConfig {
         self.half_open_max_calls = max_calls;
         self
     }
+
+    /// 设置熔断触发策略
+    pub fn trip_policy(mut self, policy: TripPolicy) -> Self {
+        self.trip_policy = policy;
+        self
+    }
+}
+
+/// 最近调用结果的环形缓冲区（用于失败率策略）
+#[derive(Debug, Default)]
+struct OutcomeWindow {
+    /// 调用结果，true 表示失败
+    outcomes: VecDeque<bool>,
+    /// 窗口内失败次数
+    failures: usize,
+}
+
+impl OutcomeWindow {
+    /// 记录一次调用结果，返回 (窗口内调用数, 失败数)
+    fn record(&mut self, failed: bool, window: usize) -> (usize, usize) {
+        if self.outcomes.len() >= window.max(1) {
+            if let Some(true) = self.outcomes.pop_front() {
+                self.failures -= 1;
+            }
+        }
+        self.outcomes.push_back(failed);
+        if failed {
+            self.failures += 1;
+        }
+        (self.outcomes.len(), self.failures)
+    }
+
+    fn clear(&mut self) {
+        self.outcomes.clear();
+        self.failures = 0;
+    }
 }
 
 #[cfg(feature = "circuit-breaker")]
@@ -91,8 +176,10 @@ pub struct CircuitBreaker {
     last_failure_time: Arc<RwLock<Option<Instant>>>,
     /// 最后状态变更时间
     last_state_change: Arc<RwLock<Option<Instant>>>,
-    /// 半开状态下的调用计数
-    half_open_calls: Arc<AtomicU64>,
+    /// 半开状态并发探测许可（容量为 `half_open_max_calls`）
+    half_open_permits: Arc<Semaphore>,
+    /// 最近调用结果（失败率策略）
+    outcomes: Arc<Mutex<OutcomeWindow>>,
     /// 配置
     config: CircuitBreakerConfig,
 }
@@ -124,7 +211,10 @@ impl CircuitBreaker {
             total_calls: Arc::new(AtomicU64::new(0)),
             last_failure_time: Arc::new(RwLock::new(None)),
             last_state_change: Arc::new(RwLock::new(Some(Instant::now()))),
-            half_open_calls: Arc::new(AtomicU64::new(0)),
+            half_open_permits: Arc::new(Semaphore::new(
+                usize::try_from(config.half_open_max_calls).unwrap_or(Semaphore::MAX_PERMITS),
+            )),
+            outcomes: Arc::new(Mutex::new(OutcomeWindow::default())),
             config,
         }
     }
@@ -139,12 +229,15 @@ impl Default for CircuitBreaker {
 impl CircuitBreaker {
     /// 执行操作，自动处理熔断逻辑
     ///
+    /// 与 [`CircuitBreaker::call`] 语义相同（半开状态下限制同时在途的探测数量），
+    /// 熔断短路时返回 [`FlowGuardError::CircuitBreakerError`]。
+    ///
     /// # 参数
     /// - `operation`: 要执行的操作
     ///
     /// # 返回
     /// - `Ok(T)`: 操作成功
-    /// - `Err(FlowGuardError)`: 操作失败或熔断器打开
+    /// - `Err(FlowGuardError)`: 操作本身的错误，或熔断器打开时的 `CircuitBreakerError`
     ///
     /// # 示例
     /// ```rust
@@ -167,62 +260,72 @@ impl CircuitBreaker {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T, FlowGuardError>>,
     {
-        // 增加总调用次数
-        self.total_calls.fetch_add(1, Ordering::Relaxed);
+        self.call(operation).await.map_err(FlowGuardError::from)
+    }
 
-        // 检查熔断器状态
-        let state = self.state.read().await;
+    /// 包装异步操作，自动执行熔断判断并记录结果
+    ///
+    /// 半开状态下同时在途的探测请求数量受 `half_open_max_calls` 限制（信号量），
+    /// 探测结束后名额即归还；超出的请求直接短路。
+    /// 半开状态下任意一次失败都会立即回到打开状态并重新计时。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(5, 2, Duration::from_secs(60)));
+    ///
+    /// let result: Result<u32, CircuitBreakerError<std::io::Error>> =
+    ///     breaker.call(|| async { Ok(42) }).await;
+    /// assert_eq!(result.unwrap(), 42);
+    /// # }
+    /// ```
+    pub async fn call<F, Fut, T, E>(&self, f: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        self.total_calls.fetch_add(1, Ordering::Relaxed);
 
-        match *state {
-            CircuitState::Open => {
-                // 检查是否可以尝试恢复
-                let last_failure = self.last_failure_time.read().await;
-                if let Some(last_failure) = *last_failure {
-                    if last_failure.elapsed() >= self.config.timeout {
-                        // 超时，切换到半开状态
-                        drop(state);
-                        self.transition_to_half_open().await;
-                    } else {
-                        // 仍在熔断状态，拒绝请求
-                        drop(state);
-                        warn!("熔断器打开，拒绝请求");
-                        return Err(FlowGuardError::LimitError(
-                            "熔断器打开，请求被拒绝".to_string(),
-                        ));
-                    }
+        let mut state = *self.state.read().await;
+        if state == CircuitState::Open {
+            let last_failure = *self.last_failure_time.read().await;
+            match last_failure {
+                Some(last_failure) if last_failure.elapsed() < self.config.timeout => {
+                    trace!("熔断器打开，短路请求");
+                    return Err(CircuitBreakerError::Open);
                 }
-            }
-            CircuitState::HalfOpen => {
-                // 检查半开状态下的调用次数
-                let calls = self.half_open_calls.load(Ordering::Relaxed);
-                if calls >= self.config.half_open_max_calls {
-                    drop(state);
-                    warn!("半开状态调用次数已达上限，拒绝请求");
-                    return Err(FlowGuardError::LimitError(
-                        "半开状态调用次数已达上限".to_string(),
-                    ));
+                _ => {
+                    self.transition_to_half_open().await;
+                    state = *self.state.read().await;
                 }
-                self.half_open_calls.fetch_add(1, Ordering::Relaxed);
-                drop(state);
-            }
-            CircuitState::Closed => {
-                // 正常状态，继续执行
-                drop(state);
             }
         }
 
-        // 执行操作
-        let result = operation().await;
+        // 半开状态下只放行有限数量的并发探测
+        let _permit = match state {
+            CircuitState::HalfOpen => match self.half_open_permits.try_acquire() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    trace!("半开状态探测名额已满，短路请求");
+                    return Err(CircuitBreakerError::Open);
+                }
+            },
+            CircuitState::Open => return Err(CircuitBreakerError::Open),
+            CircuitState::Closed => None,
+        };
 
-        // 根据操作结果更新状态
-        match result {
+        match f().await {
             Ok(value) => {
                 self.on_success().await;
                 Ok(value)
             }
             Err(e) => {
                 self.on_failure().await;
-                Err(e)
+                Err(CircuitBreakerError::Inner(e))
             }
         }
     }
@@ -236,6 +339,11 @@ impl CircuitBreaker {
                 // 关闭状态下，重置失败计数
                 self.failure_count.store(0, Ordering::Relaxed);
                 self.success_count.fetch_add(1, Ordering::Relaxed);
+                if self.record_outcome(false) {
+                    drop(state);
+                    self.transition_to_open().await;
+                    return;
+                }
                 trace!("操作成功（关闭状态）");
             }
             CircuitState::HalfOpen => {
@@ -273,7 +381,7 @@ impl CircuitBreaker {
                 // 记录失败时间
                 *self.last_failure_time.write().await = Some(Instant::now());
 
-                if failure_count >= self.config.failure_threshold {
+                if self.record_outcome(true) {
                     // 达到失败阈值，切换到打开状态
                     drop(state);
                     self.transition_to_open().await;
@@ -286,8 +394,9 @@ impl CircuitBreaker {
                 }
             }
             CircuitState::HalfOpen => {
-                // 半开状态下失败，立即切换到打开状态
+                // 半开状态下失败，立即切换到打开状态并重新计时
                 drop(state);
+                *self.last_failure_time.write().await = Some(Instant::now());
                 self.transition_to_open().await;
             }
             CircuitState::Open => {
@@ -297,6 +406,32 @@ impl CircuitBreaker {
         }
     }
 
+    /// 按熔断策略记录关闭状态下的调用结果，返回是否应当熔断
+    fn record_outcome(&self, failed: bool) -> bool {
+        match self.config.trip_policy {
+            TripPolicy::ConsecutiveFailures => {
+                failed
+                    && self.failure_count.load(Ordering::Relaxed) >= self.config.failure_threshold
+            }
+            TripPolicy::FailureRate {
+                window,
+                threshold,
+                min_calls,
+            } => {
+                let (calls, failures) = self.outcomes.lock().record(failed, window);
+                let rate = failures as f64 / calls as f64;
+                trace!(
+                    "失败率: {}/{} = {:.2} (阈值 {:.2})",
+                    failures,
+                    calls,
+                    rate,
+                    threshold
+                );
+                calls >= min_calls && rate > threshold
+            }
+        }
+    }
+
     /// 切换到打开状态
     async fn transition_to_open(&self) {
         let old_state = *self.state.read().await;
@@ -304,7 +439,7 @@ impl CircuitBreaker {
             *self.state.write().await = CircuitState::Open;
             *self.last_state_change.write().await = Some(Instant::now());
             self.success_count.store(0, Ordering::Relaxed);
-            self.half_open_calls.store(0, Ordering::Relaxed);
+            self.outcomes.lock().clear();
             warn!(
                 "熔断器状态变更: {:?} -> Open (failure_count={})",
                 old_state,
@@ -320,9 +455,6 @@ impl CircuitBreaker {
             *self.state.write().await = CircuitState::HalfOpen;
             *self.last_state_change.write().await = Some(Instant::now());
             self.success_count.store(0, Ordering::Relaxed);
-            // 重置半开状态调用计数
-            // 注意：将计数设置为1，因为当前请求（探针请求）将被允许通过
-            self.half_open_calls.store(1, Ordering::Relaxed);
             info!("熔断器状态变更: {:?} -> HalfOpen", old_state);
         }
     }
@@ -335,7 +467,7 @@ impl CircuitBreaker {
             *self.last_state_change.write().await = Some(Instant::now());
             self.failure_count.store(0, Ordering::Relaxed);
             self.success_count.store(0, Ordering::Relaxed);
-            self.half_open_calls.store(0, Ordering::Relaxed);
+            self.outcomes.lock().clear();
             info!("熔断器状态变更: {:?} -> Closed", old_state);
         }
     }
@@ -372,7 +504,7 @@ impl CircuitBreaker {
         self.total_calls.store(0, Ordering::Relaxed);
         *self.last_failure_time.write().await = None;
         *self.last_state_change.write().await = Some(Instant::now());
-        self.half_open_calls.store(0, Ordering::Relaxed);
+        self.outcomes.lock().clear();
     }
 
     /// 获取统计信息
@@ -525,8 +657,51 @@ mod tests {
         let result = breaker
             .execute(|| async { Ok::<(), FlowGuardError>(()) })
             .await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("熔断器打开"));
+        assert!(matches!(
+            result,
+            Err(FlowGuardError::CircuitBreakerError(ref msg)) if msg.contains("熔断器打开")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_limits_concurrent_half_open_probes() {
+        let config =
+            CircuitBreakerConfig::new(2, 2, Duration::from_millis(50)).half_open_max_calls(1);
+        let breaker = Arc::new(CircuitBreaker::new(config));
+
+        trip(&breaker).await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        let probe = {
+            let breaker = Arc::clone(&breaker);
+            tokio::spawn(async move {
+                breaker
+                    .execute(|| async {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok::<(), FlowGuardError>(())
+                    })
+                    .await
+            })
+        };
+        tokio::task::yield_now().await;
+
+        // 探测名额被占用时短路
+        let result = breaker
+            .execute(|| async { Ok::<(), FlowGuardError>(()) })
+            .await;
+        assert!(matches!(
+            result,
+            Err(FlowGuardError::CircuitBreakerError(_))
+        ));
+
+        // 探测结束后名额归还，而不是累计计数
+        assert!(probe.await.unwrap().is_ok());
+        assert!(breaker.is_half_open().await);
+        assert!(breaker
+            .execute(|| async { Ok::<(), FlowGuardError>(()) })
+            .await
+            .is_ok());
+        assert!(breaker.is_closed().await);
     }
 
     #[tokio::test]
@@ -667,27 +842,21 @@ mod tests {
         // 等待超时
         tokio::time::sleep(Duration::from_millis(150)).await;
 
-        // 第一次调用，进入半开状态
-        let result = breaker
-            .execute(|| async { Ok::<(), FlowGuardError>(()) })
-            .await;
-        assert!(result.is_ok());
+        // 上限只约束同时在途的探测，顺序探测结束后名额归还，不会被累计耗尽
+        for _ in 0..2 {
+            let result = breaker
+                .execute(|| async { Ok::<(), FlowGuardError>(()) })
+                .await;
+            assert!(result.is_ok());
+            assert!(breaker.is_half_open().await);
+        }
 
-        // 第二次调用，达到上限
+        // 第三次成功探测达到 success_threshold，熔断器关闭
         let result = breaker
             .execute(|| async { Ok::<(), FlowGuardError>(()) })
             .await;
         assert!(result.is_ok());
-
-        // 第三次调用，应该被拒绝
-        let result = breaker
-            .execute(|| async { Ok::<(), FlowGuardError>(()) })
-            .await;
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("半开状态调用次数已达上限"));
+        assert!(breaker.is_closed().await);
     }
 
     #[tokio::test]
@@ -700,4 +869,208 @@ mod tests {
         assert_eq!(breaker_config.success_threshold, 5);
         assert_eq!(breaker_config.timeout, Duration::from_secs(30));
     }
+
+    /// 通过 `call` 触发熔断
+    async fn trip(breaker: &CircuitBreaker) {
+        for _ in 0..breaker.config().failure_threshold {
+            let _ = breaker.call(|| async { Err::<(), _>("boom") }).await;
+        }
+        assert!(breaker.is_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_call_full_cycle_with_half_open_gate() {
+        let config =
+            CircuitBreakerConfig::new(2, 2, Duration::from_millis(50)).half_open_max_calls(2);
+        let breaker = Arc::new(CircuitBreaker::new(config));
+
+        // Closed -> Open
+        trip(&breaker).await;
+        let result = breaker.call(|| async { Ok::<_, &str>(()) }).await;
+        assert!(matches!(result, Err(CircuitBreakerError::Open)));
+
+        // Open -> HalfOpen：并发探测只放行 half_open_max_calls 个
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let max_in_flight = Arc::new(AtomicU64::new(0));
+        let mut handles = Vec::new();
+        for _ in 0..6 {
+            let breaker = Arc::clone(&breaker);
+            let in_flight = Arc::clone(&in_flight);
+            let max_in_flight = Arc::clone(&max_in_flight);
+            handles.push(tokio::spawn(async move {
+                breaker
+                    .call(|| async {
+                        let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(current, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        Ok::<_, &str>(())
+                    })
+                    .await
+            }));
+        }
+
+        let mut admitted = 0;
+        let mut rejected = 0;
+        for handle in handles {
+            match handle.await.unwrap() {
+                Ok(()) => admitted += 1,
+                Err(CircuitBreakerError::Open) => rejected += 1,
+                Err(CircuitBreakerError::Inner(e)) => panic!("unexpected error: {}", e),
+            }
+        }
+        assert_eq!(admitted, 2);
+        assert_eq!(rejected, 4);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+
+        // HalfOpen -> Closed
+        assert!(breaker.is_closed().await);
+        assert!(breaker.call(|| async { Ok::<_, &str>(()) }).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_call_half_open_failure_reopens() {
+        let config =
+            CircuitBreakerConfig::new(2, 2, Duration::from_millis(50)).half_open_max_calls(2);
+        let breaker = Arc::new(CircuitBreaker::new(config));
+
+        trip(&breaker).await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // 两个并发探测：一个很快失败，一个较慢成功
+        let slow = {
+            let breaker = Arc::clone(&breaker);
+            tokio::spawn(async move {
+                breaker
+                    .call(|| async {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok::<_, &str>(())
+                    })
+                    .await
+            })
+        };
+        tokio::task::yield_now().await;
+        let result = breaker
+            .call(|| async { Err::<(), _>("probe failed") })
+            .await;
+        assert!(matches!(
+            result,
+            Err(CircuitBreakerError::Inner("probe failed"))
+        ));
+
+        // 单次失败立即回到打开状态，并重新开始计时
+        assert!(breaker.is_open().await);
+        let result = breaker.call(|| async { Ok::<_, &str>(()) }).await;
+        assert!(matches!(result, Err(CircuitBreakerError::Open)));
+
+        // 迟到的成功不会改变打开状态
+        assert!(slow.await.unwrap().is_ok());
+        assert!(breaker.is_open().await);
+
+        // 超时后再次探测并恢复
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        for _ in 0..2 {
+            assert!(breaker.call(|| async { Ok::<_, &str>(()) }).await.is_ok());
+        }
+        assert!(breaker.is_closed().await);
+    }
+
+    #[test]
+    fn test_circuit_breaker_error_into_flow_guard_error() {
+        let err: FlowGuardError = CircuitBreakerError::<FlowGuardError>::Open.into();
+        assert!(matches!(err, FlowGuardError::CircuitBreakerError(_)));
+
+        let inner = FlowGuardError::LimitError("inner".to_string());
+        let err: FlowGuardError = CircuitBreakerError::Inner(inner).into();
+        assert!(matches!(err, FlowGuardError::LimitError(_)));
+    }
+
+    /// 按给定结果序列调用熔断器（true 表示失败）
+    async fn drive(breaker: &CircuitBreaker, pattern: &[bool]) {
+        for &failed in pattern {
+            let _ = breaker
+                .call(|| async move {
+                    if failed {
+                        Err("failed")
+                    } else {
+                        Ok(())
+                    }
+                })
+                .await;
+        }
+    }
+
+    fn rate_breaker(window: usize, threshold: f64, min_calls: usize) -> CircuitBreaker {
+        CircuitBreaker::new(
+            CircuitBreakerConfig::new(5, 2, Duration::from_secs(60)).trip_policy(
+                TripPolicy::FailureRate {
+                    window,
+                    threshold,
+                    min_calls,
+                },
+            ),
+        )
+    }
+
+    #[test]
+    fn test_trip_policy_default_is_consecutive() {
+        let config = CircuitBreakerConfig::default();
+        assert_eq!(config.trip_policy, TripPolicy::ConsecutiveFailures);
+        let config = CircuitBreakerConfig::new(7, 2, Duration::from_secs(1))
+            .trip_policy(TripPolicy::ConsecutiveFailures);
+        assert_eq!(config.failure_threshold, 7);
+    }
+
+    #[tokio::test]
+    async fn test_failure_rate_trips_above_threshold() {
+        let breaker = rate_breaker(10, 0.5, 10);
+
+        // 交替失败：失败不连续，但 6/10 > 50%
+        drive(
+            &breaker,
+            &[true, false, true, false, true, false, true, true, false],
+        )
+        .await;
+        assert!(breaker.is_closed().await);
+        drive(&breaker, &[true]).await;
+        assert!(breaker.is_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_failure_rate_does_not_trip_at_or_below_threshold() {
+        let breaker = rate_breaker(10, 0.5, 10);
+
+        // 恰好 50% 不触发
+        drive(&breaker, &[true, false].repeat(10)).await;
+        assert!(breaker.is_closed().await);
+
+        // 连续失败数超过 failure_threshold，但失败率未超阈值
+        drive(&breaker, &[false; 5]).await;
+        drive(&breaker, &[true; 5]).await;
+        assert!(breaker.is_closed().await);
+    }
+
+    #[tokio::test]
+    async fn test_failure_rate_respects_min_calls() {
+        let breaker = rate_breaker(100, 0.5, 20);
+
+        drive(&breaker, &[true; 19]).await;
+        assert!(breaker.is_closed().await);
+        drive(&breaker, &[true]).await;
+        assert!(breaker.is_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_failure_rate_window_slides() {
+        let breaker = rate_breaker(4, 0.5, 4);
+
+        // 旧的失败滑出窗口后不再计入
+        drive(&breaker, &[true, true, false, false]).await;
+        assert!(breaker.is_closed().await);
+        drive(&breaker, &[false, false, true, false]).await;
+        assert!(breaker.is_closed().await);
+        drive(&breaker, &[true, true]).await;
+        assert!(breaker.is_open().await);
+    }
 }
@@ -0,0 +1,430 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! 配置检查命令行工具
+//!
+//! `limiteron-cli` 的实现，便于在部署前或 CI 中检查配置文件：
+//!
+//! - `validate <file>`：解析配置（JSON/YAML/TOML）并运行
+//!   [`FlowControlConfig::validate_all`]，逐条打印带路径的错误
+//! - `lint <file> [--deny-warnings]`：在校验基础上报告可疑配置，见 [`lint`]
+//!
+//! 退出码：`0` 通过，`1` 校验失败（或 `--deny-warnings` 下存在警告），`2` 用法或读取错误。
+
+use crate::config::{ConfigFormat, FlowControlConfig, LimiterConfig, Matcher};
+use crate::error::FlowGuardError;
+use ahash::AHashSet as HashSet;
+use std::io::Write;
+use std::path::Path;
+
+/// 用法说明
+pub const USAGE: &str = "用法:
+  limiteron-cli validate <file>
+  limiteron-cli lint <file> [--deny-warnings]";
+
+/// 退出码：检查通过
+pub const EXIT_OK: i32 = 0;
+/// 退出码：配置校验失败或存在被拒绝的警告
+pub const EXIT_INVALID: i32 = 1;
+/// 退出码：用法错误或无法读取、解析配置文件
+pub const EXIT_USAGE: i32 = 2;
+
+/// 可疑配置警告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// 相关字段的 JSON 路径（如 `rules[0].matchers`）
+    pub path: String,
+    /// 警告描述
+    pub message: String,
+}
+
+impl LintWarning {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// 读取并解析配置文件，不做校验
+///
+/// 按扩展名选择格式，无法识别扩展名时根据内容推断。
+pub fn load(path: &Path) -> Result<FlowControlConfig, FlowGuardError> {
+    let content = std::fs::read_to_string(path)?;
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ConfigFormat::from_extension)
+        .unwrap_or_else(|| ConfigFormat::detect(&content));
+    FlowControlConfig::parse_unvalidated(&content, format)
+}
+
+/// 检查可疑配置
+///
+/// 报告以下情况（均不影响配置校验结果）：
+/// - 规则内同类匹配器的取值没有交集，规则永远不会匹配
+/// - 两条启用的规则匹配条件完全相同
+/// - 限额为 `u64::MAX`，等同于不限流
+pub fn lint(config: &FlowControlConfig) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+
+    for (index, rule) in config.rules.iter().enumerate() {
+        let path = format!("rules[{}]", index);
+        if let Some(kind) = unsatisfiable_matcher_kind(&rule.matchers) {
+            warnings.push(LintWarning::new(
+                format!("{}.matchers", path),
+                format!("多个 {} 匹配器的取值没有交集，规则永远不会匹配", kind),
+            ));
+        }
+        for (limiter_index, limiter) in rule.limiters.iter().enumerate() {
+            if let Some(field) = unlimited_field(limiter) {
+                warnings.push(LintWarning::new(
+                    format!("{}.limiters[{}].{}", path, limiter_index, field),
+                    "限额为 u64::MAX，等同于不限流",
+                ));
+            }
+        }
+    }
+
+    let enabled: Vec<_> = config
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| !rule.disabled && !rule.matchers.is_empty())
+        .map(|(index, rule)| (index, matcher_signature(&rule.matchers)))
+        .collect();
+    for (position, (index, signature)) in enabled.iter().enumerate() {
+        if let Some((other, _)) = enabled[..position]
+            .iter()
+            .find(|(_, other_signature)| other_signature == signature)
+        {
+            warnings.push(LintWarning::new(
+                format!("rules[{}].matchers", index),
+                format!("与 rules[{}] 的匹配条件完全相同", other),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// 同类匹配器按与逻辑组合，取值没有交集时返回该匹配器类型
+///
+/// 只检查取值为精确集合的匹配器；含 `*` 或 glob 模式的用户匹配器不参与检查。
+fn unsatisfiable_matcher_kind(matchers: &[Matcher]) -> Option<&'static str> {
+    let mut sets: Vec<(&'static str, HashSet<String>)> = Vec::new();
+
+    for matcher in matchers {
+        let (kind, values): (&'static str, HashSet<String>) = match matcher {
+            Matcher::User { user_ids } => {
+                if user_ids.iter().any(|id| id == "*") {
+                    continue;
+                }
+                ("User", user_ids.iter().map(String::clone).collect())
+            }
+            Matcher::UserPattern {
+                user_ids,
+                case_insensitive,
+            } => {
+                if user_ids.iter().any(|id| id.contains('*')) {
+                    continue;
+                }
+                let values = user_ids
+                    .iter()
+                    .map(|id| {
+                        if *case_insensitive {
+                            id.to_lowercase()
+                        } else {
+                            id.clone()
+                        }
+                    })
+                    .collect();
+                ("UserPattern", values)
+            }
+            Matcher::Method { methods } => (
+                "Method",
+                methods.iter().map(|m| m.to_ascii_uppercase()).collect(),
+            ),
+            Matcher::Geo { countries } => (
+                "Geo",
+                countries.iter().map(|c| c.to_ascii_uppercase()).collect(),
+            ),
+            Matcher::ApiVersion { versions } => {
+                ("ApiVersion", versions.iter().map(String::clone).collect())
+            }
+            Matcher::Device { device_types } => (
+                "Device",
+                device_types.iter().map(|d| d.to_lowercase()).collect(),
+            ),
+            _ => continue,
+        };
+
+        match sets.iter_mut().find(|(existing, _)| *existing == kind) {
+            Some((_, existing)) => {
+                existing.retain(|value| values.contains(value));
+                if existing.is_empty() {
+                    return Some(kind);
+                }
+            }
+            None => sets.push((kind, values)),
+        }
+    }
+
+    None
+}
+
+/// 取值为 `u64::MAX` 的限额字段
+fn unlimited_field(limiter: &LimiterConfig) -> Option<&'static str> {
+    match limiter {
+        LimiterConfig::TokenBucket { capacity, .. } if *capacity == u64::MAX => Some("capacity"),
+        LimiterConfig::SlidingWindow { max_requests, .. }
+        | LimiterConfig::FixedWindow { max_requests, .. }
+            if *max_requests == u64::MAX =>
+        {
+            Some("max_requests")
+        }
+        LimiterConfig::Quota { limit, .. } if *limit == u64::MAX => Some("limit"),
+        LimiterConfig::Concurrency { max_concurrent } if *max_concurrent == u64::MAX => {
+            Some("max_concurrent")
+        }
+        LimiterConfig::Gcra { burst, .. } if *burst == u64::MAX => Some("burst"),
+        _ => None,
+    }
+}
+
+/// 与顺序无关的匹配条件签名
+fn matcher_signature(matchers: &[Matcher]) -> Vec<String> {
+    let mut signature: Vec<String> = matchers
+        .iter()
+        .map(|matcher| serde_json::to_string(matcher).unwrap_or_default())
+        .collect();
+    signature.sort();
+    signature
+}
+
+/// 执行命令行，返回退出码
+///
+/// `args` 不含程序名；正常输出写入 `out`，错误与警告写入 `err`。
+pub fn run(args: &[String], out: &mut impl Write, err: &mut impl Write) -> i32 {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => {
+            let _ = writeln!(err, "{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    let deny_warnings = rest.iter().any(|arg| arg == "--deny-warnings");
+    let files: Vec<&String> = rest.iter().filter(|arg| !arg.starts_with("--")).collect();
+    let unknown_flag = rest
+        .iter()
+        .find(|arg| arg.starts_with("--") && (command != "lint" || *arg != "--deny-warnings"));
+
+    let file = match (command, files.as_slice(), unknown_flag) {
+        ("-h" | "--help" | "help", _, _) => {
+            let _ = writeln!(out, "{}", USAGE);
+            return EXIT_OK;
+        }
+        ("validate" | "lint", [file], None) => Path::new(file.as_str()),
+        (_, _, Some(flag)) => {
+            let _ = writeln!(err, "未知参数: {}\n{}", flag, USAGE);
+            return EXIT_USAGE;
+        }
+        _ => {
+            let _ = writeln!(err, "{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    let config = match load(file) {
+        Ok(config) => config,
+        Err(e) => {
+            let _ = writeln!(err, "error: {}: {}", file.display(), e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let mut code = EXIT_OK;
+    if let Err(report) = config.validate_all() {
+        for error in &report.errors {
+            let _ = writeln!(err, "error: {}", error);
+        }
+        let _ = writeln!(
+            err,
+            "{}: 发现 {} 个错误",
+            file.display(),
+            report.errors.len()
+        );
+        code = EXIT_INVALID;
+    }
+
+    if command == "lint" {
+        let warnings = lint(&config);
+        for warning in &warnings {
+            let _ = writeln!(err, "warning: {}", warning);
+        }
+        if !warnings.is_empty() {
+            let _ = writeln!(err, "{}: 发现 {} 个警告", file.display(), warnings.len());
+            if deny_warnings {
+                code = EXIT_INVALID;
+            }
+        }
+    }
+
+    if code == EXIT_OK {
+        let _ = writeln!(out, "{}: 检查通过", file.display());
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ActionConfig, GlobalConfig, Rule};
+
+    fn rule(id: &str, matchers: Vec<Matcher>, limiter: LimiterConfig) -> Rule {
+        Rule {
+            id: id.to_string(),
+            name: id.to_string(),
+            priority: 10,
+            matchers,
+            limiters: vec![limiter],
+            action: ActionConfig {
+                on_exceed: "reject".to_string(),
+                ban: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn config(rules: Vec<Rule>) -> FlowControlConfig {
+        FlowControlConfig {
+            version: "1.0".to_string(),
+            global: GlobalConfig {
+                storage: "memory".to_string(),
+                cache: "memory".to_string(),
+                metrics: "prometheus".to_string(),
+            },
+            rules,
+        }
+    }
+
+    fn methods(methods: &[&str]) -> Matcher {
+        Matcher::Method {
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    fn window(max_requests: u64) -> LimiterConfig {
+        LimiterConfig::FixedWindow {
+            window_size: "60s".to_string(),
+            max_requests,
+        }
+    }
+
+    #[test]
+    fn test_lint_clean_config() {
+        let config = config(vec![
+            rule("reads", vec![methods(&["GET"])], window(100)),
+            rule("writes", vec![methods(&["POST", "PUT"])], window(10)),
+        ]);
+        assert!(lint(&config).is_empty());
+    }
+
+    #[test]
+    fn test_lint_unsatisfiable_matchers() {
+        let config = config(vec![
+            rule(
+                "never",
+                vec![methods(&["GET"]), methods(&["post"])],
+                window(10),
+            ),
+            rule(
+                "overlap",
+                vec![methods(&["GET", "POST"]), methods(&["post"])],
+                window(10),
+            ),
+        ]);
+        let warnings = lint(&config);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "rules[0].matchers");
+        assert!(warnings[0].message.contains("Method"));
+    }
+
+    #[test]
+    fn test_lint_duplicate_rules() {
+        let user = Matcher::User {
+            user_ids: vec!["*".to_string()],
+        };
+        let mut disabled = rule("disabled", vec![user.clone()], window(5));
+        disabled.disabled = true;
+        let config = config(vec![
+            rule("a", vec![user.clone(), methods(&["GET"])], window(10)),
+            rule("b", vec![methods(&["GET"]), user.clone()], window(20)),
+            disabled,
+        ]);
+
+        let warnings = lint(&config);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "rules[1].matchers");
+        assert!(warnings[0].message.contains("rules[0]"));
+    }
+
+    #[test]
+    fn test_lint_unlimited_limiter() {
+        let config = config(vec![rule(
+            "unlimited",
+            vec![methods(&["GET"])],
+            window(u64::MAX),
+        )]);
+        let warnings = lint(&config);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "rules[0].limiters[0].max_requests");
+    }
+
+    #[test]
+    fn test_run_usage_errors() {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        assert_eq!(run(&[], &mut out, &mut err), EXIT_USAGE);
+        assert_eq!(
+            run(
+                &["check".to_string(), "a.json".to_string()],
+                &mut out,
+                &mut err
+            ),
+            EXIT_USAGE
+        );
+        assert_eq!(
+            run(
+                &[
+                    "validate".to_string(),
+                    "a.json".to_string(),
+                    "--deny-warnings".to_string()
+                ],
+                &mut out,
+                &mut err
+            ),
+            EXIT_USAGE
+        );
+        assert_eq!(
+            run(
+                &[
+                    "validate".to_string(),
+                    "/nonexistent/limiteron.json".to_string()
+                ],
+                &mut out,
+                &mut err
+            ),
+            EXIT_USAGE
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! 时钟抽象模块
+//!
+//! 限流器与封禁逻辑通过 [`Clock`] 读取时间：生产环境使用 [`RealClock`]，
+//! 测试中使用 [`MockClock`] 手动推进时间，无需真实 `sleep`。
+//!
+//! 时钟可能回拨（NTP 校时、虚拟机暂停恢复、自定义 `Clock` 实现）。计算经过时间时，
+//! 负的间隔一律按零处理；回拨超过 [`CLOCK_BACKWARD_TOLERANCE`] 时，限流器将参考时间点
+//! 重置到当前时间（视为这段时间没有流逝），并记录一次警告。
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// 时钟回拨容差
+///
+/// 多个线程先读取时钟再更新共享状态时，读数可能有微小的先后倒置，
+/// 不超过该值的负间隔只按零处理，不视为时钟回拨。
+pub const CLOCK_BACKWARD_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// 是否已经记录过时钟回拨警告
+static CLOCK_BACKWARD_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 判断参考时间点领先当前时间 `ahead_by` 是否属于时钟回拨
+///
+/// 超过 [`CLOCK_BACKWARD_TOLERANCE`] 时返回 `true`，调用方应将参考时间点重置到当前时间；
+/// 进程内只在第一次检测到回拨时记录警告。
+pub(crate) fn clock_went_backward(source: &str, ahead_by: Duration) -> bool {
+    if ahead_by <= CLOCK_BACKWARD_TOLERANCE {
+        return false;
+    }
+    if !CLOCK_BACKWARD_WARNED.swap(true, Ordering::Relaxed) {
+        warn!(
+            source,
+            backward_ms = ahead_by.as_millis() as u64,
+            "检测到时钟回拨，按未经过时间处理"
+        );
+    }
+    true
+}
+
+/// 从 `earlier` 到 `now` 经过的时间，`now` 早于 `earlier` 时为零
+pub fn elapsed_utc(earlier: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    match (now - earlier).to_std() {
+        Ok(elapsed) => elapsed,
+        Err(_) => {
+            if let Ok(ahead_by) = (earlier - now).to_std() {
+                clock_went_backward("utc", ahead_by);
+            }
+            Duration::ZERO
+        }
+    }
+}
+
+/// 时间来源
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// 当前单调时间，用于窗口与令牌补充计算
+    fn now(&self) -> Instant;
+
+    /// 当前 UTC 时间，用于封禁时间戳等需要持久化的时间
+    fn now_utc(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealClock;
+
+impl RealClock {
+    /// 以 `Arc<dyn Clock>` 形式返回系统时钟
+    pub fn shared() -> Arc<dyn Clock> {
+        Arc::new(RealClock)
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 可手动推进的模拟时钟
+///
+/// 创建时记录真实时间作为起点，之后只在调用 [`MockClock::advance`] 时前进。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::clock::{Clock, MockClock};
+/// use std::time::Duration;
+///
+/// let clock = MockClock::new();
+/// let start = clock.now();
+/// clock.advance(Duration::from_secs(5));
+/// assert_eq!(clock.now() - start, Duration::from_secs(5));
+/// ```
+#[derive(Debug)]
+pub struct MockClock {
+    /// 单调时间起点
+    base: Instant,
+    /// UTC 时间起点
+    base_utc: DateTime<Utc>,
+    /// 已推进的纳秒数
+    offset_nanos: AtomicU64,
+}
+
+impl MockClock {
+    /// 创建新的模拟时钟
+    pub fn new() -> Self {
+        Self {
+            base: Instant::now(),
+            base_utc: Utc::now(),
+            offset_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// 创建以指定 UTC 时间为起点的模拟时钟
+    ///
+    /// 用于测试依赖日历时间（星期、时区、夏令时）的逻辑。
+    pub fn starting_at(base_utc: DateTime<Utc>) -> Self {
+        Self {
+            base_utc,
+            ..Self::new()
+        }
+    }
+
+    /// 推进时间
+    pub fn advance(&self, duration: Duration) {
+        let nanos = duration.as_nanos().min(u64::MAX as u128) as u64;
+        self.offset_nanos.fetch_add(nanos, Ordering::SeqCst);
+    }
+
+    /// 回拨时间，模拟系统时钟后退
+    ///
+    /// 最多回拨到创建时的时间点。
+    pub fn rewind(&self, duration: Duration) {
+        let nanos = duration.as_nanos().min(u64::MAX as u128) as u64;
+        let _ = self
+            .offset_nanos
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |offset| {
+                Some(offset.saturating_sub(nanos))
+            });
+    }
+
+    /// 自创建以来推进的总时长
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.offset_nanos.load(Ordering::SeqCst))
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.base + self.elapsed()
+    }
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.base_utc + chrono::Duration::from_std(self.elapsed()).unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advances_both_clocks() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let start_utc = clock.now_utc();
+
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::from_millis(1500));
+
+        assert_eq!(clock.now() - start, Duration::from_millis(1500));
+        assert_eq!(
+            clock.now_utc() - start_utc,
+            chrono::Duration::milliseconds(1500)
+        );
+
+        clock.rewind(Duration::from_millis(500));
+        assert_eq!(clock.now() - start, Duration::from_secs(1));
+        clock.rewind(Duration::from_secs(10));
+        assert_eq!(clock.now(), start);
+    }
+
+    #[test]
+    fn test_elapsed_utc_clamps_negative() {
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::seconds(3);
+        assert_eq!(elapsed_utc(earlier, now), Duration::from_secs(3));
+        assert_eq!(elapsed_utc(now, earlier), Duration::ZERO);
+        assert!(!clock_went_backward("test", Duration::from_millis(10)));
+        assert!(clock_went_backward("test", Duration::from_secs(5)));
+    }
+}
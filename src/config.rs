@@ -9,9 +9,13 @@
 use ahash::AHashSet as HashSet;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::FlowGuardError;
+use crate::limiters::SlidingWindowMode;
 
 /// 流量控制配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FlowControlConfig {
     pub version: String,
     pub global: GlobalConfig,
@@ -28,35 +32,335 @@ impl Default for FlowControlConfig {
     }
 }
 
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// 根据文件扩展名确定格式
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// 根据内容推断格式
+    ///
+    /// 以 `{` 开头视为 JSON；首个有效行以 `[` 开头或为 `key = value` 形式视为 TOML；否则视为 YAML。
+    pub fn detect(content: &str) -> Self {
+        let first_line = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default();
+
+        if first_line.starts_with('{') {
+            return Self::Json;
+        }
+        if first_line.starts_with('[') {
+            return Self::Toml;
+        }
+        match (first_line.find('='), first_line.find(':')) {
+            (Some(eq), Some(colon)) if eq < colon => Self::Toml,
+            (Some(_), None) => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// 单个配置校验错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigValidationError {
+    /// 出错字段的 JSON 路径（如 `rules[0].limiters[1].window_size`）
+    pub path: String,
+    /// 错误描述
+    pub message: String,
+}
+
+impl ConfigValidationError {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// 配置校验报告，包含一次校验发现的全部错误
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigValidationReport {
+    /// 校验错误
+    pub errors: Vec<ConfigValidationError>,
+}
+
+impl ConfigValidationReport {
+    /// 错误数量
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// 是否没有错误
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// 指定路径下的错误
+    pub fn errors_at<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> impl Iterator<Item = &'a ConfigValidationError> + 'a {
+        self.errors.iter().filter(move |error| error.path == path)
+    }
+}
+
+impl std::fmt::Display for ConfigValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, error) in self.errors.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigValidationReport {}
+
+/// 拼接校验路径
+fn join_path(base: &str, field: &str) -> String {
+    if base.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", base, field)
+    }
+}
+
+/// 运行收集器并返回第一个错误，供各子配置的 `validate` 使用
+fn first_error(collect: impl FnOnce(&mut Vec<ConfigValidationError>)) -> Result<(), String> {
+    let mut errors = Vec::new();
+    collect(&mut errors);
+    match errors.into_iter().next() {
+        Some(error) => Err(error.to_string()),
+        None => Ok(()),
+    }
+}
+
 impl FlowControlConfig {
     /// 校验配置
+    ///
+    /// 委托给 [`validate_all`](Self::validate_all)，所有错误合并为一个字符串。
     pub fn validate(&self) -> Result<(), String> {
+        self.validate_all().map_err(|report| report.to_string())
+    }
+
+    /// 校验配置并一次性报告全部错误
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::config::FlowControlConfig;
+    ///
+    /// let config = FlowControlConfig {
+    ///     version: String::new(),
+    ///     ..Default::default()
+    /// };
+    /// let report = config.validate_all().unwrap_err();
+    /// assert_eq!(report.len(), 2);
+    /// assert_eq!(report.errors[0].path, "version");
+    /// assert_eq!(report.errors[1].path, "rules");
+    /// ```
+    pub fn validate_all(&self) -> Result<(), ConfigValidationReport> {
+        self.validate_all_inner(None)
+    }
+
+    /// 校验配置，并检查自定义匹配器名称是否在 `known_custom_matchers` 中
+    pub fn validate_all_with_custom_matchers(
+        &self,
+        known_custom_matchers: &[&str],
+    ) -> Result<(), ConfigValidationReport> {
+        self.validate_all_inner(Some(known_custom_matchers))
+    }
+
+    /// 从 JSON 字符串加载配置，解析后规范化并校验
+    pub fn from_json(content: &str) -> Result<Self, FlowGuardError> {
+        Self::from_str_with_format(content, ConfigFormat::Json)
+    }
+
+    /// 从 YAML 字符串加载配置，解析后规范化并校验
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(content: &str) -> Result<Self, FlowGuardError> {
+        Self::from_str_with_format(content, ConfigFormat::Yaml)
+    }
+
+    /// 从 TOML 字符串加载配置，解析后规范化并校验
+    #[cfg(feature = "toml")]
+    pub fn from_toml(content: &str) -> Result<Self, FlowGuardError> {
+        Self::from_str_with_format(content, ConfigFormat::Toml)
+    }
+
+    /// 从文件加载配置，按扩展名（`json`、`yaml`/`yml`、`toml`）选择格式
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FlowGuardError> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ConfigFormat::from_extension)
+            .ok_or_else(|| {
+                FlowGuardError::ConfigError(format!("不支持的配置文件类型: {}", path.display()))
+            })?;
+        let content = std::fs::read_to_string(path)?;
+        Self::from_str_with_format(&content, format)
+    }
+
+    /// 按指定格式加载配置；`format` 为 `None` 时根据内容推断
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::config::FlowControlConfig;
+    ///
+    /// let json = r#"{
+    ///     "version": "1.0",
+    ///     "global": {"storage": "memory", "cache": "memory", "metrics": "prometheus"},
+    ///     "rules": [{
+    ///         "id": "api", "name": "API", "priority": 10,
+    ///         "matchers": [{"type": "User", "user_ids": ["*"]}],
+    ///         "limiters": [{"type": "TokenBucket", "capacity": 100, "refill_rate": 10}],
+    ///         "action": {"on_exceed": "reject", "ban": null}
+    ///     }]
+    /// }"#;
+    /// let config = FlowControlConfig::parse(json, None).unwrap();
+    /// assert_eq!(config.rules[0].id, "api");
+    /// ```
+    pub fn parse(content: &str, format: Option<ConfigFormat>) -> Result<Self, FlowGuardError> {
+        let format = format.unwrap_or_else(|| ConfigFormat::detect(content));
+        Self::from_str_with_format(content, format)
+    }
+
+    /// 按指定格式解析、规范化并校验配置
+    pub fn from_str_with_format(
+        content: &str,
+        format: ConfigFormat,
+    ) -> Result<Self, FlowGuardError> {
+        let config = Self::parse_unvalidated(content, format)?;
+        config
+            .validate_all()
+            .map_err(|report| FlowGuardError::ConfigError(report.to_string()))?;
+        Ok(config)
+    }
+
+    /// 按指定格式解析并规范化配置，不做校验
+    ///
+    /// 用于需要自行处理 [`validate_all`](Self::validate_all) 报告的场景（如配置检查工具）。
+    pub fn parse_unvalidated(content: &str, format: ConfigFormat) -> Result<Self, FlowGuardError> {
+        let mut config: Self = match format {
+            ConfigFormat::Json => serde_json::from_str(content)
+                .map_err(|e| FlowGuardError::ConfigError(format!("JSON解析错误: {}", e)))?,
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| FlowGuardError::ConfigError(format!("YAML解析错误: {}", e)))?,
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml => toml::from_str(content)
+                .map_err(|e| FlowGuardError::ConfigError(format!("TOML解析错误: {}", e)))?,
+            #[allow(unreachable_patterns)]
+            other => {
+                return Err(FlowGuardError::ConfigError(format!(
+                    "未启用 {:?} 配置格式支持",
+                    other
+                )))
+            }
+        };
+
+        config.normalize();
+        Ok(config)
+    }
+
+    /// 规范化配置：去除标识字段首尾空白，枚举类字符串统一为小写
+    pub fn normalize(&mut self) {
+        self.version = self.version.trim().to_string();
+        self.global.storage = self.global.storage.trim().to_ascii_lowercase();
+        self.global.cache = self.global.cache.trim().to_ascii_lowercase();
+        self.global.metrics = self.global.metrics.trim().to_ascii_lowercase();
+
+        for rule in &mut self.rules {
+            rule.id = rule.id.trim().to_string();
+            rule.name = rule.name.trim().to_string();
+            rule.action.on_exceed = rule.action.on_exceed.trim().to_ascii_lowercase();
+            if let Some(ban) = &mut rule.action.ban {
+                ban.scope = ban.scope.trim().to_ascii_lowercase();
+            }
+        }
+    }
+
+    fn validate_all_inner(
+        &self,
+        known_custom_matchers: Option<&[&str]>,
+    ) -> Result<(), ConfigValidationReport> {
+        let mut errors = Vec::new();
+
         // 校验版本
         if self.version.is_empty() {
-            return Err("版本号不能为空".to_string());
+            errors.push(ConfigValidationError::new("version", "版本号不能为空"));
         }
 
         // 校验全局配置
-        self.global.validate()?;
+        self.global.collect_errors("global", &mut errors);
 
         // 校验规则
+        if self.rules.is_empty() {
+            errors.push(ConfigValidationError::new("rules", "至少需要一个规则"));
+        }
+
         let mut rule_ids = HashSet::new();
         for (index, rule) in self.rules.iter().enumerate() {
+            let path = format!("rules[{}]", index);
+
             // 检查规则ID是否唯一
-            if !rule_ids.insert(&rule.id) {
-                return Err(format!("规则ID重复: {}", rule.id));
+            if !rule.id.is_empty() && !rule_ids.insert(&rule.id) {
+                errors.push(ConfigValidationError::new(
+                    join_path(&path, "id"),
+                    format!("规则ID重复: {}", rule.id),
+                ));
             }
 
-            // 校验规则
-            rule.validate()
-                .map_err(|e| format!("规则[{}]校验失败: {}", index, e))?;
+            rule.collect_errors(&path, &mut errors);
+
+            if let Some(known) = known_custom_matchers {
+                for (matcher_index, matcher) in rule.matchers.iter().enumerate() {
+                    if let Matcher::Custom { name, .. } = matcher {
+                        if !name.is_empty() && !known.contains(&name.as_str()) {
+                            errors.push(ConfigValidationError::new(
+                                format!("{}.matchers[{}].name", path, matcher_index),
+                                format!("未知的自定义匹配器: {}", name),
+                            ));
+                        }
+                    }
+                }
+            }
         }
 
-        if self.rules.is_empty() {
-            return Err("至少需要一个规则".to_string());
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigValidationReport { errors })
         }
-
-        Ok(())
     }
 
     /// 计算配置哈希值
@@ -97,9 +401,62 @@ impl FlowControlConfig {
             } else {
                 vec!["初始配置".to_string()]
             },
+            diff: old_config
+                .map(|old| self.structural_diff(old))
+                .unwrap_or_default(),
         }
     }
 
+    /// 结构化比较配置差异
+    ///
+    /// 规则按 ID 对应（路径形如 `rules[api].limiters[0].max_requests`），
+    /// 新增或移除的规则整体记录为一条变更。
+    pub fn structural_diff(&self, old: &FlowControlConfig) -> Vec<ConfigFieldChange> {
+        let mut diff = Vec::new();
+
+        if self.version != old.version {
+            diff.push(ConfigFieldChange::Modified {
+                path: "version".to_string(),
+                old: old.version.clone().into(),
+                new: self.version.clone().into(),
+            });
+        }
+
+        diff_json_values(
+            "global",
+            &to_json_value(&old.global),
+            &to_json_value(&self.global),
+            &mut diff,
+        );
+
+        for old_rule in &old.rules {
+            let path = format!("rules[{}]", old_rule.id);
+            match self.rules.iter().find(|rule| rule.id == old_rule.id) {
+                Some(new_rule) => diff_json_values(
+                    &path,
+                    &to_json_value(old_rule),
+                    &to_json_value(new_rule),
+                    &mut diff,
+                ),
+                None => diff.push(ConfigFieldChange::Removed {
+                    path,
+                    value: to_json_value(old_rule),
+                }),
+            }
+        }
+
+        for new_rule in &self.rules {
+            if !old.rules.iter().any(|rule| rule.id == new_rule.id) {
+                diff.push(ConfigFieldChange::Added {
+                    path: format!("rules[{}]", new_rule.id),
+                    value: to_json_value(new_rule),
+                });
+            }
+        }
+
+        diff
+    }
+
     /// 比较配置差异
     fn diff_changes(&self, old: &FlowControlConfig) -> Vec<String> {
         let mut changes = Vec::new();
@@ -146,6 +503,118 @@ impl FlowControlConfig {
     }
 }
 
+fn to_json_value<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// 递归比较两个 JSON 值，把差异写入 `diff`
+///
+/// `type` 标签不同的对象（如不同种类的限流器）整体记录为修改。
+fn diff_json_values(
+    path: &str,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    diff: &mut Vec<ConfigFieldChange>,
+) {
+    use serde_json::Value;
+
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map))
+            if old_map.get("type") == new_map.get("type") =>
+        {
+            for (key, old_value) in old_map {
+                let field_path = join_path(path, key);
+                match new_map.get(key) {
+                    Some(new_value) => diff_json_values(&field_path, old_value, new_value, diff),
+                    None => diff.push(ConfigFieldChange::Removed {
+                        path: field_path,
+                        value: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    diff.push(ConfigFieldChange::Added {
+                        path: join_path(path, key),
+                        value: new_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for index in 0..old_items.len().max(new_items.len()) {
+                let item_path = format!("{}[{}]", path, index);
+                match (old_items.get(index), new_items.get(index)) {
+                    (Some(old_item), Some(new_item)) => {
+                        diff_json_values(&item_path, old_item, new_item, diff)
+                    }
+                    (Some(old_item), None) => diff.push(ConfigFieldChange::Removed {
+                        path: item_path,
+                        value: old_item.clone(),
+                    }),
+                    (None, Some(new_item)) => diff.push(ConfigFieldChange::Added {
+                        path: item_path,
+                        value: new_item.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => diff.push(ConfigFieldChange::Modified {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+/// 配置字段变更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigFieldChange {
+    /// 新增字段或规则
+    Added {
+        path: String,
+        value: serde_json::Value,
+    },
+    /// 移除字段或规则
+    Removed {
+        path: String,
+        value: serde_json::Value,
+    },
+    /// 修改字段
+    Modified {
+        path: String,
+        old: serde_json::Value,
+        new: serde_json::Value,
+    },
+}
+
+impl ConfigFieldChange {
+    /// 变更字段的路径
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Modified { path, .. } => {
+                path
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            Self::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            Self::Modified { path, old, new } => write!(f, "~ {}: {} -> {}", path, old, new),
+        }
+    }
+}
+
 /// 配置变更来源
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ChangeSource {
@@ -169,6 +638,31 @@ pub struct ConfigChangeRecord {
     pub new_hash: String,
     pub source: ChangeSource,
     pub changes: Vec<String>,
+    /// 与旧配置的结构化差异，初始配置为空
+    #[serde(default)]
+    pub diff: Vec<ConfigFieldChange>,
+}
+
+impl ConfigChangeRecord {
+    /// 生成可读的变更摘要，每条差异一行
+    pub fn summary(&self) -> String {
+        let mut summary = match &self.old_version {
+            Some(old_version) => format!(
+                "配置 {} -> {}（来源: {:?}）",
+                old_version, self.new_version, self.source
+            ),
+            None => format!("初始配置 {}（来源: {:?}）", self.new_version, self.source),
+        };
+
+        if self.old_version.is_some() && self.diff.is_empty() {
+            summary.push_str("\n  配置内容无变化");
+        }
+        for change in &self.diff {
+            summary.push_str("\n  ");
+            summary.push_str(&change.to_string());
+        }
+        summary
+    }
 }
 
 /// 配置变更历史
@@ -233,36 +727,46 @@ impl Default for GlobalConfig {
 impl GlobalConfig {
     /// 校验全局配置
     pub fn validate(&self) -> Result<(), String> {
+        first_error(|errors| self.collect_errors("", errors))
+    }
+
+    fn collect_errors(&self, path: &str, errors: &mut Vec<ConfigValidationError>) {
         let valid_storages = ["memory", "redis", "postgresql"];
         if !valid_storages.contains(&self.storage.as_str()) {
-            return Err(format!(
-                "无效的存储类型: {}, 有效值: {:?}",
-                self.storage, valid_storages
+            errors.push(ConfigValidationError::new(
+                join_path(path, "storage"),
+                format!(
+                    "无效的存储类型: {}, 有效值: {:?}",
+                    self.storage, valid_storages
+                ),
             ));
         }
 
         let valid_caches = ["memory", "redis"];
         if !valid_caches.contains(&self.cache.as_str()) {
-            return Err(format!(
-                "无效的缓存类型: {}, 有效值: {:?}",
-                self.cache, valid_caches
+            errors.push(ConfigValidationError::new(
+                join_path(path, "cache"),
+                format!("无效的缓存类型: {}, 有效值: {:?}", self.cache, valid_caches),
             ));
         }
 
         let valid_metrics = ["prometheus", "opentelemetry"];
         if !valid_metrics.contains(&self.metrics.as_str()) {
-            return Err(format!(
-                "无效的指标类型: {}, 有效值: {:?}",
-                self.metrics, valid_metrics
+            errors.push(ConfigValidationError::new(
+                join_path(path, "metrics"),
+                format!(
+                    "无效的指标类型: {}, 有效值: {:?}",
+                    self.metrics, valid_metrics
+                ),
             ));
         }
-
-        Ok(())
     }
 }
 
 /// 规则配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// 新增的可选字段都有默认值，构造时可用 `..Default::default()` 省略。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Rule {
     pub id: String,
     pub name: String,
@@ -270,55 +774,85 @@ pub struct Rule {
     pub matchers: Vec<Matcher>,
     pub limiters: Vec<LimiterConfig>,
     pub action: ActionConfig,
+    /// 是否禁用，禁用的规则不参与匹配
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
 }
 
 impl Rule {
     /// 校验规则
     pub fn validate(&self) -> Result<(), String> {
+        first_error(|errors| self.collect_errors("", errors))
+    }
+
+    fn collect_errors(&self, path: &str, errors: &mut Vec<ConfigValidationError>) {
         if self.id.is_empty() {
-            return Err("规则ID不能为空".to_string());
+            errors.push(ConfigValidationError::new(
+                join_path(path, "id"),
+                "规则ID不能为空",
+            ));
         }
 
         if self.name.is_empty() {
-            return Err("规则名称不能为空".to_string());
+            errors.push(ConfigValidationError::new(
+                join_path(path, "name"),
+                "规则名称不能为空",
+            ));
         }
 
         if self.matchers.is_empty() {
-            return Err("规则至少需要一个匹配器".to_string());
+            errors.push(ConfigValidationError::new(
+                join_path(path, "matchers"),
+                "规则至少需要一个匹配器",
+            ));
         }
 
         if self.limiters.is_empty() {
-            return Err("规则至少需要一个限流器".to_string());
+            errors.push(ConfigValidationError::new(
+                join_path(path, "limiters"),
+                "规则至少需要一个限流器",
+            ));
         }
 
         // 校验匹配器
         for (index, matcher) in self.matchers.iter().enumerate() {
-            matcher
-                .validate()
-                .map_err(|e| format!("匹配器[{}]: {}", index, e))?;
+            if let Err(message) = matcher.validate() {
+                errors.push(ConfigValidationError::new(
+                    format!("{}[{}]", join_path(path, "matchers"), index),
+                    message,
+                ));
+            }
         }
 
         // 校验限流器
         for (index, limiter) in self.limiters.iter().enumerate() {
-            limiter
-                .validate()
-                .map_err(|e| format!("限流器[{}]: {}", index, e))?;
+            limiter.collect_errors(
+                &format!("{}[{}]", join_path(path, "limiters"), index),
+                errors,
+            );
         }
 
         // 校验动作
-        self.action.validate()?;
-
-        Ok(())
+        self.action
+            .collect_errors(&join_path(path, "action"), errors);
     }
 }
 
 /// 匹配器
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Matcher {
+    /// 用户ID精确匹配，`*` 匹配任意用户
     User {
         user_ids: Vec<String>,
     },
+    /// 用户ID模式匹配，支持 `*` 与 glob 模式（如 `tenant-*`、`*-admin`）
+    UserPattern {
+        user_ids: Vec<String>,
+        /// 是否忽略大小写
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        case_insensitive: bool,
+    },
     Ip {
         ip_ranges: Vec<String>,
     },
@@ -331,6 +865,24 @@ pub enum Matcher {
     Device {
         device_types: Vec<String>,
     },
+    /// HTTP方法匹配（如 `GET`、`POST`），忽略大小写
+    Method {
+        methods: Vec<String>,
+    },
+    /// 请求路径正则匹配
+    #[cfg(feature = "regex")]
+    PathRegex {
+        /// 正则表达式
+        pattern: String,
+    },
+    /// HTTP头正则匹配
+    #[cfg(feature = "regex")]
+    HeaderRegex {
+        /// HTTP头名称
+        name: String,
+        /// 正则表达式
+        pattern: String,
+    },
     /// 自定义匹配器
     Custom {
         /// 匹配器名称
@@ -344,7 +896,7 @@ impl Matcher {
     /// 校验匹配器
     pub fn validate(&self) -> Result<(), String> {
         match self {
-            Matcher::User { user_ids } => {
+            Matcher::User { user_ids } | Matcher::UserPattern { user_ids, .. } => {
                 if user_ids.is_empty() {
                     return Err("用户ID列表不能为空".to_string());
                 }
@@ -369,6 +921,27 @@ impl Matcher {
                     return Err("设备类型列表不能为空".to_string());
                 }
             }
+            Matcher::Method { methods } => {
+                if methods.is_empty() {
+                    return Err("HTTP方法列表不能为空".to_string());
+                }
+                if let Some(method) = methods.iter().find(|method| {
+                    method.is_empty() || !method.chars().all(|c| c.is_ascii_alphabetic())
+                }) {
+                    return Err(format!("无效的HTTP方法: {:?}", method));
+                }
+            }
+            #[cfg(feature = "regex")]
+            Matcher::PathRegex { pattern } => {
+                regex::Regex::new(pattern).map_err(|e| format!("路径正则表达式无效: {}", e))?;
+            }
+            #[cfg(feature = "regex")]
+            Matcher::HeaderRegex { name, pattern } => {
+                if name.is_empty() {
+                    return Err("HTTP头名称不能为空".to_string());
+                }
+                regex::Regex::new(pattern).map_err(|e| format!("HTTP头正则表达式无效: {}", e))?;
+            }
             Matcher::Custom { name, config } => {
                 if name.is_empty() {
                     return Err("自定义匹配器名称不能为空".to_string());
@@ -383,7 +956,7 @@ impl Matcher {
 }
 
 /// 限流器配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum LimiterConfig {
     TokenBucket {
@@ -393,6 +966,9 @@ pub enum LimiterConfig {
     SlidingWindow {
         window_size: String,
         max_requests: u64,
+        /// 算法模式，默认使用内存占用固定的计数器模式
+        #[serde(default)]
+        mode: SlidingWindowMode,
     },
     FixedWindow {
         window_size: String,
@@ -407,6 +983,13 @@ pub enum LimiterConfig {
     Concurrency {
         max_concurrent: u64,
     },
+    /// GCRA 限流器
+    Gcra {
+        /// 发射间隔（如 "1s"）
+        period: String,
+        /// 突发容量
+        burst: u64,
+    },
     /// 自定义限流器
     Custom {
         /// 限流器名称
@@ -419,35 +1002,37 @@ pub enum LimiterConfig {
 impl LimiterConfig {
     /// 校验限流器
     pub fn validate(&self) -> Result<(), String> {
+        first_error(|errors| self.collect_errors("", errors))
+    }
+
+    fn collect_errors(&self, path: &str, errors: &mut Vec<ConfigValidationError>) {
+        let mut check = |failed: bool, field: &str, message: &str| {
+            if failed {
+                errors.push(ConfigValidationError::new(join_path(path, field), message));
+            }
+        };
+
         match self {
             LimiterConfig::TokenBucket {
                 capacity,
                 refill_rate,
             } => {
-                if *capacity == 0 {
-                    return Err("令牌桶容量不能为0".to_string());
-                }
-                if *refill_rate == 0 {
-                    return Err("填充速率不能为0".to_string());
-                }
+                check(*capacity == 0, "capacity", "令牌桶容量不能为0");
+                check(*refill_rate == 0, "refill_rate", "填充速率不能为0");
             }
             LimiterConfig::SlidingWindow {
                 window_size,
                 max_requests,
-            } => {
-                if *max_requests == 0 {
-                    return Err("最大请求数不能为0".to_string());
-                }
-                Self::validate_window_size(window_size)?;
+                ..
             }
-            LimiterConfig::FixedWindow {
+            | LimiterConfig::FixedWindow {
                 window_size,
                 max_requests,
             } => {
-                if *max_requests == 0 {
-                    return Err("最大请求数不能为0".to_string());
+                check(*max_requests == 0, "max_requests", "最大请求数不能为0");
+                if let Err(message) = Self::validate_window_size(window_size) {
+                    check(true, "window_size", &message);
                 }
-                Self::validate_window_size(window_size)?;
             }
             LimiterConfig::Quota {
                 quota_type,
@@ -455,47 +1040,41 @@ impl LimiterConfig {
                 window,
                 overdraft,
             } => {
-                if quota_type.is_empty() {
-                    return Err("配额类型不能为空".to_string());
+                check(quota_type.is_empty(), "quota_type", "配额类型不能为空");
+                check(*limit == 0, "limit", "配额限制不能为0");
+                if let Err(message) = Self::validate_window_size(window) {
+                    check(true, "window", &message);
                 }
-                if *limit == 0 {
-                    return Err("配额限制不能为0".to_string());
-                }
-                Self::validate_window_size(window)?;
-                if let Some(overdraft) = overdraft {
-                    overdraft.validate()?;
+                if let Some(Err(message)) = overdraft.as_ref().map(OverdraftConfig::validate) {
+                    check(true, "overdraft", &message);
                 }
             }
             LimiterConfig::Concurrency { max_concurrent } => {
-                if *max_concurrent == 0 {
-                    return Err("最大并发数不能为0".to_string());
+                check(*max_concurrent == 0, "max_concurrent", "最大并发数不能为0");
+            }
+            LimiterConfig::Gcra { period, burst } => {
+                check(*burst == 0, "burst", "突发容量不能为0");
+                if let Err(message) = Self::validate_window_size(period) {
+                    check(true, "period", &message);
                 }
             }
             LimiterConfig::Custom { name, config } => {
-                if name.is_empty() {
-                    return Err("自定义限流器名称不能为空".to_string());
-                }
-                if config.is_null() {
-                    return Err("自定义限流器配置不能为空".to_string());
-                }
+                check(name.is_empty(), "name", "自定义限流器名称不能为空");
+                check(config.is_null(), "config", "自定义限流器配置不能为空");
             }
         }
-        Ok(())
     }
 
-    /// 校验窗口大小
+    /// 校验窗口大小，规则同 [`parse_duration`](crate::factory::parse_duration)
     fn validate_window_size(window_size: &str) -> Result<(), String> {
-        // 简单校验窗口大小格式
-        if window_size.is_empty() {
-            return Err("窗口大小不能为空".to_string());
-        }
-        // TODO: 添加更详细的格式校验
-        Ok(())
+        crate::factory::parse_duration(window_size)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
     }
 }
 
 /// 透支配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OverdraftConfig {
     pub enabled: bool,
     pub max_overdraft: u64,
@@ -512,10 +1091,24 @@ impl OverdraftConfig {
 }
 
 /// 动作配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ActionConfig {
     pub on_exceed: String,
     pub ban: Option<BanConfig>,
+    /// 同一标识符在 `rejection_window` 内被该规则拒绝达到此次数后自动封禁，
+    /// 封禁时长按封禁管理器的退避策略计算
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ban_after_rejections: Option<u32>,
+    /// 统计拒绝次数的滑动窗口（如 `"60s"`），默认 60 秒
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection_window: Option<String>,
+    /// `on_exceed = "delay"` 时每次超限增加的延迟（毫秒），
+    /// 实际延迟为该值乘以 `rejection_window` 内的超限次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    /// `on_exceed = "delay"` 时的延迟上限（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
 }
 
 impl Default for ActionConfig {
@@ -523,6 +1116,10 @@ impl Default for ActionConfig {
         Self {
             on_exceed: "reject".to_string(),
             ban: None,
+            ban_after_rejections: None,
+            rejection_window: None,
+            delay_ms: None,
+            max_delay_ms: None,
         }
     }
 }
@@ -530,24 +1127,92 @@ impl Default for ActionConfig {
 impl ActionConfig {
     /// 校验动作配置
     pub fn validate(&self) -> Result<(), String> {
-        let valid_actions = ["reject", "allow", "degrade"];
+        first_error(|errors| self.collect_errors("", errors))
+    }
+
+    fn collect_errors(&self, path: &str, errors: &mut Vec<ConfigValidationError>) {
+        let valid_actions = ["reject", "allow", "degrade", "delay"];
         if !valid_actions.contains(&self.on_exceed.as_str()) {
-            return Err(format!(
-                "无效的动作: {}, 有效值: {:?}",
-                self.on_exceed, valid_actions
+            errors.push(ConfigValidationError::new(
+                join_path(path, "on_exceed"),
+                format!(
+                    "无效的动作: {}, 有效值: {:?}",
+                    self.on_exceed, valid_actions
+                ),
             ));
         }
 
-        if let Some(ban) = &self.ban {
-            ban.validate()?;
+        if let Some(Err(message)) = self.ban.as_ref().map(BanConfig::validate) {
+            errors.push(ConfigValidationError::new(join_path(path, "ban"), message));
         }
 
-        Ok(())
+        if self.on_exceed == "delay" {
+            if cfg!(not(feature = "soft-limit")) {
+                errors.push(ConfigValidationError::new(
+                    join_path(path, "on_exceed"),
+                    "delay 动作需要启用 soft-limit 特性",
+                ));
+            }
+            let (step, max) = self.delay_bounds();
+            if step.is_zero() {
+                errors.push(ConfigValidationError::new(
+                    join_path(path, "delay_ms"),
+                    "延迟不能为0",
+                ));
+            } else if max < step {
+                errors.push(ConfigValidationError::new(
+                    join_path(path, "max_delay_ms"),
+                    "延迟上限不能小于单次延迟",
+                ));
+            }
+        }
+
+        if self.ban_after_rejections == Some(0) {
+            errors.push(ConfigValidationError::new(
+                join_path(path, "ban_after_rejections"),
+                "拒绝次数阈值不能为0",
+            ));
+        }
+
+        if let Some(window) = &self.rejection_window {
+            if let Err(message) = LimiterConfig::validate_window_size(window) {
+                errors.push(ConfigValidationError::new(
+                    join_path(path, "rejection_window"),
+                    message,
+                ));
+            }
+        }
+    }
+
+    /// 软限流的单次延迟与延迟上限，未配置时使用
+    /// [`DEFAULT_SOFT_LIMIT_DELAY_MS`](crate::constants::DEFAULT_SOFT_LIMIT_DELAY_MS) 与
+    /// [`DEFAULT_SOFT_LIMIT_MAX_DELAY_MS`](crate::constants::DEFAULT_SOFT_LIMIT_MAX_DELAY_MS)
+    pub fn delay_bounds(&self) -> (std::time::Duration, std::time::Duration) {
+        let step = self
+            .delay_ms
+            .unwrap_or(crate::constants::DEFAULT_SOFT_LIMIT_DELAY_MS);
+        let max = self
+            .max_delay_ms
+            .unwrap_or(crate::constants::DEFAULT_SOFT_LIMIT_MAX_DELAY_MS.max(step));
+        (
+            std::time::Duration::from_millis(step),
+            std::time::Duration::from_millis(max),
+        )
+    }
+
+    /// 拒绝次数统计窗口，未配置时为 [`DEFAULT_REJECTION_WINDOW_SECS`](crate::constants::DEFAULT_REJECTION_WINDOW_SECS)
+    pub fn rejection_window_duration(&self) -> Result<std::time::Duration, FlowGuardError> {
+        match &self.rejection_window {
+            Some(window) => Ok(crate::factory::parse_duration(window)?),
+            None => Ok(std::time::Duration::from_secs(
+                crate::constants::DEFAULT_REJECTION_WINDOW_SECS,
+            )),
+        }
     }
 }
 
 /// 封禁配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BanConfig {
     pub threshold: u32,
     pub initial_duration: String,
@@ -622,7 +1287,9 @@ mod tests {
                 action: ActionConfig {
                     on_exceed: "reject".to_string(),
                     ban: None,
+                    ..Default::default()
                 },
+                ..Default::default()
             }],
         };
 
@@ -660,7 +1327,9 @@ mod tests {
             action: ActionConfig {
                 on_exceed: "reject".to_string(),
                 ban: None,
+                ..Default::default()
             },
+            ..Default::default()
         };
 
         let config = FlowControlConfig {
@@ -738,4 +1407,587 @@ on_exceed = "reject"
         assert_eq!(config.rules.len(), 1);
         assert!(config.validate().is_ok());
     }
+
+    #[test]
+    fn test_sliding_window_mode_parsing() {
+        let yaml = r#"
+- type: SlidingWindow
+  window_size: "1s"
+  max_requests: 100
+- type: SlidingWindow
+  window_size: "1s"
+  max_requests: 100
+  mode: Log
+"#;
+
+        let limiters: Vec<LimiterConfig> = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(
+            limiters[0],
+            LimiterConfig::SlidingWindow {
+                mode: SlidingWindowMode::Counter,
+                ..
+            }
+        ));
+        assert!(matches!(
+            limiters[1],
+            LimiterConfig::SlidingWindow {
+                mode: SlidingWindowMode::Log,
+                ..
+            }
+        ));
+    }
+
+    fn rule_with(id: &str, limiters: Vec<LimiterConfig>) -> Rule {
+        Rule {
+            id: id.to_string(),
+            name: format!("Rule {}", id),
+            priority: 100,
+            matchers: vec![Matcher::User {
+                user_ids: vec!["*".to_string()],
+            }],
+            limiters,
+            action: ActionConfig {
+                on_exceed: "reject".to_string(),
+                ban: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_all_reports_every_error() {
+        let mut custom = rule_with(
+            "custom",
+            vec![LimiterConfig::TokenBucket {
+                capacity: 10,
+                refill_rate: 1,
+            }],
+        );
+        custom.matchers.push(Matcher::Custom {
+            name: "geo".to_string(),
+            config: serde_json::json!({"country": "CN"}),
+        });
+
+        let config = FlowControlConfig {
+            version: String::new(),
+            global: GlobalConfig::default(),
+            rules: vec![
+                rule_with(
+                    "dup",
+                    vec![
+                        LimiterConfig::FixedWindow {
+                            window_size: "10x".to_string(),
+                            max_requests: 0,
+                        },
+                        LimiterConfig::TokenBucket {
+                            capacity: 0,
+                            refill_rate: 1,
+                        },
+                    ],
+                ),
+                rule_with(
+                    "dup",
+                    vec![LimiterConfig::Gcra {
+                        period: "0s".to_string(),
+                        burst: 5,
+                    }],
+                ),
+                custom,
+            ],
+        };
+
+        let report = config
+            .validate_all_with_custom_matchers(&["tenant"])
+            .unwrap_err();
+        let paths: Vec<&str> = report.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "version",
+                "rules[0].limiters[0].max_requests",
+                "rules[0].limiters[0].window_size",
+                "rules[0].limiters[1].capacity",
+                "rules[1].id",
+                "rules[1].limiters[0].period",
+                "rules[2].matchers[1].name",
+            ]
+        );
+        assert_eq!(report.errors_at("rules[1].id").count(), 1);
+
+        // 不检查自定义匹配器名称时不报告未知匹配器
+        assert_eq!(config.validate_all().unwrap_err().len(), 6);
+
+        // validate 保持兼容，错误信息包含全部问题
+        let message = config.validate().unwrap_err();
+        assert!(message.contains("版本号不能为空"));
+        assert!(message.contains("规则ID重复: dup"));
+    }
+
+    #[test]
+    fn test_validate_all_rejects_overflowing_windows() {
+        let config = FlowControlConfig {
+            version: "1.0".to_string(),
+            global: GlobalConfig::default(),
+            rules: vec![rule_with(
+                "huge",
+                vec![
+                    LimiterConfig::SlidingWindow {
+                        window_size: "999999999999999999d".to_string(),
+                        max_requests: 10,
+                        mode: SlidingWindowMode::default(),
+                    },
+                    LimiterConfig::Gcra {
+                        period: "45d".to_string(),
+                        burst: 5,
+                    },
+                ],
+            )],
+        };
+
+        let report = config.validate_all().unwrap_err();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report.errors[0].path, "rules[0].limiters[0].window_size");
+        assert!(report.errors[0].message.contains("溢出"));
+        assert_eq!(report.errors[1].path, "rules[0].limiters[1].period");
+        assert!(report.errors[1].message.contains("超过上限"));
+    }
+
+    #[test]
+    fn test_validate_all_global_errors_in_one_pass() {
+        let config = FlowControlConfig {
+            version: "1.0".to_string(),
+            global: GlobalConfig {
+                storage: "disk".to_string(),
+                cache: "disk".to_string(),
+                metrics: "statsd".to_string(),
+            },
+            rules: vec![],
+        };
+
+        let report = config.validate_all().unwrap_err();
+        let paths: Vec<&str> = report.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["global.storage", "global.cache", "global.metrics", "rules"]
+        );
+    }
+
+    #[test]
+    fn test_validate_all_accepts_valid_config() {
+        let config = FlowControlConfig {
+            rules: vec![rule_with(
+                "ok",
+                vec![LimiterConfig::SlidingWindow {
+                    window_size: "1m".to_string(),
+                    max_requests: 10,
+                    mode: SlidingWindowMode::Counter,
+                }],
+            )],
+            ..Default::default()
+        };
+
+        assert!(config.validate_all().is_ok());
+        assert!(config.validate_all_with_custom_matchers(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejection_escalation() {
+        let mut rule = rule_with(
+            "escalate",
+            vec![LimiterConfig::TokenBucket {
+                capacity: 10,
+                refill_rate: 1,
+            }],
+        );
+        rule.action.ban_after_rejections = Some(0);
+        rule.action.rejection_window = Some("10x".to_string());
+        let config = FlowControlConfig {
+            rules: vec![rule],
+            ..Default::default()
+        };
+
+        let report = config.validate_all().unwrap_err();
+        let paths: Vec<&str> = report.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "rules[0].action.ban_after_rejections",
+                "rules[0].action.rejection_window",
+            ]
+        );
+
+        let action = ActionConfig {
+            ban_after_rejections: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(
+            action.rejection_window_duration().unwrap(),
+            std::time::Duration::from_secs(crate::constants::DEFAULT_REJECTION_WINDOW_SECS)
+        );
+    }
+
+    #[test]
+    fn test_validate_method_matcher() {
+        let matcher: Matcher = serde_json::from_value(serde_json::json!({
+            "type": "Method",
+            "methods": ["POST", "put"]
+        }))
+        .unwrap();
+        assert!(matcher.validate().is_ok());
+
+        assert!(Matcher::Method { methods: vec![] }.validate().is_err());
+        assert!(Matcher::Method {
+            methods: vec!["GET /".to_string()]
+        }
+        .validate()
+        .is_err());
+        assert!(Matcher::Method {
+            methods: vec![String::new()]
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_delay_action() {
+        let action = ActionConfig {
+            on_exceed: "delay".to_string(),
+            delay_ms: Some(200),
+            max_delay_ms: Some(100),
+            ..Default::default()
+        };
+        let mut errors = Vec::new();
+        action.collect_errors("action", &mut errors);
+        let mut paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        if cfg!(not(feature = "soft-limit")) {
+            assert_eq!(paths.remove(0), "action.on_exceed");
+        }
+        assert_eq!(paths, vec!["action.max_delay_ms"]);
+
+        let action = ActionConfig {
+            on_exceed: "delay".to_string(),
+            delay_ms: Some(0),
+            ..Default::default()
+        };
+        assert!(action.validate().is_err());
+
+        // 未配置时使用默认延迟，上限不小于单次延迟
+        let action = ActionConfig {
+            on_exceed: "delay".to_string(),
+            delay_ms: Some(10_000),
+            ..Default::default()
+        };
+        let (step, max) = action.delay_bounds();
+        assert_eq!(step, std::time::Duration::from_secs(10));
+        assert_eq!(max, step);
+    }
+
+    const ROUND_TRIP_JSON: &str = r#"{
+        "version": "1.0",
+        "global": {"storage": "memory", "cache": "memory", "metrics": "prometheus"},
+        "rules": [{
+            "id": "api",
+            "name": "API",
+            "priority": 100,
+            "matchers": [
+                {"type": "User", "user_ids": ["*"]},
+                {"type": "Ip", "ip_ranges": ["10.0.0.0/8"]}
+            ],
+            "limiters": [
+                {"type": "SlidingWindow", "window_size": "1m", "max_requests": 100, "mode": "Log"},
+                {"type": "Quota", "quota_type": "count", "limit": 1000, "window": "1d",
+                 "overdraft": {"enabled": true, "max_overdraft": 50}}
+            ],
+            "action": {
+                "on_exceed": "reject",
+                "ban": {"threshold": 3, "initial_duration": "1m", "backoff_multiplier": 2.0,
+                        "max_duration": "1h", "scope": "ip"}
+            }
+        }]
+    }"#;
+
+    const ROUND_TRIP_YAML: &str = r#"
+# 与 ROUND_TRIP_JSON 等价
+version: "1.0"
+global:
+  storage: memory
+  cache: memory
+  metrics: prometheus
+rules:
+  - id: api
+    name: API
+    priority: 100
+    matchers:
+      - type: User
+        user_ids: ["*"]
+      - type: Ip
+        ip_ranges: ["10.0.0.0/8"]
+    limiters:
+      - type: SlidingWindow
+        window_size: 1m
+        max_requests: 100
+        mode: Log
+      - type: Quota
+        quota_type: count
+        limit: 1000
+        window: 1d
+        overdraft:
+          enabled: true
+          max_overdraft: 50
+    action:
+      on_exceed: reject
+      ban:
+        threshold: 3
+        initial_duration: 1m
+        backoff_multiplier: 2.0
+        max_duration: 1h
+        scope: ip
+"#;
+
+    const ROUND_TRIP_TOML: &str = r#"
+# 与 ROUND_TRIP_JSON 等价
+version = "1.0"
+
+[global]
+storage = "memory"
+cache = "memory"
+metrics = "prometheus"
+
+[[rules]]
+id = "api"
+name = "API"
+priority = 100
+
+[[rules.matchers]]
+type = "User"
+user_ids = ["*"]
+
+[[rules.matchers]]
+type = "Ip"
+ip_ranges = ["10.0.0.0/8"]
+
+[[rules.limiters]]
+type = "SlidingWindow"
+window_size = "1m"
+max_requests = 100
+mode = "Log"
+
+[[rules.limiters]]
+type = "Quota"
+quota_type = "count"
+limit = 1000
+window = "1d"
+overdraft = { enabled = true, max_overdraft = 50 }
+
+[rules.action]
+on_exceed = "reject"
+
+[rules.action.ban]
+threshold = 3
+initial_duration = "1m"
+backoff_multiplier = 2.0
+max_duration = "1h"
+scope = "ip"
+"#;
+
+    #[test]
+    fn test_config_format_detection() {
+        assert_eq!(ConfigFormat::detect(ROUND_TRIP_JSON), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::detect(ROUND_TRIP_YAML), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::detect(ROUND_TRIP_TOML), ConfigFormat::Toml);
+        assert_eq!(
+            ConfigFormat::detect("[global]\nstorage = \"memory\""),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_extension("YML"),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(ConfigFormat::from_extension("ini"), None);
+    }
+
+    #[cfg(all(feature = "yaml", feature = "toml"))]
+    #[test]
+    fn test_json_yaml_toml_round_trip() {
+        let from_json = FlowControlConfig::from_json(ROUND_TRIP_JSON).unwrap();
+        let from_yaml = FlowControlConfig::from_yaml(ROUND_TRIP_YAML).unwrap();
+        let from_toml = FlowControlConfig::from_toml(ROUND_TRIP_TOML).unwrap();
+
+        assert_eq!(from_json, from_yaml);
+        assert_eq!(from_json, from_toml);
+        assert_eq!(
+            FlowControlConfig::parse(ROUND_TRIP_TOML, None).unwrap(),
+            from_json
+        );
+
+        // 重新序列化为各格式后仍然等价
+        let yaml = serde_yaml::to_string(&from_json).unwrap();
+        let toml = toml::to_string(&from_json).unwrap();
+        assert_eq!(FlowControlConfig::from_yaml(&yaml).unwrap(), from_json);
+        assert_eq!(FlowControlConfig::from_toml(&toml).unwrap(), from_json);
+    }
+
+    #[test]
+    fn test_load_normalizes_and_validates() {
+        let json = ROUND_TRIP_JSON
+            .replace(r#""storage": "memory""#, r#""storage": " Memory ""#)
+            .replace(r#""on_exceed": "reject""#, r#""on_exceed": "REJECT""#);
+        let config = FlowControlConfig::from_json(&json).unwrap();
+        assert_eq!(config.global.storage, "memory");
+        assert_eq!(config.rules[0].action.on_exceed, "reject");
+
+        let invalid = ROUND_TRIP_JSON.replace(r#""max_requests": 100"#, r#""max_requests": 0"#);
+        let err = FlowControlConfig::from_json(&invalid).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("rules[0].limiters[0].max_requests"));
+
+        // 只解析不校验：规范化照常进行，错误留给调用方处理
+        let config = FlowControlConfig::parse_unvalidated(
+            &invalid.replace(r#""on_exceed": "reject""#, r#""on_exceed": "REJECT""#),
+            ConfigFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(config.rules[0].action.on_exceed, "reject");
+        let report = config.validate_all().unwrap_err();
+        assert_eq!(report.errors[0].path, "rules[0].limiters[0].max_requests");
+    }
+
+    #[test]
+    fn test_from_path_dispatches_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let json_path = dir.path().join("limits.json");
+        std::fs::write(&json_path, ROUND_TRIP_JSON).unwrap();
+        let from_json = FlowControlConfig::from_path(&json_path).unwrap();
+        assert_eq!(from_json.rules[0].id, "api");
+
+        #[cfg(feature = "yaml")]
+        {
+            let yaml_path = dir.path().join("limits.yml");
+            std::fs::write(&yaml_path, ROUND_TRIP_YAML).unwrap();
+            assert_eq!(FlowControlConfig::from_path(&yaml_path).unwrap(), from_json);
+        }
+
+        let ini_path = dir.path().join("limits.ini");
+        std::fs::write(&ini_path, ROUND_TRIP_JSON).unwrap();
+        assert!(FlowControlConfig::from_path(&ini_path).is_err());
+    }
+
+    fn diff_base_config() -> FlowControlConfig {
+        FlowControlConfig {
+            version: "1.0".to_string(),
+            rules: vec![
+                rule_with(
+                    "api",
+                    vec![LimiterConfig::TokenBucket {
+                        capacity: 100,
+                        refill_rate: 10,
+                    }],
+                ),
+                rule_with(
+                    "login",
+                    vec![LimiterConfig::FixedWindow {
+                        window_size: "60s".to_string(),
+                        max_requests: 5,
+                    }],
+                ),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_added_rule() {
+        let old = diff_base_config();
+        let mut new = old.clone();
+        new.rules.push(rule_with(
+            "upload",
+            vec![LimiterConfig::Concurrency { max_concurrent: 2 }],
+        ));
+
+        let record = new.create_change_record(Some(&old), ChangeSource::Api);
+        assert_eq!(record.diff.len(), 1);
+        match &record.diff[0] {
+            ConfigFieldChange::Added { path, value } => {
+                assert_eq!(path, "rules[upload]");
+                assert_eq!(value["limiters"][0]["max_concurrent"], 2);
+            }
+            other => panic!("unexpected change: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff_removed_rule() {
+        let old = diff_base_config();
+        let mut new = old.clone();
+        new.rules.retain(|rule| rule.id != "login");
+
+        let diff = new.structural_diff(&old);
+        assert_eq!(diff.len(), 1);
+        assert!(matches!(
+            &diff[0],
+            ConfigFieldChange::Removed { path, value }
+                if path == "rules[login]" && value["id"] == "login"
+        ));
+    }
+
+    #[test]
+    fn test_diff_modified_limiter_and_global() {
+        let old = diff_base_config();
+        let mut new = old.clone();
+        new.version = "1.1".to_string();
+        new.global.cache = "redis".to_string();
+        new.rules[0].limiters[0] = LimiterConfig::TokenBucket {
+            capacity: 200,
+            refill_rate: 10,
+        };
+        // 限流器种类变化时整体记录为修改
+        new.rules[1].limiters[0] = LimiterConfig::Gcra {
+            period: "1s".to_string(),
+            burst: 5,
+        };
+
+        let diff = new.structural_diff(&old);
+        let paths: Vec<&str> = diff.iter().map(ConfigFieldChange::path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "version",
+                "global.cache",
+                "rules[api].limiters[0].capacity",
+                "rules[login].limiters[0]",
+            ]
+        );
+        assert_eq!(
+            diff[2],
+            ConfigFieldChange::Modified {
+                path: "rules[api].limiters[0].capacity".to_string(),
+                old: 100.into(),
+                new: 200.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_record_summary() {
+        let old = diff_base_config();
+        let mut new = old.clone();
+        new.rules[0].action.on_exceed = "allow".to_string();
+
+        let summary = new
+            .create_change_record(Some(&old), ChangeSource::Api)
+            .summary();
+        assert!(summary.starts_with("配置 1.0 -> 1.0（来源: Api）"));
+        assert!(summary.contains(r#"~ rules[api].action.on_exceed: "reject" -> "allow""#));
+
+        let unchanged = old.create_change_record(Some(&old), ChangeSource::Poll);
+        assert!(unchanged.diff.is_empty());
+        assert!(unchanged.summary().contains("配置内容无变化"));
+
+        let initial = old.create_change_record(None, ChangeSource::Poll);
+        assert!(initial.diff.is_empty());
+        assert!(initial.summary().starts_with("初始配置 1.0"));
+    }
 }
@@ -178,7 +178,7 @@ impl ConfigSecurityValidator {
         report: &mut ConfigSecurityReport,
     ) {
         match matcher {
-            Matcher::User { user_ids } => {
+            Matcher::User { user_ids } | Matcher::UserPattern { user_ids, .. } => {
                 for user_id in user_ids {
                     Self::validate_user_id(user_id, rule_index, matcher_index, report);
                 }
@@ -209,6 +209,23 @@ impl ConfigSecurityValidator {
                     ));
                 }
             }
+            Matcher::Method { methods } => {
+                if methods.is_empty() {
+                    report.add_warning(format!(
+                        "规则[{}]匹配器[{}]的HTTP方法列表为空",
+                        rule_index, matcher_index
+                    ));
+                }
+            }
+            #[cfg(feature = "regex")]
+            Matcher::PathRegex { pattern } | Matcher::HeaderRegex { pattern, .. } => {
+                if pattern.is_empty() {
+                    report.add_warning(format!(
+                        "规则[{}]匹配器[{}]的正则表达式为空，将匹配所有请求",
+                        rule_index, matcher_index
+                    ));
+                }
+            }
             Matcher::Custom { name, .. } => {
                 if name.is_empty() {
                     report.add_warning(format!(
@@ -361,6 +378,7 @@ impl ConfigSecurityValidator {
             LimiterConfig::SlidingWindow {
                 window_size,
                 max_requests,
+                ..
             } => {
                 Self::validate_window_size(window_size, rule_index, limiter_index, report);
                 if *max_requests == 0 {
@@ -436,6 +454,21 @@ impl ConfigSecurityValidator {
                     ));
                 }
             }
+            LimiterConfig::Gcra { period, burst } => {
+                Self::validate_window_size(period, rule_index, limiter_index, report);
+                if *burst == 0 {
+                    report.add_warning(format!(
+                        "规则[{}]限流器[{}]的突发容量为0",
+                        rule_index, limiter_index
+                    ));
+                }
+                if *burst > 1_000_000 {
+                    report.add_warning(format!(
+                        "规则[{}]限流器[{}]的突发容量过大: {}",
+                        rule_index, limiter_index, burst
+                    ));
+                }
+            }
             LimiterConfig::Custom { name, config: _ } => {
                 if name.is_empty() {
                     report.add_warning(format!(
@@ -470,14 +503,10 @@ impl ConfigSecurityValidator {
         }
 
         // 检查格式
-        if !window_size.ends_with("ms")
-            && !window_size.ends_with('s')
-            && !window_size.ends_with('m')
-            && !window_size.ends_with('h')
-        {
+        if let Err(e) = crate::factory::parse_duration(window_size) {
             report.add_warning(format!(
-                "规则[{}]限流器[{}]的窗口大小格式无效: {}",
-                rule_index, limiter_index, window_size
+                "规则[{}]限流器[{}]的窗口大小格式无效: {}: {}",
+                rule_index, limiter_index, window_size, e
             ));
         }
     }
@@ -509,6 +538,7 @@ mod tests {
                     refill_rate: 10,
                 }],
                 action: Default::default(),
+                ..Default::default()
             }],
         };
 
@@ -551,6 +581,7 @@ mod tests {
                     refill_rate: 10,
                 }],
                 action: Default::default(),
+                ..Default::default()
             }],
         };
 
@@ -5,19 +5,25 @@
 //! 配置监视器
 //!
 //! 实现配置变更检测功能，支持轮询和Watch两种模式。
+//! [`IpListWatcher`] 以同样的轮询方式热加载按行分隔的 IP 范围列表。
 
-use crate::config::{ChangeSource, ConfigChangeRecord, ConfigHistory, FlowControlConfig};
+use crate::config::{
+    ChangeSource, ConfigChangeRecord, ConfigFormat, ConfigHistory, FlowControlConfig,
+};
 use crate::error::{FlowGuardError, StorageError};
+use crate::matchers::{IpRange, SharedIpList};
 use crate::storage::Storage;
 use notify::{Event, EventKind, RecursiveMode, Watcher};
+#[cfg(feature = "postgres")]
 use serde::Deserialize;
+#[cfg(feature = "postgres")]
 use sqlx::Row;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 
 /// 配置监视器回调类型
 pub type ConfigChangeCallback = Arc<
@@ -330,32 +336,17 @@ impl ConfigWatcher {
             .await
             .map_err(FlowGuardError::IoError)?;
 
-        let extension = path
+        let format = path
             .extension()
             .and_then(|ext| ext.to_str())
-            .ok_or_else(|| FlowGuardError::ConfigError("无法确定配置文件类型".to_string()))?;
+            .ok_or_else(|| FlowGuardError::ConfigError("无法确定配置文件类型".to_string()))
+            .and_then(|extension| {
+                ConfigFormat::from_extension(extension).ok_or_else(|| {
+                    FlowGuardError::ConfigError(format!("不支持的配置文件类型: {}", extension))
+                })
+            })?;
 
-        match extension {
-            "yaml" | "yml" => {
-                let config: FlowControlConfig = serde_yaml::from_str(&content)
-                    .map_err(|e| FlowGuardError::ConfigError(format!("YAML解析错误: {}", e)))?;
-                Ok(config)
-            }
-            "toml" => {
-                let config: FlowControlConfig = toml::from_str(&content)
-                    .map_err(|e| FlowGuardError::ConfigError(format!("TOML解析错误: {}", e)))?;
-                Ok(config)
-            }
-            "json" => {
-                let config: FlowControlConfig = serde_json::from_str(&content)
-                    .map_err(|e| FlowGuardError::ConfigError(format!("JSON解析错误: {}", e)))?;
-                Ok(config)
-            }
-            _ => Err(FlowGuardError::ConfigError(format!(
-                "不支持的配置文件类型: {}",
-                extension
-            ))),
-        }
+        FlowControlConfig::from_str_with_format(&content, format)
     }
 
     /// 从数据库加载配置
@@ -367,10 +358,8 @@ impl ConfigWatcher {
             .map_err(FlowGuardError::StorageError)?
             .ok_or_else(|| FlowGuardError::StorageError(StorageError::NotFound(key.to_string())))?;
 
-        let config: FlowControlConfig = serde_json::from_str(&value)
-            .map_err(|e| FlowGuardError::ConfigError(format!("JSON解析错误: {}", e)))?;
-
-        Ok(config)
+        // 数据库中的配置可以是 JSON、YAML 或 TOML，按内容推断格式
+        FlowControlConfig::parse(&value, None)
     }
 
     /// 加载当前配置（用于比较）
@@ -433,7 +422,170 @@ impl ConfigWatcher {
     }
 }
 
+// ============================================================================
+// IP 列表监视器
+// ============================================================================
+
+/// 解析按行分隔的 IP 范围列表
+///
+/// 每行一个 [`IpRange`]（单个 IP、CIDR 或 `start-end` 范围）。空行与 `#` 之后的注释被忽略，
+/// 无法解析的行记录警告后跳过，不影响其余行。
+pub fn parse_ip_list(content: &str) -> Vec<IpRange> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                return None;
+            }
+            match line.parse::<IpRange>() {
+                Ok(range) => Some(range),
+                Err(e) => {
+                    warn!("Skipping malformed IP list line {}: {:?}", index + 1, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// IP 列表监视器
+///
+/// 按轮询间隔读取文件，内容变化时解析并原子替换 [`SharedIpList`]。
+/// 读取失败时保留上一次成功加载的列表。监视器被丢弃时中止轮询任务。
+///
+/// # 示例
+/// ```rust,no_run
+/// use limiteron::config_watcher::IpListWatcher;
+/// use limiteron::matchers::MatchCondition;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let watcher = IpListWatcher::new("/etc/limiteron/denylist.txt", Duration::from_secs(5));
+///     let deny = MatchCondition::IpList(watcher.list());
+///     watcher.start().await.unwrap();
+/// }
+/// ```
+pub struct IpListWatcher {
+    /// 列表文件路径
+    path: PathBuf,
+    /// 轮询间隔
+    poll_interval: Duration,
+    /// 当前列表
+    list: SharedIpList,
+    /// 上次加载的文件内容
+    last_content: Arc<RwLock<Option<String>>>,
+    /// 轮询任务句柄
+    task: parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl IpListWatcher {
+    /// 创建新的 IP 列表监视器，初始列表为空
+    ///
+    /// # 参数
+    /// - `path`: 列表文件路径
+    /// - `poll_interval`: 轮询间隔
+    pub fn new(path: impl Into<PathBuf>, poll_interval: Duration) -> Self {
+        Self::with_list(path, poll_interval, SharedIpList::default())
+    }
+
+    /// 创建更新指定列表的 IP 列表监视器
+    pub fn with_list(
+        path: impl Into<PathBuf>,
+        poll_interval: Duration,
+        list: SharedIpList,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            poll_interval,
+            list,
+            last_content: Arc::new(RwLock::new(None)),
+            task: parking_lot::Mutex::new(None),
+        }
+    }
+
+    /// 监视器更新的列表
+    pub fn list(&self) -> SharedIpList {
+        self.list.clone()
+    }
+
+    /// 重新读取文件，内容变化时替换列表
+    ///
+    /// # 返回
+    /// - `Ok(true)`: 列表已更新
+    /// - `Ok(false)`: 文件内容未变化
+    /// - `Err(_)`: 读取文件失败，列表保持不变
+    pub async fn reload(&self) -> Result<bool, FlowGuardError> {
+        let content = tokio::fs::read_to_string(&self.path).await?;
+
+        let mut last_content = self.last_content.write().await;
+        if last_content.as_deref() == Some(content.as_str()) {
+            return Ok(false);
+        }
+
+        let ranges = parse_ip_list(&content);
+        info!(
+            "Reloaded IP list from {:?}: {} ranges",
+            self.path,
+            ranges.len()
+        );
+        self.list.store(ranges);
+        *last_content = Some(content);
+        Ok(true)
+    }
+
+    /// 启动轮询，立即加载一次
+    #[instrument(skip(self))]
+    pub async fn start(&self) -> Result<(), FlowGuardError> {
+        let mut task = self.task.lock();
+        if task.as_ref().is_some_and(|handle| !handle.is_finished()) {
+            return Err(FlowGuardError::ConfigError(
+                "IP 列表监视器已在运行".to_string(),
+            ));
+        }
+
+        let watcher = Self {
+            path: self.path.clone(),
+            poll_interval: self.poll_interval,
+            list: self.list.clone(),
+            last_content: self.last_content.clone(),
+            task: parking_lot::Mutex::new(None),
+        };
+        *task = Some(tokio::spawn(async move {
+            loop {
+                if let Err(e) = watcher.reload().await {
+                    error!("IP list reload failed: {:?}", e);
+                }
+                sleep(watcher.poll_interval).await;
+            }
+        }));
+
+        Ok(())
+    }
+
+    /// 停止轮询
+    pub async fn stop(&self) -> Result<(), FlowGuardError> {
+        if let Some(handle) = self.task.lock().take() {
+            handle.abort();
+        }
+        info!("IP list watcher stopped");
+        Ok(())
+    }
+}
+
+impl Drop for IpListWatcher {
+    fn drop(&mut self) {
+        if let Some(handle) = self.task.get_mut().take() {
+            handle.abort();
+            debug!("IP list watcher task aborted on drop");
+        }
+    }
+}
+
 /// PostgreSQL配置存储
+#[cfg(feature = "postgres")]
 #[derive(Debug, Deserialize)]
 pub struct PostgresConfigStorage {
     pub connection_string: String,
@@ -442,6 +594,7 @@ pub struct PostgresConfigStorage {
     pub value_column: String,
 }
 
+#[cfg(feature = "postgres")]
 impl PostgresConfigStorage {
     /// 验证表名和列名是否安全（白名单验证）
     fn validate_identifier(identifier: &str, field_name: &str) -> Result<(), FlowGuardError> {
@@ -538,7 +691,9 @@ mod tests {
                 action: crate::config::ActionConfig {
                     on_exceed: "reject".to_string(),
                     ban: None,
+                    ..Default::default()
                 },
+                ..Default::default()
             }],
         }
     }
@@ -637,6 +792,7 @@ mod tests {
                 operator: "test".to_string(),
             },
             changes: vec!["版本变更".to_string()],
+            diff: Vec::new(),
         };
 
         history.add_record(record.clone());
@@ -663,6 +819,7 @@ mod tests {
                     operator: "test".to_string(),
                 },
                 changes: vec![format!("变更{}", i)],
+                diff: Vec::new(),
             };
             history.add_record(record);
         }
@@ -1019,6 +1176,71 @@ on_exceed = "reject"
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_ip_list_skips_malformed_lines() {
+        let ranges = parse_ip_list(
+            "# denylist\n10.0.0.0/8\n\nnot-an-ip\n192.168.1.1 # office\n10.0.0.1/99\n2001:db8::/32\n",
+        );
+        assert_eq!(ranges.len(), 3);
+        assert!(ranges[1].contains(&"192.168.1.1".parse().unwrap()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ip_list_watcher_reloads_file() {
+        use crate::matchers::{ConditionEvaluator, MatchCondition, RequestContext};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("denylist.txt");
+        fs::write(&path, "10.0.0.0/8\n").await.unwrap();
+
+        let watcher = IpListWatcher::new(&path, Duration::from_millis(50));
+        let deny = MatchCondition::IpList(watcher.list());
+        let request = |ip: &str| RequestContext::new().with_client_ip(ip);
+
+        watcher.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(deny.evaluate(&request("10.1.2.3")));
+        assert!(!deny.evaluate(&request("192.168.1.1")));
+
+        // 更新文件，含一行无效内容
+        fs::write(&path, "192.168.0.0/16\ngarbage\n").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!deny.evaluate(&request("10.1.2.3")));
+        assert!(deny.evaluate(&request("192.168.1.1")));
+        assert_eq!(watcher.list().len(), 1);
+
+        // 文件被删除时保留上一次的列表
+        fs::remove_file(&path).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(deny.evaluate(&request("192.168.1.1")));
+
+        watcher.stop().await.unwrap();
+        assert!(watcher.reload().await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ip_list_watcher_drop_aborts_task() {
+        use crate::matchers::{ConditionEvaluator, MatchCondition, RequestContext};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("denylist.txt");
+        fs::write(&path, "10.0.0.0/8\n").await.unwrap();
+
+        let watcher = IpListWatcher::new(&path, Duration::from_millis(50));
+        let deny = MatchCondition::IpList(watcher.list());
+        let request = RequestContext::new().with_client_ip("192.168.1.1");
+
+        watcher.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(watcher.list().len(), 1);
+
+        // 丢弃监视器后不再轮询，文件变化不会生效
+        drop(watcher);
+        fs::write(&path, "192.168.0.0/16\n").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!deny.evaluate(&request));
+    }
+
     #[tokio::test]
     async fn test_double_start_fails() {
         let storage = Arc::new(MemoryStorage::new());
@@ -114,6 +114,44 @@ pub const DEFAULT_BAN_PAGINATION_LIMIT: u32 = 100;
 /// Prevents excessive memory usage when querying large ban lists.
 pub const MAX_BAN_PAGINATION_LIMIT: u32 = 1000;
 
+/// Default TTL for cached ban-check results (1 second).
+///
+/// Both positive and negative results are cached; `ban_identifier` and
+/// `unban_identifier` invalidate the affected entry immediately.
+pub const DEFAULT_BAN_CACHE_TTL_MS: u64 = 1000;
+
+/// Maximum number of cached ban-check results.
+///
+/// Expired entries are purged when the cache is full; new results are not
+/// cached while it stays full.
+pub const DEFAULT_BAN_CACHE_CAPACITY: usize = 10_000;
+
+/// Maximum number of identifiers whose last known results are kept for the
+/// `UseLastKnown` fallback strategy.
+///
+/// Entries do not expire; new identifiers are not recorded once the limit is
+/// reached.
+pub const DEFAULT_LAST_KNOWN_CAPACITY: usize = 10_000;
+
+/// Default sliding window for counting rule rejections (1 minute).
+///
+/// Used by `ActionConfig::ban_after_rejections` when `rejection_window` is unset.
+pub const DEFAULT_REJECTION_WINDOW_SECS: u64 = 60;
+
+/// Maximum number of identifiers tracked for rejection escalation.
+///
+/// Entries whose rejections have all left the window are purged when the limit
+/// is reached.
+pub const DEFAULT_REJECTION_TRACKER_CAPACITY: usize = 10_000;
+
+/// Default delay added per over-limit request for `on_exceed = "delay"` (100 ms).
+pub const DEFAULT_SOFT_LIMIT_DELAY_MS: u64 = 100;
+
+/// Default upper bound on the soft-limit delay (5 seconds).
+///
+/// Keeps callers that stay over the limit from being parked indefinitely.
+pub const DEFAULT_SOFT_LIMIT_MAX_DELAY_MS: u64 = 5_000;
+
 /// Maximum ban reason length (500 characters).
 ///
 /// Prevents overly long ban reasons that could cause display issues.
@@ -173,6 +211,33 @@ pub const DEFAULT_FIXED_WINDOW_MAX_REQUESTS: u64 = 1000;
 /// Standard concurrency limit for concurrent access control.
 pub const DEFAULT_CONCURRENCY_LIMIT: u64 = 50;
 
+/// Default maximum number of per-identifier limiters cached by a decision chain.
+///
+/// Least recently used identifiers are evicted once this bound is reached.
+pub const DEFAULT_MAX_KEYED_LIMITERS: usize = 10_000;
+
+/// Default maximum number of distinct values recorded per metrics label.
+///
+/// Further values are collapsed into a single `__other__` label.
+pub const DEFAULT_MAX_LABEL_CARDINALITY: usize = 1_000;
+
+/// Default idle TTL for limiters held by the global limiter manager (10 minutes).
+///
+/// Limiters not accessed within this duration are reclaimed by a background sweep.
+pub const DEFAULT_LIMITER_IDLE_TTL_SECS: u64 = 600;
+
+/// Default per-kind cap on limiters held by the global limiter manager.
+///
+/// Least recently used entries are evicted once this bound is reached.
+pub const DEFAULT_MAX_LIMITERS: usize = 100_000;
+
+/// Maximum length of a sanitized limiter key component.
+///
+/// Used by [`sanitize_key()`] to bound identifier-derived keys.
+///
+/// [`sanitize_key()`]: crate::limiter_manager::sanitize_key
+pub const MAX_LIMITER_KEY_LEN: usize = 128;
+
 // ============================================================================
 // Retry and Backoff Constants
 // ============================================================================
@@ -192,6 +257,11 @@ pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 10;
 /// Caps the exponential backoff to prevent excessive delays.
 pub const DEFAULT_MAX_BACKOFF_MS: u64 = 30000;
 
+/// Interval between Redis recovery probes while degraded (1 second).
+///
+/// Doubles after each failed probe, capped by `DEFAULT_MAX_BACKOFF_MS`.
+pub const DEFAULT_REDIS_RECOVERY_PROBE_INTERVAL_MS: u64 = 1000;
+
 /// Maximum spin loop iterations for exponential backoff.
 ///
 /// Used in retry logic to limit spin loop iterations.
@@ -232,6 +302,13 @@ pub const SECONDS_PER_DAY: u64 = 86400;
 /// Milliseconds per second.
 pub const MS_PER_SECOND: u64 = 1000;
 
+/// Maximum duration accepted by [`parse_duration()`] (30 days, in seconds).
+///
+/// Longer windows are almost always a typo and make counters effectively unbounded.
+///
+/// [`parse_duration()`]: crate::factory::parse_duration
+pub const MAX_DURATION_SECS: u64 = 30 * SECONDS_PER_DAY;
+
 /// Nanoseconds per millisecond.
 pub const NS_PER_MS: u64 = 1_000_000;
 
@@ -61,11 +61,14 @@
 //! ```
 
 use crate::error::FlowGuardError;
+use crate::limiters::Limiter;
 use ahash::AHashMap as HashMap;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -165,6 +168,39 @@ impl Default for LimiterStats {
     }
 }
 
+// ============================================================================
+// CustomLimiterAdapter
+// ============================================================================
+
+/// 自定义限流器工厂，按标识符键创建独立的限流器实例
+pub type CustomLimiterFactory = Arc<dyn Fn(&str) -> Arc<dyn CustomLimiter> + Send + Sync>;
+
+/// 将 [`CustomLimiter`] 适配为 [`Limiter`]，以便加入决策链
+pub struct CustomLimiterAdapter {
+    inner: Arc<dyn CustomLimiter>,
+}
+
+impl CustomLimiterAdapter {
+    /// 包装自定义限流器
+    pub fn new(inner: Arc<dyn CustomLimiter>) -> Self {
+        Self { inner }
+    }
+
+    /// 获取被包装的自定义限流器
+    pub fn inner(&self) -> &Arc<dyn CustomLimiter> {
+        &self.inner
+    }
+}
+
+impl Limiter for CustomLimiterAdapter {
+    fn allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        self.inner.allow(cost)
+    }
+}
+
 // ============================================================================
 // CustomLimiterRegistry
 // ============================================================================
@@ -172,10 +208,14 @@ impl Default for LimiterStats {
 /// 自定义限流器注册表
 ///
 /// 提供线程安全的限流器注册、查询和注销功能。
+/// 除共享实例外，还可以注册按标识符创建实例的工厂，供配置中的
+/// `LimiterConfig::Custom { name, .. }` 为每个标识符使用独立状态。
 #[derive(Clone)]
 pub struct CustomLimiterRegistry {
     /// 限流器存储（使用 RwLock 实现线程安全）
     limiters: Arc<RwLock<HashMap<String, Box<dyn CustomLimiter>>>>,
+    /// 限流器工厂（构建决策链时同步读取）
+    factories: Arc<parking_lot::RwLock<HashMap<String, CustomLimiterFactory>>>,
 }
 
 impl std::fmt::Debug for CustomLimiterRegistry {
@@ -198,9 +238,66 @@ impl CustomLimiterRegistry {
     pub fn new() -> Self {
         Self {
             limiters: Arc::new(RwLock::new(HashMap::new())),
+            factories: Arc::new(parking_lot::RwLock::new(HashMap::new())),
         }
     }
 
+    /// 注册按标识符创建实例的限流器工厂
+    ///
+    /// Governor 为引用该名称的 `LimiterConfig::Custom` 节点按标识符调用工厂，
+    /// 创建的实例与内置限流器一样按 LRU 缓存和淘汰。
+    ///
+    /// # 参数
+    /// - `name`: 工厂名称（对应配置中的 `name`）
+    /// - `factory`: 以标识符键为参数创建限流器
+    ///
+    /// # 返回
+    /// - `Ok(())`: 注册成功
+    /// - `Err(FlowGuardError::ConfigError)`: 名称已存在
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::custom_limiter::{CustomLimiter, CustomLimiterRegistry, LeakyBucketLimiter};
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let registry = CustomLimiterRegistry::new();
+    ///     registry
+    ///         .register_factory(
+    ///             "per_user_leaky".to_string(),
+    ///             Arc::new(|_key: &str| {
+    ///                 Arc::new(LeakyBucketLimiter::new(100, 10)) as Arc<dyn CustomLimiter>
+    ///             }),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///     assert!(registry.factory("per_user_leaky").is_some());
+    /// }
+    /// ```
+    pub async fn register_factory(
+        &self,
+        name: String,
+        factory: CustomLimiterFactory,
+    ) -> Result<(), FlowGuardError> {
+        let mut factories = self.factories.write();
+
+        if factories.contains_key(&name) {
+            let error_msg = format!("限流器工厂 '{}' 已存在", name);
+            warn!("{}", error_msg);
+            return Err(FlowGuardError::ConfigError(error_msg));
+        }
+
+        info!("注册自定义限流器工厂: {}", name);
+        factories.insert(name, factory);
+        Ok(())
+    }
+
+    /// 获取限流器工厂
+    pub fn factory(&self, name: &str) -> Option<CustomLimiterFactory> {
+        self.factories.read().get(name).cloned()
+    }
+
     /// 注册自定义限流器
     ///
     /// # 参数
@@ -362,6 +459,7 @@ impl CustomLimiterRegistry {
         let mut limiters = self.limiters.write().await;
         info!("清空所有自定义限流器");
         limiters.clear();
+        self.factories.write().clear();
     }
 
     /// 检查是否允许通过
@@ -452,10 +550,64 @@ pub struct LeakyBucketLimiter {
     current: Arc<AtomicU64>,
     /// 请求时间戳队列
     queue: Arc<Mutex<VecDeque<Instant>>>,
+    /// 预约队列排空的时间点（[`reserve`](Self::reserve) 使用）
+    next_free: Arc<Mutex<Option<Instant>>>,
+    /// 允许的最大预约等待时间
+    max_delay: Option<Duration>,
     /// 统计信息
     stats: Arc<Mutex<LimiterStats>>,
 }
 
+/// 漏桶预约结果
+///
+/// 可直接 `.await` 等待到预约时间点，也可先检查 [`delay`](Self::delay) 决定是否继续。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::custom_limiter::LeakyBucketLimiter;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let limiter = LeakyBucketLimiter::new(100, 10);
+///     let reservation = limiter.reserve(1).unwrap();
+///     assert!(reservation.delay().is_zero());
+///     reservation.await;
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reservation {
+    /// 预约时需要等待的时间
+    delay: Duration,
+    /// 可以继续执行的时间点
+    ready_at: Instant,
+}
+
+impl Reservation {
+    /// 预约时需要等待的时间
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// 可以继续执行的时间点
+    pub fn ready_at(&self) -> Instant {
+        self.ready_at
+    }
+
+    /// 是否无需等待
+    pub fn is_ready(&self) -> bool {
+        self.ready_at <= Instant::now()
+    }
+}
+
+impl std::future::IntoFuture for Reservation {
+    type Output = ();
+    type IntoFuture = tokio::time::Sleep;
+
+    fn into_future(self) -> Self::IntoFuture {
+        tokio::time::sleep_until(self.ready_at.into())
+    }
+}
+
 impl LeakyBucketLimiter {
     /// 创建新的漏桶限流器
     ///
@@ -469,14 +621,92 @@ impl LeakyBucketLimiter {
     ///
     /// let limiter = LeakyBucketLimiter::new(100, 10);
     /// ```
+    ///
+    /// # Panics
+    /// `leak_rate` 为 0 时 panic，需要处理该情况时使用 [`try_new`](Self::try_new)。
     pub fn new(capacity: u64, leak_rate: u64) -> Self {
-        Self {
+        match Self::try_new(capacity, leak_rate) {
+            Ok(limiter) => limiter,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// 创建新的漏桶限流器，`leak_rate` 为 0 时返回错误
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::custom_limiter::LeakyBucketLimiter;
+    ///
+    /// assert!(LeakyBucketLimiter::try_new(100, 0).is_err());
+    /// assert!(LeakyBucketLimiter::try_new(100, 10).is_ok());
+    /// ```
+    pub fn try_new(capacity: u64, leak_rate: u64) -> Result<Self, FlowGuardError> {
+        if leak_rate == 0 {
+            return Err(FlowGuardError::ConfigError(
+                "leak_rate 必须大于 0".to_string(),
+            ));
+        }
+        Ok(Self {
             capacity,
             leak_rate,
             current: Arc::new(AtomicU64::new(0)),
             queue: Arc::new(Mutex::new(VecDeque::new())),
+            next_free: Arc::new(Mutex::new(None)),
+            max_delay: None,
             stats: Arc::new(Mutex::new(LimiterStats::new())),
+        })
+    }
+
+    /// 设置 [`reserve`](Self::reserve) 允许的最大等待时间，超过时拒绝预约
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    /// 获取最大预约等待时间
+    pub fn max_delay(&self) -> Option<Duration> {
+        self.max_delay
+    }
+
+    /// 预约 `n` 个请求的流出时间
+    ///
+    /// 不拒绝请求，而是按流出速率排队并返回需要等待的时间，适用于客户端平滑发送。
+    /// 预约与 [`allow`](CustomLimiter::allow) 的桶状态相互独立。
+    ///
+    /// # 错误
+    /// 等待时间超过 [`with_max_delay`](Self::with_max_delay) 配置时返回
+    /// [`FlowGuardError::RateLimitExceeded`]，且不占用预约队列。
+    pub fn reserve(&self, n: u64) -> Result<Reservation, FlowGuardError> {
+        let now = Instant::now();
+        let mut next_free = self.next_free.lock().unwrap();
+        let start = next_free.map_or(now, |next_free| next_free.max(now));
+        let delay = start - now;
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.total_requests += 1;
+
+        if let Some(max_delay) = self.max_delay {
+            if delay > max_delay {
+                stats.rejected_requests += 1;
+                debug!("漏桶预约拒绝: 等待={:?}, 最大等待={:?}", delay, max_delay);
+                return Err(FlowGuardError::RateLimitExceeded(format!(
+                    "预约等待时间 {:?} 超过上限 {:?}",
+                    delay, max_delay
+                )));
+            }
         }
+
+        let drain = Duration::from_secs(1)
+            .div_f64(self.leak_rate as f64)
+            .saturating_mul(n.min(u32::MAX as u64) as u32);
+        *next_free = Some(start + drain);
+        stats.allowed_requests += 1;
+
+        debug!("漏桶预约: 数量={}, 等待={:?}", n, delay);
+        Ok(Reservation {
+            delay,
+            ready_at: start,
+        })
     }
 
     /// 获取桶容量
@@ -585,6 +815,9 @@ impl CustomLimiter for LeakyBucketLimiter {
 
         self.capacity = capacity;
         self.leak_rate = leak_rate;
+        if let Some(max_delay_ms) = config["max_delay_ms"].as_u64() {
+            self.max_delay = Some(Duration::from_millis(max_delay_ms));
+        }
 
         info!(
             "加载漏桶限流器配置: 容量={}, 流出速率={}",
@@ -948,6 +1181,35 @@ mod tests {
         assert_eq!(registry.count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_registry_register_factory() {
+        let registry = CustomLimiterRegistry::new();
+        let factory: CustomLimiterFactory = Arc::new(|_key: &str| {
+            Arc::new(LeakyBucketLimiter::new(2, 1)) as Arc<dyn CustomLimiter>
+        });
+
+        registry
+            .register_factory("per_key".to_string(), factory.clone())
+            .await
+            .unwrap();
+        assert!(registry
+            .register_factory("per_key".to_string(), factory)
+            .await
+            .is_err());
+
+        // 每次调用工厂得到独立状态
+        let factory = registry.factory("per_key").unwrap();
+        let first = CustomLimiterAdapter::new(factory("user:a"));
+        let second = CustomLimiterAdapter::new(factory("user:b"));
+        assert!(Limiter::allow(&first, 2).await.unwrap());
+        assert!(!Limiter::allow(&first, 1).await.unwrap());
+        assert!(Limiter::allow(&second, 2).await.unwrap());
+
+        assert!(registry.factory("missing").is_none());
+        registry.clear().await;
+        assert!(registry.factory("per_key").is_none());
+    }
+
     #[tokio::test]
     async fn test_registry_allow() {
         let registry = CustomLimiterRegistry::new();
@@ -997,6 +1259,15 @@ mod tests {
         assert_eq!(limiter.current(), 0);
     }
 
+    #[test]
+    fn test_leaky_bucket_rejects_zero_leak_rate() {
+        assert!(matches!(
+            LeakyBucketLimiter::try_new(100, 0),
+            Err(FlowGuardError::ConfigError(_))
+        ));
+        assert!(std::panic::catch_unwind(|| LeakyBucketLimiter::new(100, 0)).is_err());
+    }
+
     #[tokio::test]
     async fn test_leaky_bucket_allow() {
         let limiter = LeakyBucketLimiter::new(100, 10);
@@ -1057,6 +1328,78 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// 断言实际等待时间接近预期（允许调度误差）
+    fn assert_delay_near(actual: Duration, expected_ms: u64) {
+        let expected = Duration::from_millis(expected_ms);
+        assert!(
+            actual <= expected && actual + Duration::from_millis(20) >= expected,
+            "delay {:?}, expected {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_leaky_bucket_reserve_follows_drain_rate() {
+        let limiter = LeakyBucketLimiter::new(100, 10); // 每 100ms 流出一个
+
+        assert!(limiter.reserve(1).unwrap().delay().is_zero());
+        assert_delay_near(limiter.reserve(1).unwrap().delay(), 100);
+        assert_delay_near(limiter.reserve(5).unwrap().delay(), 200);
+        // 前一次预约了 5 个，占用 500ms
+        assert_delay_near(limiter.reserve(1).unwrap().delay(), 700);
+
+        let stats = limiter.stats();
+        assert_eq!(stats.total_requests, 4);
+        assert_eq!(stats.allowed_requests, 4);
+    }
+
+    #[test]
+    fn test_leaky_bucket_reserve_max_delay() {
+        let limiter = LeakyBucketLimiter::new(100, 10).with_max_delay(Duration::from_millis(250));
+
+        for _ in 0..3 {
+            assert!(limiter.reserve(1).is_ok());
+        }
+        // 第四个需要等待约 300ms，超过上限
+        assert!(matches!(
+            limiter.reserve(1),
+            Err(FlowGuardError::RateLimitExceeded(_))
+        ));
+        // 被拒绝的预约不占用队列
+        assert!(matches!(
+            limiter.reserve(1),
+            Err(FlowGuardError::RateLimitExceeded(_))
+        ));
+        assert_eq!(limiter.stats().rejected_requests, 2);
+    }
+
+    #[test]
+    fn test_leaky_bucket_reserve_max_delay_from_config() {
+        let mut limiter = LeakyBucketLimiter::new(100, 10);
+        limiter
+            .load_config(serde_json::json!({
+                "capacity": 100,
+                "leak_rate": 10,
+                "max_delay_ms": 150
+            }))
+            .unwrap();
+        assert_eq!(limiter.max_delay(), Some(Duration::from_millis(150)));
+    }
+
+    #[tokio::test]
+    async fn test_leaky_bucket_reservation_await() {
+        let limiter = LeakyBucketLimiter::new(100, 20); // 每 50ms 流出一个
+        limiter.reserve(1).unwrap().await;
+
+        let reservation = limiter.reserve(1).unwrap();
+        assert!(!reservation.is_ready());
+        let started = Instant::now();
+        reservation.await;
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert!(reservation.is_ready());
+    }
+
     // ==================== TokenBucketLimiter 测试 ====================
 
     #[tokio::test]
@@ -13,16 +13,52 @@
 //! - 优先级排序：按优先级顺序执行限流器
 //! - 决策聚合：聚合所有限流器的决策结果
 //! - 可扩展：易于添加新的限流器类型
-
-use crate::error::{Decision, FlowGuardError};
-use crate::limiters::Limiter;
+//! - 按标识符隔离：节点可为每个标识符创建独立的限流器，LRU 淘汰
+//! - 节点组合：一组节点按 AND / OR 合并为一个节点，组内同样短路
+
+use crate::constants::DEFAULT_MAX_KEYED_LIMITERS;
+use crate::error::{Decision, FlowGuardError, RejectReason, Rejection};
+use crate::limiters::{Limiter, LimiterSnapshot, RateLimitDecision};
+#[cfg(feature = "monitoring")]
+use crate::telemetry::Metrics;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, info, trace, warn};
 
 // ============================================================================
 // 决策链节点
 // ============================================================================
 
+/// 限流器工厂，以标识符键为参数为每个标识符创建独立的限流器实例
+pub type LimiterFactory = Arc<dyn Fn(&str) -> Arc<dyn Limiter> + Send + Sync>;
+
+/// 按 (节点ID, 标识符) 缓存的限流器
+type KeyedLimiters = LruCache<(String, String), Arc<dyn Limiter>>;
+
+/// 节点组的合并方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CombineOp {
+    /// 所有节点都允许时才允许，遇到首个拒绝即停止
+    And,
+    /// 任一节点允许即允许，遇到首个允许即停止
+    Or,
+}
+
+impl CombineOp {
+    /// 运算符名称（`AND` / `OR`）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CombineOp::And => "AND",
+            CombineOp::Or => "OR",
+        }
+    }
+}
+
 /// 决策链节点
 ///
 /// 责任链中的单个节点，包含一个限流器和相关配置。
@@ -42,6 +78,10 @@ pub struct DecisionNode {
     pub short_circuit: bool,
     /// 成本（每次请求消耗的令牌数）
     pub cost: u64,
+    /// 限流器工厂（设置后按标识符创建独立限流器）
+    pub limiter_factory: Option<LimiterFactory>,
+    /// 限流器类型（用作指标标签，如 `token_bucket`）
+    pub limiter_type: String,
 }
 
 impl DecisionNode {
@@ -76,6 +116,99 @@ impl DecisionNode {
             enabled: true,
             short_circuit: true,
             cost: 1,
+            limiter_factory: None,
+            limiter_type: "unknown".to_string(),
+        }
+    }
+
+    /// 创建按标识符隔离的决策节点
+    ///
+    /// 通过 [`DecisionChain::check_keyed`] 检查时，每个标识符使用工厂创建的独立限流器；
+    /// 通过 [`DecisionChain::check`] 检查时，使用以空键创建的共享 `limiter`。
+    ///
+    /// 决策链最多缓存 [`DEFAULT_MAX_KEYED_LIMITERS`] 个标识符的限流器（可通过
+    /// [`DecisionChain::with_max_keyed_entries`] 或 `Governor::set_max_keyed_limiters` 调整），
+    /// 超出时淘汰最久未使用的标识符，该标识符再次访问时以新建的限流器从满额度开始。
+    /// 上限过小时，轮换大量标识符的调用方可以把其他调用方的限流状态挤出并使其重置。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::decision_chain::DecisionNode;
+    /// use limiteron::limiters::{Limiter, TokenBucketLimiter};
+    /// use std::sync::Arc;
+    ///
+    /// let node = DecisionNode::keyed(
+    ///     "node1".to_string(),
+    ///     "Per-user Token Bucket".to_string(),
+    ///     Arc::new(|_key: &str| Arc::new(TokenBucketLimiter::new(100, 10)) as Arc<dyn Limiter>),
+    ///     100,
+    /// );
+    /// ```
+    pub fn keyed(id: String, name: String, factory: LimiterFactory, priority: u16) -> Self {
+        let limiter = factory("");
+        Self {
+            limiter_factory: Some(factory),
+            ..Self::new(id, name, limiter, priority)
+        }
+    }
+
+    /// 创建组合节点
+    ///
+    /// 组内启用的节点按优先级顺序以 `op` 合并求值，合并结果作为本节点的决策参与决策链。
+    /// 求值会短路：`And` 在首个拒绝处停止，`Or` 在首个允许处停止，后续节点的限流器状态
+    /// 不会被修改。组内节点各自使用自己的 `cost`；组内有按标识符隔离的节点时，
+    /// 组合节点同样按标识符隔离。空组总是允许。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::decision_chain::{CombineOp, DecisionNode};
+    /// use limiteron::limiters::TokenBucketLimiter;
+    /// use std::sync::Arc;
+    ///
+    /// let per_second = DecisionNode::new(
+    ///     "per_second".to_string(),
+    ///     "Per Second".to_string(),
+    ///     Arc::new(TokenBucketLimiter::new(10, 10)),
+    ///     100,
+    /// );
+    /// let burst = DecisionNode::new(
+    ///     "burst".to_string(),
+    ///     "Burst".to_string(),
+    ///     Arc::new(TokenBucketLimiter::new(50, 1)),
+    ///     50,
+    /// );
+    /// let group = DecisionNode::group(
+    ///     "group1".to_string(),
+    ///     "Per Second OR Burst".to_string(),
+    ///     vec![per_second, burst],
+    ///     CombineOp::Or,
+    ///     100,
+    /// );
+    /// ```
+    pub fn group(
+        id: String,
+        name: String,
+        nodes: Vec<DecisionNode>,
+        op: CombineOp,
+        priority: u16,
+    ) -> Self {
+        let mut members: Vec<DecisionNode> = nodes.into_iter().filter(|n| n.enabled).collect();
+        members.sort_by_key(|n| std::cmp::Reverse(n.priority));
+
+        let limiter: Arc<dyn Limiter> = Arc::new(GroupLimiter::new(&members, op, None));
+        let limiter_factory = if members.iter().any(|n| n.limiter_factory.is_some()) {
+            let members = Arc::new(members);
+            Some(Arc::new(move |key: &str| {
+                Arc::new(GroupLimiter::new(&members, op, Some(key))) as Arc<dyn Limiter>
+            }) as LimiterFactory)
+        } else {
+            None
+        };
+
+        Self {
+            limiter_factory,
+            limiter_type: "group".to_string(),
+            ..Self::new(id, name, limiter, priority)
         }
     }
 
@@ -106,15 +239,38 @@ impl DecisionNode {
         self
     }
 
+    /// 设置限流器类型
+    ///
+    /// # 参数
+    /// - `limiter_type`: 限流器类型，用作指标标签
+    pub fn with_limiter_type(mut self, limiter_type: impl Into<String>) -> Self {
+        self.limiter_type = limiter_type.into();
+        self
+    }
+
+    /// 根据限流器类型得出拒绝原因
+    fn reject_reason(&self) -> RejectReason {
+        match self.limiter_type.as_str() {
+            "quota" => RejectReason::Quota,
+            "concurrency" => RejectReason::Concurrency,
+            _ => RejectReason::RateLimit,
+        }
+    }
+
     /// 执行限流检查
     ///
     /// # 返回
-    /// - `Ok(allowed)`: 是否允许
+    /// - `Ok(decision)`: 限流详细决策
     /// - `Err(_)`: 错误
-    async fn check(&self) -> Result<bool, FlowGuardError> {
+    async fn check(&self, limiter: &dyn Limiter) -> Result<RateLimitDecision, FlowGuardError> {
         if !self.enabled {
             debug!("DecisionNode {} is disabled, skipping", self.id);
-            return Ok(true);
+            return Ok(RateLimitDecision {
+                allowed: true,
+                remaining: 0,
+                limit: 0,
+                retry_after: None,
+            });
         }
 
         trace!(
@@ -122,7 +278,132 @@ impl DecisionNode {
             self.name,
             self.cost
         );
-        self.limiter.allow(self.cost).await
+        limiter.allow_detailed(self.cost).await
+    }
+}
+
+/// 组合节点使用的限流器，按 [`CombineOp`] 依次检查组内节点
+struct GroupLimiter {
+    /// 组内节点的限流器与成本（按优先级排序）
+    members: Vec<(Arc<dyn Limiter>, u64)>,
+    /// 合并方式
+    op: CombineOp,
+}
+
+impl GroupLimiter {
+    /// 以组内节点创建组合限流器，`key` 不为空时按标识符节点使用工厂创建的限流器
+    fn new(nodes: &[DecisionNode], op: CombineOp, key: Option<&str>) -> Self {
+        let members = nodes
+            .iter()
+            .map(|node| {
+                let limiter = match (&node.limiter_factory, key) {
+                    (Some(factory), Some(key)) => factory(key),
+                    _ => node.limiter.clone(),
+                };
+                (limiter, node.cost)
+            })
+            .collect();
+        Self { members, op }
+    }
+}
+
+impl Limiter for GroupLimiter {
+    fn allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move { Ok(self.allow_detailed(cost).await?.allowed) })
+    }
+
+    fn would_allow(
+        &self,
+        _cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            for (limiter, cost) in &self.members {
+                let allowed = limiter.would_allow(*cost).await?;
+                match self.op {
+                    CombineOp::And if !allowed => return Ok(false),
+                    CombineOp::Or if allowed => return Ok(true),
+                    _ => {}
+                }
+            }
+            Ok(self.op == CombineOp::And || self.members.is_empty())
+        })
+    }
+
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            for (limiter, _) in &self.members {
+                limiter.reset().await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// 组内节点使用各自的成本，忽略 `cost`
+    ///
+    /// `And` 允许时返回剩余额度最少的决策，拒绝时返回首个拒绝的决策；
+    /// `Or` 允许时返回首个允许的决策，全部拒绝时返回重试时间最短的决策。
+    fn allow_detailed(
+        &self,
+        _cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<RateLimitDecision, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let unlimited = RateLimitDecision {
+                allowed: true,
+                remaining: 0,
+                limit: 0,
+                retry_after: None,
+            };
+
+            match self.op {
+                CombineOp::And => {
+                    let mut tightest = None;
+                    for (limiter, cost) in &self.members {
+                        let decision = limiter.allow_detailed(*cost).await?;
+                        if !decision.allowed {
+                            return Ok(decision);
+                        }
+                        tightest = tighter_limits(tightest, decision);
+                    }
+                    Ok(tightest.unwrap_or(unlimited))
+                }
+                CombineOp::Or => {
+                    let mut soonest: Option<RateLimitDecision> = None;
+                    for (limiter, cost) in &self.members {
+                        let decision = limiter.allow_detailed(*cost).await?;
+                        if decision.allowed {
+                            return Ok(decision);
+                        }
+                        soonest = match soonest {
+                            Some(current)
+                                if current.retry_after.unwrap_or(Duration::MAX)
+                                    <= decision.retry_after.unwrap_or(Duration::MAX) =>
+                            {
+                                Some(current)
+                            }
+                            _ => Some(decision),
+                        };
+                    }
+                    Ok(soonest.unwrap_or(unlimited))
+                }
+            }
+        })
+    }
+}
+
+/// 在两个详细决策中取剩余额度更少的一个，忽略不带额度信息的决策
+pub(crate) fn tighter_limits(
+    current: Option<RateLimitDecision>,
+    next: RateLimitDecision,
+) -> Option<RateLimitDecision> {
+    if next.limit == 0 {
+        return current;
+    }
+    match current {
+        Some(current) if current.remaining <= next.remaining => Some(current),
+        _ => Some(next),
     }
 }
 
@@ -139,10 +420,28 @@ pub struct DecisionChain {
     nodes: Vec<DecisionNode>,
     /// 统计信息
     stats: Arc<std::sync::RwLock<ChainStats>>,
+    /// 按标识符隔离的限流器缓存
+    keyed_limiters: Arc<Mutex<KeyedLimiters>>,
+    /// 所属规则ID与监控指标
+    #[cfg(feature = "monitoring")]
+    metrics: Option<(String, Arc<Metrics>)>,
+}
+
+/// 节点限流器的状态快照
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct NodeSnapshot {
+    /// 节点ID
+    pub node_id: String,
+    /// 标识符，共享限流器的节点为 `None`
+    pub key: Option<String>,
+    /// 状态快照
+    pub snapshot: LimiterSnapshot,
 }
 
 /// 决策链统计信息
-#[derive(Debug, Clone, Default)]
+///
+/// 所有字段均为单调递增的计数器，可通过 [`DecisionChain::import_stats`] 跨重启累加。
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ChainStats {
     /// 总检查次数
     pub total_checks: u64,
@@ -154,6 +453,26 @@ pub struct ChainStats {
     pub node_rejections: Vec<(String, u64)>,
 }
 
+impl ChainStats {
+    /// 累加另一份统计，节点拒绝次数按节点 ID 合并
+    pub fn merge(&mut self, other: &ChainStats) {
+        self.total_checks = self.total_checks.saturating_add(other.total_checks);
+        self.allowed_count = self.allowed_count.saturating_add(other.allowed_count);
+        self.rejected_count = self.rejected_count.saturating_add(other.rejected_count);
+        for (node_id, count) in &other.node_rejections {
+            if let Some(pos) = self
+                .node_rejections
+                .iter()
+                .position(|(id, _)| id == node_id)
+            {
+                self.node_rejections[pos].1 = self.node_rejections[pos].1.saturating_add(*count);
+            } else {
+                self.node_rejections.push((node_id.clone(), *count));
+            }
+        }
+    }
+}
+
 impl DecisionChain {
     /// 创建新的决策链
     ///
@@ -180,6 +499,11 @@ impl DecisionChain {
         let mut chain = Self {
             nodes: Vec::new(),
             stats: Arc::new(std::sync::RwLock::new(ChainStats::default())),
+            keyed_limiters: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(DEFAULT_MAX_KEYED_LIMITERS).unwrap_or(NonZeroUsize::MIN),
+            ))),
+            #[cfg(feature = "monitoring")]
+            metrics: None,
         };
 
         for node in nodes {
@@ -189,6 +513,102 @@ impl DecisionChain {
         chain
     }
 
+    /// 设置按标识符缓存的限流器数量上限
+    ///
+    /// 超出上限时淘汰最久未使用的标识符，已缓存的限流器会被清空。
+    ///
+    /// # 参数
+    /// - `max_entries`: 最大条目数（至少为 1）
+    pub fn with_max_keyed_entries(mut self, max_entries: usize) -> Self {
+        self.keyed_limiters = Arc::new(Mutex::new(LruCache::new(
+            NonZeroUsize::new(max_entries).unwrap_or(NonZeroUsize::MIN),
+        )));
+        self
+    }
+
+    /// 设置监控指标，按 `rule_id` 与节点的限流器类型记录每个节点的决策
+    ///
+    /// # 参数
+    /// - `rule_id`: 规则ID
+    /// - `metrics`: 监控指标
+    #[cfg(feature = "monitoring")]
+    pub fn with_metrics(mut self, rule_id: impl Into<String>, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some((rule_id.into(), metrics));
+        self
+    }
+
+    /// 记录节点决策指标
+    fn record_decision(&self, node: &DecisionNode, outcome: &str) {
+        #[cfg(feature = "monitoring")]
+        if let Some((rule_id, metrics)) = &self.metrics {
+            metrics.record_decision(rule_id, &node.limiter_type, outcome);
+        }
+        #[cfg(not(feature = "monitoring"))]
+        let _ = (node, outcome);
+    }
+
+    /// 调整按标识符缓存的限流器数量上限，保留已缓存的限流器
+    ///
+    /// 缩小上限时立即淘汰最久未使用的标识符。
+    ///
+    /// # 参数
+    /// - `max_entries`: 最大条目数（至少为 1）
+    pub fn set_max_keyed_entries(&self, max_entries: usize) {
+        self.keyed_limiters
+            .lock()
+            .resize(NonZeroUsize::new(max_entries).unwrap_or(NonZeroUsize::MIN));
+    }
+
+    /// 获取当前缓存的按标识符限流器数量
+    pub fn keyed_limiter_count(&self) -> usize {
+        self.keyed_limiters.lock().len()
+    }
+
+    /// 汇总各节点限流器的状态快照，不消费额度
+    ///
+    /// 按标识符隔离的节点返回每个已缓存标识符的快照，其余节点返回共享限流器的快照；
+    /// 不支持观测的限流器被跳过。结果按节点ID与标识符排序。
+    pub fn snapshots(&self) -> Vec<NodeSnapshot> {
+        let mut snapshots: Vec<NodeSnapshot> = self
+            .nodes
+            .iter()
+            .filter(|node| node.limiter_factory.is_none())
+            .filter_map(|node| {
+                Some(NodeSnapshot {
+                    node_id: node.id.clone(),
+                    key: None,
+                    snapshot: node.limiter.snapshot()?,
+                })
+            })
+            .collect();
+
+        for ((node_id, key), limiter) in self.keyed_limiters.lock().iter() {
+            if let Some(snapshot) = limiter.snapshot() {
+                snapshots.push(NodeSnapshot {
+                    node_id: node_id.clone(),
+                    key: Some(key.clone()),
+                    snapshot,
+                });
+            }
+        }
+
+        snapshots.sort_by(|a, b| (&a.node_id, &a.key).cmp(&(&b.node_id, &b.key)));
+        snapshots
+    }
+
+    /// 获取节点在给定标识符下使用的限流器
+    fn limiter_for(&self, node: &DecisionNode, key: Option<&str>) -> Arc<dyn Limiter> {
+        match (&node.limiter_factory, key) {
+            (Some(factory), Some(key)) => {
+                let mut limiters = self.keyed_limiters.lock();
+                limiters
+                    .get_or_insert((node.id.clone(), key.to_string()), || factory(key))
+                    .clone()
+            }
+            _ => node.limiter.clone(),
+        }
+    }
+
     /// 添加节点
     ///
     /// # 参数
@@ -235,6 +655,32 @@ impl DecisionChain {
     /// }
     /// ```
     pub async fn check(&self) -> Result<Decision, FlowGuardError> {
+        self.check_with_limits(None)
+            .await
+            .map(|(decision, _)| decision)
+    }
+
+    /// 按标识符执行决策链检查
+    ///
+    /// 与 [`check`](Self::check) 相同，但使用 [`DecisionNode::keyed`] 创建的节点会为
+    /// 每个 `key` 使用独立的限流器，不同标识符之间互不影响。
+    ///
+    /// # 参数
+    /// - `key`: 标识符键（如 `Identifier::key()`）
+    pub async fn check_keyed(&self, key: &str) -> Result<Decision, FlowGuardError> {
+        self.check_with_limits(Some(key))
+            .await
+            .map(|(decision, _)| decision)
+    }
+
+    /// 执行决策链检查，并返回最严格节点的限流详细决策
+    ///
+    /// 拒绝时为首个拒绝节点的详细决策；允许时为剩余额度最少的节点。
+    /// 没有节点提供额度信息（`limit` 为 0）时返回 `None`。
+    pub async fn check_with_limits(
+        &self,
+        key: Option<&str>,
+    ) -> Result<(Decision, Option<RateLimitDecision>), FlowGuardError> {
         {
             let mut stats = self.stats.write().unwrap();
             stats.total_checks += 1;
@@ -246,6 +692,8 @@ impl DecisionChain {
         );
 
         let mut rejected_reason = None;
+        let mut rejected_limits = None;
+        let mut tightest = None;
 
         // 按优先级顺序检查每个节点
         for node in &self.nodes {
@@ -256,14 +704,18 @@ impl DecisionChain {
 
             trace!("Checking node: {}", node.name);
 
-            match node.check().await {
-                Ok(true) => {
+            let limiter = self.limiter_for(node, key);
+            match node.check(limiter.as_ref()).await {
+                Ok(limits) if limits.allowed => {
                     trace!("Node {} allowed", node.name);
+                    self.record_decision(node, "allowed");
+                    tightest = tighter_limits(tightest, limits);
                     // 继续检查下一个节点
                 }
-                Ok(false) => {
+                Ok(limits) => {
                     // 节点拒绝
                     warn!("Node {} rejected request", node.name);
+                    self.record_decision(node, "rejected");
 
                     {
                         let mut stats = self.stats.write().unwrap();
@@ -283,27 +735,34 @@ impl DecisionChain {
 
                     // 记录拒绝原因（如果是第一次拒绝）
                     if rejected_reason.is_none() {
-                        rejected_reason =
-                            Some(format!("Rejected by {}: rate limit exceeded", node.name));
+                        rejected_reason = Some(Rejection::new(
+                            node.reject_reason(),
+                            format!("Rejected by {}: rate limit exceeded", node.name),
+                        ));
+                        rejected_limits = Some(limits);
                     }
 
                     // 如果启用了短路，立即返回
                     if node.short_circuit {
                         info!("Decision chain short-circuited by node: {}", node.name);
-                        return Ok(Decision::Rejected(rejected_reason.unwrap()));
+                        return Ok((
+                            Decision::Rejected(rejected_reason.unwrap()),
+                            rejected_limits,
+                        ));
                     }
                 }
                 Err(e) => {
                     // 发生错误
                     warn!("Node {} check failed: {:?}", node.name, e);
+                    self.record_decision(node, "error");
                     return Err(e);
                 }
             }
         }
 
         // 如果有任何节点拒绝，返回拒绝
-        if let Some(reason) = rejected_reason {
-            return Ok(Decision::Rejected(reason));
+        if let Some(rejection) = rejected_reason {
+            return Ok((Decision::Rejected(rejection), rejected_limits));
         }
 
         // 所有节点都允许
@@ -313,7 +772,7 @@ impl DecisionChain {
         }
 
         debug!("Decision chain: all nodes allowed");
-        Ok(Decision::Allowed(None))
+        Ok((Decision::Allowed(None), tightest))
     }
 
     /// 执行完整检查（不短路）
@@ -336,6 +795,7 @@ impl DecisionChain {
         );
 
         let mut rejection_reasons = Vec::new();
+        let mut first_reason = None;
 
         // 检查所有节点
         for node in &self.nodes {
@@ -346,13 +806,16 @@ impl DecisionChain {
 
             trace!("Checking node: {}", node.name);
 
-            match node.check().await {
-                Ok(true) => {
+            match node.check(node.limiter.as_ref()).await {
+                Ok(limits) if limits.allowed => {
                     trace!("Node {} allowed", node.name);
+                    self.record_decision(node, "allowed");
                 }
-                Ok(false) => {
+                Ok(_) => {
                     warn!("Node {} rejected request", node.name);
+                    self.record_decision(node, "rejected");
                     rejection_reasons.push(format!("{}: rate limit exceeded", node.name));
+                    first_reason.get_or_insert_with(|| node.reject_reason());
 
                     // 更新统计
                     {
@@ -372,6 +835,7 @@ impl DecisionChain {
                 }
                 Err(e) => {
                     warn!("Node {} check failed: {:?}", node.name, e);
+                    self.record_decision(node, "error");
                     return Err(e);
                 }
             }
@@ -389,7 +853,10 @@ impl DecisionChain {
         } else {
             let reason = rejection_reasons.join("; ");
             info!("Decision chain rejected: {}", reason);
-            Ok(Decision::Rejected(reason))
+            Ok(Decision::rejected(
+                first_reason.unwrap_or(RejectReason::RateLimit),
+                reason,
+            ))
         }
     }
 
@@ -404,6 +871,13 @@ impl DecisionChain {
         *stats = ChainStats::default();
     }
 
+    /// 累加导入统计信息
+    ///
+    /// 计数器在当前值的基础上累加，不会回退；节点拒绝次数按节点 ID 合并。
+    pub fn import_stats(&self, imported: &ChainStats) {
+        self.stats.write().unwrap().merge(imported);
+    }
+
     /// 获取节点数量
     pub fn node_count(&self) -> usize {
         self.nodes.len()
@@ -497,6 +971,28 @@ impl DecisionChainBuilder {
         self
     }
 
+    /// 添加组合节点
+    ///
+    /// 组内节点以 `op` 合并为一个节点（见 [`DecisionNode::group`]），节点ID为
+    /// `group-<序号>`，优先级取组内节点的最高优先级。
+    ///
+    /// # 参数
+    /// - `nodes`: 组内节点
+    /// - `op`: 合并方式
+    pub fn add_group(self, nodes: Vec<DecisionNode>, op: CombineOp) -> Self {
+        let id = format!("group-{}", self.nodes.len());
+        let name = format!(
+            "({})",
+            nodes
+                .iter()
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>()
+                .join(&format!(" {} ", op.as_str()))
+        );
+        let priority = nodes.iter().map(|n| n.priority).max().unwrap_or(0);
+        self.add_node(DecisionNode::group(id, name, nodes, op, priority))
+    }
+
     /// 构建决策链
     ///
     /// # 返回
@@ -522,9 +1018,6 @@ mod tests {
     use crate::limiters::{
         ConcurrencyLimiter, FixedWindowLimiter, SlidingWindowLimiter, TokenBucketLimiter,
     };
-    use std::future::Future;
-    use std::pin::Pin;
-    use std::time::Duration;
 
     // Helper structs for testing
     struct MockLimiter {
@@ -673,6 +1166,39 @@ mod tests {
         assert!(matches!(decision, Decision::Rejected(_)));
     }
 
+    #[tokio::test]
+    async fn test_decision_chain_check_with_limits() {
+        let node1 = DecisionNode::new(
+            "node1".to_string(),
+            "Sliding Window".to_string(),
+            Arc::new(SlidingWindowLimiter::new(Duration::from_secs(60), 10)),
+            100,
+        );
+        let node2 = DecisionNode::new(
+            "node2".to_string(),
+            "Sliding Window".to_string(),
+            Arc::new(SlidingWindowLimiter::new(Duration::from_secs(60), 2)),
+            50,
+        );
+        let chain = DecisionChain::new(vec![node1, node2]);
+
+        // 允许时返回剩余额度最少的节点
+        let (decision, limits) = chain.check_with_limits(None).await.unwrap();
+        assert_eq!(decision, Decision::Allowed(None));
+        let limits = limits.unwrap();
+        assert_eq!((limits.limit, limits.remaining), (2, 1));
+
+        chain.check_with_limits(None).await.unwrap();
+
+        // 拒绝时返回拒绝节点的详细决策
+        let (decision, limits) = chain.check_with_limits(None).await.unwrap();
+        assert!(matches!(decision, Decision::Rejected(_)));
+        let limits = limits.unwrap();
+        assert!(!limits.allowed);
+        assert_eq!((limits.limit, limits.remaining), (2, 0));
+        assert!(limits.retry_after.is_some());
+    }
+
     #[tokio::test]
     async fn test_decision_chain_priority() {
         let limiter1 = Arc::new(TokenBucketLimiter::new(10, 1));
@@ -705,8 +1231,9 @@ mod tests {
         assert!(matches!(decision, Decision::Rejected(_)));
 
         // 验证拒绝原因来自node2
-        if let Decision::Rejected(reason) = decision {
-            assert!(reason.contains("High Priority"));
+        assert_eq!(decision.reason(), Some(&RejectReason::RateLimit));
+        if let Decision::Rejected(rejection) = decision {
+            assert!(rejection.message.contains("High Priority"));
         }
     }
 
@@ -796,9 +1323,9 @@ mod tests {
 
         // 第4个请求应该检查所有节点
         let decision = chain.check_all().await.unwrap();
-        if let Decision::Rejected(reason) = decision {
+        if let Decision::Rejected(rejection) = decision {
             // 应该包含两个节点的拒绝原因
-            assert!(reason.contains("First Node"));
+            assert!(rejection.message.contains("First Node"));
         }
     }
 
@@ -970,6 +1497,144 @@ mod tests {
         ); // Increased
     }
 
+    // ==================== 组合节点测试 ====================
+
+    fn mock_node(id: &str, limiter: Arc<dyn Limiter>, priority: u16) -> DecisionNode {
+        DecisionNode::new(id.to_string(), id.to_string(), limiter, priority)
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_and_group() {
+        let first = Arc::new(MockLimiter::new(true));
+        let spy = Arc::new(SpyLimiter::new());
+        let calls = spy.calls.clone();
+
+        let chain = DecisionChainBuilder::new()
+            .add_group(
+                vec![
+                    mock_node("first", first.clone(), 100),
+                    mock_node("spy", spy, 50),
+                ],
+                CombineOp::And,
+            )
+            .build();
+
+        assert_eq!(chain.node_count(), 1);
+        assert_eq!(chain.check().await.unwrap(), Decision::Allowed(None));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // 首个节点拒绝后不再检查后续节点
+        first.set_allowed(false);
+        assert!(matches!(
+            chain.check().await.unwrap(),
+            Decision::Rejected(_)
+        ));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let stats = chain.stats();
+        assert_eq!(stats.node_rejections, vec![("group-0".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_group_would_allow() {
+        use crate::limiters::TokenBucketLimiter;
+
+        let empty = Arc::new(TokenBucketLimiter::new(1, 0));
+        assert!(empty.allow(1).await.unwrap());
+        let nodes = || {
+            vec![
+                mock_node("empty", empty.clone(), 100),
+                mock_node("full", Arc::new(TokenBucketLimiter::new(1, 0)), 50),
+            ]
+        };
+
+        let and = DecisionNode::group("g".into(), "g".into(), nodes(), CombineOp::And, 0);
+        let or = DecisionNode::group("g".into(), "g".into(), nodes(), CombineOp::Or, 0);
+        assert!(!and.limiter.would_allow(1).await.unwrap());
+        assert!(or.limiter.would_allow(1).await.unwrap());
+        // 只读检查不消费组内的令牌
+        assert!(or.limiter.allow(1).await.unwrap());
+        assert!(!or.limiter.would_allow(1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_or_group() {
+        let first = Arc::new(MockLimiter::new(true));
+        let second = Arc::new(MockLimiter::new(false));
+        let spy = Arc::new(SpyLimiter::new());
+        let calls = spy.calls.clone();
+
+        let group = DecisionNode::group(
+            "either".to_string(),
+            "Either".to_string(),
+            vec![
+                mock_node("first", first.clone(), 100),
+                mock_node("second", second.clone(), 50),
+                mock_node("spy", spy, 10),
+            ],
+            CombineOp::Or,
+            100,
+        );
+        let chain = DecisionChain::new(vec![group]);
+
+        // 首个节点允许后不再检查后续节点
+        assert_eq!(chain.check().await.unwrap(), Decision::Allowed(None));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // 前两个拒绝时由第三个节点放行
+        first.set_allowed(false);
+        assert_eq!(chain.check().await.unwrap(), Decision::Allowed(None));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // 全部拒绝时组合节点拒绝
+        let chain = DecisionChain::new(vec![DecisionNode::group(
+            "either".to_string(),
+            "Either".to_string(),
+            vec![
+                mock_node("first", first, 100),
+                mock_node("second", second.clone(), 50),
+            ],
+            CombineOp::Or,
+            100,
+        )]);
+        assert!(matches!(
+            chain.check().await.unwrap(),
+            Decision::Rejected(_)
+        ));
+        second.set_allowed(true);
+        assert_eq!(chain.check().await.unwrap(), Decision::Allowed(None));
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_group_limits_and_keys() {
+        let tight = DecisionNode::keyed(
+            "tight".to_string(),
+            "Tight".to_string(),
+            fixed_window_factory(2),
+            100,
+        );
+        let loose = mock_node("loose", Arc::new(TokenBucketLimiter::new(10, 1)), 50);
+        let chain = DecisionChainBuilder::new()
+            .add_group(vec![tight, loose], CombineOp::And)
+            .build();
+
+        let (decision, limits) = chain.check_with_limits(Some("alice")).await.unwrap();
+        assert_eq!(decision, Decision::Allowed(None));
+        let limits = limits.unwrap();
+        assert_eq!((limits.limit, limits.remaining), (2, 1));
+
+        chain.check_keyed("alice").await.unwrap();
+        assert!(matches!(
+            chain.check_keyed("alice").await.unwrap(),
+            Decision::Rejected(_)
+        ));
+        // 按标识符隔离的组内节点对其他标识符互不影响
+        assert_eq!(
+            chain.check_keyed("bob").await.unwrap(),
+            Decision::Allowed(None)
+        );
+    }
+
     // ==================== DecisionChainBuilder 测试 ====================
 
     #[test]
@@ -1099,6 +1764,37 @@ mod tests {
         assert_eq!(stats.total_checks, 0);
     }
 
+    #[tokio::test]
+    async fn test_decision_chain_import_stats_is_additive() {
+        let limiter = Arc::new(TokenBucketLimiter::new(1, 1));
+        let node = DecisionNode::new(
+            "node1".to_string(),
+            "Token Bucket".to_string(),
+            limiter,
+            100,
+        );
+        let chain = DecisionChain::new(vec![node]);
+
+        chain.check().await.unwrap();
+        chain.check().await.unwrap();
+
+        chain.import_stats(&ChainStats {
+            total_checks: 10,
+            allowed_count: 7,
+            rejected_count: 3,
+            node_rejections: vec![("node1".to_string(), 2), ("node2".to_string(), 1)],
+        });
+
+        let stats = chain.stats();
+        assert_eq!(stats.total_checks, 12);
+        assert_eq!(stats.allowed_count, 8);
+        assert_eq!(stats.rejected_count, 4);
+        assert_eq!(
+            stats.node_rejections,
+            vec![("node1".to_string(), 3), ("node2".to_string(), 1)]
+        );
+    }
+
     #[tokio::test]
     async fn test_decision_chain_concurrent_checks() {
         let limiter = Arc::new(TokenBucketLimiter::new(100, 10));
@@ -1135,4 +1831,145 @@ mod tests {
         let stats = chain.stats();
         assert_eq!(stats.total_checks, 10);
     }
+
+    fn fixed_window_factory(max_requests: u64) -> LimiterFactory {
+        Arc::new(move |_key: &str| {
+            Arc::new(FixedWindowLimiter::new(
+                Duration::from_secs(60),
+                max_requests,
+            )) as Arc<dyn Limiter>
+        })
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_keyed_limiters_are_independent() {
+        let node = DecisionNode::keyed(
+            "node1".to_string(),
+            "Per-user Fixed Window".to_string(),
+            fixed_window_factory(3),
+            100,
+        );
+        let chain = DecisionChain::new(vec![node]);
+
+        // 每个用户都获得完整配额
+        for key in ["user:a", "user:b"] {
+            for _ in 0..3 {
+                assert_eq!(
+                    chain.check_keyed(key).await.unwrap(),
+                    Decision::Allowed(None)
+                );
+            }
+            assert!(matches!(
+                chain.check_keyed(key).await.unwrap(),
+                Decision::Rejected(_)
+            ));
+        }
+        assert_eq!(chain.keyed_limiter_count(), 2);
+
+        // 不带标识符的检查使用共享限流器
+        assert_eq!(chain.check().await.unwrap(), Decision::Allowed(None));
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_snapshots() {
+        let keyed = DecisionNode::keyed(
+            "keyed".to_string(),
+            "Per-user Fixed Window".to_string(),
+            fixed_window_factory(3),
+            100,
+        );
+        let shared = DecisionNode::new(
+            "shared".to_string(),
+            "Shared Token Bucket".to_string(),
+            Arc::new(crate::limiters::TokenBucketLimiter::new(10, 1)),
+            50,
+        );
+        let chain = DecisionChain::new(vec![keyed, shared]);
+        chain.check_keyed("user:b").await.unwrap();
+        chain.check_keyed("user:a").await.unwrap();
+        chain.check_keyed("user:a").await.unwrap();
+
+        let snapshots = chain.snapshots();
+        let keys: Vec<_> = snapshots
+            .iter()
+            .map(|s| (s.node_id.as_str(), s.key.as_deref()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("keyed", Some("user:a")),
+                ("keyed", Some("user:b")),
+                ("shared", None)
+            ]
+        );
+        assert!(matches!(
+            snapshots[0].snapshot,
+            LimiterSnapshot::Window {
+                used: 2,
+                limit: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            snapshots[2].snapshot,
+            LimiterSnapshot::TokenBucket { available: 7, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_keyed_limiters_lru_eviction() {
+        let node = DecisionNode::keyed(
+            "node1".to_string(),
+            "Per-user Fixed Window".to_string(),
+            fixed_window_factory(1),
+            100,
+        );
+        let chain = DecisionChain::new(vec![node]).with_max_keyed_entries(2);
+
+        assert_eq!(
+            chain.check_keyed("user:a").await.unwrap(),
+            Decision::Allowed(None)
+        );
+        assert_eq!(
+            chain.check_keyed("user:b").await.unwrap(),
+            Decision::Allowed(None)
+        );
+        assert_eq!(
+            chain.check_keyed("user:c").await.unwrap(),
+            Decision::Allowed(None)
+        );
+        assert_eq!(chain.keyed_limiter_count(), 2);
+
+        // user:a 已被淘汰，重新获得新的限流器
+        assert_eq!(
+            chain.check_keyed("user:a").await.unwrap(),
+            Decision::Allowed(None)
+        );
+        // user:c 仍在缓存中，配额已耗尽
+        assert!(matches!(
+            chain.check_keyed("user:c").await.unwrap(),
+            Decision::Rejected(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decision_chain_reject_reason_from_limiter_type() {
+        for (limiter_type, expected) in [
+            ("token_bucket", RejectReason::RateLimit),
+            ("quota", RejectReason::Quota),
+            ("concurrency", RejectReason::Concurrency),
+        ] {
+            let node = DecisionNode::new(
+                "node1".to_string(),
+                "Node".to_string(),
+                Arc::new(MockLimiter::new(false)),
+                100,
+            )
+            .with_limiter_type(limiter_type);
+            let chain = DecisionChain::new(vec![node]);
+
+            assert_eq!(chain.check().await.unwrap().reason(), Some(&expected));
+            assert_eq!(chain.check_all().await.unwrap().reason(), Some(&expected));
+        }
+    }
 }
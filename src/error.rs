@@ -48,8 +48,10 @@ pub enum FlowGuardError {
     SerdeError(#[from] serde_json::Error),
 
     /// YAML解析错误
+    ///
+    /// 保存错误描述而不是 `serde_yaml::Error`，变体在未启用 `yaml` 特性时同样存在。
     #[error("YAML解析错误: {0}")]
-    YamlError(#[from] serde_yaml::Error),
+    YamlError(String),
 
     /// 速率限制超出
     #[error("速率限制超出: {0}")]
@@ -114,6 +116,10 @@ pub enum StorageError {
     /// 验证错误
     #[error("验证错误: {0}")]
     ValidationError(String),
+
+    /// 存储后端不支持该操作
+    #[error("不支持的操作: {0}")]
+    Unsupported(String),
 }
 
 impl StorageError {
@@ -134,10 +140,64 @@ impl StorageError {
             StorageError::AuthenticationError(_)
                 | StorageError::PermissionError(_)
                 | StorageError::InvalidConfig(_)
+                | StorageError::Unsupported(_)
         )
     }
 }
 
+/// 时长解析错误
+///
+/// 由 [`parse_duration`](crate::factory::parse_duration) 返回，转换为
+/// [`FlowGuardError::ConfigError`]。
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DurationParseError {
+    /// 输入为空
+    #[error("时长不能为空")]
+    Empty,
+
+    /// 缺少数字部分
+    #[error("时长格式错误：缺少数字部分: {0}")]
+    MissingNumber(String),
+
+    /// 数字部分无法解析
+    #[error("无效的数字格式: {0}")]
+    InvalidNumber(String),
+
+    /// 不支持的单位
+    #[error("不支持的单位: {0}。支持的单位: ms, s, m, h, d")]
+    InvalidUnit(String),
+
+    /// 时长为 0
+    #[error("时长必须大于0")]
+    Zero,
+
+    /// 换算为秒时溢出
+    #[error("时长过大，换算时溢出: {0}")]
+    Overflow(String),
+
+    /// 超过允许的最大时长
+    #[error("时长 {input} 超过上限 {max_secs} 秒")]
+    TooLong {
+        /// 原始输入
+        input: String,
+        /// 上限（秒）
+        max_secs: u64,
+    },
+}
+
+impl From<DurationParseError> for FlowGuardError {
+    fn from(err: DurationParseError) -> Self {
+        FlowGuardError::ConfigError(err.to_string())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for FlowGuardError {
+    fn from(err: serde_yaml::Error) -> Self {
+        FlowGuardError::YamlError(err.to_string())
+    }
+}
+
 #[cfg(feature = "postgres")]
 impl From<sqlx::Error> for StorageError {
     fn from(err: sqlx::Error) -> Self {
@@ -179,15 +239,128 @@ pub struct CircuitBreakerStats {
     pub last_state_change: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl FlowGuardError {
+    /// 获取限流类错误对应的拒绝原因
+    ///
+    /// `flow_control` 宏生成的代码以 `RateLimitExceeded` / `QuotaExceeded` /
+    /// `ConcurrencyLimitExceeded` 返回拒绝，可通过此方法获得结构化原因。
+    pub fn reject_reason(&self) -> Option<RejectReason> {
+        match self {
+            FlowGuardError::RateLimitExceeded(_) => Some(RejectReason::RateLimit),
+            FlowGuardError::QuotaExceeded(_) => Some(RejectReason::Quota),
+            FlowGuardError::ConcurrencyLimitExceeded(_) => Some(RejectReason::Concurrency),
+            _ => None,
+        }
+    }
+}
+
 /// 决策结果
 #[derive(Debug, Clone, PartialEq)]
 pub enum Decision {
     /// 允许
     Allowed(Option<String>),
     /// 拒绝
-    Rejected(String),
+    Rejected(Rejection),
     /// 封禁
     Banned(BanInfo),
+    /// 软限流：超限但不拒绝，调用方应等待给定时长后继续处理请求
+    ///
+    /// 变体始终存在，不随特性变化；只有启用 `soft-limit` 特性并配置 `on_exceed = "delay"`
+    /// 的规则才会产生该决策。
+    Delayed(std::time::Duration),
+}
+
+impl Decision {
+    /// 创建拒绝决策
+    pub fn rejected(reason: RejectReason, message: impl Into<String>) -> Self {
+        Decision::Rejected(Rejection::new(reason, message))
+    }
+
+    /// 获取拒绝原因（非拒绝决策返回 `None`）
+    pub fn reason(&self) -> Option<&RejectReason> {
+        match self {
+            Decision::Rejected(rejection) => Some(&rejection.reason),
+            _ => None,
+        }
+    }
+
+    /// 软限流要求的等待时长（非延迟决策返回 `None`）
+    pub fn delay(&self) -> Option<std::time::Duration> {
+        match self {
+            Decision::Delayed(delay) => Some(*delay),
+            _ => None,
+        }
+    }
+
+    /// 获取拒绝消息（非拒绝决策返回 `None`）
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Decision::Rejected(rejection) => Some(&rejection.message),
+            _ => None,
+        }
+    }
+}
+
+/// 拒绝原因
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RejectReason {
+    /// 超出速率限制
+    RateLimit,
+    /// 超出配额
+    Quota,
+    /// 超出并发限制
+    Concurrency,
+    /// 无法提取请求标识符
+    NoIdentifier,
+    /// 存储或限流器故障，按失败关闭策略拒绝
+    StorageUnavailable,
+    /// 自定义原因
+    Custom(String),
+}
+
+impl RejectReason {
+    /// 获取原因的字符串表示
+    pub fn as_str(&self) -> &str {
+        match self {
+            RejectReason::RateLimit => "rate_limit",
+            RejectReason::Quota => "quota",
+            RejectReason::Concurrency => "concurrency",
+            RejectReason::NoIdentifier => "no_identifier",
+            RejectReason::StorageUnavailable => "storage_unavailable",
+            RejectReason::Custom(reason) => reason,
+        }
+    }
+}
+
+impl std::fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 拒绝详情
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection {
+    /// 结构化的拒绝原因
+    pub reason: RejectReason,
+    /// 可读的拒绝消息
+    pub message: String,
+}
+
+impl Rejection {
+    /// 创建拒绝详情
+    pub fn new(reason: RejectReason, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
 /// 封禁信息
@@ -204,6 +377,8 @@ pub struct ConsumeResult {
     pub allowed: bool,
     pub remaining: u64,
     pub alert_triggered: bool,
+    /// 本次消费新跨越的最高告警阈值（百分比）
+    pub crossed_threshold: Option<u8>,
 }
 
 #[cfg(test)]
@@ -239,8 +414,53 @@ mod tests {
 
     #[test]
     fn test_decision_rejected() {
-        let decision = Decision::Rejected("rate limit exceeded".to_string());
+        let decision = Decision::rejected(RejectReason::RateLimit, "rate limit exceeded");
         assert!(matches!(decision, Decision::Rejected(_)));
+        assert_eq!(decision.reason(), Some(&RejectReason::RateLimit));
+        assert_eq!(decision.message(), Some("rate limit exceeded"));
+        assert_eq!(Decision::Allowed(None).reason(), None);
+    }
+
+    #[test]
+    fn test_reject_reason_variants() {
+        let cases = [
+            (RejectReason::RateLimit, "rate_limit"),
+            (RejectReason::Quota, "quota"),
+            (RejectReason::Concurrency, "concurrency"),
+            (RejectReason::NoIdentifier, "no_identifier"),
+            (
+                RejectReason::Custom("maintenance".to_string()),
+                "maintenance",
+            ),
+        ];
+        for (reason, expected) in cases {
+            assert_eq!(reason.to_string(), expected);
+            let decision = Decision::rejected(reason.clone(), format!("rejected: {}", expected));
+            assert_eq!(decision.reason(), Some(&reason));
+            if let Decision::Rejected(rejection) = &decision {
+                assert_eq!(rejection.to_string(), format!("rejected: {}", expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_flow_guard_error_reject_reason() {
+        assert_eq!(
+            FlowGuardError::RateLimitExceeded("x".to_string()).reject_reason(),
+            Some(RejectReason::RateLimit)
+        );
+        assert_eq!(
+            FlowGuardError::QuotaExceeded("x".to_string()).reject_reason(),
+            Some(RejectReason::Quota)
+        );
+        assert_eq!(
+            FlowGuardError::ConcurrencyLimitExceeded("x".to_string()).reject_reason(),
+            Some(RejectReason::Concurrency)
+        );
+        assert_eq!(
+            FlowGuardError::ConfigError("x".to_string()).reject_reason(),
+            None
+        );
     }
 
     #[test]
@@ -14,11 +14,14 @@
 //! - **错误处理** - 完善的错误信息和类型
 
 use crate::config::LimiterConfig;
-use crate::error::FlowGuardError;
+use crate::constants::{MAX_DURATION_SECS, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
+use crate::error::{DurationParseError, FlowGuardError};
 use crate::limiters::{
-    ConcurrencyLimiter, FixedWindowLimiter, Limiter, SlidingWindowLimiter, TokenBucketLimiter,
+    ConcurrencyLimiter, FixedWindowLimiter, GcraLimiter, Limiter, SlidingWindowLimiter,
+    TokenBucketLimiter,
 };
 use std::sync::Arc;
+use std::time::Duration;
 
 /// 配置限制常量
 ///
@@ -27,10 +30,87 @@ use std::sync::Arc;
 /// - MAX_TOKEN_BUCKET_REFILL_RATE: 防止CPU过度消耗，每秒100万次补充操作可能导致性能问题
 /// - MAX_WINDOW_REQUESTS: 防止窗口数据结构过大，影响内存和性能
 /// - MAX_CONCURRENT_REQUESTS: 防止并发控制结构过大，影响系统稳定性
+/// - MAX_GCRA_BURST: 防止突发容量过大，使 GCRA 失去平滑效果
 const MAX_TOKEN_BUCKET_CAPACITY: u64 = 10_000_000;
 const MAX_TOKEN_BUCKET_REFILL_RATE: u64 = 1_000_000;
 const MAX_WINDOW_REQUESTS: u64 = 10_000_000;
 const MAX_CONCURRENT_REQUESTS: u64 = 100_000;
+const MAX_GCRA_BURST: u64 = 10_000_000;
+
+/// 解析时长字符串
+///
+/// 窗口大小、GCRA 周期、拒绝统计窗口等配置项共用此函数，保证各处接受的格式一致。
+///
+/// # 支持的格式
+///
+/// - `100ms` - 100毫秒
+/// - `10s` - 10秒
+/// - `5m` - 5分钟
+/// - `2h` - 2小时
+/// - `1d` - 1天
+///
+/// 单位不区分大小写，也接受 `sec`、`minutes`、`hours`、`days` 等全称；时长必须大于0，
+/// 且不超过 [`MAX_DURATION_SECS`]（30天）。换算溢出或超过上限时返回错误而不是回绕。
+///
+/// # 示例
+///
+/// ```rust
+/// use limiteron::factory::parse_duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+/// assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+/// assert!(parse_duration("0s").is_err());
+/// ```
+pub fn parse_duration(input: &str) -> Result<Duration, DurationParseError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(DurationParseError::Empty);
+    }
+
+    let (num_part, unit_part) = input.split_at(
+        input
+            .find(|c: char| c.is_alphabetic())
+            .unwrap_or(input.len()),
+    );
+    let num_str = num_part.trim();
+    let unit = unit_part.trim().to_lowercase();
+
+    if num_str.is_empty() {
+        return Err(DurationParseError::MissingNumber(input.to_string()));
+    }
+
+    let num: u64 = num_str
+        .parse()
+        .map_err(|_| DurationParseError::InvalidNumber(num_str.to_string()))?;
+
+    if num == 0 {
+        return Err(DurationParseError::Zero);
+    }
+
+    let scale_secs = |factor: u64| {
+        num.checked_mul(factor)
+            .map(Duration::from_secs)
+            .ok_or_else(|| DurationParseError::Overflow(input.to_string()))
+    };
+    let duration = match unit.as_str() {
+        "ms" | "msec" | "millisecond" | "milliseconds" => Duration::from_millis(num),
+        "s" | "sec" | "second" | "seconds" => Duration::from_secs(num),
+        "m" | "min" | "minute" | "minutes" => scale_secs(SECONDS_PER_MINUTE)?,
+        "h" | "hr" | "hour" | "hours" => scale_secs(SECONDS_PER_HOUR)?,
+        "d" | "day" | "days" => scale_secs(SECONDS_PER_DAY)?,
+        _ => return Err(DurationParseError::InvalidUnit(unit)),
+    };
+
+    if duration > Duration::from_secs(MAX_DURATION_SECS) {
+        return Err(DurationParseError::TooLong {
+            input: input.to_string(),
+            max_secs: MAX_DURATION_SECS,
+        });
+    }
+
+    Ok(duration)
+}
 
 /// 限流器工厂
 ///
@@ -82,9 +162,14 @@ impl LimiterFactory {
             LimiterConfig::SlidingWindow {
                 window_size,
                 max_requests,
+                mode,
             } => {
                 let duration = Self::parse_window_size(window_size)?;
-                Ok(Arc::new(SlidingWindowLimiter::new(duration, *max_requests)))
+                Ok(Arc::new(SlidingWindowLimiter::with_mode(
+                    duration,
+                    *max_requests,
+                    *mode,
+                )))
             }
             LimiterConfig::FixedWindow {
                 window_size,
@@ -96,6 +181,10 @@ impl LimiterFactory {
             LimiterConfig::Concurrency { max_concurrent } => {
                 Ok(Arc::new(ConcurrencyLimiter::new(*max_concurrent)))
             }
+            LimiterConfig::Gcra { period, burst } => {
+                let duration = Self::parse_window_size(period)?;
+                Ok(Arc::new(GcraLimiter::new(duration, *burst)))
+            }
             LimiterConfig::Quota {
                 quota_type: _,
                 limit: _limit,
@@ -154,19 +243,7 @@ impl LimiterFactory {
 
     /// 解析窗口大小字符串
     ///
-    /// # 参数
-    /// - `window_size`: 窗口大小字符串（如 "1s", "1m", "1h"）
-    ///
-    /// # 返回
-    /// - `Ok(Duration)`: 解析成功的时间段
-    /// - `Err(FlowGuardError)`: 解析失败
-    ///
-    /// # 支持的格式
-    ///
-    /// - `10s` - 10秒
-    /// - `5m` - 5分钟  
-    /// - `2h` - 2小时
-    /// - `1d` - 1天
+    /// 规则同 [`parse_duration`]，错误转换为 [`FlowGuardError::ConfigError`]。
     ///
     /// # 示例
     ///
@@ -177,69 +254,10 @@ impl LimiterFactory {
     /// let duration = LimiterFactory::parse_window_size("5m").unwrap();
     /// assert_eq!(duration, Duration::from_secs(300));
     /// ```
-    pub fn parse_window_size(window_size: &str) -> Result<std::time::Duration, FlowGuardError> {
-        if window_size.is_empty() {
-            return Err(FlowGuardError::ConfigError("窗口大小不能为空".to_string()));
-        }
-
-        let (num_part, unit_part) = window_size.split_at(
-            window_size
-                .find(|c: char| c.is_alphabetic())
-                .unwrap_or(window_size.len()),
-        );
-
-        let num_str = num_part.trim();
-        let unit = unit_part.trim().to_lowercase();
-
-        if num_str.is_empty() {
-            return Err(FlowGuardError::ConfigError(
-                "窗口大小格式错误：缺少数字部分".to_string(),
-            ));
-        }
-
-        let num: u64 = num_str
-            .parse()
-            .map_err(|_| FlowGuardError::ConfigError(format!("无效的数字格式: {}", num_str)))?;
-
-        if num == 0 {
-            return Err(FlowGuardError::ConfigError("窗口大小必须大于0".to_string()));
-        }
-
-        let duration = match unit.as_str() {
-            "s" | "sec" | "second" | "seconds" => std::time::Duration::from_secs(num),
-            "m" | "min" | "minute" | "minutes" => std::time::Duration::from_secs(num * 60),
-            "h" | "hr" | "hour" | "hours" => std::time::Duration::from_secs(num * 3600),
-            "d" | "day" | "days" => std::time::Duration::from_secs(num * 86400),
-            _ => {
-                return Err(FlowGuardError::ConfigError(format!(
-                    "不支持的单位: {}。支持的单位: s, m, h, d",
-                    unit
-                )));
-            }
-        };
-
-        Ok(duration)
+    pub fn parse_window_size(window_size: &str) -> Result<Duration, FlowGuardError> {
+        Ok(parse_duration(window_size)?)
     }
 
-    /// 验证限流器配置
-    ///
-    /// # 参数
-    /// - `config`: 要验证的限流器配置
-    ///
-    /// # 返回
-    /// - `Ok(())`: 验证通过
-    /// - `Err(FlowGuardError)`: 验证失败
-    ///
-    /// # 示例
-    ///
-    /// ```rust
-    /// use limiteron::factory::LimiterFactory;
-    /// use limiteron::config::LimiterConfig;
-    ///
-    /// let config = LimiterConfig::TokenBucket { capacity: 1000, refill_rate: 100 };
-    /// LimiterFactory::validate_config(&config).unwrap();
-    /// ```
-
     /// 验证窗口配置（适用于滑动窗口和固定窗口）
     fn validate_window_config(
         window_size: &str,
@@ -262,6 +280,24 @@ impl LimiterFactory {
         Ok(())
     }
 
+    /// 验证限流器配置
+    ///
+    /// # 参数
+    /// - `config`: 要验证的限流器配置
+    ///
+    /// # 返回
+    /// - `Ok(())`: 验证通过
+    /// - `Err(FlowGuardError)`: 验证失败
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use limiteron::factory::LimiterFactory;
+    /// use limiteron::config::LimiterConfig;
+    ///
+    /// let config = LimiterConfig::TokenBucket { capacity: 1000, refill_rate: 100 };
+    /// LimiterFactory::validate_config(&config).unwrap();
+    /// ```
     pub fn validate_config(config: &LimiterConfig) -> Result<(), FlowGuardError> {
         match config {
             LimiterConfig::TokenBucket {
@@ -294,6 +330,7 @@ impl LimiterFactory {
             LimiterConfig::SlidingWindow {
                 window_size,
                 max_requests,
+                ..
             } => {
                 Self::validate_window_config(window_size, *max_requests, "滑动窗口")?;
             }
@@ -316,6 +353,20 @@ impl LimiterFactory {
                     )));
                 }
             }
+            LimiterConfig::Gcra { period, burst } => {
+                Self::parse_window_size(period)?;
+                if *burst == 0 {
+                    return Err(FlowGuardError::ConfigError(
+                        "GCRA突发容量必须大于0".to_string(),
+                    ));
+                }
+                if *burst > MAX_GCRA_BURST {
+                    return Err(FlowGuardError::ConfigError(format!(
+                        "GCRA突发容量过大，最大值为{}",
+                        MAX_GCRA_BURST
+                    )));
+                }
+            }
             LimiterConfig::Quota { .. } => {
                 // Quota 类型由QuotaController处理
                 return Err(FlowGuardError::LimitError(
@@ -341,7 +392,7 @@ impl LimiterFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
+    use crate::limiters::SlidingWindowMode;
 
     #[test]
     fn test_create_token_bucket() {
@@ -359,6 +410,7 @@ mod tests {
         let config = LimiterConfig::SlidingWindow {
             window_size: "1m".to_string(),
             max_requests: 60,
+            mode: SlidingWindowMode::Log,
         };
 
         let limiter = LimiterFactory::create(&config);
@@ -399,6 +451,17 @@ mod tests {
         assert_eq!(limiters.unwrap().len(), 2);
     }
 
+    #[test]
+    fn test_create_gcra() {
+        let config = LimiterConfig::Gcra {
+            period: "1s".to_string(),
+            burst: 10,
+        };
+
+        let limiter = LimiterFactory::create(&config);
+        assert!(limiter.is_ok());
+    }
+
     #[test]
     fn test_parse_window_size_seconds() {
         let duration = LimiterFactory::parse_window_size("10s");
@@ -445,6 +508,78 @@ mod tests {
         assert!(duration.is_err());
     }
 
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("100ms"), Ok(Duration::from_millis(100)));
+        assert_eq!(parse_duration(" 30 sec "), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5M"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2hours"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        assert_eq!(parse_duration(""), Err(DurationParseError::Empty));
+        assert_eq!(parse_duration("  "), Err(DurationParseError::Empty));
+        assert_eq!(
+            parse_duration("ms"),
+            Err(DurationParseError::MissingNumber("ms".to_string()))
+        );
+        assert_eq!(
+            parse_duration("1.5s"),
+            Err(DurationParseError::InvalidNumber("1.5".to_string()))
+        );
+        assert_eq!(
+            parse_duration("10"),
+            Err(DurationParseError::InvalidUnit(String::new()))
+        );
+        assert_eq!(
+            parse_duration("10w"),
+            Err(DurationParseError::InvalidUnit("w".to_string()))
+        );
+        assert_eq!(parse_duration("0ms"), Err(DurationParseError::Zero));
+
+        // 换算溢出与超过上限
+        assert_eq!(
+            parse_duration("999999999999999999d"),
+            Err(DurationParseError::Overflow(
+                "999999999999999999d".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_duration("18446744073709551615m"),
+            Err(DurationParseError::Overflow(
+                "18446744073709551615m".to_string()
+            ))
+        );
+        assert!(matches!(
+            parse_duration("18446744073709551615s"),
+            Err(DurationParseError::TooLong { max_secs, .. }) if max_secs == MAX_DURATION_SECS
+        ));
+        assert!(matches!(
+            parse_duration("31d"),
+            Err(DurationParseError::TooLong { .. })
+        ));
+        assert!(matches!(
+            parse_duration("2592000001ms"),
+            Err(DurationParseError::TooLong { .. })
+        ));
+        assert_eq!(
+            parse_duration("30d"),
+            Ok(Duration::from_secs(MAX_DURATION_SECS))
+        );
+        assert_eq!(
+            parse_duration("720h"),
+            Ok(Duration::from_secs(MAX_DURATION_SECS))
+        );
+
+        // 转换为配置错误
+        assert!(matches!(
+            LimiterFactory::parse_window_size("0s"),
+            Err(FlowGuardError::ConfigError(message)) if message == "时长必须大于0"
+        ));
+    }
+
     #[test]
     fn test_validate_token_bucket_valid() {
         let config = LimiterConfig::TokenBucket {
@@ -493,4 +628,25 @@ mod tests {
         let result = LimiterFactory::validate_config(&config);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_validate_gcra() {
+        let valid = LimiterConfig::Gcra {
+            period: "1s".to_string(),
+            burst: 10,
+        };
+        assert!(LimiterFactory::validate_config(&valid).is_ok());
+
+        let zero_burst = LimiterConfig::Gcra {
+            period: "1s".to_string(),
+            burst: 0,
+        };
+        assert!(LimiterFactory::validate_config(&zero_burst).is_err());
+
+        let invalid_period = LimiterConfig::Gcra {
+            period: "0s".to_string(),
+            burst: 10,
+        };
+        assert!(LimiterFactory::validate_config(&invalid_period).is_err());
+    }
 }
@@ -8,10 +8,24 @@
 //!
 //! # 特性
 //!
-//! - **多种策略**: FailOpen、FailClosed、Degraded
+//! - **多种策略**: FailOpen、FailClosed、Degraded、UseLastKnown
 //! - **组件级配置**: 为不同组件配置不同策略
 //! - **热更新**: 支持动态更新策略
 //! - **故障注入**: 支持模拟故障进行测试
+//!
+//! # 与 Governor 的配合
+//!
+//! [`Governor`](crate::governor::Governor) 在组件熔断或出错时按组件策略决策：
+//!
+//! | 策略 | 封禁存储（`Ban`） | 限流存储（`Limiter`） |
+//! |------|------------------|----------------------|
+//! | `FailOpen` | 视为未封禁，继续限流 | 放行 |
+//! | `FailClosed` | 拒绝 | 拒绝 |
+//! | `Degraded` | 熔断期间使用封禁缓存（可能已过期） | 按 Governor 故障处理策略 |
+//! | `UseLastKnown` | 熔断期间使用该标识符最近一次查询结果 | 熔断期间复用该标识符最近一次决策 |
+//!
+//! 缓存中没有对应标识符时按 Governor 的故障处理策略处理。配额存储（`Quota`）
+//! 不在 Governor 的请求检查路径上，其策略供 [`FallbackManager::execute_with_fallback`] 使用。
 
 use crate::cache::l2::L2Cache;
 use crate::error::{FlowGuardError, StorageError};
@@ -23,6 +37,7 @@ use tracing::{debug, error, info, warn};
 
 /// 降级策略
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
 pub enum FallbackStrategy {
     /// 故障时允许所有请求（降级为全开放）
     FailOpen,
@@ -30,10 +45,13 @@ pub enum FallbackStrategy {
     FailClosed,
     /// 故障时使用降级服务（如L2缓存、缓存配置）
     Degraded,
+    /// 故障时使用组件最近一次成功返回的结果
+    UseLastKnown,
 }
 
 /// 组件类型
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
 pub enum ComponentType {
     /// Redis存储
     Redis,
@@ -47,6 +65,8 @@ pub enum ComponentType {
     Ban,
     /// 配额服务
     Quota,
+    /// 限流器（限流状态存储）
+    Limiter,
     /// 其他组件
     Other(String),
 }
@@ -60,6 +80,7 @@ impl From<&str> for ComponentType {
             "config" => ComponentType::Config,
             "ban" => ComponentType::Ban,
             "quota" => ComponentType::Quota,
+            "limiter" => ComponentType::Limiter,
             other => ComponentType::Other(other.to_string()),
         }
     }
@@ -74,9 +95,21 @@ impl ComponentType {
             ComponentType::Config => "config",
             ComponentType::Ban => "ban",
             ComponentType::Quota => "quota",
+            ComponentType::Limiter => "limiter",
             ComponentType::Other(s) => s,
         }
     }
+
+    /// 规范化组件类型：`Other` 中的名称对应内置组件时转换为该组件
+    ///
+    /// 旧版本中 `ComponentType::from("limiter")` 得到 `Other("limiter")`，
+    /// [`FallbackManager`] 按规范化后的组件登记和查找策略，这类配置仍然生效。
+    pub fn normalized(self) -> Self {
+        match self {
+            ComponentType::Other(name) => ComponentType::from(name.as_str()),
+            component => component,
+        }
+    }
 }
 
 /// 降级策略配置
@@ -131,6 +164,16 @@ impl FallbackConfig {
     }
 }
 
+impl From<FallbackStrategy> for FallbackConfig {
+    /// 使用默认参数的启用策略，组件由 [`FallbackManager::set_strategy`] 填入
+    fn from(strategy: FallbackStrategy) -> Self {
+        Self {
+            strategy,
+            ..Default::default()
+        }
+    }
+}
+
 /// 降级策略管理器
 pub struct FallbackManager {
     /// 策略配置
@@ -197,12 +240,35 @@ impl FallbackManager {
         }
     }
 
-    /// 设置降级策略
+    /// 设置降级策略，运行时修改立即生效
     ///
     /// # 参数
     /// - `component`: 组件类型
-    /// - `config`: 策略配置
-    pub async fn set_strategy(&self, component: ComponentType, config: FallbackConfig) {
+    /// - `config`: 策略配置，或直接传入 [`FallbackStrategy`] 使用默认参数
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::fallback::{ComponentType, FallbackManager, FallbackStrategy};
+    /// use limiteron::L2Cache;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let manager = FallbackManager::new(Arc::new(L2Cache::new(100, Duration::from_secs(60))));
+    /// manager
+    ///     .set_strategy(ComponentType::Ban, FallbackStrategy::FailClosed)
+    ///     .await;
+    /// assert_eq!(
+    ///     manager.active_strategy(&ComponentType::Ban).await,
+    ///     Some(FallbackStrategy::FailClosed)
+    /// );
+    /// # }
+    /// ```
+    pub async fn set_strategy(&self, component: ComponentType, config: impl Into<FallbackConfig>) {
+        let component = component.normalized();
+        let mut config = config.into();
+        config.component = component.clone();
         info!(
             "设置降级策略: component={:?}, strategy={:?}",
             component, config.strategy
@@ -221,7 +287,17 @@ impl FallbackManager {
     /// - 策略配置
     pub async fn get_strategy(&self, component: ComponentType) -> Option<FallbackConfig> {
         let strategies = self.strategies.read().await;
-        strategies.get(&component).cloned()
+        strategies.get(&component.normalized()).cloned()
+    }
+
+    /// 获取组件当前启用的降级策略，未配置或已禁用时返回 `None`
+    pub async fn active_strategy(&self, component: &ComponentType) -> Option<FallbackStrategy> {
+        let component = component.clone().normalized();
+        let strategies = self.strategies.read().await;
+        strategies
+            .get(&component)
+            .filter(|config| config.enabled)
+            .map(|config| config.strategy)
     }
 
     /// 执行带降级策略的操作
@@ -342,6 +418,11 @@ impl FallbackManager {
                 debug!("降级策略: Degraded - 使用备用方案");
                 fallback_operation().await
             }
+            FallbackStrategy::UseLastKnown => {
+                // 由降级操作提供最近一次成功的结果
+                debug!("降级策略: UseLastKnown - 使用最近一次成功的结果");
+                fallback_operation().await
+            }
         }
     }
 
@@ -430,6 +511,7 @@ mod tests {
         assert_eq!(ComponentType::from("redis"), ComponentType::Redis);
         assert_eq!(ComponentType::from("postgres"), ComponentType::Postgres);
         assert_eq!(ComponentType::from("l3_cache"), ComponentType::L3Cache);
+        assert_eq!(ComponentType::from("limiter"), ComponentType::Limiter);
         assert_eq!(
             ComponentType::from("other"),
             ComponentType::Other("other".to_string())
@@ -495,6 +577,52 @@ mod tests {
         let strategy = manager.get_strategy(ComponentType::Redis).await;
         assert!(strategy.is_some());
         assert_eq!(strategy.unwrap().strategy, FallbackStrategy::FailOpen);
+
+        // 直接传入策略，运行时替换
+        manager
+            .set_strategy(ComponentType::Limiter, FallbackStrategy::UseLastKnown)
+            .await;
+        let config = manager.get_strategy(ComponentType::Limiter).await.unwrap();
+        assert_eq!(config.component, ComponentType::Limiter);
+        assert_eq!(
+            manager.active_strategy(&ComponentType::Limiter).await,
+            Some(FallbackStrategy::UseLastKnown)
+        );
+
+        manager
+            .set_strategy(
+                ComponentType::Limiter,
+                FallbackConfig::new(ComponentType::Limiter, FallbackStrategy::FailClosed)
+                    .enabled(false),
+            )
+            .await;
+        assert_eq!(manager.active_strategy(&ComponentType::Limiter).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_manager_normalizes_other_component() {
+        let l2_cache = Arc::new(L2Cache::new(10000, Duration::from_secs(60)));
+        let manager = FallbackManager::new(l2_cache);
+
+        // 旧版本以 Other("limiter") 登记的策略作用于 Limiter 组件
+        manager
+            .set_strategy(
+                ComponentType::Other("limiter".to_string()),
+                FallbackStrategy::UseLastKnown,
+            )
+            .await;
+        assert_eq!(
+            manager.active_strategy(&ComponentType::Limiter).await,
+            Some(FallbackStrategy::UseLastKnown)
+        );
+        assert_eq!(
+            manager
+                .active_strategy(&ComponentType::Other("Limiter".to_string()))
+                .await,
+            Some(FallbackStrategy::UseLastKnown)
+        );
+        let config = manager.get_strategy(ComponentType::Limiter).await.unwrap();
+        assert_eq!(config.component, ComponentType::Limiter);
     }
 
     #[tokio::test]
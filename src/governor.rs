@@ -12,27 +12,32 @@
 #[cfg(feature = "fallback")]
 use crate::cache::l2::L2Cache;
 use crate::config::{
-    ChangeSource, ConfigChangeRecord, ConfigHistory, FlowControlConfig, LimiterConfig,
-    Matcher as ConfigMatcher,
+    ChangeSource, ConfigChangeRecord, ConfigFormat, ConfigHistory, FlowControlConfig,
+    LimiterConfig, Matcher as ConfigMatcher, Rule as ConfigRule,
 };
 #[allow(unused_imports)]
 use crate::constants::{
-    DEFAULT_L2_CACHE_CAPACITY, DEFAULT_L2_CACHE_TTL_SECS, SECONDS_PER_HOUR, SECONDS_PER_MINUTE,
+    DEFAULT_L2_CACHE_CAPACITY, DEFAULT_L2_CACHE_TTL_SECS, DEFAULT_MAX_KEYED_LIMITERS,
 };
-use crate::decision_chain::{DecisionChain, DecisionNode};
-use crate::error::{Decision, FlowGuardError};
+use crate::decision_chain::{tighter_limits, DecisionChain, DecisionNode, LimiterFactory};
+use crate::error::{BanInfo, Decision, FlowGuardError, RejectReason, StorageError};
+use crate::factory::parse_duration;
 #[cfg(feature = "fallback")]
 use crate::fallback::FallbackManager;
-use crate::limiters::{FixedWindowLimiter, Limiter, SlidingWindowLimiter, TokenBucketLimiter};
-use crate::log_redaction::{redact_ip, redact_user_id};
+use crate::limiter_manager::{sanitize_key, KeyStrategy};
+use crate::limiters::{
+    FixedWindowLimiter, GcraLimiter, Limiter, LimiterSnapshot, RateLimitDecision,
+    SlidingWindowLimiter, TokenBucketLimiter,
+};
+use crate::log_redaction::{redact_ip, redact_user_id, redaction_policy};
 use crate::matchers::{
-    CompositeCondition, ConditionEvaluator, IdentifierExtractor, IpRange, LogicalOperator,
-    MatchCondition, RequestContext, Rule as MatcherRule, RuleMatcher,
+    CompositeCondition, ConditionEvaluator, Identifier, IdentifierExtractor, IpRange,
+    LogicalOperator, MatchCondition, RequestContext, Rule as MatcherRule, RuleMatcher,
 };
 use crate::storage::{BanStorage, Storage};
 use chrono::Utc;
 use dashmap::DashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -44,9 +49,9 @@ use crate::audit_log::AuditLogger;
 #[cfg(feature = "ban-manager")]
 use crate::ban_manager::BanManager;
 #[cfg(feature = "circuit-breaker")]
-use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
-#[cfg(feature = "parallel-checker")]
-use crate::matchers::Identifier;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError};
+#[cfg(feature = "custom-limiter")]
+use crate::custom_limiter::{CustomLimiterAdapter, CustomLimiterRegistry};
 #[cfg(feature = "parallel-checker")]
 use crate::storage::BanTarget;
 #[cfg(feature = "monitoring")]
@@ -57,7 +62,7 @@ use crate::telemetry::Tracer;
 use crate::BanSource;
 
 /// Governor 统计信息
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct GovernorStats {
     /// 总请求数
     pub total_requests: u64,
@@ -69,10 +74,586 @@ pub struct GovernorStats {
     pub banned_requests: u64,
     /// 错误数
     pub error_count: u64,
+    /// 白名单放行的请求数
+    pub allowlist_bypass_requests: u64,
     /// 最后更新时间
     pub last_updated: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// 请求检查的详细结果
+///
+/// 由 [`Governor::check_detailed`] 返回，在决策之外携带产生决策的规则，
+/// 用于日志、调试与审计。
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// 决策
+    pub decision: Decision,
+    /// 产生决策的规则ID
+    ///
+    /// 拒绝或封禁时为触发的规则，软限流延迟时为延迟最长的规则，放行时为优先级最高的
+    /// 匹配规则；白名单放行、封禁检查命中或没有匹配规则时为 `None`。
+    pub matched_rule: Option<String>,
+    /// 从请求中提取的标识符
+    pub identifier: Option<Identifier>,
+    /// 最严格限流器的详细决策，含义同 [`Governor::check_with_limits`]
+    pub limits: Option<RateLimitDecision>,
+    /// 检查耗时
+    pub elapsed: Duration,
+}
+
+/// Governor 的单调计数器
+///
+/// 对应 [`GovernorStats`] 中只增不减的部分，导入时在当前值上累加。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GovernorCounters {
+    /// 总请求数
+    pub total_requests: u64,
+    /// 允许的请求数
+    pub allowed_requests: u64,
+    /// 拒绝的请求数
+    pub rejected_requests: u64,
+    /// 封禁的请求数
+    pub banned_requests: u64,
+    /// 错误数
+    pub error_count: u64,
+    /// 白名单放行的请求数
+    pub allowlist_bypass_requests: u64,
+}
+
+/// 统计快照
+///
+/// 由 [`Governor::export_stats`] 生成，服务关闭前写入存储，启动后通过
+/// [`Governor::import_stats`] 回填，使累计计数器跨重启延续。
+/// 快照只包含单调计数器；平均耗时、最后更新时间等瞬时值在重启后重新计算。
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StatsSnapshot {
+    /// 导出时间
+    pub exported_at: chrono::DateTime<Utc>,
+    /// Governor 计数器
+    pub governor: GovernorCounters,
+    /// 规则匹配器计数器
+    pub matcher: crate::matchers::MatcherCounters,
+    /// 决策链计数器
+    pub chain: crate::decision_chain::ChainStats,
+}
+
+/// Governor 运行状态转储
+///
+/// 由 [`Governor::dump_state`] 生成，可序列化为 JSON 作为调试接口的响应。
+/// 限流器与封禁中的标识符按全局脱敏策略处理，保留类型前缀；
+/// 配置与配置历史中的匹配器取值（用户ID、IP 列表等）同样脱敏。
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GovernorStateDump {
+    /// 生成时间
+    pub generated_at: chrono::DateTime<Utc>,
+    /// 当前生效的配置（匹配器取值已脱敏）
+    pub config: FlowControlConfig,
+    /// 统计信息
+    pub stats: GovernorStats,
+    /// 各规则限流器的状态快照
+    pub limiters: Vec<LimiterStateDump>,
+    /// 生效中的封禁
+    pub bans: Vec<BanStateDump>,
+    /// 最近的配置变更（差异中的匹配器取值已脱敏）
+    pub config_history: Vec<ConfigChangeRecord>,
+}
+
+/// 规则限流器的状态快照
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct LimiterStateDump {
+    /// 规则ID
+    pub rule_id: String,
+    /// 决策节点ID
+    pub node_id: String,
+    /// 脱敏后的标识符，共享限流器为 `None`
+    pub key: Option<String>,
+    /// 状态快照
+    pub snapshot: LimiterSnapshot,
+}
+
+/// 生效中的封禁
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BanStateDump {
+    /// 脱敏后的封禁目标
+    pub target: String,
+    /// 封禁原因
+    pub reason: String,
+    /// 累计封禁次数
+    pub ban_times: u32,
+    /// 封禁时间
+    pub banned_at: chrono::DateTime<Utc>,
+    /// 到期时间
+    pub expires_at: chrono::DateTime<Utc>,
+    /// 是否手动封禁
+    pub is_manual: bool,
+}
+
+/// 按全局脱敏策略处理 `类型:值` 形式的标识符，保留类型前缀
+fn redact_identifier_key(key: &str) -> String {
+    let policy = redaction_policy();
+    match key.split_once(':') {
+        Some((kind, value)) => format!("{}:{}", kind, policy.mask(value)),
+        None => policy.mask(key),
+    }
+}
+
+/// 按全局脱敏策略处理配置中所有匹配器的取值，匹配器种类保持不变
+fn redact_config_matchers(mut config: FlowControlConfig) -> FlowControlConfig {
+    let policy = redaction_policy();
+    let mask_all = |values: &mut Vec<String>| {
+        for value in values.iter_mut() {
+            *value = policy.mask(value);
+        }
+    };
+    for rule in &mut config.rules {
+        for matcher in &mut rule.matchers {
+            match matcher {
+                ConfigMatcher::User { user_ids } => mask_all(user_ids),
+                ConfigMatcher::UserPattern { user_ids, .. } => mask_all(user_ids),
+                ConfigMatcher::Ip { ip_ranges } => mask_all(ip_ranges),
+                ConfigMatcher::Geo { countries } => mask_all(countries),
+                ConfigMatcher::ApiVersion { versions } => mask_all(versions),
+                ConfigMatcher::Device { device_types } => mask_all(device_types),
+                ConfigMatcher::Method { methods } => mask_all(methods),
+                #[cfg(feature = "regex")]
+                ConfigMatcher::PathRegex { pattern } => *pattern = policy.mask(pattern),
+                #[cfg(feature = "regex")]
+                ConfigMatcher::HeaderRegex { pattern, .. } => *pattern = policy.mask(pattern),
+                ConfigMatcher::Custom { config, .. } => redact_json_strings(config),
+            }
+        }
+    }
+    config
+}
+
+/// 按全局脱敏策略处理配置差异中涉及匹配器的取值
+///
+/// 路径位于匹配器内部时整体脱敏；整条规则的新增或移除只脱敏其中的 `matchers`。
+fn redact_change_record(mut record: ConfigChangeRecord) -> ConfigChangeRecord {
+    use crate::config::ConfigFieldChange;
+
+    for change in &mut record.diff {
+        let in_matchers = change.path().contains(".matchers");
+        let values = match change {
+            ConfigFieldChange::Added { value, .. } | ConfigFieldChange::Removed { value, .. } => {
+                vec![value]
+            }
+            ConfigFieldChange::Modified { old, new, .. } => vec![old, new],
+        };
+        for value in values {
+            if in_matchers {
+                redact_json_strings(value);
+            } else if let Some(matchers) = value.get_mut("matchers") {
+                redact_json_strings(matchers);
+            }
+        }
+    }
+    record
+}
+
+/// 脱敏 JSON 中的所有字符串，保留 `type` 标签
+fn redact_json_strings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => *text = redaction_policy().mask(text),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json_strings),
+        serde_json::Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                if key != "type" {
+                    redact_json_strings(item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 多条规则同时匹配时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RuleEvaluationPolicy {
+    /// 只执行优先级最高的匹配规则
+    FirstMatch,
+    /// 执行所有匹配规则，任一规则拒绝即拒绝
+    #[default]
+    AllMatch,
+}
+
+/// 存储或限流器故障时的处理策略
+///
+/// 未通过 [`Governor::set_failure_policy`] 设置时，封禁检查故障按 `Open` 处理，
+/// 限流器故障按 `Propagate` 处理，与引入该策略前的行为一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FailurePolicy {
+    /// 将错误返回给调用方
+    Propagate,
+    /// 失败开放：封禁检查故障视为未封禁，限流器故障视为允许
+    Open,
+    /// 失败关闭：拒绝请求
+    Closed,
+}
+
+impl FailurePolicy {
+    /// 未设置策略时组件使用的默认策略
+    fn default_for(component: &str) -> Self {
+        if component == LIMITER_COMPONENT {
+            FailurePolicy::Propagate
+        } else {
+            FailurePolicy::Open
+        }
+    }
+
+    /// 指标标签使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailurePolicy::Propagate => "propagate",
+            FailurePolicy::Open => "open",
+            FailurePolicy::Closed => "closed",
+        }
+    }
+}
+
+/// 封禁检查组件名称，用于故障策略与指标
+#[cfg(feature = "parallel-checker")]
+const BAN_COMPONENT: &str = "ban";
+
+/// 限流器组件名称，用于故障策略与指标
+const LIMITER_COMPONENT: &str = "limiter";
+
+/// 批量检查中单个标识符的封禁检查结果：`Err` 表示故障并携带失败关闭时的拒绝决策
+type BanOutcome = Result<Option<BanInfo>, Option<Decision>>;
+
+/// 配置来源：存储键与格式
+type ConfigSource = (String, Option<ConfigFormat>);
+
+/// 白名单
+///
+/// IP 标识符按 [`IpRange`] 匹配（支持单个 IP、CIDR 与范围），其他标识符精确匹配。
+#[derive(Debug, Default)]
+struct Allowlist {
+    /// 非 IP 标识符
+    identifiers: ahash::AHashSet<Identifier>,
+    /// IP 条目：原始标识符与解析后的范围
+    ip_ranges: Vec<(Identifier, IpRange)>,
+}
+
+impl Allowlist {
+    fn insert(&mut self, identifier: Identifier) -> Result<(), FlowGuardError> {
+        match &identifier {
+            Identifier::Ip(range) => {
+                if self
+                    .ip_ranges
+                    .iter()
+                    .any(|(existing, _)| existing == &identifier)
+                {
+                    return Ok(());
+                }
+                let parsed: IpRange = range.parse()?;
+                self.ip_ranges.push((identifier, parsed));
+            }
+            _ => {
+                self.identifiers.insert(identifier);
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, identifier: &Identifier) -> bool {
+        match identifier {
+            Identifier::Ip(_) => {
+                let before = self.ip_ranges.len();
+                self.ip_ranges
+                    .retain(|(existing, _)| existing != identifier);
+                self.ip_ranges.len() != before
+            }
+            _ => self.identifiers.remove(identifier),
+        }
+    }
+
+    fn contains(&self, identifier: &Identifier) -> bool {
+        match identifier {
+            Identifier::Ip(ip) => match ip.parse::<std::net::IpAddr>() {
+                Ok(addr) => self
+                    .ip_ranges
+                    .iter()
+                    .any(|(_, range)| range.contains(&addr)),
+                Err(_) => false,
+            },
+            _ => self.identifiers.contains(identifier),
+        }
+    }
+}
+
+/// 组件最近一次成功返回的结果
+///
+/// 供 `FallbackStrategy::UseLastKnown` 在组件熔断期间使用，仅在该策略启用时记录。
+/// 条目不过期，达到 [`DEFAULT_LAST_KNOWN_CAPACITY`](crate::constants::DEFAULT_LAST_KNOWN_CAPACITY)
+/// 后不再记录新的标识符。
+#[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+struct LastKnown<T> {
+    entries: DashMap<Identifier, T>,
+}
+
+#[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+impl<T: Clone> LastKnown<T> {
+    fn get(&self, identifier: &Identifier) -> Option<T> {
+        self.entries.get(identifier).map(|entry| entry.clone())
+    }
+
+    fn record(&self, identifier: &Identifier, value: T) {
+        if let Some(mut entry) = self.entries.get_mut(identifier) {
+            *entry = value;
+        } else if self.entries.len() < crate::constants::DEFAULT_LAST_KNOWN_CAPACITY {
+            self.entries.insert(identifier.clone(), value);
+        }
+    }
+
+    #[cfg(feature = "parallel-checker")]
+    fn invalidate(&self, identifier: &Identifier) {
+        self.entries.remove(identifier);
+    }
+
+    /// 清空所有记录（策略切换后旧记录不再更新，不应在之后被复用）
+    fn clear(&self) {
+        if !self.entries.is_empty() {
+            self.entries.clear();
+        }
+    }
+}
+
+#[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+impl<T> Default for LastKnown<T> {
+    fn default() -> Self {
+        Self {
+            entries: DashMap::new(),
+        }
+    }
+}
+
+/// 封禁检查结果缓存
+///
+/// 同时缓存封禁与未封禁（否定）结果，条目在 TTL 后失效；
+/// 手动封禁、解封时由 Governor 立即失效对应条目。TTL 为零时不缓存。
+#[cfg(feature = "parallel-checker")]
+struct BanCache {
+    entries: DashMap<Identifier, (Option<BanInfo>, std::time::Instant)>,
+    /// 缓存有效期（毫秒）
+    ttl_ms: AtomicU64,
+    /// 每个标识符最近一次存储查询结果，不受 TTL 影响
+    #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+    last_known: LastKnown<Option<BanInfo>>,
+}
+
+#[cfg(feature = "parallel-checker")]
+impl BanCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl_ms: AtomicU64::new(ttl.as_millis() as u64),
+            #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+            last_known: LastKnown::default(),
+        }
+    }
+
+    fn set_ttl(&self, ttl: Duration) {
+        self.ttl_ms.store(ttl.as_millis() as u64, Ordering::Relaxed);
+        self.entries.clear();
+    }
+
+    /// 查询缓存，忽略 TTL，用于熔断期间不访问存储时的降级
+    ///
+    /// 缓存的封禁已到期时视为未封禁。
+    #[cfg(feature = "circuit-breaker")]
+    fn get_stale(&self, identifier: &Identifier) -> Option<Option<BanInfo>> {
+        let cached = self.entries.get(identifier).map(|entry| entry.0.clone())?;
+        Some(cached.filter(|info| info.banned_until > Utc::now()))
+    }
+
+    /// 最近一次存储查询结果，不受 TTL 与 [`set_ttl`](Self::set_ttl) 影响
+    ///
+    /// 记录的封禁已到期时视为未封禁。
+    #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+    fn get_last_known(&self, identifier: &Identifier) -> Option<Option<BanInfo>> {
+        let last_known = self.last_known.get(identifier)?;
+        Some(last_known.filter(|info| info.banned_until > Utc::now()))
+    }
+
+    /// 查询缓存，未命中或已过期时返回 `None`
+    fn get(&self, identifier: &Identifier) -> Option<Option<BanInfo>> {
+        let now = std::time::Instant::now();
+        let cached = self
+            .entries
+            .get(identifier)
+            .filter(|entry| entry.1 > now)
+            .map(|entry| entry.0.clone())?;
+
+        // 缓存的封禁已到期时视为未命中，重新查询存储
+        match cached {
+            Some(info) if info.banned_until <= Utc::now() => None,
+            cached => Some(cached),
+        }
+    }
+
+    fn insert(&self, identifier: &Identifier, result: Option<BanInfo>) {
+        #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+        self.last_known.record(identifier, result.clone());
+
+        let ttl = Duration::from_millis(self.ttl_ms.load(Ordering::Relaxed));
+        if ttl.is_zero() {
+            return;
+        }
+
+        let now = std::time::Instant::now();
+        if self.entries.len() >= crate::constants::DEFAULT_BAN_CACHE_CAPACITY {
+            self.entries.retain(|_, (_, expires_at)| *expires_at > now);
+            if self.entries.len() >= crate::constants::DEFAULT_BAN_CACHE_CAPACITY {
+                return;
+            }
+        }
+        self.entries.insert(identifier.clone(), (result, now + ttl));
+    }
+
+    fn invalidate(&self, identifier: &Identifier) {
+        self.entries.remove(identifier);
+        #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+        self.last_known.invalidate(identifier);
+    }
+}
+
+/// 规则对超限请求的后续处理
+#[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+#[derive(Debug, Clone, Copy)]
+struct RejectionPolicy {
+    /// 统计拒绝次数的滑动窗口
+    window: Duration,
+    /// 窗口内拒绝达到此次数后自动封禁
+    #[cfg(feature = "ban-manager")]
+    ban_after: Option<u32>,
+    /// 软限流的单次延迟与延迟上限
+    #[cfg(feature = "soft-limit")]
+    delay: Option<(Duration, Duration)>,
+}
+
+/// 规则拒绝次数统计
+///
+/// 按规则与标识符记录滑动窗口内的拒绝时间，供自动封禁
+/// （`ban_after_rejections`）与软限流（`on_exceed = "delay"`）使用。
+#[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+#[derive(Default)]
+struct RejectionTracker {
+    /// 规则ID -> 处理策略，只包含需要统计拒绝次数的规则
+    policies: parking_lot::RwLock<ahash::AHashMap<String, RejectionPolicy>>,
+    /// (规则ID, 标识符) -> 窗口内的拒绝时间
+    hits: DashMap<(String, String), std::collections::VecDeque<std::time::Instant>>,
+    /// 按拒绝时间排列的 (拒绝时间, 条目)，用于从最早的条目开始清除
+    order: parking_lot::Mutex<std::collections::VecDeque<(std::time::Instant, (String, String))>>,
+}
+
+#[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+impl RejectionTracker {
+    /// 按配置重建各规则的处理策略，并清除已不再统计的规则的计数
+    fn sync(&self, config: &FlowControlConfig) {
+        let mut policies = ahash::AHashMap::new();
+        for rule in &config.rules {
+            let tracked = (cfg!(feature = "ban-manager")
+                && rule.action.ban_after_rejections.is_some())
+                || (cfg!(feature = "soft-limit") && rule.action.on_exceed == "delay");
+            if !tracked {
+                continue;
+            }
+
+            let window = rule.action.rejection_window_duration().unwrap_or_else(|e| {
+                warn!("规则 {} 的拒绝统计窗口无效，使用默认值: {}", rule.id, e);
+                Duration::from_secs(crate::constants::DEFAULT_REJECTION_WINDOW_SECS)
+            });
+            let policy = RejectionPolicy {
+                window,
+                #[cfg(feature = "ban-manager")]
+                ban_after: rule.action.ban_after_rejections,
+                #[cfg(feature = "soft-limit")]
+                delay: (rule.action.on_exceed == "delay").then(|| rule.action.delay_bounds()),
+            };
+            policies.insert(rule.id.clone(), policy);
+        }
+
+        self.hits
+            .retain(|(rule_id, _), _| policies.contains_key(rule_id));
+        *self.policies.write() = policies;
+    }
+
+    /// 记录一次拒绝，返回窗口内的拒绝次数（含本次）与规则的处理策略
+    ///
+    /// 规则不需要统计拒绝次数时返回 `None`。
+    fn record(&self, rule_id: &str, key: &str) -> Option<(usize, RejectionPolicy)> {
+        let policy = self.policies.read().get(rule_id).copied()?;
+
+        let now = std::time::Instant::now();
+        if self.hits.len() >= crate::constants::DEFAULT_REJECTION_TRACKER_CAPACITY {
+            self.purge(now);
+        }
+
+        let entry_key = (rule_id.to_string(), key.to_string());
+        let count = {
+            let mut times = self.hits.entry(entry_key.clone()).or_default();
+            while times
+                .front()
+                .is_some_and(|at| now.duration_since(*at) >= policy.window)
+            {
+                times.pop_front();
+            }
+            times.push_back(now);
+            times.len()
+        };
+        // 释放条目后再入队，避免与 purge 的加锁顺序相反
+        self.order.lock().push_back((now, entry_key));
+        Some((count, policy))
+    }
+
+    /// 清空标识符在规则下的拒绝计数
+    #[cfg(feature = "ban-manager")]
+    fn clear(&self, rule_id: &str, key: &str) {
+        self.hits.remove(&(rule_id.to_string(), key.to_string()));
+    }
+
+    /// 从最早的拒绝开始清除拒绝都已移出窗口的条目，遇到仍在窗口内的条目即停止
+    ///
+    /// 每次拒绝只入队一次、最多出队一次，清除开销摊还到每次拒绝上；
+    /// 已被更新、清空或不再统计的条目留下的记录直接丢弃。
+    fn purge(&self, now: std::time::Instant) {
+        let policies = self.policies.read();
+        let mut order = self.order.lock();
+
+        while let Some((at, entry_key)) = order.front() {
+            let latest = self
+                .hits
+                .get(entry_key)
+                .and_then(|times| times.back().copied());
+            if latest == Some(*at) {
+                let in_window = policies
+                    .get(&entry_key.0)
+                    .is_some_and(|policy| now.duration_since(*at) < policy.window);
+                if in_window {
+                    break;
+                }
+                let at = *at;
+                self.hits
+                    .remove_if(entry_key, |_, times| times.back() == Some(&at));
+            }
+            order.pop_front();
+        }
+
+        // 队首条目仍在窗口内时，其后的过期记录无法出队；记录过多时按条目重建队列
+        let capacity = crate::constants::DEFAULT_REJECTION_TRACKER_CAPACITY.max(self.hits.len());
+        if order.len() > capacity * 2 {
+            let mut rebuilt: Vec<_> = self
+                .hits
+                .iter()
+                .filter_map(|entry| entry.value().back().map(|at| (*at, entry.key().clone())))
+                .collect();
+            rebuilt.sort_by_key(|(at, _)| *at);
+            *order = rebuilt.into();
+        }
+    }
+}
+
 /// Governor 主控制器
 ///
 /// 重构后的 Governor，具有更清晰的职责分离和更好的性能。
@@ -94,6 +675,14 @@ pub struct Governor {
     #[cfg(feature = "parallel-checker")]
     parallel_ban_checker: Arc<crate::parallel_ban_checker::ParallelBanChecker>,
 
+    /// 封禁检查结果缓存
+    #[cfg(feature = "parallel-checker")]
+    ban_cache: Arc<BanCache>,
+
+    /// 规则拒绝次数统计，用于自动封禁与软限流
+    #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+    rejection_tracker: RejectionTracker,
+
     /// 决策链
     decision_chain: Arc<RwLock<DecisionChain>>,
 
@@ -106,140 +695,222 @@ pub struct Governor {
     /// 标识符提取器
     identifier_extractor: Arc<dyn IdentifierExtractor>,
 
-    /// 熔断器
+    /// 封禁存储熔断器，保护请求检查中的封禁查询
     #[cfg(feature = "circuit-breaker")]
-    #[allow(dead_code)]
     circuit_breaker: Arc<CircuitBreaker>,
 
+    /// 限流器熔断器，保护请求检查中的规则评估
+    #[cfg(feature = "circuit-breaker")]
+    limiter_circuit_breaker: Arc<CircuitBreaker>,
+
+    /// 每个标识符最近一次限流决策，供 `UseLastKnown` 降级策略使用
+    #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+    last_known_decisions: LastKnown<Decision>,
+
     /// 降级管理器
     #[cfg(feature = "fallback")]
-    _fallback_manager: Arc<FallbackManager>,
+    fallback_manager: Arc<FallbackManager>,
 
     /// 审计日志记录器
     #[cfg(feature = "audit-log")]
     audit_logger: Arc<RwLock<Option<Arc<AuditLogger>>>>,
 
+    /// 自定义限流器注册表，用于解析 `LimiterConfig::Custom`
+    #[cfg(feature = "custom-limiter")]
+    custom_limiters: Arc<RwLock<Option<Arc<CustomLimiterRegistry>>>>,
+
     /// 配置历史记录
     config_history: Arc<RwLock<ConfigHistory>>,
 
+    /// 多规则匹配策略
+    rule_evaluation_policy: parking_lot::RwLock<RuleEvaluationPolicy>,
+
+    /// 限流键清洗策略，`None` 表示直接使用标识符的键
+    limiter_key_strategy: parking_lot::RwLock<Option<KeyStrategy>>,
+
+    /// 每条规则决策链按标识符缓存的限流器数量上限
+    max_keyed_limiters: AtomicUsize,
+
+    /// 存储或限流器故障时的处理策略，`None` 表示各组件使用默认策略
+    failure_policy: parking_lot::RwLock<Option<FailurePolicy>>,
+
+    /// 重新加载配置时读取的存储键与格式（`None` 表示按内容推断）
+    config_source: Arc<RwLock<Option<ConfigSource>>>,
+
+    /// 白名单，命中时跳过封禁与限流检查
+    allowlist: Arc<RwLock<Allowlist>>,
+
+    /// 监控指标
+    #[cfg(feature = "monitoring")]
+    metrics: Option<Arc<Metrics>>,
+
+    /// 追踪器
+    #[cfg(feature = "telemetry")]
+    tracer: Option<Arc<Tracer>>,
+
     // 统计计数器
     total_requests: AtomicU64,
     allowed_requests: AtomicU64,
     rejected_requests: AtomicU64,
     banned_requests: AtomicU64,
     error_count: AtomicU64,
+    allowlist_bypass_requests: AtomicU64,
 }
 
 impl Governor {
-    fn parse_duration(s: &str) -> Result<Duration, FlowGuardError> {
-        let s = s.trim();
-        let (num, unit) = if s.ends_with("ms") {
-            (s.trim_end_matches("ms"), "ms")
-        } else if s.ends_with('s') {
-            (s.trim_end_matches('s'), "s")
-        } else if s.ends_with('m') {
-            (s.trim_end_matches('m'), "m")
-        } else if s.ends_with('h') {
-            (s.trim_end_matches('h'), "h")
-        } else {
-            return Err(FlowGuardError::ConfigError(format!(
-                "Invalid duration format: {}",
-                s
-            )));
-        };
-
-        let val: u64 = num.parse().map_err(|_| {
-            FlowGuardError::ConfigError(format!("Invalid duration number: {}", num))
-        })?;
-
-        match unit {
-            "ms" => Ok(Duration::from_millis(val)),
-            "s" => Ok(Duration::from_secs(val)),
-            "m" => Ok(Duration::from_secs(val * SECONDS_PER_MINUTE)),
-            "h" => Ok(Duration::from_secs(val * SECONDS_PER_HOUR)),
-            _ => Err(FlowGuardError::ConfigError(format!(
-                "Invalid duration unit '{}'. Valid units: ms, s, m, h",
-                unit
-            ))),
-        }
-    }
-
     fn build_rule_chains(
         config: &FlowControlConfig,
+        max_keyed_limiters: usize,
+        #[cfg(feature = "monitoring")] metrics: Option<&Arc<Metrics>>,
+        #[cfg(feature = "custom-limiter")] custom_limiters: Option<&CustomLimiterRegistry>,
     ) -> Result<DashMap<String, DecisionChain>, FlowGuardError> {
         let chains = DashMap::new();
 
         for rule in &config.rules {
-            let mut nodes: Vec<DecisionNode> = Vec::new();
-
-            for (index, limiter_config) in rule.limiters.iter().enumerate() {
-                let (limiter, type_name): (Arc<dyn Limiter>, &str) = match limiter_config {
-                    LimiterConfig::TokenBucket {
-                        capacity,
-                        refill_rate,
-                    } => (
-                        Arc::new(TokenBucketLimiter::new(*capacity, *refill_rate)),
-                        "TokenBucket",
-                    ),
-                    LimiterConfig::SlidingWindow {
-                        window_size,
-                        max_requests,
-                    } => {
-                        let duration = Self::parse_duration(window_size)?;
-                        (
-                            Arc::new(SlidingWindowLimiter::new(duration, *max_requests)),
-                            "SlidingWindow",
-                        )
-                    }
-                    LimiterConfig::FixedWindow {
-                        window_size,
-                        max_requests,
-                    } => {
-                        let duration = Self::parse_duration(window_size)?;
-                        (
-                            Arc::new(FixedWindowLimiter::new(duration, *max_requests)),
-                            "FixedWindow",
-                        )
-                    }
-                    LimiterConfig::Quota {
-                        quota_type: _,
-                        limit: _,
-                        window: _,
-                        overdraft: _,
-                    } => {
-                        // Quota limiter requires quota-control feature
-                        warn!(
-                            "QuotaLimiter requires 'quota-control' feature to be enabled, \
-                             skipping Quota configuration"
-                        );
-                        continue;
-                    }
-                    LimiterConfig::Concurrency { max_concurrent } => {
-                        warn!(
-                            "ConcurrencyLimiter not implemented yet, skipping: {}",
-                            max_concurrent
-                        );
-                        continue;
-                    }
-                    LimiterConfig::Custom { name, config: _ } => {
-                        warn!("CustomLimiter not implemented yet, skipping: {}", name);
-                        continue;
-                    }
-                };
+            let chain = Self::build_rule_chain(
+                rule,
+                max_keyed_limiters,
+                #[cfg(feature = "monitoring")]
+                metrics,
+                #[cfg(feature = "custom-limiter")]
+                custom_limiters,
+            )?;
+            chains.insert(rule.id.clone(), chain);
+        }
 
-                let node = DecisionNode::new(
-                    format!("{}_limiter_{}", rule.id, index),
-                    format!("{} - {}", rule.name, type_name),
-                    limiter,
-                    100u16.saturating_sub(index as u16), // Priority: earlier limiters have higher priority
-                );
-                nodes.push(node);
-            }
+        Ok(chains)
+    }
 
-            chains.insert(rule.id.clone(), DecisionChain::new(nodes));
+    /// 构建单条规则的决策链
+    fn build_rule_chain(
+        rule: &ConfigRule,
+        max_keyed_limiters: usize,
+        #[cfg(feature = "monitoring")] metrics: Option<&Arc<Metrics>>,
+        #[cfg(feature = "custom-limiter")] custom_limiters: Option<&CustomLimiterRegistry>,
+    ) -> Result<DecisionChain, FlowGuardError> {
+        let mut nodes: Vec<DecisionNode> = Vec::new();
+
+        for (index, limiter_config) in rule.limiters.iter().enumerate() {
+            let (factory, limiter_type): (LimiterFactory, &str) = match limiter_config {
+                LimiterConfig::TokenBucket {
+                    capacity,
+                    refill_rate,
+                } => {
+                    let (capacity, refill_rate) = (*capacity, *refill_rate);
+                    (
+                        Arc::new(move |_: &str| {
+                            Arc::new(TokenBucketLimiter::new(capacity, refill_rate))
+                                as Arc<dyn Limiter>
+                        }),
+                        "token_bucket",
+                    )
+                }
+                LimiterConfig::SlidingWindow {
+                    window_size,
+                    max_requests,
+                    mode,
+                } => {
+                    let duration = parse_duration(window_size)?;
+                    let (max_requests, mode) = (*max_requests, *mode);
+                    (
+                        Arc::new(move |_: &str| {
+                            Arc::new(SlidingWindowLimiter::with_mode(
+                                duration,
+                                max_requests,
+                                mode,
+                            )) as Arc<dyn Limiter>
+                        }),
+                        "sliding_window",
+                    )
+                }
+                LimiterConfig::FixedWindow {
+                    window_size,
+                    max_requests,
+                } => {
+                    let duration = parse_duration(window_size)?;
+                    let max_requests = *max_requests;
+                    (
+                        Arc::new(move |_: &str| {
+                            Arc::new(FixedWindowLimiter::new(duration, max_requests))
+                                as Arc<dyn Limiter>
+                        }),
+                        "fixed_window",
+                    )
+                }
+                LimiterConfig::Gcra { period, burst } => {
+                    let duration = parse_duration(period)?;
+                    let burst = *burst;
+                    (
+                        Arc::new(move |_: &str| {
+                            Arc::new(GcraLimiter::new(duration, burst)) as Arc<dyn Limiter>
+                        }),
+                        "gcra",
+                    )
+                }
+                LimiterConfig::Quota {
+                    quota_type: _,
+                    limit: _,
+                    window: _,
+                    overdraft: _,
+                } => {
+                    // Quota limiter requires quota-control feature
+                    warn!(
+                        "QuotaLimiter requires 'quota-control' feature to be enabled, \
+                         skipping Quota configuration"
+                    );
+                    continue;
+                }
+                LimiterConfig::Concurrency { max_concurrent } => {
+                    warn!(
+                        "ConcurrencyLimiter not implemented yet, skipping: {}",
+                        max_concurrent
+                    );
+                    continue;
+                }
+                #[cfg(feature = "custom-limiter")]
+                LimiterConfig::Custom { name, config: _ } => {
+                    match custom_limiters.and_then(|registry| registry.factory(name)) {
+                        Some(factory) => (
+                            Arc::new(move |key: &str| {
+                                Arc::new(CustomLimiterAdapter::new(factory(key)))
+                                    as Arc<dyn Limiter>
+                            }),
+                            "custom",
+                        ),
+                        None => {
+                            warn!("CustomLimiter factory not registered, skipping: {}", name);
+                            continue;
+                        }
+                    }
+                }
+                #[cfg(not(feature = "custom-limiter"))]
+                LimiterConfig::Custom { name, config: _ } => {
+                    warn!(
+                        "CustomLimiter requires 'custom-limiter' feature, skipping: {}",
+                        name
+                    );
+                    continue;
+                }
+            };
+
+            // 每个标识符使用独立的限流器实例
+            let node = DecisionNode::keyed(
+                format!("{}_limiter_{}", rule.id, index),
+                format!("{} - {}", rule.name, limiter_type),
+                factory,
+                100u16.saturating_sub(index as u16), // Priority: earlier limiters have higher priority
+            )
+            .with_limiter_type(limiter_type);
+            nodes.push(node);
         }
 
-        Ok(chains)
+        let chain = DecisionChain::new(nodes).with_max_keyed_entries(max_keyed_limiters);
+        #[cfg(feature = "monitoring")]
+        let chain = match metrics {
+            Some(metrics) => chain.with_metrics(rule.id.clone(), metrics.clone()),
+            None => chain,
+        };
+        Ok(chain)
     }
 
     /// 从配置构建规则列表
@@ -247,62 +918,83 @@ impl Governor {
         let mut rules = Vec::new();
 
         for rule_config in &config.rules {
-            let mut conditions: Vec<Box<dyn ConditionEvaluator>> = Vec::new();
-
-            for matcher in &rule_config.matchers {
-                let condition: Box<dyn ConditionEvaluator> = match matcher {
-                    ConfigMatcher::User { user_ids } => {
-                        Box::new(MatchCondition::User(user_ids.clone()))
-                    }
-                    ConfigMatcher::Ip { ip_ranges } => {
-                        let ranges: Result<Vec<IpRange>, _> =
-                            ip_ranges.iter().map(|s| s.parse()).collect();
-                        Box::new(MatchCondition::Ip(ranges?))
-                    }
-                    ConfigMatcher::Geo { countries } => {
-                        Box::new(MatchCondition::Geo(countries.clone()))
-                    }
-                    ConfigMatcher::ApiVersion { versions } => {
-                        Box::new(MatchCondition::ApiVersion(versions.clone()))
-                    }
-                    ConfigMatcher::Device { device_types } => {
-                        Box::new(MatchCondition::Device(device_types.clone()))
-                    }
-                    ConfigMatcher::Custom { name, config: _ } => {
-                        let name = name.clone();
-                        Box::new(MatchCondition::Custom(Arc::new(move |_context| {
-                            tracing::warn!(
-                                "自定义匹配器 '{}' 需要通过CustomMatcherRegistry处理",
-                                name
-                            );
-                            false
-                        })))
-                    }
-                };
-                conditions.push(condition);
+            if let Some(rule) = Self::build_rule(rule_config)? {
+                rules.push(rule);
             }
+        }
 
-            let final_condition: Box<dyn ConditionEvaluator> = if conditions.len() == 1 {
-                conditions.pop().unwrap()
-            } else if conditions.is_empty() {
-                continue;
-            } else {
-                Box::new(CompositeCondition {
-                    conditions,
-                    operator: LogicalOperator::And,
-                })
-            };
+        Ok(rules)
+    }
 
-            rules.push(MatcherRule {
-                id: rule_config.id.clone(),
-                name: rule_config.name.clone(),
-                priority: rule_config.priority,
-                condition: final_condition,
-                enabled: true,
-            });
+    /// 构建单条匹配规则，没有匹配条件时返回 `None`
+    fn build_rule(rule_config: &ConfigRule) -> Result<Option<MatcherRule>, FlowGuardError> {
+        let mut conditions: Vec<Arc<dyn ConditionEvaluator>> = Vec::new();
+
+        for matcher in &rule_config.matchers {
+            let condition: Arc<dyn ConditionEvaluator> = match matcher {
+                ConfigMatcher::User { user_ids } => {
+                    Arc::new(MatchCondition::User(user_ids.clone()))
+                }
+                ConfigMatcher::UserPattern {
+                    user_ids,
+                    case_insensitive,
+                } => Arc::new(MatchCondition::from_config_user_pattern(
+                    user_ids,
+                    *case_insensitive,
+                )),
+                ConfigMatcher::Ip { ip_ranges } => {
+                    let ranges: Result<Vec<IpRange>, _> =
+                        ip_ranges.iter().map(|s| s.parse()).collect();
+                    Arc::new(MatchCondition::Ip(ranges?))
+                }
+                ConfigMatcher::Geo { countries } => {
+                    Arc::new(MatchCondition::Geo(countries.clone()))
+                }
+                ConfigMatcher::ApiVersion { versions } => {
+                    Arc::new(MatchCondition::ApiVersion(versions.clone()))
+                }
+                ConfigMatcher::Device { device_types } => {
+                    Arc::new(MatchCondition::Device(device_types.clone()))
+                }
+                ConfigMatcher::Method { methods } => {
+                    Arc::new(MatchCondition::Method(methods.clone()))
+                }
+                #[cfg(feature = "regex")]
+                ConfigMatcher::PathRegex { pattern } => {
+                    Arc::new(MatchCondition::path_regex(pattern)?)
+                }
+                #[cfg(feature = "regex")]
+                ConfigMatcher::HeaderRegex { name, pattern } => {
+                    Arc::new(MatchCondition::header_regex(name, pattern)?)
+                }
+                ConfigMatcher::Custom { name, config: _ } => {
+                    let name = name.clone();
+                    Arc::new(MatchCondition::Custom(Arc::new(move |_context| {
+                        tracing::warn!("自定义匹配器 '{}' 需要通过CustomMatcherRegistry处理", name);
+                        false
+                    })))
+                }
+            };
+            conditions.push(condition);
         }
 
-        Ok(rules)
+        let final_condition: Arc<dyn ConditionEvaluator> = if conditions.len() == 1 {
+            conditions.pop().unwrap()
+        } else if conditions.is_empty() {
+            return Ok(None);
+        } else {
+            Arc::new(CompositeCondition {
+                conditions,
+                operator: LogicalOperator::And,
+            })
+        };
+        Ok(Some(MatcherRule {
+            id: rule_config.id.clone(),
+            name: rule_config.name.clone(),
+            priority: rule_config.priority,
+            condition: final_condition,
+            enabled: !rule_config.disabled,
+        }))
     }
 
     /// 创建新的 Governor 实例
@@ -335,13 +1027,17 @@ impl Governor {
         let decision_chain = Arc::new(RwLock::new(DecisionChain::new(vec![])));
 
         // 创建熔断器 (仅当 circuit-breaker 特性启用时)
+        // 封禁存储与限流器各自独立计数，一方持续故障不影响另一方
         #[cfg(feature = "circuit-breaker")]
-        let circuit_breaker = Arc::new(CircuitBreaker::new(CircuitBreakerConfig {
-            failure_threshold: 5,
-            success_threshold: 3,
-            timeout: Duration::from_secs(30),
-            half_open_max_calls: 3,
-        }));
+        let new_circuit_breaker = || {
+            Arc::new(CircuitBreaker::new(CircuitBreakerConfig {
+                failure_threshold: 5,
+                success_threshold: 3,
+                timeout: Duration::from_secs(30),
+                half_open_max_calls: 3,
+                trip_policy: crate::circuit_breaker::TripPolicy::ConsecutiveFailures,
+            }))
+        };
 
         // 创建 L2Cache 用于 FallbackManager
         #[cfg(feature = "fallback")]
@@ -353,175 +1049,891 @@ impl Governor {
         #[cfg(feature = "fallback")]
         let fallback_manager = Arc::new(FallbackManager::new(fallback_l2_cache));
 
-        // 创建审计日志记录器 (仅当 audit-log 特性启用时)
-        #[cfg(feature = "audit-log")]
-        let audit_logger = Arc::new(RwLock::new(None));
+        // 创建审计日志记录器 (仅当 audit-log 特性启用时)
+        #[cfg(feature = "audit-log")]
+        let audit_logger = Arc::new(RwLock::new(None));
+
+        // 创建封禁管理器 (仅当 ban-manager 特性启用时)
+        #[cfg(feature = "ban-manager")]
+        let ban_manager = Arc::new(BanManager::new(ban_storage.clone(), None).await?);
+
+        // 创建并行封禁检查器 (仅当 parallel-checker 特性启用时)
+        #[cfg(feature = "parallel-checker")]
+        let parallel_ban_checker = Arc::new(crate::parallel_ban_checker::ParallelBanChecker::new(
+            ban_manager.clone(),
+        ));
+
+        // 创建规则对应的决策链
+        // 未显式传入时使用全局监控指标
+        #[cfg(feature = "monitoring")]
+        let metrics = metrics.or_else(crate::telemetry::try_global);
+        let rule_chains_map = Self::build_rule_chains(
+            &config,
+            DEFAULT_MAX_KEYED_LIMITERS,
+            #[cfg(feature = "monitoring")]
+            metrics.as_ref(),
+            #[cfg(feature = "custom-limiter")]
+            None,
+        )?;
+        let rule_chains = Arc::new(RwLock::new(rule_chains_map));
+
+        #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+        let rejection_tracker = RejectionTracker::default();
+        #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+        rejection_tracker.sync(&config);
+
+        Ok(Self {
+            config: Arc::new(RwLock::new(config)),
+            _storage: storage,
+            _ban_storage: ban_storage,
+            #[cfg(feature = "ban-manager")]
+            ban_manager,
+            #[cfg(feature = "parallel-checker")]
+            parallel_ban_checker,
+            #[cfg(feature = "parallel-checker")]
+            ban_cache: Arc::new(BanCache::new(Duration::from_millis(
+                crate::constants::DEFAULT_BAN_CACHE_TTL_MS,
+            ))),
+            #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+            rejection_tracker,
+            decision_chain,
+            rule_matcher,
+            rule_chains,
+            identifier_extractor,
+            #[cfg(feature = "circuit-breaker")]
+            circuit_breaker: new_circuit_breaker(),
+            #[cfg(feature = "circuit-breaker")]
+            limiter_circuit_breaker: new_circuit_breaker(),
+            #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+            last_known_decisions: LastKnown::default(),
+            #[cfg(feature = "fallback")]
+            fallback_manager,
+            #[cfg(feature = "audit-log")]
+            audit_logger,
+            #[cfg(feature = "custom-limiter")]
+            custom_limiters: Arc::new(RwLock::new(None)),
+            config_history: Arc::new(RwLock::new(ConfigHistory::new(100))),
+            rule_evaluation_policy: parking_lot::RwLock::new(RuleEvaluationPolicy::default()),
+            limiter_key_strategy: parking_lot::RwLock::new(None),
+            max_keyed_limiters: AtomicUsize::new(DEFAULT_MAX_KEYED_LIMITERS),
+            failure_policy: parking_lot::RwLock::new(None),
+            config_source: Arc::new(RwLock::new(None)),
+            allowlist: Arc::new(RwLock::new(Allowlist::default())),
+            #[cfg(feature = "monitoring")]
+            metrics,
+            #[cfg(feature = "telemetry")]
+            tracer,
+            total_requests: AtomicU64::new(0),
+            allowed_requests: AtomicU64::new(0),
+            rejected_requests: AtomicU64::new(0),
+            banned_requests: AtomicU64::new(0),
+            error_count: AtomicU64::new(0),
+            allowlist_bypass_requests: AtomicU64::new(0),
+        })
+    }
+
+    /// 检查请求 - 简化版本使用并行检查器
+    pub async fn check(&self, context: &RequestContext) -> Result<Decision, FlowGuardError> {
+        self.check_detailed(context)
+            .await
+            .map(|result| result.decision)
+    }
+
+    /// 检查请求，并返回最严格限流器的详细决策
+    ///
+    /// 详细决策包含剩余额度、上限和重试时间，用于生成 `X-RateLimit-*`、`Retry-After`
+    /// 等响应头。白名单、封禁或没有限流器提供额度信息时为 `None`。
+    pub async fn check_with_limits(
+        &self,
+        context: &RequestContext,
+    ) -> Result<(Decision, Option<RateLimitDecision>), FlowGuardError> {
+        self.check_detailed(context)
+            .await
+            .map(|result| (result.decision, result.limits))
+    }
+
+    /// 检查请求，并返回产生决策的规则、标识符与耗时
+    #[instrument(skip(self), fields(
+        user_id = %redact_user_id(context.user_id.as_deref()),
+        ip = %redact_ip(context.ip.as_deref()),
+        path = %context.path,
+        method = %context.method
+    ))]
+    pub async fn check_detailed(
+        &self,
+        context: &RequestContext,
+    ) -> Result<CheckResult, FlowGuardError> {
+        let started = std::time::Instant::now();
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+
+        // 延续调用方通过 traceparent / tracestate 传入的 trace
+        #[cfg(feature = "telemetry")]
+        let _span = {
+            use opentelemetry::trace::TraceContextExt;
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+            // 仅在请求携带有效的父 Span 且当前处于 tracing Span 中时才改写父级，
+            // 否则会把调用方已有的父级替换为空上下文
+            let parent = crate::telemetry::extract_trace_context(context);
+            let current = tracing::Span::current();
+            if parent.span().span_context().is_valid() && !current.is_disabled() {
+                current.set_parent(parent);
+            }
+            self.tracer
+                .as_ref()
+                .map(|tracer| tracer.start_span_with_context("governor.check", context))
+        };
+
+        debug!(
+            "开始请求检查: user_id={}, ip={}, path={}, method={}",
+            redact_user_id(context.user_id.as_deref()),
+            redact_ip(context.ip.as_deref()),
+            context.path,
+            context.method
+        );
+
+        // Extracted identifier
+        let Some(identifier) = self.extract_identifier(context) else {
+            return Ok(CheckResult {
+                decision: self.no_identifier_decision(),
+                matched_rule: None,
+                identifier: None,
+                limits: None,
+                elapsed: started.elapsed(),
+            });
+        };
+        trace!("Extracted identifier: {}", identifier.key());
+
+        let result = |decision, matched_rule, limits| CheckResult {
+            decision,
+            matched_rule,
+            identifier: Some(identifier.clone()),
+            limits,
+            elapsed: started.elapsed(),
+        };
+
+        // 白名单检查，命中时跳过封禁与限流
+        if self.is_allowlisted(&identifier).await {
+            self.record_allowlist_bypass(&identifier);
+            return Ok(result(Decision::Allowed(None), None, None));
+        }
+
+        // 并行封禁检查 (仅当 parallel-checker 特性启用时)
+        #[cfg(feature = "parallel-checker")]
+        match self.check_ban(&identifier).await {
+            Ok(Some(info)) => {
+                warn!(
+                    "Request banned: 用户={}, 原因={}",
+                    identifier.key(),
+                    info.reason
+                );
+                self.banned_requests.fetch_add(1, Ordering::Relaxed);
+                return Ok(result(Decision::Banned(info), None, None));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.error_count.fetch_add(1, Ordering::Relaxed);
+                if let Some(decision) = self.apply_failure_policy(BAN_COMPONENT, e).await? {
+                    self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+                    return Ok(result(decision, None, None));
+                }
+            }
+        }
+
+        // 继续其他检查
+        // 规则匹配
+        let matched_rules = {
+            let matcher = self.rule_matcher.read().await;
+            #[allow(clippy::disallowed_methods)]
+            matcher
+                .match_all(context)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let rule_chains = self.rule_chains.read().await;
+        let default_chain = self.decision_chain.read().await;
+        match self
+            .evaluate_rules_guarded(&identifier, matched_rules, &rule_chains, &default_chain)
+            .await
+        {
+            Ok((decision, limits, matched_rule)) => Ok(result(decision, matched_rule, limits)),
+            Err(e) => {
+                let decision = self.apply_failure_policy(LIMITER_COMPONENT, e).await?;
+                Ok(result(self.count_failure_decision(decision), None, None))
+            }
+        }
+    }
+
+    /// 批量检查请求
+    ///
+    /// 按输入顺序返回每个请求的决策，结果与逐个调用 [`check`](Self::check) 一致：
+    /// - 先提取所有请求的标识符，无法提取的请求以 [`RejectReason::NoIdentifier`] 拒绝
+    /// - 启用 `parallel-checker` 时，封禁检查按去重后的标识符并发执行；未启用时与
+    ///   [`check`](Self::check) 相同，不做封禁检查
+    /// - 规则匹配器与决策链在整批中只加锁一次，限流器按输入顺序依次消费
+    ///
+    /// 批量检查仅减少加锁与封禁查询次数，不会把存储访问合并为流水线（pipeline）：
+    /// 各请求的限流判断仍逐个执行，以保证与逐个调用 `check` 的消费顺序一致。
+    #[instrument(skip(self, contexts), fields(batch_size = contexts.len()))]
+    pub async fn check_batch(
+        &self,
+        contexts: &[RequestContext],
+    ) -> Result<Vec<Decision>, FlowGuardError> {
+        let identifiers: Vec<Option<Identifier>> = contexts
+            .iter()
+            .map(|context| self.extract_identifier(context))
+            .collect();
+
+        self.total_requests
+            .fetch_add(contexts.len() as u64, Ordering::Relaxed);
+        debug!("开始批量请求检查: 数量={}", contexts.len());
+
+        let allowlisted: Vec<bool> = {
+            let allowlist = self.allowlist.read().await;
+            identifiers
+                .iter()
+                .map(|identifier| identifier.as_ref().is_some_and(|id| allowlist.contains(id)))
+                .collect()
+        };
+
+        // 并行封禁检查 (仅当 parallel-checker 特性启用时)，跳过白名单中的请求
+        #[cfg(feature = "parallel-checker")]
+        let bans: Vec<BanOutcome> = {
+            let mut seen = ahash::AHashSet::new();
+            let unique: Vec<&Identifier> = identifiers
+                .iter()
+                .zip(&allowlisted)
+                .filter_map(|(identifier, allowlisted)| {
+                    identifier.as_ref().filter(|_| !*allowlisted)
+                })
+                .filter(|identifier| seen.insert(*identifier))
+                .collect();
+
+            let results = futures::future::join_all(
+                unique.iter().map(|identifier| self.check_ban(identifier)),
+            )
+            .await;
+
+            let mut banned = ahash::AHashMap::with_capacity(unique.len());
+            for (identifier, result) in unique.into_iter().zip(results) {
+                let outcome = match result {
+                    Ok(ban) => Ok(ban),
+                    Err(e) => {
+                        self.error_count.fetch_add(1, Ordering::Relaxed);
+                        Err(self.apply_failure_policy(BAN_COMPONENT, e).await?)
+                    }
+                };
+                banned.insert(identifier, outcome);
+            }
+
+            identifiers
+                .iter()
+                .map(|identifier| {
+                    identifier
+                        .as_ref()
+                        .and_then(|id| banned.get(id).cloned())
+                        .unwrap_or(Ok(None))
+                })
+                .collect()
+        };
+        // 封禁检查依赖 parallel-checker；未启用时与 check 一样跳过，视为未封禁
+        #[cfg(not(feature = "parallel-checker"))]
+        let bans: Vec<BanOutcome> = identifiers.iter().map(|_| Ok(None)).collect();
+
+        let matched_rules = {
+            let matcher = self.rule_matcher.read().await;
+            #[allow(clippy::disallowed_methods)]
+            contexts
+                .iter()
+                .zip(&identifiers)
+                .zip(&allowlisted)
+                .map(|((context, identifier), allowlisted)| {
+                    if *allowlisted || identifier.is_none() {
+                        return Vec::new();
+                    }
+                    matcher
+                        .match_all(context)
+                        .into_iter()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let rule_chains = self.rule_chains.read().await;
+        let default_chain = self.decision_chain.read().await;
+
+        let mut decisions = Vec::with_capacity(contexts.len());
+        for (((identifier, allowlisted), ban), rules) in identifiers
+            .iter()
+            .zip(allowlisted)
+            .zip(bans)
+            .zip(matched_rules)
+        {
+            let Some(identifier) = identifier else {
+                decisions.push(self.no_identifier_decision());
+                continue;
+            };
+            if allowlisted {
+                self.record_allowlist_bypass(identifier);
+                decisions.push(Decision::Allowed(None));
+                continue;
+            }
+
+            match ban {
+                Ok(Some(info)) => {
+                    warn!(
+                        "Request banned: 用户={}, 原因={}",
+                        identifier.key(),
+                        info.reason
+                    );
+                    self.banned_requests.fetch_add(1, Ordering::Relaxed);
+                    decisions.push(Decision::Banned(info));
+                    continue;
+                }
+                // 失败关闭
+                Err(Some(decision)) => {
+                    self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+                    decisions.push(decision);
+                    continue;
+                }
+                // 未封禁，或失败开放
+                Ok(None) | Err(None) => {}
+            }
+
+            let decision = match self
+                .evaluate_rules_guarded(identifier, rules, &rule_chains, &default_chain)
+                .await
+            {
+                Ok((decision, ..)) => decision,
+                Err(e) => {
+                    let decision = self.apply_failure_policy(LIMITER_COMPONENT, e).await?;
+                    self.count_failure_decision(decision)
+                }
+            };
+            decisions.push(decision);
+        }
+
+        Ok(decisions)
+    }
+
+    /// 从请求中提取标识符
+    fn extract_identifier(&self, context: &RequestContext) -> Option<Identifier> {
+        self.identifier_extractor.extract(context)
+    }
+
+    /// 限流器故障时按处理策略得出的最终决策
+    ///
+    /// 失败开放放行的请求计入允许数，失败关闭拒绝的请求计入拒绝数，
+    /// 使 [`GovernorStats`] 的各项之和与总请求数一致（故障本身另计入错误数）。
+    fn count_failure_decision(&self, decision: Option<Decision>) -> Decision {
+        match decision {
+            Some(decision) => {
+                self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+                decision
+            }
+            None => {
+                self.allowed_requests.fetch_add(1, Ordering::Relaxed);
+                Decision::Allowed(None)
+            }
+        }
+    }
+
+    /// 无法提取标识符时的拒绝决策，计入拒绝数
+    fn no_identifier_decision(&self) -> Decision {
+        debug!("无法提取请求标识符，拒绝请求");
+        self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+        Decision::rejected(RejectReason::NoIdentifier, "无法提取请求标识符")
+    }
+
+    /// 设置白名单，替换已有条目
+    ///
+    /// IP 标识符可以是单个 IP、CIDR（如 `10.0.0.0/8`）或范围（如 `10.0.0.1-10.0.0.9`）。
+    /// 任一条目无效时返回错误，原白名单保持不变。
+    #[instrument(skip(self, identifiers))]
+    pub async fn set_allowlist(&self, identifiers: Vec<Identifier>) -> Result<(), FlowGuardError> {
+        let mut allowlist = Allowlist::default();
+        for identifier in identifiers {
+            allowlist.insert(identifier)?;
+        }
+        *self.allowlist.write().await = allowlist;
+        info!("白名单已更新");
+        Ok(())
+    }
+
+    /// 添加白名单条目
+    #[instrument(skip(self))]
+    pub async fn add_to_allowlist(&self, identifier: Identifier) -> Result<(), FlowGuardError> {
+        self.allowlist.write().await.insert(identifier)
+    }
+
+    /// 移除白名单条目
+    ///
+    /// IP 条目需与添加时的字符串一致。返回是否存在并被移除。
+    #[instrument(skip(self))]
+    pub async fn remove_from_allowlist(&self, identifier: &Identifier) -> bool {
+        self.allowlist.write().await.remove(identifier)
+    }
+
+    /// 检查请求是否命中白名单
+    ///
+    /// 只比对由标识符提取器提取出的标识符（同时也是封禁检查的目标），
+    /// 不比对请求中客户端可自行填写的其他字段，避免伪造字段绕过限流与封禁。
+    async fn is_allowlisted(&self, identifier: &Identifier) -> bool {
+        self.allowlist.read().await.contains(identifier)
+    }
+
+    /// 记录白名单放行
+    fn record_allowlist_bypass(&self, identifier: &Identifier) {
+        debug!("Allowlist bypass: {}", identifier.key());
+        self.allowlist_bypass_requests
+            .fetch_add(1, Ordering::Relaxed);
+        self.allowed_requests.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "monitoring")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_allowlist_bypass();
+        }
+    }
+
+    /// 检查标识符是否被封禁
+    ///
+    /// 优先查询封禁缓存，未命中时查询存储并缓存结果（包括未封禁）。
+    #[cfg(feature = "parallel-checker")]
+    async fn check_ban(&self, identifier: &Identifier) -> Result<Option<BanInfo>, FlowGuardError> {
+        // 尝试转换为 BanTarget 进行检查
+        let ban_target = match identifier {
+            Identifier::UserId(id) => BanTarget::UserId(id.clone()),
+            Identifier::Ip(ip) => BanTarget::Ip(ip.clone()),
+            Identifier::Mac(mac) => BanTarget::Mac(mac.clone()),
+            _ => return Ok(None),
+        };
+
+        if let Some(cached) = self.ban_cache.get(identifier) {
+            trace!("封禁缓存命中: {}", identifier.key());
+            return Ok(cached);
+        }
+
+        // 使用专门的并行封禁检查器，经熔断器访问封禁存储
+        #[cfg(feature = "circuit-breaker")]
+        let result = match self
+            .circuit_breaker
+            .call(|| self.parallel_ban_checker.check_single_target(&ban_target))
+            .await
+        {
+            Ok(result) => result,
+            Err(CircuitBreakerError::Inner(e)) => return Err(e),
+            Err(CircuitBreakerError::Open) => return self.check_ban_circuit_open(identifier).await,
+        };
+        #[cfg(not(feature = "circuit-breaker"))]
+        let result = self
+            .parallel_ban_checker
+            .check_single_target(&ban_target)
+            .await?;
+        self.ban_cache.insert(identifier, result.clone());
+        Ok(result)
+    }
+
+    /// 封禁存储熔断期间的封禁检查
+    ///
+    /// 不访问存储，按封禁组件的降级策略取结果：`Degraded` 使用封禁缓存（忽略 TTL），
+    /// `UseLastKnown` 使用该标识符最近一次查询结果；未启用 `fallback` 特性时使用封禁缓存。
+    /// 取不到结果（包括 `FailOpen` / `FailClosed` 策略）时返回熔断错误，由调用方按
+    /// 故障处理策略处理。
+    #[cfg(all(feature = "parallel-checker", feature = "circuit-breaker"))]
+    async fn check_ban_circuit_open(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Option<BanInfo>, FlowGuardError> {
+        self.record_circuit_open(BAN_COMPONENT);
+
+        #[cfg(feature = "fallback")]
+        let cached = {
+            use crate::fallback::{ComponentType, FallbackStrategy};
+
+            self.fallback_manager
+                .record_failure(ComponentType::Ban, "circuit open")
+                .await;
+            match self
+                .fallback_manager
+                .active_strategy(&ComponentType::Ban)
+                .await
+            {
+                Some(FallbackStrategy::Degraded) => self.ban_cache.get_stale(identifier),
+                Some(FallbackStrategy::UseLastKnown) => self.ban_cache.get_last_known(identifier),
+                _ => None,
+            }
+        };
+        #[cfg(not(feature = "fallback"))]
+        let cached = self.ban_cache.get_stale(identifier);
+
+        match cached {
+            Some(cached) => {
+                debug!("封禁存储熔断，使用缓存结果: {}", identifier.key());
+                Ok(cached)
+            }
+            None => Err(CircuitBreakerError::<FlowGuardError>::Open.into()),
+        }
+    }
+
+    /// 经限流器熔断器执行规则评估
+    ///
+    /// 限流器连续故障达到阈值后熔断器打开，之后的评估直接短路：限流器组件的降级策略为
+    /// `UseLastKnown` 时复用该标识符最近一次决策，否则返回
+    /// [`FlowGuardError::CircuitBreakerError`]，由调用方按故障处理策略处理。
+    async fn evaluate_rules_guarded(
+        &self,
+        identifier: &Identifier,
+        matched_rules: Vec<MatcherRule>,
+        rule_chains: &DashMap<String, DecisionChain>,
+        default_chain: &DecisionChain,
+    ) -> Result<(Decision, Option<RateLimitDecision>, Option<String>), FlowGuardError> {
+        #[cfg(feature = "circuit-breaker")]
+        {
+            let result = self
+                .limiter_circuit_breaker
+                .call(|| self.evaluate_rules(identifier, matched_rules, rule_chains, default_chain))
+                .await;
+            match result {
+                Ok(result) => {
+                    #[cfg(feature = "fallback")]
+                    if self.limiter_uses_last_known().await {
+                        self.last_known_decisions
+                            .record(identifier, result.0.clone());
+                    } else {
+                        self.last_known_decisions.clear();
+                    }
+                    Ok(result)
+                }
+                Err(CircuitBreakerError::Open) => {
+                    self.record_circuit_open(LIMITER_COMPONENT);
+                    #[cfg(feature = "fallback")]
+                    if let Some(decision) = self.last_known_decision(identifier).await {
+                        return Ok((decision, None, None));
+                    }
+                    Err(CircuitBreakerError::<FlowGuardError>::Open.into())
+                }
+                Err(CircuitBreakerError::Inner(e)) => Err(e),
+            }
+        }
+        #[cfg(not(feature = "circuit-breaker"))]
+        self.evaluate_rules(identifier, matched_rules, rule_chains, default_chain)
+            .await
+    }
+
+    /// 限流器熔断期间按 `UseLastKnown` 策略复用的决策
+    #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+    async fn last_known_decision(&self, identifier: &Identifier) -> Option<Decision> {
+        if !self.limiter_uses_last_known().await {
+            return None;
+        }
+        let decision = self.last_known_decisions.get(identifier)?;
+        debug!("限流器熔断，复用最近一次决策: {}", identifier.key());
+        Some(decision)
+    }
+
+    /// 限流器组件当前启用的降级策略是否为 `UseLastKnown`
+    #[cfg(all(feature = "fallback", feature = "circuit-breaker"))]
+    async fn limiter_uses_last_known(&self) -> bool {
+        use crate::fallback::{ComponentType, FallbackStrategy};
 
-        // 创建封禁管理器 (仅当 ban-manager 特性启用时)
-        #[cfg(feature = "ban-manager")]
-        let ban_manager = Arc::new(BanManager::new(ban_storage.clone(), None).await?);
+        self.fallback_manager
+            .active_strategy(&ComponentType::Limiter)
+            .await
+            == Some(FallbackStrategy::UseLastKnown)
+    }
 
-        // 创建并行封禁检查器 (仅当 parallel-checker 特性启用时)
-        #[cfg(feature = "parallel-checker")]
-        let parallel_ban_checker = Arc::new(crate::parallel_ban_checker::ParallelBanChecker::new(
-            ban_manager.clone(),
-        ));
+    /// 记录一次熔断短路
+    #[cfg(feature = "circuit-breaker")]
+    fn record_circuit_open(&self, component: &'static str) {
+        warn!(component, "熔断器打开，跳过存储访问");
 
-        // 创建规则对应的决策链
-        let rule_chains_map = Self::build_rule_chains(&config)?;
-        let rule_chains = Arc::new(RwLock::new(rule_chains_map));
+        #[cfg(feature = "monitoring")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_circuit_open(component);
+        }
+    }
 
-        Ok(Self {
-            config: Arc::new(RwLock::new(config)),
-            _storage: storage,
-            _ban_storage: ban_storage,
-            #[cfg(feature = "ban-manager")]
-            ban_manager,
-            #[cfg(feature = "parallel-checker")]
-            parallel_ban_checker,
-            decision_chain,
-            rule_matcher,
-            rule_chains,
-            identifier_extractor,
-            #[cfg(feature = "circuit-breaker")]
-            circuit_breaker,
-            #[cfg(feature = "fallback")]
-            _fallback_manager: fallback_manager,
-            #[cfg(feature = "audit-log")]
-            audit_logger,
-            config_history: Arc::new(RwLock::new(ConfigHistory::new(100))),
-            total_requests: AtomicU64::new(0),
-            allowed_requests: AtomicU64::new(0),
-            rejected_requests: AtomicU64::new(0),
-            banned_requests: AtomicU64::new(0),
-            error_count: AtomicU64::new(0),
-        })
+    /// 封禁存储熔断器
+    ///
+    /// 请求检查中的封禁查询经此熔断器访问存储，连续失败 5 次后打开 30 秒；
+    /// 打开期间封禁检查按封禁组件的降级策略决策，见 [`crate::fallback`]。
+    #[cfg(feature = "circuit-breaker")]
+    pub fn circuit_breaker(&self) -> &Arc<CircuitBreaker> {
+        &self.circuit_breaker
     }
 
-    /// 检查请求 - 简化版本使用并行检查器
-    #[instrument(skip(self), fields(
-        user_id = %redact_user_id(context.user_id.as_deref()),
-        ip = %redact_ip(context.ip.as_deref()),
-        path = %context.path,
-        method = %context.method
-    ))]
-    pub async fn check(&self, context: &RequestContext) -> Result<Decision, FlowGuardError> {
-        self.total_requests.fetch_add(1, Ordering::Relaxed);
+    /// 限流器熔断器
+    ///
+    /// 请求检查中的规则评估经此熔断器执行，打开期间按限流器组件的降级策略决策。
+    #[cfg(feature = "circuit-breaker")]
+    pub fn limiter_circuit_breaker(&self) -> &Arc<CircuitBreaker> {
+        &self.limiter_circuit_breaker
+    }
 
-        debug!(
-            "开始请求检查: user_id={}, ip={}, path={}, method={}",
-            redact_user_id(context.user_id.as_deref()),
-            redact_ip(context.ip.as_deref()),
-            context.path,
-            context.method
-        );
+    /// 设置封禁检查结果的缓存有效期
+    ///
+    /// 默认 [`DEFAULT_BAN_CACHE_TTL_MS`](crate::constants::DEFAULT_BAN_CACHE_TTL_MS)，
+    /// 设为零时关闭缓存。直接通过 [`BanManager`] 修改的封禁最多延迟一个 TTL 生效，
+    /// 通过 [`ban_identifier`](Self::ban_identifier) / [`unban_identifier`](Self::unban_identifier)
+    /// 修改的封禁立即生效。
+    #[cfg(feature = "parallel-checker")]
+    pub fn set_ban_cache_ttl(&self, ttl: Duration) {
+        self.ban_cache.set_ttl(ttl);
+    }
 
-        // Extracted identifier
-        let identifier = self.identifier_extractor.extract(context).ok_or_else(|| {
-            FlowGuardError::ConfigError("Failed to extract identifier".to_string())
-        })?;
-        trace!("Extracted identifier: {}", identifier.key());
+    /// 设置存储或限流器故障时的处理策略，同时作用于封禁检查与限流器
+    ///
+    /// 未设置时封禁检查故障失败开放，限流器故障返回给调用方。
+    ///
+    /// 启用 `fallback` 特性时，[`FallbackManager`] 中为对应组件（封禁检查为
+    /// `ComponentType::Ban`，限流器为 `ComponentType::Limiter`）启用的
+    /// `FailOpen` / `FailClosed` 策略优先于此设置。
+    pub fn set_failure_policy(&self, policy: FailurePolicy) {
+        *self.failure_policy.write() = Some(policy);
+    }
 
-        // 并行封禁检查 (仅当 parallel-checker 特性启用时)
-        #[cfg(feature = "parallel-checker")]
-        {
-            // 尝试转换为 BanTarget 进行检查
-            let ban_target = match &identifier {
-                Identifier::UserId(id) => Some(BanTarget::UserId(id.clone())),
-                Identifier::Ip(ip) => Some(BanTarget::Ip(ip.clone())),
-                Identifier::Mac(mac) => Some(BanTarget::Mac(mac.clone())),
+    /// 当前的故障处理策略，未设置时返回 `None`
+    pub fn failure_policy(&self) -> Option<FailurePolicy> {
+        *self.failure_policy.read()
+    }
+
+    /// 获取降级策略管理器
+    #[cfg(feature = "fallback")]
+    pub fn fallback_manager(&self) -> &Arc<FallbackManager> {
+        &self.fallback_manager
+    }
+
+    /// 按故障处理策略处理存储或限流器错误
+    ///
+    /// 返回 `Err` 表示错误需返回给调用方；`Ok(None)` 表示失败开放；
+    /// `Ok(Some(decision))` 为失败关闭时的拒绝决策。
+    async fn apply_failure_policy(
+        &self,
+        component: &'static str,
+        error: FlowGuardError,
+    ) -> Result<Option<Decision>, FlowGuardError> {
+        #[cfg(feature = "fallback")]
+        let fallback_policy = {
+            use crate::fallback::{ComponentType, FallbackStrategy};
+
+            let component_type = ComponentType::from(component);
+            self.fallback_manager
+                .record_failure(component_type.clone(), &error.to_string())
+                .await;
+            match self.fallback_manager.active_strategy(&component_type).await {
+                Some(FallbackStrategy::FailOpen) => Some(FailurePolicy::Open),
+                Some(FallbackStrategy::FailClosed) => Some(FailurePolicy::Closed),
                 _ => None,
-            };
+            }
+        };
+        #[cfg(not(feature = "fallback"))]
+        let fallback_policy = None;
 
-            if let Some(target) = ban_target {
-                // 使用专门的并行封禁检查器
-                let ban_info = self
-                    .parallel_ban_checker
-                    .check_single_target(&target)
-                    .await?;
+        let policy = fallback_policy
+            .or_else(|| self.failure_policy())
+            .unwrap_or_else(|| FailurePolicy::default_for(component));
 
-                if let Some(info) = ban_info {
-                    warn!(
-                        "Request banned: 用户={}, 原因={}",
-                        identifier.key(),
-                        info.reason
-                    );
-                    self.banned_requests.fetch_add(1, Ordering::Relaxed);
-                    return Ok(Decision::Banned(info));
-                }
+        #[cfg(feature = "monitoring")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_storage_error(component, policy.as_str());
+        }
+
+        match policy {
+            FailurePolicy::Propagate => Err(error),
+            FailurePolicy::Open => {
+                warn!(component, error = %error, "存储故障，按失败开放策略放行");
+                Ok(None)
+            }
+            FailurePolicy::Closed => {
+                warn!(component, error = %error, "存储故障，按失败关闭策略拒绝");
+                Ok(Some(Decision::rejected(
+                    RejectReason::StorageUnavailable,
+                    format!("存储故障: {}", error),
+                )))
             }
         }
+    }
 
-        // 继续其他检查
-        // 规则匹配
-        let matched_rules = {
-            let matcher = self.rule_matcher.read().await;
-            #[allow(clippy::disallowed_methods)]
-            matcher
-                .match_all(context)
-                .into_iter()
-                .cloned()
-                .collect::<Vec<_>>()
-        };
+    /// 设置多条规则同时匹配时的处理策略，默认 [`RuleEvaluationPolicy::AllMatch`]
+    pub fn set_rule_evaluation_policy(&self, policy: RuleEvaluationPolicy) {
+        *self.rule_evaluation_policy.write() = policy;
+    }
+
+    /// 当前的多规则匹配策略
+    pub fn rule_evaluation_policy(&self) -> RuleEvaluationPolicy {
+        *self.rule_evaluation_policy.read()
+    }
+
+    /// 设置限流键清洗策略，使限流键与 `flow_control` 宏生成的键一致
+    ///
+    /// 默认直接以标识符的键（如 `user_id:alice`）隔离限流状态；启用后按
+    /// [`sanitize_key`] 清洗，已有标识符的限流状态不会迁移到新键。
+    pub fn set_limiter_key_strategy(&self, strategy: KeyStrategy) {
+        *self.limiter_key_strategy.write() = Some(strategy);
+    }
+
+    /// 当前的限流键清洗策略，未设置时返回 `None`
+    pub fn limiter_key_strategy(&self) -> Option<KeyStrategy> {
+        *self.limiter_key_strategy.read()
+    }
+
+    /// 设置每条规则按标识符缓存的限流器数量上限
+    ///
+    /// 默认 [`DEFAULT_MAX_KEYED_LIMITERS`]。超出上限时淘汰最久未使用的标识符，
+    /// 被淘汰的标识符再次访问时从满额度重新开始；不同标识符数量可能超过默认上限时
+    /// （如按 IP 限流）应调大该值，否则轮换大量标识符的调用方会把其他调用方的限流状态挤出。
+    /// 已有决策链原地调整上限，之后重新加载的规则同样使用该上限。
+    pub async fn set_max_keyed_limiters(&self, max_entries: usize) {
+        self.max_keyed_limiters
+            .store(max_entries, Ordering::Relaxed);
+        for entry in self.rule_chains.read().await.iter() {
+            entry.value().set_max_keyed_entries(max_entries);
+        }
+    }
+
+    /// 每条规则按标识符缓存的限流器数量上限
+    pub fn max_keyed_limiters(&self) -> usize {
+        self.max_keyed_limiters.load(Ordering::Relaxed)
+    }
 
+    /// 依次执行匹配规则的决策链并更新统计
+    ///
+    /// `matched_rules` 按优先级从高到低排列；[`RuleEvaluationPolicy::FirstMatch`]
+    /// 下只执行第一条。返回值的第三项为产生决策的规则ID，见 [`CheckResult::matched_rule`]。
+    async fn evaluate_rules(
+        &self,
+        identifier: &Identifier,
+        mut matched_rules: Vec<MatcherRule>,
+        rule_chains: &DashMap<String, DecisionChain>,
+        default_chain: &DecisionChain,
+    ) -> Result<(Decision, Option<RateLimitDecision>, Option<String>), FlowGuardError> {
         if matched_rules.is_empty() {
             // 如果没有匹配的规则，检查默认决策链
             // 目前默认决策链为空，相当于直接允许
-            let result = self.decision_chain.read().await.check().await;
+            let result = default_chain.check_with_limits(None).await;
             match &result {
-                Ok(Decision::Allowed(_)) => {
+                Ok((Decision::Allowed(_), _)) => {
+                    self.allowed_requests.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok((Decision::Delayed(_), _)) => {
                     self.allowed_requests.fetch_add(1, Ordering::Relaxed);
                 }
-                Ok(Decision::Banned(_)) => {
+                Ok((Decision::Banned(_), _)) => {
                     self.banned_requests.fetch_add(1, Ordering::Relaxed);
                 }
-                Ok(Decision::Rejected(_)) => {
+                Ok((Decision::Rejected(_), _)) => {
                     self.rejected_requests.fetch_add(1, Ordering::Relaxed);
                 }
                 Err(_) => {
                     self.error_count.fetch_add(1, Ordering::Relaxed);
                 }
             }
-            return result;
+            return result.map(|(decision, limits)| (decision, limits, None));
         }
 
-        // 有匹配的规则，按顺序执行（级联）
-        // 只要有一个规则拒绝，请求就被拒绝
-        let rule_chains = self.rule_chains.read().await;
+        if self.rule_evaluation_policy() == RuleEvaluationPolicy::FirstMatch {
+            matched_rules.truncate(1);
+        }
+
+        // 放行时归因于优先级最高的匹配规则
+        let first_rule = matched_rules.first().map(|rule| rule.id.clone());
+        let limiter_key = match self.limiter_key_strategy() {
+            Some(strategy) => sanitize_key(&identifier.key(), strategy),
+            None => identifier.key(),
+        };
 
+        // 有匹配的规则，按顺序执行（级联）
+        // 只要有一个规则拒绝，请求就被拒绝；软限流规则超限时记录延迟并继续检查
+        let mut tightest = None;
+        #[cfg(feature = "soft-limit")]
+        let mut delay: Option<(Duration, String)> = None;
         for rule in matched_rules {
             if let Some(chain) = rule_chains.get(&rule.id) {
-                // 执行决策链
-                let result = chain.check().await;
+                // 执行决策链，按标识符隔离限流状态
+                #[cfg(feature = "monitoring")]
+                let started = std::time::Instant::now();
+                let result = chain.check_with_limits(Some(&limiter_key)).await;
+                #[cfg(feature = "monitoring")]
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_rule_check(&rule.id, started.elapsed());
+                }
 
                 match result {
-                    Ok(Decision::Allowed(_)) => {
+                    Ok((Decision::Allowed(_), limits)) => {
                         // 当前规则允许，继续检查下一个规则
+                        if let Some(limits) = limits {
+                            tightest = tighter_limits(tightest, limits);
+                        }
                         continue;
                     }
-                    _ => {
-                        // 拒绝、封禁或错误，直接返回
-                        match &result {
-                            Ok(Decision::Rejected(_)) => {
-                                self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+                    Ok((Decision::Rejected(rejection), limits)) => {
+                        #[cfg(all(feature = "ban-manager", not(feature = "soft-limit")))]
+                        self.record_rejection(&rule.id, identifier).await;
+                        #[cfg(feature = "soft-limit")]
+                        if let Some(rule_delay) = self.record_rejection(&rule.id, identifier).await
+                        {
+                            if delay.as_ref().is_none_or(|(max, _)| *max < rule_delay) {
+                                delay = Some((rule_delay, rule.id.clone()));
                             }
-                            Ok(Decision::Banned(_)) => {
-                                self.banned_requests.fetch_add(1, Ordering::Relaxed);
+                            if let Some(limits) = limits {
+                                tightest = tighter_limits(tightest, limits);
                             }
-                            Err(_) => {
-                                self.error_count.fetch_add(1, Ordering::Relaxed);
-                            }
-                            _ => {}
+                            continue;
                         }
-                        return result;
+                        self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+                        return Ok((Decision::Rejected(rejection), limits, Some(rule.id)));
+                    }
+                    Ok((decision, limits)) => {
+                        // 封禁，直接返回
+                        self.banned_requests.fetch_add(1, Ordering::Relaxed);
+                        return Ok((decision, limits, Some(rule.id)));
+                    }
+                    Err(e) => {
+                        self.error_count.fetch_add(1, Ordering::Relaxed);
+                        return Err(e);
                     }
                 }
             }
         }
 
-        // 所有规则都允许
+        // 所有规则都允许（软限流超限的请求同样计入允许数）
         self.allowed_requests.fetch_add(1, Ordering::Relaxed);
-        Ok(Decision::Allowed(None))
+        #[cfg(feature = "soft-limit")]
+        if let Some((delay, rule_id)) = delay {
+            debug!("软限流: 标识符 {} 延迟 {:?}", identifier.key(), delay);
+            return Ok((Decision::Delayed(delay), tightest, Some(rule_id)));
+        }
+        Ok((Decision::Allowed(None), tightest, first_rule))
+    }
+
+    /// 记录规则拒绝，按规则动作自动封禁或计算软限流延迟
+    ///
+    /// 返回 `Some(delay)` 表示规则为软限流（`on_exceed = "delay"`），请求改为延迟放行；
+    /// 延迟为单次延迟乘以窗口内的超限次数，不超过延迟上限。本次拒绝触发封禁时返回 `None`。
+    #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+    async fn record_rejection(&self, rule_id: &str, identifier: &Identifier) -> Option<Duration> {
+        let key = identifier.key();
+        let (count, policy) = self.rejection_tracker.record(rule_id, &key)?;
+
+        #[cfg(feature = "ban-manager")]
+        if policy
+            .ban_after
+            .is_some_and(|threshold| count >= threshold as usize)
+        {
+            self.rejection_tracker.clear(rule_id, &key);
+            self.ban_after_rejections(rule_id, identifier).await;
+            return None;
+        }
+
+        #[cfg(feature = "soft-limit")]
+        return policy.delay.map(|(step, max)| {
+            step.saturating_mul(u32::try_from(count).unwrap_or(u32::MAX))
+                .min(max)
+        });
+        #[cfg(not(feature = "soft-limit"))]
+        None
     }
 
     /// 并行资源检查 - 保持原有接口兼容性
@@ -567,14 +1979,7 @@ impl Governor {
     ) -> Result<(), FlowGuardError> {
         debug!("Ban user: {} 原因: {}", identifier.key(), reason);
 
-        let ban_target = match identifier {
-            Identifier::UserId(id) => Some(BanTarget::UserId(id.clone())),
-            Identifier::Ip(ip) => Some(BanTarget::Ip(ip.clone())),
-            Identifier::Mac(mac) => Some(BanTarget::Mac(mac.clone())),
-            _ => None,
-        };
-
-        if let Some(target) = ban_target {
+        if let Some(target) = Self::ban_target(identifier) {
             let ban_source = match source {
                 Some(ChangeSource::Manual { operator }) => BanSource::Manual { operator },
                 _ => BanSource::Manual {
@@ -582,7 +1987,8 @@ impl Governor {
                 },
             };
 
-            self.ban_manager
+            let detail = self
+                .ban_manager
                 .create_ban(
                     target,
                     reason.to_string(),
@@ -591,7 +1997,13 @@ impl Governor {
                     None,
                 )
                 .await?;
-            info!("用户 {} 已被封禁", identifier.key());
+            #[cfg(feature = "parallel-checker")]
+            self.ban_cache.invalidate(identifier);
+            if detail.is_some() {
+                info!("用户 {} 已被封禁", identifier.key());
+            } else {
+                info!("用户 {} 的封禁被预封禁钩子跳过", identifier.key());
+            }
         } else {
             return Err(FlowGuardError::ValidationError(
                 "Unsupported identifier type".to_string(),
@@ -601,23 +2013,70 @@ impl Governor {
         Ok(())
     }
 
-    /// 取消用户封禁
+    /// 标识符对应的封禁目标，不支持封禁的标识符类型返回 `None`
     #[cfg(feature = "ban-manager")]
-    #[instrument(skip(self))]
-    pub async fn unban_identifier(&self, identifier: &Identifier) -> Result<(), FlowGuardError> {
-        debug!("取消Ban user: {}", identifier.key());
+    fn ban_target(identifier: &Identifier) -> Option<crate::storage::BanTarget> {
+        use crate::storage::BanTarget;
 
-        let ban_target = match identifier {
+        match identifier {
             Identifier::UserId(id) => Some(BanTarget::UserId(id.clone())),
             Identifier::Ip(ip) => Some(BanTarget::Ip(ip.clone())),
             Identifier::Mac(mac) => Some(BanTarget::Mac(mac.clone())),
             _ => None,
+        }
+    }
+
+    /// 规则拒绝次数达到阈值后自动封禁
+    ///
+    /// 封禁时长由封禁管理器按违规次数退避计算；封禁失败只记录日志，不影响本次决策。
+    #[cfg(feature = "ban-manager")]
+    async fn ban_after_rejections(&self, rule_id: &str, identifier: &Identifier) {
+        let Some(target) = Self::ban_target(identifier) else {
+            debug!(
+                "标识符 {} 不支持封禁，跳过规则 {} 的自动封禁",
+                identifier.key(),
+                rule_id
+            );
+            return;
         };
 
-        if let Some(target) = ban_target {
+        let result = self
+            .ban_manager
+            .create_ban(
+                target,
+                format!("规则 {} 拒绝次数达到阈值", rule_id),
+                BanSource::Auto,
+                serde_json::json!({ "rule_id": rule_id }),
+                None,
+            )
+            .await;
+        match result {
+            Ok(Some(_)) => {
+                #[cfg(feature = "parallel-checker")]
+                self.ban_cache.invalidate(identifier);
+                warn!(
+                    "标识符 {} 触发规则 {} 的拒绝阈值，已自动封禁",
+                    identifier.key(),
+                    rule_id
+                );
+            }
+            Ok(None) => info!("标识符 {} 的自动封禁被预封禁钩子跳过", identifier.key()),
+            Err(e) => warn!("标识符 {} 自动封禁失败: {}", identifier.key(), e),
+        }
+    }
+
+    /// 取消用户封禁
+    #[cfg(feature = "ban-manager")]
+    #[instrument(skip(self))]
+    pub async fn unban_identifier(&self, identifier: &Identifier) -> Result<(), FlowGuardError> {
+        debug!("取消Ban user: {}", identifier.key());
+
+        if let Some(target) = Self::ban_target(identifier) {
             self.ban_manager
                 .delete_ban(&target, "admin".to_string())
                 .await?;
+            #[cfg(feature = "parallel-checker")]
+            self.ban_cache.invalidate(identifier);
             info!("用户 {} 封禁已取消", identifier.key());
         } else {
             return Err(FlowGuardError::ValidationError(
@@ -635,17 +2094,21 @@ impl Governor {
 
         // 更新规则匹配器
         let rules = Self::build_rules(&new_config)?;
-        {
-            let mut matcher = self.rule_matcher.write().await;
-            *matcher = RuleMatcher::new(rules);
-        }
+        self.rebuild_rule_matcher(rules).await;
 
         // 更新规则决策链
-        let chains = Self::build_rule_chains(&new_config)?;
-        {
-            let mut rule_chains = self.rule_chains.write().await;
-            *rule_chains = chains;
-        }
+        let chains = Self::build_rule_chains(
+            &new_config,
+            self.max_keyed_limiters(),
+            #[cfg(feature = "monitoring")]
+            self.metrics.as_ref(),
+            #[cfg(feature = "custom-limiter")]
+            self.custom_limiters.read().await.as_deref(),
+        )?;
+        self.replace_rule_chains(chains).await;
+
+        #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+        self.rejection_tracker.sync(&new_config);
 
         let mut config = self.config.write().await;
         *config = new_config;
@@ -664,35 +2127,184 @@ impl Governor {
 
         // 更新规则匹配器
         let rules = Self::build_rules(&new_config)?;
+        self.rebuild_rule_matcher(rules).await;
+
+        // 更新规则决策链
+        let chains = Self::build_rule_chains(
+            &new_config,
+            self.max_keyed_limiters(),
+            #[cfg(feature = "monitoring")]
+            self.metrics.as_ref(),
+            #[cfg(feature = "custom-limiter")]
+            self.custom_limiters.read().await.as_deref(),
+        )?;
+        self.replace_rule_chains(chains).await;
+
+        #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+        self.rejection_tracker.sync(&new_config);
+
+        let mut config = self.config.write().await;
+        *config = new_config;
+
+        Ok(())
+    }
+
+    /// 新增或替换单条规则
+    ///
+    /// 只更新该规则的匹配条件与决策链，其他规则的限流状态保持不变；
+    /// 限流器配置未变化时沿用原有决策链。变更写入配置历史。
+    #[instrument(skip(self, rule), fields(rule_id = %rule.id))]
+    pub async fn upsert_rule(&self, rule: ConfigRule) -> Result<(), FlowGuardError> {
+        let mut config = self.config.write().await;
+        let mut new_config = config.clone();
+        let previous = match new_config.rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => Some(std::mem::replace(existing, rule.clone())),
+            None => {
+                new_config.rules.push(rule.clone());
+                None
+            }
+        };
+        new_config.validate().map_err(FlowGuardError::ConfigError)?;
+
+        let matcher_rule = Self::build_rule(&rule)?;
+        let chain = match &previous {
+            Some(previous) if previous.limiters == rule.limiters => None,
+            _ => Some(Self::build_rule_chain(
+                &rule,
+                self.max_keyed_limiters(),
+                #[cfg(feature = "monitoring")]
+                self.metrics.as_ref(),
+                #[cfg(feature = "custom-limiter")]
+                self.custom_limiters.read().await.as_deref(),
+            )?),
+        };
+
+        // 先替换决策链再更新匹配器，保证命中的规则总有对应的决策链
+        if let Some(chain) = chain {
+            let replaced = self.rule_chains.read().await.insert(rule.id.clone(), chain);
+            self.retire_chain_stats(replaced.as_ref()).await;
+        }
         {
             let mut matcher = self.rule_matcher.write().await;
-            *matcher = RuleMatcher::new(rules);
+            matcher.remove_rule(&rule.id);
+            if let Some(matcher_rule) = matcher_rule {
+                matcher.add_rule(matcher_rule);
+            }
         }
 
-        // 更新规则决策链
-        let chains = Self::build_rule_chains(&new_config)?;
-        {
-            let mut rule_chains = self.rule_chains.write().await;
-            *rule_chains = chains;
+        info!(
+            "规则 {} 已{}",
+            rule.id,
+            if previous.is_some() {
+                "更新"
+            } else {
+                "新增"
+            }
+        );
+        self.commit_rule_change(&mut config, new_config).await;
+        Ok(())
+    }
+
+    /// 移除单条规则及其限流状态
+    ///
+    /// 规则不存在时返回 `false`；移除后配置不再有效（如没有剩余规则）时返回错误。
+    #[instrument(skip(self))]
+    pub async fn remove_rule(&self, rule_id: &str) -> Result<bool, FlowGuardError> {
+        let mut config = self.config.write().await;
+        if !config.rules.iter().any(|r| r.id == rule_id) {
+            return Ok(false);
         }
 
+        let mut new_config = config.clone();
+        new_config.rules.retain(|r| r.id != rule_id);
+        new_config.validate().map_err(FlowGuardError::ConfigError)?;
+
+        self.rule_matcher.write().await.remove_rule(rule_id);
+        let removed = self.rule_chains.read().await.remove(rule_id);
+        self.retire_chain_stats(removed.as_ref().map(|(_, chain)| chain))
+            .await;
+
+        info!("规则 {} 已移除", rule_id);
+        self.commit_rule_change(&mut config, new_config).await;
+        Ok(true)
+    }
+
+    /// 启用或禁用单条规则
+    ///
+    /// 禁用的规则不参与匹配，但保留限流状态，重新启用后继续生效。
+    /// 规则不存在时返回 `false`。
+    #[instrument(skip(self))]
+    pub async fn set_rule_enabled(
+        &self,
+        rule_id: &str,
+        enabled: bool,
+    ) -> Result<bool, FlowGuardError> {
         let mut config = self.config.write().await;
+        let mut new_config = config.clone();
+        let Some(rule) = new_config.rules.iter_mut().find(|r| r.id == rule_id) else {
+            return Ok(false);
+        };
+        if rule.disabled != enabled {
+            return Ok(true);
+        }
+        rule.disabled = !enabled;
+
+        self.rule_matcher
+            .write()
+            .await
+            .set_rule_enabled(rule_id, enabled);
+
+        info!(
+            "规则 {} 已{}",
+            rule_id,
+            if enabled { "启用" } else { "禁用" }
+        );
+        self.commit_rule_change(&mut config, new_config).await;
+        Ok(true)
+    }
+
+    /// 应用单条规则变更后的配置并写入配置历史
+    async fn commit_rule_change(
+        &self,
+        config: &mut FlowControlConfig,
+        new_config: FlowControlConfig,
+    ) {
+        let record = new_config.create_change_record(Some(config), ChangeSource::Api);
+        self.config_history.write().await.add_record(record);
+        #[cfg(any(feature = "ban-manager", feature = "soft-limit"))]
+        self.rejection_tracker.sync(&new_config);
         *config = new_config;
+    }
 
-        Ok(())
+    /// 设置重新加载配置时读取的存储键
+    ///
+    /// `format` 为 `None` 时根据存储内容推断 JSON、YAML 或 TOML。
+    pub async fn set_config_source(&self, key: impl Into<String>, format: Option<ConfigFormat>) {
+        *self.config_source.write().await = Some((key.into(), format));
     }
 
     /// 重新加载配置
+    ///
+    /// 从 [`set_config_source`](Self::set_config_source) 指定的存储键读取配置，
+    /// 解析、规范化并校验后应用；未设置配置来源时不做任何处理。
     #[instrument(skip(self))]
     pub async fn reload_config(&self) -> Result<(), FlowGuardError> {
         info!("重新加载配置");
 
-        let _config = self.config.read().await.clone();
+        let Some((key, format)) = self.config_source.read().await.clone() else {
+            warn!("未设置配置来源，跳过重新加载");
+            return Ok(());
+        };
 
-        // 这里应该从配置存储重新加载配置
-        // 具体实现取决于配置存储类型
+        let content = self
+            ._storage
+            .get(&key)
+            .await?
+            .ok_or_else(|| FlowGuardError::StorageError(StorageError::NotFound(key.clone())))?;
+        let new_config = FlowControlConfig::parse(&content, format)?;
 
-        Ok(())
+        self.update_config_with_source(new_config, ChangeSource::Api)
+            .await
     }
 
     /// 回滚配置
@@ -711,6 +2323,83 @@ impl Governor {
         self.config_history.read().await.get_records().to_vec()
     }
 
+    /// 获取当前生效的配置
+    pub async fn get_config(&self) -> FlowControlConfig {
+        self.config.read().await.clone()
+    }
+
+    /// 转储当前运行状态，用于排查问题
+    ///
+    /// 汇总生效配置、统计信息、各规则限流器的快照（只读，不消费额度）、生效中的封禁
+    /// 与配置历史。标识符与匹配器取值均已脱敏，封禁存储不支持列出记录时封禁列表为空。
+    #[instrument(skip(self))]
+    pub async fn dump_state(&self) -> GovernorStateDump {
+        let mut limiters: Vec<LimiterStateDump> = Vec::new();
+        for entry in self.rule_chains.read().await.iter() {
+            limiters.extend(
+                entry
+                    .value()
+                    .snapshots()
+                    .into_iter()
+                    .map(|node| LimiterStateDump {
+                        rule_id: entry.key().clone(),
+                        node_id: node.node_id,
+                        key: node.key.as_deref().map(redact_identifier_key),
+                        snapshot: node.snapshot,
+                    }),
+            );
+        }
+        limiters.sort_by(|a, b| (&a.rule_id, &a.node_id).cmp(&(&b.rule_id, &b.node_id)));
+
+        #[cfg(feature = "ban-manager")]
+        let bans = {
+            let filter = crate::ban_manager::BanFilter {
+                active_only: true,
+                ..Default::default()
+            };
+            match self._ban_storage.list_active(&filter).await {
+                Ok(records) => records
+                    .into_iter()
+                    .map(|record| {
+                        let target = match &record.target {
+                            crate::storage::BanTarget::Ip(ip) => format!("ip:{}", ip),
+                            crate::storage::BanTarget::UserId(id) => format!("user_id:{}", id),
+                            crate::storage::BanTarget::Mac(mac) => format!("mac:{}", mac),
+                        };
+                        BanStateDump {
+                            target: redact_identifier_key(&target),
+                            reason: record.reason,
+                            ban_times: record.ban_times,
+                            banned_at: record.banned_at,
+                            expires_at: record.expires_at,
+                            is_manual: record.is_manual,
+                        }
+                    })
+                    .collect(),
+                Err(e) => {
+                    warn!("转储状态时读取封禁列表失败: {}", e);
+                    Vec::new()
+                }
+            }
+        };
+        #[cfg(not(feature = "ban-manager"))]
+        let bans = Vec::new();
+
+        GovernorStateDump {
+            generated_at: Utc::now(),
+            config: redact_config_matchers(self.get_config().await),
+            stats: self.stats().await,
+            limiters,
+            bans,
+            config_history: self
+                .get_config_history()
+                .await
+                .into_iter()
+                .map(redact_change_record)
+                .collect(),
+        }
+    }
+
     /*
     /// 启动配置监视器
     #[instrument(skip(self))]
@@ -760,14 +2449,45 @@ impl Governor {
             rejected_requests: self.rejected_requests.load(Ordering::Relaxed),
             banned_requests: self.banned_requests.load(Ordering::Relaxed),
             error_count: self.error_count.load(Ordering::Relaxed),
+            allowlist_bypass_requests: self.allowlist_bypass_requests.load(Ordering::Relaxed),
             last_updated: Some(Utc::now()),
         }
     }
 
     /// 获取决策链统计
+    ///
+    /// 汇总所有规则决策链与默认决策链；规则变更时被替换或移除的决策链的统计并入默认决策链，
+    /// 汇总计数不会回退。
     #[instrument(skip(self))]
     pub async fn decision_chain_stats(&self) -> crate::decision_chain::ChainStats {
-        self.decision_chain.read().await.stats().clone()
+        let mut stats = self.decision_chain.read().await.stats();
+        for entry in self.rule_chains.read().await.iter() {
+            stats.merge(&entry.value().stats());
+        }
+        stats
+    }
+
+    /// 将不再使用的规则决策链的统计并入默认决策链
+    async fn retire_chain_stats<'a>(&self, chains: impl IntoIterator<Item = &'a DecisionChain>) {
+        let default_chain = self.decision_chain.read().await;
+        for chain in chains {
+            default_chain.import_stats(&chain.stats());
+        }
+    }
+
+    /// 用新规则重建规则匹配器，保留单调计数器（含导入的计数）
+    async fn rebuild_rule_matcher(&self, rules: Vec<MatcherRule>) {
+        let mut matcher = self.rule_matcher.write().await;
+        let counters = matcher.counters();
+        *matcher = RuleMatcher::new(rules);
+        matcher.import_counters(&counters);
+    }
+
+    /// 替换全部规则决策链，旧决策链的统计并入默认决策链
+    async fn replace_rule_chains(&self, chains: DashMap<String, DecisionChain>) {
+        let retired = std::mem::replace(&mut *self.rule_chains.write().await, chains);
+        let retired: Vec<DecisionChain> = retired.into_iter().map(|(_, chain)| chain).collect();
+        self.retire_chain_stats(&retired).await;
     }
 
     /// 获取规则匹配器统计
@@ -776,18 +2496,75 @@ impl Governor {
         self.rule_matcher.read().await.stats().clone()
     }
 
+    /// 导出统计快照
+    ///
+    /// 只导出单调计数器，用于跨重启持久化，见 [`import_stats`](Self::import_stats)。
+    #[instrument(skip(self))]
+    pub async fn export_stats(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            exported_at: Utc::now(),
+            governor: GovernorCounters {
+                total_requests: self.total_requests.load(Ordering::Relaxed),
+                allowed_requests: self.allowed_requests.load(Ordering::Relaxed),
+                rejected_requests: self.rejected_requests.load(Ordering::Relaxed),
+                banned_requests: self.banned_requests.load(Ordering::Relaxed),
+                error_count: self.error_count.load(Ordering::Relaxed),
+                allowlist_bypass_requests: self.allowlist_bypass_requests.load(Ordering::Relaxed),
+            },
+            matcher: self.rule_matcher.read().await.counters(),
+            chain: self.decision_chain_stats().await,
+        }
+    }
+
+    /// 导入统计快照
+    ///
+    /// 计数器在当前值上累加而不是覆盖，启动后已处理的请求不会丢失，计数也不会回退。
+    /// 决策链统计累加到默认决策链，计入 [`decision_chain_stats`](Self::decision_chain_stats) 的汇总。
+    /// 平均耗时等瞬时值不受影响。
+    #[instrument(skip(self, snapshot))]
+    pub async fn import_stats(&self, snapshot: StatsSnapshot) {
+        info!("导入统计快照: exported_at={}", snapshot.exported_at);
+
+        let counters = &snapshot.governor;
+        self.total_requests
+            .fetch_add(counters.total_requests, Ordering::Relaxed);
+        self.allowed_requests
+            .fetch_add(counters.allowed_requests, Ordering::Relaxed);
+        self.rejected_requests
+            .fetch_add(counters.rejected_requests, Ordering::Relaxed);
+        self.banned_requests
+            .fetch_add(counters.banned_requests, Ordering::Relaxed);
+        self.error_count
+            .fetch_add(counters.error_count, Ordering::Relaxed);
+        self.allowlist_bypass_requests
+            .fetch_add(counters.allowlist_bypass_requests, Ordering::Relaxed);
+
+        self.rule_matcher
+            .read()
+            .await
+            .import_counters(&snapshot.matcher);
+        self.decision_chain
+            .read()
+            .await
+            .import_stats(&snapshot.chain);
+    }
+
     /// 重置统计信息
     #[instrument(skip(self))]
     pub async fn reset_stats(&self) {
         info!("重置统计信息");
 
         self.decision_chain.write().await.reset_stats();
+        for entry in self.rule_chains.read().await.iter() {
+            entry.value().reset_stats();
+        }
         self.rule_matcher.write().await.reset_stats();
         self.total_requests.store(0, Ordering::Relaxed);
         self.allowed_requests.store(0, Ordering::Relaxed);
         self.rejected_requests.store(0, Ordering::Relaxed);
         self.banned_requests.store(0, Ordering::Relaxed);
         self.error_count.store(0, Ordering::Relaxed);
+        self.allowlist_bypass_requests.store(0, Ordering::Relaxed);
     }
 
     /// 设置审计日志记录器
@@ -800,6 +2577,32 @@ impl Governor {
         info!("审计日志记录器已设置");
     }
 
+    /// 设置自定义限流器注册表
+    ///
+    /// 按当前配置重建规则决策链：`LimiterConfig::Custom { name, .. }` 使用注册表中
+    /// 同名的工厂为每个标识符创建独立的限流器，未注册工厂的节点被跳过。
+    #[cfg(feature = "custom-limiter")]
+    #[instrument(skip(self, registry))]
+    pub async fn set_custom_limiter_registry(
+        &self,
+        registry: Arc<CustomLimiterRegistry>,
+    ) -> Result<(), FlowGuardError> {
+        let config = self.config.read().await.clone();
+        let chains = Self::build_rule_chains(
+            &config,
+            self.max_keyed_limiters(),
+            #[cfg(feature = "monitoring")]
+            self.metrics.as_ref(),
+            Some(&registry),
+        )?;
+
+        *self.custom_limiters.write().await = Some(registry);
+        self.replace_rule_chains(chains).await;
+
+        info!("自定义限流器注册表已设置");
+        Ok(())
+    }
+
     /// 获取审计日志记录器
     #[cfg(feature = "audit-log")]
     #[instrument(skip(self))]
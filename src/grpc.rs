@@ -0,0 +1,342 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! gRPC 集成模块
+//!
+//! 将 gRPC 元数据映射为 [`RequestContext`] 的请求头，复用 Governor 的标识符提取链完成限流检查。
+//! 提供两种接入方式：
+//!
+//! - [`FlowGuardGrpcLayer`]：异步 tower 中间件层，通过 `Server::builder().layer(..)` 接入，
+//!   检查与软限流等待都不占用运行时工作线程，推荐使用。
+//! - [`FlowGuardInterceptor`]：tonic 拦截器。`Interceptor` 是同步接口，拦截器通过
+//!   `tokio::task::block_in_place` 等待 `Governor::check`，每个请求都会阻塞一个工作线程，
+//!   且只能运行在多线程 tokio 运行时中，在 `current_thread` 运行时中返回 `Status::internal`。
+
+use crate::error::{Decision, RejectReason};
+use crate::governor::Governor;
+use crate::matchers::RequestContext;
+use crate::middleware::{
+    GovernorService, GovernorServiceLayer, GuardRejection, RequestContextBuilder, ResponseBuilder,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::RuntimeFlavor;
+use tonic::body::BoxBody;
+use tonic::codegen::http;
+use tonic::metadata::{KeyAndValueRef, MetadataMap, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::transport::server::TcpConnectInfo;
+use tonic::{Request, Status};
+use tower_layer::Layer;
+
+/// 拒绝时写入的重试等待元数据键
+pub const RETRY_AFTER_METADATA_KEY: &str = "retry-after";
+
+/// 默认重试等待时间（秒）
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+/// 从 gRPC 元数据和对端地址构建请求上下文
+///
+/// 所有 ASCII 元数据按小写键写入 `headers`，二进制元数据（`-bin` 后缀）被忽略；
+/// 对端地址写入 `client_ip`。请求方法固定为 `POST`。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::grpc::request_context_from_metadata;
+/// use tonic::metadata::MetadataMap;
+///
+/// let mut metadata = MetadataMap::new();
+/// metadata.insert("x-user-id", "user123".parse().unwrap());
+/// let context = request_context_from_metadata(&metadata, Some("10.0.0.1:5000".parse().unwrap()));
+/// assert_eq!(context.get_header("X-User-Id"), Some(&"user123".to_string()));
+/// assert_eq!(context.client_ip.as_deref(), Some("10.0.0.1"));
+/// ```
+pub fn request_context_from_metadata(
+    metadata: &MetadataMap,
+    peer: Option<SocketAddr>,
+) -> RequestContext {
+    let mut context = RequestContext::new();
+    for entry in metadata.iter() {
+        if let KeyAndValueRef::Ascii(key, value) = entry {
+            if let Ok(value) = value.to_str() {
+                context = context.with_header(key.as_str(), value);
+            }
+        }
+    }
+    if let Some(peer) = peer {
+        context = context.with_client_ip(&peer.ip().to_string());
+    }
+    context.method = "POST".to_string();
+    context
+}
+
+/// 软限流要求的等待时长
+///
+/// [`FlowGuardInterceptor`] 放行 [`Decision::Delayed`] 的请求时写入请求扩展，
+/// 处理函数通过 `request.extensions().get::<SoftLimitDelay>()` 取出后自行异步等待。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoftLimitDelay(pub Duration);
+
+/// Governor 的 tonic 拦截器
+///
+/// 被拒绝的请求返回 `Status::resource_exhausted`，被封禁的请求返回
+/// `Status::permission_denied`，两者都带有 `retry-after`（秒）元数据；
+/// 无法提取标识符的请求返回 `Status::unauthenticated`。
+/// 软限流的请求直接放行，等待时长以 [`SoftLimitDelay`] 写入请求扩展。
+///
+/// # 示例
+/// ```rust,no_run
+/// use limiteron::governor::Governor;
+/// use limiteron::grpc::FlowGuardInterceptor;
+/// use std::sync::Arc;
+///
+/// # fn demo(governor: Arc<Governor>) {
+/// let interceptor = FlowGuardInterceptor::new(governor)
+///     .with_metadata_mapping("authorization-key", "x-api-key");
+/// // let service = EchoServer::with_interceptor(EchoService, interceptor);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FlowGuardInterceptor {
+    governor: Arc<Governor>,
+    /// 元数据键到请求头名称的映射
+    metadata_mapping: Vec<(String, String)>,
+    /// 限流拒绝时返回的重试等待时间
+    retry_after: Duration,
+}
+
+impl FlowGuardInterceptor {
+    /// 创建新的拦截器
+    pub fn new(governor: Arc<Governor>) -> Self {
+        Self {
+            governor,
+            metadata_mapping: Vec::new(),
+            retry_after: Duration::from_secs(DEFAULT_RETRY_AFTER_SECS),
+        }
+    }
+
+    /// 将元数据键映射为提取链使用的请求头名称
+    ///
+    /// 例如将 `authorization-key` 映射为 `x-api-key`，使默认的 API Key 提取器生效。
+    pub fn with_metadata_mapping(
+        mut self,
+        metadata_key: impl Into<String>,
+        header_name: impl Into<String>,
+    ) -> Self {
+        self.metadata_mapping.push((
+            metadata_key.into().to_lowercase(),
+            header_name.into().to_lowercase(),
+        ));
+        self
+    }
+
+    /// 设置限流拒绝时的重试等待时间
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// 从请求构建上下文，并应用元数据映射
+    pub fn request_context<T>(&self, request: &Request<T>) -> RequestContext {
+        let context = request_context_from_metadata(request.metadata(), request.remote_addr());
+        apply_metadata_mapping(context, &self.metadata_mapping)
+    }
+}
+
+impl Interceptor for FlowGuardInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let context = self.request_context(&request);
+        let handle = tokio::runtime::Handle::try_current()
+            .map_err(|_| Status::internal("FlowGuardInterceptor 需要在 tokio 运行时中使用"))?;
+        // block_in_place 在 current_thread 运行时中会 panic
+        if handle.runtime_flavor() != RuntimeFlavor::MultiThread {
+            tracing::error!(
+                "FlowGuardInterceptor 需要多线程 tokio 运行时，请改用 FlowGuardGrpcLayer"
+            );
+            return Err(Status::internal(
+                "FlowGuardInterceptor 需要多线程 tokio 运行时",
+            ));
+        }
+
+        let decision =
+            tokio::task::block_in_place(|| handle.block_on(self.governor.check(&context)))
+                .map_err(|e| {
+                    tracing::error!(error = %e, "gRPC 请求限流检查失败");
+                    Status::internal("限流检查失败")
+                })?;
+
+        // 软限流：不在拦截器中等待，避免长时间占用工作线程，把等待时长交给处理函数
+        let delay = decision.delay();
+        match rejection_status(decision, self.retry_after) {
+            Some(status) => Err(status),
+            None => {
+                let mut request = request;
+                if let Some(delay) = delay {
+                    request.extensions_mut().insert(SoftLimitDelay(delay));
+                }
+                Ok(request)
+            }
+        }
+    }
+}
+
+/// 从 `http::Request` 构建请求上下文：请求头按 gRPC 元数据处理，对端地址取自连接信息
+#[derive(Clone, Default)]
+pub struct GrpcContextBuilder {
+    /// 元数据键到请求头名称的映射
+    metadata_mapping: Vec<(String, String)>,
+}
+
+impl<B> RequestContextBuilder<http::Request<B>> for GrpcContextBuilder {
+    fn build(&self, request: http::Request<B>) -> (http::Request<B>, RequestContext) {
+        let metadata = MetadataMap::from_headers(request.headers().clone());
+        let peer = request
+            .extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(TcpConnectInfo::remote_addr);
+        let context = request_context_from_metadata(&metadata, peer);
+        let context = apply_metadata_mapping(context, &self.metadata_mapping);
+        (request, context)
+    }
+}
+
+/// 构造 gRPC 拒绝响应：限流返回 `RESOURCE_EXHAUSTED`，封禁返回 `PERMISSION_DENIED`，
+/// 无法提取标识符返回 `UNAUTHENTICATED`，检查失败返回 `INTERNAL`
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcResponseBuilder {
+    /// 限流拒绝时返回的重试等待时间
+    retry_after: Duration,
+}
+
+impl Default for GrpcResponseBuilder {
+    fn default() -> Self {
+        Self {
+            retry_after: Duration::from_secs(DEFAULT_RETRY_AFTER_SECS),
+        }
+    }
+}
+
+impl ResponseBuilder<http::Response<BoxBody>> for GrpcResponseBuilder {
+    fn rejected(&self, rejection: GuardRejection) -> http::Response<BoxBody> {
+        let status = match rejection {
+            GuardRejection::Denied { decision, .. } => rejection_status(decision, self.retry_after)
+                .unwrap_or_else(|| Status::internal("限流检查失败")),
+            GuardRejection::Error(_) => Status::internal("限流检查失败"),
+        };
+        status.to_http()
+    }
+}
+
+/// [`FlowGuardGrpcLayer`] 生成的中间件服务
+pub type FlowGuardGrpcService<S> = GovernorService<S, GrpcContextBuilder, GrpcResponseBuilder>;
+
+/// Governor 的 tonic 异步中间件层
+///
+/// 与 [`FlowGuardInterceptor`] 的行为一致，但在异步服务中等待检查结果，不阻塞运行时工作线程，
+/// 可用于任意 flavor 的 tokio 运行时；软限流的等待同样异步进行，等待期间请求被取消时随之结束。
+///
+/// # 示例
+/// ```rust,no_run
+/// use limiteron::governor::Governor;
+/// use limiteron::grpc::FlowGuardGrpcLayer;
+/// use std::sync::Arc;
+///
+/// # fn demo(governor: Arc<Governor>) {
+/// let layer = FlowGuardGrpcLayer::new(governor).with_metadata_mapping("caller-id", "x-user-id");
+/// // Server::builder().layer(layer).add_service(EchoServer::new(EchoService))
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FlowGuardGrpcLayer {
+    governor: Arc<Governor>,
+    context_builder: GrpcContextBuilder,
+    response_builder: GrpcResponseBuilder,
+}
+
+impl FlowGuardGrpcLayer {
+    /// 创建新的中间件层
+    pub fn new(governor: Arc<Governor>) -> Self {
+        Self {
+            governor,
+            context_builder: GrpcContextBuilder::default(),
+            response_builder: GrpcResponseBuilder::default(),
+        }
+    }
+
+    /// 将元数据键映射为提取链使用的请求头名称
+    pub fn with_metadata_mapping(
+        mut self,
+        metadata_key: impl Into<String>,
+        header_name: impl Into<String>,
+    ) -> Self {
+        self.context_builder.metadata_mapping.push((
+            metadata_key.into().to_lowercase(),
+            header_name.into().to_lowercase(),
+        ));
+        self
+    }
+
+    /// 设置限流拒绝时的重试等待时间
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.response_builder.retry_after = retry_after;
+        self
+    }
+}
+
+impl<S> Layer<S> for FlowGuardGrpcLayer {
+    type Service = FlowGuardGrpcService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GovernorServiceLayer::new(
+            self.governor.clone(),
+            self.context_builder.clone(),
+            self.response_builder,
+        )
+        .layer(inner)
+    }
+}
+
+/// 将映射的元数据值复制到提取链使用的请求头
+fn apply_metadata_mapping(
+    mut context: RequestContext,
+    metadata_mapping: &[(String, String)],
+) -> RequestContext {
+    for (metadata_key, header_name) in metadata_mapping {
+        if let Some(value) = context.headers.get(metadata_key).cloned() {
+            context.headers.insert(header_name.clone(), value);
+        }
+    }
+    context
+}
+
+/// 拒绝决策对应的 gRPC 状态，允许（含软限流延迟）时返回 `None`
+fn rejection_status(decision: Decision, retry_after: Duration) -> Option<Status> {
+    match decision {
+        Decision::Allowed(_) => None,
+        Decision::Delayed(_) => None,
+        Decision::Rejected(rejection) if rejection.reason == RejectReason::NoIdentifier => {
+            Some(Status::unauthenticated(rejection.message))
+        }
+        Decision::Rejected(rejection) => Some(with_retry_after(
+            Status::resource_exhausted(rejection.message),
+            retry_after.as_secs().max(1),
+        )),
+        Decision::Banned(ban) => {
+            let remaining = (ban.banned_until - chrono::Utc::now()).num_seconds().max(1) as u64;
+            Some(with_retry_after(
+                Status::permission_denied(ban.reason),
+                remaining,
+            ))
+        }
+    }
+}
+
+/// 为状态附加 `retry-after` 元数据
+fn with_retry_after(mut status: Status, secs: u64) -> Status {
+    status
+        .metadata_mut()
+        .insert(RETRY_AFTER_METADATA_KEY, MetadataValue::from(secs));
+    status
+}
@@ -0,0 +1,280 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! 限流响应头模块
+//!
+//! 将 [`Decision`] 与 [`RateLimitDecision`] 转换为限流相关的 HTTP 响应头，支持两种格式：
+//!
+//! - [`RateLimitHeaderFormat::Legacy`]：`X-RateLimit-Limit`、`X-RateLimit-Remaining`、`X-RateLimit-Reset`
+//! - [`RateLimitHeaderFormat::Draft`]：IETF 草案 `draft-ietf-httpapi-ratelimit-headers` 的
+//!   `RateLimit-Policy` 与 `RateLimit` 结构化字段
+//!
+//! 两种格式在拒绝或封禁时都附加 `Retry-After`。所有时间均为相对秒数，向上取整。
+
+use crate::error::Decision;
+use crate::limiters::RateLimitDecision;
+use std::time::Duration;
+
+/// 默认重试等待时间（秒），限流器未给出 `retry_after` 时使用
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+/// 默认策略名称
+pub const DEFAULT_POLICY_NAME: &str = "default";
+
+/// 响应头格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitHeaderFormat {
+    /// `X-RateLimit-*` 传统格式
+    #[default]
+    Legacy,
+    /// IETF 草案 `RateLimit` / `RateLimit-Policy` 格式
+    Draft,
+}
+
+/// 限流响应头生成器
+///
+/// # 示例
+/// ```rust
+/// use limiteron::error::Decision;
+/// use limiteron::headers::{RateLimitHeaderFormat, RateLimitHeaders};
+/// use limiteron::limiters::RateLimitDecision;
+/// use std::time::Duration;
+///
+/// let limits = RateLimitDecision {
+///     allowed: true,
+///     remaining: 7,
+///     limit: 10,
+///     retry_after: None,
+/// };
+///
+/// let headers = RateLimitHeaders::from_decision(&Decision::Allowed(None), &limits);
+/// assert_eq!(headers[0], ("X-RateLimit-Limit".to_string(), "10".to_string()));
+///
+/// let headers = RateLimitHeaders::new(RateLimitHeaderFormat::Draft)
+///     .with_window(Duration::from_secs(60))
+///     .build(&Decision::Allowed(None), &limits);
+/// assert_eq!(headers[0].1, "\"default\";q=10;w=60");
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimitHeaders {
+    format: RateLimitHeaderFormat,
+    policy_name: String,
+    window: Option<Duration>,
+}
+
+impl Default for RateLimitHeaders {
+    fn default() -> Self {
+        Self::new(RateLimitHeaderFormat::default())
+    }
+}
+
+impl RateLimitHeaders {
+    /// 创建指定格式的生成器
+    pub fn new(format: RateLimitHeaderFormat) -> Self {
+        Self {
+            format,
+            policy_name: DEFAULT_POLICY_NAME.to_string(),
+            window: None,
+        }
+    }
+
+    /// 设置策略名称（仅用于草案格式）
+    pub fn with_policy_name(mut self, name: impl Into<String>) -> Self {
+        self.policy_name = name.into();
+        self
+    }
+
+    /// 设置限流窗口（草案格式写入 `RateLimit-Policy` 的 `w` 参数）
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// 以传统格式生成响应头
+    pub fn from_decision(decision: &Decision, limits: &RateLimitDecision) -> Vec<(String, String)> {
+        Self::default().build(decision, limits)
+    }
+
+    /// 生成响应头
+    ///
+    /// 重置时间取封禁剩余时长或 `limits.retry_after`，两者都没有时省略
+    /// （`X-RateLimit-Reset` 与 `RateLimit` 的 `t` 参数）。
+    pub fn build(&self, decision: &Decision, limits: &RateLimitDecision) -> Vec<(String, String)> {
+        let reset = match decision {
+            Decision::Banned(ban) => Some(
+                (ban.banned_until - chrono::Utc::now())
+                    .to_std()
+                    .unwrap_or_default(),
+            ),
+            _ => limits.retry_after,
+        };
+        let mut headers = Vec::with_capacity(4);
+
+        match self.format {
+            RateLimitHeaderFormat::Legacy => {
+                headers.push(("X-RateLimit-Limit".to_string(), limits.limit.to_string()));
+                headers.push((
+                    "X-RateLimit-Remaining".to_string(),
+                    limits.remaining.to_string(),
+                ));
+                if let Some(reset) = reset {
+                    headers.push((
+                        "X-RateLimit-Reset".to_string(),
+                        ceil_secs(reset).to_string(),
+                    ));
+                }
+            }
+            RateLimitHeaderFormat::Draft => {
+                let name = sf_string(&self.policy_name);
+                let mut policy = format!("{};q={}", name, limits.limit);
+                if let Some(window) = self.window {
+                    policy.push_str(&format!(";w={}", ceil_secs(window)));
+                }
+                let mut state = format!("{};r={}", name, limits.remaining);
+                if let Some(reset) = reset {
+                    state.push_str(&format!(";t={}", ceil_secs(reset)));
+                }
+                headers.push(("RateLimit-Policy".to_string(), policy));
+                headers.push(("RateLimit".to_string(), state));
+            }
+        }
+
+        if !matches!(decision, Decision::Allowed(_)) {
+            headers.push((
+                "Retry-After".to_string(),
+                retry_after_secs(reset).to_string(),
+            ));
+        }
+        headers
+    }
+}
+
+/// `Retry-After` 秒数：向上取整，至少 1 秒，未知时为 [`DEFAULT_RETRY_AFTER_SECS`]
+pub fn retry_after_secs(retry_after: Option<Duration>) -> u64 {
+    retry_after
+        .map(ceil_secs)
+        .unwrap_or(DEFAULT_RETRY_AFTER_SECS)
+        .max(1)
+}
+
+/// 向上取整到秒
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// 编码为结构化字段字符串（RFC 8941 sf-string）
+fn sf_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{BanInfo, RejectReason};
+
+    fn pairs(headers: &[(&str, &str)]) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn allowed() -> RateLimitDecision {
+        RateLimitDecision {
+            allowed: true,
+            remaining: 7,
+            limit: 10,
+            retry_after: None,
+        }
+    }
+
+    fn rejected() -> RateLimitDecision {
+        RateLimitDecision {
+            allowed: false,
+            remaining: 0,
+            limit: 10,
+            retry_after: Some(Duration::from_millis(1500)),
+        }
+    }
+
+    #[test]
+    fn test_legacy_headers() {
+        assert_eq!(
+            RateLimitHeaders::from_decision(&Decision::Allowed(None), &allowed()),
+            pairs(&[("X-RateLimit-Limit", "10"), ("X-RateLimit-Remaining", "7")])
+        );
+
+        let decision = Decision::rejected(RejectReason::RateLimit, "too many requests");
+        assert_eq!(
+            RateLimitHeaders::from_decision(&decision, &rejected()),
+            pairs(&[
+                ("X-RateLimit-Limit", "10"),
+                ("X-RateLimit-Remaining", "0"),
+                ("X-RateLimit-Reset", "2"),
+                ("Retry-After", "2"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_draft_headers() {
+        let builder = RateLimitHeaders::new(RateLimitHeaderFormat::Draft)
+            .with_policy_name("api")
+            .with_window(Duration::from_secs(60));
+
+        assert_eq!(
+            builder.build(&Decision::Allowed(None), &allowed()),
+            pairs(&[
+                ("RateLimit-Policy", "\"api\";q=10;w=60"),
+                ("RateLimit", "\"api\";r=7"),
+            ])
+        );
+
+        let decision = Decision::rejected(RejectReason::RateLimit, "too many requests");
+        assert_eq!(
+            builder.build(&decision, &rejected()),
+            pairs(&[
+                ("RateLimit-Policy", "\"api\";q=10;w=60"),
+                ("RateLimit", "\"api\";r=0;t=2"),
+                ("Retry-After", "2"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rejected_without_retry_after_and_banned() {
+        let limits = RateLimitDecision {
+            retry_after: None,
+            ..rejected()
+        };
+        let decision = Decision::rejected(RejectReason::Quota, "quota exceeded");
+        assert_eq!(
+            RateLimitHeaders::from_decision(&decision, &limits),
+            pairs(&[
+                ("X-RateLimit-Limit", "10"),
+                ("X-RateLimit-Remaining", "0"),
+                ("Retry-After", "1"),
+            ])
+        );
+
+        let banned = Decision::Banned(BanInfo {
+            reason: "abuse".to_string(),
+            banned_until: chrono::Utc::now() + chrono::Duration::seconds(30),
+            ban_times: 1,
+        });
+        let headers = RateLimitHeaders::from_decision(&banned, &limits);
+        assert_eq!(
+            headers.last(),
+            Some(&("Retry-After".to_string(), "30".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_policy_name_is_escaped() {
+        let headers = RateLimitHeaders::new(RateLimitHeaderFormat::Draft)
+            .with_policy_name("a\"b")
+            .build(&Decision::Allowed(None), &allowed());
+        assert_eq!(headers[0].1, "\"a\\\"b\";q=10");
+    }
+}
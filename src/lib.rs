@@ -32,6 +32,13 @@
 //! - Ban management (requires `ban-manager` feature)
 //! - Circuit breaker (requires `circuit-breaker` feature)
 //! - Quota control (requires `quota-control` feature)
+//! - Webhook quota alerts (requires `webhook-alerts` feature)
+//! - gRPC interceptor and async tower layer for tonic (requires `grpc` feature)
+//! - Framework-agnostic tower middleware (requires `tower` feature)
+//! - axum middleware layer (requires `axum` feature)
+//! - Standalone HTTP rate-limit service (requires `server` feature)
+//! - Config validation and lint CLI (requires `cli` feature)
+//! - Cron-like time-window matching in IANA timezones (requires `cron` feature)
 //! - Macros (requires `macros` feature)
 //!
 //! # Examples
@@ -64,11 +71,16 @@
 pub mod prelude;
 
 pub mod audit_log;
+#[cfg(feature = "axum")]
+pub mod axum_layer;
 #[cfg(feature = "ban-manager")]
 pub mod ban_manager;
 pub mod cache;
 #[cfg(feature = "circuit-breaker")]
 pub mod circuit_breaker;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod clock;
 #[cfg(feature = "code-review")]
 pub mod code_review;
 pub mod config;
@@ -88,6 +100,9 @@ pub mod factory;
 #[cfg(feature = "fallback")]
 pub mod fallback;
 pub mod governor;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod headers;
 pub mod limiter_manager;
 pub mod limiters;
 pub mod log_redaction;
@@ -96,6 +111,8 @@ pub mod lua_scripts;
 #[cfg(feature = "macros")]
 pub mod macros;
 pub mod matchers;
+#[cfg(feature = "tower")]
+pub mod middleware;
 #[cfg(feature = "parallel-checker")]
 pub mod parallel_ban_checker;
 #[cfg(feature = "postgres")]
@@ -104,49 +121,77 @@ pub mod postgres_storage;
 pub mod quota_controller;
 #[cfg(feature = "redis")]
 pub mod redis_storage;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod storage;
 #[cfg(any(feature = "telemetry", feature = "monitoring"))]
 pub mod telemetry;
+#[cfg(feature = "webhook-alerts")]
+pub mod webhook_alert;
 
 // 重新导出常用类型
 #[cfg(feature = "audit-log")]
-pub use audit_log::{AuditEvent, AuditLogConfig, AuditLogStats, AuditLogger};
+pub use audit_log::{
+    AuditEvent, AuditLogConfig, AuditLogStats, AuditLogger, AuditSink, DecisionKind,
+    JsonStdoutSink, RotatingFileSink, TracingSink,
+};
+#[cfg(feature = "axum")]
+pub use axum_layer::{GovernorLayer, GovernorMiddleware};
 #[cfg(feature = "ban-manager")]
 pub use ban_manager::{
-    BackoffConfig, BanDetail, BanFilter, BanManager, BanManagerConfig, BanPriority, BanSource,
+    BackoffConfig, BanCursor, BanDecision, BanDetail, BanFilter, BanManager, BanManagerConfig,
+    BanPriority, BanSource, PreBanHook,
 };
-pub use cache::{L2Cache, L2CacheConfig, SmartCacheStrategy};
+pub use cache::{EvictionPolicy, L2Cache, L2CacheConfig, SmartCacheStrategy, WritePolicy};
 #[cfg(feature = "redis")]
 pub use cache::{L3Cache, L3CacheConfig, L3CacheStats};
 #[cfg(feature = "circuit-breaker")]
-pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, TripPolicy};
+pub use clock::{Clock, MockClock, RealClock};
 #[cfg(feature = "code-review")]
 pub use code_review::{
     CodeReviewConfig, CodeReviewIssue, CodeReviewManager, CodeReviewReport, CodeReviewStats,
     IssueCategory, ReviewConclusion, ReviewStatus, ReviewSummary, Severity,
 };
 pub use config::{
-    ActionConfig, ChangeSource, ConfigChangeRecord, ConfigHistory, FlowControlConfig,
-    LimiterConfig, Matcher as ConfigMatcher, Rule as ConfigRule,
+    ActionConfig, ChangeSource, ConfigChangeRecord, ConfigFieldChange, ConfigHistory,
+    ConfigValidationError, ConfigValidationReport, FlowControlConfig, LimiterConfig,
+    Matcher as ConfigMatcher, Rule as ConfigRule,
 };
+#[cfg(all(feature = "config-watcher", feature = "postgres"))]
+pub use config_watcher::PostgresConfigStorage;
 #[cfg(feature = "config-watcher")]
-pub use config_watcher::{ConfigChangeCallback, ConfigWatcher, PostgresConfigStorage, WatchMode};
+pub use config_watcher::{
+    parse_ip_list, ConfigChangeCallback, ConfigWatcher, IpListWatcher, WatchMode,
+};
 #[cfg(feature = "custom-limiter")]
 pub use custom_limiter::{
-    CustomLimiter, CustomLimiterRegistry, LeakyBucketLimiter, LimiterStats, TokenBucketLimiter,
+    CustomLimiter, CustomLimiterAdapter, CustomLimiterFactory, CustomLimiterRegistry,
+    LeakyBucketLimiter, LimiterStats, Reservation, TokenBucketLimiter,
+};
+pub use decision_chain::{
+    ChainStats, CombineOp, DecisionChain, DecisionChainBuilder, DecisionNode, NodeSnapshot,
 };
-pub use decision_chain::{ChainStats, DecisionChain, DecisionChainBuilder, DecisionNode};
 pub use error::{
-    BanInfo, CircuitBreakerStats, CircuitState, ConsumeResult, Decision, FlowGuardError,
-    StorageError,
+    BanInfo, CircuitBreakerStats, CircuitState, ConsumeResult, Decision, DurationParseError,
+    FlowGuardError, RejectReason, Rejection, StorageError,
 };
-pub use factory::LimiterFactory;
+pub use factory::{parse_duration, LimiterFactory};
 #[cfg(feature = "fallback")]
 pub use fallback::{ComponentType, FallbackConfig, FallbackManager, FallbackStrategy};
-pub use governor::{Governor, GovernorStats};
-pub use limiter_manager::GLOBAL_LIMITER_MANAGER;
+pub use governor::{
+    BanStateDump, CheckResult, FailurePolicy, Governor, GovernorCounters, GovernorStateDump,
+    GovernorStats, LimiterStateDump, RuleEvaluationPolicy, StatsSnapshot,
+};
+#[cfg(feature = "grpc")]
+pub use grpc::{
+    request_context_from_metadata, FlowGuardGrpcLayer, FlowGuardInterceptor, SoftLimitDelay,
+};
+pub use headers::{RateLimitHeaderFormat, RateLimitHeaders};
+pub use limiter_manager::{sanitize_key, KeyStrategy, GLOBAL_LIMITER_MANAGER};
 #[cfg(feature = "quota-control")]
 pub use limiters::QuotaLimiter;
+pub use limiters::{LimiterSnapshot, Observable, RateLimitDecision, SlidingWindowMode};
 #[cfg(feature = "redis")]
 pub use lua_scripts::{LuaScriptInfo, LuaScriptManager, LuaScriptType};
 #[cfg(feature = "macros")]
@@ -154,26 +199,45 @@ pub use macros::{
     flow_control, parse_quota_limit, parse_rate_limit, FlowControlConfig as MacroFlowControlConfig,
     QuotaLimit, RateLimit,
 };
+#[cfg(feature = "cron")]
+pub use matchers::CronTimeWindowMatcher;
 pub use matchers::{
-    ApiKeyExtractor, CompositeCondition, CompositeExtractor, ConditionEvaluator, CustomExtractor,
-    DeviceIdExtractor, Identifier, IdentifierExtractor, IpExtractor, IpRange, LogicalOperator,
-    MacExtractor, MatchCondition, MatcherStats, RequestContext, Rule, RuleMatcher, UserIdExtractor,
+    parse_forwarded_for, ApiKeyExtractor, CompositeCondition, CompositeExtractor,
+    ConditionEvaluator, CookieExtractor, CustomExtractor, DeviceIdExtractor, EvaluationCache,
+    HeaderValueParser, Identifier, IdentifierExtractor, IpExtractor, IpRange, JsonBodyExtractor,
+    LogicalOperator, MacExtractor, MatchCondition, MatcherCounters, MatcherStats, RequestContext,
+    Rule, RuleMatcher, RuleTieBreak, SharedIpList, UserIdExtractor, UserPatterns,
+};
+pub use matchers::{
+    CustomMatcher, CustomMatcherCondition, CustomMatcherRegistry, HeaderMatcher, TimeWindowMatcher,
 };
-pub use matchers::{CustomMatcher, CustomMatcherRegistry, HeaderMatcher, TimeWindowMatcher};
 #[cfg(feature = "device-matching")]
 pub use matchers::{DeviceCacheStats, DeviceCondition, DeviceInfo, DeviceMatcher, DeviceType};
 #[cfg(feature = "geo-matching")]
 pub use matchers::{GeoCacheStats, GeoCondition, GeoInfo, GeoMatcher};
+#[cfg(feature = "tower")]
+pub use middleware::{
+    GovernorService, GovernorServiceLayer, GuardRejection, RequestContextBuilder, ResponseBuilder,
+};
 #[cfg(feature = "postgres")]
 pub use postgres_storage::{PostgresStorage, PostgresStorageConfig};
 #[cfg(feature = "quota-control")]
 pub use quota_controller::{
-    AlertChannel, AlertConfig, AlertInfo, QuotaConfig, QuotaController, QuotaState, QuotaType,
+    AlertChannel, AlertConfig, AlertInfo, CompositeQuota, CostWeights, QuotaConfig,
+    QuotaController, QuotaReservation, QuotaState, QuotaType, RolloverConfig,
 };
 #[cfg(feature = "redis")]
-pub use redis_storage::{RedisConfig, RedisStorage, RetryStats};
-pub use storage::{BanConfig, BanRecord, BanScope, BanStorage, BanTarget, QuotaStorage, Storage};
+pub use redis_storage::{
+    CombinedDecision, LimiterSpec, RedisConcurrencyLimiter, RedisConcurrencyPermit, RedisConfig,
+    RedisHealth, RedisStorage, RetryStats,
+};
+pub use storage::{
+    BanConfig, BanRecord, BanScope, BanStorage, BanTarget, CompositeConsumeResult,
+    CompositeQuotaLimit, QuotaStorage, Storage,
+};
 #[cfg(feature = "telemetry")]
 pub use telemetry::{init_telemetry, TelemetryConfig, Tracer};
 #[cfg(feature = "monitoring")]
 pub use telemetry::{set_global_metrics, try_global, Metrics};
+#[cfg(feature = "webhook-alerts")]
+pub use webhook_alert::{WebhookAlertChannel, WebhookRetryPolicy};
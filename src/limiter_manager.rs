@@ -4,31 +4,394 @@
 //!
 //! 全局限流器管理器
 //!
-//! 为 `flow_control` 宏提供全局共享的 limiter 实例。
+//! 为 `flow_control` 宏提供全局共享的 limiter 实例，以及宏与 Governor 共用的
+//! 限流键清洗逻辑。
 
-use crate::limiters::{ConcurrencyLimiter, FixedWindowLimiter, TokenBucketLimiter};
+use crate::constants::{DEFAULT_LIMITER_IDLE_TTL_SECS, DEFAULT_MAX_LIMITERS, MAX_LIMITER_KEY_LEN};
+use crate::error::FlowGuardError;
+use crate::limiters::{
+    ConcurrencyLimiter, FixedWindowLimiter, Limiter, LimiterSnapshot, Observable,
+    TokenBucketLimiter,
+};
 use ahash::AHashMap as HashMap;
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::Duration;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// 限流键清洗策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyStrategy {
+    /// 过滤非法字符后截断到长度上限（共享同一前缀的长标识符会落入同一个键）
+    #[default]
+    Truncate,
+    /// 值合法且不超过长度上限时原样保留；否则保留清洗后的前缀并附加原始值的哈希，
+    /// 键长度有界且不同的原始值得到不同的键
+    Hash,
+}
+
+impl std::str::FromStr for KeyStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truncate" => Ok(Self::Truncate),
+            "hash" => Ok(Self::Hash),
+            _ => Err(format!(
+                "Invalid key strategy: {} (expected \"truncate\" or \"hash\")",
+                s
+            )),
+        }
+    }
+}
+
+/// 哈希策略下附加的十六进制哈希长度（FNV-1a 128 位）
+const KEY_HASH_HEX_LEN: usize = 32;
+
+/// 限流键中允许保留的字符，与 `flow_control` 宏原有的清洗规则一致
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// FNV-1a 128 位哈希，跨进程稳定，可用于持久化存储中的键
+fn fnv1a_128(input: &str) -> u128 {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    input.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u128::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// 清洗标识符，生成长度有界的限流键
+///
+/// `flow_control` 宏与 Governor（通过 `Governor::set_limiter_key_strategy` 启用时）
+/// 使用同一实现，保证相同的标识符得到相同的键。长度上限为 [`MAX_LIMITER_KEY_LEN`] 个字符；
+/// [`KeyStrategy::Truncate`] 的结果与宏原有的清洗逻辑逐字节相同。
+///
+/// # 示例
+///
+/// ```rust
+/// use limiteron::limiter_manager::{sanitize_key, KeyStrategy};
+///
+/// assert_eq!(sanitize_key("alice", KeyStrategy::Hash), "alice");
+/// assert_eq!(sanitize_key("user:alice", KeyStrategy::Truncate), "useralice");
+///
+/// let a = format!("{}a", "x".repeat(200));
+/// let b = format!("{}b", "x".repeat(200));
+/// assert_eq!(sanitize_key(&a, KeyStrategy::Truncate), sanitize_key(&b, KeyStrategy::Truncate));
+/// assert_ne!(sanitize_key(&a, KeyStrategy::Hash), sanitize_key(&b, KeyStrategy::Hash));
+/// ```
+pub fn sanitize_key(input: &str, strategy: KeyStrategy) -> String {
+    match strategy {
+        KeyStrategy::Truncate => input
+            .chars()
+            .filter(|c| is_key_char(*c))
+            .take(MAX_LIMITER_KEY_LEN)
+            .collect(),
+        KeyStrategy::Hash => {
+            if input.chars().count() <= MAX_LIMITER_KEY_LEN && input.chars().all(is_key_char) {
+                return input.to_string();
+            }
+            // `~` 不属于合法字符，哈希后的键不会与原样保留的键冲突
+            let mut key: String = input
+                .chars()
+                .filter(|c| is_key_char(*c))
+                .take(MAX_LIMITER_KEY_LEN - KEY_HASH_HEX_LEN - 1)
+                .collect();
+            key.push('~');
+            key.push_str(&format!("{:032x}", fnv1a_128(input)));
+            key
+        }
+    }
+}
+
+/// 分片数量（2 的幂，便于按位取模）
+const SHARD_COUNT: usize = 64;
+
+/// 后台清理的最短间隔
+const MIN_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 映射条目：限流器及其最后访问时间
+struct Entry<V> {
+    value: Arc<V>,
+    last_access: Instant,
+}
+
+/// 单个分片
+type Shard<V> = Mutex<HashMap<String, Entry<V>>>;
+
+/// 分片映射
+///
+/// 按 key 的哈希值选择分片，不同 key 的查找通常落在不同分片上，互不争用同一把锁。
+struct ShardedMap<V> {
+    shards: Box<[Shard<V>]>,
+    hasher: ahash::RandomState,
+}
+
+impl<V> ShardedMap<V> {
+    fn new() -> Self {
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            hasher: ahash::RandomState::new(),
+        }
+    }
+
+    fn shard(&self, key: &str) -> &Shard<V> {
+        let index = self.hasher.hash_one(key) as usize & (SHARD_COUNT - 1);
+        &self.shards[index]
+    }
+
+    /// 获取 key 对应的值，不刷新最后访问时间
+    fn get(&self, key: &str) -> Option<Arc<V>> {
+        self.shard(key)
+            .lock()
+            .get(key)
+            .map(|entry| entry.value.clone())
+    }
+
+    /// 获取或创建 key 对应的值，并刷新最后访问时间
+    ///
+    /// 设置了 `shard_cap` 时，插入新条目前若分片已满，淘汰该分片中最久未访问且
+    /// 未被外部持有的条目；所有条目都被持有时允许暂时超过上限，不淘汰仍在使用的限流器。
+    /// 返回值及被淘汰的条目数。
+    fn get_or_insert_with(
+        &self,
+        key: &str,
+        shard_cap: Option<usize>,
+        create: impl FnOnce() -> V,
+    ) -> (Arc<V>, u64) {
+        let now = Instant::now();
+        let mut shard = self.shard(key).lock();
+        if let Some(entry) = shard.get_mut(key) {
+            entry.last_access = now;
+            return (entry.value.clone(), 0);
+        }
+
+        let mut evicted = 0;
+        if let Some(cap) = shard_cap {
+            while shard.len() >= cap {
+                let oldest = shard
+                    .iter()
+                    .filter(|(_, entry)| Arc::strong_count(&entry.value) == 1)
+                    .min_by_key(|(_, entry)| entry.last_access)
+                    .map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => {
+                        shard.remove(&oldest);
+                        evicted += 1;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        let value = Arc::new(create());
+        shard.insert(
+            key.to_string(),
+            Entry {
+                value: value.clone(),
+                last_access: now,
+            },
+        );
+        (value, evicted)
+    }
+
+    /// 移除最后访问时间早于 `deadline` 且未被外部持有的条目，返回移除数量
+    fn evict_idle(&self, deadline: Instant) -> u64 {
+        let mut evicted = 0;
+        for shard in self.shards.iter() {
+            let mut shard = shard.lock();
+            let before = shard.len();
+            shard.retain(|_, entry| {
+                entry.last_access >= deadline || Arc::strong_count(&entry.value) > 1
+            });
+            evicted += (before - shard.len()) as u64;
+        }
+        evicted
+    }
+
+    /// 依次遍历所有分片中的条目
+    fn for_each(&self, mut f: impl FnMut(&String, &Arc<V>)) {
+        for shard in self.shards.iter() {
+            for (key, entry) in shard.lock().iter() {
+                f(key, &entry.value);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().len()).sum()
+    }
+
+    fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().clear();
+        }
+    }
+}
+
+/// 空闲淘汰配置
+#[derive(Debug, Clone, Copy)]
+struct EvictionConfig {
+    /// 空闲超过该时长的限流器会被后台清理
+    idle_ttl: Duration,
+    /// 每个分片的条目上限（由总上限均分）
+    shard_cap: usize,
+}
+
+/// 限流器管理器统计信息
+#[derive(Debug, Clone, Default)]
+pub struct LimiterManagerStats {
+    /// 当前缓存的限流器数量
+    pub active_limiters: usize,
+    /// 累计淘汰的限流器数量
+    pub evicted_total: u64,
+}
+
+/// 管理器共享状态，后台清理任务通过弱引用访问
+struct Inner {
+    rate_limiters: ShardedMap<TokenBucketLimiter>,
+    quota_limiters: ShardedMap<FixedWindowLimiter>,
+    concurrency_limiters: ShardedMap<ConcurrencyLimiter>,
+    eviction: Option<EvictionConfig>,
+    evicted_total: AtomicU64,
+}
+
+impl Inner {
+    fn shard_cap(&self) -> Option<usize> {
+        self.eviction.map(|eviction| eviction.shard_cap)
+    }
+
+    fn record_evicted(&self, evicted: u64) {
+        if evicted > 0 {
+            self.evicted_total.fetch_add(evicted, Ordering::Relaxed);
+        }
+    }
+
+    fn evict_idle(&self) -> u64 {
+        let Some(eviction) = self.eviction else {
+            return 0;
+        };
+        let Some(deadline) = Instant::now().checked_sub(eviction.idle_ttl) else {
+            return 0;
+        };
+
+        let evicted = self.rate_limiters.evict_idle(deadline)
+            + self.quota_limiters.evict_idle(deadline)
+            + self.concurrency_limiters.evict_idle(deadline);
+        self.record_evicted(evicted);
+        evicted
+    }
+}
+
+/// 后台清理任务
+struct SweeperTask {
+    handle: tokio::task::JoinHandle<()>,
+    /// 是否运行在多线程运行时上
+    multi_thread: bool,
+}
 
 /// 全局限流器管理器
+///
+/// 内部按 key 哈希分片存储，高并发下访问不同 key 不会串行化在同一把锁上。
+/// 通过 [`with_eviction`](Self::with_eviction) 创建时，会记录每个 key 的最后访问时间，
+/// 由后台任务清理空闲的限流器，并以条目上限作为兜底。
 pub struct LimiterManager {
-    rate_limiters: Mutex<HashMap<String, Arc<TokenBucketLimiter>>>,
-    quota_limiters: Mutex<HashMap<String, Arc<FixedWindowLimiter>>>,
-    concurrency_limiters: Mutex<HashMap<String, Arc<ConcurrencyLimiter>>>,
+    inner: Arc<Inner>,
+    sweeper: Mutex<Option<SweeperTask>>,
 }
 
 impl LimiterManager {
-    /// 创建新的限流器管理器
+    /// 创建新的限流器管理器（不淘汰）
     pub fn new() -> Self {
+        Self::build(None)
+    }
+
+    /// 创建带空闲淘汰的限流器管理器
+    ///
+    /// # 参数
+    /// - `idle_ttl`: 空闲超过该时长的限流器会被后台任务清理
+    /// - `max_entries`: 每类限流器的条目上限，按分片均分；分片满时淘汰其中最久未访问的条目
+    ///
+    /// 后台清理任务在于 Tokio 运行时内获取限流器时启动，管理器释放后自动退出；
+    /// 任务所在的运行时关闭后，下次获取限流器时在当前运行时重新启动，
+    /// 并优先迁移到多线程运行时上。
+    ///
+    /// 正在被外部持有的限流器（如持有许可的并发限制器）既不会因空闲被清理，
+    /// 也不会因条目上限被淘汰；分片中的条目都被持有时，条目数可以暂时超过上限。
+    pub fn with_eviction(idle_ttl: Duration, max_entries: usize) -> Self {
+        Self::build(Some(EvictionConfig {
+            idle_ttl,
+            shard_cap: max_entries.div_ceil(SHARD_COUNT).max(1),
+        }))
+    }
+
+    fn build(eviction: Option<EvictionConfig>) -> Self {
         Self {
-            rate_limiters: Mutex::new(HashMap::new()),
-            quota_limiters: Mutex::new(HashMap::new()),
-            concurrency_limiters: Mutex::new(HashMap::new()),
+            inner: Arc::new(Inner {
+                rate_limiters: ShardedMap::new(),
+                quota_limiters: ShardedMap::new(),
+                concurrency_limiters: ShardedMap::new(),
+                eviction,
+                evicted_total: AtomicU64::new(0),
+            }),
+            sweeper: Mutex::new(None),
         }
     }
 
+    /// 确保后台清理任务在运行
+    ///
+    /// 任务尚未启动或所在运行时已关闭时在当前 Tokio 运行时中启动；任务运行在
+    /// current_thread 运行时（如同步函数路径的线程局部运行时）上而当前处于多线程
+    /// 运行时时，迁移到当前运行时，避免任务随不再被驱动的运行时停止。
+    fn ensure_sweeper(&self) {
+        let Some(eviction) = self.inner.eviction else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let multi_thread = !matches!(
+            handle.runtime_flavor(),
+            tokio::runtime::RuntimeFlavor::CurrentThread
+        );
+        // 其他线程正在检查时直接返回
+        let Some(mut sweeper) = self.sweeper.try_lock() else {
+            return;
+        };
+        if let Some(task) = sweeper.as_ref() {
+            if !task.handle.is_finished() && (task.multi_thread || !multi_thread) {
+                return;
+            }
+            task.handle.abort();
+        }
+
+        let inner: Weak<Inner> = Arc::downgrade(&self.inner);
+        let period = (eviction.idle_ttl / 2).max(MIN_SWEEP_INTERVAL);
+        let task = handle.spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(inner) = inner.upgrade() else {
+                    break;
+                };
+                let evicted = inner.evict_idle();
+                if evicted > 0 {
+                    debug!("LimiterManager evicted {} idle limiters", evicted);
+                }
+            }
+        });
+        *sweeper = Some(SweeperTask {
+            handle: task,
+            multi_thread,
+        });
+    }
+
     /// 获取或创建速率限制器
     pub fn get_rate_limiter(
         &self,
@@ -36,12 +399,14 @@ impl LimiterManager {
         capacity: u64,
         refill_rate: u64,
     ) -> Arc<TokenBucketLimiter> {
-        let mut limiters = self.rate_limiters.lock();
-        if let Some(limiter) = limiters.get(key) {
-            return limiter.clone();
-        }
-        let limiter = Arc::new(TokenBucketLimiter::new(capacity, refill_rate));
-        limiters.insert(key.to_string(), limiter.clone());
+        self.ensure_sweeper();
+        let (limiter, evicted) =
+            self.inner
+                .rate_limiters
+                .get_or_insert_with(key, self.inner.shard_cap(), || {
+                    TokenBucketLimiter::new(capacity, refill_rate)
+                });
+        self.inner.record_evicted(evicted);
         limiter
     }
 
@@ -52,12 +417,14 @@ impl LimiterManager {
         duration: Duration,
         max_requests: u64,
     ) -> Arc<FixedWindowLimiter> {
-        let mut limiters = self.quota_limiters.lock();
-        if let Some(limiter) = limiters.get(key) {
-            return limiter.clone();
-        }
-        let limiter = Arc::new(FixedWindowLimiter::new(duration, max_requests));
-        limiters.insert(key.to_string(), limiter.clone());
+        self.ensure_sweeper();
+        let (limiter, evicted) =
+            self.inner
+                .quota_limiters
+                .get_or_insert_with(key, self.inner.shard_cap(), || {
+                    FixedWindowLimiter::new(duration, max_requests)
+                });
+        self.inner.record_evicted(evicted);
         limiter
     }
 
@@ -67,24 +434,73 @@ impl LimiterManager {
         key: &str,
         max_concurrent: u64,
     ) -> Arc<ConcurrencyLimiter> {
-        let mut limiters = self.concurrency_limiters.lock();
-        if let Some(limiter) = limiters.get(key) {
-            return limiter.clone();
-        }
+        self.ensure_sweeper();
         // 使用带超时的并发限制器，超时时间 50ms
-        let limiter = Arc::new(ConcurrencyLimiter::with_timeout(
-            max_concurrent,
-            Duration::from_millis(50),
-        ));
-        limiters.insert(key.to_string(), limiter.clone());
+        let (limiter, evicted) =
+            self.inner
+                .concurrency_limiters
+                .get_or_insert_with(key, self.inner.shard_cap(), || {
+                    ConcurrencyLimiter::with_timeout(max_concurrent, Duration::from_millis(50))
+                });
+        self.inner.record_evicted(evicted);
         limiter
     }
 
+    /// 立即清理空闲超过 `idle_ttl` 的限流器，返回清理数量
+    ///
+    /// 未启用淘汰时返回 0。
+    pub fn evict_idle(&self) -> u64 {
+        self.inner.evict_idle()
+    }
+
+    /// 获取统计信息
+    pub fn stats(&self) -> LimiterManagerStats {
+        LimiterManagerStats {
+            active_limiters: self.inner.rate_limiters.len()
+                + self.inner.quota_limiters.len()
+                + self.inner.concurrency_limiters.len(),
+            evicted_total: self.inner.evicted_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 汇总所有速率与配额限流器的状态快照
+    ///
+    /// 只读取状态，不消费额度。返回 `(key, snapshot)` 列表，按 key 排序。
+    pub fn snapshots(&self) -> Vec<(String, LimiterSnapshot)> {
+        let mut snapshots: Vec<(String, LimiterSnapshot)> = Vec::new();
+        self.inner
+            .rate_limiters
+            .for_each(|key, limiter| snapshots.push((key.clone(), limiter.peek())));
+        self.inner
+            .quota_limiters
+            .for_each(|key, limiter| snapshots.push((key.clone(), limiter.peek())));
+        snapshots.sort_by(|a, b| a.0.cmp(&b.0));
+        snapshots
+    }
+
+    /// 将 key 对应的速率与配额限流器恢复到初始状态
+    ///
+    /// `key` 为宏生成的完整限流键（如 `rate:handler:alice`）。限流器被原地重置，
+    /// 已持有该实例的调用方立即看到补满的额度。并发限制器的许可由调用方持有，
+    /// 不受影响。返回是否找到了对应的限流器。
+    pub async fn reset(&self, key: &str) -> Result<bool, FlowGuardError> {
+        let mut found = false;
+        if let Some(limiter) = self.inner.rate_limiters.get(key) {
+            limiter.reset().await?;
+            found = true;
+        }
+        if let Some(limiter) = self.inner.quota_limiters.get(key) {
+            limiter.reset().await?;
+            found = true;
+        }
+        Ok(found)
+    }
+
     /// 清除所有限流器
     pub fn clear(&self) {
-        self.rate_limiters.lock().clear();
-        self.quota_limiters.lock().clear();
-        self.concurrency_limiters.lock().clear();
+        self.inner.rate_limiters.clear();
+        self.inner.quota_limiters.clear();
+        self.inner.concurrency_limiters.clear();
     }
 }
 
@@ -96,5 +512,231 @@ impl Default for LimiterManager {
 
 lazy_static::lazy_static! {
     /// 全局限流器管理器实例
-    pub static ref GLOBAL_LIMITER_MANAGER: LimiterManager = LimiterManager::new();
+    pub static ref GLOBAL_LIMITER_MANAGER: LimiterManager = LimiterManager::with_eviction(
+        Duration::from_secs(DEFAULT_LIMITER_IDLE_TTL_SECS),
+        DEFAULT_MAX_LIMITERS,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limiters::Limiter;
+
+    #[tokio::test]
+    async fn test_snapshots_aggregate_all_keys() {
+        let manager = LimiterManager::new();
+        let rate = manager.get_rate_limiter("rate:a", 10, 1);
+        let quota = manager.get_quota_limiter("quota:b", Duration::from_secs(60), 100);
+
+        assert!(rate.allow(3).await.unwrap());
+        assert!(quota.allow(5).await.unwrap());
+
+        let snapshots = manager.snapshots();
+        assert_eq!(snapshots.len(), 2);
+
+        assert_eq!(snapshots[0].0, "quota:b");
+        assert!(matches!(
+            snapshots[0].1,
+            LimiterSnapshot::Window {
+                used: 5,
+                limit: 100,
+                ..
+            }
+        ));
+
+        assert_eq!(snapshots[1].0, "rate:a");
+        assert!(matches!(
+            snapshots[1].1,
+            LimiterSnapshot::TokenBucket {
+                available: 7,
+                capacity: 10,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reset_restores_depleted_limiters() {
+        let manager = LimiterManager::new();
+        let rate = manager.get_rate_limiter("rate:h:alice", 2, 1);
+        let quota = manager.get_quota_limiter("quota:h:alice", Duration::from_secs(60), 1);
+
+        assert!(rate.allow(2).await.unwrap());
+        assert!(!rate.allow(1).await.unwrap());
+        assert!(quota.allow(1).await.unwrap());
+        assert!(!quota.allow(1).await.unwrap());
+
+        assert!(manager.reset("rate:h:alice").await.unwrap());
+        assert!(manager.reset("quota:h:alice").await.unwrap());
+        assert!(!manager.reset("rate:h:bob").await.unwrap());
+
+        // 重置后立即放行，且与已持有的实例是同一个限流器
+        assert!(rate.allow(2).await.unwrap());
+        assert!(quota.allow(1).await.unwrap());
+    }
+
+    #[test]
+    fn test_sanitize_key_strategies() {
+        let prefix = "p".repeat(MAX_LIMITER_KEY_LEN);
+        let a = format!("{}-alice", prefix);
+        let b = format!("{}-bob", prefix);
+
+        // 截断策略下共享前缀的长标识符会冲突
+        assert_eq!(
+            sanitize_key(&a, KeyStrategy::Truncate),
+            sanitize_key(&b, KeyStrategy::Truncate)
+        );
+        assert_eq!(
+            sanitize_key(&a, KeyStrategy::Truncate).chars().count(),
+            MAX_LIMITER_KEY_LEN
+        );
+
+        let hashed_a = sanitize_key(&a, KeyStrategy::Hash);
+        let hashed_b = sanitize_key(&b, KeyStrategy::Hash);
+        assert_ne!(hashed_a, hashed_b);
+        assert_eq!(hashed_a.chars().count(), MAX_LIMITER_KEY_LEN);
+        assert_eq!(hashed_a, sanitize_key(&a, KeyStrategy::Hash));
+
+        // 合法的短标识符原样保留，含非法字符时通过哈希区分
+        assert_eq!(sanitize_key("10.0.0.1", KeyStrategy::Hash), "10.0.0.1");
+        assert_ne!(
+            sanitize_key("ip:10.0.0.1", KeyStrategy::Hash),
+            sanitize_key("ip10.0.0.1", KeyStrategy::Hash)
+        );
+        assert_ne!(
+            sanitize_key("a b", KeyStrategy::Hash),
+            sanitize_key("ab", KeyStrategy::Hash)
+        );
+        assert_eq!(sanitize_key("a b", KeyStrategy::Truncate), "ab");
+        // 截断策略与宏原有的清洗闭包逐字节相同
+        let legacy = |s: &str| {
+            s.chars()
+                .filter(|c: &char| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.')
+                .take(128)
+                .collect::<String>()
+        };
+        for input in ["user:alice", "a b/c", "ünï-cødé_1.2", a.as_str()] {
+            assert_eq!(sanitize_key(input, KeyStrategy::Truncate), legacy(input));
+        }
+
+        assert_eq!("hash".parse::<KeyStrategy>().unwrap(), KeyStrategy::Hash);
+        assert!("md5".parse::<KeyStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_same_key_returns_shared_instance() {
+        let manager = LimiterManager::new();
+        let a = manager.get_concurrency_limiter("conc:a", 4);
+        let b = manager.get_concurrency_limiter("conc:a", 8);
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let keys: Vec<String> = (0..256).map(|i| format!("rate:{}", i)).collect();
+        for key in &keys {
+            manager.get_rate_limiter(key, 10, 1);
+        }
+        assert_eq!(manager.snapshots().len(), keys.len());
+
+        manager.clear();
+        assert!(manager.snapshots().is_empty());
+        let c = manager.get_concurrency_limiter("conc:a", 4);
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_limiters_are_evicted() {
+        let manager = LimiterManager::with_eviction(Duration::from_secs(60), 100_000);
+        for i in 0..10_000 {
+            manager.get_rate_limiter(&format!("rate:{}", i), 10, 1);
+        }
+        assert_eq!(manager.stats().active_limiters, 10_000);
+
+        // 30 秒后仍在访问的 key 不会被清理
+        tokio::time::advance(Duration::from_secs(30)).await;
+        for i in 0..100 {
+            manager.get_rate_limiter(&format!("rate:{}", i), 10, 1);
+        }
+
+        // 后台任务在空闲超过 60 秒后清理其余 key
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        let stats = manager.stats();
+        assert_eq!(stats.active_limiters, 100);
+        assert_eq!(stats.evicted_total, 9_900);
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(manager.evict_idle(), 100);
+        assert_eq!(manager.stats().active_limiters, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_held_limiters_are_not_evicted_when_idle() {
+        let manager = LimiterManager::with_eviction(Duration::from_secs(60), 100_000);
+        let held = manager.get_concurrency_limiter("conc:held", 1);
+        manager.get_concurrency_limiter("conc:idle", 1);
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert_eq!(manager.evict_idle(), 1);
+
+        let again = manager.get_concurrency_limiter("conc:held", 1);
+        assert!(Arc::ptr_eq(&held, &again));
+    }
+
+    #[test]
+    fn test_sweeper_restarts_after_runtime_shutdown() {
+        let manager = LimiterManager::with_eviction(Duration::from_secs(60), 100_000);
+
+        // 首次访问所在的运行时随后被释放，清理任务随之停止
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            manager.get_rate_limiter("rate:a", 10, 1);
+        });
+        drop(runtime);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            manager.get_rate_limiter("rate:b", 10, 1);
+            // 清理周期为 30 秒，第 90 秒的清理时两个 key 都已空闲超过 60 秒
+            tokio::time::sleep(Duration::from_secs(91)).await;
+            let stats = manager.stats();
+            assert_eq!(stats.active_limiters, 0);
+            assert_eq!(stats.evicted_total, 2);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_max_entries_keeps_held_limiters() {
+        // 每个分片只容纳一个条目
+        let manager = LimiterManager::with_eviction(Duration::from_secs(3600), SHARD_COUNT);
+        let held = manager.get_concurrency_limiter("conc:held", 1);
+        let _permit = held.acquire(1).await.unwrap();
+
+        for i in 0..1_000 {
+            manager.get_concurrency_limiter(&format!("conc:{}", i), 1);
+        }
+
+        // 持有许可的限制器未被替换，并发上限仍然生效
+        let again = manager.get_concurrency_limiter("conc:held", 1);
+        assert!(Arc::ptr_eq(&held, &again));
+        assert!(again.acquire(1).await.is_err());
+        assert!(manager.stats().active_limiters <= SHARD_COUNT + 1);
+    }
+
+    #[test]
+    fn test_max_entries_evicts_least_recently_used() {
+        let manager = LimiterManager::with_eviction(Duration::from_secs(3600), SHARD_COUNT);
+        for i in 0..1_000 {
+            manager.get_quota_limiter(&format!("quota:{}", i), Duration::from_secs(60), 10);
+        }
+
+        let stats = manager.stats();
+        assert!(stats.active_limiters <= SHARD_COUNT);
+        assert_eq!(stats.evicted_total as usize, 1_000 - stats.active_limiters);
+    }
 }
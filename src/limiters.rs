@@ -6,9 +6,11 @@
 //!
 //! 实现各种限流算法。
 
+mod gcra;
 #[cfg(feature = "quota-control")]
 mod quota_limiter;
 
+use crate::clock::{clock_went_backward, Clock, RealClock};
 use crate::constants::MAX_COST;
 use crate::constants::MAX_SPIN_ITERATIONS;
 use crate::error::FlowGuardError;
@@ -51,6 +53,22 @@ fn validate_cost(cost: u64) -> Result<u64, FlowGuardError> {
     Ok(cost)
 }
 
+/// 限流详细决策
+///
+/// 除了是否允许之外，还携带剩余额度和重试时间，
+/// 便于调用方生成 `X-RateLimit-Remaining`、`Retry-After` 等响应头。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    /// 是否允许
+    pub allowed: bool,
+    /// 本次检查后剩余的额度
+    pub remaining: u64,
+    /// 额度上限
+    pub limit: u64,
+    /// 被拒绝时建议的重试等待时间
+    pub retry_after: Option<Duration>,
+}
+
 /// 限流器 trait
 pub trait Limiter: Send + Sync {
     /// 检查是否允许
@@ -59,6 +77,64 @@ pub trait Limiter: Send + Sync {
         cost: u64,
     ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>>;
 
+    /// 检查是否允许，并返回剩余额度、上限和重试时间
+    ///
+    /// 默认实现基于 `allow`，额外字段尽力而为：`remaining` 和 `limit` 为 0，
+    /// `retry_after` 为 `None`。内置限流器会覆盖此方法给出准确信息。
+    fn allow_detailed(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<RateLimitDecision, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let allowed = self.allow(cost).await?;
+            Ok(RateLimitDecision {
+                allowed,
+                remaining: 0,
+                limit: 0,
+                retry_after: None,
+            })
+        })
+    }
+
+    /// 预判 `cost` 个单位的请求当前是否会被允许，不消费额度
+    ///
+    /// 结果仅供参考：检查与后续的 `allow` 之间没有原子性，并发请求可能在此期间耗尽额度，
+    /// 因此 `would_allow` 返回 `true` 不保证随后的 `allow` 成功。适用于预估、展示等场景，
+    /// 不能代替真正的限流检查。
+    ///
+    /// 默认实现返回 `FlowGuardError::LimitError`，表示该限流器不支持只读检查。
+    fn would_allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let _ = cost;
+            Err(FlowGuardError::LimitError(
+                "该限流器不支持 would_allow".to_string(),
+            ))
+        })
+    }
+
+    /// 读取当前状态快照，不消费额度
+    ///
+    /// 实现了 [`Observable`] 的内置限流器返回 `Some`，默认实现返回 `None`。
+    fn snapshot(&self) -> Option<LimiterSnapshot> {
+        None
+    }
+
+    /// 将限流器恢复到初始状态（令牌桶补满、窗口计数清零）
+    ///
+    /// 用于管理操作，例如人工豁免某个用户后立即恢复其额度。
+    ///
+    /// 默认实现返回 `FlowGuardError::LimitError`，表示该限流器不支持重置。
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            Err(FlowGuardError::LimitError(
+                "该限流器不支持 reset".to_string(),
+            ))
+        })
+    }
+
     /// 检查是否允许（接受 key 参数，用于宏）
     /// 默认实现：消费 1 个单位的 cost
     fn check(
@@ -72,6 +148,50 @@ pub trait Limiter: Send + Sync {
     }
 }
 
+/// 限流器状态快照
+///
+/// 由 [`Observable::peek`] 返回，反映限流器当前的计数状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LimiterSnapshot {
+    /// 令牌桶类限流器（令牌桶、GCRA）
+    TokenBucket {
+        /// 当前可用的令牌数
+        available: u64,
+        /// 桶容量
+        capacity: u64,
+        /// 距离下一个令牌补充的时间，桶已满或不补充时为 `None`
+        next_refill: Option<Duration>,
+    },
+    /// 窗口类限流器（滑动窗口、固定窗口）
+    Window {
+        /// 当前窗口内已使用的请求数
+        used: u64,
+        /// 窗口内最大请求数
+        limit: u64,
+        /// 距离窗口重置（或最早的请求滑出窗口）的时间，窗口为空时为 `None`
+        window_reset: Option<Duration>,
+    },
+}
+
+/// 可观测的限流器
+///
+/// 在不消费额度的前提下读取限流器的当前状态，用于监控面板等场景。
+pub trait Observable: Send + Sync {
+    /// 读取当前状态快照（只读，不修改限流器状态）
+    fn peek(&self) -> LimiterSnapshot;
+}
+
+impl LimiterSnapshot {
+    /// 按当前快照判断 `cost` 个单位的请求是否会被允许
+    pub fn allows(&self, cost: u64) -> bool {
+        match *self {
+            LimiterSnapshot::TokenBucket { available, .. } => available >= cost,
+            LimiterSnapshot::Window { used, limit, .. } => used.saturating_add(cost) <= limit,
+        }
+    }
+}
+
 /// 令牌桶限流器
 ///
 /// 使用令牌桶算法实现速率限制，令牌以恒定速率补充到桶中，
@@ -105,7 +225,13 @@ pub struct TokenBucketLimiter {
     tokens: std::sync::atomic::AtomicU64,
     /// 令牌补充速率（令牌/秒）
     refill_rate: u64,
-    /// 最后补充时间（纳秒时间戳）
+    /// 时间来源
+    clock: Arc<dyn Clock>,
+    /// 计时起点（单调时钟）
+    epoch: Instant,
+    /// 最后补充时间（相对 `epoch` 的纳秒数）
+    ///
+    /// 只推进到已折算为整数令牌的时间点，不足一个令牌的余量留待下次补充。
     last_refill: std::sync::atomic::AtomicU64,
 }
 
@@ -123,27 +249,94 @@ impl TokenBucketLimiter {
     /// let limiter = TokenBucketLimiter::new(100, 10);
     /// ```
     pub fn new(capacity: u64, refill_rate: u64) -> Self {
+        Self::with_clock(capacity, refill_rate, RealClock::shared())
+    }
+
+    /// Creates a token bucket limiter that reads time from `clock`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::clock::MockClock;
+    /// use limiteron::limiters::{LimiterSnapshot, Observable, TokenBucketLimiter};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Arc::new(MockClock::new());
+    /// let limiter = TokenBucketLimiter::with_clock(10, 5, clock.clone());
+    /// clock.advance(Duration::from_secs(1));
+    /// assert!(matches!(limiter.peek(), LimiterSnapshot::TokenBucket { available: 10, .. }));
+    /// ```
+    pub fn with_clock(capacity: u64, refill_rate: u64, clock: Arc<dyn Clock>) -> Self {
+        Self::with_tokens(capacity, refill_rate, capacity, clock)
+    }
+
+    /// Creates a token bucket limiter that starts with `initial` tokens instead of a full bucket.
+    ///
+    /// Starting empty (or partially filled) avoids a synchronized burst when many instances
+    /// deploy at the same time; the bucket then fills at `refill_rate`.
+    ///
+    /// # Errors
+    /// Returns [`FlowGuardError::ConfigError`] if `initial` exceeds `capacity`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::limiters::{LimiterSnapshot, Observable, TokenBucketLimiter};
+    ///
+    /// let limiter = TokenBucketLimiter::with_initial_tokens(100, 10, 0).unwrap();
+    /// assert!(matches!(limiter.peek(), LimiterSnapshot::TokenBucket { available: 0, .. }));
+    /// assert!(TokenBucketLimiter::with_initial_tokens(100, 10, 101).is_err());
+    /// ```
+    pub fn with_initial_tokens(
+        capacity: u64,
+        refill_rate: u64,
+        initial: u64,
+    ) -> Result<Self, FlowGuardError> {
+        if initial > capacity {
+            return Err(FlowGuardError::ConfigError(format!(
+                "Initial tokens ({}) cannot exceed capacity ({})",
+                initial, capacity
+            )));
+        }
+        Ok(Self::with_tokens(
+            capacity,
+            refill_rate,
+            initial,
+            RealClock::shared(),
+        ))
+    }
+
+    fn with_tokens(capacity: u64, refill_rate: u64, initial: u64, clock: Arc<dyn Clock>) -> Self {
         Self {
             capacity,
-            tokens: std::sync::atomic::AtomicU64::new(capacity),
+            tokens: std::sync::atomic::AtomicU64::new(initial),
             refill_rate,
-            last_refill: std::sync::atomic::AtomicU64::new(
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64,
-            ),
+            epoch: clock.now(),
+            clock,
+            last_refill: std::sync::atomic::AtomicU64::new(0),
         }
     }
 
     /// Refills tokens based on elapsed time.
     ///
-    /// Uses CAS loop for atomicity with SeqCst ordering.
+    /// Uses CAS loop for atomicity with SeqCst ordering. Only the time that was
+    /// converted into whole tokens is consumed, so fractional tokens accumulate
+    /// across calls instead of being truncated away.
     fn refill_tokens(&self) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        let now = self.now_nanos();
+
+        // 时钟回拨：不补充令牌，回拨幅度较大时把补充起点移到当前时间，避免长时间无法补充
+        let last = self.last_refill.load(std::sync::atomic::Ordering::Acquire);
+        if last > now {
+            if clock_went_backward("token_bucket", Duration::from_nanos(last - now)) {
+                let _ = self.last_refill.compare_exchange(
+                    last,
+                    now,
+                    std::sync::atomic::Ordering::Release,
+                    std::sync::atomic::Ordering::Relaxed,
+                );
+            }
+            return;
+        }
 
         // Use CAS loop to update last_refill and tokens atomically
         loop {
@@ -155,20 +348,24 @@ impl TokenBucketLimiter {
                 break;
             }
 
-            // Calculate tokens to add
-            let elapsed_seconds = elapsed_nanos as f64 / 1_000_000_000.0;
-            let tokens_to_add = (elapsed_seconds * self.refill_rate as f64) as u64;
-
+            let tokens_to_add = self.tokens_for(elapsed_nanos);
             if tokens_to_add == 0 {
                 break;
             }
 
+            // A full bucket discards any remainder; otherwise keep it for the next refill
+            let next_refill = if tokens_to_add >= self.capacity {
+                now
+            } else {
+                last + self.nanos_for(tokens_to_add)
+            };
+
             // Try to update last_refill timestamp
             if self
                 .last_refill
                 .compare_exchange(
                     last,
-                    now,
+                    next_refill,
                     std::sync::atomic::Ordering::Release,
                     std::sync::atomic::Ordering::Relaxed,
                 )
@@ -197,16 +394,30 @@ impl TokenBucketLimiter {
         }
     }
 
+    /// Whole tokens earned over `elapsed_nanos`, capped at capacity.
+    ///
+    /// Computed in `u128` so long idle gaps cannot overflow.
+    fn tokens_for(&self, elapsed_nanos: u64) -> u64 {
+        let tokens = elapsed_nanos as u128 * self.refill_rate as u128 / 1_000_000_000;
+        tokens.min(self.capacity as u128) as u64
+    }
+
+    /// Nanoseconds needed to earn `tokens` (rounded up).
+    fn nanos_for(&self, tokens: u64) -> u64 {
+        let rate = self.refill_rate.max(1) as u128;
+        let nanos = (tokens as u128 * 1_000_000_000).div_ceil(rate);
+        nanos.min(u64::MAX as u128) as u64
+    }
+
     /// 尝试消费指定数量的令牌
     ///
     /// # 参数
     /// - `cost`: 需要消费的令牌数量
     ///
     /// # 返回
-    /// - `Ok(true)`: 成功消费令牌
-    /// - `Ok(false)`: 令牌不足，无法消费
-    /// - `Err(_)`: 发生错误
-    fn try_consume(&self, cost: u64) -> bool {
+    /// - `Ok(remaining)`: 成功消费令牌，返回剩余令牌数
+    /// - `Err(available)`: 令牌不足，无法消费，返回当前可用令牌数
+    fn try_consume(&self, cost: u64) -> Result<u64, u64> {
         let mut retry_count = 0u32;
         const MAX_RETRY: u32 = 3;
 
@@ -215,7 +426,7 @@ impl TokenBucketLimiter {
 
             // 检查令牌是否足够
             if current < cost {
-                return false;
+                return Err(current);
             }
 
             // 尝试消费令牌
@@ -225,12 +436,12 @@ impl TokenBucketLimiter {
                 std::sync::atomic::Ordering::Release,
                 std::sync::atomic::Ordering::Relaxed,
             ) {
-                Ok(_) => return true,
-                Err(_) => {
+                Ok(_) => return Ok(current - cost),
+                Err(actual) => {
                     retry_count += 1;
                     if retry_count >= MAX_RETRY {
                         // 超过最大重试次数，放弃
-                        return false;
+                        return Err(actual);
                     }
 
                     // 指数退避：使用自旋提示替代阻塞睡眠
@@ -247,6 +458,15 @@ impl TokenBucketLimiter {
         }
     }
 
+    /// 当前时间（相对 `epoch` 的纳秒数）
+    fn now_nanos(&self) -> u64 {
+        self.clock
+            .now()
+            .saturating_duration_since(self.epoch)
+            .as_nanos()
+            .min(u64::MAX as u128) as u64
+    }
+
     /// 获取当前令牌数（仅用于测试）
     #[cfg(test)]
     fn get_tokens(&self) -> u64 {
@@ -267,15 +487,155 @@ impl Limiter for TokenBucketLimiter {
             self.refill_tokens();
 
             // 尝试消费令牌
-            Ok(self.try_consume(cost))
+            Ok(self.try_consume(cost).is_ok())
+        })
+    }
+
+    fn allow_detailed(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<RateLimitDecision, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            self.refill_tokens();
+
+            let decision = match self.try_consume(cost) {
+                Ok(remaining) => RateLimitDecision {
+                    allowed: true,
+                    remaining,
+                    limit: self.capacity,
+                    retry_after: None,
+                },
+                Err(available) => {
+                    // 超过容量的请求永远无法满足，不给出重试时间
+                    let retry_after = if cost > self.capacity || self.refill_rate == 0 {
+                        None
+                    } else {
+                        let missing = cost - available.min(cost);
+                        Some(Duration::from_secs_f64(
+                            missing as f64 / self.refill_rate as f64,
+                        ))
+                    };
+                    RateLimitDecision {
+                        allowed: false,
+                        remaining: available,
+                        limit: self.capacity,
+                        retry_after,
+                    }
+                }
+            };
+
+            Ok(decision)
         })
     }
+
+    fn would_allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            // peek 计算补充后的可用令牌，但不写回
+            Ok(self.peek().allows(cost))
+        })
+    }
+
+    fn snapshot(&self) -> Option<LimiterSnapshot> {
+        Some(self.peek())
+    }
+
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            // 先推进补充起点，避免补满后又按旧起点重复补充
+            self.last_refill
+                .store(self.now_nanos(), std::sync::atomic::Ordering::Release);
+            self.tokens
+                .store(self.capacity, std::sync::atomic::Ordering::Release);
+            Ok(())
+        })
+    }
+}
+
+impl Observable for TokenBucketLimiter {
+    fn peek(&self) -> LimiterSnapshot {
+        let tokens = self.tokens.load(std::sync::atomic::Ordering::Acquire);
+        let last = self.last_refill.load(std::sync::atomic::Ordering::Acquire);
+        let elapsed_nanos = self.now_nanos().saturating_sub(last);
+
+        // 与 refill_tokens 相同的计算方式，但不写回
+        let pending = self.tokens_for(elapsed_nanos);
+        let available = tokens.saturating_add(pending).min(self.capacity);
+
+        let next_refill = if available >= self.capacity || self.refill_rate == 0 {
+            None
+        } else {
+            let next_token_at = self.nanos_for(pending + 1);
+            Some(Duration::from_nanos(
+                next_token_at.saturating_sub(elapsed_nanos),
+            ))
+        };
+
+        LimiterSnapshot::TokenBucket {
+            available,
+            capacity: self.capacity,
+            next_refill,
+        }
+    }
+}
+
+/// 滑动窗口算法模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum SlidingWindowMode {
+    /// 滑动窗口计数器：按上一个与当前子窗口的计数加权估算，内存占用固定，结果近似
+    #[default]
+    Counter,
+    /// 滑动窗口日志：记录每个请求的时间戳，结果精确，内存随请求数增长
+    Log,
+}
+
+/// 滑动窗口计数器状态
+#[derive(Debug, Clone, Copy)]
+struct CounterWindow {
+    /// 当前子窗口开始时间
+    window_start: Instant,
+    /// 上一个子窗口的计数
+    previous: u64,
+    /// 当前子窗口的计数
+    current: u64,
+}
+
+impl CounterWindow {
+    /// 推进到 now 所在的子窗口，返回当前子窗口已经过的时间
+    fn advance(&mut self, window_size: Duration, now: Instant) -> Duration {
+        // 时钟回拨：保留计数，回拨幅度较大时子窗口从当前时间重新开始
+        if let Some(ahead_by) = self.window_start.checked_duration_since(now) {
+            if clock_went_backward("sliding_window", ahead_by) {
+                self.window_start = now;
+            }
+            return Duration::ZERO;
+        }
+
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < window_size {
+            return elapsed;
+        }
+
+        let window_nanos = window_size.as_nanos().max(1);
+        let windows_passed = elapsed.as_nanos() / window_nanos;
+        // 只跨过一个子窗口时当前计数成为上一个窗口的计数，否则两者都已过期
+        self.previous = if windows_passed == 1 { self.current } else { 0 };
+        self.current = 0;
+        let offset = Duration::from_nanos((elapsed.as_nanos() % window_nanos) as u64);
+        self.window_start = now - offset;
+        offset
+    }
 }
 
 /// 滑动窗口限流器
 ///
-/// 使用滑动窗口算法实现速率限制，记录请求的时间戳，
-/// 统计滑动窗口内的请求数量，超过阈值则拒绝请求。
+/// 使用滑动窗口算法实现速率限制，统计滑动窗口内的请求数量，超过阈值则拒绝请求。
+/// 支持两种模式（见 [`SlidingWindowMode`]）：`new` 使用精确的日志模式，
+/// `with_mode` 可以选择内存占用固定的计数器模式。
 ///
 /// # 特性
 /// - 支持可配置窗口精度（通过分片数）
@@ -303,8 +663,14 @@ pub struct SlidingWindowLimiter {
     window_size: Duration,
     /// 窗口内最大请求数
     max_requests: u64,
-    /// 请求时间戳队列（使用 Arc<Mutex> 实现线程安全）
+    /// 算法模式
+    mode: SlidingWindowMode,
+    /// 请求时间戳队列（日志模式，使用 Arc<Mutex> 实现线程安全）
     requests: Arc<Mutex<VecDeque<Instant>>>,
+    /// 子窗口计数（计数器模式）
+    counter: Arc<Mutex<CounterWindow>>,
+    /// 时间来源
+    clock: Arc<dyn Clock>,
 }
 
 impl SlidingWindowLimiter {
@@ -322,19 +688,91 @@ impl SlidingWindowLimiter {
     /// let limiter = SlidingWindowLimiter::new(Duration::from_secs(1), 100);
     /// ```
     pub fn new(window_size: Duration, max_requests: u64) -> Self {
+        Self::with_mode(window_size, max_requests, SlidingWindowMode::Log)
+    }
+
+    /// Creates a sliding window limiter using the given algorithm mode.
+    ///
+    /// # Arguments
+    /// * `window_size` - Sliding window duration
+    /// * `max_requests` - Maximum requests per window
+    /// * `mode` - `Log` keeps exact timestamps, `Counter` interpolates sub-window counts
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::limiters::{SlidingWindowLimiter, SlidingWindowMode};
+    /// use std::time::Duration;
+    ///
+    /// let limiter =
+    ///     SlidingWindowLimiter::with_mode(Duration::from_secs(1), 100, SlidingWindowMode::Counter);
+    /// ```
+    pub fn with_mode(window_size: Duration, max_requests: u64, mode: SlidingWindowMode) -> Self {
+        Self::with_clock(window_size, max_requests, mode, RealClock::shared())
+    }
+
+    /// Creates a sliding window limiter that reads time from `clock`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::clock::MockClock;
+    /// use limiteron::limiters::{SlidingWindowLimiter, SlidingWindowMode};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Arc::new(MockClock::new());
+    /// let limiter = SlidingWindowLimiter::with_clock(
+    ///     Duration::from_secs(1),
+    ///     100,
+    ///     SlidingWindowMode::Log,
+    ///     clock.clone(),
+    /// );
+    /// ```
+    pub fn with_clock(
+        window_size: Duration,
+        max_requests: u64,
+        mode: SlidingWindowMode,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         // Pre-allocate deque capacity based on max_requests to reduce allocations
-        let capacity = (max_requests as usize).min(10_000);
+        let capacity = match mode {
+            SlidingWindowMode::Log => (max_requests as usize).min(10_000),
+            SlidingWindowMode::Counter => 0,
+        };
         Self {
             window_size,
             max_requests,
+            mode,
             requests: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            counter: Arc::new(Mutex::new(CounterWindow {
+                window_start: clock.now(),
+                previous: 0,
+                current: 0,
+            })),
+            clock,
         }
     }
 
+    /// 获取算法模式
+    pub fn mode(&self) -> SlidingWindowMode {
+        self.mode
+    }
+
     /// 清理过期的请求记录
     fn cleanup_expired_requests(&self) {
         let mut requests = self.requests.lock().unwrap();
-        let now = Instant::now();
+        let now = self.clock.now();
+
+        // 时钟回拨：整体平移时间戳，相当于自最近一次请求以来没有经过时间
+        if let Some(ahead_by) = requests
+            .back()
+            .and_then(|&back| back.checked_duration_since(now))
+        {
+            if clock_went_backward("sliding_window", ahead_by) {
+                for ts in requests.iter_mut() {
+                    *ts = ts.checked_sub(ahead_by).unwrap_or(now);
+                }
+            }
+        }
 
         // 移除窗口外的请求
         while let Some(&front) = requests.front() {
@@ -354,6 +792,122 @@ impl SlidingWindowLimiter {
     }
 }
 
+impl SlidingWindowLimiter {
+    /// 检查并记录请求，返回详细决策
+    fn acquire(&self, cost: u64) -> RateLimitDecision {
+        match self.mode {
+            SlidingWindowMode::Log => self.acquire_log(cost),
+            SlidingWindowMode::Counter => self.acquire_counter(cost),
+        }
+    }
+
+    /// 计数器模式：estimate = previous * (1 - elapsed / window) + current
+    fn acquire_counter(&self, cost: u64) -> RateLimitDecision {
+        let mut counter = self.counter.lock().unwrap();
+        let window = self.window_size.as_secs_f64();
+        let elapsed = counter
+            .advance(self.window_size, self.clock.now())
+            .as_secs_f64();
+
+        let previous_weight = if window > 0.0 {
+            (1.0 - elapsed / window).max(0.0)
+        } else {
+            0.0
+        };
+        let estimated =
+            (counter.previous as f64 * previous_weight).floor() as u64 + counter.current;
+
+        if estimated + cost > self.max_requests {
+            let retry_after = if cost > self.max_requests {
+                None
+            } else {
+                Some(self.counter_retry_after(&counter, cost, elapsed))
+            };
+            return RateLimitDecision {
+                allowed: false,
+                remaining: self.max_requests.saturating_sub(estimated),
+                limit: self.max_requests,
+                retry_after,
+            };
+        }
+
+        counter.current += cost;
+
+        RateLimitDecision {
+            allowed: true,
+            remaining: self.max_requests - estimated - cost,
+            limit: self.max_requests,
+            retry_after: None,
+        }
+    }
+
+    /// 计算计数器模式下 cost 个请求可以通过的等待时间
+    fn counter_retry_after(&self, counter: &CounterWindow, cost: u64, elapsed: f64) -> Duration {
+        let window = self.window_size.as_secs_f64();
+        let max = self.max_requests as f64;
+        let cost = cost as f64;
+
+        // previous * (1 - t / window) + current + cost <= max 解出 t
+        let decay_until = |previous: f64, current: f64| -> f64 {
+            if previous <= 0.0 {
+                return 0.0;
+            }
+            (window * (1.0 - (max - current - cost) / previous)).max(0.0)
+        };
+
+        let wait = if counter.current as f64 + cost <= max {
+            // 当前子窗口内等待上一个窗口的权重衰减
+            (decay_until(counter.previous as f64, counter.current as f64) - elapsed).max(0.0)
+        } else {
+            // 等到下一个子窗口，当前计数成为上一个窗口的计数
+            (window - elapsed) + decay_until(counter.current as f64, 0.0)
+        };
+
+        Duration::from_secs_f64(wait)
+    }
+
+    /// 日志模式：精确统计窗口内的请求时间戳
+    fn acquire_log(&self, cost: u64) -> RateLimitDecision {
+        // 清理过期请求
+        self.cleanup_expired_requests();
+
+        let mut requests = self.requests.lock().unwrap();
+        let current_count = requests.len() as u64;
+
+        // 检查是否超过限制
+        if current_count + cost > self.max_requests {
+            // 需要等待最早的若干条记录滑出窗口
+            let retry_after = if cost > self.max_requests {
+                None
+            } else {
+                let expire_index = (current_count + cost - self.max_requests - 1) as usize;
+                requests
+                    .get(expire_index)
+                    .map(|&ts| (ts + self.window_size).saturating_duration_since(self.clock.now()))
+            };
+            return RateLimitDecision {
+                allowed: false,
+                remaining: self.max_requests.saturating_sub(current_count),
+                limit: self.max_requests,
+                retry_after,
+            };
+        }
+
+        // 添加新的请求记录
+        let now = self.clock.now();
+        for _ in 0..cost {
+            requests.push_back(now);
+        }
+
+        RateLimitDecision {
+            allowed: true,
+            remaining: self.max_requests - current_count - cost,
+            limit: self.max_requests,
+            retry_after: None,
+        }
+    }
+}
+
 impl Limiter for SlidingWindowLimiter {
     fn allow(
         &self,
@@ -363,28 +917,86 @@ impl Limiter for SlidingWindowLimiter {
             // 验证 cost 参数
             let cost = validate_cost(cost)?;
 
-            // 清理过期请求
-            self.cleanup_expired_requests();
+            Ok(self.acquire(cost).allowed)
+        })
+    }
 
-            let mut requests = self.requests.lock().unwrap();
-            let current_count = requests.len() as u64;
+    fn allow_detailed(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<RateLimitDecision, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            Ok(self.acquire(cost))
+        })
+    }
 
-            // 检查是否超过限制
-            if current_count + cost > self.max_requests {
-                return Ok(false);
-            }
+    fn would_allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            Ok(self.peek().allows(cost))
+        })
+    }
 
-            // 添加新的请求记录
-            let now = Instant::now();
-            for _ in 0..cost {
-                requests.push_back(now);
-            }
+    fn snapshot(&self) -> Option<LimiterSnapshot> {
+        Some(self.peek())
+    }
 
-            Ok(true)
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            self.requests.lock().unwrap().clear();
+            let mut counter = self.counter.lock().unwrap();
+            counter.previous = 0;
+            counter.current = 0;
+            Ok(())
         })
     }
 }
 
+impl Observable for SlidingWindowLimiter {
+    fn peek(&self) -> LimiterSnapshot {
+        let now = self.clock.now();
+
+        let (used, window_reset) = match self.mode {
+            SlidingWindowMode::Log => {
+                let requests = self.requests.lock().unwrap();
+                let mut live = requests
+                    .iter()
+                    .filter(|&&ts| now.saturating_duration_since(ts) <= self.window_size);
+                let oldest = live.next();
+                let used = oldest.map_or(0, |_| 1 + live.count() as u64);
+                let window_reset =
+                    oldest.map(|&ts| (ts + self.window_size).saturating_duration_since(now));
+                (used, window_reset)
+            }
+            SlidingWindowMode::Counter => {
+                // 在副本上推进子窗口，避免修改状态
+                let mut counter = *self.counter.lock().unwrap();
+                let elapsed = counter.advance(self.window_size, now);
+                let window = self.window_size.as_secs_f64();
+                let previous_weight = if window > 0.0 {
+                    (1.0 - elapsed.as_secs_f64() / window).max(0.0)
+                } else {
+                    0.0
+                };
+                let used =
+                    (counter.previous as f64 * previous_weight).floor() as u64 + counter.current;
+                let window_reset = (used > 0).then(|| self.window_size.saturating_sub(elapsed));
+                (used, window_reset)
+            }
+        };
+
+        LimiterSnapshot::Window {
+            used,
+            limit: self.max_requests,
+            window_reset,
+        }
+    }
+}
+
 /// 固定窗口限流器
 ///
 /// 使用固定窗口算法实现速率限制，将时间划分为固定长度的窗口，
@@ -418,8 +1030,12 @@ pub struct FixedWindowLimiter {
     max_requests: u64,
     /// 当前窗口的计数
     count: std::sync::atomic::AtomicU64,
-    /// 当前窗口的开始时间（纳秒时间戳）
+    /// 当前窗口的开始时间（相对 `epoch` 的纳秒数）
     window_start: std::sync::atomic::AtomicU64,
+    /// 时间来源
+    clock: Arc<dyn Clock>,
+    /// 计时起点（单调时钟）
+    epoch: Instant,
 }
 
 impl FixedWindowLimiter {
@@ -437,27 +1053,46 @@ impl FixedWindowLimiter {
     /// let limiter = FixedWindowLimiter::new(Duration::from_secs(1), 100);
     /// ```
     pub fn new(window_size: Duration, max_requests: u64) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        Self::with_clock(window_size, max_requests, RealClock::shared())
+    }
 
+    /// Creates a fixed window limiter that reads time from `clock`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::clock::MockClock;
+    /// use limiteron::limiters::FixedWindowLimiter;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Arc::new(MockClock::new());
+    /// let limiter = FixedWindowLimiter::with_clock(Duration::from_secs(1), 100, clock.clone());
+    /// ```
+    pub fn with_clock(window_size: Duration, max_requests: u64, clock: Arc<dyn Clock>) -> Self {
         Self {
             window_size,
             max_requests,
             count: std::sync::atomic::AtomicU64::new(0),
-            window_start: std::sync::atomic::AtomicU64::new(now),
+            window_start: std::sync::atomic::AtomicU64::new(0),
+            epoch: clock.now(),
+            clock,
         }
     }
 
+    /// 当前时间（相对 `epoch` 的纳秒数）
+    fn now_nanos(&self) -> u64 {
+        self.clock
+            .now()
+            .saturating_duration_since(self.epoch)
+            .as_nanos()
+            .min(u64::MAX as u128) as u64
+    }
+
     /// Checks and resets the window if expired.
     ///
     /// Uses CAS for atomic window reset with proper alignment.
     fn check_and_reset_window(&self) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        let now = self.now_nanos();
 
         let window_size_nanos = self.window_size.as_nanos() as u64;
 
@@ -465,6 +1100,19 @@ impl FixedWindowLimiter {
             let current_start = self.window_start.load(std::sync::atomic::Ordering::Acquire);
             let window_end = current_start.saturating_add(window_size_nanos);
 
+            // 时钟回拨：保留计数，回拨幅度较大时窗口从当前时间重新开始
+            if current_start > now {
+                if clock_went_backward("fixed_window", Duration::from_nanos(current_start - now)) {
+                    let _ = self.window_start.compare_exchange(
+                        current_start,
+                        now,
+                        std::sync::atomic::Ordering::Release,
+                        std::sync::atomic::Ordering::Relaxed,
+                    );
+                }
+                break;
+            }
+
             // Current time still within window
             if now < window_end {
                 break;
@@ -499,6 +1147,60 @@ impl FixedWindowLimiter {
     }
 }
 
+impl FixedWindowLimiter {
+    /// 距离当前窗口结束的剩余时间
+    fn time_until_reset(&self) -> Duration {
+        let now = self.now_nanos();
+        let window_start = self.window_start.load(std::sync::atomic::Ordering::Acquire);
+        let window_end = window_start.saturating_add(self.window_size.as_nanos() as u64);
+        Duration::from_nanos(window_end.saturating_sub(now))
+    }
+
+    /// 检查并增加计数，返回详细决策
+    fn acquire(&self, cost: u64) -> RateLimitDecision {
+        // 检查并重置窗口
+        self.check_and_reset_window();
+
+        // 使用 CAS 循环尝试增加计数
+        loop {
+            let current = self.count.load(std::sync::atomic::Ordering::Acquire);
+
+            // 检查是否超过限制
+            if current + cost > self.max_requests {
+                let retry_after = if cost > self.max_requests {
+                    None
+                } else {
+                    Some(self.time_until_reset())
+                };
+                return RateLimitDecision {
+                    allowed: false,
+                    remaining: self.max_requests.saturating_sub(current),
+                    limit: self.max_requests,
+                    retry_after,
+                };
+            }
+
+            // 尝试增加计数
+            match self.count.compare_exchange(
+                current,
+                current + cost,
+                std::sync::atomic::Ordering::Release,
+                std::sync::atomic::Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    return RateLimitDecision {
+                        allowed: true,
+                        remaining: self.max_requests - current - cost,
+                        limit: self.max_requests,
+                        retry_after: None,
+                    }
+                }
+                Err(_) => continue, // CAS 失败，重试
+            }
+        }
+    }
+}
+
 impl Limiter for FixedWindowLimiter {
     fn allow(
         &self,
@@ -508,33 +1210,67 @@ impl Limiter for FixedWindowLimiter {
             // 验证 cost 参数
             let cost = validate_cost(cost)?;
 
-            // 检查并重置窗口
-            self.check_and_reset_window();
+            Ok(self.acquire(cost).allowed)
+        })
+    }
 
-            // 使用 CAS 循环尝试增加计数
-            loop {
-                let current = self.count.load(std::sync::atomic::Ordering::Acquire);
+    fn allow_detailed(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<RateLimitDecision, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            Ok(self.acquire(cost))
+        })
+    }
 
-                // 检查是否超过限制
-                if current + cost > self.max_requests {
-                    return Ok(false);
-                }
+    fn would_allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            Ok(self.peek().allows(cost))
+        })
+    }
 
-                // 尝试增加计数
-                match self.count.compare_exchange(
-                    current,
-                    current + cost,
-                    std::sync::atomic::Ordering::Release,
-                    std::sync::atomic::Ordering::Relaxed,
-                ) {
-                    Ok(_) => return Ok(true),
-                    Err(_) => continue, // CAS 失败，重试
-                }
-            }
+    fn snapshot(&self) -> Option<LimiterSnapshot> {
+        Some(self.peek())
+    }
+
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            self.check_and_reset_window();
+            self.count.store(0, std::sync::atomic::Ordering::Release);
+            Ok(())
         })
     }
 }
 
+impl Observable for FixedWindowLimiter {
+    fn peek(&self) -> LimiterSnapshot {
+        let now = self.now_nanos();
+        let window_size_nanos = (self.window_size.as_nanos() as u64).max(1);
+        let window_start = self.window_start.load(std::sync::atomic::Ordering::Acquire);
+        let elapsed = now.saturating_sub(window_start);
+
+        // 窗口已过期但尚未被重置时，视为新窗口
+        let used = if elapsed >= window_size_nanos {
+            0
+        } else {
+            self.count.load(std::sync::atomic::Ordering::Acquire)
+        };
+        let window_reset = (used > 0)
+            .then(|| Duration::from_nanos(window_size_nanos - elapsed % window_size_nanos));
+
+        LimiterSnapshot::Window {
+            used,
+            limit: self.max_requests,
+            window_reset,
+        }
+    }
+}
+
 /// 并发控制器
 ///
 /// 使用信号量实现并发控制，限制同时进行的操作数量。
@@ -557,76 +1293,465 @@ impl Limiter for FixedWindowLimiter {
 ///
 ///     // 尝试获取许可
 ///     let permit = limiter.acquire(1).await.unwrap();
+///     assert_eq!(limiter.in_flight(), 1);
 ///     // 使用许可...
 ///     drop(permit); // 释放许可
+///     assert_eq!(limiter.in_flight(), 0);
 /// }
 /// ```
 pub struct ConcurrencyLimiter {
     /// 信号量，用于管理并发数
     semaphore: Arc<tokio::sync::Semaphore>,
+    /// 最大并发数
+    max_concurrent: u64,
+    /// 正在 `acquire` 中等待许可的调用方数
+    waiters: std::sync::atomic::AtomicU64,
     /// 超时时间
     timeout: Option<Duration>,
+    /// FIFO 准入队列，仅由 [`ConcurrencyLimiter::fifo`] 创建时存在
+    queue: Option<AdmissionQueue>,
+    /// 指标中的控制器名称与监控指标
+    #[cfg(feature = "monitoring")]
+    metrics: Option<(String, Arc<crate::telemetry::Metrics>)>,
+}
+
+/// FIFO 准入队列
+///
+/// 按到达顺序排队的票号，只有队首能取得许可，许可归还或队首变化时唤醒所有等待者重新检查。
+struct AdmissionQueue {
+    /// 最大排队长度，`None` 表示不限制
+    max_queue: Option<usize>,
+    /// 下一个票号
+    next_ticket: std::sync::atomic::AtomicU64,
+    /// 排队中的票号，队首最先准入
+    waiting: parking_lot::Mutex<VecDeque<u64>>,
+    /// 队列变化通知
+    notify: tokio::sync::Notify,
+}
+
+impl AdmissionQueue {
+    fn position(&self, ticket: u64) -> Option<usize> {
+        self.waiting.lock().iter().position(|&t| t == ticket)
+    }
+}
+
+/// FIFO 模式下的排队凭证
+///
+/// 由 [`ConcurrencyLimiter::enqueue`] 返回，调用 [`wait`](Self::wait) 等待准入。
+/// 未准入前丢弃时离开队列，不影响其后的等待者。
+#[must_use]
+pub struct QueuedAcquire<'a> {
+    limiter: &'a ConcurrencyLimiter,
+    cost: u32,
+    /// 排队票号，入队时已直接取得许可则为 `None`
+    ticket: Option<u64>,
+    /// 入队时直接取得的许可
+    permit: Option<tokio::sync::SemaphorePermit<'a>>,
+}
+
+impl<'a> QueuedAcquire<'a> {
+    /// 当前排队位置，0 表示位于队首；已取得许可时返回 `None`
+    pub fn position(&self) -> Option<usize> {
+        let ticket = self.ticket?;
+        self.limiter.queue.as_ref()?.position(ticket)
+    }
+
+    /// 等待轮到自己并取得许可
+    ///
+    /// 被取消时离开队列，不会占用许可。
+    pub async fn wait(mut self) -> Result<ConcurrencyPermit<'a>, FlowGuardError> {
+        let limiter = self.limiter;
+        if let Some(permit) = self.permit.take() {
+            return Ok(ConcurrencyPermit {
+                permit: Some(permit),
+                limiter,
+            });
+        }
+        let (Some(queue), Some(ticket)) = (limiter.queue.as_ref(), self.ticket) else {
+            return Err(FlowGuardError::LimitError("排队凭证无效".to_string()));
+        };
+
+        loop {
+            // 先登记通知再检查，避免检查与等待之间的唤醒丢失
+            let notified = queue.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if queue.waiting.lock().front() == Some(&ticket) {
+                // 队首直接在信号量上等待，许可无论经由哪种许可对象归还都能唤醒
+                let permit = limiter
+                    .semaphore
+                    .acquire_many(self.cost)
+                    .await
+                    .map_err(|_| FlowGuardError::LimitError("信号量已关闭".to_string()))?;
+                queue.waiting.lock().retain(|&t| t != ticket);
+                self.ticket = None;
+                // 队首变化，唤醒下一位
+                queue.notify.notify_waiters();
+                limiter.record_metrics();
+                return Ok(ConcurrencyPermit {
+                    permit: Some(permit),
+                    limiter,
+                });
+            }
+
+            notified.await;
+        }
+    }
+}
+
+impl Drop for QueuedAcquire<'_> {
+    fn drop(&mut self) {
+        let (Some(queue), Some(ticket)) = (self.limiter.queue.as_ref(), self.ticket) else {
+            return;
+        };
+        let mut waiting = queue.waiting.lock();
+        if let Some(index) = waiting.iter().position(|&t| t == ticket) {
+            waiting.remove(index);
+            drop(waiting);
+            queue.notify.notify_waiters();
+            self.limiter.record_metrics();
+        }
+    }
+}
+
+/// 并发许可
+///
+/// 由 [`ConcurrencyLimiter::acquire_permit`] 返回，释放时归还许可并更新监控指标。
+#[must_use]
+pub struct ConcurrencyPermit<'a> {
+    permit: Option<tokio::sync::SemaphorePermit<'a>>,
+    limiter: &'a ConcurrencyLimiter,
+}
+
+impl<'a> ConcurrencyPermit<'a> {
+    /// 持有的许可数量
+    pub fn num_permits(&self) -> u64 {
+        self.permit
+            .as_ref()
+            .map_or(0, |permit| permit.num_permits() as u64)
+    }
+
+    /// 取出底层的信号量许可
+    ///
+    /// 取出后许可归还时不再更新监控指标。
+    pub fn into_inner(mut self) -> tokio::sync::SemaphorePermit<'a> {
+        self.permit.take().expect("并发许可已被取出")
+    }
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        // 先归还许可再更新指标
+        self.permit.take();
+        self.limiter.record_metrics();
+    }
+}
+
+/// 等待计数守卫，获取完成、超时或被取消时减少等待数
+struct WaiterGuard<'a>(&'a ConcurrencyLimiter);
+
+impl Drop for WaiterGuard<'_> {
+    fn drop(&mut self) {
+        self.0
+            .waiters
+            .fetch_sub(1, std::sync::atomic::Ordering::AcqRel);
+        self.0.record_metrics();
+    }
 }
 
 impl ConcurrencyLimiter {
     /// 创建新的并发控制器
     ///
     /// # 参数
-    /// - `max_concurrent`: 最大并发数
+    /// - `max_concurrent`: 最大并发数
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::limiters::ConcurrencyLimiter;
+    ///
+    /// let limiter = ConcurrencyLimiter::new(10);
+    /// ```
+    pub fn new(max_concurrent: u64) -> Self {
+        Self {
+            semaphore: Arc::new(tokio::sync::Semaphore::new(max_concurrent as usize)),
+            max_concurrent,
+            waiters: std::sync::atomic::AtomicU64::new(0),
+            timeout: None,
+            queue: None,
+            #[cfg(feature = "monitoring")]
+            metrics: None,
+        }
+    }
+
+    /// 创建按到达顺序准入的并发控制器
+    ///
+    /// 许可严格按排队顺序分配：队首请求的许可不足时，后到的小请求也不会插队。
+    /// 排队数达到 `max_queue` 时新的请求直接被拒绝。
+    ///
+    /// # 参数
+    /// - `max_concurrent`: 最大并发数
+    /// - `max_queue`: 最大排队长度，`None` 表示不限制
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::limiters::ConcurrencyLimiter;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let limiter = ConcurrencyLimiter::fifo(1, Some(1));
+    ///     let permit = limiter.acquire(1).await.unwrap();
+    ///
+    ///     let queued = limiter.enqueue(1).unwrap();
+    ///     assert_eq!(queued.position(), Some(0));
+    ///     // 队列已满
+    ///     assert!(limiter.enqueue(1).is_err());
+    ///
+    ///     drop(permit);
+    ///     let _permit = queued.wait().await.unwrap();
+    /// }
+    /// ```
+    pub fn fifo(max_concurrent: u64, max_queue: Option<usize>) -> Self {
+        Self {
+            queue: Some(AdmissionQueue {
+                max_queue,
+                next_ticket: std::sync::atomic::AtomicU64::new(0),
+                waiting: parking_lot::Mutex::new(VecDeque::new()),
+                notify: tokio::sync::Notify::new(),
+            }),
+            ..Self::new(max_concurrent)
+        }
+    }
+
+    /// 创建带超时的并发控制器
+    ///
+    /// # 参数
+    /// - `max_concurrent`: 最大并发数
+    /// - `timeout`: 获取许可的超时时间
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::limiters::ConcurrencyLimiter;
+    /// use std::time::Duration;
+    ///
+    /// let limiter = ConcurrencyLimiter::with_timeout(10, Duration::from_secs(5));
+    /// ```
+    pub fn with_timeout(max_concurrent: u64, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..Self::new(max_concurrent)
+        }
+    }
+
+    /// 设置监控指标，按 `name` 记录已持有许可数与等待数
+    ///
+    /// # 参数
+    /// - `name`: 控制器名称（指标标签）
+    /// - `metrics`: 监控指标
+    #[cfg(feature = "monitoring")]
+    pub fn with_metrics(
+        mut self,
+        name: impl Into<String>,
+        metrics: Arc<crate::telemetry::Metrics>,
+    ) -> Self {
+        self.metrics = Some((name.into(), metrics));
+        self.record_metrics();
+        self
+    }
+
+    /// 当前已持有的许可数
+    pub fn in_flight(&self) -> u64 {
+        self.max_concurrent.saturating_sub(self.available())
+    }
+
+    /// 当前可用的许可数
+    pub fn available(&self) -> u64 {
+        self.semaphore.available_permits() as u64
+    }
+
+    /// 当前在 [`acquire`](Self::acquire) 中等待许可的调用方数
+    ///
+    /// 调用方进入 `acquire` 时计入，获得许可、超时或被取消时移出。
+    /// FIFO 模式下为排队中的请求数。
+    pub fn waiters(&self) -> u64 {
+        match &self.queue {
+            Some(queue) => queue.waiting.lock().len() as u64,
+            None => self.waiters.load(std::sync::atomic::Ordering::Acquire),
+        }
+    }
+
+    /// 是否为 FIFO 准入模式
+    pub fn is_fifo(&self) -> bool {
+        self.queue.is_some()
+    }
+
+    /// 在 FIFO 队列中排队
+    ///
+    /// 队列为空且许可充足时直接取得许可，否则分配排队位置，可通过
+    /// [`QueuedAcquire::position`] 查看。
+    ///
+    /// # 返回
+    /// - `Ok(queued)`: 已取得许可或已入队
+    /// - `Err(FlowGuardError::ConcurrencyLimitExceeded)`: 排队数已达上限
+    /// - `Err(FlowGuardError::LimitError)`: 非 FIFO 模式或许可数量无效
+    pub fn enqueue(&self, cost: u64) -> Result<QueuedAcquire<'_>, FlowGuardError> {
+        let cost = Self::cost_u32(cost)?;
+        let Some(queue) = &self.queue else {
+            return Err(FlowGuardError::LimitError(
+                "仅 FIFO 模式支持排队".to_string(),
+            ));
+        };
+
+        let mut waiting = queue.waiting.lock();
+        if waiting.is_empty() {
+            if let Ok(permit) = self.semaphore.try_acquire_many(cost) {
+                drop(waiting);
+                self.record_metrics();
+                return Ok(QueuedAcquire {
+                    limiter: self,
+                    cost,
+                    ticket: None,
+                    permit: Some(permit),
+                });
+            }
+        }
+        if queue.max_queue.is_some_and(|max| waiting.len() >= max) {
+            return Err(FlowGuardError::ConcurrencyLimitExceeded(format!(
+                "等待队列已满: {}",
+                waiting.len()
+            )));
+        }
+
+        let ticket = queue
+            .next_ticket
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        waiting.push_back(ticket);
+        drop(waiting);
+        self.record_metrics();
+        Ok(QueuedAcquire {
+            limiter: self,
+            cost,
+            ticket: Some(ticket),
+            permit: None,
+        })
+    }
+
+    /// 校验许可数量是否在 u32 范围内
+    fn cost_u32(cost: u64) -> Result<u32, FlowGuardError> {
+        u32::try_from(cost)
+            .map_err(|_| FlowGuardError::LimitError("许可数量超出 u32 范围".to_string()))
+    }
+
+    /// 更新已持有许可数与等待数指标
+    fn record_metrics(&self) {
+        #[cfg(feature = "monitoring")]
+        if let Some((name, metrics)) = &self.metrics {
+            metrics.update_concurrency(name, self.in_flight(), self.waiters());
+        }
+    }
+
+    /// 获取许可并执行操作
+    ///
+    /// 创建时指定了超时则最多等待该时长，否则一直等待到有可用许可。
+    /// FIFO 模式下按到达顺序排队，排队数已达上限时直接返回
+    /// `FlowGuardError::ConcurrencyLimitExceeded`。
+    ///
+    /// # 参数
+    /// - `cost`: 需要获取的许可数量
+    ///
+    /// # 返回
+    /// - `Ok(permit)`: 成功获取许可，返回许可对象
+    /// - `Err(_)`: 获取许可失败
+    ///
+    /// 返回的信号量许可归还时不会更新监控指标，需要准确的指标时使用
+    /// [`acquire_permit`](Self::acquire_permit)。
+    pub async fn acquire(
+        &self,
+        cost: u64,
+    ) -> Result<tokio::sync::SemaphorePermit<'_>, FlowGuardError> {
+        self.acquire_permit(cost)
+            .await
+            .map(ConcurrencyPermit::into_inner)
+    }
+
+    /// 获取许可，与 [`acquire`](Self::acquire) 相同，但返回的许可归还时更新监控指标
     ///
     /// # 示例
     /// ```rust
     /// use limiteron::limiters::ConcurrencyLimiter;
     ///
-    /// let limiter = ConcurrencyLimiter::new(10);
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let limiter = ConcurrencyLimiter::new(2);
+    ///     let permit = limiter.acquire_permit(2).await.unwrap();
+    ///     assert_eq!(permit.num_permits(), 2);
+    /// }
     /// ```
-    pub fn new(max_concurrent: u64) -> Self {
-        Self {
-            semaphore: Arc::new(tokio::sync::Semaphore::new(max_concurrent as usize)),
-            timeout: None,
-        }
+    pub async fn acquire_permit(&self, cost: u64) -> Result<ConcurrencyPermit<'_>, FlowGuardError> {
+        self.acquire_with_deadline(cost, self.timeout).await
     }
 
-    /// 创建带超时的并发控制器
+    /// 获取许可，最多等待 `timeout`
+    ///
+    /// 用于在并发已满时快速失败而不是无限排队。超时后放弃等待，不会占用许可：
+    /// 等待期间分配给该调用方的部分许可随等待一起归还，调用方被取消时同样如此。
     ///
     /// # 参数
-    /// - `max_concurrent`: 最大并发数
-    /// - `timeout`: 获取许可的超时时间
+    /// - `cost`: 需要获取的许可数量
+    /// - `timeout`: 最长等待时间，忽略创建时指定的超时
+    ///
+    /// # 返回
+    /// - `Ok(permit)`: 成功获取许可
+    /// - `Err(FlowGuardError::LimitError)`: 等待超时或许可数量无效
     ///
     /// # 示例
     /// ```rust
     /// use limiteron::limiters::ConcurrencyLimiter;
     /// use std::time::Duration;
     ///
-    /// let limiter = ConcurrencyLimiter::with_timeout(10, Duration::from_secs(5));
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let limiter = ConcurrencyLimiter::new(1);
+    ///     let _permit = limiter.acquire(1).await.unwrap();
+    ///
+    ///     let result = limiter
+    ///         .try_acquire_timeout(1, Duration::from_millis(10))
+    ///         .await;
+    ///     assert!(result.is_err());
+    /// }
     /// ```
-    pub fn with_timeout(max_concurrent: u64, timeout: Duration) -> Self {
-        Self {
-            semaphore: Arc::new(tokio::sync::Semaphore::new(max_concurrent as usize)),
-            timeout: Some(timeout),
-        }
+    pub async fn try_acquire_timeout(
+        &self,
+        cost: u64,
+        timeout: Duration,
+    ) -> Result<ConcurrencyPermit<'_>, FlowGuardError> {
+        self.acquire_with_deadline(cost, Some(timeout)).await
     }
 
-    /// 获取许可并执行操作
-    ///
-    /// # 参数
-    /// - `cost`: 需要获取的许可数量
-    ///
-    /// # 返回
-    /// - `Ok(permit)`: 成功获取许可，返回许可对象
-    /// - `Err(_)`: 获取许可失败
-    pub async fn acquire(
+    /// 获取许可，`timeout` 为 `None` 时一直等待
+    async fn acquire_with_deadline(
         &self,
         cost: u64,
-    ) -> Result<tokio::sync::SemaphorePermit<'_>, FlowGuardError> {
-        let cost_u32 = cost as u32;
-        if cost_u32 as u64 != cost {
-            return Err(FlowGuardError::LimitError(
-                "许可数量超出 u32 范围".to_string(),
-            ));
+        timeout: Option<Duration>,
+    ) -> Result<ConcurrencyPermit<'_>, FlowGuardError> {
+        if self.queue.is_some() {
+            let queued = self.enqueue(cost)?;
+            return match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, queued.wait())
+                    .await
+                    .map_err(|_| FlowGuardError::LimitError("获取许可超时".to_string()))?,
+                None => queued.wait().await,
+            };
         }
 
-        let permit = match self.timeout {
+        let cost_u32 = Self::cost_u32(cost)?;
+
+        self.waiters
+            .fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+        self.record_metrics();
+        let waiter = WaiterGuard(self);
+
+        let permit = match timeout {
             Some(timeout) => tokio::time::timeout(timeout, self.semaphore.acquire_many(cost_u32))
                 .await
                 .map_err(|_| FlowGuardError::LimitError("获取许可超时".to_string()))?
@@ -638,15 +1763,14 @@ impl ConcurrencyLimiter {
                 .map_err(|_| FlowGuardError::LimitError("信号量已关闭".to_string()))?,
         };
 
+        let permit = ConcurrencyPermit {
+            permit: Some(permit),
+            limiter: self,
+        };
+        drop(waiter);
         Ok(permit)
     }
 
-    /// 获取当前可用的许可数（仅用于测试）
-    #[cfg(test)]
-    fn available_permits(&self) -> usize {
-        self.semaphore.available_permits()
-    }
-
     /// 尝试获取许可（非阻塞）
     ///
     /// # 参数
@@ -684,6 +1808,15 @@ impl Limiter for ConcurrencyLimiter {
                 ));
             }
 
+            // FIFO 模式下有请求排队时不允许插队
+            if self
+                .queue
+                .as_ref()
+                .is_some_and(|queue| !queue.waiting.lock().is_empty())
+            {
+                return Ok(false);
+            }
+
             match self.semaphore.try_acquire_many(cost_u32) {
                 Ok(_permit) => {
                     // 立即释放许可，因为 allow 方法不应该持有许可
@@ -694,8 +1827,22 @@ impl Limiter for ConcurrencyLimiter {
             }
         })
     }
+
+    fn would_allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let queued = self
+                .queue
+                .as_ref()
+                .is_some_and(|queue| !queue.waiting.lock().is_empty());
+            Ok(!queued && self.available() >= cost)
+        })
+    }
 }
 
+pub use gcra::{GcraDecision, GcraLimiter};
 #[cfg(feature = "quota-control")]
 pub use quota_limiter::QuotaLimiter;
 
@@ -706,6 +1853,7 @@ pub use quota_limiter::QuotaLimiter;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
     use std::time::Duration;
     use tokio::time::sleep;
 
@@ -727,13 +1875,87 @@ mod tests {
 
     #[tokio::test]
     async fn test_token_bucket_refill() {
-        let limiter = TokenBucketLimiter::new(10, 100); // 100 tokens/sec
+        let clock = Arc::new(MockClock::new());
+        let limiter = TokenBucketLimiter::with_clock(10, 100, clock.clone()); // 100 tokens/sec
         limiter.allow(10).await.unwrap();
         assert_eq!(limiter.get_tokens(), 0);
 
-        sleep(Duration::from_millis(20)).await; // 等待 20ms，应该补充约 2 个令牌
-        limiter.allow(1).await.unwrap(); // 触发补充，使用 cost=1
-        assert!(limiter.get_tokens() >= 1);
+        clock.advance(Duration::from_millis(20)); // 推进 20ms，补充 2 个令牌
+        assert!(limiter.allow(1).await.unwrap()); // 触发补充，使用 cost=1
+        assert_eq!(limiter.get_tokens(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_initial_tokens() {
+        let limiter = TokenBucketLimiter::with_initial_tokens(10, 20, 0).unwrap();
+        assert_eq!(limiter.get_tokens(), 0);
+        assert!(!limiter.allow(1).await.unwrap());
+
+        // 空桶按补充速率填充：20 tokens/sec，250ms 后恰好补充 5 个令牌
+        let clock = Arc::new(MockClock::new());
+        let limiter = TokenBucketLimiter::with_clock(10, 20, clock.clone());
+        assert!(limiter.allow(10).await.unwrap());
+        assert_eq!(limiter.get_tokens(), 0);
+        clock.advance(Duration::from_millis(250));
+        let mut allowed = 0;
+        while limiter.allow(1).await.unwrap() {
+            allowed += 1;
+        }
+        assert_eq!(allowed, 5);
+
+        let partial = TokenBucketLimiter::with_initial_tokens(10, 1, 3).unwrap();
+        assert!(partial.allow(3).await.unwrap());
+        assert!(!partial.allow(1).await.unwrap());
+
+        assert!(TokenBucketLimiter::with_initial_tokens(10, 1, 11).is_err());
+        assert_eq!(
+            TokenBucketLimiter::with_initial_tokens(10, 1, 10)
+                .unwrap()
+                .get_tokens(),
+            10
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_accumulates_fractional_tokens() {
+        // 每 5ms 只能补充半个令牌，余量必须累积而不是被截断
+        let limiter = TokenBucketLimiter::with_initial_tokens(100, 100, 0).unwrap();
+        let start = Instant::now();
+        let mut granted = 0u64;
+        while start.elapsed() < Duration::from_secs(1) {
+            if limiter.allow(1).await.unwrap() {
+                granted += 1;
+            }
+            sleep(Duration::from_millis(5)).await;
+        }
+
+        assert!(
+            (80..=110).contains(&granted),
+            "expected roughly 100 tokens, got {}",
+            granted
+        );
+    }
+
+    #[test]
+    fn test_token_bucket_refill_math_caps_long_idle() {
+        let limiter = TokenBucketLimiter::with_initial_tokens(10, u64::MAX, 0).unwrap();
+        assert_eq!(limiter.tokens_for(u64::MAX), 10);
+
+        let clock = Arc::new(MockClock::new());
+        let limiter = TokenBucketLimiter::with_clock(100, 100, clock.clone());
+        assert_eq!(limiter.tokens_for(5_000_000), 0);
+        assert_eq!(limiter.tokens_for(15_000_000), 1);
+        assert_eq!(limiter.nanos_for(1), 10_000_000);
+
+        // 模拟长时间空闲：补充到容量上限并丢弃余量
+        assert!(limiter.try_consume(100).is_ok());
+        clock.advance(Duration::from_secs(3600) + Duration::from_millis(5));
+        limiter.refill_tokens();
+        assert_eq!(limiter.get_tokens(), 100);
+        let last = limiter
+            .last_refill
+            .load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(last, limiter.now_nanos());
     }
 
     #[tokio::test]
@@ -781,6 +2003,99 @@ mod tests {
         assert!(allowed_count <= 10);
     }
 
+    #[tokio::test]
+    async fn test_token_bucket_allow_detailed() {
+        let limiter = TokenBucketLimiter::new(10, 5);
+
+        let decision = limiter.allow_detailed(4).await.unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 6);
+        assert_eq!(decision.limit, 10);
+        assert!(decision.retry_after.is_none());
+
+        let decision = limiter.allow_detailed(8).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.remaining, 6);
+        // 缺 2 个令牌，补充速率 5/s，约需 400ms
+        let retry_after = decision.retry_after.unwrap();
+        assert!(retry_after <= Duration::from_millis(400));
+        assert!(retry_after >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_peek() {
+        let limiter = TokenBucketLimiter::new(10, 5);
+        assert_eq!(
+            limiter.peek(),
+            LimiterSnapshot::TokenBucket {
+                available: 10,
+                capacity: 10,
+                next_refill: None,
+            }
+        );
+
+        assert!(limiter.allow(4).await.unwrap());
+        match limiter.peek() {
+            LimiterSnapshot::TokenBucket {
+                available,
+                capacity,
+                next_refill,
+            } => {
+                assert_eq!(available, 6);
+                assert_eq!(capacity, 10);
+                // 补充速率 5/s，下一个令牌约 200ms 后到达
+                assert!(next_refill.unwrap() <= Duration::from_millis(200));
+            }
+            other => panic!("unexpected snapshot: {:?}", other),
+        }
+
+        // peek 不消费令牌
+        limiter.peek();
+        assert_eq!(limiter.get_tokens(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_would_allow_does_not_consume() {
+        let clock = Arc::new(MockClock::new());
+        let limiter = TokenBucketLimiter::with_clock(10, 5, clock.clone());
+
+        for _ in 0..100 {
+            assert!(limiter.would_allow(10).await.unwrap());
+        }
+        assert_eq!(limiter.get_tokens(), 10);
+        assert!(!limiter.would_allow(11).await.unwrap());
+
+        assert!(limiter.allow(10).await.unwrap());
+        assert!(!limiter.would_allow(1).await.unwrap());
+
+        // 计入补充但不写回
+        clock.advance(Duration::from_millis(400));
+        assert!(limiter.would_allow(2).await.unwrap());
+        assert!(!limiter.would_allow(3).await.unwrap());
+        assert_eq!(limiter.get_tokens(), 0);
+        assert!(limiter.allow(2).await.unwrap());
+        assert!(limiter.would_allow(0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_reset() {
+        let clock = Arc::new(MockClock::new());
+        let limiter = TokenBucketLimiter::with_clock(10, 1, clock.clone());
+
+        assert!(limiter.allow(10).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+
+        limiter.reset().await.unwrap();
+        assert_eq!(limiter.get_tokens(), 10);
+        assert!(limiter.allow(10).await.unwrap());
+
+        // 重置不会额外累积重置前经过的时间
+        clock.advance(Duration::from_secs(5));
+        limiter.reset().await.unwrap();
+        assert!(limiter.allow(10).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+    }
+
     // ==================== SlidingWindowLimiter 测试 ====================
 
     #[tokio::test]
@@ -801,18 +2116,25 @@ mod tests {
 
     #[tokio::test]
     async fn test_sliding_window_sliding() {
-        let limiter = SlidingWindowLimiter::new(Duration::from_millis(100), 5);
+        let clock = Arc::new(MockClock::new());
+        let limiter = SlidingWindowLimiter::with_clock(
+            Duration::from_millis(100),
+            5,
+            SlidingWindowMode::Log,
+            clock.clone(),
+        );
 
         // 发送 5 个请求
         for _ in 0..5 {
             assert!(limiter.allow(1).await.unwrap());
         }
 
-        // 应该被拒绝
+        // 窗口边界上仍被拒绝
+        clock.advance(Duration::from_millis(100));
         assert!(!limiter.allow(1).await.unwrap());
 
-        // 等待窗口滑动
-        sleep(Duration::from_millis(101)).await;
+        // 窗口滑过后放行
+        clock.advance(Duration::from_millis(1));
 
         // 现在应该可以发送新请求
         assert!(limiter.allow(1).await.unwrap());
@@ -849,8 +2171,200 @@ mod tests {
         assert!(!limiter.allow(1).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_sliding_window_counter_mode() {
+        let limiter =
+            SlidingWindowLimiter::with_mode(Duration::from_secs(1), 10, SlidingWindowMode::Counter);
+        assert_eq!(limiter.mode(), SlidingWindowMode::Counter);
+        assert!(limiter.allow(5).await.unwrap());
+        assert!(limiter.allow(5).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+
+        let decision = limiter.allow_detailed(1).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        // 需要进入下一个子窗口，并等待上一个窗口的权重衰减出 1 个请求的余量
+        let retry_after = decision.retry_after.unwrap();
+        assert!(retry_after > Duration::from_secs(1));
+        assert!(retry_after <= Duration::from_millis(1100));
+
+        assert_eq!(
+            SlidingWindowLimiter::new(Duration::from_secs(1), 10).mode(),
+            SlidingWindowMode::Log
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_mode_accuracy_at_boundary() {
+        let clock = Arc::new(MockClock::new());
+        let window = Duration::from_millis(200);
+        let log =
+            SlidingWindowLimiter::with_clock(window, 10, SlidingWindowMode::Log, clock.clone());
+        let counter =
+            SlidingWindowLimiter::with_clock(window, 10, SlidingWindowMode::Counter, clock.clone());
+
+        // 在第一个子窗口的后半段打满限额
+        clock.advance(Duration::from_millis(150));
+        for _ in 0..10 {
+            assert!(log.allow(1).await.unwrap());
+            assert!(counter.allow(1).await.unwrap());
+        }
+
+        // 跨过子窗口边界 50ms：请求仍在真实的滑动窗口内
+        clock.advance(Duration::from_millis(100));
+        let mut log_allowed = 0;
+        let mut counter_allowed = 0;
+        for _ in 0..10 {
+            if log.allow(1).await.unwrap() {
+                log_allowed += 1;
+            }
+            if counter.allow(1).await.unwrap() {
+                counter_allowed += 1;
+            }
+        }
+
+        // 日志模式精确拒绝；计数器模式按上一个子窗口的剩余权重（75%）近似，会多放行一部分
+        assert_eq!(log_allowed, 0);
+        assert_eq!(counter_allowed, 3);
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_counter_resets_after_idle() {
+        let limiter = SlidingWindowLimiter::with_mode(
+            Duration::from_millis(50),
+            3,
+            SlidingWindowMode::Counter,
+        );
+        assert!(limiter.allow(3).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+
+        // 超过两个子窗口后上一个窗口的计数也过期
+        sleep(Duration::from_millis(110)).await;
+        assert!(limiter.allow(3).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_allow_detailed() {
+        let limiter = SlidingWindowLimiter::new(Duration::from_secs(1), 3);
+
+        let decision = limiter.allow_detailed(2).await.unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 1);
+        assert_eq!(decision.limit, 3);
+
+        let decision = limiter.allow_detailed(2).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.remaining, 1);
+        let retry_after = decision.retry_after.unwrap();
+        assert!(retry_after > Duration::ZERO);
+        assert!(retry_after <= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_would_allow_does_not_consume() {
+        for mode in [SlidingWindowMode::Counter, SlidingWindowMode::Log] {
+            let limiter = SlidingWindowLimiter::with_mode(Duration::from_secs(1), 3, mode);
+
+            for _ in 0..10 {
+                assert!(limiter.would_allow(3).await.unwrap());
+            }
+            assert!(limiter.allow(2).await.unwrap());
+            assert!(limiter.would_allow(1).await.unwrap());
+            assert!(!limiter.would_allow(2).await.unwrap());
+            assert!(limiter.allow(1).await.unwrap());
+            assert!(!limiter.would_allow(1).await.unwrap());
+        }
+    }
+
     // ==================== FixedWindowLimiter 测试 ====================
 
+    #[tokio::test]
+    async fn test_sliding_window_peek() {
+        for mode in [SlidingWindowMode::Log, SlidingWindowMode::Counter] {
+            let limiter = SlidingWindowLimiter::with_mode(Duration::from_secs(1), 10, mode);
+            assert_eq!(
+                limiter.peek(),
+                LimiterSnapshot::Window {
+                    used: 0,
+                    limit: 10,
+                    window_reset: None,
+                }
+            );
+
+            assert!(limiter.allow(3).await.unwrap());
+            match limiter.peek() {
+                LimiterSnapshot::Window {
+                    used,
+                    limit,
+                    window_reset,
+                } => {
+                    assert_eq!(used, 3, "mode {:?}", mode);
+                    assert_eq!(limit, 10);
+                    assert!(window_reset.unwrap() <= Duration::from_secs(1));
+                }
+                other => panic!("unexpected snapshot: {:?}", other),
+            }
+
+            // peek 不记录请求
+            limiter.peek();
+            let decision = limiter.allow_detailed(1).await.unwrap();
+            assert_eq!(decision.remaining, 6, "mode {:?}", mode);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixed_window_peek() {
+        let clock = Arc::new(MockClock::new());
+        let limiter = FixedWindowLimiter::with_clock(Duration::from_millis(100), 5, clock.clone());
+        assert!(limiter.allow(2).await.unwrap());
+        clock.advance(Duration::from_millis(40));
+
+        match limiter.peek() {
+            LimiterSnapshot::Window {
+                used, window_reset, ..
+            } => {
+                assert_eq!(used, 2);
+                assert_eq!(window_reset, Some(Duration::from_millis(60)));
+            }
+            other => panic!("unexpected snapshot: {:?}", other),
+        }
+
+        // 窗口过期后即使尚未重置也视为空窗口
+        clock.advance(Duration::from_millis(80));
+        assert_eq!(
+            limiter.peek(),
+            LimiterSnapshot::Window {
+                used: 0,
+                limit: 5,
+                window_reset: None,
+            }
+        );
+        assert_eq!(limiter.count.load(std::sync::atomic::Ordering::Acquire), 2);
+    }
+
+    #[tokio::test]
+    async fn test_window_limiters_reset() {
+        for mode in [SlidingWindowMode::Log, SlidingWindowMode::Counter] {
+            let limiter = SlidingWindowLimiter::with_mode(Duration::from_secs(60), 3, mode);
+            assert!(limiter.allow(3).await.unwrap());
+            assert!(!limiter.allow(1).await.unwrap());
+
+            limiter.reset().await.unwrap();
+            assert!(limiter.allow(3).await.unwrap(), "mode {:?}", mode);
+        }
+
+        let limiter = FixedWindowLimiter::new(Duration::from_secs(60), 3);
+        assert!(limiter.allow(3).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+
+        limiter.reset().await.unwrap();
+        assert_eq!(limiter.get_count(), 0);
+        assert!(limiter.allow(3).await.unwrap());
+
+        // 不支持重置的限流器返回错误
+        assert!(ConcurrencyLimiter::new(1).reset().await.is_err());
+    }
+
     #[tokio::test]
     async fn test_fixed_window_basic() {
         let limiter = FixedWindowLimiter::new(Duration::from_secs(1), 10);
@@ -869,7 +2383,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_fixed_window_reset() {
-        let limiter = FixedWindowLimiter::new(Duration::from_millis(100), 5);
+        let clock = Arc::new(MockClock::new());
+        let limiter = FixedWindowLimiter::with_clock(Duration::from_millis(100), 5, clock.clone());
 
         // 发送 5 个请求
         for _ in 0..5 {
@@ -877,15 +2392,61 @@ mod tests {
         }
 
         // 应该被拒绝
+        clock.advance(Duration::from_millis(99));
         assert!(!limiter.allow(1).await.unwrap());
 
-        // 等待窗口重置
-        sleep(Duration::from_millis(101)).await;
+        // 窗口重置
+        clock.advance(Duration::from_millis(1));
 
         // 新窗口应该重置
         assert!(limiter.allow(1).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_window_limiters_survive_clock_backward() {
+        let clock = Arc::new(MockClock::new());
+        let bucket = TokenBucketLimiter::with_clock(10, 10, clock.clone());
+        let fixed = FixedWindowLimiter::with_clock(Duration::from_secs(1), 5, clock.clone());
+        let log = SlidingWindowLimiter::with_clock(
+            Duration::from_secs(1),
+            5,
+            SlidingWindowMode::Log,
+            clock.clone(),
+        );
+        let counter = SlidingWindowLimiter::with_clock(
+            Duration::from_secs(1),
+            5,
+            SlidingWindowMode::Counter,
+            clock.clone(),
+        );
+        clock.advance(Duration::from_secs(10));
+
+        assert!(bucket.allow(10).await.unwrap());
+        assert!(fixed.allow(5).await.unwrap());
+        assert!(log.allow(5).await.unwrap());
+        assert!(counter.allow(5).await.unwrap());
+
+        // 回拨后不补充令牌、不重置窗口，已用配额仍然有效
+        clock.rewind(Duration::from_secs(5));
+        assert!(!bucket.allow(1).await.unwrap());
+        assert!(!fixed.allow(1).await.unwrap());
+        assert!(!log.allow(1).await.unwrap());
+        assert!(!counter.allow(1).await.unwrap());
+
+        // 时间再次前进后按回拨后的时间恢复，而不是等到原来的时间点
+        clock.advance(Duration::from_millis(100));
+        assert!(bucket.allow(1).await.unwrap());
+        assert!(!bucket.allow(1).await.unwrap());
+
+        clock.advance(Duration::from_secs(2));
+        assert!(fixed.allow(5).await.unwrap());
+        assert!(log.allow(5).await.unwrap());
+        assert!(counter.allow(5).await.unwrap());
+        assert!(!fixed.allow(1).await.unwrap());
+        assert!(!log.allow(1).await.unwrap());
+        assert!(!counter.allow(1).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_fixed_window_concurrent() {
         let limiter = Arc::new(FixedWindowLimiter::new(Duration::from_secs(1), 10));
@@ -917,6 +2478,32 @@ mod tests {
         assert!(!limiter.allow(1).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_fixed_window_allow_detailed() {
+        let limiter = FixedWindowLimiter::new(Duration::from_secs(1), 5);
+
+        let decision = limiter.allow_detailed(5).await.unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.limit, 5);
+
+        let decision = limiter.allow_detailed(1).await.unwrap();
+        assert!(!decision.allowed);
+        let retry_after = decision.retry_after.unwrap();
+        assert!(retry_after <= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_default_allow_detailed() {
+        // ConcurrencyLimiter 使用默认实现
+        let limiter = ConcurrencyLimiter::new(1);
+        let decision = limiter.allow_detailed(1).await.unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.limit, 0);
+        assert!(decision.retry_after.is_none());
+    }
+
     // ==================== ConcurrencyLimiter 测试 ====================
 
     #[tokio::test]
@@ -925,7 +2512,7 @@ mod tests {
         // allow 方法只检查是否有足够的许可，但不持有
         assert!(limiter.allow(1).await.unwrap());
         // 因为 allow 不持有许可，所以许可数仍然是 10
-        assert_eq!(limiter.available_permits(), 10);
+        assert_eq!(limiter.available(), 10);
     }
 
     #[tokio::test]
@@ -951,21 +2538,152 @@ mod tests {
 
         // 获取许可
         let permit1 = limiter.acquire(1).await.unwrap();
-        assert_eq!(limiter.available_permits(), 1);
+        assert_eq!(limiter.available(), 1);
 
         let _permit2 = limiter.acquire(1).await.unwrap();
-        assert_eq!(limiter.available_permits(), 0);
+        assert_eq!(limiter.available(), 0);
 
         // 应该无法获取更多许可（使用 try_acquire 测试）
         assert!(limiter.try_acquire(1).is_err());
 
         // 释放许可
         drop(permit1);
-        assert_eq!(limiter.available_permits(), 1);
+        assert_eq!(limiter.available(), 1);
 
         // 现在应该可以获取许可
         let _permit3 = limiter.acquire(1).await.unwrap();
-        assert_eq!(limiter.available_permits(), 0);
+        assert_eq!(limiter.available(), 0);
+    }
+
+    /// 等待并发控制器达到预期的持有数与等待数
+    async fn wait_for_counts(limiter: &ConcurrencyLimiter, in_flight: u64, waiters: u64) {
+        for _ in 0..200 {
+            if limiter.in_flight() == in_flight && limiter.waiters() == waiters {
+                return;
+            }
+            sleep(Duration::from_millis(5)).await;
+        }
+        panic!(
+            "expected in_flight={} waiters={}, got in_flight={} waiters={}",
+            in_flight,
+            waiters,
+            limiter.in_flight(),
+            limiter.waiters()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_in_flight_and_waiters() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(2));
+        let release = Arc::new(tokio::sync::Notify::new());
+        let mut handles = vec![];
+
+        for _ in 0..5 {
+            let limiter = Arc::clone(&limiter);
+            let release = Arc::clone(&release);
+            handles.push(tokio::spawn(async move {
+                let _permit = limiter.acquire(1).await.unwrap();
+                release.notified().await;
+            }));
+        }
+
+        wait_for_counts(&limiter, 2, 3).await;
+        assert_eq!(limiter.available(), 0);
+
+        // 每释放一个许可，就有一个等待者获得许可
+        for waiters in (0..3).rev() {
+            release.notify_one();
+            wait_for_counts(&limiter, 2, waiters).await;
+        }
+        release.notify_one();
+        wait_for_counts(&limiter, 1, 0).await;
+        release.notify_one();
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(limiter.in_flight(), 0);
+        assert_eq!(limiter.available(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_waiters_after_timeout() {
+        let limiter = ConcurrencyLimiter::with_timeout(1, Duration::from_millis(20));
+        let permit = limiter.acquire(1).await.unwrap();
+        assert_eq!(permit.num_permits(), 1);
+
+        assert!(limiter.acquire(1).await.is_err());
+        assert_eq!(limiter.waiters(), 0);
+        assert_eq!(limiter.in_flight(), 1);
+
+        drop(permit);
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_try_acquire_timeout() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(1));
+        let permit = limiter.acquire(1).await.unwrap();
+
+        // 许可在超时前释放，等待者获得许可
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                limiter
+                    .try_acquire_timeout(1, Duration::from_secs(5))
+                    .await
+                    .map(|permit| permit.num_permits())
+            })
+        };
+        wait_for_counts(&limiter, 1, 1).await;
+        drop(permit);
+        assert_eq!(waiter.await.unwrap().unwrap(), 1);
+        assert_eq!(limiter.in_flight(), 0);
+
+        // 许可一直被持有，等待者超时且不占用许可
+        let _held = limiter.acquire(1).await.unwrap();
+        let started = std::time::Instant::now();
+        assert!(limiter
+            .try_acquire_timeout(1, Duration::from_millis(30))
+            .await
+            .is_err());
+        assert!(started.elapsed() >= Duration::from_millis(30));
+        assert_eq!(limiter.waiters(), 0);
+        assert_eq!(limiter.in_flight(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_timed_out_waiter_does_not_leak() {
+        let limiter = ConcurrencyLimiter::new(3);
+        let first = limiter.acquire(2).await.unwrap();
+
+        // 等待 2 个许可时会先分到剩余的 1 个，超时后必须归还
+        assert!(limiter
+            .try_acquire_timeout(2, Duration::from_millis(20))
+            .await
+            .is_err());
+        assert_eq!(limiter.available(), 1);
+
+        drop(first);
+        assert_eq!(limiter.available(), 3);
+        assert_eq!(limiter.acquire(3).await.unwrap().num_permits(), 3);
+    }
+
+    #[cfg(feature = "monitoring")]
+    #[tokio::test]
+    async fn test_concurrency_limiter_metrics() {
+        let metrics = Arc::new(crate::telemetry::Metrics::new());
+        let limiter = ConcurrencyLimiter::new(3).with_metrics("db", metrics.clone());
+
+        let permit = limiter.acquire_permit(2).await.unwrap();
+        let text = metrics.gather();
+        assert!(text.contains("limiteron_concurrency_in_flight{limiter=\"db\"} 2"));
+        assert!(text.contains("limiteron_concurrency_waiters{limiter=\"db\"} 0"));
+
+        drop(permit);
+        assert!(metrics
+            .gather()
+            .contains("limiteron_concurrency_in_flight{limiter=\"db\"} 0"));
     }
 
     #[tokio::test]
@@ -1018,6 +2736,125 @@ mod tests {
         assert!(allowed_count <= 5);
     }
 
+    #[tokio::test]
+    async fn test_concurrency_limiter_fifo_admits_in_arrival_order() {
+        let limiter = Arc::new(ConcurrencyLimiter::fifo(1, None));
+        let holder = limiter.acquire(1).await.unwrap();
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let mut handles = vec![];
+
+        for i in 0..5 {
+            let task_limiter = Arc::clone(&limiter);
+            let order = Arc::clone(&order);
+            handles.push(tokio::spawn(async move {
+                let _permit = task_limiter.acquire(1).await.unwrap();
+                order.lock().push(i);
+                sleep(Duration::from_millis(5)).await;
+            }));
+            // 确保按顺序入队
+            wait_for_counts(&limiter, 1, i + 1).await;
+        }
+
+        drop(holder);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(*order.lock(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(limiter.in_flight(), 0);
+        assert_eq!(limiter.waiters(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_fifo_no_barging() {
+        let limiter = ConcurrencyLimiter::fifo(2, None);
+        let holder = limiter.acquire(1).await.unwrap();
+
+        // 队首需要 2 个许可，后到的 1 个许可请求不能插队
+        let large = limiter.enqueue(2).unwrap();
+        let small = limiter.enqueue(1).unwrap();
+        assert_eq!(large.position(), Some(0));
+        assert_eq!(small.position(), Some(1));
+        assert!(!limiter.allow(1).await.unwrap());
+        assert!(!limiter.would_allow(1).await.unwrap());
+
+        drop(holder);
+        let large = large.wait().await.unwrap();
+        assert_eq!(large.num_permits(), 2);
+        assert_eq!(small.position(), Some(0));
+        drop(large);
+        let _small = small.wait().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_fifo_wakes_on_raw_permit_release() {
+        let limiter = Arc::new(ConcurrencyLimiter::fifo(1, None));
+        let holder = limiter.acquire(1).await.unwrap();
+
+        let task_limiter = limiter.clone();
+        let waiter = tokio::spawn(async move {
+            let permit = task_limiter.acquire_permit(1).await.unwrap();
+            permit.num_permits()
+        });
+        while limiter.waiters() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // 归还 acquire 返回的信号量许可同样唤醒队首
+        drop(holder);
+        let permits = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("队首未被唤醒")
+            .unwrap();
+        assert_eq!(permits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_fifo_max_queue() {
+        let limiter = ConcurrencyLimiter::fifo(1, Some(2));
+        let holder = limiter.acquire(1).await.unwrap();
+
+        let first = limiter.enqueue(1).unwrap();
+        let second = limiter.enqueue(1).unwrap();
+        assert_eq!(limiter.waiters(), 2);
+        assert!(matches!(
+            limiter.enqueue(1),
+            Err(FlowGuardError::ConcurrencyLimitExceeded(_))
+        ));
+        assert!(matches!(
+            limiter.acquire(1).await,
+            Err(FlowGuardError::ConcurrencyLimitExceeded(_))
+        ));
+
+        // 离开队列后位置前移，并腾出排队名额
+        drop(first);
+        assert_eq!(second.position(), Some(0));
+        let third = limiter.enqueue(1).unwrap();
+        assert_eq!(third.position(), Some(1));
+
+        drop(holder);
+        let permit = second.wait().await.unwrap();
+        assert_eq!(third.position(), Some(0));
+        drop(permit);
+        let _permit = third.wait().await.unwrap();
+        assert_eq!(limiter.waiters(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limiter_fifo_timeout_leaves_queue() {
+        let limiter = ConcurrencyLimiter::fifo(1, Some(1));
+        let holder = limiter.acquire(1).await.unwrap();
+
+        let result = limiter
+            .try_acquire_timeout(1, Duration::from_millis(20))
+            .await;
+        assert!(matches!(result, Err(FlowGuardError::LimitError(_))));
+        assert_eq!(limiter.waiters(), 0);
+
+        drop(holder);
+        let _permit = limiter.acquire(1).await.unwrap();
+        assert!(limiter.enqueue(1).unwrap().position().is_some());
+    }
+
     #[tokio::test]
     async fn test_concurrency_limiter_allow_does_not_hold() {
         let limiter = Arc::new(ConcurrencyLimiter::new(2));
@@ -1029,12 +2866,29 @@ mod tests {
 
         // 获取许可会真正持有
         let _permit1 = limiter.acquire(1).await.unwrap();
-        assert_eq!(limiter.available_permits(), 1);
+        assert_eq!(limiter.available(), 1);
 
         let _permit2 = limiter.acquire(1).await.unwrap();
-        assert_eq!(limiter.available_permits(), 0);
+        assert_eq!(limiter.available(), 0);
 
         // 无法获取更多许可
         assert!(limiter.try_acquire(1).is_err());
     }
+
+    #[tokio::test]
+    async fn test_fixed_window_and_concurrency_would_allow() {
+        let limiter = FixedWindowLimiter::new(Duration::from_secs(60), 2);
+        for _ in 0..10 {
+            assert!(limiter.would_allow(2).await.unwrap());
+        }
+        assert!(limiter.allow(2).await.unwrap());
+        assert!(!limiter.would_allow(1).await.unwrap());
+
+        let limiter = ConcurrencyLimiter::new(2);
+        assert!(limiter.would_allow(2).await.unwrap());
+        let _permit = limiter.acquire(1).await.unwrap();
+        assert!(limiter.would_allow(1).await.unwrap());
+        assert!(!limiter.would_allow(2).await.unwrap());
+        assert_eq!(limiter.available(), 1);
+    }
 }
@@ -0,0 +1,449 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! GCRA 限流器
+//!
+//! 基于通用信元速率算法（Generic Cell Rate Algorithm）实现的限流器，
+//! 等价于以计量方式实现的漏桶，只需存储一个"理论到达时间"（TAT）。
+
+use super::{validate_cost, Limiter, LimiterSnapshot, Observable, RateLimitDecision};
+use crate::clock::{clock_went_backward, Clock, RealClock};
+use crate::error::FlowGuardError;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// GCRA 单次检查的详细结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GcraDecision {
+    /// 是否允许
+    pub allowed: bool,
+    /// 当前突发容量内剩余可用的请求数
+    pub remaining: u64,
+    /// 被拒绝时，距离请求可以通过还需等待的时间
+    ///
+    /// 当请求的 cost 超过突发容量（永远无法通过）时为 `None`。
+    pub retry_after: Option<Duration>,
+}
+
+/// GCRA 限流器
+///
+/// 每个请求单元占用一个发射间隔 `period`，限流器记录理论到达时间（TAT），
+/// 只要 `TAT - now` 不超过 `period * burst` 的容差，请求即可通过。
+/// 相比令牌桶，GCRA 让请求间隔更平滑，并且能直接给出 `retry_after`。
+///
+/// # 特性
+/// - 仅使用一个 AtomicU64 保存 TAT
+/// - 使用 CAS 循环保证并发安全
+/// - 支持突发（burst）吸收
+///
+/// # 示例
+/// ```rust
+/// use limiteron::limiters::{GcraLimiter, Limiter};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     // 每 100ms 放行 1 个请求，允许 10 个请求的突发
+///     let limiter = GcraLimiter::new(Duration::from_millis(100), 10);
+///
+///     let allowed = limiter.allow(1).await.unwrap();
+///     assert!(allowed);
+///
+///     let info = limiter.allow_with_info(10).await.unwrap();
+///     assert!(!info.allowed);
+///     assert!(info.retry_after.is_some());
+/// }
+/// ```
+pub struct GcraLimiter {
+    /// 发射间隔（每个请求单元的时间成本，纳秒）
+    emission_interval: u64,
+    /// 突发容量
+    burst: u64,
+    /// 延迟容差（纳秒），等于 emission_interval * burst
+    tolerance: u64,
+    /// 时间来源
+    clock: Arc<dyn Clock>,
+    /// 时间基准点
+    epoch: Instant,
+    /// 理论到达时间（相对 epoch 的纳秒数）
+    tat: AtomicU64,
+    /// 见过的最大当前时间（相对 epoch 的纳秒数），用于检测时钟回拨
+    last_now: AtomicU64,
+}
+
+impl GcraLimiter {
+    /// Creates a new GCRA limiter.
+    ///
+    /// # Arguments
+    /// * `period` - Emission interval, i.e. time cost of one request unit
+    /// * `burst` - Maximum number of request units that may arrive back-to-back
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::limiters::GcraLimiter;
+    /// use std::time::Duration;
+    ///
+    /// // 10 requests per second with a burst of 5
+    /// let limiter = GcraLimiter::new(Duration::from_millis(100), 5);
+    /// ```
+    pub fn new(period: Duration, burst: u64) -> Self {
+        Self::with_clock(period, burst, RealClock::shared())
+    }
+
+    /// Creates a GCRA limiter that reads time from `clock`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limiteron::clock::MockClock;
+    /// use limiteron::limiters::GcraLimiter;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let clock = Arc::new(MockClock::new());
+    /// let limiter = GcraLimiter::with_clock(Duration::from_millis(100), 5, clock.clone());
+    /// ```
+    pub fn with_clock(period: Duration, burst: u64, clock: Arc<dyn Clock>) -> Self {
+        let emission_interval = (period.as_nanos() as u64).max(1);
+        let burst = burst.max(1);
+        Self {
+            emission_interval,
+            burst,
+            tolerance: emission_interval.saturating_mul(burst),
+            epoch: clock.now(),
+            clock,
+            tat: AtomicU64::new(0),
+            last_now: AtomicU64::new(0),
+        }
+    }
+
+    /// 当前时间（相对 epoch 的纳秒数）
+    fn now_nanos(&self) -> u64 {
+        self.clock
+            .now()
+            .saturating_duration_since(self.epoch)
+            .as_nanos() as u64
+    }
+
+    /// 当前时间，并处理时钟回拨
+    ///
+    /// TAT 可能领先当前时间，无法据此判断回拨，因此记录见过的最大时间。
+    /// 回拨幅度较大时将 TAT 一并前移，相当于这段时间没有流逝。
+    fn observe_now(&self) -> u64 {
+        let now = self.now_nanos();
+        let latest = self.last_now.fetch_max(now, Ordering::AcqRel);
+        if latest > now && clock_went_backward("gcra", Duration::from_nanos(latest - now)) {
+            let shift = latest - now;
+            let _ = self
+                .tat
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |tat| {
+                    Some(tat.saturating_sub(shift))
+                });
+            self.last_now.store(now, Ordering::Release);
+        }
+        now
+    }
+
+    /// 计算给定 TAT 下剩余的突发容量
+    fn remaining_at(&self, tat: u64, now: u64) -> u64 {
+        let used = tat.saturating_sub(now);
+        self.tolerance.saturating_sub(used) / self.emission_interval
+    }
+
+    /// 检查并消费，返回包含 `retry_after` 的详细结果
+    ///
+    /// # 参数
+    /// - `cost`: 需要消费的请求单元数
+    ///
+    /// # 返回
+    /// - `Ok(GcraDecision)`: 检查结果
+    /// - `Err(_)`: cost 参数无效
+    pub async fn allow_with_info(&self, cost: u64) -> Result<GcraDecision, FlowGuardError> {
+        let cost = validate_cost(cost)?;
+        Ok(self.try_acquire(cost))
+    }
+
+    /// GCRA 核心逻辑
+    fn try_acquire(&self, cost: u64) -> GcraDecision {
+        let now = self.observe_now();
+
+        // 超过突发容量的请求永远无法通过
+        if cost > self.burst {
+            return GcraDecision {
+                allowed: false,
+                remaining: self.remaining_at(self.tat.load(Ordering::Acquire), now),
+                retry_after: None,
+            };
+        }
+
+        let increment = self.emission_interval.saturating_mul(cost);
+
+        loop {
+            let stored_tat = self.tat.load(Ordering::Acquire);
+            let tat = stored_tat.max(now);
+            let new_tat = tat.saturating_add(increment);
+            let allow_at = new_tat.saturating_sub(self.tolerance);
+
+            if now < allow_at {
+                return GcraDecision {
+                    allowed: false,
+                    remaining: self.remaining_at(stored_tat, now),
+                    retry_after: Some(Duration::from_nanos(allow_at - now)),
+                };
+            }
+
+            if self
+                .tat
+                .compare_exchange(stored_tat, new_tat, Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return GcraDecision {
+                    allowed: true,
+                    remaining: self.remaining_at(new_tat, now),
+                    retry_after: None,
+                };
+            }
+        }
+    }
+}
+
+impl Limiter for GcraLimiter {
+    fn allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move { Ok(self.allow_with_info(cost).await?.allowed) })
+    }
+
+    fn allow_detailed(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<RateLimitDecision, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let info = self.allow_with_info(cost).await?;
+            Ok(RateLimitDecision {
+                allowed: info.allowed,
+                remaining: info.remaining,
+                limit: self.burst,
+                retry_after: info.retry_after,
+            })
+        })
+    }
+
+    fn would_allow(
+        &self,
+        cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            let cost = validate_cost(cost)?;
+            if cost > self.burst {
+                return Ok(false);
+            }
+            // 与 try_acquire 相同的判断，但不更新 TAT
+            let now = self.observe_now();
+            let tat = self.tat.load(Ordering::Acquire).max(now);
+            let new_tat = tat.saturating_add(self.emission_interval.saturating_mul(cost));
+            Ok(now >= new_tat.saturating_sub(self.tolerance))
+        })
+    }
+
+    fn snapshot(&self) -> Option<LimiterSnapshot> {
+        Some(self.peek())
+    }
+
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            // TAT 不晚于当前时间即为满突发容量
+            self.tat.store(0, Ordering::Release);
+            Ok(())
+        })
+    }
+}
+
+impl Observable for GcraLimiter {
+    fn peek(&self) -> LimiterSnapshot {
+        let now = self.now_nanos();
+        let tat = self.tat.load(Ordering::Acquire);
+        let available = self.remaining_at(tat, now);
+
+        // 下一个请求单元在占用量降到 tolerance - (available + 1) * interval 时释放
+        let next_refill = (available < self.burst).then(|| {
+            let used = tat.saturating_sub(now);
+            let threshold = self
+                .tolerance
+                .saturating_sub((available + 1).saturating_mul(self.emission_interval));
+            Duration::from_nanos(used.saturating_sub(threshold))
+        });
+
+        LimiterSnapshot::TokenBucket {
+            available,
+            capacity: self.burst,
+            next_refill,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[tokio::test]
+    async fn test_gcra_reset_restores_burst() {
+        let limiter = GcraLimiter::new(Duration::from_secs(1), 3);
+        for _ in 0..3 {
+            assert!(limiter.allow(1).await.unwrap());
+        }
+        assert!(!limiter.allow(1).await.unwrap());
+
+        limiter.reset().await.unwrap();
+        for _ in 0..3 {
+            assert!(limiter.allow(1).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gcra_burst_absorption() {
+        let limiter = GcraLimiter::new(Duration::from_secs(1), 5);
+
+        // 突发容量内的请求全部放行
+        for _ in 0..5 {
+            assert!(limiter.allow(1).await.unwrap());
+        }
+
+        // 超出突发容量后拒绝，并给出 retry_after
+        let info = limiter.allow_with_info(1).await.unwrap();
+        assert!(!info.allowed);
+        assert_eq!(info.remaining, 0);
+        let retry_after = info.retry_after.unwrap();
+        assert!(retry_after > Duration::from_millis(900));
+        assert!(retry_after <= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_gcra_steady_state_spacing() {
+        let clock = Arc::new(MockClock::new());
+        let limiter = GcraLimiter::with_clock(Duration::from_millis(50), 1, clock.clone());
+
+        assert!(limiter.allow(1).await.unwrap());
+        // 间隔不足一个周期，拒绝
+        clock.advance(Duration::from_millis(49));
+        assert!(!limiter.allow(1).await.unwrap());
+
+        // 满一个周期后放行
+        clock.advance(Duration::from_millis(1));
+        assert!(limiter.allow(1).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gcra_clock_backward() {
+        let clock = Arc::new(MockClock::new());
+        let limiter = GcraLimiter::with_clock(Duration::from_millis(100), 2, clock.clone());
+        clock.advance(Duration::from_secs(10));
+
+        assert!(limiter.allow(2).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+
+        // 回拨后已用的突发容量仍然有效，但不会被锁定到原来的时间点
+        clock.rewind(Duration::from_secs(5));
+        assert!(!limiter.allow(1).await.unwrap());
+        assert!(!limiter.would_allow(1).await.unwrap());
+        clock.advance(Duration::from_millis(100));
+        assert!(limiter.allow(1).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gcra_cost_exceeds_burst() {
+        let limiter = GcraLimiter::new(Duration::from_millis(10), 3);
+
+        let info = limiter.allow_with_info(4).await.unwrap();
+        assert!(!info.allowed);
+        assert!(info.retry_after.is_none());
+
+        // 未消费任何容量
+        assert!(limiter.allow(3).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gcra_peek() {
+        let limiter = GcraLimiter::new(Duration::from_secs(1), 3);
+        assert_eq!(
+            limiter.peek(),
+            LimiterSnapshot::TokenBucket {
+                available: 3,
+                capacity: 3,
+                next_refill: None,
+            }
+        );
+
+        assert!(limiter.allow(2).await.unwrap());
+        match limiter.peek() {
+            LimiterSnapshot::TokenBucket {
+                available,
+                next_refill,
+                ..
+            } => {
+                assert_eq!(available, 1);
+                let next_refill = next_refill.unwrap();
+                assert!(next_refill > Duration::from_millis(900));
+                assert!(next_refill <= Duration::from_secs(1));
+            }
+            other => panic!("unexpected snapshot: {:?}", other),
+        }
+
+        // peek 不消费容量
+        assert!(limiter.allow(1).await.unwrap());
+        assert!(!limiter.allow(1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gcra_invalid_cost() {
+        let limiter = GcraLimiter::new(Duration::from_millis(10), 3);
+        assert!(limiter.allow(0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gcra_concurrent() {
+        let limiter = std::sync::Arc::new(GcraLimiter::new(Duration::from_secs(1), 10));
+        let mut handles = vec![];
+
+        for _ in 0..50 {
+            let limiter_clone = limiter.clone();
+            handles.push(tokio::spawn(async move {
+                limiter_clone.allow(1).await.unwrap()
+            }));
+        }
+
+        let mut allowed_count = 0;
+        for handle in handles {
+            if handle.await.unwrap() {
+                allowed_count += 1;
+            }
+        }
+
+        assert_eq!(allowed_count, 10);
+    }
+
+    #[tokio::test]
+    async fn test_gcra_would_allow_does_not_consume() {
+        let clock = Arc::new(MockClock::new());
+        let limiter = GcraLimiter::with_clock(Duration::from_millis(100), 3, clock.clone());
+
+        for _ in 0..10 {
+            assert!(limiter.would_allow(3).await.unwrap());
+        }
+        assert!(!limiter.would_allow(4).await.unwrap());
+
+        assert!(limiter.allow(3).await.unwrap());
+        assert!(!limiter.would_allow(1).await.unwrap());
+        clock.advance(Duration::from_millis(100));
+        assert!(limiter.would_allow(1).await.unwrap());
+        assert!(!limiter.would_allow(2).await.unwrap());
+        assert!(limiter.allow(1).await.unwrap());
+    }
+}
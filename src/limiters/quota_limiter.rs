@@ -55,6 +55,8 @@ impl QuotaLimiter {
     ///     allow_overdraft: false,
     ///     overdraft_limit_percent: 20,
     ///     alert_config: Default::default(),
+    ///     rollover: None,
+    ///     cost_weights: Default::default(),
     /// };
     /// let limiter = QuotaLimiter::new(config);
     /// ```
@@ -126,6 +128,22 @@ impl crate::limiters::Limiter for QuotaLimiter {
         })
     }
 
+    fn would_allow(
+        &self,
+        _cost: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, FlowGuardError>> + Send + '_>> {
+        // Same as allow(): without a key there is no usage to check
+        Box::pin(async move { Ok(true) })
+    }
+
+    /// Clears the usage of every key.
+    fn reset(&self) -> Pin<Box<dyn Future<Output = Result<(), FlowGuardError>> + Send + '_>> {
+        Box::pin(async move {
+            self.usage.clear();
+            Ok(())
+        })
+    }
+
     fn check(
         &self,
         key: &str,
@@ -152,6 +170,8 @@ mod tests {
             allow_overdraft: false,
             overdraft_limit_percent: 0,
             alert_config: Default::default(),
+            rollover: None,
+            cost_weights: Default::default(),
         }
     }
 
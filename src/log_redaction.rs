@@ -6,6 +6,9 @@
 //!
 //! 提供日志脱敏功能，保护敏感信息不被泄露到日志中。
 //! 即使没有启用 log-redaction feature，基础脱敏函数也可用。
+//!
+//! 哪些字段名视为敏感、以何种方式脱敏由全局 [`RedactionPolicy`] 决定，
+//! 可通过 [`set_redaction_policy`] 替换。
 
 /// 基础脱敏函数 - 即使没有启用 log-redaction feature 也可用
 #[inline]
@@ -90,6 +93,151 @@ pub fn redact_email(value: Option<&str>) -> String {
     redact_basic(Some(value))
 }
 
+/// 64 位 FNV-1a 哈希，跨进程稳定
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// 脱敏方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedactionMode {
+    /// 完全替换为 `***`
+    #[default]
+    Full,
+    /// 保留首尾各两个字符，如 `ab***yz`（短值完全替换）
+    Partial,
+    /// 替换为值的哈希，如 `hash:1a2b3c4d5e6f7a8b`，便于关联同一值而不暴露原文
+    ///
+    /// 哈希不具备密码学强度，低熵的值可被穷举还原。
+    Hash,
+}
+
+/// 脱敏策略
+///
+/// 字段名（请求头、查询参数等）包含任一 `sensitive_keys`（不区分大小写）时视为敏感，
+/// 其值按 `mode` 脱敏。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::log_redaction::{RedactionMode, RedactionPolicy};
+///
+/// let policy = RedactionPolicy::default()
+///     .with_sensitive_key("x-tenant")
+///     .with_mode(RedactionMode::Partial);
+///
+/// assert_eq!(policy.redact("X-Tenant-Id", "tenant-42"), "te***42");
+/// assert_eq!(policy.redact("accept", "text/html"), "text/html");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    /// 敏感字段名片段（小写匹配）
+    pub sensitive_keys: Vec<String>,
+    /// 脱敏方式
+    pub mode: RedactionMode,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            sensitive_keys: [
+                "auth",
+                "cookie",
+                "key",
+                "token",
+                "secret",
+                "password",
+                "credential",
+            ]
+            .iter()
+            .map(|key| key.to_string())
+            .collect(),
+            mode: RedactionMode::Full,
+        }
+    }
+}
+
+impl RedactionPolicy {
+    /// 创建脱敏策略
+    pub fn new(sensitive_keys: Vec<String>, mode: RedactionMode) -> Self {
+        Self {
+            sensitive_keys,
+            mode,
+        }
+    }
+
+    /// 追加敏感字段名片段
+    pub fn with_sensitive_key(mut self, key: impl Into<String>) -> Self {
+        self.sensitive_keys.push(key.into());
+        self
+    }
+
+    /// 设置脱敏方式
+    pub fn with_mode(mut self, mode: RedactionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 字段名是否敏感
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.sensitive_keys
+            .iter()
+            .any(|key| name.contains(&key.to_lowercase()))
+    }
+
+    /// 按脱敏方式处理值
+    pub fn mask(&self, value: &str) -> String {
+        match self.mode {
+            RedactionMode::Full => "***".to_string(),
+            RedactionMode::Partial => {
+                let chars: Vec<char> = value.chars().collect();
+                if chars.len() <= 4 {
+                    return "***".to_string();
+                }
+                let prefix: String = chars[..2].iter().collect();
+                let suffix: String = chars[chars.len() - 2..].iter().collect();
+                format!("{}***{}", prefix, suffix)
+            }
+            RedactionMode::Hash => format!("hash:{:016x}", fnv1a(value.as_bytes())),
+        }
+    }
+
+    /// 字段名敏感时脱敏，否则原样返回
+    pub fn redact(&self, name: &str, value: &str) -> String {
+        if self.is_sensitive(name) {
+            self.mask(value)
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+/// 全局脱敏策略，未设置时使用默认策略
+static REDACTION_POLICY: std::sync::RwLock<Option<std::sync::Arc<RedactionPolicy>>> =
+    std::sync::RwLock::new(None);
+
+/// 设置全局脱敏策略
+pub fn set_redaction_policy(policy: RedactionPolicy) {
+    let mut current = REDACTION_POLICY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = Some(std::sync::Arc::new(policy));
+}
+
+/// 获取当前全局脱敏策略
+pub fn redaction_policy() -> std::sync::Arc<RedactionPolicy> {
+    REDACTION_POLICY
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_else(|| std::sync::Arc::new(RedactionPolicy::default()))
+}
+
 #[cfg(feature = "log-redaction")]
 use regex::Regex;
 #[cfg(feature = "log-redaction")]
@@ -154,17 +302,11 @@ pub fn redact_enhanced(value: Option<&str>, field_name: Option<&str>) -> String
         return "unknown".to_string();
     }
 
-    // 检查是否是已知的敏感字段
+    // 检查是否是脱敏策略中的敏感字段
     if let Some(name) = field_name {
-        let lower_name = name.to_lowercase();
-        if lower_name.contains("password")
-            || lower_name.contains("secret")
-            || lower_name.contains("token")
-            || lower_name.contains("key")
-            || lower_name.contains("credential")
-            || lower_name.contains("authorization")
-        {
-            return "***".to_string();
+        let policy = redaction_policy();
+        if policy.is_sensitive(name) {
+            return policy.mask(value);
         }
     }
 
@@ -304,6 +446,34 @@ mod tests {
         assert_eq!(redact_ip(Some("::1")), ":***:***");
     }
 
+    #[test]
+    fn test_redaction_policy_modes() {
+        let full = RedactionPolicy::default();
+        assert_eq!(full.redact("Authorization", "Bearer abc"), "***");
+        assert_eq!(full.redact("accept", "text/html"), "text/html");
+
+        let partial = RedactionPolicy::default().with_mode(RedactionMode::Partial);
+        assert_eq!(partial.redact("api_key", "abcdefyz"), "ab***yz");
+        assert_eq!(partial.redact("api_key", "abcd"), "***");
+
+        let hash = RedactionPolicy::default().with_mode(RedactionMode::Hash);
+        let masked = hash.redact("session_token", "secret-value");
+        assert!(masked.starts_with("hash:"));
+        assert_eq!(masked.len(), "hash:".len() + 16);
+        assert!(!masked.contains("secret-value"));
+        // 相同的值得到相同的哈希
+        assert_eq!(masked, hash.mask("secret-value"));
+        assert_ne!(masked, hash.mask("other-value"));
+    }
+
+    #[test]
+    fn test_redaction_policy_custom_key() {
+        let policy = RedactionPolicy::new(vec!["X-Tenant".to_string()], RedactionMode::Full);
+        assert!(policy.is_sensitive("x-tenant-id"));
+        assert!(!policy.is_sensitive("authorization"));
+        assert_eq!(policy.redact("X-TENANT-ID", "acme"), "***");
+    }
+
     #[test]
     fn test_redact_email() {
         assert_eq!(redact_email(None), "unknown");
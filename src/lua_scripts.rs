@@ -28,8 +28,28 @@ pub enum LuaScriptType {
     QuotaConsume,
     /// 配额重置
     QuotaReset,
+    /// 组合配额扣减（共享总额度 + 资源子额度）
+    CompositeQuotaConsume,
+    /// 配额预留
+    QuotaReserve,
+    /// 配额预留提交或回滚
+    QuotaReservationFinish,
     /// 令牌桶
     TokenBucket,
+    /// 分布式并发许可获取（兼作租约续期）
+    ConcurrencyAcquire,
+    /// 分布式并发许可释放
+    ConcurrencyRelease,
+    /// 滑动窗口只读检查
+    SlidingWindowPeek,
+    /// 固定窗口只读检查
+    FixedWindowPeek,
+    /// 令牌桶只读检查
+    TokenBucketPeek,
+    /// 分布式并发只读检查
+    ConcurrencyPeek,
+    /// 多个限流器的组合检查（全部通过才扣减）
+    CombinedCheck,
 }
 
 impl LuaScriptType {
@@ -40,18 +60,38 @@ impl LuaScriptType {
             LuaScriptType::FixedWindow => "fixed_window",
             LuaScriptType::QuotaConsume => "quota_consume",
             LuaScriptType::QuotaReset => "quota_reset",
+            LuaScriptType::CompositeQuotaConsume => "composite_quota_consume",
+            LuaScriptType::QuotaReserve => "quota_reserve",
+            LuaScriptType::QuotaReservationFinish => "quota_reservation_finish",
             LuaScriptType::TokenBucket => "token_bucket",
+            LuaScriptType::ConcurrencyAcquire => "concurrency_acquire",
+            LuaScriptType::ConcurrencyRelease => "concurrency_release",
+            LuaScriptType::SlidingWindowPeek => "sliding_window_peek",
+            LuaScriptType::FixedWindowPeek => "fixed_window_peek",
+            LuaScriptType::TokenBucketPeek => "token_bucket_peek",
+            LuaScriptType::ConcurrencyPeek => "concurrency_peek",
+            LuaScriptType::CombinedCheck => "combined_check",
         }
     }
 
     /// 获取脚本版本
-    pub fn version(&self) -> &str {
+    pub fn version(&self) -> &'static str {
         match self {
             LuaScriptType::SlidingWindow => "1.0",
             LuaScriptType::FixedWindow => "1.0",
-            LuaScriptType::QuotaConsume => "1.0",
+            LuaScriptType::QuotaConsume => "1.2",
             LuaScriptType::QuotaReset => "1.0",
+            LuaScriptType::CompositeQuotaConsume => "1.0",
+            LuaScriptType::QuotaReserve => "1.0",
+            LuaScriptType::QuotaReservationFinish => "1.1",
             LuaScriptType::TokenBucket => "1.0",
+            LuaScriptType::ConcurrencyAcquire => "1.0",
+            LuaScriptType::ConcurrencyRelease => "1.0",
+            LuaScriptType::SlidingWindowPeek => "1.0",
+            LuaScriptType::FixedWindowPeek => "1.0",
+            LuaScriptType::TokenBucketPeek => "1.0",
+            LuaScriptType::ConcurrencyPeek => "1.0",
+            LuaScriptType::CombinedCheck => "1.0",
         }
     }
 }
@@ -130,8 +170,9 @@ return {allowed and 1 or 0, current_count, reset_time}
 
 /// 配额扣减Lua脚本
 ///
-/// 使用Redis Hash存储配额信息，支持透支
-/// 参数: KEYS[1] - key, ARGV[1] - cost, ARGV[2] - limit, ARGV[3] - overdraft_limit, ARGV[4] - window_start, ARGV[5] - window_end, ARGV[6] - consumed_field, ARGV[7] - limit_field, ARGV[8] - window_start_field, ARGV[9] - window_end_field
+/// 使用Redis Hash存储配额信息，支持透支；窗口在存储的结束时间之前保持不变。
+/// 传入 `reservations_field` 时先回滚已超时的配额预留（见 [`QUOTA_RESERVE_SCRIPT`]）
+/// 参数: KEYS[1] - key, ARGV[1] - cost, ARGV[2] - limit, ARGV[3] - overdraft_limit, ARGV[4] - window_start（当前时间）, ARGV[5] - window_end, ARGV[6] - consumed_field, ARGV[7] - limit_field, ARGV[8] - window_start_field, ARGV[9] - window_end_field, ARGV[10] - reservations_field（可选）
 /// 返回: (allowed: bool, remaining: int, consumed: int)
 pub const QUOTA_CONSUME_SCRIPT: &str = r#"
 -- 获取参数
@@ -146,14 +187,10 @@ local limit_field = ARGV[7]
 local window_start_field = ARGV[8]
 local window_end_field = ARGV[9]
 
--- 检查窗口是否过期
-local stored_window_start = tonumber(redis.call('HGET', key, window_start_field))
-if stored_window_start and stored_window_start ~= window_start then
-    -- 窗口已过期，重置配额
-    redis.call('HMSET', key, consumed_field, 0, window_start_field, window_start, window_end_field, window_end, limit_field, limit)
-    redis.call('EXPIRE', key, math.ceil((window_end - window_start) / 1000) + 10)
-elseif not stored_window_start then
-    -- 首次消费，初始化配额信息
+-- 检查窗口是否过期（window_start 即当前时间）
+local stored_window_end = tonumber(redis.call('HGET', key, window_end_field))
+if not stored_window_end or stored_window_end <= window_start then
+    -- 首次消费或窗口已过期，开启新窗口（limit 为新窗口的上限，可包含结转配额）
     redis.call('HMSET', key, consumed_field, 0, window_start_field, window_start, window_end_field, window_end, limit_field, limit)
     redis.call('EXPIRE', key, math.ceil((window_end - window_start) / 1000) + 10)
 else
@@ -164,6 +201,30 @@ end
 -- 获取当前已消费量
 local consumed = tonumber(redis.call('HGET', key, consumed_field)) or 0
 
+-- 回滚已超时的预留
+local reservations_field = ARGV[10]
+if reservations_field then
+    local raw = redis.call('HGET', key, reservations_field)
+    if raw then
+        local current_window_end = redis.call('HGET', key, window_end_field)
+        local reservations = cjson.decode(raw)
+        for id, reservation in pairs(reservations) do
+            if tonumber(reservation[2]) <= window_start then
+                if reservation[3] == current_window_end then
+                    consumed = math.max(consumed - tonumber(reservation[1]), 0)
+                end
+                reservations[id] = nil
+            end
+        end
+        redis.call('HSET', key, consumed_field, consumed)
+        if next(reservations) == nil then
+            redis.call('HDEL', key, reservations_field)
+        else
+            redis.call('HSET', key, reservations_field, cjson.encode(reservations))
+        end
+    end
+end
+
 -- 计算剩余配额（包括透支）
 local total_limit = limit + overdraft_limit
 local remaining = total_limit - consumed
@@ -204,6 +265,172 @@ redis.call('EXPIRE', key, math.ceil((window_end - window_start) / 1000) + 10)
 return 1
 "#;
 
+/// 配额预留Lua脚本
+///
+/// 与配额扣减脚本共用同一个Hash，预留记录以JSON形式保存在 `reservations_field` 中
+/// （预留ID -> [cost, expires_at, window_end]），保证所有数据位于同一个键，可在集群中原子执行。
+/// 预留前先回滚已超时的预留；预留所在窗口已结束的不再退还。
+/// 参数: KEYS[1] - key, ARGV[1] - cost, ARGV[2] - limit, ARGV[3] - now (ms), ARGV[4] - window_end (ms), ARGV[5] - consumed_field, ARGV[6] - limit_field, ARGV[7] - window_start_field, ARGV[8] - window_end_field, ARGV[9] - reservations_field, ARGV[10] - reservation_id, ARGV[11] - expires_at (ms)
+/// 返回: (allowed: bool, remaining: int, consumed: int)
+pub const QUOTA_RESERVE_SCRIPT: &str = r#"
+-- 获取参数
+local key = KEYS[1]
+local cost = tonumber(ARGV[1])
+local limit = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local window_end = tonumber(ARGV[4])
+local consumed_field = ARGV[5]
+local limit_field = ARGV[6]
+local window_start_field = ARGV[7]
+local window_end_field = ARGV[8]
+local reservations_field = ARGV[9]
+local reservation_id = ARGV[10]
+local expires_at = ARGV[11]
+
+-- 首次消费或窗口已过期，开启新窗口
+local stored_window_end = redis.call('HGET', key, window_end_field)
+if not stored_window_end or tonumber(stored_window_end) <= now then
+    stored_window_end = ARGV[4]
+    redis.call('HMSET', key, consumed_field, 0, window_start_field, now, window_end_field, window_end, limit_field, limit)
+    redis.call('EXPIRE', key, math.ceil((window_end - now) / 1000) + 10)
+end
+
+local consumed = tonumber(redis.call('HGET', key, consumed_field)) or 0
+
+-- 回滚已超时的预留
+local raw = redis.call('HGET', key, reservations_field)
+local reservations = raw and cjson.decode(raw) or {}
+for id, reservation in pairs(reservations) do
+    if tonumber(reservation[2]) <= now then
+        if reservation[3] == stored_window_end then
+            consumed = math.max(consumed - tonumber(reservation[1]), 0)
+        end
+        reservations[id] = nil
+    end
+end
+
+local allowed = consumed + cost <= limit
+if allowed then
+    consumed = consumed + cost
+    reservations[reservation_id] = {ARGV[1], expires_at, stored_window_end}
+    -- 保证键至少存活到预留超时之后
+    local ttl = redis.call('PTTL', key)
+    local hold = tonumber(expires_at) - now + 10000
+    if ttl < hold then
+        redis.call('PEXPIRE', key, hold)
+    end
+end
+
+redis.call('HSET', key, consumed_field, consumed)
+if next(reservations) == nil then
+    redis.call('HDEL', key, reservations_field)
+else
+    redis.call('HSET', key, reservations_field, cjson.encode(reservations))
+end
+
+return {allowed and 1 or 0, math.max(limit - consumed, 0), consumed}
+"#;
+
+/// 配额预留提交/回滚Lua脚本
+///
+/// 移除预留记录；回滚或预留已超时时退还配额（预留所在窗口已结束的不退还），
+/// 同时回滚同一配额下其他已超时的预留
+/// 参数: KEYS[1] - key, ARGV[1] - reservation_id, ARGV[2] - now (ms), ARGV[3] - commit (1 提交 / 0 回滚), ARGV[4] - consumed_field, ARGV[5] - window_end_field, ARGV[6] - reservations_field
+/// 返回: 1 预留有效并已处理，0 预留不存在或已超时
+pub const QUOTA_RESERVATION_FINISH_SCRIPT: &str = r#"
+-- 获取参数
+local key = KEYS[1]
+local reservation_id = ARGV[1]
+local now = tonumber(ARGV[2])
+local commit = ARGV[3] == '1'
+local consumed_field = ARGV[4]
+local window_end_field = ARGV[5]
+local reservations_field = ARGV[6]
+
+local raw = redis.call('HGET', key, reservations_field)
+if not raw then
+    return 0
+end
+local reservations = cjson.decode(raw)
+local reservation = reservations[reservation_id]
+if not reservation then
+    return 0
+end
+
+local current_window_end = redis.call('HGET', key, window_end_field)
+local consumed = tonumber(redis.call('HGET', key, consumed_field)) or 0
+
+reservations[reservation_id] = nil
+local expired = tonumber(reservation[2]) <= now
+if (expired or not commit) and reservation[3] == current_window_end then
+    consumed = math.max(consumed - tonumber(reservation[1]), 0)
+end
+
+-- 顺带回滚其他已超时的预留
+for id, other in pairs(reservations) do
+    if tonumber(other[2]) <= now then
+        if other[3] == current_window_end then
+            consumed = math.max(consumed - tonumber(other[1]), 0)
+        end
+        reservations[id] = nil
+    end
+end
+
+if current_window_end then
+    redis.call('HSET', key, consumed_field, consumed)
+end
+if next(reservations) == nil then
+    redis.call('HDEL', key, reservations_field)
+else
+    redis.call('HSET', key, reservations_field, cjson.encode(reservations))
+end
+
+return expired and 0 or 1
+"#;
+
+/// 组合配额扣减Lua脚本
+///
+/// 使用Redis Hash在同一键下存储共享总用量（`total`）、各资源用量（`r:<resource>`）与窗口结束时间，
+/// 在一次调用内同时检查总额度与资源子额度，任一不足则两者都不扣减
+/// 参数: KEYS[1] - key, ARGV[1] - cost, ARGV[2] - total_limit, ARGV[3] - resource_limit, ARGV[4] - now (ms), ARGV[5] - window_end (ms), ARGV[6] - resource_field
+/// 返回: (allowed: bool, total_remaining: int, resource_remaining: int, rejected_by: 0 无 / 1 总额度 / 2 子额度)
+pub const COMPOSITE_QUOTA_CONSUME_SCRIPT: &str = r#"
+-- 获取参数
+local key = KEYS[1]
+local cost = tonumber(ARGV[1])
+local total_limit = tonumber(ARGV[2])
+local resource_limit = tonumber(ARGV[3])
+local now = tonumber(ARGV[4])
+local window_end = tonumber(ARGV[5])
+local resource_field = ARGV[6]
+
+-- 首次消费或窗口已过期，开启新窗口并清空所有资源用量
+local stored_window_end = tonumber(redis.call('HGET', key, 'window_end'))
+if not stored_window_end or stored_window_end <= now then
+    redis.call('DEL', key)
+    redis.call('HMSET', key, 'window_end', window_end, 'total', 0)
+    redis.call('PEXPIRE', key, window_end - now + 10000)
+end
+
+local total = tonumber(redis.call('HGET', key, 'total')) or 0
+local used = tonumber(redis.call('HGET', key, resource_field)) or 0
+
+-- 先检查共享总额度，再检查资源子额度
+local rejected_by = 0
+if total + cost > total_limit then
+    rejected_by = 1
+elseif used + cost > resource_limit then
+    rejected_by = 2
+end
+
+if rejected_by == 0 then
+    total = redis.call('HINCRBY', key, 'total', cost)
+    used = redis.call('HINCRBY', key, resource_field, cost)
+end
+
+return {rejected_by == 0 and 1 or 0, math.max(total_limit - total, 0), math.max(resource_limit - used, 0), rejected_by}
+"#;
+
 /// 令牌桶Lua脚本
 ///
 /// 使用Redis Hash实现令牌桶算法
@@ -249,6 +476,244 @@ local refill_time = current_timestamp + math.ceil(1 / refill_rate)
 return {allowed and 1 or 0, tokens_remaining, refill_time}
 "#;
 
+/// 并发许可获取Lua脚本
+///
+/// 使用Redis Sorted Set记录持有者及其租约到期时间，Hash记录每个持有者占用的许可数。
+/// 租约过期的持有者（如进程崩溃）会被自动清理；已持有许可的持有者再次调用时仅续期租约。
+/// 参数: KEYS[1] - holders_key, KEYS[2] - permits_key, ARGV[1] - max_concurrent, ARGV[2] - permits, ARGV[3] - current_timestamp (ms), ARGV[4] - lease (ms), ARGV[5] - holder_id
+/// 返回: (acquired: bool, in_flight: int)
+pub const CONCURRENCY_ACQUIRE_SCRIPT: &str = r#"
+-- 获取参数
+local holders_key = KEYS[1]
+local permits_key = KEYS[2]
+local max_concurrent = tonumber(ARGV[1])
+local permits = tonumber(ARGV[2])
+local current_timestamp = tonumber(ARGV[3])
+local lease = tonumber(ARGV[4])
+local holder_id = ARGV[5]
+
+-- 清理租约已过期的持有者
+local expired = redis.call('ZRANGEBYSCORE', holders_key, '-inf', current_timestamp)
+for _, holder in ipairs(expired) do
+    redis.call('HDEL', permits_key, holder)
+end
+redis.call('ZREMRANGEBYSCORE', holders_key, '-inf', current_timestamp)
+
+-- 统计当前在途许可数
+local in_flight = 0
+for _, count in ipairs(redis.call('HVALS', permits_key)) do
+    in_flight = in_flight + tonumber(count)
+end
+
+local expire_ms = lease * 2
+
+-- 已持有许可：仅续期租约
+if redis.call('ZSCORE', holders_key, holder_id) then
+    redis.call('ZADD', holders_key, current_timestamp + lease, holder_id)
+    redis.call('PEXPIRE', holders_key, expire_ms)
+    redis.call('PEXPIRE', permits_key, expire_ms)
+    return {1, in_flight}
+end
+
+-- 判断是否有足够的许可
+if in_flight + permits > max_concurrent then
+    return {0, in_flight}
+end
+
+-- 登记持有者
+redis.call('ZADD', holders_key, current_timestamp + lease, holder_id)
+redis.call('HSET', permits_key, holder_id, permits)
+redis.call('PEXPIRE', holders_key, expire_ms)
+redis.call('PEXPIRE', permits_key, expire_ms)
+
+return {1, in_flight + permits}
+"#;
+
+/// 并发许可释放Lua脚本
+///
+/// 参数: KEYS[1] - holders_key, KEYS[2] - permits_key, ARGV[1] - holder_id
+/// 返回: 释放的许可数（持有者不存在时为0）
+pub const CONCURRENCY_RELEASE_SCRIPT: &str = r#"
+-- 获取参数
+local holders_key = KEYS[1]
+local permits_key = KEYS[2]
+local holder_id = ARGV[1]
+
+local released = tonumber(redis.call('HGET', permits_key, holder_id)) or 0
+redis.call('ZREM', holders_key, holder_id)
+redis.call('HDEL', permits_key, holder_id)
+
+return released
+"#;
+
+/// 滑动窗口只读检查Lua脚本
+///
+/// 与滑动窗口脚本的判断一致，但不清理过期元素、不记录请求
+/// 参数: KEYS[1] - key, ARGV[1] - window_size (ms), ARGV[2] - max_requests, ARGV[3] - current_timestamp, ARGV[4] - cost
+/// 返回: (allowed: bool, current_count: int)
+pub const SLIDING_WINDOW_PEEK_SCRIPT: &str = r#"
+local key = KEYS[1]
+local window_size = tonumber(ARGV[1])
+local max_requests = tonumber(ARGV[2])
+local current_timestamp = tonumber(ARGV[3])
+local cost = tonumber(ARGV[4])
+
+-- 只统计窗口内的元素（分数大于窗口起点）
+local current_count = redis.call('ZCOUNT', key, '(' .. (current_timestamp - window_size), '+inf')
+
+return {current_count + cost <= max_requests and 1 or 0, current_count}
+"#;
+
+/// 固定窗口只读检查Lua脚本
+///
+/// 参数: KEYS[1] - key, ARGV[1] - window_size (ms), ARGV[2] - max_requests, ARGV[3] - current_timestamp, ARGV[4] - cost
+/// 返回: (allowed: bool, current_count: int)
+pub const FIXED_WINDOW_PEEK_SCRIPT: &str = r#"
+local key = KEYS[1]
+local window_size = tonumber(ARGV[1])
+local max_requests = tonumber(ARGV[2])
+local current_timestamp = tonumber(ARGV[3])
+local cost = tonumber(ARGV[4])
+
+local current_window = math.floor(current_timestamp / window_size) * window_size
+local current_count = tonumber(redis.call('GET', key .. ':' .. current_window)) or 0
+
+return {current_count + cost <= max_requests and 1 or 0, current_count}
+"#;
+
+/// 令牌桶只读检查Lua脚本
+///
+/// 按经过的时间计算补充后的令牌数，但不扣除、不写回
+/// 参数: KEYS[1] - key, ARGV[1] - capacity, ARGV[2] - refill_rate (tokens/ms), ARGV[3] - current_timestamp, ARGV[4] - tokens_requested
+/// 返回: (allowed: bool, tokens_available: int)
+pub const TOKEN_BUCKET_PEEK_SCRIPT: &str = r#"
+local key = KEYS[1]
+local capacity = tonumber(ARGV[1])
+local refill_rate = tonumber(ARGV[2])
+local current_timestamp = tonumber(ARGV[3])
+local tokens_requested = tonumber(ARGV[4])
+
+local tokens = tonumber(redis.call('HGET', key, 'tokens')) or capacity
+local last_refill = tonumber(redis.call('HGET', key, 'last_refill')) or current_timestamp
+
+local elapsed = current_timestamp - last_refill
+if elapsed > 0 then
+    tokens = math.min(capacity, tokens + elapsed * refill_rate)
+end
+
+return {tokens >= tokens_requested and 1 or 0, math.floor(tokens)}
+"#;
+
+/// 分布式并发只读检查Lua脚本
+///
+/// 只统计租约未过期的持有者，不清理过期持有者
+/// 参数: KEYS[1] - holders_key, KEYS[2] - permits_key, ARGV[1] - max_concurrent, ARGV[2] - permits, ARGV[3] - current_timestamp (ms)
+/// 返回: (allowed: bool, in_flight: int)
+pub const CONCURRENCY_PEEK_SCRIPT: &str = r#"
+local holders_key = KEYS[1]
+local permits_key = KEYS[2]
+local max_concurrent = tonumber(ARGV[1])
+local permits = tonumber(ARGV[2])
+local current_timestamp = tonumber(ARGV[3])
+
+local in_flight = 0
+for _, holder in ipairs(redis.call('ZRANGEBYSCORE', holders_key, '(' .. current_timestamp, '+inf')) do
+    in_flight = in_flight + (tonumber(redis.call('HGET', permits_key, holder)) or 0)
+end
+
+return {in_flight + permits <= max_concurrent and 1 or 0, in_flight}
+"#;
+
+/// 组合检查Lua脚本
+///
+/// 先按只读方式判断每个限流器，全部通过后才依次扣减；任一限流器拒绝时不修改任何键，
+/// 避免前面的限流器已消费额度而请求最终被拒绝。
+/// 参数: KEYS[i] - 第 i 个限流器的键, ARGV[1] - current_timestamp (ms), ARGV[2] - 请求唯一标识,
+/// 之后每个限流器 4 个参数：类型（sliding_window / fixed_window / token_bucket）、
+/// 参数 a、参数 b、cost。滑动窗口与固定窗口中 a 为 window_size (ms)、b 为 max_requests；
+/// 令牌桶中 a 为 capacity、b 为 refill_rate (tokens/ms)
+/// 返回: (allowed: bool, rejected_index: int（从 1 开始，0 表示全部通过）, remaining...)
+pub const COMBINED_CHECK_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local request_id = ARGV[2]
+local states = {}
+local rejected = 0
+
+-- 第一阶段：只读判断
+for i = 1, #KEYS do
+    local key = KEYS[i]
+    local base = 2 + (i - 1) * 4
+    local state = {
+        kind = ARGV[base + 1],
+        a = tonumber(ARGV[base + 2]),
+        b = tonumber(ARGV[base + 3]),
+        cost = tonumber(ARGV[base + 4]),
+    }
+    if state.kind == 'sliding_window' then
+        state.count = redis.call('ZCOUNT', key, '(' .. (now - state.a), '+inf')
+        state.allowed = state.count + state.cost <= state.b
+    elseif state.kind == 'fixed_window' then
+        local current_window = math.floor(now / state.a) * state.a
+        state.window_key = key .. ':' .. current_window
+        state.count = tonumber(redis.call('GET', state.window_key)) or 0
+        state.allowed = state.count + state.cost <= state.b
+    elseif state.kind == 'token_bucket' then
+        local tokens = tonumber(redis.call('HGET', key, 'tokens')) or state.a
+        local last_refill = tonumber(redis.call('HGET', key, 'last_refill')) or now
+        local elapsed = now - last_refill
+        if elapsed > 0 then
+            tokens = math.min(state.a, tokens + elapsed * state.b)
+        end
+        state.tokens = tokens
+        state.allowed = tokens >= state.cost
+    else
+        return redis.error_reply('unknown limiter type: ' .. tostring(state.kind))
+    end
+    if not state.allowed and rejected == 0 then
+        rejected = i
+    end
+    states[i] = state
+end
+
+-- 第二阶段：全部通过时扣减
+local result = {rejected == 0 and 1 or 0, rejected}
+for i = 1, #KEYS do
+    local key = KEYS[i]
+    local state = states[i]
+    local remaining
+    if state.kind == 'sliding_window' then
+        if rejected == 0 then
+            redis.call('ZREMRANGEBYSCORE', key, '-inf', now - state.a)
+            for j = 1, state.cost do
+                redis.call('ZADD', key, now, request_id .. ':' .. i .. ':' .. j)
+            end
+            redis.call('EXPIRE', key, math.ceil(state.a / 1000) + 1)
+            state.count = state.count + state.cost
+        end
+        remaining = math.max(0, state.b - state.count)
+    elseif state.kind == 'fixed_window' then
+        if rejected == 0 then
+            redis.call('INCRBY', state.window_key, state.cost)
+            redis.call('EXPIRE', state.window_key, math.ceil(state.a / 1000) + 1)
+            state.count = state.count + state.cost
+        end
+        remaining = math.max(0, state.b - state.count)
+    else
+        if rejected == 0 then
+            state.tokens = state.tokens - state.cost
+            redis.call('HMSET', key, 'tokens', state.tokens, 'last_refill', now)
+            if state.b > 0 then
+                redis.call('EXPIRE', key, math.ceil(state.a / state.b / 1000) + 60)
+            end
+        end
+        remaining = math.floor(state.tokens)
+    end
+    table.insert(result, remaining)
+end
+
+return result
+"#;
+
 /// Lua脚本信息
 #[derive(Debug, Clone)]
 pub struct LuaScriptInfo {
@@ -256,20 +721,40 @@ pub struct LuaScriptInfo {
     pub script_type: LuaScriptType,
     /// 脚本内容
     pub script: &'static str,
+    /// 脚本版本
+    pub version: &'static str,
     /// SHA哈希（计算后填充）
     pub sha: Arc<parking_lot::Mutex<Option<String>>>,
+    /// 带版本标记的实际加载内容
+    source: String,
 }
 
 impl LuaScriptInfo {
     /// 创建新的脚本信息
+    ///
+    /// 加载内容以 `-- limiteron:<名称>:v<版本>` 开头，升级脚本版本后SHA随之变化，
+    /// 不会误用Redis中缓存的旧脚本。
     pub fn new(script_type: LuaScriptType, script: &'static str) -> Self {
+        let version = script_type.version();
         Self {
             script_type,
             script,
+            version,
             sha: Arc::new(parking_lot::Mutex::new(None)),
+            source: format!(
+                "-- limiteron:{}:v{}\n{}",
+                script_type.name(),
+                version,
+                script
+            ),
         }
     }
 
+    /// 带版本标记的实际加载内容
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
     /// 获取脚本SHA，如果未计算则返回None
     pub fn get_sha(&self) -> Option<String> {
         self.sha.lock().clone()
@@ -309,10 +794,62 @@ impl LuaScriptManager {
             LuaScriptType::QuotaReset,
             LuaScriptInfo::new(LuaScriptType::QuotaReset, QUOTA_RESET_SCRIPT),
         );
+        scripts.insert(
+            LuaScriptType::CompositeQuotaConsume,
+            LuaScriptInfo::new(
+                LuaScriptType::CompositeQuotaConsume,
+                COMPOSITE_QUOTA_CONSUME_SCRIPT,
+            ),
+        );
+        scripts.insert(
+            LuaScriptType::QuotaReserve,
+            LuaScriptInfo::new(LuaScriptType::QuotaReserve, QUOTA_RESERVE_SCRIPT),
+        );
+        scripts.insert(
+            LuaScriptType::QuotaReservationFinish,
+            LuaScriptInfo::new(
+                LuaScriptType::QuotaReservationFinish,
+                QUOTA_RESERVATION_FINISH_SCRIPT,
+            ),
+        );
         scripts.insert(
             LuaScriptType::TokenBucket,
             LuaScriptInfo::new(LuaScriptType::TokenBucket, TOKEN_BUCKET_SCRIPT),
         );
+        scripts.insert(
+            LuaScriptType::ConcurrencyAcquire,
+            LuaScriptInfo::new(
+                LuaScriptType::ConcurrencyAcquire,
+                CONCURRENCY_ACQUIRE_SCRIPT,
+            ),
+        );
+        scripts.insert(
+            LuaScriptType::ConcurrencyRelease,
+            LuaScriptInfo::new(
+                LuaScriptType::ConcurrencyRelease,
+                CONCURRENCY_RELEASE_SCRIPT,
+            ),
+        );
+        scripts.insert(
+            LuaScriptType::SlidingWindowPeek,
+            LuaScriptInfo::new(LuaScriptType::SlidingWindowPeek, SLIDING_WINDOW_PEEK_SCRIPT),
+        );
+        scripts.insert(
+            LuaScriptType::FixedWindowPeek,
+            LuaScriptInfo::new(LuaScriptType::FixedWindowPeek, FIXED_WINDOW_PEEK_SCRIPT),
+        );
+        scripts.insert(
+            LuaScriptType::TokenBucketPeek,
+            LuaScriptInfo::new(LuaScriptType::TokenBucketPeek, TOKEN_BUCKET_PEEK_SCRIPT),
+        );
+        scripts.insert(
+            LuaScriptType::ConcurrencyPeek,
+            LuaScriptInfo::new(LuaScriptType::ConcurrencyPeek, CONCURRENCY_PEEK_SCRIPT),
+        );
+        scripts.insert(
+            LuaScriptType::CombinedCheck,
+            LuaScriptInfo::new(LuaScriptType::CombinedCheck, COMBINED_CHECK_SCRIPT),
+        );
 
         Self { scripts }
     }
@@ -351,17 +888,21 @@ impl LuaScriptManager {
     where
         C: AsyncCommands + redis::aio::ConnectionLike,
     {
-        // 计算SHA
-        let script = Script::new(script_info.script);
-        let sha = script.get_hash().to_string();
-
-        // 缓存SHA
-        script_info.set_sha(sha.clone());
+        self.load_script(conn, script_info).await.map(|_| ())
+    }
 
-        // 执行SCRIPT LOAD预加载
-        let _: String = redis::cmd("SCRIPT")
+    /// 执行SCRIPT LOAD并缓存返回的SHA
+    async fn load_script<C>(
+        &self,
+        conn: &mut C,
+        script_info: &LuaScriptInfo,
+    ) -> Result<String, StorageError>
+    where
+        C: AsyncCommands + redis::aio::ConnectionLike,
+    {
+        let sha: String = redis::cmd("SCRIPT")
             .arg("LOAD")
-            .arg(script_info.script)
+            .arg(script_info.source())
             .query_async(conn)
             .await
             .map_err(|e| {
@@ -369,16 +910,22 @@ impl LuaScriptManager {
                 StorageError::ConnectionError(format!("预加载脚本失败: {}", e))
             })?;
 
+        debug_assert_eq!(sha, Script::new(script_info.source()).get_hash());
+        script_info.set_sha(sha.clone());
+
         debug!(
-            "脚本预加载成功: {:?}, SHA: {}",
-            script_info.script_type, sha
+            "脚本预加载成功: {:?} v{}, SHA: {}",
+            script_info.script_type, script_info.version, sha
         );
 
-        Ok(())
+        Ok(sha)
     }
 
     /// 执行脚本（使用SHA）
     ///
+    /// SHA未缓存时先加载脚本；Redis返回NOSCRIPT时重新加载并重试一次，
+    /// 调用方不会感知脚本缓存被清空。
+    ///
     /// # 安全说明
     /// - 添加超时保护，防止脚本执行时间过长
     /// - 限制参数数量，防止参数过多导致资源消耗
@@ -418,67 +965,73 @@ impl LuaScriptManager {
             .get_script(script_type)
             .ok_or_else(|| StorageError::QueryError(format!("未找到脚本: {:?}", script_type)))?;
 
-        let sha = script_info
-            .get_sha()
-            .ok_or_else(|| StorageError::QueryError("脚本SHA未初始化".to_string()))?;
+        // SHA缓存被清除时按需加载
+        let sha = match script_info.get_sha() {
+            Some(sha) => sha,
+            None => self.load_script(conn, script_info).await?,
+        };
 
         trace!("执行脚本: {:?}, SHA: {}", script_type, sha);
 
+        let error = match Self::eval_sha(conn, script_type, &sha, keys, args).await? {
+            Ok(result) => return Ok(result),
+            Err(e) if Self::is_no_script(&e) => e,
+            Err(e) => return Err(Self::script_error(script_type, e)),
+        };
+
+        // Redis重启、故障转移或SCRIPT FLUSH后脚本缓存丢失，重新加载后重试一次
+        debug!(
+            "脚本SHA不存在，重新加载: {:?}, 错误: {}",
+            script_type, error
+        );
+        let sha = self.load_script(conn, script_info).await?;
+        Self::eval_sha(conn, script_type, &sha, keys, args)
+            .await?
+            .map_err(|e| Self::script_error(script_type, e))
+    }
+
+    /// 使用SHA执行脚本（带超时保护）
+    ///
+    /// 外层错误为超时，内层为Redis返回的错误。
+    async fn eval_sha<C, T>(
+        conn: &mut C,
+        script_type: LuaScriptType,
+        sha: &str,
+        keys: &[&str],
+        args: &[&str],
+    ) -> Result<Result<T, redis::RedisError>, StorageError>
+    where
+        C: AsyncCommands + redis::aio::ConnectionLike,
+        T: redis::FromRedisValue,
+    {
         // 添加超时保护（5秒）
         let timeout = Duration::from_secs(5);
 
-        // 尝试使用SHA执行
-        let result = tokio::time::timeout(
+        tokio::time::timeout(
             timeout,
             redis::cmd("EVALSHA")
-                .arg(&sha)
+                .arg(sha)
                 .arg(keys.len())
                 .arg(keys)
                 .arg(args)
                 .query_async::<_, T>(conn),
         )
-        .await;
-
-        match result {
-            Ok(Ok(result)) => Ok(result),
-            Ok(Err(e)) => {
-                // 如果SHA不存在，重新加载脚本
-                if e.to_string().contains("NOSCRIPT") {
-                    debug!("脚本SHA不存在，重新加载: {:?}", script_type);
-                    self.preload_script(conn, script_info).await?;
-
-                    // 重试执行（带超时）
-                    match tokio::time::timeout(
-                        timeout,
-                        redis::cmd("EVALSHA")
-                            .arg(&sha)
-                            .arg(keys.len())
-                            .arg(keys)
-                            .arg(args)
-                            .query_async::<_, T>(conn),
-                    )
-                    .await
-                    {
-                        Ok(Ok(result)) => Ok(result),
-                        Ok(Err(e)) => {
-                            error!("脚本执行失败: {:?}, 错误: {}", script_type, e);
-                            Err(StorageError::QueryError(format!("脚本执行失败: {}", e)))
-                        }
-                        Err(_) => {
-                            error!("脚本执行超时: {:?}", script_type);
-                            Err(StorageError::TimeoutError("脚本执行超时".to_string()))
-                        }
-                    }
-                } else {
-                    error!("脚本执行失败: {:?}, 错误: {}", script_type, e);
-                    Err(StorageError::QueryError(format!("脚本执行失败: {}", e)))
-                }
-            }
-            Err(_) => {
-                error!("脚本执行超时: {:?}", script_type);
-                Err(StorageError::TimeoutError("脚本执行超时".to_string()))
-            }
-        }
+        .await
+        .map_err(|_| {
+            error!("脚本执行超时: {:?}", script_type);
+            StorageError::TimeoutError("脚本执行超时".to_string())
+        })
+    }
+
+    /// 是否为脚本缓存中不存在该SHA的错误
+    fn is_no_script(error: &redis::RedisError) -> bool {
+        error.kind() == redis::ErrorKind::NoScriptError || error.to_string().contains("NOSCRIPT")
+    }
+
+    /// 转换脚本执行错误
+    fn script_error(script_type: LuaScriptType, error: redis::RedisError) -> StorageError {
+        error!("脚本执行失败: {:?}, 错误: {}", script_type, error);
+        StorageError::QueryError(format!("脚本执行失败: {}", error))
     }
 
     /// 执行脚本（直接使用脚本内容）
@@ -500,7 +1053,7 @@ impl LuaScriptManager {
         trace!("直接执行脚本: {:?}", script_type);
 
         redis::cmd("EVAL")
-            .arg(script_info.script)
+            .arg(script_info.source())
             .arg(keys.len())
             .arg(keys)
             .arg(args)
@@ -537,7 +1090,24 @@ mod tests {
         assert_eq!(LuaScriptType::FixedWindow.name(), "fixed_window");
         assert_eq!(LuaScriptType::QuotaConsume.name(), "quota_consume");
         assert_eq!(LuaScriptType::QuotaReset.name(), "quota_reset");
+        assert_eq!(
+            LuaScriptType::CompositeQuotaConsume.name(),
+            "composite_quota_consume"
+        );
+        assert_eq!(LuaScriptType::QuotaReserve.name(), "quota_reserve");
+        assert_eq!(
+            LuaScriptType::QuotaReservationFinish.name(),
+            "quota_reservation_finish"
+        );
         assert_eq!(LuaScriptType::TokenBucket.name(), "token_bucket");
+        assert_eq!(
+            LuaScriptType::ConcurrencyAcquire.name(),
+            "concurrency_acquire"
+        );
+        assert_eq!(
+            LuaScriptType::ConcurrencyRelease.name(),
+            "concurrency_release"
+        );
     }
 
     #[test]
@@ -553,7 +1123,20 @@ mod tests {
         assert!(manager.get_script(LuaScriptType::FixedWindow).is_some());
         assert!(manager.get_script(LuaScriptType::QuotaConsume).is_some());
         assert!(manager.get_script(LuaScriptType::QuotaReset).is_some());
+        assert!(manager
+            .get_script(LuaScriptType::CompositeQuotaConsume)
+            .is_some());
+        assert!(manager.get_script(LuaScriptType::QuotaReserve).is_some());
+        assert!(manager
+            .get_script(LuaScriptType::QuotaReservationFinish)
+            .is_some());
         assert!(manager.get_script(LuaScriptType::TokenBucket).is_some());
+        assert!(manager
+            .get_script(LuaScriptType::ConcurrencyAcquire)
+            .is_some());
+        assert!(manager
+            .get_script(LuaScriptType::ConcurrencyRelease)
+            .is_some());
     }
 
     #[test]
@@ -567,6 +1150,28 @@ mod tests {
         assert_eq!(script_info.get_sha(), Some("test_sha".to_string()));
     }
 
+    #[test]
+    fn test_lua_script_source_carries_version() {
+        let manager = LuaScriptManager::new();
+        for script_info in manager.get_all_scripts() {
+            let header = format!(
+                "-- limiteron:{}:v{}\n",
+                script_info.script_type.name(),
+                script_info.script_type.version()
+            );
+            assert_eq!(script_info.version, script_info.script_type.version());
+            assert!(script_info.source().starts_with(&header));
+            assert!(script_info.source().ends_with(script_info.script));
+        }
+
+        // 版本标记参与SHA计算，与未标记的脚本内容不同
+        let script_info = manager.get_script(LuaScriptType::QuotaConsume).unwrap();
+        assert_ne!(
+            Script::new(script_info.source()).get_hash(),
+            Script::new(script_info.script).get_hash()
+        );
+    }
+
     #[test]
     fn test_clear_sha_cache() {
         let manager = LuaScriptManager::new();
@@ -607,7 +1212,46 @@ mod tests {
         assert!(QUOTA_CONSUME_SCRIPT.contains("HINCRBY"));
         assert!(QUOTA_CONSUME_SCRIPT.contains("HMSET"));
 
+        assert!(COMPOSITE_QUOTA_CONSUME_SCRIPT.contains("HINCRBY"));
+        assert!(COMPOSITE_QUOTA_CONSUME_SCRIPT.contains("PEXPIRE"));
+
+        assert!(QUOTA_RESERVE_SCRIPT.contains("cjson.encode"));
+        assert!(QUOTA_RESERVATION_FINISH_SCRIPT.contains("HDEL"));
+
         assert!(TOKEN_BUCKET_SCRIPT.contains("HGET"));
         assert!(TOKEN_BUCKET_SCRIPT.contains("HMSET"));
+
+        assert!(CONCURRENCY_ACQUIRE_SCRIPT.contains("ZREMRANGEBYSCORE"));
+        assert!(CONCURRENCY_ACQUIRE_SCRIPT.contains("PEXPIRE"));
+        assert!(CONCURRENCY_RELEASE_SCRIPT.contains("ZREM"));
+
+        // 组合检查在扣减前完成全部判断
+        let check = COMBINED_CHECK_SCRIPT.find("ZCOUNT").unwrap();
+        let consume = COMBINED_CHECK_SCRIPT.find("'ZADD'").unwrap();
+        assert!(check < consume);
+        assert!(COMBINED_CHECK_SCRIPT.contains("if rejected == 0 then"));
+    }
+
+    #[test]
+    fn test_peek_scripts_are_read_only() {
+        let manager = LuaScriptManager::new();
+        for script_type in [
+            LuaScriptType::SlidingWindowPeek,
+            LuaScriptType::FixedWindowPeek,
+            LuaScriptType::TokenBucketPeek,
+            LuaScriptType::ConcurrencyPeek,
+        ] {
+            let script = manager.get_script(script_type).unwrap().script;
+            for command in [
+                "ZADD", "ZREM", "INCR", "HSET", "HMSET", "HDEL", "DEL", "EXPIRE",
+            ] {
+                assert!(
+                    !script.contains(&format!("'{}'", command)),
+                    "{:?} must not call {}",
+                    script_type,
+                    command
+                );
+            }
+        }
     }
 }
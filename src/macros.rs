@@ -25,6 +25,58 @@
 // 重新导出过程宏
 pub use limiteron_macros::flow_control;
 
+/// 过程宏生成代码所使用的运行时支持，不属于公开 API
+#[doc(hidden)]
+pub mod __private {
+    use crate::error::FlowGuardError;
+    use std::cell::RefCell;
+    use std::future::Future;
+
+    thread_local! {
+        static RUNTIME: RefCell<Option<tokio::runtime::Runtime>> = const { RefCell::new(None) };
+    }
+
+    /// 同步函数路径：在当前线程的 current_thread 运行时上执行限流检查
+    ///
+    /// 运行时按线程惰性创建并复用。若调用方已处于 Tokio 运行时中，
+    /// 阻塞等待会导致 panic，因此直接返回错误。
+    pub fn block_on<F: Future>(future: F) -> Result<F::Output, FlowGuardError> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Err(FlowGuardError::Other(
+                "flow_control: sync function with runtime = \"current_thread\" cannot be called from within a Tokio runtime".to_string(),
+            ));
+        }
+
+        RUNTIME.with(|cell| {
+            let mut slot = cell.borrow_mut();
+            if slot.is_none() {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(FlowGuardError::IoError)?;
+                *slot = Some(runtime);
+            }
+            match slot.as_ref() {
+                Some(runtime) => Ok(runtime.block_on(future)),
+                None => Err(FlowGuardError::Other(
+                    "flow_control: failed to initialize runtime".to_string(),
+                )),
+            }
+        })
+    }
+
+    /// 软限流等待
+    pub use tokio::time::sleep;
+
+    /// 记录一次通过流量控制的请求（未启用 monitoring 特性时为空操作）
+    pub fn record_request() {
+        #[cfg(feature = "monitoring")]
+        if let Some(metrics) = crate::telemetry::try_global() {
+            metrics.requests_total.inc();
+        }
+    }
+}
+
 /// 流量控制配置
 #[derive(Debug, Clone)]
 pub struct FlowControlConfig {
@@ -34,12 +86,24 @@ pub struct FlowControlConfig {
     pub quota: Option<QuotaLimit>,
     /// 并发限制
     pub concurrency: Option<u32>,
-    /// 标识符列表
+    /// 获取并发许可的最长等待时间
+    pub acquire_timeout: Option<std::time::Duration>,
+    /// 标识符表达式列表（在被注解函数内求值，通常引用函数参数）
     pub identifiers: Vec<String>,
     /// 超限行为
     pub on_exceed: String,
+    /// 软限流（`on_exceed = "delay"`）时超出速率限制的调用等待的时长
+    pub delay: Option<std::time::Duration>,
     /// 拒绝消息
     pub reject_message: String,
+    /// 每次调用消耗的固定成本（默认 1）
+    pub cost: Option<u64>,
+    /// 根据函数参数计算成本的函数路径
+    pub cost_fn: Option<String>,
+    /// 同步函数使用的运行时（目前仅支持 "current_thread"）
+    pub runtime: Option<String>,
+    /// 将 FlowGuardError 转换为函数错误类型的函数路径
+    pub on_reject: Option<String>,
 }
 
 /// 速率限制配置
@@ -204,9 +268,15 @@ mod tests {
             rate: None,
             quota: None,
             concurrency: None,
+            acquire_timeout: None,
             identifiers: vec![],
             on_exceed: "reject".to_string(),
+            delay: None,
             reject_message: "Rate limit exceeded".to_string(),
+            cost: Some(5),
+            cost_fn: None,
+            runtime: None,
+            on_reject: None,
         };
 
         assert!(config.rate.is_none());
@@ -215,5 +285,6 @@ mod tests {
         assert!(config.identifiers.is_empty());
         assert_eq!(config.on_exceed, "reject");
         assert_eq!(config.reject_message, "Rate limit exceeded");
+        assert_eq!(config.cost, Some(5));
     }
 }
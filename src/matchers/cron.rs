@@ -0,0 +1,412 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! Cron 时间窗口匹配器
+//!
+//! 按 cron 表达式与 IANA 时区判断当前时间是否处于生效时段，用于只在工作时间、
+//! 特定星期等时段生效的规则。
+//!
+//! # 表达式
+//!
+//! 标准五段式：`分 时 日 月 星期`，每段支持 `*`、单值、范围 `a-b`、步长 `*/n` 与 `a-b/n`、
+//! 逗号分隔的列表；月份支持 `JAN`-`DEC`，星期支持 `SUN`-`SAT`（`0` 与 `7` 均表示周日）。
+//! 日与星期都不是 `*` 时，两者满足其一即可（与 Vixie cron 一致）。
+//!
+//! 当前时间所在的本地分钟满足表达式即视为生效，例如 `* 9-17 * * MON-FRI`
+//! 表示周一至周五 9:00-17:59。
+//!
+//! # 夏令时
+//!
+//! 匹配始终基于 UTC 时间换算出的本地挂钟时间：
+//! - 夏令时开始时跳过的本地时间（如 2:00-2:59）不存在，相应时段不会生效；
+//! - 夏令时结束时重复的本地时间会出现两次，两次都按表达式匹配。
+//!
+//! # 使用示例
+//!
+//! ```rust
+//! use limiteron::matchers::cron::CronTimeWindowMatcher;
+//!
+//! let matcher = CronTimeWindowMatcher::new("* 9-17 * * MON-FRI", "Asia/Shanghai").unwrap();
+//! assert_eq!(matcher.timezone(), "Asia/Shanghai");
+//! ```
+
+use crate::clock::{Clock, RealClock};
+use crate::error::FlowGuardError;
+use crate::matchers::{ConditionEvaluator, RequestContext};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono_tz::Tz;
+use std::fmt;
+use std::sync::Arc;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// cron 表达式中的一段，以位图记录允许的取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronField {
+    bits: u64,
+    /// 该段是否以 `*` 开头（不限制）
+    any: bool,
+}
+
+impl CronField {
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+
+    /// 解析一段表达式，取值范围为 `min..=max`
+    fn parse(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Self, String> {
+        let mut bits = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step.parse().map_err(|_| format!("无效的步长 '{}'", step))?;
+                    if step == 0 {
+                        return Err("步长不能为 0".to_string());
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (
+                    Self::parse_value(start, min, max, names)?,
+                    Self::parse_value(end, min, max, names)?,
+                )
+            } else {
+                let start = Self::parse_value(range, min, max, names)?;
+                // `a/n` 表示从 a 开始到最大值
+                let end = if part.contains('/') { max } else { start };
+                (start, end)
+            };
+
+            if start > end {
+                return Err(format!("范围 '{}' 的起点大于终点", range));
+            }
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+
+        Ok(Self {
+            bits,
+            any: field.starts_with('*'),
+        })
+    }
+
+    fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+        let parsed = match names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            Some(index) => index as u32 + min,
+            None => value
+                .parse()
+                .map_err(|_| format!("无效的取值 '{}'", value))?,
+        };
+        if parsed < min || parsed > max {
+            return Err(format!("取值 {} 超出范围 {}-{}", parsed, min, max));
+        }
+        Ok(parsed)
+    }
+}
+
+/// 解析后的五段式 cron 表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronSchedule {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+}
+
+impl CronSchedule {
+    fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!("需要 5 段，实际 {} 段", fields.len()));
+        };
+
+        let mut day_of_week = CronField::parse(day_of_week, 0, 7, &WEEKDAY_NAMES)?;
+        // 7 与 0 都表示周日
+        if day_of_week.contains(7) {
+            day_of_week.bits = (day_of_week.bits | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minute: CronField::parse(minute, 0, 59, &[])?,
+            hour: CronField::parse(hour, 0, 23, &[])?,
+            day_of_month: CronField::parse(day_of_month, 1, 31, &[])?,
+            month: CronField::parse(month, 1, 12, &MONTH_NAMES)?,
+            day_of_week,
+        })
+    }
+
+    fn includes<T: Datelike + Timelike>(&self, local: &T) -> bool {
+        let day_of_month = self.day_of_month.contains(local.day());
+        let day_of_week = self
+            .day_of_week
+            .contains(local.weekday().num_days_from_sunday());
+        let day = if self.day_of_month.any || self.day_of_week.any {
+            day_of_month && day_of_week
+        } else {
+            day_of_month || day_of_week
+        };
+
+        day && self.minute.contains(local.minute())
+            && self.hour.contains(local.hour())
+            && self.month.contains(local.month())
+    }
+}
+
+/// Cron 时间窗口匹配器
+///
+/// 当前时间（由 [`Clock`] 提供）换算到指定时区后满足 cron 表达式时匹配，与请求内容无关。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::clock::MockClock;
+/// use limiteron::matchers::cron::CronTimeWindowMatcher;
+/// use limiteron::matchers::{ConditionEvaluator, RequestContext};
+/// use std::sync::Arc;
+///
+/// // 2026-01-05 是周一，UTC 02:00 即上海时间 10:00
+/// let clock = Arc::new(MockClock::starting_at("2026-01-05T02:00:00Z".parse().unwrap()));
+/// let matcher = CronTimeWindowMatcher::new("* 9-17 * * MON-FRI", "Asia/Shanghai")
+///     .unwrap()
+///     .with_clock(clock);
+/// assert!(matcher.evaluate(&RequestContext::new()));
+/// ```
+#[derive(Clone)]
+pub struct CronTimeWindowMatcher {
+    expression: String,
+    schedule: CronSchedule,
+    timezone: Tz,
+    clock: Arc<dyn Clock>,
+}
+
+impl CronTimeWindowMatcher {
+    /// 从 cron 表达式与 IANA 时区名（如 `Europe/Berlin`）创建匹配器
+    ///
+    /// 表达式或时区无效时返回 [`FlowGuardError::ConfigError`]。
+    pub fn new(expression: &str, timezone: &str) -> Result<Self, FlowGuardError> {
+        let schedule = CronSchedule::parse(expression).map_err(|e| {
+            FlowGuardError::ConfigError(format!("无效的 cron 表达式 '{}': {}", expression, e))
+        })?;
+        let timezone: Tz = timezone
+            .parse()
+            .map_err(|_| FlowGuardError::ConfigError(format!("未知的时区 '{}'", timezone)))?;
+
+        Ok(Self {
+            expression: expression.to_string(),
+            schedule,
+            timezone,
+            clock: RealClock::shared(),
+        })
+    }
+
+    /// 设置时间来源
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 获取 cron 表达式
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// 获取时区名
+    pub fn timezone(&self) -> &str {
+        self.timezone.name()
+    }
+
+    /// 判断指定时间是否处于生效时段
+    pub fn is_active_at(&self, time: DateTime<Utc>) -> bool {
+        self.schedule.includes(&time.with_timezone(&self.timezone))
+    }
+
+    /// 判断当前时间是否处于生效时段
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(self.clock.now_utc())
+    }
+}
+
+impl fmt::Debug for CronTimeWindowMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CronTimeWindowMatcher")
+            .field("expression", &self.expression)
+            .field("timezone", &self.timezone.name())
+            .finish()
+    }
+}
+
+impl ConditionEvaluator for CronTimeWindowMatcher {
+    fn evaluate(&self, _context: &RequestContext) -> bool {
+        self.is_active()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "CronTimeWindow({} @ {})",
+            self.expression,
+            self.timezone.name()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::time::Duration;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn matcher_at(
+        expression: &str,
+        timezone: &str,
+        time: &str,
+    ) -> (CronTimeWindowMatcher, Arc<MockClock>) {
+        let clock = Arc::new(MockClock::starting_at(utc(time)));
+        let matcher = CronTimeWindowMatcher::new(expression, timezone)
+            .unwrap()
+            .with_clock(clock.clone());
+        (matcher, clock)
+    }
+
+    #[test]
+    fn test_cron_schedule_parse() {
+        let schedule = CronSchedule::parse("*/15 9-17 1,15 JAN-mar SUN,7").unwrap();
+        assert_eq!(schedule.minute.bits, 1 | 1 << 15 | 1 << 30 | 1 << 45);
+        assert!(schedule.hour.contains(9) && schedule.hour.contains(17));
+        assert!(!schedule.hour.contains(18));
+        assert!(schedule.day_of_month.contains(15) && !schedule.day_of_month.contains(2));
+        assert!(schedule.month.contains(3) && !schedule.month.contains(4));
+        assert_eq!(schedule.day_of_week.bits, 1);
+        assert!(!schedule.day_of_week.any);
+
+        let schedule = CronSchedule::parse("5/20 * * * *").unwrap();
+        assert_eq!(schedule.minute.bits, 1 << 5 | 1 << 25 | 1 << 45);
+        assert!(schedule.day_of_week.any);
+
+        for invalid in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "10-5 * * * *",
+            "* * * FOO *",
+        ] {
+            assert!(CronSchedule::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_cron_matcher_rejects_invalid_input() {
+        assert!(matches!(
+            CronTimeWindowMatcher::new("* * *", "UTC"),
+            Err(FlowGuardError::ConfigError(_))
+        ));
+        assert!(matches!(
+            CronTimeWindowMatcher::new("* * * * *", "Mars/Olympus"),
+            Err(FlowGuardError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cron_day_of_month_or_day_of_week() {
+        // 日与星期都受限时满足其一即可：每月 13 日或每周五
+        let matcher = CronTimeWindowMatcher::new("* * 13 * FRI", "UTC").unwrap();
+        assert!(matcher.is_active_at(utc("2026-01-13T12:00:00Z"))); // 周二，13 日
+        assert!(matcher.is_active_at(utc("2026-01-16T12:00:00Z"))); // 周五
+        assert!(!matcher.is_active_at(utc("2026-01-14T12:00:00Z")));
+
+        // 只限制星期时日按 `*` 处理
+        let matcher = CronTimeWindowMatcher::new("* * * * FRI", "UTC").unwrap();
+        assert!(!matcher.is_active_at(utc("2026-01-13T12:00:00Z")));
+    }
+
+    #[test]
+    fn test_cron_matcher_weekday_boundary() {
+        // 2026-01-09 周五 17:59（纽约，UTC-5）
+        let (matcher, clock) = matcher_at(
+            "* 9-17 * * MON-FRI",
+            "America/New_York",
+            "2026-01-09T22:59:00Z",
+        );
+        let context = RequestContext::new();
+        assert!(matcher.evaluate(&context));
+
+        // 周五 18:00 下班
+        clock.advance(Duration::from_secs(60));
+        assert!(!matcher.evaluate(&context));
+
+        // 周六 10:00 不生效，尽管当天是工作时间段
+        clock.advance(Duration::from_secs(16 * 3600));
+        assert!(!matcher.evaluate(&context));
+
+        // 周一 09:00 恢复
+        clock.advance(Duration::from_secs(47 * 3600));
+        assert!(matcher.evaluate(&context));
+
+        // 按本地时间判断星期：纽约周五 23:59 时 UTC 已是周六
+        let matcher = CronTimeWindowMatcher::new("* * * * MON-FRI", "America/New_York").unwrap();
+        assert!(matcher.is_active_at(utc("2026-01-10T04:59:00Z")));
+        assert!(!matcher.is_active_at(utc("2026-01-10T05:00:00Z")));
+    }
+
+    #[test]
+    fn test_cron_matcher_across_dst_start() {
+        // 纽约 2026-03-08 02:00 进入夏令时，UTC 偏移从 -5 变为 -4
+        let (matcher, clock) = matcher_at("* 9 * * *", "America/New_York", "2026-03-07T14:30:00Z");
+        let context = RequestContext::new();
+        assert!(matcher.evaluate(&context)); // 09:30 EST
+
+        // 同一 UTC 时刻在夏令时后是 10:30
+        clock.advance(Duration::from_secs(24 * 3600));
+        assert!(!matcher.evaluate(&context));
+        assert!(matcher.is_active_at(utc("2026-03-08T13:30:00Z"))); // 09:30 EDT
+
+        // 被跳过的 02:00-02:59 不存在，整晚都不生效
+        let matcher = CronTimeWindowMatcher::new("* 2 * * *", "America/New_York").unwrap();
+        let start = utc("2026-03-08T05:00:00Z");
+        let active = (0..4 * 60)
+            .filter(|minute| matcher.is_active_at(start + chrono::Duration::minutes(*minute)))
+            .count();
+        assert_eq!(active, 0);
+    }
+
+    #[test]
+    fn test_cron_matcher_across_dst_end() {
+        // 纽约 2026-11-01 02:00 退出夏令时，01:00-01:59 出现两次
+        let matcher = CronTimeWindowMatcher::new("* 1 * * *", "America/New_York").unwrap();
+        let start = utc("2026-11-01T04:00:00Z");
+        let active = (0..4 * 60)
+            .filter(|minute| matcher.is_active_at(start + chrono::Duration::minutes(*minute)))
+            .count();
+        assert_eq!(active, 120);
+
+        let (matcher, clock) =
+            matcher_at("0-29 1 * * *", "America/New_York", "2026-11-01T05:15:00Z");
+        assert!(matcher.is_active()); // 01:15 EDT
+        clock.advance(Duration::from_secs(30 * 60));
+        assert!(!matcher.is_active()); // 01:45 EDT
+        clock.advance(Duration::from_secs(30 * 60));
+        assert!(matcher.is_active()); // 01:15 EST
+    }
+}
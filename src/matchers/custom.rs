@@ -53,7 +53,7 @@
 //! ```
 
 use crate::error::FlowGuardError;
-use crate::matchers::RequestContext;
+use crate::matchers::{ConditionEvaluator, RequestContext};
 use ahash::AHashMap as HashMap;
 use async_trait::async_trait;
 use chrono::Timelike;
@@ -355,7 +355,7 @@ pub trait CustomMatcher: Send + Sync {
 #[derive(Clone)]
 pub struct CustomMatcherRegistry {
     /// 匹配器存储（使用 RwLock 实现线程安全）
-    matchers: Arc<RwLock<HashMap<String, Box<dyn CustomMatcher>>>>,
+    matchers: Arc<RwLock<HashMap<String, Arc<dyn CustomMatcher>>>>,
 }
 
 impl std::fmt::Debug for CustomMatcherRegistry {
@@ -419,7 +419,7 @@ impl CustomMatcherRegistry {
         }
 
         info!("注册自定义匹配器: {}", name);
-        matchers.insert(name.clone(), matcher);
+        matchers.insert(name.clone(), Arc::from(matcher));
         debug!("当前注册的匹配器数量: {}", matchers.len());
 
         Ok(())
@@ -462,6 +462,22 @@ impl CustomMatcherRegistry {
         }
     }
 
+    /// 获取共享的匹配器实例
+    ///
+    /// 与 [`get`](Self::get) 不同，返回的 `Arc` 与注册表共享同一个实例，
+    /// 可用于构建 [`CustomMatcherCondition`]。
+    ///
+    /// # 参数
+    /// - `name`: 匹配器名称
+    ///
+    /// # 返回
+    /// - `Some(matcher)`: 找到匹配器
+    /// - `None`: 未找到匹配器
+    pub async fn resolve(&self, name: &str) -> Option<Arc<dyn CustomMatcher>> {
+        let matchers = self.matchers.read().await;
+        matchers.get(name).cloned()
+    }
+
     /// 检查匹配器是否存在
     ///
     /// # 参数
@@ -588,6 +604,76 @@ impl Default for CustomMatcherRegistry {
     }
 }
 
+// ============================================================================
+// CustomMatcherCondition
+// ============================================================================
+
+/// 基于已注册自定义匹配器的条件
+///
+/// 将 [`CustomMatcher`] 适配为同步的 [`ConditionEvaluator`]，
+/// 供 [`RuleMatcher`](crate::matchers::RuleMatcher) 在规则中使用。
+///
+/// 由于 `evaluate` 是同步调用，匹配器的 `matches` 只会被轮询一次：
+/// 需要真正等待 I/O 的匹配器会被视为不匹配并记录警告。
+/// 匹配器返回错误时同样视为不匹配。
+#[derive(Clone)]
+pub struct CustomMatcherCondition {
+    /// 匹配器名称
+    name: String,
+    /// 匹配器实例
+    matcher: Arc<dyn CustomMatcher>,
+}
+
+impl CustomMatcherCondition {
+    /// 创建新的自定义匹配器条件
+    ///
+    /// # 参数
+    /// - `name`: 匹配器名称
+    /// - `matcher`: 匹配器实例
+    pub fn new(name: &str, matcher: Arc<dyn CustomMatcher>) -> Self {
+        Self {
+            name: name.to_string(),
+            matcher,
+        }
+    }
+
+    /// 获取匹配器名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl std::fmt::Debug for CustomMatcherCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomMatcherCondition")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl ConditionEvaluator for CustomMatcherCondition {
+    fn evaluate(&self, context: &RequestContext) -> bool {
+        let mut future = self.matcher.matches(context);
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+
+        match future.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(Ok(matched)) => matched,
+            std::task::Poll::Ready(Err(e)) => {
+                warn!("自定义匹配器 '{}' 执行失败: {}", self.name, e);
+                false
+            }
+            std::task::Poll::Pending => {
+                warn!("自定义匹配器 '{}' 无法同步完成，视为不匹配", self.name);
+                false
+            }
+        }
+    }
+
+    fn description(&self) -> String {
+        format!("Custom matcher '{}'", self.name)
+    }
+}
+
 // ============================================================================
 // TimeWindowMatcher 示例实现
 // ============================================================================
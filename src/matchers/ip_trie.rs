@@ -0,0 +1,213 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! IP 前缀索引
+//!
+//! 将规则的 IP 条件展开为 CIDR 前缀并插入二叉前缀树，查询时按客户端 IP 的比特逐位下探，
+//! 沿途节点上登记的规则即为命中的规则。查询复杂度取决于地址长度（IPv4 32 位、IPv6 128 位），
+//! 与规则数量无关。
+
+use super::IpRange;
+use std::net::IpAddr;
+
+/// 前缀树节点
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// 下一比特为 0 / 1 时的子节点下标
+    children: [Option<u32>; 2],
+    /// 前缀恰好终止于此节点的规则下标
+    rules: Vec<usize>,
+}
+
+/// 定长地址的二叉前缀树
+#[derive(Debug, Clone)]
+struct BitTrie {
+    /// 地址位数
+    width: u32,
+    /// 节点池，下标 0 为根节点
+    nodes: Vec<Node>,
+}
+
+impl BitTrie {
+    fn new(width: u32) -> Self {
+        Self {
+            width,
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// 第 `index` 位（从最高位开始）
+    fn bit(&self, value: u128, index: u32) -> usize {
+        ((value >> (self.width - 1 - index)) & 1) as usize
+    }
+
+    fn insert(&mut self, value: u128, prefix: u32, rule: usize) {
+        let mut node = 0;
+        for index in 0..prefix.min(self.width) {
+            let bit = self.bit(value, index);
+            node = match self.nodes[node].children[bit] {
+                Some(child) => child as usize,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children[bit] = Some(child as u32);
+                    child
+                }
+            };
+        }
+
+        let rules = &mut self.nodes[node].rules;
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+
+    /// 收集所有包含 `value` 的前缀上登记的规则
+    fn collect(&self, value: u128, out: &mut Vec<usize>) {
+        let mut node = 0;
+        for index in 0..=self.width {
+            out.extend_from_slice(&self.nodes[node].rules);
+            if index == self.width {
+                break;
+            }
+            match self.nodes[node].children[self.bit(value, index)] {
+                Some(child) => node = child as usize,
+                None => break,
+            }
+        }
+    }
+
+    /// 插入闭区间 `[start, end]`，拆分为最少数量的对齐前缀
+    fn insert_range(&mut self, start: u128, end: u128, rule: usize) {
+        let max = if self.width == 128 {
+            u128::MAX
+        } else {
+            (1u128 << self.width) - 1
+        };
+        let end = end.min(max);
+        let mut current = start;
+
+        while current <= end {
+            // 从 current 开始、不越过 end 的最大对齐块
+            let mut block_bits = current.trailing_zeros().min(self.width);
+            let last = loop {
+                let mask = if block_bits == 128 {
+                    u128::MAX
+                } else {
+                    (1u128 << block_bits) - 1
+                };
+                let last = current | mask;
+                if last <= end {
+                    break last;
+                }
+                block_bits -= 1;
+            };
+
+            self.insert(current, self.width - block_bits, rule);
+            if last == end {
+                break;
+            }
+            current = last + 1;
+        }
+    }
+}
+
+/// 按 IP 前缀索引的规则集合
+#[derive(Debug, Clone)]
+pub(crate) struct IpIndex {
+    v4: BitTrie,
+    v6: BitTrie,
+}
+
+impl Default for IpIndex {
+    fn default() -> Self {
+        Self {
+            v4: BitTrie::new(32),
+            v6: BitTrie::new(128),
+        }
+    }
+}
+
+impl IpIndex {
+    /// 为规则登记一个 IP 范围
+    pub(crate) fn insert(&mut self, range: &IpRange, rule: usize) {
+        match range {
+            IpRange::Single(IpAddr::V4(addr)) => self.v4.insert(u32::from(*addr) as u128, 32, rule),
+            IpRange::Single(IpAddr::V6(addr)) => self.v6.insert(u128::from(*addr), 128, rule),
+            IpRange::Ipv4Cidr { addr, prefix } => {
+                self.v4
+                    .insert(u32::from(*addr) as u128, u32::from(*prefix), rule)
+            }
+            IpRange::Ipv6Cidr { addr, prefix } => {
+                self.v6.insert(u128::from(*addr), u32::from(*prefix), rule)
+            }
+            IpRange::Ipv4Range { start, end } => {
+                self.v4
+                    .insert_range(u32::from(*start) as u128, u32::from(*end) as u128, rule)
+            }
+            IpRange::Ipv6Range { start, end } => {
+                self.v6
+                    .insert_range(u128::from(*start), u128::from(*end), rule)
+            }
+        }
+    }
+
+    /// 查询包含 `ip` 的所有规则下标（升序、去重）
+    pub(crate) fn lookup(&self, ip: &IpAddr) -> Vec<usize> {
+        let mut rules = Vec::new();
+        match ip {
+            IpAddr::V4(addr) => self.v4.collect(u32::from(*addr) as u128, &mut rules),
+            IpAddr::V6(addr) => self.v6.collect(u128::from(*addr), &mut rules),
+        }
+        rules.sort_unstable();
+        rules.dedup();
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_is_split_into_aligned_prefixes() {
+        let range: IpRange = "10.0.0.3-10.0.0.17".parse().unwrap();
+        let mut index = IpIndex::default();
+        index.insert(&range, 0);
+
+        for last_octet in 0..=20u8 {
+            let ip = IpAddr::from([10, 0, 0, last_octet]);
+            assert_eq!(
+                index.lookup(&ip).is_empty(),
+                !range.contains(&ip),
+                "ip {}",
+                ip
+            );
+        }
+        // 3, 4-7, 8-15, 16-17
+        assert_eq!(
+            index
+                .v4
+                .nodes
+                .iter()
+                .filter(|n| !n.rules.is_empty())
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_lookup_collects_nested_prefixes() {
+        let mut index = IpIndex::default();
+        index.insert(&"0.0.0.0/0".parse().unwrap(), 2);
+        index.insert(&"192.168.0.0/16".parse().unwrap(), 0);
+        index.insert(&"192.168.1.1".parse().unwrap(), 1);
+        index.insert(&"::/0".parse().unwrap(), 3);
+
+        assert_eq!(index.lookup(&"192.168.1.1".parse().unwrap()), vec![0, 1, 2]);
+        assert_eq!(index.lookup(&"192.168.2.1".parse().unwrap()), vec![0, 2]);
+        assert_eq!(index.lookup(&"8.8.8.8".parse().unwrap()), vec![2]);
+        assert_eq!(index.lookup(&"2001:db8::1".parse().unwrap()), vec![3]);
+    }
+}
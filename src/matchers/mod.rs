@@ -32,13 +32,22 @@ pub mod device;
 
 pub mod custom;
 
+#[cfg(feature = "cron")]
+pub mod cron;
+
+mod ip_trie;
+
 use crate::config::Matcher as ConfigMatcher;
 use crate::error::FlowGuardError;
 use ahash::AHashMap as HashMap;
+use ip_trie::IpIndex;
+use std::cell::RefCell;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
+use tracing::warn;
 
 // ============================================================================
 // 标识符提取器
@@ -47,6 +56,7 @@ use std::time::Instant;
 /// 标识符类型
 ///
 /// 支持多种标识符类型，用于限流和封禁的键。
+/// 内置类型之外的标识符（如租户 ID、会话 ID）使用 [`Identifier::Custom`]。
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Identifier {
     /// 用户ID
@@ -59,9 +69,38 @@ pub enum Identifier {
     ApiKey(String),
     /// 设备ID
     DeviceId(String),
+    /// 自定义类型的标识符
+    ///
+    /// 键名位于独立的 `custom:` 命名空间，不会与内置类型冲突；
+    /// 类型名称通过 [`Identifier::custom_kind`] 获取。
+    Custom {
+        /// 类型名称（如 `tenant`）
+        kind: String,
+        /// 标识符的值
+        value: String,
+    },
 }
 
 impl Identifier {
+    /// 创建自定义类型的标识符
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::Identifier;
+    ///
+    /// let tenant = Identifier::custom("tenant", "acme");
+    /// assert_eq!(tenant.type_name(), "custom");
+    /// assert_eq!(tenant.custom_kind(), Some("tenant"));
+    /// assert_eq!(tenant.key(), "custom:tenant:acme");
+    /// assert_ne!(tenant.key(), Identifier::UserId("acme".to_string()).key());
+    /// ```
+    pub fn custom(kind: impl Into<String>, value: impl Into<String>) -> Self {
+        Identifier::Custom {
+            kind: kind.into(),
+            value: value.into(),
+        }
+    }
+
     /// 获取标识符的字符串表示
     pub fn as_str(&self) -> &str {
         match self {
@@ -70,10 +109,11 @@ impl Identifier {
             Identifier::Mac(s) => s,
             Identifier::ApiKey(s) => s,
             Identifier::DeviceId(s) => s,
+            Identifier::Custom { value, .. } => value,
         }
     }
 
-    /// 获取标识符类型名称
+    /// 获取标识符类型名称，自定义标识符统一返回 `custom`
     pub fn type_name(&self) -> &'static str {
         match self {
             Identifier::UserId(_) => "user_id",
@@ -81,12 +121,39 @@ impl Identifier {
             Identifier::Mac(_) => "mac",
             Identifier::ApiKey(_) => "api_key",
             Identifier::DeviceId(_) => "device_id",
+            Identifier::Custom { .. } => "custom",
+        }
+    }
+
+    /// 自定义标识符的类型名称，内置类型返回 `None`
+    pub fn custom_kind(&self) -> Option<&str> {
+        match self {
+            Identifier::Custom { kind, .. } => Some(kind),
+            _ => None,
         }
     }
 
     /// 带类型前缀的键名
+    ///
+    /// 自定义标识符的键名为 `custom:{kind}:{value}`，与同值的内置类型互不冲突。
+    /// 类型名称中的 `%` 与 `:` 分别转义为 `%25` 与 `%3A`，
+    /// 因此不同的类型名称与值组合不会得到相同的键名。
     pub fn key(&self) -> String {
-        format!("{}:{}", self.type_name(), self.as_str())
+        match self {
+            Identifier::Custom { kind, value } => {
+                format!("custom:{}:{}", escape_custom_kind(kind), value)
+            }
+            _ => format!("{}:{}", self.type_name(), self.as_str()),
+        }
+    }
+}
+
+/// 转义自定义标识符类型名称中的分隔符
+fn escape_custom_kind(kind: &str) -> std::borrow::Cow<'_, str> {
+    if kind.contains(['%', ':']) {
+        std::borrow::Cow::Owned(kind.replace('%', "%25").replace(':', "%3A"))
+    } else {
+        std::borrow::Cow::Borrowed(kind)
     }
 }
 
@@ -115,33 +182,60 @@ pub struct RequestContext {
     pub client_ip: Option<String>,
     /// 查询参数
     pub query_params: HashMap<String, String>,
+    /// 请求体（原始字节）
+    pub body: Option<Arc<[u8]>>,
+    /// 请求体 JSON 解析缓存（由 [`JsonBodyExtractor`] 惰性填充）
+    pub json_body: JsonBodyCache,
+}
+
+/// 请求体 JSON 解析缓存
+///
+/// 同一请求上的多个 [`JsonBodyExtractor`] 共享一次解析结果，
+/// 克隆 [`RequestContext`] 时缓存随之共享。
+#[derive(Clone, Default)]
+pub struct JsonBodyCache(Arc<OnceLock<Option<serde_json::Value>>>);
+
+impl JsonBodyCache {
+    /// 获取解析结果，首次调用时解析 `body`
+    ///
+    /// 请求体为空或不是合法 JSON 时返回 `None`。
+    fn get_or_parse(&self, body: Option<&[u8]>) -> Option<&serde_json::Value> {
+        self.0
+            .get_or_init(|| body.and_then(|bytes| serde_json::from_slice(bytes).ok()))
+            .as_ref()
+    }
+}
+
+impl std::fmt::Debug for JsonBodyCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self.0.get() {
+            None => "unparsed",
+            Some(Some(_)) => "parsed",
+            Some(None) => "invalid",
+        };
+        f.debug_tuple("JsonBodyCache").field(&state).finish()
+    }
 }
 
 impl std::fmt::Debug for RequestContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = crate::log_redaction::redaction_policy();
         let mut debug = f.debug_struct("RequestContext");
         debug
             .field("user_id", &self.user_id)
             .field("ip", &self.ip)
             .field("mac", &self.mac)
             .field("device_id", &self.device_id)
-            .field("api_key", &self.api_key.as_ref().map(|_| "***"));
+            .field(
+                "api_key",
+                &self.api_key.as_deref().map(|key| policy.mask(key)),
+            );
 
-        // 脱敏 headers
+        // 按脱敏策略脱敏 headers
         let headers: HashMap<String, String> = self
             .headers
             .iter()
-            .map(|(k, v)| {
-                let v = if k.to_lowercase().contains("auth")
-                    || k.to_lowercase().contains("cookie")
-                    || k.to_lowercase().contains("key")
-                {
-                    "***".to_string()
-                } else {
-                    v.clone()
-                };
-                (k.clone(), v)
-            })
+            .map(|(k, v)| (k.clone(), policy.redact(k, v)))
             .collect();
         debug.field("headers", &headers);
 
@@ -150,24 +244,17 @@ impl std::fmt::Debug for RequestContext {
             .field("method", &self.method)
             .field("client_ip", &self.client_ip);
 
-        // 脱敏 query_params
+        // 按脱敏策略脱敏 query_params
         let query_params: HashMap<String, String> = self
             .query_params
             .iter()
-            .map(|(k, v)| {
-                let v = if k.to_lowercase().contains("token")
-                    || k.to_lowercase().contains("key")
-                    || k.to_lowercase().contains("secret")
-                {
-                    "***".to_string()
-                } else {
-                    v.clone()
-                };
-                (k.clone(), v)
-            })
+            .map(|(k, v)| (k.clone(), policy.redact(k, v)))
             .collect();
         debug.field("query_params", &query_params);
 
+        // 请求体可能包含敏感数据，仅输出长度
+        debug.field("body_len", &self.body.as_ref().map(|body| body.len()));
+
         debug.finish()
     }
 }
@@ -186,6 +273,8 @@ impl RequestContext {
             method: String::new(),
             client_ip: None,
             query_params: HashMap::new(),
+            body: None,
+            json_body: JsonBodyCache::default(),
         }
     }
 
@@ -213,6 +302,28 @@ impl RequestContext {
         self
     }
 
+    /// 设置 HTTP 方法
+    pub fn with_method(mut self, method: &str) -> Self {
+        self.method = method.to_string();
+        self
+    }
+
+    /// 设置请求体
+    ///
+    /// 同时重置 JSON 解析缓存。
+    pub fn with_body(mut self, body: impl Into<Arc<[u8]>>) -> Self {
+        self.body = Some(body.into());
+        self.json_body = JsonBodyCache::default();
+        self
+    }
+
+    /// 以 JSON 形式获取请求体（惰性解析并缓存）
+    ///
+    /// 请求体缺失或不是合法 JSON 时返回 `None`。
+    pub fn json_body(&self) -> Option<&serde_json::Value> {
+        self.json_body.get_or_parse(self.body.as_deref())
+    }
+
     /// 获取HTTP头（不区分大小写）
     pub fn get_header(&self, key: &str) -> Option<&String> {
         self.headers.get(&key.to_lowercase())
@@ -243,6 +354,75 @@ pub trait IdentifierExtractor: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// 请求头取值解析函数
+///
+/// 输入原始请求头的值，返回其中的标识符；返回 `None` 表示该请求头中没有可用的值。
+pub type HeaderValueParser = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// 解析 RFC 7239 `Forwarded` 请求头中的 `for=` 地址
+///
+/// 多个转发节点的地址按出现顺序以逗号连接，与 X-Forwarded-For 的格式一致，
+/// 可直接交给 [`IpExtractor`] 的可信代理逻辑从右向左解析。
+/// 去除引号、IPv6 的方括号和端口；`unknown` 与混淆标识（`_` 开头）的节点保留为 `unknown`，
+/// 作为不可信的跳点参与解析，不会被跳过而把更左侧可伪造的地址当作客户端。
+/// 所有节点都没有可用地址时返回 `None`。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::matchers::parse_forwarded_for;
+///
+/// assert_eq!(
+///     parse_forwarded_for("for=192.0.2.60;proto=http;by=203.0.113.43").as_deref(),
+///     Some("192.0.2.60")
+/// );
+/// assert_eq!(
+///     parse_forwarded_for(r#"for="[2001:db8:cafe::17]:4711", for=198.51.100.17"#).as_deref(),
+///     Some("2001:db8:cafe::17, 198.51.100.17")
+/// );
+/// assert_eq!(
+///     parse_forwarded_for("for=192.0.2.60, for=_hidden").as_deref(),
+///     Some("192.0.2.60, unknown")
+/// );
+/// ```
+pub fn parse_forwarded_for(raw: &str) -> Option<String> {
+    let addresses: Vec<Option<&str>> = raw
+        .split(',')
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| value.trim().trim_matches('"'))
+            })
+        })
+        .filter_map(|node| {
+            let addr = match node.strip_prefix('[') {
+                // [IPv6]:port
+                Some(rest) => rest.split(']').next()?,
+                // IPv4:port；不含方括号的裸 IPv6 原样保留
+                None => match node.split_once(':') {
+                    Some((host, port)) if !port.contains(':') => host,
+                    _ => node,
+                },
+            };
+            let hidden =
+                addr.is_empty() || addr.eq_ignore_ascii_case("unknown") || addr.starts_with('_');
+            Some((!hidden).then_some(addr))
+        })
+        .collect();
+
+    if addresses.iter().all(Option::is_none) {
+        return None;
+    }
+    Some(
+        addresses
+            .iter()
+            .map(|addr| addr.unwrap_or("unknown"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
 // ============================================================================
 // 用户ID提取器
 // ============================================================================
@@ -257,6 +437,8 @@ pub struct UserIdExtractor {
     query_param_name: Option<String>,
     /// 默认用户ID（当无法提取时使用）
     default_user_id: Option<String>,
+    /// 请求头取值解析函数
+    header_parser: Option<HeaderValueParser>,
 }
 
 impl UserIdExtractor {
@@ -275,6 +457,7 @@ impl UserIdExtractor {
             header_name,
             query_param_name,
             default_user_id,
+            header_parser: None,
         }
     }
 
@@ -293,6 +476,34 @@ impl UserIdExtractor {
         Self::new(Some(header_name.to_string()), None, None)
     }
 
+    /// 从HTTP头提取用户ID，并用 `parser` 从原始值中取出用户ID
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::{Identifier, IdentifierExtractor, RequestContext, UserIdExtractor};
+    ///
+    /// // Authorization: Token user=alice,scope=read
+    /// let extractor = UserIdExtractor::from_header_with_parser("Authorization", |raw| {
+    ///     raw.split(|c| c == ' ' || c == ',')
+    ///         .find_map(|field| field.strip_prefix("user="))
+    ///         .map(str::to_string)
+    /// });
+    /// let context =
+    ///     RequestContext::new().with_header("Authorization", "Token user=alice,scope=read");
+    /// assert_eq!(
+    ///     extractor.extract(&context),
+    ///     Some(Identifier::UserId("alice".to_string()))
+    /// );
+    /// ```
+    pub fn from_header_with_parser<F>(header_name: &str, parser: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        let mut extractor = Self::from_header(header_name);
+        extractor.header_parser = Some(Arc::new(parser));
+        extractor
+    }
+
     /// 从查询参数提取用户ID（便捷方法）
     ///
     /// # 参数
@@ -322,9 +533,13 @@ impl IdentifierExtractor for UserIdExtractor {
     fn extract(&self, context: &RequestContext) -> Option<Identifier> {
         // 优先从HTTP头提取
         if let Some(header_name) = &self.header_name {
-            if let Some(user_id) = context.get_header(header_name) {
-                if !user_id.is_empty() {
-                    return Some(Identifier::UserId(user_id.clone()));
+            if let Some(value) = context.get_header(header_name) {
+                let user_id = match &self.header_parser {
+                    Some(parser) => parser(value),
+                    None => Some(value.clone()),
+                };
+                if let Some(user_id) = user_id.filter(|user_id| !user_id.is_empty()) {
+                    return Some(Identifier::UserId(user_id));
                 }
             }
         }
@@ -358,11 +573,19 @@ impl IdentifierExtractor for UserIdExtractor {
 /// IP提取器
 ///
 /// 从请求上下文中提取IP地址，支持从多个HTTP头中提取真实IP。
+/// 配置可信代理（[`IpExtractor::with_trusted_proxies`]）后，
+/// X-Forwarded-For 链从右向左解析，跳过可信代理，返回第一个不可信的地址。
 pub struct IpExtractor {
     /// HTTP头名称列表（按优先级顺序）
     header_names: Vec<String>,
     /// 是否验证IP格式
     validate: bool,
+    /// 可信代理地址范围
+    trusted_proxies: Vec<IpRange>,
+    /// 是否已经输出过"未配置可信代理"的警告
+    untrusted_warned: AtomicBool,
+    /// 请求头取值解析函数
+    header_parser: Option<HeaderValueParser>,
 }
 
 impl IpExtractor {
@@ -375,9 +598,37 @@ impl IpExtractor {
         Self {
             header_names,
             validate,
+            trusted_proxies: Vec::new(),
+            untrusted_warned: AtomicBool::new(false),
+            header_parser: None,
         }
     }
 
+    /// 设置可信代理地址范围
+    ///
+    /// 配置后：
+    /// - 直连对端（`client_ip`）不是可信代理时，忽略转发头，直接使用对端地址
+    /// - X-Forwarded-For 链从右向左解析，跳过可信代理，返回第一个不可信的地址
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::IpExtractor;
+    ///
+    /// let extractor = IpExtractor::from_header("X-Forwarded-For").with_trusted_proxies(vec![
+    ///     "10.0.0.0/8".parse().unwrap(),
+    ///     "2001:db8::/32".parse().unwrap(),
+    /// ]);
+    /// ```
+    pub fn with_trusted_proxies(mut self, trusted_proxies: Vec<IpRange>) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// 检查地址是否属于可信代理
+    fn is_trusted_proxy(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|range| range.contains(ip))
+    }
+
     /// 创建默认的IP提取器（从Remote Addr提取）
     ///
     /// # 示例
@@ -405,6 +656,33 @@ impl IpExtractor {
         Self::new(vec![header_name.to_string()], true)
     }
 
+    /// 创建从指定HTTP头提取的IP提取器，并用 `parser` 从原始值中取出地址
+    ///
+    /// `parser` 的结果仍按 X-Forwarded-For 格式解析，可以返回逗号分隔的地址链，
+    /// 配合 [`with_trusted_proxies`](Self::with_trusted_proxies) 跳过可信代理。
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::{
+    ///     parse_forwarded_for, Identifier, IdentifierExtractor, IpExtractor, RequestContext,
+    /// };
+    ///
+    /// let extractor = IpExtractor::from_header_with_parser("Forwarded", parse_forwarded_for);
+    /// let context = RequestContext::new().with_header("Forwarded", "for=192.0.2.60;proto=http");
+    /// assert_eq!(
+    ///     extractor.extract(&context),
+    ///     Some(Identifier::Ip("192.0.2.60".to_string()))
+    /// );
+    /// ```
+    pub fn from_header_with_parser<F>(header_name: &str, parser: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        let mut extractor = Self::from_header(header_name);
+        extractor.header_parser = Some(Arc::new(parser));
+        extractor
+    }
+
     /// 创建从多个HTTP头提取的IP提取器（按优先级顺序）
     ///
     /// # 参数
@@ -431,6 +709,7 @@ impl IpExtractor {
     /// # 安全说明
     /// X-Forwarded-For 头可能被客户端伪造，因此不能直接信任第一个 IP。
     /// 正确的做法是从右向左查找，跳过已知的可信代理。
+    /// 未配置可信代理时保持旧行为（取最左边的 IP），并输出一次警告。
     ///
     /// # 参数
     /// - `value`: IP 地址或 IP 列表字符串
@@ -440,9 +719,7 @@ impl IpExtractor {
     /// - `None`: 无法解析或验证失败
     fn parse_ip(&self, value: &str) -> Option<String> {
         // 处理IP列表（X-Forwarded-For格式：client, proxy1, proxy2）
-        // 注意：真实客户端IP在最左边，代理依次向右追加
-        // 攻击者可以通过在左边添加伪造IP来欺骗
-        // 因此：取最左边的IP作为客户端IP（因为它是最早由第一个代理添加的）
+        // 真实客户端IP在最左边，代理依次向右追加；攻击者可以在左边添加伪造IP
         let ips: Vec<&str> = value
             .split(',')
             .map(|s| s.trim())
@@ -462,11 +739,24 @@ impl IpExtractor {
             return Some(ip.to_string());
         }
 
-        // 多个IP时，取最左边的IP作为客户端IP
-        // 这是安全的，因为第一个代理会将自己的IP追加到右边
-        // 攻击者伪造的IP会在最左边，但如果我们信任第一个代理，
-        // 它会追加自己的IP，所以左边第二个IP开始是可信的
-        // 简化处理：使用最左边的IP（假设第一个代理是可信的）
+        if !self.trusted_proxies.is_empty() {
+            // 从右向左跳过可信代理，第一个不可信的地址即为真实客户端
+            for ip in ips.iter().rev() {
+                match ip.parse::<IpAddr>() {
+                    Ok(addr) if self.is_trusted_proxy(&addr) => continue,
+                    Ok(_) => return Some(ip.to_string()),
+                    // 不可信的跳点格式错误，无法确定客户端
+                    Err(_) if self.validate => return None,
+                    Err(_) => return Some(ip.to_string()),
+                }
+            }
+            // 整条链都是可信代理时，最左边的地址就是客户端
+        } else if !self.untrusted_warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                "IpExtractor 未配置可信代理，X-Forwarded-For 将取最左边的 IP，该值可能被客户端伪造"
+            );
+        }
+
         let ip = ips[0];
 
         // 验证IP格式
@@ -480,10 +770,22 @@ impl IpExtractor {
 
 impl IdentifierExtractor for IpExtractor {
     fn extract(&self, context: &RequestContext) -> Option<Identifier> {
+        // 直连对端不是可信代理时，转发头可能被伪造，直接使用对端地址
+        let peer_untrusted = !self.trusted_proxies.is_empty()
+            && context
+                .client_ip
+                .as_deref()
+                .and_then(|ip| ip.parse::<IpAddr>().ok())
+                .is_some_and(|ip| !self.is_trusted_proxy(&ip));
+
         // 从HTTP头列表中提取
-        for header_name in &self.header_names {
+        for header_name in self.header_names.iter().filter(|_| !peer_untrusted) {
             if let Some(value) = context.get_header(header_name) {
-                if let Some(ip) = self.parse_ip(value) {
+                let parsed = match &self.header_parser {
+                    Some(parser) => parser(value),
+                    None => Some(value.clone()),
+                };
+                if let Some(ip) = parsed.and_then(|value| self.parse_ip(&value)) {
                     return Some(Identifier::Ip(ip));
                 }
             }
@@ -826,6 +1128,184 @@ impl IdentifierExtractor for DeviceIdExtractor {
     }
 }
 
+// ============================================================================
+// JSON 请求体提取器
+// ============================================================================
+
+/// JSON 请求体提取器
+///
+/// 按 JSON Pointer（RFC 6901，如 `/auth/user_id`）从请求体中读取标识符，
+/// 适用于 GraphQL、RPC-over-POST 等将租户或用户放在请求体中的场景。
+/// 请求体只在首次提取时解析一次，结果缓存在 [`RequestContext`] 上。
+pub struct JsonBodyExtractor<F>
+where
+    F: Fn(String) -> Identifier + Send + Sync,
+{
+    /// JSON Pointer 路径
+    pointer: String,
+    /// 标识符构造函数
+    constructor: F,
+}
+
+impl<F> JsonBodyExtractor<F>
+where
+    F: Fn(String) -> Identifier + Send + Sync,
+{
+    /// 创建新的JSON请求体提取器
+    ///
+    /// # 参数
+    /// - `pointer`: JSON Pointer 路径
+    /// - `constructor`: 将提取到的值转换为标识符
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::{Identifier, IdentifierExtractor, JsonBodyExtractor, RequestContext};
+    ///
+    /// let extractor = JsonBodyExtractor::new("/auth/user_id", Identifier::UserId);
+    /// let context = RequestContext::new().with_body(&br#"{"auth":{"user_id":"u1"}}"#[..]);
+    ///
+    /// assert_eq!(extractor.extract(&context), Some(Identifier::UserId("u1".to_string())));
+    /// ```
+    pub fn new(pointer: &str, constructor: F) -> Self {
+        Self {
+            pointer: pointer.to_string(),
+            constructor,
+        }
+    }
+}
+
+impl<F> IdentifierExtractor for JsonBodyExtractor<F>
+where
+    F: Fn(String) -> Identifier + Send + Sync,
+{
+    fn extract(&self, context: &RequestContext) -> Option<Identifier> {
+        let value = match context.json_body()?.pointer(&self.pointer)? {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            // null、对象、数组不能作为标识符
+            _ => return None,
+        };
+
+        if value.is_empty() {
+            return None;
+        }
+
+        Some((self.constructor)(value))
+    }
+
+    fn name(&self) -> &str {
+        "JsonBodyExtractor"
+    }
+}
+
+// ============================================================================
+// Cookie提取器
+// ============================================================================
+
+/// Cookie提取器
+///
+/// 解析 `Cookie` 头中的键值对，按名称读取标识符。
+/// 支持带双引号的值以及百分号编码。
+pub struct CookieExtractor<F>
+where
+    F: Fn(String) -> Identifier + Send + Sync,
+{
+    /// Cookie 名称
+    cookie_name: String,
+    /// 标识符构造函数
+    constructor: F,
+}
+
+impl<F> CookieExtractor<F>
+where
+    F: Fn(String) -> Identifier + Send + Sync,
+{
+    /// 创建新的Cookie提取器
+    ///
+    /// # 参数
+    /// - `cookie_name`: Cookie 名称（区分大小写）
+    /// - `constructor`: 将 Cookie 值转换为标识符
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::{CookieExtractor, Identifier, IdentifierExtractor, RequestContext};
+    ///
+    /// let extractor = CookieExtractor::new("session", Identifier::UserId);
+    /// let context = RequestContext::new().with_header("Cookie", "theme=dark; session=abc123");
+    ///
+    /// assert_eq!(extractor.extract(&context), Some(Identifier::UserId("abc123".to_string())));
+    /// ```
+    pub fn new(cookie_name: &str, constructor: F) -> Self {
+        Self {
+            cookie_name: cookie_name.to_string(),
+            constructor,
+        }
+    }
+
+    /// 从 Cookie 头中查找指定名称的值
+    fn find_cookie<'a>(&self, header: &'a str) -> Option<&'a str> {
+        header.split(';').find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name.trim() == self.cookie_name).then(|| value.trim())
+        })
+    }
+}
+
+/// 百分号解码
+///
+/// 非法的转义序列按原样保留；解码结果不是合法 UTF-8 时返回 `None`。
+fn percent_decode(value: &str) -> Option<String> {
+    if !value.contains('%') {
+        return Some(value.to_string());
+    }
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+impl<F> IdentifierExtractor for CookieExtractor<F>
+where
+    F: Fn(String) -> Identifier + Send + Sync,
+{
+    fn extract(&self, context: &RequestContext) -> Option<Identifier> {
+        let header = context.get_header("Cookie")?;
+        let raw = self.find_cookie(header)?;
+
+        // 去掉成对的双引号
+        let raw = raw
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(raw);
+
+        let value = percent_decode(raw)?;
+        if value.is_empty() {
+            return None;
+        }
+
+        Some((self.constructor)(value))
+    }
+
+    fn name(&self) -> &str {
+        "CookieExtractor"
+    }
+}
+
 // ============================================================================
 // 组合提取器
 // ============================================================================
@@ -980,6 +1460,188 @@ where
 // 规则匹配引擎
 // ============================================================================
 
+/// 预编译的用户ID模式集合
+///
+/// 每个模式可以是精确的用户ID、单独的 `*`（匹配任意用户，包括未提供用户ID的请求），
+/// 或包含 `*` 通配符的 glob 模式（如 `tenant-*`、`*-admin`、`org-*-ops`）。
+/// 精确ID存入哈希集合，glob 模式在构造时拆分为字面量片段，匹配时无需再解析。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::matchers::UserPatterns;
+///
+/// let patterns = UserPatterns::new(vec!["tenant-*".to_string(), "*-admin".to_string()]);
+/// assert!(patterns.matches("tenant-42"));
+/// assert!(patterns.matches("ops-admin"));
+/// assert!(!patterns.matches("Tenant-42"));
+///
+/// let patterns = UserPatterns::case_insensitive(vec!["tenant-*".to_string()]);
+/// assert!(patterns.matches("TENANT-42"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserPatterns {
+    /// 原始模式
+    patterns: Vec<String>,
+    /// 是否忽略大小写
+    case_insensitive: bool,
+    /// 是否包含 `*`
+    any: bool,
+    /// 精确匹配的用户ID
+    exact: ahash::AHashSet<String>,
+    /// glob 模式按 `*` 拆分后的片段
+    globs: Vec<Vec<String>>,
+}
+
+impl UserPatterns {
+    /// 创建区分大小写的模式集合
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self::compile(patterns, false)
+    }
+
+    /// 创建忽略大小写的模式集合
+    pub fn case_insensitive(patterns: Vec<String>) -> Self {
+        Self::compile(patterns, true)
+    }
+
+    fn compile(patterns: Vec<String>, case_insensitive: bool) -> Self {
+        let mut any = false;
+        let mut exact = ahash::AHashSet::new();
+        let mut globs = Vec::new();
+
+        for pattern in &patterns {
+            let pattern = if case_insensitive {
+                pattern.to_lowercase()
+            } else {
+                pattern.clone()
+            };
+            if pattern == "*" {
+                any = true;
+            } else if pattern.contains('*') {
+                globs.push(pattern.split('*').map(str::to_string).collect());
+            } else {
+                exact.insert(pattern);
+            }
+        }
+
+        Self {
+            patterns,
+            case_insensitive,
+            any,
+            exact,
+            globs,
+        }
+    }
+
+    /// 原始模式列表
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// 是否忽略大小写
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// 是否包含匹配任意用户的 `*`
+    pub fn matches_any(&self) -> bool {
+        self.any
+    }
+
+    /// 检查用户ID是否匹配任一模式
+    pub fn matches(&self, user_id: &str) -> bool {
+        if self.any {
+            return true;
+        }
+
+        let lowered;
+        let user_id = if self.case_insensitive {
+            lowered = user_id.to_lowercase();
+            lowered.as_str()
+        } else {
+            user_id
+        };
+
+        self.exact.contains(user_id)
+            || self
+                .globs
+                .iter()
+                .any(|segments| glob_matches(segments, user_id))
+    }
+}
+
+impl From<Vec<String>> for UserPatterns {
+    fn from(patterns: Vec<String>) -> Self {
+        Self::new(patterns)
+    }
+}
+
+/// 按 `*` 拆分后的片段依次匹配：首段为前缀，末段为后缀，中间段按顺序出现
+fn glob_matches(segments: &[String], value: &str) -> bool {
+    let (first, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => return false,
+    };
+    let Some(mut remaining) = value.strip_prefix(first.as_str()) else {
+        return false;
+    };
+    let Some((last, middle)) = rest.split_last() else {
+        return remaining.is_empty();
+    };
+
+    for segment in middle {
+        match remaining.find(segment.as_str()) {
+            Some(pos) => remaining = &remaining[pos + segment.len()..],
+            None => return false,
+        }
+    }
+
+    remaining.ends_with(last.as_str())
+}
+
+/// 可在运行时整体替换的 IP 范围列表
+///
+/// 克隆共享同一份列表；[`store`](Self::store) 原子地替换为新列表，正在评估的请求
+/// 继续使用替换前的快照。通常由 `IpListWatcher` 从文件热加载，
+/// 并通过 [`MatchCondition::IpList`] 作为拒绝（或放行）条件使用。
+#[derive(Debug, Clone, Default)]
+pub struct SharedIpList {
+    ranges: Arc<parking_lot::RwLock<Arc<Vec<IpRange>>>>,
+}
+
+impl SharedIpList {
+    /// 以初始范围创建列表
+    pub fn new(ranges: Vec<IpRange>) -> Self {
+        Self {
+            ranges: Arc::new(parking_lot::RwLock::new(Arc::new(ranges))),
+        }
+    }
+
+    /// 当前列表快照
+    pub fn load(&self) -> Arc<Vec<IpRange>> {
+        self.ranges.read().clone()
+    }
+
+    /// 替换为新列表
+    pub fn store(&self, ranges: Vec<IpRange>) {
+        *self.ranges.write() = Arc::new(ranges);
+    }
+
+    /// 当前范围数量
+    pub fn len(&self) -> usize {
+        self.ranges.read().len()
+    }
+
+    /// 列表是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// IP 是否在当前列表中
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.load().iter().any(|range| range.contains(ip))
+    }
+}
+
 /// 匹配条件
 ///
 /// 定义单个匹配条件。
@@ -987,14 +1649,31 @@ where
 pub enum MatchCondition {
     /// 用户ID匹配
     User(Vec<String>),
+    /// 用户ID模式匹配（支持 `*` 与 glob 模式，可忽略大小写，见 [`UserPatterns`]）
+    UserPattern(UserPatterns),
     /// IP范围匹配
     Ip(Vec<IpRange>),
+    /// 可热更新的 IP 列表匹配（如从文件加载的拒绝列表）
+    IpList(SharedIpList),
     /// 地理位置匹配
     Geo(Vec<String>),
     /// API版本匹配
     ApiVersion(Vec<String>),
     /// 设备类型匹配
     Device(Vec<String>),
+    /// HTTP方法匹配（忽略大小写）
+    Method(Vec<String>),
+    /// 请求路径正则匹配
+    #[cfg(feature = "regex")]
+    PathRegex(regex::Regex),
+    /// HTTP头正则匹配（HTTP头缺失时不匹配）
+    #[cfg(feature = "regex")]
+    HeaderRegex {
+        /// HTTP头名称
+        name: String,
+        /// 正则表达式
+        pattern: regex::Regex,
+    },
     /// 自定义匹配
     Custom(Arc<dyn Fn(&RequestContext) -> bool + Send + Sync>),
 }
@@ -1003,7 +1682,12 @@ impl std::fmt::Debug for MatchCondition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             MatchCondition::User(ids) => f.debug_tuple("User").field(ids).finish(),
+            MatchCondition::UserPattern(patterns) => f
+                .debug_tuple("UserPattern")
+                .field(&patterns.patterns())
+                .finish(),
             MatchCondition::Ip(ranges) => f.debug_tuple("Ip").field(&ranges.len()).finish(),
+            MatchCondition::IpList(list) => f.debug_tuple("IpList").field(&list.len()).finish(),
             MatchCondition::Geo(countries) => f.debug_tuple("Geo").field(countries).finish(),
             MatchCondition::ApiVersion(versions) => {
                 f.debug_tuple("ApiVersion").field(versions).finish()
@@ -1011,22 +1695,100 @@ impl std::fmt::Debug for MatchCondition {
             MatchCondition::Device(device_types) => {
                 f.debug_tuple("Device").field(device_types).finish()
             }
+            MatchCondition::Method(methods) => f.debug_tuple("Method").field(methods).finish(),
+            #[cfg(feature = "regex")]
+            MatchCondition::PathRegex(pattern) => {
+                f.debug_tuple("PathRegex").field(&pattern.as_str()).finish()
+            }
+            #[cfg(feature = "regex")]
+            MatchCondition::HeaderRegex { name, pattern } => f
+                .debug_struct("HeaderRegex")
+                .field("name", name)
+                .field("pattern", &pattern.as_str())
+                .finish(),
             MatchCondition::Custom(_) => f.debug_tuple("Custom").field(&"<closure>").finish(),
         }
     }
 }
 
-/// IP范围
-#[derive(Debug, Clone)]
-pub enum IpRange {
-    /// 单个IP
-    Single(IpAddr),
-    /// IPv4 CIDR
-    Ipv4Cidr { addr: Ipv4Addr, prefix: u8 },
-    /// IPv6 CIDR
-    Ipv6Cidr { addr: Ipv6Addr, prefix: u8 },
+impl MatchCondition {
+    /// 创建用户ID模式匹配条件（区分大小写）
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::MatchCondition;
+    ///
+    /// let condition =
+    ///     MatchCondition::user_pattern(vec!["tenant-*".to_string(), "root".to_string()]);
+    /// ```
+    pub fn user_pattern(patterns: Vec<String>) -> Self {
+        MatchCondition::UserPattern(UserPatterns::new(patterns))
+    }
+
+    /// 创建忽略大小写的用户ID模式匹配条件
+    pub fn user_pattern_case_insensitive(patterns: Vec<String>) -> Self {
+        MatchCondition::UserPattern(UserPatterns::case_insensitive(patterns))
+    }
+
+    /// 根据配置中的 `UserPattern` 匹配器创建条件
+    pub(crate) fn from_config_user_pattern(patterns: &[String], case_insensitive: bool) -> Self {
+        if case_insensitive {
+            Self::user_pattern_case_insensitive(patterns.to_vec())
+        } else {
+            Self::user_pattern(patterns.to_vec())
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+impl MatchCondition {
+    /// 创建请求路径正则匹配条件
+    ///
+    /// # 错误
+    /// - `FlowGuardError::ConfigError`: 正则表达式无效
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::MatchCondition;
+    ///
+    /// let condition = MatchCondition::path_regex("^/admin/.*").unwrap();
+    /// ```
+    pub fn path_regex(pattern: &str) -> Result<Self, FlowGuardError> {
+        Ok(MatchCondition::PathRegex(compile_regex(pattern)?))
+    }
+
+    /// 创建HTTP头正则匹配条件
+    ///
+    /// # 错误
+    /// - `FlowGuardError::ConfigError`: 正则表达式无效
+    pub fn header_regex(name: &str, pattern: &str) -> Result<Self, FlowGuardError> {
+        Ok(MatchCondition::HeaderRegex {
+            name: name.to_string(),
+            pattern: compile_regex(pattern)?,
+        })
+    }
+}
+
+/// 编译正则表达式，错误转换为配置错误
+#[cfg(feature = "regex")]
+fn compile_regex(pattern: &str) -> Result<regex::Regex, FlowGuardError> {
+    regex::Regex::new(pattern)
+        .map_err(|e| FlowGuardError::ConfigError(format!("无效的正则表达式 '{}': {}", pattern, e)))
+}
+
+/// IP范围
+#[derive(Debug, Clone)]
+pub enum IpRange {
+    /// 单个IP
+    Single(IpAddr),
+    /// IPv4 CIDR
+    Ipv4Cidr { addr: Ipv4Addr, prefix: u8 },
+    /// IPv6 CIDR
+    Ipv6Cidr { addr: Ipv6Addr, prefix: u8 },
     /// IPv4范围
     Ipv4Range { start: Ipv4Addr, end: Ipv4Addr },
+    /// IPv6范围
+    Ipv6Range { start: Ipv6Addr, end: Ipv6Addr },
 }
 
 impl IpRange {
@@ -1055,6 +1817,14 @@ impl IpRange {
                     false
                 }
             }
+            IpRange::Ipv6Range { start, end } => {
+                if let IpAddr::V6(ipv6) = ip {
+                    let value = u128::from(*ipv6);
+                    value >= u128::from(*start) && value <= u128::from(*end)
+                } else {
+                    false
+                }
+            }
         }
     }
 
@@ -1140,31 +1910,45 @@ impl FromStr for IpRange {
                     Ok(IpRange::Ipv6Cidr { addr: ipv6, prefix })
                 }
             }
-        } else if s.contains('-') {
-            // 范围格式
-            let parts: Vec<&str> = s.split('-').collect();
-            if parts.len() != 2 {
+        } else if let Some((start_str, end_str)) = s.rsplit_once('-') {
+            // 范围格式：IP地址本身不含 '-'，按最后一个 '-' 拆分；IPv6 地址可以用 [] 包裹
+            let strip = |part: &str| {
+                let part = part.trim();
+                part.strip_prefix('[')
+                    .and_then(|p| p.strip_suffix(']'))
+                    .unwrap_or(part)
+                    .to_string()
+            };
+            let (start_str, end_str) = (strip(start_str), strip(end_str));
+            if start_str.contains('-') {
                 return Err(FlowGuardError::ConfigError(format!(
                     "无效的IP范围格式: {}",
                     s
                 )));
             }
 
-            let start: Ipv4Addr = parts[0]
+            let start: IpAddr = start_str
                 .parse()
-                .map_err(|_| FlowGuardError::ConfigError(format!("无效的起始IP: {}", parts[0])))?;
-            let end: Ipv4Addr = parts[1]
+                .map_err(|_| FlowGuardError::ConfigError(format!("无效的起始IP: {}", start_str)))?;
+            let end: IpAddr = end_str
                 .parse()
-                .map_err(|_| FlowGuardError::ConfigError(format!("无效的结束IP: {}", parts[1])))?;
+                .map_err(|_| FlowGuardError::ConfigError(format!("无效的结束IP: {}", end_str)))?;
 
             if start > end {
                 return Err(FlowGuardError::ConfigError(format!(
                     "起始IP不能大于结束IP: {} - {}",
-                    parts[0], parts[1]
+                    start_str, end_str
                 )));
             }
 
-            Ok(IpRange::Ipv4Range { start, end })
+            match (start, end) {
+                (IpAddr::V4(start), IpAddr::V4(end)) => Ok(IpRange::Ipv4Range { start, end }),
+                (IpAddr::V6(start), IpAddr::V6(end)) => Ok(IpRange::Ipv6Range { start, end }),
+                _ => Err(FlowGuardError::ConfigError(format!(
+                    "IP范围的起止地址必须属于同一地址族: {}",
+                    s
+                ))),
+            }
         } else {
             // 单个IP
             let addr: IpAddr = s
@@ -1188,10 +1972,11 @@ pub enum LogicalOperator {
 
 /// 复合条件
 ///
-/// 支持AND/OR/NOT逻辑操作。
+/// 支持AND/OR/NOT逻辑操作。子条件以 `Arc` 共享，克隆开销很小。
+#[derive(Clone)]
 pub struct CompositeCondition {
     /// 子条件列表
-    pub conditions: Vec<Box<dyn ConditionEvaluator>>,
+    pub conditions: Vec<Arc<dyn ConditionEvaluator>>,
     /// 逻辑操作符
     pub operator: LogicalOperator,
 }
@@ -1205,22 +1990,46 @@ impl std::fmt::Debug for CompositeCondition {
     }
 }
 
-impl Clone for CompositeCondition {
-    fn clone(&self) -> Self {
-        // 由于 ConditionEvaluator 不能 Clone，我们创建一个新的 CompositeCondition
-        // 实际使用时，用户需要重新构建条件
-        Self {
-            conditions: self
-                .conditions
-                .iter()
-                .map(|_| {
-                    // 由于无法克隆 trait 对象，这里返回一个占位符
-                    // 实际使用中，需要重新构建条件
-                    Box::new(MatchCondition::User(vec![])) as Box<dyn ConditionEvaluator>
-                })
-                .collect(),
-            operator: self.operator,
+/// 单次请求的条件评估缓存
+///
+/// 以条件的 `Arc` 地址为键记录评估结果：嵌套的 AND/OR 树或多条规则共享同一个子条件时，
+/// 该条件在一次请求评估中只执行一次。缓存只应在一次请求评估期间存在，
+/// 不可跨请求复用。
+#[derive(Debug, Default)]
+pub struct EvaluationCache {
+    results: RefCell<HashMap<usize, bool>>,
+}
+
+impl EvaluationCache {
+    /// 创建空缓存
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 评估条件，已评估过的条件直接返回缓存结果
+    pub fn evaluate(
+        &self,
+        condition: &Arc<dyn ConditionEvaluator>,
+        context: &RequestContext,
+    ) -> bool {
+        let key = Arc::as_ptr(condition) as *const () as usize;
+        if let Some(&result) = self.results.borrow().get(&key) {
+            return result;
         }
+
+        let result = condition.evaluate_with_cache(context, self);
+        self.results.borrow_mut().insert(key, result);
+        result
+    }
+
+    /// 已缓存的条件数量
+    pub fn len(&self) -> usize {
+        self.results.borrow().len()
+    }
+
+    /// 是否没有缓存任何条件
+    pub fn is_empty(&self) -> bool {
+        self.results.borrow().is_empty()
     }
 }
 
@@ -1231,8 +2040,24 @@ pub trait ConditionEvaluator: Send + Sync {
     /// 评估条件
     fn evaluate(&self, context: &RequestContext) -> bool;
 
+    /// 借助单次请求的评估缓存评估条件
+    ///
+    /// 默认忽略缓存，直接调用 [`evaluate`](Self::evaluate)；包含子条件的实现应通过
+    /// [`EvaluationCache::evaluate`] 评估子条件。
+    fn evaluate_with_cache(&self, context: &RequestContext, cache: &EvaluationCache) -> bool {
+        let _ = cache;
+        self.evaluate(context)
+    }
+
     /// 获取条件描述
     fn description(&self) -> String;
+
+    /// 条件仅由 IP 范围构成时返回这些范围
+    ///
+    /// [`RuleMatcher`] 据此将规则登记到 IP 前缀索引，不再逐条线性评估。
+    fn ip_ranges(&self) -> Option<&[IpRange]> {
+        None
+    }
 }
 
 impl ConditionEvaluator for MatchCondition {
@@ -1245,6 +2070,10 @@ impl ConditionEvaluator for MatchCondition {
                     user_ids.contains(&"*".to_string())
                 }
             }
+            MatchCondition::UserPattern(patterns) => match context.get_header("X-User-Id") {
+                Some(user_id) => patterns.matches(user_id),
+                None => patterns.matches_any(),
+            },
             MatchCondition::Ip(ip_ranges) => {
                 if let Some(client_ip) = &context.client_ip {
                     if let Ok(ip) = client_ip.parse::<IpAddr>() {
@@ -1253,6 +2082,11 @@ impl ConditionEvaluator for MatchCondition {
                 }
                 false
             }
+            MatchCondition::IpList(list) => context
+                .client_ip
+                .as_deref()
+                .and_then(|client_ip| client_ip.parse::<IpAddr>().ok())
+                .is_some_and(|ip| list.contains(&ip)),
             MatchCondition::Geo(countries) => {
                 if let Some(country) = context.get_header("X-Country") {
                     countries.contains(&country.to_string()) || countries.contains(&"*".to_string())
@@ -1275,6 +2109,15 @@ impl ConditionEvaluator for MatchCondition {
                     device_types.contains(&"*".to_string())
                 }
             }
+            MatchCondition::Method(methods) => methods
+                .iter()
+                .any(|method| method.eq_ignore_ascii_case(&context.method)),
+            #[cfg(feature = "regex")]
+            MatchCondition::PathRegex(pattern) => pattern.is_match(&context.path),
+            #[cfg(feature = "regex")]
+            MatchCondition::HeaderRegex { name, pattern } => context
+                .get_header(name)
+                .is_some_and(|value| pattern.is_match(value)),
             MatchCondition::Custom(eval_fn) => eval_fn(context),
         }
     }
@@ -1282,25 +2125,48 @@ impl ConditionEvaluator for MatchCondition {
     fn description(&self) -> String {
         match self {
             MatchCondition::User(ids) => format!("User in {:?}", ids),
+            MatchCondition::UserPattern(patterns) => {
+                format!("User matches {:?}", patterns.patterns())
+            }
             MatchCondition::Ip(ranges) => format!("IP in {} ranges", ranges.len()),
+            MatchCondition::IpList(list) => format!("IP in list of {} ranges", list.len()),
             MatchCondition::Geo(countries) => format!("Country in {:?}", countries),
             MatchCondition::ApiVersion(versions) => format!("API version in {:?}", versions),
             MatchCondition::Device(device_types) => format!("Device type in {:?}", device_types),
+            MatchCondition::Method(methods) => format!("Method in {:?}", methods),
+            #[cfg(feature = "regex")]
+            MatchCondition::PathRegex(pattern) => format!("Path matches /{}/", pattern),
+            #[cfg(feature = "regex")]
+            MatchCondition::HeaderRegex { name, pattern } => {
+                format!("Header {} matches /{}/", name, pattern)
+            }
             MatchCondition::Custom(_) => "Custom condition".to_string(),
         }
     }
+
+    fn ip_ranges(&self) -> Option<&[IpRange]> {
+        match self {
+            MatchCondition::Ip(ranges) => Some(ranges),
+            _ => None,
+        }
+    }
 }
 
 impl ConditionEvaluator for CompositeCondition {
     fn evaluate(&self, context: &RequestContext) -> bool {
+        self.evaluate_with_cache(context, &EvaluationCache::new())
+    }
+
+    /// 短路评估子条件，子条件结果记录在 `cache` 中
+    fn evaluate_with_cache(&self, context: &RequestContext, cache: &EvaluationCache) -> bool {
         match self.operator {
-            LogicalOperator::And => self.conditions.iter().all(|c| c.evaluate(context)),
-            LogicalOperator::Or => self.conditions.iter().any(|c| c.evaluate(context)),
+            LogicalOperator::And => self.conditions.iter().all(|c| cache.evaluate(c, context)),
+            LogicalOperator::Or => self.conditions.iter().any(|c| cache.evaluate(c, context)),
             LogicalOperator::Not => {
                 // NOT操作符只应该有一个子条件
                 self.conditions
                     .first()
-                    .is_some_and(|c| !c.evaluate(context))
+                    .is_some_and(|c| !cache.evaluate(c, context))
             }
         }
     }
@@ -1315,17 +2181,42 @@ impl ConditionEvaluator for CompositeCondition {
     }
 }
 
+/// 同优先级规则的排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuleTieBreak {
+    /// 按加入顺序，先加入的规则排在前面
+    #[default]
+    InsertionOrder,
+    /// 按规则 ID 的字典序
+    RuleId,
+}
+
 /// 规则匹配器
 ///
 /// 高性能规则匹配引擎，支持优先级排序和复合条件。
+/// 纯 IP 条件的规则登记在 IP 前缀索引中，按客户端 IP 查询，复杂度与规则数量无关；
+/// 其他规则仍按优先级线性评估。
+///
+/// # 规则顺序
+/// 规则按优先级降序排列，同优先级的规则按 [`RuleTieBreak`] 决定先后（默认按加入顺序），
+/// 因此相同的配置总是得到相同的顺序，[`matches`](Self::matches) 总是返回同一条规则。
 pub struct RuleMatcher {
     /// 规则列表（按优先级排序）
     rules: Vec<Rule>,
+    /// 同优先级规则的排序方式
+    tie_break: RuleTieBreak,
+    /// 纯 IP 条件规则的前缀索引（值为 `rules` 中的下标）
+    ip_index: IpIndex,
+    /// 需要线性评估的规则下标（升序）
+    linear_rules: Vec<usize>,
     /// 匹配统计
     stats: std::sync::RwLock<MatcherStats>,
 }
 
 /// 规则
+///
+/// 匹配条件以 `Arc` 共享，克隆后的规则与原规则使用同一个条件。
+#[derive(Clone)]
 pub struct Rule {
     /// 规则ID
     pub id: String,
@@ -1334,7 +2225,7 @@ pub struct Rule {
     /// 优先级（数值越大优先级越高）
     pub priority: u16,
     /// 匹配条件
-    pub condition: Box<dyn ConditionEvaluator>,
+    pub condition: Arc<dyn ConditionEvaluator>,
     /// 是否启用
     pub enabled: bool,
 }
@@ -1351,20 +2242,30 @@ impl std::fmt::Debug for Rule {
     }
 }
 
-impl Clone for Rule {
+impl Clone for RuleMatcher {
+    /// 克隆规则（共享条件）并复制当前统计信息
     fn clone(&self) -> Self {
-        // 由于 ConditionEvaluator 不能 Clone，我们创建一个新的 Rule
-        // 实际使用时，用户需要重新构建规则
         Self {
-            id: self.id.clone(),
-            name: self.name.clone(),
-            priority: self.priority,
-            condition: Box::new(MatchCondition::User(vec![])) as Box<dyn ConditionEvaluator>,
-            enabled: self.enabled,
+            rules: self.rules.clone(),
+            tie_break: self.tie_break,
+            ip_index: self.ip_index.clone(),
+            linear_rules: self.linear_rules.clone(),
+            stats: std::sync::RwLock::new(self.stats()),
         }
     }
 }
 
+/// 匹配器的单调计数器
+///
+/// [`MatcherStats`] 中可跨重启累加的部分；平均耗时与最后匹配时间属于瞬时值，不参与持久化。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MatcherCounters {
+    /// 总匹配次数
+    pub total_matches: u64,
+    /// 总不匹配次数
+    pub total_mismatches: u64,
+}
+
 /// 匹配器统计信息
 #[derive(Debug, Clone, Default)]
 pub struct MatcherStats {
@@ -1387,26 +2288,52 @@ impl RuleMatcher {
     /// # 示例
     /// ```rust
     /// use limiteron::matchers::{RuleMatcher, Rule, MatchCondition};
+    /// use std::sync::Arc;
     ///
     /// let matcher = RuleMatcher::new(vec![
     ///     Rule {
     ///         id: "rule1".to_string(),
     ///         name: "Test Rule".to_string(),
     ///         priority: 100,
-    ///         condition: Box::new(MatchCondition::User(vec!["user1".to_string()])),
+    ///         condition: Arc::new(MatchCondition::User(vec!["user1".to_string()])),
     ///         enabled: true,
     ///     },
     /// ]);
     /// ```
     pub fn new(rules: Vec<Rule>) -> Self {
+        Self::with_tie_break(rules, RuleTieBreak::default())
+    }
+
+    /// 使用指定的同优先级排序方式创建规则匹配器
+    ///
+    /// # 示例
+    /// ```rust
+    /// use limiteron::matchers::{MatchCondition, RequestContext, Rule, RuleMatcher, RuleTieBreak};
+    /// use std::sync::Arc;
+    ///
+    /// let rule = |id: &str| Rule {
+    ///     id: id.to_string(),
+    ///     name: id.to_string(),
+    ///     priority: 100,
+    ///     condition: Arc::new(MatchCondition::User(vec!["*".to_string()])),
+    ///     enabled: true,
+    /// };
+    /// let matcher = RuleMatcher::with_tie_break(vec![rule("b"), rule("a")], RuleTieBreak::RuleId);
+    /// assert_eq!(matcher.matches(&RequestContext::new()).unwrap().id, "a");
+    /// ```
+    pub fn with_tie_break(rules: Vec<Rule>, tie_break: RuleTieBreak) -> Self {
         let mut matcher = Self {
             rules: Vec::new(),
+            tie_break,
+            ip_index: IpIndex::default(),
+            linear_rules: Vec::new(),
             stats: std::sync::RwLock::new(MatcherStats::default()),
         };
 
         for rule in rules {
-            matcher.add_rule(rule);
+            matcher.insert_sorted(rule);
         }
+        matcher.rebuild_index();
 
         matcher
     }
@@ -1416,27 +2343,109 @@ impl RuleMatcher {
     /// # 参数
     /// - `rule`: 规则
     pub fn add_rule(&mut self, rule: Rule) {
-        // 按优先级排序（降序）
-        let pos = self
-            .rules
-            .binary_search_by(|r| r.priority.cmp(&rule.priority).reverse())
-            .unwrap_or_else(|pos| pos);
+        self.insert_sorted(rule);
+        self.rebuild_index();
+    }
+
+    /// 按优先级（降序）插入规则，同优先级按 `tie_break` 排在已有规则之后或按 ID 排序
+    fn insert_sorted(&mut self, rule: Rule) {
+        let pos = self.rules.partition_point(|r| match self.tie_break {
+            RuleTieBreak::InsertionOrder => r.priority >= rule.priority,
+            RuleTieBreak::RuleId => {
+                r.priority > rule.priority || (r.priority == rule.priority && r.id <= rule.id)
+            }
+        });
 
         self.rules.insert(pos, rule);
     }
 
+    /// 获取同优先级规则的排序方式
+    pub fn tie_break(&self) -> RuleTieBreak {
+        self.tie_break
+    }
+
+    /// 重建 IP 前缀索引与线性评估列表
+    ///
+    /// 规则下标随插入和删除变化，因此每次修改规则后整体重建。
+    fn rebuild_index(&mut self) {
+        let mut ip_index = IpIndex::default();
+        let mut linear_rules = Vec::new();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            match rule.condition.ip_ranges() {
+                Some(ranges) => {
+                    for range in ranges {
+                        ip_index.insert(range, index);
+                    }
+                }
+                None => linear_rules.push(index),
+            }
+        }
+
+        self.ip_index = ip_index;
+        self.linear_rules = linear_rules;
+    }
+
     /// 移除规则
     ///
     /// # 参数
     /// - `rule_id`: 规则ID
     pub fn remove_rule(&mut self, rule_id: &str) -> Option<Rule> {
-        if let Some(pos) = self.rules.iter().position(|r| r.id == rule_id) {
-            Some(self.rules.remove(pos))
-        } else {
-            None
+        let pos = self.rules.iter().position(|r| r.id == rule_id)?;
+        let rule = self.rules.remove(pos);
+        self.rebuild_index();
+        Some(rule)
+    }
+
+    /// 启用或禁用规则，规则不存在时返回 `false`
+    ///
+    /// # 参数
+    /// - `rule_id`: 规则ID
+    /// - `enabled`: 是否启用
+    pub fn set_rule_enabled(&mut self, rule_id: &str, enabled: bool) -> bool {
+        match self.rules.iter_mut().find(|r| r.id == rule_id) {
+            Some(rule) => {
+                rule.enabled = enabled;
+                true
+            }
+            None => false,
         }
     }
 
+    /// 通过 IP 前缀索引查找命中的已启用规则下标（升序）
+    fn matched_ip_rules(&self, context: &RequestContext) -> Vec<usize> {
+        let Some(ip) = context
+            .client_ip
+            .as_deref()
+            .and_then(|ip| ip.parse::<IpAddr>().ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut matched = self.ip_index.lookup(&ip);
+        matched.retain(|&index| self.rules[index].enabled);
+        matched
+    }
+
+    /// 线性评估下标小于 `limit` 的规则，返回命中的下标
+    ///
+    /// 规则间共享的条件通过 `cache` 在本次请求中只评估一次。
+    fn matched_linear_rules<'a>(
+        &'a self,
+        context: &'a RequestContext,
+        cache: &'a EvaluationCache,
+        limit: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.linear_rules
+            .iter()
+            .copied()
+            .take_while(move |&index| index < limit)
+            .filter(move |&index| {
+                let rule = &self.rules[index];
+                rule.enabled && cache.evaluate(&rule.condition, context)
+            })
+    }
+
     /// 检查请求是否匹配任何规则
     ///
     /// # 参数
@@ -1452,31 +2461,31 @@ impl RuleMatcher {
     pub fn matches(&self, context: &RequestContext) -> Option<&Rule> {
         let start = Instant::now();
 
-        // 按优先级顺序检查规则
-        for rule in &self.rules {
-            if !rule.enabled {
-                continue;
-            }
-
-            if rule.condition.evaluate(context) {
-                // 更新统计信息
-                let elapsed = start.elapsed().as_nanos() as u64;
-                {
-                    if let Ok(mut stats) = self.stats.write() {
-                        stats.total_matches += 1;
-                        stats.last_match_time = Some(Instant::now());
-
-                        // 更新平均匹配时间（使用指数移动平均）
-                        if stats.total_matches == 1 {
-                            stats.avg_match_time_ns = elapsed;
-                        } else {
-                            stats.avg_match_time_ns = (stats.avg_match_time_ns * 9 + elapsed) / 10;
-                        }
-                    }
+        // IP 规则命中的最高优先级规则，只需线性检查排在它之前的规则
+        let first_ip = self.matched_ip_rules(context).first().copied();
+        let limit = first_ip.unwrap_or(self.rules.len());
+        let cache = EvaluationCache::new();
+        let matched = self
+            .matched_linear_rules(context, &cache, limit)
+            .next()
+            .or(first_ip);
+
+        if let Some(index) = matched {
+            // 更新统计信息
+            let elapsed = start.elapsed().as_nanos() as u64;
+            if let Ok(mut stats) = self.stats.write() {
+                stats.total_matches += 1;
+                stats.last_match_time = Some(Instant::now());
+
+                // 更新平均匹配时间（使用指数移动平均）
+                if stats.total_matches == 1 {
+                    stats.avg_match_time_ns = elapsed;
+                } else {
+                    stats.avg_match_time_ns = (stats.avg_match_time_ns * 9 + elapsed) / 10;
                 }
-
-                return Some(rule);
             }
+
+            return Some(&self.rules[index]);
         }
 
         {
@@ -1494,9 +2503,13 @@ impl RuleMatcher {
     /// # 返回
     /// - 匹配的规则列表（按优先级排序）
     pub fn match_all(&self, context: &RequestContext) -> Vec<&Rule> {
-        self.rules
-            .iter()
-            .filter(|rule| rule.enabled && rule.condition.evaluate(context))
+        let cache = EvaluationCache::new();
+        let mut matched = self.matched_ip_rules(context);
+        matched.extend(self.matched_linear_rules(context, &cache, self.rules.len()));
+        matched.sort_unstable();
+        matched
+            .into_iter()
+            .map(|index| &self.rules[index])
             .collect()
     }
 
@@ -1511,6 +2524,24 @@ impl RuleMatcher {
         *stats = MatcherStats::default();
     }
 
+    /// 获取单调计数器
+    pub fn counters(&self) -> MatcherCounters {
+        let stats = self.stats.read().unwrap();
+        MatcherCounters {
+            total_matches: stats.total_matches,
+            total_mismatches: stats.total_mismatches,
+        }
+    }
+
+    /// 累加导入单调计数器，平均耗时等瞬时值保持不变
+    pub fn import_counters(&self, counters: &MatcherCounters) {
+        let mut stats = self.stats.write().unwrap();
+        stats.total_matches = stats.total_matches.saturating_add(counters.total_matches);
+        stats.total_mismatches = stats
+            .total_mismatches
+            .saturating_add(counters.total_mismatches);
+    }
+
     /// 获取规则数量
     pub fn rule_count(&self) -> usize {
         self.rules.len()
@@ -1518,42 +2549,111 @@ impl RuleMatcher {
 
     /// 从配置创建规则匹配器
     ///
+    /// 自定义匹配器不会被解析，始终视为不匹配；
+    /// 需要自定义匹配器参与评估时请使用 [`from_config_with_registry`](Self::from_config_with_registry)。
+    ///
     /// # 参数
     /// - `config_matchers`: 配置中的匹配器列表
     pub fn from_config(config_matchers: &[ConfigMatcher]) -> Result<Self, FlowGuardError> {
+        Self::build_from_config(config_matchers, |name| {
+            // 自定义匹配器需要在运行时通过CustomMatcherRegistry处理
+            // 这里返回一个占位符，实际匹配逻辑由CustomMatcherRegistry处理
+            let name = name.to_string();
+            Arc::new(MatchCondition::Custom(Arc::new(move |_context| {
+                // 自定义匹配器的实际匹配逻辑在CustomMatcherRegistry中实现
+                // 这里只是占位符，返回false表示不匹配
+                tracing::warn!("自定义匹配器 '{}' 需要通过CustomMatcherRegistry处理", name);
+                false
+            })))
+        })
+    }
+
+    /// 从配置创建规则匹配器，并通过注册表解析自定义匹配器
+    ///
+    /// 配置中的 `Custom` 匹配器会按名称在注册表中查找，
+    /// 并包装为委托给该匹配器的 [`CustomMatcherCondition`](custom::CustomMatcherCondition)。
+    ///
+    /// # 参数
+    /// - `config_matchers`: 配置中的匹配器列表
+    /// - `registry`: 自定义匹配器注册表
+    ///
+    /// # 错误
+    /// - `FlowGuardError::ConfigError`: 自定义匹配器未在注册表中注册（或未提供注册表）
+    pub async fn from_config_with_registry(
+        config_matchers: &[ConfigMatcher],
+        registry: Option<&custom::CustomMatcherRegistry>,
+    ) -> Result<Self, FlowGuardError> {
+        let mut resolved = HashMap::new();
+        for matcher in config_matchers {
+            if let ConfigMatcher::Custom { name, config: _ } = matcher {
+                let found = match registry {
+                    Some(registry) => registry.resolve(name).await,
+                    None => None,
+                };
+                let custom_matcher = found.ok_or_else(|| {
+                    FlowGuardError::ConfigError(format!("自定义匹配器 '{}' 未注册", name))
+                })?;
+                resolved.insert(name.clone(), custom_matcher);
+            }
+        }
+
+        Self::build_from_config(config_matchers, |name| {
+            Arc::new(custom::CustomMatcherCondition::new(
+                name,
+                resolved[name].clone(),
+            ))
+        })
+    }
+
+    /// 根据配置构建规则，自定义匹配器由 `custom_condition` 负责构建
+    fn build_from_config<F>(
+        config_matchers: &[ConfigMatcher],
+        custom_condition: F,
+    ) -> Result<Self, FlowGuardError>
+    where
+        F: Fn(&str) -> Arc<dyn ConditionEvaluator>,
+    {
         let mut rules = Vec::new();
 
         for (index, matcher) in config_matchers.iter().enumerate() {
-            let condition: Box<dyn ConditionEvaluator> = match matcher {
+            let condition: Arc<dyn ConditionEvaluator> = match matcher {
                 ConfigMatcher::User { user_ids } => {
-                    Box::new(MatchCondition::User(user_ids.clone()))
+                    Arc::new(MatchCondition::User(user_ids.clone()))
                 }
+                ConfigMatcher::UserPattern {
+                    user_ids,
+                    case_insensitive,
+                } => Arc::new(MatchCondition::from_config_user_pattern(
+                    user_ids,
+                    *case_insensitive,
+                )),
                 ConfigMatcher::Ip { ip_ranges } => {
                     let ranges: Result<Vec<IpRange>, _> =
                         ip_ranges.iter().map(|s| s.parse()).collect();
 
-                    Box::new(MatchCondition::Ip(ranges?))
+                    Arc::new(MatchCondition::Ip(ranges?))
                 }
                 ConfigMatcher::Geo { countries } => {
-                    Box::new(MatchCondition::Geo(countries.clone()))
+                    Arc::new(MatchCondition::Geo(countries.clone()))
                 }
                 ConfigMatcher::ApiVersion { versions } => {
-                    Box::new(MatchCondition::ApiVersion(versions.clone()))
+                    Arc::new(MatchCondition::ApiVersion(versions.clone()))
                 }
                 ConfigMatcher::Device { device_types } => {
-                    Box::new(MatchCondition::Device(device_types.clone()))
+                    Arc::new(MatchCondition::Device(device_types.clone()))
+                }
+                ConfigMatcher::Method { methods } => {
+                    Arc::new(MatchCondition::Method(methods.clone()))
                 }
-                ConfigMatcher::Custom { name, config: _ } => {
-                    // 自定义匹配器需要在运行时通过CustomMatcherRegistry处理
-                    // 这里返回一个占位符，实际匹配逻辑由CustomMatcherRegistry处理
-                    let name = name.clone();
-                    Box::new(MatchCondition::Custom(Arc::new(move |_context| {
-                        // 自定义匹配器的实际匹配逻辑在CustomMatcherRegistry中实现
-                        // 这里只是占位符，返回false表示不匹配
-                        tracing::warn!("自定义匹配器 '{}' 需要通过CustomMatcherRegistry处理", name);
-                        false
-                    })))
+                #[cfg(feature = "regex")]
+                ConfigMatcher::PathRegex { pattern } => {
+                    Arc::new(MatchCondition::path_regex(pattern)?)
                 }
+                #[cfg(feature = "regex")]
+                ConfigMatcher::HeaderRegex { name, pattern } => {
+                    Arc::new(MatchCondition::header_regex(name, pattern)?)
+                }
+                ConfigMatcher::Custom { name, config: _ } => custom_condition(name),
             };
 
             rules.push(Rule {
@@ -1578,6 +2678,43 @@ impl RuleMatcher {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ip_list_condition_follows_store() {
+        let list = SharedIpList::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let condition = MatchCondition::IpList(list.clone());
+        let context = |ip: &str| RequestContext::new().with_client_ip(ip);
+
+        assert!(condition.evaluate(&context("10.1.2.3")));
+        assert!(!condition.evaluate(&context("192.168.1.1")));
+        assert!(!condition.evaluate(&RequestContext::new()));
+        // 动态列表不参与 IP 前缀索引
+        assert!(condition.ip_ranges().is_none());
+
+        list.store(vec!["192.168.0.0/16".parse().unwrap()]);
+        assert!(!condition.evaluate(&context("10.1.2.3")));
+        assert!(condition.evaluate(&context("192.168.1.1")));
+    }
+
+    #[test]
+    fn test_request_context_debug_uses_redaction_policy() {
+        use crate::log_redaction::{set_redaction_policy, RedactionPolicy};
+
+        // 保留默认敏感字段，避免影响并行运行的其他测试
+        set_redaction_policy(RedactionPolicy::default().with_sensitive_key("x-tenant"));
+
+        let context = RequestContext::new()
+            .with_header("X-Tenant-Id", "acme-corp")
+            .with_header("Authorization", "Bearer secret")
+            .with_header("Accept", "text/html")
+            .with_query_param("access_token", "tok-123");
+        let debug = format!("{:?}", context);
+
+        assert!(!debug.contains("acme-corp"));
+        assert!(!debug.contains("Bearer secret"));
+        assert!(!debug.contains("tok-123"));
+        assert!(debug.contains("text/html"));
+    }
+
     // ==================== 标识符提取器测试 ====================
 
     #[test]
@@ -1664,6 +2801,148 @@ mod tests {
         assert_eq!(identifier, Identifier::Ip("192.168.1.1".to_string()));
     }
 
+    #[test]
+    fn test_ip_extractor_forwarded_header() {
+        let extractor = IpExtractor::from_header_with_parser("Forwarded", parse_forwarded_for);
+        let context = RequestContext::new().with_header("Forwarded", "for=192.0.2.60;proto=http");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::Ip("192.0.2.60".to_string()))
+        );
+
+        // 引号、端口、IPv6 方括号与大小写
+        let context = RequestContext::new()
+            .with_header("Forwarded", r#"For="[2001:db8:cafe::17]:4711";proto=https"#);
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::Ip("2001:db8:cafe::17".to_string()))
+        );
+
+        // 没有 for= 字段时回退到对端地址
+        let context = RequestContext::new()
+            .with_header("Forwarded", "proto=http;by=203.0.113.43")
+            .with_client_ip("10.0.0.9");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::Ip("10.0.0.9".to_string()))
+        );
+        assert_eq!(parse_forwarded_for("for=unknown, for=_hidden"), None);
+    }
+
+    #[test]
+    fn test_ip_extractor_forwarded_header_with_trusted_proxies() {
+        let extractor = IpExtractor::from_header_with_parser("Forwarded", parse_forwarded_for)
+            .with_trusted_proxies(vec!["10.0.0.0/8".parse().unwrap()]);
+
+        // 伪造的最左侧地址被跳过，取最右侧的不可信地址
+        let context = RequestContext::new()
+            .with_header(
+                "Forwarded",
+                "for=1.2.3.4, for=192.0.2.60;proto=http, for=\"10.0.0.2:8080\"",
+            )
+            .with_client_ip("10.0.0.1");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::Ip("192.0.2.60".to_string()))
+        );
+
+        // 对端不是可信代理时忽略请求头
+        let context = RequestContext::new()
+            .with_header("Forwarded", "for=192.0.2.60")
+            .with_client_ip("198.51.100.1");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::Ip("198.51.100.1".to_string()))
+        );
+
+        // 混淆节点视为不可信跳点，不会被跳过而取到更左侧可伪造的地址，
+        // 无法确定客户端时回退到对端地址
+        let context = RequestContext::new()
+            .with_header("Forwarded", "for=1.2.3.4, for=_hidden, for=10.0.0.2")
+            .with_client_ip("10.0.0.1");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::Ip("10.0.0.1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_user_id_extractor_with_parser() {
+        let extractor = UserIdExtractor::from_header_with_parser("X-Identity", |raw| {
+            raw.split(';')
+                .find_map(|field| field.trim().strip_prefix("uid="))
+                .map(str::to_string)
+        })
+        .with_default("anonymous");
+
+        let context = RequestContext::new().with_header("X-Identity", "tenant=acme; uid=alice");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::UserId("alice".to_string()))
+        );
+
+        // 解析失败时继续走后续来源
+        let context = RequestContext::new().with_header("X-Identity", "tenant=acme");
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::UserId("anonymous".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ip_extractor_trusted_proxies_skip_spoofed_ip() {
+        let extractor = IpExtractor::from_header("X-Forwarded-For")
+            .with_trusted_proxies(vec!["10.0.0.0/8".parse().unwrap()]);
+
+        // 客户端伪造了最左边的 IP，可信代理追加了真实的客户端地址
+        let context = RequestContext::new()
+            .with_header("X-Forwarded-For", "1.2.3.4, 203.0.113.7")
+            .with_client_ip("10.0.0.2");
+
+        let identifier = extractor.extract(&context).unwrap();
+        assert_eq!(identifier, Identifier::Ip("203.0.113.7".to_string()));
+    }
+
+    #[test]
+    fn test_ip_extractor_trusted_proxies_multi_hop() {
+        let extractor = IpExtractor::from_header("X-Forwarded-For").with_trusted_proxies(vec![
+            "10.0.0.0/8".parse().unwrap(),
+            "172.16.0.0/12".parse().unwrap(),
+            "2001:db8::/32".parse().unwrap(),
+        ]);
+
+        let context = RequestContext::new().with_header(
+            "X-Forwarded-For",
+            "198.51.100.1, 203.0.113.9, 172.16.0.5, 2001:db8::1, 10.1.1.1",
+        );
+        let identifier = extractor.extract(&context).unwrap();
+        assert_eq!(identifier, Identifier::Ip("203.0.113.9".to_string()));
+
+        // 整条链都是可信代理时取最左边的地址
+        let context = RequestContext::new().with_header("X-Forwarded-For", "10.0.0.1, 172.16.0.1");
+        let identifier = extractor.extract(&context).unwrap();
+        assert_eq!(identifier, Identifier::Ip("10.0.0.1".to_string()));
+
+        // 不可信的跳点格式错误时不返回
+        let context =
+            RequestContext::new().with_header("X-Forwarded-For", "1.2.3.4, garbage, 10.0.0.1");
+        assert!(extractor.extract(&context).is_none());
+    }
+
+    #[test]
+    fn test_ip_extractor_untrusted_peer_ignores_header() {
+        let extractor = IpExtractor::from_header("X-Forwarded-For")
+            .with_trusted_proxies(vec!["10.0.0.0/8".parse().unwrap()]);
+
+        // 直连对端不是可信代理，转发头不可信
+        let context = RequestContext::new()
+            .with_header("X-Forwarded-For", "1.2.3.4")
+            .with_client_ip("198.51.100.20");
+
+        let identifier = extractor.extract(&context).unwrap();
+        assert_eq!(identifier, Identifier::Ip("198.51.100.20".to_string()));
+    }
+
     #[test]
     fn test_mac_extractor_from_header() {
         let extractor = MacExtractor::from_header("X-Mac-Address");
@@ -1749,6 +3028,126 @@ mod tests {
         assert_eq!(identifier, Identifier::UserId("custom123".to_string()));
     }
 
+    #[test]
+    fn test_cookie_extractor_multiple_cookies() {
+        let extractor = CookieExtractor::new("session", Identifier::UserId);
+        let context =
+            RequestContext::new().with_header("Cookie", "theme=dark;session=abc123 ; lang=zh-CN");
+
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::UserId("abc123".to_string()))
+        );
+
+        // 名称需完整匹配
+        let partial = CookieExtractor::new("sess", Identifier::UserId);
+        assert!(partial.extract(&context).is_none());
+    }
+
+    #[test]
+    fn test_cookie_extractor_quoted_and_encoded() {
+        let extractor = CookieExtractor::new("uid", Identifier::UserId);
+
+        let quoted = RequestContext::new().with_header("Cookie", "uid=\"user 42\"; a=b");
+        assert_eq!(
+            extractor.extract(&quoted),
+            Some(Identifier::UserId("user 42".to_string()))
+        );
+
+        let encoded = RequestContext::new().with_header("Cookie", "uid=%E7%94%A8%E6%88%B7%3D1");
+        assert_eq!(
+            extractor.extract(&encoded),
+            Some(Identifier::UserId("用户=1".to_string()))
+        );
+
+        // 非法转义按原样保留
+        let invalid = RequestContext::new().with_header("Cookie", "uid=100%zz%");
+        assert_eq!(
+            extractor.extract(&invalid),
+            Some(Identifier::UserId("100%zz%".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_cookie_extractor_missing_cookie() {
+        let extractor = CookieExtractor::new("session", Identifier::UserId);
+
+        assert!(extractor.extract(&RequestContext::new()).is_none());
+
+        let other = RequestContext::new().with_header("Cookie", "theme=dark");
+        assert!(extractor.extract(&other).is_none());
+
+        let empty = RequestContext::new().with_header("Cookie", "session=; theme=dark");
+        assert!(extractor.extract(&empty).is_none());
+
+        let empty_quoted = RequestContext::new().with_header("Cookie", "session=\"\"");
+        assert!(extractor.extract(&empty_quoted).is_none());
+    }
+
+    #[test]
+    fn test_json_body_extractor_nested() {
+        let extractor = JsonBodyExtractor::new("/auth/user_id", Identifier::UserId);
+        let context = RequestContext::new()
+            .with_body(&br#"{"auth":{"user_id":"user123"},"tenant":{"id":42}}"#[..]);
+
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::UserId("user123".to_string()))
+        );
+
+        // 数字值转换为字符串
+        let tenant = JsonBodyExtractor::new("/tenant/id", Identifier::UserId);
+        assert_eq!(
+            tenant.extract(&context),
+            Some(Identifier::UserId("42".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_json_body_extractor_missing_pointer() {
+        let extractor = JsonBodyExtractor::new("/auth/api_key", Identifier::ApiKey);
+        let context = RequestContext::new().with_body(&br#"{"auth":{"user_id":"u1"}}"#[..]);
+        assert!(extractor.extract(&context).is_none());
+
+        // 对象不能作为标识符
+        let object = JsonBodyExtractor::new("/auth", Identifier::UserId);
+        assert!(object.extract(&context).is_none());
+
+        // 没有请求体
+        assert!(extractor.extract(&RequestContext::new()).is_none());
+    }
+
+    #[test]
+    fn test_json_body_extractor_non_json_body() {
+        let extractor = JsonBodyExtractor::new("/user_id", Identifier::UserId);
+        let context = RequestContext::new().with_body(&b"user_id=u1"[..]);
+        assert!(extractor.extract(&context).is_none());
+    }
+
+    #[test]
+    fn test_json_body_parsed_once_in_composite() {
+        let extractor = CompositeExtractor::new(
+            vec![
+                Box::new(JsonBodyExtractor::new("/api_key", Identifier::ApiKey)),
+                Box::new(JsonBodyExtractor::new("/user_id", Identifier::UserId)),
+            ],
+            false,
+        );
+        let context = RequestContext::new().with_body(&br#"{"user_id":"u1"}"#[..]);
+
+        assert_eq!(
+            extractor.extract(&context),
+            Some(Identifier::UserId("u1".to_string()))
+        );
+        // 解析结果已缓存在上下文中，并与克隆共享
+        assert!(context.json_body.0.get().is_some());
+        let cloned = context.clone();
+        assert!(std::ptr::eq(
+            cloned.json_body().unwrap(),
+            context.json_body().unwrap()
+        ));
+    }
+
     // ==================== IP范围测试 ====================
 
     #[test]
@@ -1790,6 +3189,46 @@ mod tests {
         assert!("invalid".parse::<IpRange>().is_err());
         assert!("192.168.1.1/33".parse::<IpRange>().is_err());
         assert!("192.168.1.10-192.168.1.1".parse::<IpRange>().is_err());
+        assert!("2001:db8::1/129".parse::<IpRange>().is_err());
+        assert!("2001:db8::ff-2001:db8::1".parse::<IpRange>().is_err());
+        assert!("192.168.1.1-2001:db8::1".parse::<IpRange>().is_err());
+        assert!("1.1.1.1-2.2.2.2-3.3.3.3".parse::<IpRange>().is_err());
+    }
+
+    #[test]
+    fn test_ip_range_ipv6_single() {
+        let range: IpRange = "2001:db8::1".parse().unwrap();
+        assert!(range.contains(&"2001:db8::1".parse().unwrap()));
+        assert!(!range.contains(&"2001:db8::2".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_range_ipv6_cidr() {
+        let range: IpRange = "2001:db8::/32".parse().unwrap();
+        assert!(range.contains(&"2001:db8::1".parse().unwrap()));
+        assert!(range.contains(&"2001:db8:ffff:ffff:ffff:ffff:ffff:ffff".parse().unwrap()));
+        assert!(!range.contains(&"2001:db9::1".parse().unwrap()));
+        assert!(!range.contains(&"192.168.1.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_range_ipv6_range() {
+        let range: IpRange = "2001:db8::1-2001:db8::ff".parse().unwrap();
+        assert!(matches!(range, IpRange::Ipv6Range { .. }));
+
+        // 边界地址
+        assert!(range.contains(&"2001:db8::1".parse().unwrap()));
+        assert!(range.contains(&"2001:db8::ff".parse().unwrap()));
+        assert!(range.contains(&"2001:db8::80".parse().unwrap()));
+        assert!(!range.contains(&"2001:db8::".parse().unwrap()));
+        assert!(!range.contains(&"2001:db8::100".parse().unwrap()));
+        assert!(!range.contains(&"192.168.1.1".parse().unwrap()));
+
+        // 方括号包裹的写法，跨越段边界
+        let range: IpRange = "[::ffff]-[::1:0]".parse().unwrap();
+        assert!(range.contains(&"::ffff".parse().unwrap()));
+        assert!(range.contains(&"::1:0".parse().unwrap()));
+        assert!(!range.contains(&"::1:1".parse().unwrap()));
     }
 
     // ==================== 规则匹配器测试 ====================
@@ -1800,7 +3239,7 @@ mod tests {
             id: "rule1".to_string(),
             name: "Test Rule".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::User(vec![
+            condition: Arc::new(MatchCondition::User(vec![
                 "user1".to_string(),
                 "user2".to_string(),
             ])),
@@ -1816,13 +3255,91 @@ mod tests {
         assert!(matcher.matches(&context2).is_none());
     }
 
+    #[test]
+    fn test_rule_matcher_clone_keeps_conditions() {
+        let rule = Rule {
+            id: "rule1".to_string(),
+            name: "Test Rule".to_string(),
+            priority: 100,
+            condition: Arc::new(CompositeCondition {
+                conditions: vec![
+                    Arc::new(MatchCondition::User(vec!["user1".to_string()])),
+                    Arc::new(MatchCondition::Geo(vec!["US".to_string()])),
+                ],
+                operator: LogicalOperator::And,
+            }),
+            enabled: true,
+        };
+
+        let original = RuleMatcher::new(vec![rule]);
+        let cloned = original.clone();
+        drop(original);
+
+        let context1 = RequestContext::new()
+            .with_header("X-User-Id", "user1")
+            .with_header("X-Country", "US");
+        assert_eq!(
+            cloned.matches(&context1).map(|r| r.id.as_str()),
+            Some("rule1")
+        );
+
+        // 克隆后的条件不是占位符
+        let context2 = RequestContext::new().with_header("X-User-Id", "user2");
+        assert!(cloned.matches(&context2).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_from_config_with_registry_evaluates_custom_matcher() {
+        let registry = custom::CustomMatcherRegistry::new();
+        registry
+            .register(
+                "tenant_header".to_string(),
+                Box::new(custom::HeaderMatcher::new("X-Tenant", vec!["acme".to_string()]).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        let config = vec![ConfigMatcher::Custom {
+            name: "tenant_header".to_string(),
+            config: serde_json::json!({}),
+        }];
+        let matcher = RuleMatcher::from_config_with_registry(&config, Some(&registry))
+            .await
+            .unwrap();
+
+        let context1 = RequestContext::new().with_header("X-Tenant", "acme");
+        assert!(matcher.matches(&context1).is_some());
+
+        let context2 = RequestContext::new().with_header("X-Tenant", "other");
+        assert!(matcher.matches(&context2).is_none());
+
+        // 不带注册表的 from_config 仍然不会匹配
+        let placeholder = RuleMatcher::from_config(&config).unwrap();
+        assert!(placeholder.matches(&context1).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_from_config_with_registry_unknown_matcher() {
+        let registry = custom::CustomMatcherRegistry::new();
+        let config = vec![ConfigMatcher::Custom {
+            name: "missing".to_string(),
+            config: serde_json::json!({}),
+        }];
+
+        let result = RuleMatcher::from_config_with_registry(&config, Some(&registry)).await;
+        assert!(matches!(result, Err(FlowGuardError::ConfigError(_))));
+
+        let result = RuleMatcher::from_config_with_registry(&config, None).await;
+        assert!(matches!(result, Err(FlowGuardError::ConfigError(_))));
+    }
+
     #[test]
     fn test_rule_matcher_wildcard_user() {
         let rule = Rule {
             id: "rule1".to_string(),
             name: "Test Rule".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::User(vec!["*".to_string()])),
+            condition: Arc::new(MatchCondition::User(vec!["*".to_string()])),
             enabled: true,
         };
 
@@ -1832,13 +3349,115 @@ mod tests {
         assert!(matcher.matches(&context).is_some());
     }
 
+    #[test]
+    fn test_user_condition_glob_patterns() {
+        let condition = MatchCondition::user_pattern(vec![
+            "tenant-*".to_string(),
+            "*-admin".to_string(),
+            "org-*-ops".to_string(),
+            "alice".to_string(),
+        ]);
+        let user = |id: &str| RequestContext::new().with_header("X-User-Id", id);
+
+        // 前缀、后缀、中间通配与精确匹配
+        assert!(condition.evaluate(&user("tenant-42")));
+        assert!(condition.evaluate(&user("tenant-")));
+        assert!(condition.evaluate(&user("db-admin")));
+        assert!(condition.evaluate(&user("org-eu-ops")));
+        assert!(condition.evaluate(&user("alice")));
+
+        assert!(!condition.evaluate(&user("tenant")));
+        assert!(!condition.evaluate(&user("my-tenant-42")));
+        assert!(!condition.evaluate(&user("admin-db")));
+        assert!(!condition.evaluate(&user("org-ops")));
+        assert!(!condition.evaluate(&user("alice2")));
+        // 默认区分大小写
+        assert!(!condition.evaluate(&user("Tenant-42")));
+        assert!(!condition.evaluate(&user("ALICE")));
+        // 只有 `*` 能匹配未提供用户ID的请求
+        assert!(!condition.evaluate(&RequestContext::new()));
+    }
+
+    #[test]
+    fn test_user_condition_case_insensitive() {
+        let condition = MatchCondition::user_pattern_case_insensitive(vec![
+            "Tenant-*".to_string(),
+            "Bob".to_string(),
+        ]);
+        let user = |id: &str| RequestContext::new().with_header("X-User-Id", id);
+
+        assert!(condition.evaluate(&user("TENANT-42")));
+        assert!(condition.evaluate(&user("tenant-x")));
+        assert!(condition.evaluate(&user("bob")));
+        assert!(condition.evaluate(&user("BOB")));
+        assert!(!condition.evaluate(&user("bobby")));
+        assert!(!condition.evaluate(&user("ten-42")));
+    }
+
+    #[test]
+    fn test_method_condition() {
+        let condition = MatchCondition::Method(vec!["POST".to_string(), "put".to_string()]);
+        let request = |method: &str| {
+            RequestContext::new()
+                .with_path("/api/items")
+                .with_method(method)
+        };
+
+        assert!(condition.evaluate(&request("POST")));
+        assert!(condition.evaluate(&request("post")));
+        assert!(condition.evaluate(&request("PUT")));
+        assert!(!condition.evaluate(&request("GET")));
+        // 未设置方法的请求不匹配
+        assert!(!condition.evaluate(&RequestContext::new()));
+    }
+
+    #[test]
+    fn test_user_condition_from_config() {
+        let config: ConfigMatcher = serde_json::from_value(serde_json::json!({
+            "type": "UserPattern",
+            "user_ids": ["svc-*"],
+            "case_insensitive": true
+        }))
+        .unwrap();
+        let matcher = RuleMatcher::from_config(&[config]).unwrap();
+        let context = RequestContext::new().with_header("X-User-Id", "SVC-billing");
+        assert!(matcher.matches(&context).is_some());
+
+        // case_insensitive 缺省为区分大小写
+        let config: ConfigMatcher = serde_json::from_value(serde_json::json!({
+            "type": "UserPattern",
+            "user_ids": ["svc-*"]
+        }))
+        .unwrap();
+        assert_eq!(
+            config,
+            ConfigMatcher::UserPattern {
+                user_ids: vec!["svc-*".to_string()],
+                case_insensitive: false,
+            }
+        );
+        let matcher = RuleMatcher::from_config(&[config]).unwrap();
+        assert!(matcher.matches(&context).is_none());
+        let lower = RequestContext::new().with_header("X-User-Id", "svc-billing");
+        assert!(matcher.matches(&lower).is_some());
+
+        // User 匹配器保持精确匹配，`*` 不作为 glob 展开
+        let config: ConfigMatcher = serde_json::from_value(serde_json::json!({
+            "type": "User",
+            "user_ids": ["svc-*"]
+        }))
+        .unwrap();
+        let matcher = RuleMatcher::from_config(&[config]).unwrap();
+        assert!(matcher.matches(&lower).is_none());
+    }
+
     #[test]
     fn test_rule_matcher_ip_condition() {
         let rule = Rule {
             id: "rule1".to_string(),
             name: "Test Rule".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::Ip(vec!["192.168.1.0/24".parse().unwrap()])),
+            condition: Arc::new(MatchCondition::Ip(vec!["192.168.1.0/24".parse().unwrap()])),
             enabled: true,
         };
 
@@ -1851,13 +3470,112 @@ mod tests {
         assert!(matcher.matches(&context2).is_none());
     }
 
+    #[test]
+    fn test_rule_matcher_ip_index_matches_linear_scan() {
+        // 简单的线性同余生成器，保证用例可复现
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 33) as u32
+        };
+
+        let mut rules = Vec::new();
+        for i in 0..300 {
+            let a = (next() % 4) as u8 + 10;
+            let b = (next() % 8) as u8;
+            let c = next() as u8;
+            let range = match i % 5 {
+                0 => format!("{}.{}.0.0/{}", a, b, 12 + next() % 12),
+                1 => format!("{}.{}.{}.0/24", a, b, c),
+                2 => format!("{}.{}.{}.{}", a, b, c, next() as u8),
+                3 => format!("{}.{}.{}.10-{}.{}.{}.200", a, b, c, a, b, c.wrapping_add(1)),
+                _ => format!("2001:db8:{:x}::/{}", next() % 16, 40 + next() % 40),
+            };
+            let condition: Arc<dyn ConditionEvaluator> = if i % 17 == 0 {
+                // 混入需要线性评估的规则
+                Arc::new(MatchCondition::User(vec![format!("user{}", i % 3)]))
+            } else {
+                Arc::new(MatchCondition::Ip(vec![range.parse().unwrap()]))
+            };
+            rules.push(Rule {
+                id: format!("rule{}", i),
+                name: format!("Rule {}", i),
+                priority: (next() % 50) as u16,
+                condition,
+                enabled: i % 11 != 0,
+            });
+        }
+
+        let matcher = RuleMatcher::new(rules);
+        for _ in 0..2000 {
+            let ip = if next() % 5 == 0 {
+                format!("2001:db8:{:x}::{:x}", next() % 16, next() % 0xffff)
+            } else {
+                format!(
+                    "{}.{}.{}.{}",
+                    (next() % 4) + 10,
+                    next() % 8,
+                    next() as u8,
+                    next() as u8
+                )
+            };
+            let context = RequestContext::new()
+                .with_client_ip(&ip)
+                .with_header("X-User-Id", &format!("user{}", next() % 4));
+
+            let linear: Vec<&str> = matcher
+                .rules
+                .iter()
+                .filter(|rule| rule.enabled && rule.condition.evaluate(&context))
+                .map(|rule| rule.id.as_str())
+                .collect();
+            let indexed: Vec<&str> = matcher
+                .match_all(&context)
+                .into_iter()
+                .map(|rule| rule.id.as_str())
+                .collect();
+
+            assert_eq!(indexed, linear, "ip {}", ip);
+            assert_eq!(
+                matcher.matches(&context).map(|rule| rule.id.as_str()),
+                linear.first().copied(),
+                "ip {}",
+                ip
+            );
+        }
+    }
+
+    #[test]
+    fn test_rule_matcher_ip_index_follows_add_remove() {
+        let ip_rule = |id: &str, priority: u16, range: &str| Rule {
+            id: id.to_string(),
+            name: id.to_string(),
+            priority,
+            condition: Arc::new(MatchCondition::Ip(vec![range.parse().unwrap()])),
+            enabled: true,
+        };
+
+        let mut matcher = RuleMatcher::new(vec![ip_rule("wide", 10, "10.0.0.0/8")]);
+        matcher.add_rule(ip_rule("narrow", 20, "10.1.0.0/16"));
+        let context = RequestContext::new().with_client_ip("10.1.2.3");
+        assert_eq!(matcher.matches(&context).unwrap().id, "narrow");
+
+        matcher.remove_rule("narrow");
+        assert_eq!(matcher.matches(&context).unwrap().id, "wide");
+        assert!(matcher
+            .matches(&RequestContext::new().with_client_ip("not-an-ip"))
+            .is_none());
+    }
+
     #[test]
     fn test_rule_matcher_priority() {
         let rule1 = Rule {
             id: "rule1".to_string(),
             name: "Low Priority".to_string(),
             priority: 50,
-            condition: Box::new(MatchCondition::User(vec!["*".to_string()])),
+            condition: Arc::new(MatchCondition::User(vec!["*".to_string()])),
             enabled: true,
         };
 
@@ -1865,7 +3583,7 @@ mod tests {
             id: "rule2".to_string(),
             name: "High Priority".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::User(vec!["user1".to_string()])),
+            condition: Arc::new(MatchCondition::User(vec!["user1".to_string()])),
             enabled: true,
         };
 
@@ -1878,13 +3596,53 @@ mod tests {
         assert_eq!(matched.id, "rule2");
     }
 
+    #[test]
+    fn test_rule_matcher_equal_priority_tie_break() {
+        let rule = |id: &str| Rule {
+            id: id.to_string(),
+            name: id.to_string(),
+            priority: 100,
+            condition: Arc::new(MatchCondition::User(vec!["*".to_string()])),
+            enabled: true,
+        };
+        let ids = ["delta", "alpha", "charlie", "echo", "bravo"];
+        let context = RequestContext::new().with_header("X-User-Id", "user1");
+
+        for _ in 0..10 {
+            // 默认按加入顺序：第一条规则胜出
+            let matcher = RuleMatcher::new(ids.iter().map(|id| rule(id)).collect());
+            assert_eq!(matcher.matches(&context).unwrap().id, "delta");
+            let order: Vec<_> = matcher
+                .match_all(&context)
+                .iter()
+                .map(|r| r.id.clone())
+                .collect();
+            assert_eq!(order, ids);
+
+            // 按 ID 排序：与加入顺序无关
+            let mut matcher = RuleMatcher::with_tie_break(
+                ids.iter().rev().map(|id| rule(id)).collect(),
+                RuleTieBreak::RuleId,
+            );
+            assert_eq!(matcher.tie_break(), RuleTieBreak::RuleId);
+            assert_eq!(matcher.matches(&context).unwrap().id, "alpha");
+            matcher.add_rule(rule("aaa"));
+            assert_eq!(matcher.matches(&context).unwrap().id, "aaa");
+        }
+
+        // 后加入的同优先级规则排在已有规则之后
+        let mut matcher = RuleMatcher::new(vec![rule("first")]);
+        matcher.add_rule(rule("second"));
+        assert_eq!(matcher.matches(&context).unwrap().id, "first");
+    }
+
     #[test]
     fn test_rule_matcher_disabled_rule() {
         let rule = Rule {
             id: "rule1".to_string(),
             name: "Test Rule".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::User(vec!["user1".to_string()])),
+            condition: Arc::new(MatchCondition::User(vec!["user1".to_string()])),
             enabled: false,
         };
 
@@ -1900,7 +3658,7 @@ mod tests {
             id: "rule1".to_string(),
             name: "Test Rule".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::User(vec!["user1".to_string()])),
+            condition: Arc::new(MatchCondition::User(vec!["user1".to_string()])),
             enabled: true,
         };
 
@@ -1923,7 +3681,7 @@ mod tests {
             id: "rule1".to_string(),
             name: "Rule 1".to_string(),
             priority: 100,
-            condition: Box::new(MatchCondition::User(vec!["user1".to_string()])),
+            condition: Arc::new(MatchCondition::User(vec!["user1".to_string()])),
             enabled: true,
         };
 
@@ -1941,8 +3699,8 @@ mod tests {
     fn test_composite_condition_and() {
         let condition = CompositeCondition {
             conditions: vec![
-                Box::new(MatchCondition::User(vec!["user1".to_string()])),
-                Box::new(MatchCondition::Geo(vec!["US".to_string()])),
+                Arc::new(MatchCondition::User(vec!["user1".to_string()])),
+                Arc::new(MatchCondition::Geo(vec!["US".to_string()])),
             ],
             operator: LogicalOperator::And,
         };
@@ -1962,8 +3720,8 @@ mod tests {
     fn test_composite_condition_or() {
         let condition = CompositeCondition {
             conditions: vec![
-                Box::new(MatchCondition::User(vec!["user1".to_string()])),
-                Box::new(MatchCondition::User(vec!["user2".to_string()])),
+                Arc::new(MatchCondition::User(vec!["user1".to_string()])),
+                Arc::new(MatchCondition::User(vec!["user2".to_string()])),
             ],
             operator: LogicalOperator::Or,
         };
@@ -1981,7 +3739,7 @@ mod tests {
     #[test]
     fn test_composite_condition_not() {
         let condition = CompositeCondition {
-            conditions: vec![Box::new(MatchCondition::User(vec!["user1".to_string()]))],
+            conditions: vec![Arc::new(MatchCondition::User(vec!["user1".to_string()]))],
             operator: LogicalOperator::Not,
         };
 
@@ -1992,9 +3750,105 @@ mod tests {
         assert!(!condition.evaluate(&context2));
     }
 
+    /// 返回一个计数的叶子条件：命中 `X-Special: yes` 时为真
+    fn counting_leaf(calls: &Arc<std::sync::atomic::AtomicUsize>) -> Arc<dyn ConditionEvaluator> {
+        let calls = calls.clone();
+        Arc::new(MatchCondition::Custom(Arc::new(
+            move |context: &RequestContext| -> bool {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                context.get_header("X-Special").is_some_and(|v| v == "yes")
+            },
+        )))
+    }
+
+    #[test]
+    fn test_composite_condition_shared_leaf_evaluated_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let leaf = counting_leaf(&calls);
+        let user: Arc<dyn ConditionEvaluator> =
+            Arc::new(MatchCondition::User(vec!["user1".to_string()]));
+
+        // (leaf AND user) OR (NOT user AND leaf) OR leaf
+        let condition = CompositeCondition {
+            conditions: vec![
+                Arc::new(CompositeCondition {
+                    conditions: vec![leaf.clone(), user.clone()],
+                    operator: LogicalOperator::And,
+                }),
+                Arc::new(CompositeCondition {
+                    conditions: vec![
+                        Arc::new(CompositeCondition {
+                            conditions: vec![user.clone()],
+                            operator: LogicalOperator::Not,
+                        }),
+                        leaf.clone(),
+                    ],
+                    operator: LogicalOperator::And,
+                }),
+                leaf.clone(),
+            ],
+            operator: LogicalOperator::Or,
+        };
+
+        let context = RequestContext::new()
+            .with_header("X-User-Id", "user2")
+            .with_header("X-Special", "yes");
+        assert!(condition.evaluate(&context));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let context = RequestContext::new()
+            .with_header("X-User-Id", "user2")
+            .with_header("X-Special", "no");
+        assert!(!condition.evaluate(&context));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 短路：AND 的第一个子条件为假时不再评估后续条件
+        let cache = EvaluationCache::new();
+        let short_circuit = CompositeCondition {
+            conditions: vec![user.clone(), leaf.clone()],
+            operator: LogicalOperator::And,
+        };
+        assert!(!short_circuit.evaluate_with_cache(&context, &cache));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_rule_matcher_shared_condition_evaluated_once_per_request() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let leaf = counting_leaf(&calls);
+        let rule = |id: &str, priority: u16| Rule {
+            id: id.to_string(),
+            name: id.to_string(),
+            priority,
+            condition: Arc::new(CompositeCondition {
+                conditions: vec![leaf.clone()],
+                operator: LogicalOperator::And,
+            }),
+            enabled: true,
+        };
+        let matcher = RuleMatcher::new(vec![rule("a", 10), rule("b", 20), rule("c", 30)]);
+
+        let context = RequestContext::new().with_header("X-Special", "yes");
+        assert_eq!(matcher.match_all(&context).len(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 缓存不跨请求复用
+        assert_eq!(matcher.match_all(&context).len(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let context = RequestContext::new().with_header("X-Special", "no");
+        assert!(matcher.matches(&context).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
     #[test]
     fn test_custom_condition() {
-        let condition: Box<dyn ConditionEvaluator> = Box::new(MatchCondition::Custom(Arc::new(
+        let condition: Arc<dyn ConditionEvaluator> = Arc::new(MatchCondition::Custom(Arc::new(
             |context: &RequestContext| -> bool {
                 context.get_header("X-Special").is_some_and(|v| v == "yes")
             },
@@ -2007,6 +3861,67 @@ mod tests {
         assert!(!condition.evaluate(&context2));
     }
 
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_path_regex_condition() {
+        let condition = MatchCondition::path_regex("^/admin/.*").unwrap();
+
+        assert!(condition.evaluate(&RequestContext::new().with_path("/admin/users")));
+        assert!(!condition.evaluate(&RequestContext::new().with_path("/api/admin/users")));
+        assert!(!condition.evaluate(&RequestContext::new().with_path("/admin")));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_header_regex_condition() {
+        let condition = MatchCondition::header_regex("User-Agent", r"(?i)bot|crawler").unwrap();
+
+        let context1 = RequestContext::new().with_header("User-Agent", "Googlebot/2.1");
+        assert!(condition.evaluate(&context1));
+
+        let context2 = RequestContext::new().with_header("User-Agent", "Mozilla/5.0");
+        assert!(!condition.evaluate(&context2));
+
+        // HTTP头缺失时不匹配
+        assert!(!condition.evaluate(&RequestContext::new()));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex_condition_from_config() {
+        let config = vec![
+            ConfigMatcher::PathRegex {
+                pattern: "^/admin/".to_string(),
+            },
+            ConfigMatcher::HeaderRegex {
+                name: "X-Client".to_string(),
+                pattern: "^mobile-".to_string(),
+            },
+        ];
+        let matcher = RuleMatcher::from_config(&config).unwrap();
+
+        let context = RequestContext::new().with_path("/admin/settings");
+        assert_eq!(matcher.match_all(&context).len(), 1);
+
+        let context = RequestContext::new()
+            .with_path("/admin/settings")
+            .with_header("X-Client", "mobile-ios");
+        assert_eq!(matcher.match_all(&context).len(), 2);
+
+        // 无效的正则表达式返回配置错误
+        let invalid = vec![ConfigMatcher::PathRegex {
+            pattern: "^/admin/(".to_string(),
+        }];
+        assert!(matches!(
+            RuleMatcher::from_config(&invalid),
+            Err(FlowGuardError::ConfigError(_))
+        ));
+        assert!(matches!(
+            MatchCondition::header_regex("X-Client", "[a-"),
+            Err(FlowGuardError::ConfigError(_))
+        ));
+    }
+
     #[test]
     fn test_identifier_key() {
         let user_id = Identifier::UserId("user123".to_string());
@@ -2032,6 +3947,64 @@ mod tests {
             Identifier::DeviceId("test".to_string()).type_name(),
             "device_id"
         );
+        assert_eq!(Identifier::custom("tenant", "test").type_name(), "custom");
+        assert_eq!(
+            Identifier::custom("tenant", "test").custom_kind(),
+            Some("tenant")
+        );
+        assert_eq!(Identifier::Ip("test".to_string()).custom_kind(), None);
+    }
+
+    #[test]
+    fn test_custom_identifier_key_space() {
+        let tenant = Identifier::custom("tenant", "acme");
+        assert_eq!(tenant.as_str(), "acme");
+        assert_eq!(tenant.key(), "custom:tenant:acme");
+
+        // 同值的内置类型与自定义类型键名不同
+        let user = Identifier::UserId("acme".to_string());
+        assert_ne!(tenant.key(), user.key());
+        assert_ne!(tenant, user);
+
+        // 即使自定义类型名与内置类型同名也不冲突
+        let spoofed = Identifier::custom("user_id", "acme");
+        assert_eq!(spoofed.custom_kind(), Some("user_id"));
+        assert_ne!(spoofed.key(), user.key());
+
+        assert_ne!(tenant.key(), Identifier::custom("session", "acme").key());
+
+        // 类型名称中的分隔符被转义，不会与其他组合冲突
+        let nested = Identifier::custom("a:b", "c");
+        assert_eq!(nested.key(), "custom:a%3Ab:c");
+        assert_ne!(nested.key(), Identifier::custom("a", "b:c").key());
+        assert_ne!(
+            Identifier::custom("a%3Ab", "c").key(),
+            Identifier::custom("a:b", "c").key()
+        );
+    }
+
+    #[test]
+    fn test_extractors_produce_custom_identifiers() {
+        let context = RequestContext::new()
+            .with_header("X-Tenant-Id", "acme")
+            .with_body(br#"{"session": "s-42"}"#.to_vec());
+
+        let tenant = CustomExtractor::new("tenant", |ctx: &RequestContext| {
+            ctx.headers
+                .get("x-tenant-id")
+                .map(|tenant| Identifier::custom("tenant", tenant.as_str()))
+        });
+        assert_eq!(
+            tenant.extract(&context),
+            Some(Identifier::custom("tenant", "acme"))
+        );
+
+        let session =
+            JsonBodyExtractor::new("/session", |value| Identifier::custom("session", value));
+        assert_eq!(
+            session.extract(&context),
+            Some(Identifier::custom("session", "s-42"))
+        );
     }
 }
 
@@ -2047,5 +4020,11 @@ pub use geo::{GeoCacheStats, GeoCondition, GeoInfo, GeoMatcher};
 #[cfg(feature = "device-matching")]
 pub use device::{DeviceCacheStats, DeviceCondition, DeviceInfo, DeviceMatcher, DeviceType};
 
+// Cron 时间窗口匹配器
+#[cfg(feature = "cron")]
+pub use cron::CronTimeWindowMatcher;
+
 // 自定义匹配器
-pub use custom::{CustomMatcher, CustomMatcherRegistry, HeaderMatcher, TimeWindowMatcher};
+pub use custom::{
+    CustomMatcher, CustomMatcherCondition, CustomMatcherRegistry, HeaderMatcher, TimeWindowMatcher,
+};
@@ -0,0 +1,428 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! tower 中间件模块
+//!
+//! 提供与具体 Web 框架无关的 [`GovernorService`]：通过 [`RequestContextBuilder`]
+//! 把任意请求类型转换为 [`RequestContext`]，调用 [`Governor::check_with_limits`]，
+//! 拒绝时由 [`ResponseBuilder`] 构造响应并短路，不调用内部服务。
+//! hyper、tonic、warp 等基于 tower 的框架都可以直接使用，axum 集成也构建于此之上。
+
+use crate::error::{Decision, FlowGuardError};
+use crate::governor::Governor;
+use crate::limiters::RateLimitDecision;
+use crate::matchers::RequestContext;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// 从请求构建 [`RequestContext`]
+///
+/// 接收请求所有权并原样返回，便于需要拆分请求（如 `http::Request::into_parts`）的实现；
+/// 任何 `Fn(&Req) -> RequestContext` 闭包都自动实现该 trait。
+pub trait RequestContextBuilder<Req> {
+    /// 构建请求上下文
+    fn build(&self, request: Req) -> (Req, RequestContext);
+}
+
+impl<F, Req> RequestContextBuilder<Req> for F
+where
+    F: Fn(&Req) -> RequestContext,
+{
+    fn build(&self, request: Req) -> (Req, RequestContext) {
+        let context = self(&request);
+        (request, context)
+    }
+}
+
+/// 请求未通过检查的原因
+#[derive(Debug)]
+pub enum GuardRejection {
+    /// 被限流拒绝或封禁
+    Denied {
+        /// Governor 的决策（`Rejected` 或 `Banned`）
+        decision: Decision,
+        /// 拒绝节点的详细决策
+        limits: Option<RateLimitDecision>,
+    },
+    /// 限流检查失败
+    Error(FlowGuardError),
+}
+
+/// 构造响应
+///
+/// 任何 `Fn(GuardRejection) -> Res` 闭包都自动实现该 trait。
+pub trait ResponseBuilder<Res> {
+    /// 构造拒绝响应
+    fn rejected(&self, rejection: GuardRejection) -> Res;
+
+    /// 请求被允许时修饰内部服务返回的响应（如附加额度响应头），默认不做处理
+    fn allowed(&self, _response: &mut Res, _limits: &RateLimitDecision) {}
+}
+
+impl<F, Res> ResponseBuilder<Res> for F
+where
+    F: Fn(GuardRejection) -> Res,
+{
+    fn rejected(&self, rejection: GuardRejection) -> Res {
+        self(rejection)
+    }
+}
+
+/// 生成 [`GovernorService`] 的 tower 中间件层
+pub struct GovernorServiceLayer<C, R> {
+    governor: Arc<Governor>,
+    context_builder: Arc<C>,
+    response_builder: Arc<R>,
+}
+
+impl<C, R> Clone for GovernorServiceLayer<C, R> {
+    fn clone(&self) -> Self {
+        Self {
+            governor: self.governor.clone(),
+            context_builder: self.context_builder.clone(),
+            response_builder: self.response_builder.clone(),
+        }
+    }
+}
+
+impl<C, R> GovernorServiceLayer<C, R> {
+    /// 创建新的中间件层
+    ///
+    /// # 参数
+    /// - `governor`: 流量控制器
+    /// - `context_builder`: 从请求构建 [`RequestContext`]
+    /// - `response_builder`: 构造拒绝响应
+    pub fn new(governor: Arc<Governor>, context_builder: C, response_builder: R) -> Self {
+        Self {
+            governor,
+            context_builder: Arc::new(context_builder),
+            response_builder: Arc::new(response_builder),
+        }
+    }
+
+    /// 替换请求上下文构建方式
+    pub fn with_context_builder<C2>(self, context_builder: C2) -> GovernorServiceLayer<C2, R> {
+        GovernorServiceLayer {
+            governor: self.governor,
+            context_builder: Arc::new(context_builder),
+            response_builder: self.response_builder,
+        }
+    }
+}
+
+impl<S, C, R> Layer<S> for GovernorServiceLayer<C, R> {
+    type Service = GovernorService<S, C, R>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GovernorService {
+            inner,
+            governor: self.governor.clone(),
+            context_builder: self.context_builder.clone(),
+            response_builder: self.response_builder.clone(),
+        }
+    }
+}
+
+/// 与框架无关的限流服务
+///
+/// # 示例
+/// ```rust,no_run
+/// use limiteron::governor::Governor;
+/// use limiteron::matchers::RequestContext;
+/// use limiteron::middleware::{GovernorService, GuardRejection};
+/// use std::sync::Arc;
+///
+/// # fn demo<S>(governor: Arc<Governor>, inner: S) {
+/// let service = GovernorService::new(
+///     inner,
+///     governor,
+///     |user: &String| RequestContext::new().with_header("X-User-Id", user),
+///     |_rejection: GuardRejection| "rate limited".to_string(),
+/// );
+/// # }
+/// ```
+pub struct GovernorService<S, C, R> {
+    inner: S,
+    governor: Arc<Governor>,
+    context_builder: Arc<C>,
+    response_builder: Arc<R>,
+}
+
+impl<S: Clone, C, R> Clone for GovernorService<S, C, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            governor: self.governor.clone(),
+            context_builder: self.context_builder.clone(),
+            response_builder: self.response_builder.clone(),
+        }
+    }
+}
+
+impl<S, C, R> GovernorService<S, C, R> {
+    /// 创建新的限流服务
+    pub fn new(inner: S, governor: Arc<Governor>, context_builder: C, response_builder: R) -> Self {
+        GovernorServiceLayer::new(governor, context_builder, response_builder).layer(inner)
+    }
+
+    /// 获取内部服务
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S, C, R, Req> Service<Req> for GovernorService<S, C, R>
+where
+    S: Service<Req> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    C: RequestContextBuilder<Req>,
+    R: ResponseBuilder<S::Response> + Send + Sync + 'static,
+    Req: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Req) -> Self::Future {
+        // 使用已就绪的服务处理本次请求，留下克隆供下次使用
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let governor = self.governor.clone();
+        let response_builder = self.response_builder.clone();
+        let (request, context) = self.context_builder.build(request);
+
+        Box::pin(async move {
+            match governor.check_with_limits(&context).await {
+                Ok((Decision::Delayed(delay), limits)) => {
+                    // 软限流：等待后放行；等待期间 Future 被丢弃时请求随之取消
+                    tokio::time::sleep(delay).await;
+                    let mut response = inner.call(request).await?;
+                    if let Some(limits) = limits {
+                        response_builder.allowed(&mut response, &limits);
+                    }
+                    Ok(response)
+                }
+                Ok((Decision::Allowed(_), limits)) => {
+                    let mut response = inner.call(request).await?;
+                    if let Some(limits) = limits {
+                        response_builder.allowed(&mut response, &limits);
+                    }
+                    Ok(response)
+                }
+                Ok((decision, limits)) => {
+                    Ok(response_builder.rejected(GuardRejection::Denied { decision, limits }))
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "请求限流检查失败");
+                    Ok(response_builder.rejected(GuardRejection::Error(e)))
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ActionConfig, FlowControlConfig, GlobalConfig, LimiterConfig, Matcher, Rule,
+    };
+    use crate::storage::MemoryStorage;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 模拟内部服务：记录调用次数并回显请求
+    #[derive(Clone, Default)]
+    struct MockService {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Service<String> for MockService {
+        type Response = String;
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<String, Infallible>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: String) -> Self::Future {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { Ok(format!("ok:{}", request)) })
+        }
+    }
+
+    async fn create_governor() -> Arc<Governor> {
+        create_governor_with_action(ActionConfig {
+            on_exceed: "reject".to_string(),
+            ban: None,
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn create_governor_with_action(action: ActionConfig) -> Arc<Governor> {
+        let config = FlowControlConfig {
+            version: "1.0".to_string(),
+            global: GlobalConfig {
+                storage: "memory".to_string(),
+                cache: "memory".to_string(),
+                metrics: "prometheus".to_string(),
+            },
+            rules: vec![Rule {
+                id: "rule".to_string(),
+                name: "Rule".to_string(),
+                priority: 10,
+                matchers: vec![Matcher::User {
+                    user_ids: vec!["*".to_string()],
+                }],
+                limiters: vec![LimiterConfig::FixedWindow {
+                    window_size: "60s".to_string(),
+                    max_requests: 2,
+                }],
+                action,
+                ..Default::default()
+            }],
+        };
+
+        Arc::new(
+            Governor::new(
+                config,
+                Arc::new(MemoryStorage::new()),
+                Arc::new(MemoryStorage::new()),
+                #[cfg(feature = "monitoring")]
+                None,
+                #[cfg(feature = "telemetry")]
+                None,
+            )
+            .await
+            .unwrap(),
+        )
+    }
+
+    fn user_context() -> impl Fn(&String) -> RequestContext {
+        |user: &String| RequestContext::new().with_header("X-User-Id", user)
+    }
+
+    fn reject_response(rejection: GuardRejection) -> String {
+        match rejection {
+            GuardRejection::Denied { limits, .. } => {
+                format!("denied:{}", limits.map(|l| l.limit).unwrap_or_default())
+            }
+            GuardRejection::Error(_) => "error".to_string(),
+        }
+    }
+
+    #[cfg(feature = "soft-limit")]
+    #[tokio::test]
+    async fn test_service_waits_on_delayed_decision() {
+        use std::time::{Duration, Instant};
+
+        let inner = MockService::default();
+        let calls = inner.calls.clone();
+        let mut service = GovernorService::new(
+            inner,
+            create_governor_with_action(ActionConfig {
+                on_exceed: "delay".to_string(),
+                delay_ms: Some(100),
+                ..Default::default()
+            })
+            .await,
+            user_context(),
+            reject_response,
+        );
+
+        service.call("alice".to_string()).await.unwrap();
+        service.call("alice".to_string()).await.unwrap();
+
+        // 超限的请求等待后转发
+        let started = Instant::now();
+        assert_eq!(service.call("alice".to_string()).await.unwrap(), "ok:alice");
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // 等待期间被取消的请求不会转发
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(50), service.call("alice".to_string()))
+                .await;
+        assert!(cancelled.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_service_forwards_then_short_circuits() {
+        let inner = MockService::default();
+        let calls = inner.calls.clone();
+        let mut service = GovernorService::new(
+            inner,
+            create_governor().await,
+            user_context(),
+            reject_response,
+        );
+
+        assert_eq!(service.call("alice".to_string()).await.unwrap(), "ok:alice");
+        assert_eq!(service.call("alice".to_string()).await.unwrap(), "ok:alice");
+        assert_eq!(service.call("alice".to_string()).await.unwrap(), "denied:2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 其他用户不受影响
+        assert_eq!(service.call("bob".to_string()).await.unwrap(), "ok:bob");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_service_rejects_requests_without_identifier() {
+        let inner = MockService::default();
+        let calls = inner.calls.clone();
+        // 无法提取标识符时 Governor 以 NoIdentifier 拒绝
+        let mut service = GovernorService::new(
+            inner,
+            create_governor().await,
+            |_: &String| RequestContext::new(),
+            reject_response,
+        );
+
+        assert_eq!(service.call("anon".to_string()).await.unwrap(), "denied:0");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    /// 允许时在响应中追加剩余额度
+    struct AnnotatingBuilder;
+
+    impl ResponseBuilder<String> for AnnotatingBuilder {
+        fn rejected(&self, rejection: GuardRejection) -> String {
+            reject_response(rejection)
+        }
+
+        fn allowed(&self, response: &mut String, limits: &RateLimitDecision) {
+            response.push_str(&format!(" remaining={}", limits.remaining));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layer_applies_allowed_hook() {
+        let layer =
+            GovernorServiceLayer::new(create_governor().await, user_context(), AnnotatingBuilder);
+        let mut service = layer.layer(MockService::default());
+
+        assert_eq!(
+            service.call("carol".to_string()).await.unwrap(),
+            "ok:carol remaining=1"
+        );
+        assert_eq!(
+            service.call("carol".to_string()).await.unwrap(),
+            "ok:carol remaining=0"
+        );
+        assert_eq!(service.call("carol".to_string()).await.unwrap(), "denied:2");
+    }
+}
@@ -59,27 +59,37 @@ impl ParallelBanChecker {
         // 等待所有检查完成
         let results = join_all(check_futures).await;
 
-        // 查找第一个封禁结果
+        // 查找第一个封禁结果；没有封禁时返回遇到的第一个存储错误
+        let mut first_error = None;
         for (target, ban_result) in results {
-            if let Ok(Some(detail)) = ban_result {
-                if detail.expires_at > chrono::Utc::now() {
-                    let duration = start.elapsed();
-                    debug!(
-                        "发现活跃封禁: 目标={:?}, 原因={}, 耗时={:?}",
-                        target, detail.reason, duration
-                    );
-
-                    return Ok(Some(BanInfo {
-                        reason: detail.reason.clone(),
-                        banned_until: detail.expires_at,
-                        ban_times: detail.ban_times,
-                    }));
+            let detail = match ban_result {
+                Ok(Some(detail)) => detail,
+                Ok(None) => continue,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    continue;
                 }
+            };
+            if detail.expires_at > chrono::Utc::now() {
+                let duration = start.elapsed();
+                debug!(
+                    "发现活跃封禁: 目标={:?}, 原因={}, 耗时={:?}",
+                    target, detail.reason, duration
+                );
+
+                return Ok(Some(BanInfo {
+                    reason: detail.reason.clone(),
+                    banned_until: detail.expires_at,
+                    ban_times: detail.ban_times,
+                }));
             }
         }
 
         debug!("并行封禁检查完成，总耗时: {:?}", start.elapsed());
-        Ok(None)
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
     }
 
     /// 快速检查单个封禁目标
@@ -55,6 +55,18 @@
 //! CREATE INDEX idx_kv_expires
 //!     ON kv_store(expires_at)
 //!     WHERE expires_at IS NOT NULL;
+//!
+//! -- 限流计数表（主键即 (key, window_start) 索引）
+//! CREATE TABLE rate_limit_counters (
+//!     key VARCHAR(255) NOT NULL,
+//!     window_start BIGINT NOT NULL,
+//!     count BIGINT NOT NULL DEFAULT 0,
+//!     expires_at TIMESTAMPTZ NOT NULL,
+//!     PRIMARY KEY (key, window_start)
+//! );
+//!
+//! CREATE INDEX idx_rate_limit_expires
+//!     ON rate_limit_counters(expires_at);
 //! ```
 
 #[cfg(feature = "postgres")]
@@ -293,6 +305,134 @@ impl PostgresStorage {
 
         Ok(updated)
     }
+
+    /// 清理过期的限流计数
+    pub async fn cleanup_expired_rate_limits(&self) -> Result<u64, StorageError> {
+        let result = sqlx::query("DELETE FROM rate_limit_counters WHERE expires_at < now()")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::QueryError(format!("清理过期限流计数失败: {}", e)))?;
+
+        let deleted = result.rows_affected();
+        if deleted > 0 {
+            debug!("清理了 {} 条过期的限流计数记录", deleted);
+        }
+
+        Ok(deleted)
+    }
+
+    /// 执行滑动窗口限流
+    ///
+    /// 采用滑动窗口计数器近似：上一个窗口的计数按剩余重叠比例加权后与当前窗口计数相加。
+    /// 整个检查为一条 `INSERT ... ON CONFLICT DO UPDATE` 语句，一次往返完成。
+    ///
+    /// 返回 `(是否允许, 当前计数, 窗口重置时间戳(毫秒))`，与 Redis 实现一致。
+    pub async fn sliding_window(
+        &self,
+        key: &str,
+        window_size: Duration,
+        max_requests: u64,
+    ) -> Result<(bool, u64, i64), StorageError> {
+        let window_size_ms = window_millis(window_size)?;
+
+        let (allowed, count, reset_time) = sqlx::query_as::<_, (bool, i64, i64)>(
+            r#"
+            WITH now_ms AS (
+                SELECT ms, ms - ms % $2 AS window_start
+                FROM (SELECT (extract(epoch FROM clock_timestamp()) * 1000)::BIGINT AS ms) AS t
+            ),
+            previous AS (
+                SELECT COALESCE((
+                    SELECT p.count * ($2 - (n.ms - n.window_start))::FLOAT8 / $2
+                    FROM rate_limit_counters p, now_ms n
+                    WHERE p.key = $1 AND p.window_start = n.window_start - $2
+                ), 0) AS weighted
+            ),
+            upsert AS (
+                INSERT INTO rate_limit_counters AS c (key, window_start, count, expires_at)
+                SELECT $1, n.window_start, 1, to_timestamp((n.window_start + $2 * 2) / 1000.0)
+                FROM now_ms n, previous
+                WHERE previous.weighted + 1 <= $3
+                ON CONFLICT (key, window_start) DO UPDATE
+                    SET count = c.count + 1
+                    WHERE (SELECT weighted FROM previous) + c.count + 1 <= $3
+                RETURNING c.count
+            )
+            SELECT
+                EXISTS (SELECT 1 FROM upsert) AS allowed,
+                COALESCE(
+                    (SELECT count FROM upsert) + floor((SELECT weighted FROM previous))::BIGINT,
+                    $3
+                ) AS count,
+                (SELECT window_start FROM now_ms) + $2 AS reset_time
+            "#,
+        )
+        .bind(key)
+        .bind(window_size_ms)
+        .bind(max_requests.min(i64::MAX as u64) as i64)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::QueryError(format!("滑动窗口限流失败: {}", e)))?;
+
+        Ok((allowed, count.max(0) as u64, reset_time))
+    }
+
+    /// 执行固定窗口限流
+    ///
+    /// 按 `window_size` 对齐时间桶，计数通过一条 `INSERT ... ON CONFLICT DO UPDATE`
+    /// 原子递增；超出上限时不再递增。
+    ///
+    /// 返回 `(是否允许, 当前计数, 窗口重置时间戳(毫秒))`，与 Redis 实现一致。
+    pub async fn fixed_window(
+        &self,
+        key: &str,
+        window_size: Duration,
+        max_requests: u64,
+    ) -> Result<(bool, u64, i64), StorageError> {
+        let window_size_ms = window_millis(window_size)?;
+
+        let (allowed, count, reset_time) = sqlx::query_as::<_, (bool, i64, i64)>(
+            r#"
+            WITH bucket AS (
+                SELECT ms - ms % $2 AS window_start
+                FROM (SELECT (extract(epoch FROM clock_timestamp()) * 1000)::BIGINT AS ms) AS t
+            ),
+            upsert AS (
+                INSERT INTO rate_limit_counters AS c (key, window_start, count, expires_at)
+                SELECT $1, window_start, 1, to_timestamp((window_start + $2 * 2) / 1000.0)
+                FROM bucket
+                WHERE $3 > 0
+                ON CONFLICT (key, window_start) DO UPDATE
+                    SET count = c.count + 1
+                    WHERE c.count < $3
+                RETURNING c.count
+            )
+            SELECT
+                EXISTS (SELECT 1 FROM upsert) AS allowed,
+                COALESCE((SELECT count FROM upsert), $3) AS count,
+                (SELECT window_start FROM bucket) + $2 AS reset_time
+            "#,
+        )
+        .bind(key)
+        .bind(window_size_ms)
+        .bind(max_requests.min(i64::MAX as u64) as i64)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::QueryError(format!("固定窗口限流失败: {}", e)))?;
+
+        Ok((allowed, count.max(0) as u64, reset_time))
+    }
+}
+
+/// 将窗口大小转换为毫秒，窗口不能小于 1 毫秒
+fn window_millis(window_size: Duration) -> Result<i64, StorageError> {
+    let ms = window_size.as_millis();
+    if ms == 0 {
+        return Err(StorageError::ValidationError(
+            "窗口大小必须至少为1毫秒".to_string(),
+        ));
+    }
+    Ok(ms.min(i64::MAX as u128) as i64)
 }
 
 #[async_trait]
@@ -446,6 +586,7 @@ impl QuotaStorage for PostgresStorage {
                         allowed: false,
                         remaining: 0,
                         alert_triggered: true,
+                        crossed_threshold: None,
                     });
                 }
 
@@ -483,6 +624,7 @@ impl QuotaStorage for PostgresStorage {
                         allowed: false,
                         remaining: limit,
                         alert_triggered: true,
+                        crossed_threshold: None,
                     });
                 }
 
@@ -515,6 +657,7 @@ impl QuotaStorage for PostgresStorage {
             allowed,
             remaining,
             alert_triggered: consumed > limit,
+            crossed_threshold: None,
         })
     }
 
@@ -822,6 +965,15 @@ mod tests {
     use super::*;
     use crate::storage::{BanStorage, QuotaStorage, Storage as StorageTrait};
 
+    #[test]
+    fn test_window_millis() {
+        assert_eq!(window_millis(Duration::from_secs(1)).unwrap(), 1000);
+        assert!(matches!(
+            window_millis(Duration::from_micros(999)),
+            Err(StorageError::ValidationError(_))
+        ));
+    }
+
     #[tokio::test]
     #[ignore] // 需要真实的PostgreSQL连接
     async fn test_postgres_storage_set_get() {
@@ -4,7 +4,7 @@
 //!
 //! 配额控制器模块
 //!
-//! 实现配额控制功能，支持多种配额类型、滑动窗口重置、透支功能和告警机制。
+//! 实现配额控制功能，支持多种配额类型、滑动窗口重置、透支、未用配额结转和告警机制。
 
 /// 默认配额限制
 pub const DEFAULT_QUOTA_LIMIT: u64 = 1000;
@@ -18,11 +18,17 @@ pub const DEFAULT_DEDUP_WINDOW_SECS: u64 = 300;
 /// 默认透支限制百分比
 pub const DEFAULT_OVERDRAFT_LIMIT_PERCENT: u8 = 20;
 
+/// 默认预留超时（5分钟）
+pub const DEFAULT_RESERVATION_TIMEOUT_SECS: u64 = 300;
+
+use crate::clock::elapsed_utc;
 use crate::error::{ConsumeResult, FlowGuardError};
-use crate::storage::QuotaStorage;
+use crate::storage::{CompositeConsumeResult, QuotaStorage};
+use ahash::AHashMap;
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
 use std::time::Duration as StdDuration;
 
@@ -75,6 +81,66 @@ pub struct QuotaConfig {
     pub overdraft_limit_percent: u8,
     /// 告警配置
     pub alert_config: AlertConfig,
+    /// 未用配额结转配置（None 表示每个窗口从零开始）
+    #[serde(default)]
+    pub rollover: Option<RolloverConfig>,
+    /// 按操作计费的权重表，供 [`QuotaController::consume_weighted`] 使用
+    #[serde(default)]
+    pub cost_weights: CostWeights,
+}
+
+/// 操作权重表
+///
+/// 将操作名映射为单次调用消耗的配额数量，未登记的操作按 `default_cost` 计费。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg(feature = "quota-control")]
+pub struct CostWeights {
+    /// 未登记操作的默认消耗
+    #[serde(default = "default_weight_cost")]
+    pub default_cost: u64,
+    /// 操作名到消耗数量的映射
+    #[serde(default)]
+    pub weights: AHashMap<String, u64>,
+}
+
+fn default_weight_cost() -> u64 {
+    1
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        Self {
+            default_cost: default_weight_cost(),
+            weights: AHashMap::new(),
+        }
+    }
+}
+
+impl CostWeights {
+    /// 登记操作权重
+    pub fn with_weight(mut self, operation: impl Into<String>, cost: u64) -> Self {
+        self.weights.insert(operation.into(), cost);
+        self
+    }
+
+    /// 查询操作的消耗数量
+    pub fn cost_of(&self, operation: &str) -> u64 {
+        self.weights
+            .get(operation)
+            .copied()
+            .unwrap_or(self.default_cost)
+    }
+}
+
+/// 配额结转配置
+///
+/// 窗口重置时，上一窗口未用完的配额计入下一窗口的上限，结转量不超过
+/// 基础配额的 `max_carry_percent`。结转只作用于基础配额，透支额度仍按基础配额单独计算。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg(feature = "quota-control")]
+pub struct RolloverConfig {
+    /// 最大结转量（基础配额的百分比）
+    pub max_carry_percent: u8,
 }
 
 impl Default for QuotaConfig {
@@ -86,6 +152,8 @@ impl Default for QuotaConfig {
             allow_overdraft: false,
             overdraft_limit_percent: DEFAULT_OVERDRAFT_LIMIT_PERCENT,
             alert_config: AlertConfig::default(),
+            rollover: None,
+            cost_weights: CostWeights::default(),
         }
     }
 }
@@ -96,11 +164,11 @@ impl Default for QuotaConfig {
 pub struct AlertConfig {
     /// 是否启用告警
     pub enabled: bool,
-    /// 告警阈值（百分比）
+    /// 告警阈值（百分比），每个阈值在同一配额窗口内最多触发一次
     pub thresholds: Vec<u8>,
     /// 告警渠道
     pub channels: Vec<AlertChannel>,
-    /// 告警去重时间窗口（秒）
+    /// 告警去重时间窗口（秒），用于抑制跨配额窗口的重复告警
     pub dedup_window: u64,
 }
 
@@ -123,6 +191,9 @@ pub enum AlertChannel {
     Log,
     /// Webhook 告警
     Webhook { url: String },
+    /// 带自定义请求头和重试策略的 Webhook 告警
+    #[cfg(feature = "webhook-alerts")]
+    CustomWebhook(crate::webhook_alert::WebhookAlertChannel),
 }
 
 /// 告警信息
@@ -155,6 +226,22 @@ pub struct QuotaState {
     pub window_start: DateTime<Utc>,
     /// 窗口结束时间
     pub window_end: DateTime<Utc>,
+    /// 本窗口从上一窗口结转的配额
+    #[serde(default)]
+    pub carried_over: u64,
+    /// 本窗口内已触发的告警阈值
+    #[serde(default)]
+    pub fired_thresholds: Vec<u8>,
+}
+
+/// 某个用户资源在一个窗口内已触发的告警阈值
+#[cfg(feature = "quota-control")]
+#[derive(Debug, Clone)]
+struct FiredThresholds {
+    /// 记录所属窗口的结束时间
+    window_end: DateTime<Utc>,
+    /// 已触发的阈值
+    thresholds: Vec<u8>,
 }
 
 /// 配额控制器
@@ -166,6 +253,12 @@ pub struct QuotaController<S: QuotaStorage> {
     config: QuotaConfig,
     /// 告警去重缓存（key: user_id:resource:threshold, value: last_alert_time）
     alert_dedup: Arc<DashMap<String, DateTime<Utc>>>,
+    /// 当前窗口已触发的告警阈值（key: user_id:resource）
+    fired_thresholds: Arc<DashMap<String, FiredThresholds>>,
+    /// 下次清理已结束窗口的阈值记录的时间（毫秒时间戳）
+    next_fired_sweep: Arc<AtomicI64>,
+    /// 操作权重表（可在运行时热更新，克隆出的控制器共享同一份）
+    cost_weights: Arc<parking_lot::RwLock<CostWeights>>,
 }
 
 impl<S: QuotaStorage + Clone + 'static> Clone for QuotaController<S> {
@@ -174,6 +267,9 @@ impl<S: QuotaStorage + Clone + 'static> Clone for QuotaController<S> {
             storage: self.storage.clone(),
             config: self.config.clone(),
             alert_dedup: self.alert_dedup.clone(),
+            fired_thresholds: self.fired_thresholds.clone(),
+            next_fired_sweep: self.next_fired_sweep.clone(),
+            cost_weights: self.cost_weights.clone(),
         }
     }
 }
@@ -197,17 +293,70 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
     ///     allow_overdraft: true,
     ///     overdraft_limit_percent: 20,
     ///     alert_config: Default::default(),
+    ///     rollover: None,
+    ///     cost_weights: Default::default(),
     /// };
     /// let controller = QuotaController::new(MockQuotaStorage, config);
     /// ```
     pub fn new(storage: S, config: QuotaConfig) -> Self {
         Self {
             storage: Arc::new(storage),
+            cost_weights: Arc::new(parking_lot::RwLock::new(config.cost_weights.clone())),
             config,
             alert_dedup: Arc::new(DashMap::new()),
+            fired_thresholds: Arc::new(DashMap::new()),
+            next_fired_sweep: Arc::new(AtomicI64::new(0)),
         }
     }
 
+    /// 按操作权重消费配额
+    ///
+    /// 从权重表中查出 `operation` 的消耗数量后调用 [`consume`](Self::consume)，
+    /// 未登记的操作按 [`CostWeights::default_cost`] 计费。
+    ///
+    /// # 示例
+    /// ```rust
+    /// # use limiteron::quota_controller::{CostWeights, QuotaController, QuotaConfig};
+    /// # use limiteron::storage::MockQuotaStorage;
+    /// #
+    /// let config = QuotaConfig {
+    ///     cost_weights: CostWeights::default().with_weight("search", 10),
+    ///     ..Default::default()
+    /// };
+    /// let controller = QuotaController::new(MockQuotaStorage, config);
+    /// assert_eq!(controller.weighted_cost("search"), 10);
+    /// assert_eq!(controller.weighted_cost("read"), 1);
+    /// # async {
+    /// let result = controller.consume_weighted("user123", "api", "search").await.unwrap();
+    /// # };
+    /// ```
+    pub async fn consume_weighted(
+        &self,
+        user_id: &str,
+        resource: &str,
+        operation: &str,
+    ) -> Result<ConsumeResult, FlowGuardError> {
+        let cost = self.weighted_cost(operation);
+        self.consume(user_id, resource, cost).await
+    }
+
+    /// 查询操作在当前权重表中的消耗数量
+    pub fn weighted_cost(&self, operation: &str) -> u64 {
+        self.cost_weights.read().cost_of(operation)
+    }
+
+    /// 获取当前生效的权重表
+    pub fn cost_weights(&self) -> CostWeights {
+        self.cost_weights.read().clone()
+    }
+
+    /// 热更新权重表
+    ///
+    /// 只需共享引用，克隆出的控制器会同时生效；已有的配额状态不受影响。
+    pub fn update_cost_weights(&self, weights: CostWeights) {
+        *self.cost_weights.write() = weights;
+    }
+
     /// 消费配额
     ///
     /// # 参数
@@ -243,6 +392,7 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
                 allowed: true,
                 remaining: self.config.limit,
                 alert_triggered: false,
+                crossed_threshold: None,
             });
         }
 
@@ -252,23 +402,8 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
         // 检查窗口是否需要重置
         let updated_state = self.check_and_reset_window(quota_state).await?;
 
-        // 计算可透支上限（使用 checked_mul 防止整数溢出）
-        let overdraft_limit = if self.config.allow_overdraft {
-            self.config
-                .limit
-                .checked_mul(self.config.overdraft_limit_percent as u64)
-                .and_then(|v| v.checked_div(100))
-                .unwrap_or(u64::MAX / 2) // 如果溢出，使用安全值
-        } else {
-            0
-        };
-
-        // 计算总限制（使用 checked_add 防止整数溢出）
-        let total_limit = self
-            .config
-            .limit
-            .checked_add(overdraft_limit)
-            .unwrap_or(u64::MAX / 2); // 如果溢出，使用安全值
+        // 计算总限制：基础配额 + 结转 + 透支
+        let total_limit = self.total_limit(updated_state.carried_over);
 
         // 检查是否超过总限制
         if updated_state.consumed + cost > total_limit {
@@ -276,6 +411,7 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
                 allowed: false,
                 remaining: total_limit.saturating_sub(updated_state.consumed),
                 alert_triggered: false,
+                crossed_threshold: None,
             });
         }
 
@@ -290,14 +426,113 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
         let remaining = total_limit.saturating_sub(new_consumed);
 
         // 检查告警
-        let alert_triggered = self
-            .check_and_trigger_alert(user_id, resource, new_consumed)
+        let crossed_threshold = self
+            .check_and_trigger_alert(user_id, resource, new_consumed, updated_state.window_end)
             .await?;
 
         Ok(ConsumeResult {
             allowed: true,
             remaining,
-            alert_triggered,
+            alert_triggered: crossed_threshold.is_some(),
+            crossed_threshold,
+        })
+    }
+
+    /// 预留配额
+    ///
+    /// 立即扣减配额并返回一个预留，操作成功后调用 [`QuotaReservation::commit`]，
+    /// 失败时调用 [`QuotaReservation::rollback`] 退还配额。预留在被丢弃时自动回滚，
+    /// 超过 [`DEFAULT_RESERVATION_TIMEOUT_SECS`] 未处理的预留由存储后端自动回滚。
+    ///
+    /// # 返回
+    /// - `Ok(reservation)`: 预留成功
+    /// - `Err(FlowGuardError::QuotaExceeded)`: 剩余配额不足
+    ///
+    /// # 示例
+    /// ```rust
+    /// # use limiteron::quota_controller::{QuotaController, QuotaConfig};
+    /// # use limiteron::storage::MemoryStorage;
+    /// #
+    /// # let controller = QuotaController::new(MemoryStorage::new(), QuotaConfig::default());
+    /// #
+    /// # async {
+    /// let reservation = controller.reserve("user123", "llm_tokens", 500).await.unwrap();
+    /// // 调用下游服务……
+    /// # let succeeded = true;
+    /// if succeeded {
+    ///     reservation.commit().await.unwrap();
+    /// } else {
+    ///     reservation.rollback().await.unwrap();
+    /// }
+    /// # };
+    /// ```
+    pub async fn reserve(
+        &self,
+        user_id: &str,
+        resource: &str,
+        cost: u64,
+    ) -> Result<QuotaReservation<S>, FlowGuardError> {
+        self.reserve_with_timeout(
+            user_id,
+            resource,
+            cost,
+            StdDuration::from_secs(DEFAULT_RESERVATION_TIMEOUT_SECS),
+        )
+        .await
+    }
+
+    /// 预留配额，并指定预留超时
+    pub async fn reserve_with_timeout(
+        &self,
+        user_id: &str,
+        resource: &str,
+        cost: u64,
+        timeout: StdDuration,
+    ) -> Result<QuotaReservation<S>, FlowGuardError> {
+        let quota_state = self.get_or_create_quota_state(user_id, resource).await?;
+        let updated_state = self.check_and_reset_window(quota_state).await?;
+        let total_limit = self.total_limit(updated_state.carried_over);
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let result = self
+            .storage
+            .reserve(
+                user_id,
+                resource,
+                &id,
+                cost,
+                total_limit,
+                StdDuration::from_secs(self.config.window_size),
+                timeout,
+            )
+            .await?;
+
+        if !result.allowed {
+            return Err(FlowGuardError::QuotaExceeded(format!(
+                "user_id={}, resource={}, cost={}, remaining={}",
+                user_id, resource, cost, result.remaining
+            )));
+        }
+
+        // 预留的配额已计入用量，与直接消费一样检查告警阈值
+        let crossed_threshold = self
+            .check_and_trigger_alert(
+                user_id,
+                resource,
+                total_limit.saturating_sub(result.remaining),
+                updated_state.window_end,
+            )
+            .await?;
+
+        Ok(QuotaReservation {
+            storage: self.storage.clone(),
+            user_id: user_id.to_string(),
+            resource: resource.to_string(),
+            id,
+            cost,
+            remaining: result.remaining,
+            crossed_threshold,
+            finished: false,
         })
     }
 
@@ -323,10 +558,26 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
             .map_err(FlowGuardError::StorageError)?;
 
         if let Some(info) = quota_info {
+            // 存储中的 limit 为本窗口的总限制，扣除基础配额和透支即为结转量
+            let carried_over = if self.config.rollover.is_some() {
+                info.limit
+                    .saturating_sub(self.total_limit(0))
+                    .min(self.max_carry())
+            } else {
+                0
+            };
+            let fired_thresholds = self
+                .fired_thresholds
+                .get(&Self::state_key(user_id, resource))
+                .filter(|fired| fired.window_end > Utc::now())
+                .map(|fired| fired.thresholds.clone())
+                .unwrap_or_default();
             Ok(Some(QuotaState {
                 consumed: info.consumed,
                 window_start: info.window_start,
                 window_end: info.window_end,
+                carried_over,
+                fired_thresholds,
             }))
         } else {
             Ok(None)
@@ -352,10 +603,17 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
             )
             .await
             .map_err(FlowGuardError::StorageError)?;
+        self.fired_thresholds
+            .remove(&Self::state_key(user_id, resource));
 
         Ok(())
     }
 
+    /// 告警阈值跟踪使用的键
+    fn state_key(user_id: &str, resource: &str) -> String {
+        format!("{}:{}", user_id, resource)
+    }
+
     /// 获取或创建配额状态
     async fn get_or_create_quota_state(
         &self,
@@ -375,6 +633,8 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
             consumed: 0,
             window_start,
             window_end,
+            carried_over: 0,
+            fired_thresholds: Vec::new(),
         })
     }
 
@@ -420,13 +680,65 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
             (state.consumed as f64 * (1.0 - window_progress)) as u64
         };
 
+        // 结转上一窗口未用的基础配额（中间有整窗未使用时按整窗未用计算）
+        let carried_over = if self.config.rollover.is_some() {
+            let unused = if windows_passed >= 2 {
+                self.config.limit
+            } else {
+                self.config
+                    .limit
+                    .saturating_add(state.carried_over)
+                    .saturating_sub(state.consumed)
+            };
+            unused.min(self.max_carry())
+        } else {
+            0
+        };
+
         Ok(QuotaState {
             consumed: retained_consumed,
             window_start: new_window_start,
             window_end: new_window_end,
+            carried_over,
+            fired_thresholds: Vec::new(),
         })
     }
 
+    /// 透支额度（基础配额的百分比）
+    fn overdraft_limit(&self) -> u64 {
+        if self.config.allow_overdraft {
+            self.config
+                .limit
+                .checked_mul(self.config.overdraft_limit_percent as u64)
+                .and_then(|v| v.checked_div(100))
+                .unwrap_or(u64::MAX / 2) // 如果溢出，使用安全值
+        } else {
+            0
+        }
+    }
+
+    /// 最大结转量（基础配额的百分比）
+    fn max_carry(&self) -> u64 {
+        match self.config.rollover {
+            Some(rollover) => self
+                .config
+                .limit
+                .checked_mul(rollover.max_carry_percent as u64)
+                .and_then(|v| v.checked_div(100))
+                .unwrap_or(u64::MAX / 2),
+            None => 0,
+        }
+    }
+
+    /// 总限制：基础配额 + 结转 + 透支（防止整数溢出）
+    fn total_limit(&self, carried_over: u64) -> u64 {
+        self.config
+            .limit
+            .checked_add(carried_over)
+            .and_then(|v| v.checked_add(self.overdraft_limit()))
+            .unwrap_or(u64::MAX / 2)
+    }
+
     /// 保存配额状态
     async fn save_quota_state(
         &self,
@@ -436,22 +748,7 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
         new_consumed: u64,
     ) -> Result<(), FlowGuardError> {
         // 使用存储的 consume 方法更新配额
-        // 计算总限制（防止整数溢出）
-        let overdraft_limit = if self.config.allow_overdraft {
-            self.config
-                .limit
-                .checked_mul(self.config.overdraft_limit_percent as u64)
-                .and_then(|v| v.checked_div(100))
-                .unwrap_or(u64::MAX / 2)
-        } else {
-            0
-        };
-
-        let total_limit = self
-            .config
-            .limit
-            .checked_add(overdraft_limit)
-            .unwrap_or(u64::MAX / 2);
+        let total_limit = self.total_limit(state.carried_over);
 
         let _result = self
             .storage
@@ -469,15 +766,20 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
     }
 
     /// 检查并触发告警
+    ///
+    /// 每个阈值在同一窗口内最多触发一次；返回本次新跨越的最高阈值。
+    /// `window_end` 为当前窗口的结束时间，窗口变化时该键已触发的阈值重新计算。
     async fn check_and_trigger_alert(
         &self,
         user_id: &str,
         resource: &str,
         consumed: u64,
-    ) -> Result<bool, FlowGuardError> {
+        window_end: DateTime<Utc>,
+    ) -> Result<Option<u8>, FlowGuardError> {
         if !self.config.alert_config.enabled {
-            return Ok(false);
+            return Ok(None);
         }
+        self.sweep_fired_thresholds();
 
         // 计算使用率
         let usage_percent = if self.config.limit > 0 {
@@ -486,47 +788,78 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
             100
         };
 
-        let mut alert_triggered = false;
+        // 找出本窗口内首次跨越的阈值（未跨越任何阈值时不创建记录）
+        let crossed: Vec<u8> = {
+            let reached = self
+                .config
+                .alert_config
+                .thresholds
+                .iter()
+                .any(|&threshold| usage_percent >= threshold);
+            if !reached {
+                Vec::new()
+            } else {
+                let mut fired = self
+                    .fired_thresholds
+                    .entry(Self::state_key(user_id, resource))
+                    .or_insert_with(|| FiredThresholds {
+                        window_end,
+                        thresholds: Vec::new(),
+                    });
+                if fired.window_end != window_end {
+                    // 新窗口开始，已触发的告警阈值重新计算
+                    fired.window_end = window_end;
+                    fired.thresholds.clear();
+                }
+                let mut crossed = Vec::new();
+                for &threshold in &self.config.alert_config.thresholds {
+                    if usage_percent >= threshold && !fired.thresholds.contains(&threshold) {
+                        fired.thresholds.push(threshold);
+                        crossed.push(threshold);
+                    }
+                }
+                crossed
+            }
+        };
+
+        let mut crossed_threshold = None;
 
-        // 检查每个告警阈值
-        for &threshold in &self.config.alert_config.thresholds {
-            if usage_percent >= threshold {
-                // 检查是否需要去重
-                let dedup_key = format!("{}:{}:{}", user_id, resource, threshold);
+        for threshold in crossed {
+            // 检查是否需要去重
+            let dedup_key = format!("{}:{}:{}", user_id, resource, threshold);
 
-                let should_alert = {
-                    if let Some(last_alert_time) = self.alert_dedup.get(&dedup_key) {
-                        let elapsed = Utc::now().signed_duration_since(*last_alert_time);
-                        elapsed.num_seconds() as u64 >= self.config.alert_config.dedup_window
-                    } else {
-                        true
-                    }
+            let should_alert = {
+                if let Some(last_alert_time) = self.alert_dedup.get(&dedup_key) {
+                    let elapsed = elapsed_utc(*last_alert_time, Utc::now());
+                    elapsed.as_secs() >= self.config.alert_config.dedup_window
+                } else {
+                    true
+                }
+            };
+
+            if should_alert {
+                // 创建告警信息
+                let alert_info = AlertInfo {
+                    user_id: user_id.to_string(),
+                    resource: resource.to_string(),
+                    quota_type: self.config.quota_type,
+                    threshold,
+                    current_usage: consumed,
+                    limit: self.config.limit,
+                    triggered_at: Utc::now(),
                 };
 
-                if should_alert {
-                    // 创建告警信息
-                    let alert_info = AlertInfo {
-                        user_id: user_id.to_string(),
-                        resource: resource.to_string(),
-                        quota_type: self.config.quota_type,
-                        threshold,
-                        current_usage: consumed,
-                        limit: self.config.limit,
-                        triggered_at: Utc::now(),
-                    };
-
-                    // 异步发送告警
-                    self.send_alert(alert_info).await;
-
-                    // 更新去重缓存
-                    self.alert_dedup.insert(dedup_key, Utc::now());
-
-                    alert_triggered = true;
-                }
+                // 异步发送告警
+                self.send_alert(alert_info).await;
+
+                // 更新去重缓存
+                self.alert_dedup.insert(dedup_key, Utc::now());
+
+                crossed_threshold = crossed_threshold.max(Some(threshold));
             }
         }
 
-        Ok(alert_triggered)
+        Ok(crossed_threshold)
     }
 
     /// 发送告警
@@ -556,6 +889,12 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
                             tracing::error!(error = %e, "发送 Webhook 告警失败");
                         }
                     }
+                    #[cfg(feature = "webhook-alerts")]
+                    AlertChannel::CustomWebhook(webhook) => {
+                        if let Err(e) = webhook.send(&alert_info).await {
+                            tracing::error!(url = %webhook.url, error = %e, "发送 Webhook 告警失败");
+                        }
+                    }
                 }
             });
         }
@@ -568,9 +907,36 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
 
     /// 更新配置
     pub fn update_config(&mut self, config: QuotaConfig) {
+        self.update_cost_weights(config.cost_weights.clone());
         self.config = config;
     }
 
+    /// 清理窗口已结束的阈值记录
+    ///
+    /// 每个窗口周期最多清理一次，避免不再访问的键一直占用内存。
+    fn sweep_fired_thresholds(&self) {
+        let now = Utc::now();
+        let now_ms = now.timestamp_millis();
+        let next_sweep = self.next_fired_sweep.load(Ordering::Relaxed);
+        if now_ms < next_sweep {
+            return;
+        }
+        let period_ms = (self.config.window_size as i64).saturating_mul(1000).max(1);
+        if self
+            .next_fired_sweep
+            .compare_exchange(
+                next_sweep,
+                now_ms.saturating_add(period_ms),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            self.fired_thresholds
+                .retain(|_, fired| fired.window_end > now);
+        }
+    }
+
     /// 清理过期的告警去重记录
     pub fn cleanup_alert_dedup(&self) {
         let now = Utc::now();
@@ -582,6 +948,221 @@ impl<S: QuotaStorage + 'static> QuotaController<S> {
     }
 }
 
+/// 配额预留
+///
+/// 由 [`QuotaController::reserve`] 创建。预留的配额已计入消费量，
+/// 必须调用 [`commit`](Self::commit) 或 [`rollback`](Self::rollback) 之一结束；
+/// 两者都未调用就被丢弃时，在后台自动回滚。
+#[cfg(feature = "quota-control")]
+#[must_use = "预留在丢弃时会被回滚，请调用 commit 或 rollback"]
+pub struct QuotaReservation<S: QuotaStorage + 'static> {
+    storage: Arc<S>,
+    user_id: String,
+    resource: String,
+    id: String,
+    cost: u64,
+    remaining: u64,
+    crossed_threshold: Option<u8>,
+    /// 是否已提交或回滚
+    finished: bool,
+}
+
+impl<S: QuotaStorage + 'static> std::fmt::Debug for QuotaReservation<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuotaReservation")
+            .field("user_id", &self.user_id)
+            .field("resource", &self.resource)
+            .field("id", &self.id)
+            .field("cost", &self.cost)
+            .field("remaining", &self.remaining)
+            .field("crossed_threshold", &self.crossed_threshold)
+            .finish()
+    }
+}
+
+impl<S: QuotaStorage + 'static> QuotaReservation<S> {
+    /// 预留ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 预留数量
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    /// 预留后的剩余配额
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// 预留时跨越的最高告警阈值（百分比）
+    pub fn crossed_threshold(&self) -> Option<u8> {
+        self.crossed_threshold
+    }
+
+    /// 提交预留，保留已扣减的配额
+    ///
+    /// 预留已超时（配额已被退还）时返回 `FlowGuardError::QuotaExceeded`。
+    /// 存储出错时预留仍未结束，丢弃时按未提交处理自动回滚。
+    pub async fn commit(mut self) -> Result<(), FlowGuardError> {
+        let committed = self
+            .storage
+            .commit_reservation(&self.user_id, &self.resource, &self.id)
+            .await?;
+        self.finished = true;
+        if !committed {
+            return Err(FlowGuardError::QuotaExceeded(format!(
+                "预留已超时: id={}, user_id={}, resource={}",
+                self.id, self.user_id, self.resource
+            )));
+        }
+        Ok(())
+    }
+
+    /// 回滚预留，退还已扣减的配额
+    pub async fn rollback(mut self) -> Result<(), FlowGuardError> {
+        self.storage
+            .rollback_reservation(&self.user_id, &self.resource, &self.id)
+            .await?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl<S: QuotaStorage + 'static> Drop for QuotaReservation<S> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let storage = self.storage.clone();
+        let user_id = std::mem::take(&mut self.user_id);
+        let resource = std::mem::take(&mut self.resource);
+        let id = std::mem::take(&mut self.id);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = storage.rollback_reservation(&user_id, &resource, &id).await {
+                        tracing::warn!(error = %e, id = %id, "自动回滚配额预留失败");
+                    }
+                });
+            }
+            Err(_) => {
+                tracing::warn!(id = %id, "不在 Tokio 运行时中，配额预留将在超时后回滚");
+            }
+        }
+    }
+}
+
+/// 组合配额
+///
+/// 多个资源共享一个总额度，每个资源另有各自的子额度。每次消费都通过
+/// [`QuotaStorage::consume_composite`] 原子地同时检查并扣减总用量与资源用量，
+/// 任一额度不足即拒绝，因此并发消费不同资源也不会使总用量超出上限。
+///
+/// # 示例
+/// ```rust
+/// use limiteron::quota_controller::CompositeQuota;
+/// use limiteron::storage::MemoryStorage;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let quota = CompositeQuota::new(Arc::new(MemoryStorage::new()), "llm", 1000, Duration::from_secs(3600))
+///     .with_resource_limit("gpt", 800)
+///     .with_resource_limit("embedding", 300);
+/// assert_eq!(quota.resource_limit("gpt"), 800);
+/// ```
+#[cfg(feature = "quota-control")]
+pub struct CompositeQuota<S: QuotaStorage> {
+    /// 存储后端
+    storage: Arc<S>,
+    /// 组合配额名称，同名配额共享计数
+    name: String,
+    /// 共享总额度
+    total_limit: u64,
+    /// 资源子额度，未配置的资源仅受总额度约束
+    resource_limits: AHashMap<String, u64>,
+    /// 窗口大小
+    window: StdDuration,
+}
+
+impl<S: QuotaStorage> CompositeQuota<S> {
+    /// 创建组合配额
+    pub fn new(
+        storage: Arc<S>,
+        name: impl Into<String>,
+        total_limit: u64,
+        window: StdDuration,
+    ) -> Self {
+        Self {
+            storage,
+            name: name.into(),
+            total_limit,
+            resource_limits: AHashMap::new(),
+            window,
+        }
+    }
+
+    /// 设置资源子额度
+    pub fn with_resource_limit(mut self, resource: impl Into<String>, limit: u64) -> Self {
+        self.resource_limits.insert(resource.into(), limit);
+        self
+    }
+
+    /// 组合配额名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 共享总额度
+    pub fn total_limit(&self) -> u64 {
+        self.total_limit
+    }
+
+    /// 资源子额度，未配置时等于总额度
+    pub fn resource_limit(&self, resource: &str) -> u64 {
+        self.resource_limits
+            .get(resource)
+            .copied()
+            .unwrap_or(self.total_limit)
+    }
+
+    /// 消费资源配额
+    ///
+    /// 被拒绝时总用量与资源用量均不变，`rejected_by` 指出不足的额度。
+    pub async fn consume(
+        &self,
+        user_id: &str,
+        resource: &str,
+        cost: u64,
+    ) -> Result<CompositeConsumeResult, FlowGuardError> {
+        let result = self
+            .storage
+            .consume_composite(
+                user_id,
+                &self.name,
+                resource,
+                cost,
+                self.total_limit,
+                self.resource_limit(resource),
+                self.window,
+            )
+            .await?;
+
+        if !result.allowed {
+            tracing::debug!(
+                "组合配额拒绝: name={}, user_id={}, resource={}, rejected_by={:?}",
+                self.name,
+                user_id,
+                resource,
+                result.rejected_by
+            );
+        }
+        Ok(result)
+    }
+}
+
 /// 发送 Webhook 告警
 ///
 /// 注意：此功能需要启用 `webhook` feature 并添加 `reqwest` 依赖。
@@ -692,6 +1273,7 @@ mod tests {
                     allowed: false,
                     remaining: quota_info.limit - quota_info.consumed,
                     alert_triggered: false,
+                    crossed_threshold: None,
                 });
             }
 
@@ -701,6 +1283,7 @@ mod tests {
                 allowed: true,
                 remaining: quota_info.limit - quota_info.consumed,
                 alert_triggered: false,
+                crossed_threshold: None,
             })
         }
 
@@ -791,6 +1374,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -821,6 +1406,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -850,6 +1437,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -883,6 +1472,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -920,6 +1511,8 @@ mod tests {
                 channels: vec![AlertChannel::Log],
                 dedup_window: DEFAULT_DEDUP_WINDOW_SECS,
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -928,16 +1521,121 @@ mod tests {
         let result = controller.consume("user1", "resource1", 80).await.unwrap();
         assert!(result.allowed);
         assert!(result.alert_triggered);
+        assert_eq!(result.crossed_threshold, Some(80));
 
         // 消费 10 个配额，应该触发 90% 告警
         let result = controller.consume("user1", "resource1", 10).await.unwrap();
         assert!(result.allowed);
         assert!(result.alert_triggered);
+        assert_eq!(result.crossed_threshold, Some(90));
 
         // 消费 10 个配额，应该触发 100% 告警
         let result = controller.consume("user1", "resource1", 10).await.unwrap();
         assert!(result.allowed);
         assert!(result.alert_triggered);
+        assert_eq!(result.crossed_threshold, Some(100));
+    }
+
+    /// 测试多级告警阈值在同一窗口内只触发一次
+    #[tokio::test]
+    async fn test_alert_thresholds_fire_once_per_window() {
+        let config = QuotaConfig {
+            limit: 100,
+            window_size: 1,
+            allow_overdraft: false,
+            alert_config: AlertConfig {
+                enabled: true,
+                thresholds: vec![50, 80, 95],
+                channels: vec![AlertChannel::Log],
+                dedup_window: 0,
+            },
+            ..Default::default()
+        };
+        let controller = QuotaController::new(TestQuotaStorage::new(), config);
+
+        let mut fired = Vec::new();
+        for _ in 0..20 {
+            let result = controller.consume("user1", "resource1", 5).await.unwrap();
+            assert!(result.allowed);
+            assert_eq!(result.alert_triggered, result.crossed_threshold.is_some());
+            fired.extend(result.crossed_threshold);
+        }
+        assert_eq!(fired, vec![50, 80, 95]);
+
+        let state = controller
+            .get_quota("user1", "resource1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.fired_thresholds, vec![50, 80, 95]);
+
+        // 新窗口重新计算
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        let result = controller.consume("user1", "resource1", 50).await.unwrap();
+        assert_eq!(result.crossed_threshold, Some(50));
+    }
+
+    /// 测试窗口结束后不再访问的键的阈值记录会被清理
+    #[tokio::test]
+    async fn test_fired_thresholds_swept_after_window_ends() {
+        let config = QuotaConfig {
+            limit: 100,
+            window_size: 1,
+            alert_config: AlertConfig {
+                enabled: true,
+                thresholds: vec![50],
+                channels: vec![AlertChannel::Log],
+                dedup_window: 0,
+            },
+            ..Default::default()
+        };
+        let controller = QuotaController::new(TestQuotaStorage::new(), config);
+
+        // 未跨越阈值时不创建记录
+        controller.consume("idle", "resource1", 10).await.unwrap();
+        assert!(controller.fired_thresholds.is_empty());
+
+        for user in ["user1", "user2", "user3"] {
+            let result = controller.consume(user, "resource1", 60).await.unwrap();
+            assert_eq!(result.crossed_threshold, Some(50));
+        }
+        assert_eq!(controller.fired_thresholds.len(), 3);
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        let result = controller.consume("user4", "resource1", 60).await.unwrap();
+        assert_eq!(result.crossed_threshold, Some(50));
+        assert_eq!(controller.fired_thresholds.len(), 1);
+        assert!(controller.fired_thresholds.contains_key("user4:resource1"));
+    }
+
+    /// 测试一次消费跨越多个阈值时返回最高阈值
+    #[tokio::test]
+    async fn test_alert_reports_highest_crossed_threshold() {
+        let config = QuotaConfig {
+            limit: 100,
+            alert_config: AlertConfig {
+                enabled: true,
+                thresholds: vec![50, 80, 95],
+                channels: vec![AlertChannel::Log],
+                dedup_window: 0,
+            },
+            ..Default::default()
+        };
+        let controller = QuotaController::new(TestQuotaStorage::new(), config);
+
+        let result = controller.consume("user1", "resource1", 85).await.unwrap();
+        assert_eq!(result.crossed_threshold, Some(80));
+
+        let result = controller.consume("user1", "resource1", 15).await.unwrap();
+        assert_eq!(result.crossed_threshold, Some(95));
+
+        controller.reset_quota("user1", "resource1").await.unwrap();
+        let state = controller
+            .get_quota("user1", "resource1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(state.fired_thresholds.is_empty());
     }
 
     /// 测试告警去重
@@ -947,7 +1645,7 @@ mod tests {
         let config = QuotaConfig {
             quota_type: QuotaType::Count,
             limit: 100,
-            window_size: 3600,
+            window_size: 2,
             allow_overdraft: false,
             overdraft_limit_percent: 0,
             alert_config: AlertConfig {
@@ -956,6 +1654,8 @@ mod tests {
                 channels: vec![AlertChannel::Log],
                 dedup_window: 5, // 5 秒去重窗口
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -965,19 +1665,25 @@ mod tests {
         assert!(result.allowed);
         assert!(result.alert_triggered);
 
-        // 立即再次消费到 90%，仍然不应该触发告警（去重）
+        // 同一窗口内再次消费到 90%，不应该再次触发告警
         let result = controller.consume("user1", "resource1", 10).await.unwrap();
         assert!(result.allowed);
         assert!(!result.alert_triggered);
 
+        // 进入新窗口，但仍在去重时间窗口内，不应该触发告警
+        tokio::time::sleep(tokio::time::Duration::from_millis(2100)).await;
+        let result = controller.consume("user1", "resource1", 80).await.unwrap();
+        assert!(result.allowed);
+        assert!(!result.alert_triggered);
+
         // 等待去重窗口过期
-        tokio::time::sleep(tokio::time::Duration::from_millis(5100)).await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(3100)).await;
 
         // 清理过期的去重记录
         controller.cleanup_alert_dedup();
 
-        // 再次消费到 95%，应该触发告警
-        let result = controller.consume("user1", "resource1", 5).await.unwrap();
+        // 新窗口再次到达 80%，应该触发告警
+        let result = controller.consume("user1", "resource1", 80).await.unwrap();
         assert!(result.allowed);
         assert!(result.alert_triggered);
     }
@@ -996,6 +1702,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -1023,6 +1731,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = QuotaController::new(storage, config);
@@ -1075,6 +1785,8 @@ mod tests {
                 enabled: false,
                 ..Default::default()
             },
+            rollover: None,
+            cost_weights: Default::default(),
         };
 
         let controller = Arc::new(QuotaController::new(storage, config));
@@ -1130,4 +1842,349 @@ mod tests {
 
         assert_eq!(controller.config().limit, 500);
     }
+
+    fn rollover_config(max_carry_percent: u8, overdraft_percent: u8) -> QuotaConfig {
+        QuotaConfig {
+            limit: 100,
+            window_size: 1,
+            allow_overdraft: overdraft_percent > 0,
+            overdraft_limit_percent: overdraft_percent,
+            alert_config: AlertConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            rollover: Some(RolloverConfig { max_carry_percent }),
+            ..Default::default()
+        }
+    }
+
+    async fn next_window() {
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+    }
+
+    #[tokio::test]
+    async fn test_rollover_carries_unused_across_windows() {
+        let controller = QuotaController::new(TestQuotaStorage::new(), rollover_config(100, 0));
+
+        // 第一个窗口只用 60，剩余 40 结转
+        assert!(
+            controller
+                .consume("user1", "api", 60)
+                .await
+                .unwrap()
+                .allowed
+        );
+
+        next_window().await;
+        let result = controller.consume("user1", "api", 140).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 0);
+        assert!(!controller.consume("user1", "api", 1).await.unwrap().allowed);
+        let state = controller.get_quota("user1", "api").await.unwrap().unwrap();
+        assert_eq!(state.carried_over, 40);
+
+        // 第二个窗口全部用完，第三个窗口没有结转
+        next_window().await;
+        let result = controller.consume("user1", "api", 100).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 0);
+        assert!(!controller.consume("user1", "api", 1).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_cap_and_overdraft() {
+        // 结转上限 20%，透支 10%（按基础配额计算）
+        let controller = QuotaController::new(TestQuotaStorage::new(), rollover_config(20, 10));
+
+        assert!(
+            controller
+                .consume("user1", "api", 10)
+                .await
+                .unwrap()
+                .allowed
+        );
+
+        next_window().await;
+        // 未用 90，但结转被限制为 20：100 + 20 + 10 透支
+        let result = controller.consume("user1", "api", 130).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 0);
+        assert!(!controller.consume("user1", "api", 1).await.unwrap().allowed);
+
+        // 透支部分不会被当作未用配额结转
+        next_window().await;
+        let result = controller.consume("user1", "api", 1).await.unwrap();
+        assert_eq!(result.remaining, 109);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_disabled_by_default() {
+        let config = rollover_config(0, 0);
+        let controller = QuotaController::new(
+            TestQuotaStorage::new(),
+            QuotaConfig {
+                rollover: None,
+                ..config
+            },
+        );
+
+        assert!(
+            controller
+                .consume("user1", "api", 10)
+                .await
+                .unwrap()
+                .allowed
+        );
+        next_window().await;
+        let result = controller.consume("user1", "api", 1).await.unwrap();
+        assert_eq!(result.remaining, 99);
+    }
+
+    #[tokio::test]
+    async fn test_composite_quota_shared_total_rejects_other_resource() {
+        use crate::storage::{CompositeQuotaLimit, MemoryStorage};
+
+        let quota = CompositeQuota::new(
+            Arc::new(MemoryStorage::new()),
+            "llm",
+            100,
+            StdDuration::from_secs(60),
+        )
+        .with_resource_limit("a", 100)
+        .with_resource_limit("b", 50);
+
+        let result = quota.consume("user1", "a", 100).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.total_remaining, 0);
+
+        // b 的子额度还剩 50，但共享总额度已耗尽
+        let result = quota.consume("user1", "b", 1).await.unwrap();
+        assert!(!result.allowed);
+        assert_eq!(result.rejected_by, Some(CompositeQuotaLimit::Total));
+        assert_eq!(result.resource_remaining, 50);
+
+        // 其他用户不受影响
+        assert!(quota.consume("user2", "b", 1).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_composite_quota_resource_limit() {
+        use crate::storage::{CompositeQuotaLimit, MemoryStorage};
+
+        let quota = CompositeQuota::new(
+            Arc::new(MemoryStorage::new()),
+            "llm",
+            100,
+            StdDuration::from_secs(60),
+        )
+        .with_resource_limit("b", 30);
+
+        assert!(quota.consume("user1", "b", 30).await.unwrap().allowed);
+        let result = quota.consume("user1", "b", 1).await.unwrap();
+        assert_eq!(result.rejected_by, Some(CompositeQuotaLimit::Resource));
+        // 被拒绝的消费不计入总用量
+        assert_eq!(result.total_remaining, 70);
+
+        let result = quota.consume("user1", "a", 70).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.total_remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_composite_quota_window_reset() {
+        use crate::storage::MemoryStorage;
+
+        let quota = CompositeQuota::new(
+            Arc::new(MemoryStorage::new()),
+            "llm",
+            10,
+            StdDuration::from_millis(50),
+        );
+
+        assert!(quota.consume("user1", "a", 10).await.unwrap().allowed);
+        assert!(!quota.consume("user1", "b", 1).await.unwrap().allowed);
+        tokio::time::sleep(StdDuration::from_millis(60)).await;
+        assert!(quota.consume("user1", "b", 1).await.unwrap().allowed);
+    }
+
+    fn reservation_controller() -> QuotaController<crate::storage::MemoryStorage> {
+        QuotaController::new(
+            crate::storage::MemoryStorage::new(),
+            QuotaConfig {
+                limit: 100,
+                allow_overdraft: false,
+                ..Default::default()
+            },
+        )
+    }
+
+    async fn consumed(controller: &QuotaController<crate::storage::MemoryStorage>) -> u64 {
+        controller
+            .get_quota("user1", "api")
+            .await
+            .unwrap()
+            .map(|state| state.consumed)
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_reservation_commit() {
+        let controller = reservation_controller();
+
+        let reservation = controller.reserve("user1", "api", 30).await.unwrap();
+        assert_eq!(reservation.remaining(), 70);
+        reservation.commit().await.unwrap();
+        assert_eq!(consumed(&controller).await, 30);
+
+        // 超出剩余配额时预留失败
+        let result = controller.reserve("user1", "api", 71).await;
+        assert!(matches!(result, Err(FlowGuardError::QuotaExceeded(_))));
+        assert_eq!(consumed(&controller).await, 30);
+    }
+
+    #[tokio::test]
+    async fn test_reservation_rollback() {
+        let controller = reservation_controller();
+
+        let reservation = controller.reserve("user1", "api", 60).await.unwrap();
+        // 预留期间配额已被占用
+        assert!(controller.reserve("user1", "api", 50).await.is_err());
+        reservation.rollback().await.unwrap();
+
+        assert_eq!(consumed(&controller).await, 0);
+        controller
+            .reserve("user1", "api", 100)
+            .await
+            .unwrap()
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(consumed(&controller).await, 100);
+    }
+
+    #[tokio::test]
+    async fn test_reservation_dropped_without_commit_rolls_back() {
+        let controller = reservation_controller();
+
+        {
+            let _reservation = controller.reserve("user1", "api", 40).await.unwrap();
+            assert_eq!(consumed(&controller).await, 40);
+        }
+        // 回滚在后台任务中执行
+        tokio::time::sleep(StdDuration::from_millis(20)).await;
+        assert_eq!(consumed(&controller).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reservation_expires_after_timeout() {
+        let controller = reservation_controller();
+
+        let stale = controller
+            .reserve_with_timeout("user1", "api", 80, StdDuration::from_millis(20))
+            .await
+            .unwrap();
+        tokio::time::sleep(StdDuration::from_millis(30)).await;
+
+        // 新的预留先回收超时的预留
+        let fresh = controller.reserve("user1", "api", 90).await.unwrap();
+        fresh.commit().await.unwrap();
+        assert_eq!(consumed(&controller).await, 90);
+
+        assert!(matches!(
+            stale.commit().await,
+            Err(FlowGuardError::QuotaExceeded(_))
+        ));
+        assert_eq!(consumed(&controller).await, 90);
+    }
+
+    #[tokio::test]
+    async fn test_expired_reservation_released_on_usage() {
+        let controller = reservation_controller();
+
+        let _stale = controller
+            .reserve_with_timeout("user1", "api", 80, StdDuration::from_millis(20))
+            .await
+            .unwrap();
+        assert_eq!(consumed(&controller).await, 80);
+        tokio::time::sleep(StdDuration::from_millis(30)).await;
+
+        // 查询用量与直接消费都会先回收超时的预留
+        assert_eq!(consumed(&controller).await, 0);
+        assert!(
+            controller
+                .consume("user1", "api", 90)
+                .await
+                .unwrap()
+                .allowed
+        );
+        assert_eq!(consumed(&controller).await, 90);
+    }
+
+    #[tokio::test]
+    async fn test_reservation_triggers_alert_thresholds() {
+        let controller = reservation_controller();
+
+        let reservation = controller.reserve("user1", "api", 85).await.unwrap();
+        assert_eq!(reservation.crossed_threshold(), Some(80));
+        reservation.commit().await.unwrap();
+
+        // 同一窗口内已触发的阈值不再重复触发
+        let result = controller.consume("user1", "api", 5).await.unwrap();
+        assert_eq!(result.crossed_threshold, Some(90));
+        let state = controller.get_quota("user1", "api").await.unwrap().unwrap();
+        assert_eq!(state.fired_thresholds, vec![80, 90]);
+    }
+
+    #[tokio::test]
+    async fn test_consume_weighted() {
+        let controller = QuotaController::new(
+            crate::storage::MemoryStorage::new(),
+            QuotaConfig {
+                limit: 100,
+                cost_weights: CostWeights {
+                    default_cost: 2,
+                    ..Default::default()
+                }
+                .with_weight("search", 10)
+                .with_weight("read", 1),
+                ..Default::default()
+            },
+        );
+
+        let result = controller
+            .consume_weighted("user1", "api", "search")
+            .await
+            .unwrap();
+        assert!(result.allowed);
+        assert_eq!(consumed(&controller).await, 10);
+
+        controller
+            .consume_weighted("user1", "api", "read")
+            .await
+            .unwrap();
+        assert_eq!(consumed(&controller).await, 11);
+
+        // 未登记的操作按默认消耗计费
+        controller
+            .consume_weighted("user1", "api", "unknown")
+            .await
+            .unwrap();
+        assert_eq!(consumed(&controller).await, 13);
+    }
+
+    #[tokio::test]
+    async fn test_update_cost_weights_is_shared_by_clones() {
+        let controller = reservation_controller();
+        let clone = controller.clone();
+
+        assert_eq!(clone.weighted_cost("search"), 1);
+        controller.update_cost_weights(CostWeights::default().with_weight("search", 25));
+        assert_eq!(clone.weighted_cost("search"), 25);
+
+        clone
+            .consume_weighted("user1", "api", "search")
+            .await
+            .unwrap();
+        assert_eq!(consumed(&controller).await, 25);
+    }
 }
@@ -11,22 +11,28 @@
 //! - **连接池**: 使用ConnectionManager管理连接
 //! - **重试机制**: 指数退避重试，最多3次
 //! - **Lua脚本**: 预加载脚本，原子性操作
-//! - **集群支持**: 支持Redis Cluster
-//! - **降级机制**: Redis故障时自动降级
+//! - **集群支持**: 支持Redis Cluster，按 slot 路由命令与脚本
+//! - **降级机制**: Redis故障时自动降级，后台探测任务在恢复后自动切回
 //!
 
 #[cfg(feature = "redis")]
 use async_trait::async_trait;
-use redis::{aio::ConnectionManager, AsyncCommands, Client};
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::cluster::ClusterClientBuilder;
+use redis::cluster_async::ClusterConnection;
+use redis::{AsyncCommands, Client, Cmd, Pipeline, RedisFuture, Value};
 use secrecy::{ExposeSecret, Secret};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, trace, warn};
 
-use crate::error::{ConsumeResult, StorageError};
+use crate::error::{ConsumeResult, FlowGuardError, StorageError};
 use crate::lua_scripts::{LuaScriptManager, LuaScriptType};
-use crate::storage::{BanRecord, BanStorage, BanTarget, QuotaInfo, QuotaStorage, Storage};
+use crate::storage::{
+    BanRecord, BanStorage, BanTarget, CompositeConsumeResult, CompositeQuotaLimit, QuotaInfo,
+    QuotaStorage, Storage,
+};
 
 // ============================================================================
 // Redis 键验证常量
@@ -104,11 +110,16 @@ pub struct RedisConfig {
     /// 重试初始退避时间
     pub retry_initial_backoff: Duration,
     /// 是否启用集群模式
+    ///
+    /// 启用后 `url` 可包含以逗号分隔的多个种子节点，
+    /// 例如 `redis://10.0.0.1:7000,redis://10.0.0.2:7000`。
     pub cluster_mode: bool,
     /// 连接池大小
     pub pool_size: usize,
     /// 是否启用Lua脚本
     pub enable_lua: bool,
+    /// 降级期间恢复探测的间隔（失败后指数退避）
+    pub recovery_probe_interval: Duration,
 }
 
 impl std::fmt::Debug for RedisConfig {
@@ -124,6 +135,7 @@ impl std::fmt::Debug for RedisConfig {
             .field("cluster_mode", &self.cluster_mode)
             .field("pool_size", &self.pool_size)
             .field("enable_lua", &self.enable_lua)
+            .field("recovery_probe_interval", &self.recovery_probe_interval)
             .finish()
     }
 }
@@ -141,6 +153,9 @@ impl Default for RedisConfig {
             cluster_mode: false,
             pool_size: 10,
             enable_lua: true,
+            recovery_probe_interval: Duration::from_millis(
+                crate::constants::DEFAULT_REDIS_RECOVERY_PROBE_INTERVAL_MS,
+            ),
         }
     }
 }
@@ -213,6 +228,12 @@ impl RedisConfig {
         self.enable_lua = enable;
         self
     }
+
+    /// 设置降级期间恢复探测的间隔
+    pub fn recovery_probe_interval(mut self, interval: Duration) -> Self {
+        self.recovery_probe_interval = interval;
+        self
+    }
 }
 
 /// 重试统计
@@ -273,12 +294,131 @@ impl RetryStats {
     }
 }
 
+/// Redis健康状态快照
+///
+/// 用于服务的 `/healthz` 等端点展示 Redis 状态。
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone)]
+pub struct RedisHealth {
+    /// 是否持有可用连接
+    pub connected: bool,
+    /// 是否处于降级状态
+    pub degraded: bool,
+    /// 进入降级状态的时间（恢复后清空）
+    pub degraded_since: Option<Instant>,
+    /// 最近一次成功 PING 的时间
+    pub last_ping_at: Option<Instant>,
+    /// 最近一次成功 PING 的延迟
+    pub last_ping_latency: Option<Duration>,
+    /// 总重试次数
+    pub total_retries: u64,
+    /// 成功重试次数
+    pub successful_retries: u64,
+    /// 失败重试次数
+    pub failed_retries: u64,
+}
+
+impl RedisHealth {
+    /// 已降级的持续时间
+    pub fn degraded_duration(&self) -> Option<Duration> {
+        self.degraded_since.map(|since| since.elapsed())
+    }
+}
+
+/// [`RedisStorage::check_all`] 中的单个限流器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimiterSpec {
+    /// 滑动窗口（与 [`RedisStorage::sliding_window`] 共用状态）
+    SlidingWindow {
+        key: String,
+        window_size: Duration,
+        max_requests: u64,
+        cost: u64,
+    },
+    /// 固定窗口（与 [`RedisStorage::fixed_window`] 共用状态）
+    FixedWindow {
+        key: String,
+        window_size: Duration,
+        max_requests: u64,
+        cost: u64,
+    },
+    /// 令牌桶（与 [`RedisStorage::token_bucket`] 共用状态）
+    TokenBucket {
+        key: String,
+        capacity: u64,
+        /// 每秒补充的令牌数
+        refill_rate: u64,
+        cost: u64,
+    },
+}
+
+impl LimiterSpec {
+    /// 限流器的键
+    pub fn key(&self) -> &str {
+        match self {
+            LimiterSpec::SlidingWindow { key, .. }
+            | LimiterSpec::FixedWindow { key, .. }
+            | LimiterSpec::TokenBucket { key, .. } => key,
+        }
+    }
+}
+
+/// [`RedisStorage::check_all`] 的组合结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombinedDecision {
+    /// 是否全部通过（只有全部通过时才扣减额度）
+    pub allowed: bool,
+    /// 首个拒绝的限流器在 `specs` 中的下标
+    pub rejected_index: Option<usize>,
+    /// 每个限流器的剩余额度，与 `specs` 顺序一致
+    pub remaining: Vec<u64>,
+}
+
+/// Redis连接（单机或集群）
+///
+/// 集群连接按键所在 slot 路由命令，EVALSHA 会发送到脚本键所在的节点。
+#[derive(Clone)]
+enum RedisConnection {
+    /// 单机连接
+    Single(ConnectionManager),
+    /// 集群连接
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(conn) => conn.get_db(),
+            RedisConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
 /// Redis存储实现
 #[cfg(feature = "redis")]
 #[derive(Clone)]
 pub struct RedisStorage {
     /// 连接管理器
-    conn_manager: Arc<Mutex<Option<ConnectionManager>>>,
+    conn_manager: Arc<Mutex<Option<RedisConnection>>>,
     /// 配置
     config: RedisConfig,
     /// Lua脚本管理器
@@ -287,8 +427,22 @@ pub struct RedisStorage {
     retry_stats: RetryStats,
     /// 降级状态
     degraded: Arc<Mutex<bool>>,
-    /// 最后降级时间
-    last_degraded_at: Arc<Mutex<Option<Instant>>>,
+    /// 进入降级状态的时间
+    degraded_since: Arc<Mutex<Option<Instant>>>,
+    /// 最近一次成功 PING 的时间与延迟
+    last_ping: Arc<Mutex<Option<(Instant, Duration)>>>,
+    /// 恢复探测任务句柄，最后一个克隆释放时终止任务
+    recovery_probe: Option<Arc<RecoveryProbeGuard>>,
+}
+
+/// 恢复探测任务守卫
+struct RecoveryProbeGuard(tokio::task::JoinHandle<()>);
+
+impl Drop for RecoveryProbeGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+        debug!("Redis恢复探测任务已停止");
+    }
 }
 
 impl RedisStorage {
@@ -308,13 +462,15 @@ impl RedisStorage {
             lua_manager,
             retry_stats: RetryStats::default(),
             degraded: Arc::new(Mutex::new(false)),
-            last_degraded_at: Arc::new(Mutex::new(None)),
+            degraded_since: Arc::new(Mutex::new(None)),
+            last_ping: Arc::new(Mutex::new(None)),
+            recovery_probe: None,
         };
 
         // 初始化连接
         storage.connect().await?;
 
-        // 预加载Lua脚本
+        // 预加载Lua脚本（集群模式下 SCRIPT LOAD 会广播到所有节点，包括全部主节点）
         if let Some(lua_manager) = &storage.lua_manager {
             if let Some(conn_manager) = storage.conn_manager.lock().await.as_ref() {
                 let mut conn = conn_manager.clone();
@@ -322,35 +478,106 @@ impl RedisStorage {
             }
         }
 
+        let storage = storage.with_recovery_probe();
+
         info!("Redis存储创建成功");
         Ok(storage)
     }
 
-    /// 检查Redis连接
-    pub async fn ping(&self) -> Result<(), StorageError> {
-        self.execute_with_retry(|| async {
-            let conn_manager = self.conn_manager.lock().await;
-            let conn_manager = conn_manager
-                .as_ref()
-                .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+    /// 启动降级恢复探测任务
+    ///
+    /// 任务持有不含守卫的克隆，因此不会阻止存储被释放。
+    fn with_recovery_probe(mut self) -> Self {
+        let probe = Self {
+            recovery_probe: None,
+            ..self.clone()
+        };
+        let handle = tokio::spawn(async move { probe.run_recovery_probe().await });
+        self.recovery_probe = Some(Arc::new(RecoveryProbeGuard(handle)));
+        self
+    }
 
-            let mut conn = conn_manager.clone();
+    /// 恢复探测循环：降级期间周期性 PING，失败时指数退避
+    async fn run_recovery_probe(self) {
+        let interval = self.config.recovery_probe_interval;
+        let max_backoff = Duration::from_millis(crate::constants::DEFAULT_MAX_BACKOFF_MS);
+        let mut delay = interval;
 
-            let _: String = redis::cmd("PING")
-                .query_async(&mut conn)
-                .await
-                .map_err(|e| {
-                    error!("Redis PING失败: {}", e);
-                    StorageError::QueryError(format!("PING失败: {}", e))
-                })?;
+        loop {
+            tokio::time::sleep(delay).await;
 
-            Ok(())
+            if !self.is_degraded().await {
+                delay = interval;
+                continue;
+            }
+
+            match self.probe_once().await {
+                Ok(()) => {
+                    self.set_degraded(false).await;
+                    delay = interval;
+                }
+                Err(e) => {
+                    delay = delay.saturating_mul(2).min(max_backoff.max(interval));
+                    debug!("Redis恢复探测失败，{:?} 后重试: {}", delay, e);
+                }
+            }
+        }
+    }
+
+    /// 单次恢复探测（不经过重试逻辑，避免污染重试统计）
+    async fn probe_once(&self) -> Result<(), StorageError> {
+        let started = Instant::now();
+        tokio::time::timeout(self.config.io_timeout, async {
+            if self.conn_manager.lock().await.is_none() {
+                self.connect().await?;
+            }
+            self.ping_command().await
         })
         .await
+        .map_err(|_| StorageError::TimeoutError("恢复探测超时".to_string()))??;
+
+        *self.last_ping.lock().await = Some((Instant::now(), started.elapsed()));
+        Ok(())
+    }
+
+    /// 检查Redis连接
+    ///
+    /// 成功时记录本次 PING 的延迟，可通过 [`RedisStorage::health`] 查看。
+    pub async fn ping(&self) -> Result<(), StorageError> {
+        let started = Instant::now();
+        self.execute_with_retry(|| self.ping_command()).await?;
+
+        *self.last_ping.lock().await = Some((Instant::now(), started.elapsed()));
+        Ok(())
+    }
+
+    /// 发送一次 PING 命令
+    async fn ping_command(&self) -> Result<(), StorageError> {
+        let mut conn = self
+            .conn_manager
+            .lock()
+            .await
+            .as_ref()
+            .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?
+            .clone();
+
+        let _: String = redis::cmd("PING")
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| {
+                error!("Redis PING失败: {}", e);
+                StorageError::QueryError(format!("PING失败: {}", e))
+            })?;
+
+        Ok(())
     }
 
     /// 建立连接
     async fn connect(&self) -> Result<(), StorageError> {
+        if self.config.cluster_mode {
+            return self.connect_cluster().await;
+        }
+
         debug!("建立Redis连接");
 
         // 使用安全的 ConnectionInfo 来处理认证
@@ -427,13 +654,55 @@ impl RedisStorage {
             StorageError::ConnectionError(format!("创建Redis连接管理器失败: {}", e))
         })?;
 
-        *self.conn_manager.lock().await = Some(conn_manager);
-        *self.degraded.lock().await = false;
+        *self.conn_manager.lock().await = Some(RedisConnection::Single(conn_manager));
+        self.set_degraded(false).await;
 
         info!("Redis连接建立成功");
         Ok(())
     }
 
+    /// 建立集群连接
+    async fn connect_cluster(&self) -> Result<(), StorageError> {
+        let nodes = Self::seed_nodes(&self.config.url);
+        debug!("建立Redis集群连接, 种子节点: {:?}", nodes);
+
+        let mut builder = ClusterClientBuilder::new(nodes).retries(self.config.max_retries);
+        if let Some(password) = &self.config.password {
+            builder = builder.password(password.expose_secret().clone());
+        }
+
+        let client = builder.build().map_err(|e| {
+            error!("创建Redis集群客户端失败: {}", e);
+            StorageError::ConnectionError(format!("创建Redis集群客户端失败: {}", e))
+        })?;
+
+        let conn = client.get_async_connection().await.map_err(|e| {
+            error!("创建Redis集群连接失败: {}", e);
+            StorageError::ConnectionError(format!("创建Redis集群连接失败: {}", e))
+        })?;
+
+        *self.conn_manager.lock().await = Some(RedisConnection::Cluster(conn));
+        self.set_degraded(false).await;
+
+        info!("Redis集群连接建立成功");
+        Ok(())
+    }
+
+    /// 解析集群种子节点列表（逗号分隔）
+    fn seed_nodes(url: &str) -> Vec<String> {
+        url.split(',')
+            .map(str::trim)
+            .filter(|node| !node.is_empty())
+            .map(|node| {
+                if node.contains("://") {
+                    node.to_string()
+                } else {
+                    format!("redis://{}", node)
+                }
+            })
+            .collect()
+    }
+
     /// 带重试的执行
     async fn execute_with_retry<F, Fut, T>(&self, f: F) -> Result<T, StorageError>
     where
@@ -501,14 +770,18 @@ impl RedisStorage {
 
     /// 设置降级状态
     async fn set_degraded(&self, degraded: bool) {
-        let current = *self.degraded.lock().await;
-        if current != degraded {
-            *self.degraded.lock().await = degraded;
+        let mut current = self.degraded.lock().await;
+        if *current != degraded {
+            *current = degraded;
             if degraded {
-                *self.last_degraded_at.lock().await = Some(Instant::now());
+                *self.degraded_since.lock().await = Some(Instant::now());
                 warn!("Redis存储已降级，将使用备用存储");
             } else {
-                info!("Redis存储已恢复正常");
+                let since = self.degraded_since.lock().await.take();
+                info!(
+                    "Redis存储已恢复正常，降级持续 {:?}",
+                    since.map(|s| s.elapsed()).unwrap_or_default()
+                );
             }
         }
     }
@@ -518,6 +791,25 @@ impl RedisStorage {
         *self.degraded.lock().await
     }
 
+    /// 获取健康状态快照
+    pub async fn health(&self) -> RedisHealth {
+        let connected = self.conn_manager.lock().await.is_some();
+        let degraded = *self.degraded.lock().await;
+        let degraded_since = *self.degraded_since.lock().await;
+        let last_ping = *self.last_ping.lock().await;
+
+        RedisHealth {
+            connected,
+            degraded,
+            degraded_since,
+            last_ping_at: last_ping.map(|(at, _)| at),
+            last_ping_latency: last_ping.map(|(_, latency)| latency),
+            total_retries: self.retry_stats.total_retries(),
+            successful_retries: self.retry_stats.successful_retries(),
+            failed_retries: self.retry_stats.failed_retries(),
+        }
+    }
+
     /// 获取重试统计
     pub fn retry_stats(&self) -> &RetryStats {
         &self.retry_stats
@@ -540,6 +832,7 @@ impl RedisStorage {
             .as_ref()
             .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
 
+        let key = self.rate_limit_key(key);
         let current_timestamp = chrono::Utc::now().timestamp_millis();
         let window_size_ms = window_size.as_millis() as i64;
 
@@ -555,7 +848,7 @@ impl RedisStorage {
                     .execute_script(
                         &mut conn,
                         LuaScriptType::SlidingWindow,
-                        &[key],
+                        &[&key],
                         &[
                             &window_size_ms.to_string(),
                             &max_requests.to_string(),
@@ -585,6 +878,7 @@ impl RedisStorage {
             .as_ref()
             .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
 
+        let key = self.rate_limit_key(key);
         let current_timestamp = chrono::Utc::now().timestamp_millis();
         let window_size_ms = window_size.as_millis() as i64;
 
@@ -600,7 +894,7 @@ impl RedisStorage {
                     .execute_script(
                         &mut conn,
                         LuaScriptType::FixedWindow,
-                        &[key],
+                        &[&key],
                         &[
                             &window_size_ms.to_string(),
                             &max_requests.to_string(),
@@ -665,6 +959,469 @@ impl RedisStorage {
         Ok((allowed, tokens_remaining, refill_time))
     }
 
+    /// 只读检查滑动窗口是否允许 `cost` 个请求，不记录请求
+    ///
+    /// 结果仅供参考，与随后的 [`sliding_window`](Self::sliding_window) 之间不具备原子性。
+    pub async fn sliding_window_would_allow(
+        &self,
+        key: &str,
+        window_size: Duration,
+        max_requests: u64,
+        cost: u64,
+    ) -> Result<bool, StorageError> {
+        let key = self.rate_limit_key(key);
+        let current_timestamp = chrono::Utc::now().timestamp_millis();
+        let window_size_ms = window_size.as_millis() as i64;
+
+        let result: (i32, i64) = self
+            .eval_script(
+                LuaScriptType::SlidingWindowPeek,
+                &[&key],
+                &[
+                    &window_size_ms.to_string(),
+                    &max_requests.to_string(),
+                    &current_timestamp.to_string(),
+                    &cost.to_string(),
+                ],
+            )
+            .await?;
+        Ok(result.0 == 1)
+    }
+
+    /// 只读检查固定窗口是否允许 `cost` 个请求，不增加计数
+    ///
+    /// 结果仅供参考，与随后的 [`fixed_window`](Self::fixed_window) 之间不具备原子性。
+    pub async fn fixed_window_would_allow(
+        &self,
+        key: &str,
+        window_size: Duration,
+        max_requests: u64,
+        cost: u64,
+    ) -> Result<bool, StorageError> {
+        let key = self.rate_limit_key(key);
+        let current_timestamp = chrono::Utc::now().timestamp_millis();
+        let window_size_ms = window_size.as_millis() as i64;
+
+        let result: (i32, i64) = self
+            .eval_script(
+                LuaScriptType::FixedWindowPeek,
+                &[&key],
+                &[
+                    &window_size_ms.to_string(),
+                    &max_requests.to_string(),
+                    &current_timestamp.to_string(),
+                    &cost.to_string(),
+                ],
+            )
+            .await?;
+        Ok(result.0 == 1)
+    }
+
+    /// 只读检查令牌桶是否有 `tokens_requested` 个令牌，不扣除令牌
+    ///
+    /// 结果仅供参考，与随后的 [`token_bucket`](Self::token_bucket) 之间不具备原子性。
+    pub async fn token_bucket_would_allow(
+        &self,
+        key: &str,
+        capacity: u64,
+        refill_rate: u64, // tokens per second
+        tokens_requested: u64,
+    ) -> Result<bool, StorageError> {
+        let current_timestamp = chrono::Utc::now().timestamp_millis();
+        let refill_rate_ms = refill_rate as f64 / 1000.0;
+
+        let result: (i32, i64) = self
+            .eval_script(
+                LuaScriptType::TokenBucketPeek,
+                &[key],
+                &[
+                    &capacity.to_string(),
+                    &refill_rate_ms.to_string(),
+                    &current_timestamp.to_string(),
+                    &tokens_requested.to_string(),
+                ],
+            )
+            .await?;
+        Ok(result.0 == 1)
+    }
+
+    /// 原子地检查一组限流器，只有全部通过时才扣减额度
+    ///
+    /// 依次检查 rate、quota 等多个限流器时，前面的限流器可能已消费额度而后面的限流器拒绝。
+    /// 该方法在单个 Lua 脚本内先判断全部限流器，任一拒绝时不修改任何状态。
+    /// 集群模式下所有键需要位于同一个 slot（使用相同的 hash tag）。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// use limiteron::redis_storage::{LimiterSpec, RedisConfig, RedisStorage};
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> Result<(), limiteron::error::StorageError> {
+    /// let storage = RedisStorage::new(RedisConfig::new("redis://localhost:6379")).await?;
+    /// let decision = storage
+    ///     .check_all(&[
+    ///         LimiterSpec::TokenBucket {
+    ///             key: "{user:1}:rate".to_string(),
+    ///             capacity: 10,
+    ///             refill_rate: 5,
+    ///             cost: 1,
+    ///         },
+    ///         LimiterSpec::FixedWindow {
+    ///             key: "{user:1}:quota".to_string(),
+    ///             window_size: Duration::from_secs(3600),
+    ///             max_requests: 1000,
+    ///             cost: 1,
+    ///         },
+    ///     ])
+    ///     .await?;
+    /// if !decision.allowed {
+    ///     println!("rejected by limiter #{:?}", decision.rejected_index);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_all(&self, specs: &[LimiterSpec]) -> Result<CombinedDecision, StorageError> {
+        if specs.is_empty() {
+            return Ok(CombinedDecision {
+                allowed: true,
+                rejected_index: None,
+                remaining: Vec::new(),
+            });
+        }
+
+        let (keys, args) = self.combined_check_args(
+            specs,
+            chrono::Utc::now().timestamp_millis(),
+            &uuid::Uuid::new_v4().to_string(),
+        )?;
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        let result: Vec<i64> = self
+            .eval_script(LuaScriptType::CombinedCheck, &keys, &args)
+            .await?;
+        Self::parse_combined_result(&result, specs.len())
+    }
+
+    /// 组合检查脚本的键与参数
+    fn combined_check_args(
+        &self,
+        specs: &[LimiterSpec],
+        now_ms: i64,
+        request_id: &str,
+    ) -> Result<(Vec<String>, Vec<String>), StorageError> {
+        let mut keys = Vec::with_capacity(specs.len());
+        let mut args = Vec::with_capacity(2 + specs.len() * 4);
+        args.push(now_ms.to_string());
+        args.push(request_id.to_string());
+
+        for spec in specs {
+            validate_key(spec.key())?;
+            let (kind, a, b, cost) = match spec {
+                LimiterSpec::SlidingWindow {
+                    key,
+                    window_size,
+                    max_requests,
+                    cost,
+                }
+                | LimiterSpec::FixedWindow {
+                    key,
+                    window_size,
+                    max_requests,
+                    cost,
+                } => {
+                    let window_size_ms = window_size.as_millis();
+                    if window_size_ms == 0 {
+                        return Err(StorageError::QueryError(format!(
+                            "限流器 {} 的窗口大小必须至少为 1ms",
+                            key
+                        )));
+                    }
+                    keys.push(self.rate_limit_key(key));
+                    let kind = if matches!(spec, LimiterSpec::SlidingWindow { .. }) {
+                        "sliding_window"
+                    } else {
+                        "fixed_window"
+                    };
+                    (
+                        kind,
+                        window_size_ms.to_string(),
+                        max_requests.to_string(),
+                        *cost,
+                    )
+                }
+                LimiterSpec::TokenBucket {
+                    key,
+                    capacity,
+                    refill_rate,
+                    cost,
+                } => {
+                    // 与 token_bucket 保持一致，令牌桶使用原始键
+                    keys.push(key.clone());
+                    (
+                        "token_bucket",
+                        capacity.to_string(),
+                        (*refill_rate as f64 / 1000.0).to_string(),
+                        *cost,
+                    )
+                }
+            };
+            args.extend([kind.to_string(), a, b, cost.to_string()]);
+        }
+
+        Ok((keys, args))
+    }
+
+    /// 解析组合检查脚本的返回值
+    fn parse_combined_result(
+        result: &[i64],
+        count: usize,
+    ) -> Result<CombinedDecision, StorageError> {
+        if result.len() != count + 2 {
+            return Err(StorageError::QueryError(format!(
+                "组合检查返回值长度异常: 期望 {}，实际 {}",
+                count + 2,
+                result.len()
+            )));
+        }
+        Ok(CombinedDecision {
+            allowed: result[0] == 1,
+            rejected_index: usize::try_from(result[1])
+                .ok()
+                .and_then(|index| index.checked_sub(1)),
+            remaining: result[2..]
+                .iter()
+                .map(|remaining| (*remaining).max(0) as u64)
+                .collect(),
+        })
+    }
+
+    /// 重置滑动窗口，删除窗口内记录的全部请求
+    pub async fn reset_sliding_window(&self, key: &str) -> Result<(), StorageError> {
+        self.delete_keys(&[self.rate_limit_key(key)]).await
+    }
+
+    /// 重置固定窗口，删除当前窗口的计数
+    ///
+    /// `window_size` 需与 [`fixed_window`](Self::fixed_window) 使用的窗口大小一致，
+    /// 以定位脚本派生的 `key:window` 子键。
+    pub async fn reset_fixed_window(
+        &self,
+        key: &str,
+        window_size: Duration,
+    ) -> Result<(), StorageError> {
+        let window_key = Self::fixed_window_key(
+            &self.rate_limit_key(key),
+            window_size,
+            chrono::Utc::now().timestamp_millis(),
+        );
+        self.delete_keys(&[window_key]).await
+    }
+
+    /// 重置令牌桶，下次请求时桶为满
+    pub async fn reset_token_bucket(&self, key: &str) -> Result<(), StorageError> {
+        self.delete_keys(&[key.to_string()]).await
+    }
+
+    /// 固定窗口脚本为 `now_ms` 所在窗口派生的子键
+    fn fixed_window_key(key: &str, window_size: Duration, now_ms: i64) -> String {
+        let window_size_ms = (window_size.as_millis() as i64).max(1);
+        let current_window = now_ms.div_euclid(window_size_ms) * window_size_ms;
+        format!("{}:{}", key, current_window)
+    }
+
+    /// 删除一组键
+    async fn delete_keys(&self, keys: &[String]) -> Result<(), StorageError> {
+        self.execute_with_retry(|| async {
+            let conn_manager = self.conn_manager.lock().await;
+            let conn_manager = conn_manager
+                .as_ref()
+                .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+            let mut conn = conn_manager.clone();
+            let _: () = conn.del(keys).await.map_err(|e| {
+                error!("Redis DEL失败: {}", e);
+                StorageError::QueryError(format!("DEL失败: {}", e))
+            })?;
+
+            debug!("限流状态已重置: keys={:?}", keys);
+            Ok(())
+        })
+        .await
+    }
+
+    /// 只读检查是否还有 `permits` 个分布式并发许可，不登记持有者
+    pub async fn concurrency_would_allow(
+        &self,
+        key: &str,
+        max_concurrent: u64,
+        permits: u64,
+    ) -> Result<bool, StorageError> {
+        validate_key(key)?;
+        let (holders_key, permits_key) = Self::concurrency_keys(key);
+        let current_timestamp = chrono::Utc::now().timestamp_millis();
+
+        let result: (i32, i64) = self
+            .eval_script(
+                LuaScriptType::ConcurrencyPeek,
+                &[&holders_key, &permits_key],
+                &[
+                    &max_concurrent.to_string(),
+                    &permits.to_string(),
+                    &current_timestamp.to_string(),
+                ],
+            )
+            .await?;
+        Ok(result.0 == 1)
+    }
+
+    /// 带重试地执行Lua脚本
+    async fn eval_script<T: redis::FromRedisValue>(
+        &self,
+        script_type: LuaScriptType,
+        keys: &[&str],
+        args: &[&str],
+    ) -> Result<T, StorageError> {
+        let lua_manager = self
+            .lua_manager
+            .as_ref()
+            .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
+
+        self.execute_with_retry(|| async {
+            let conn_manager = self.conn_manager.lock().await;
+            let conn_manager = conn_manager
+                .as_ref()
+                .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+            let mut conn = conn_manager.clone();
+            lua_manager
+                .execute_script(&mut conn, script_type, keys, args)
+                .await
+        })
+        .await
+    }
+
+    /// 获取（或续期）分布式并发许可
+    ///
+    /// # 返回
+    /// - `(acquired, in_flight)`: 是否获取成功，以及当前在途许可数
+    pub async fn concurrency_acquire(
+        &self,
+        key: &str,
+        max_concurrent: u64,
+        permits: u64,
+        lease: Duration,
+        holder_id: &str,
+    ) -> Result<(bool, u64), StorageError> {
+        validate_key(key)?;
+        let lua_manager = self
+            .lua_manager
+            .as_ref()
+            .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
+
+        let (holders_key, permits_key) = Self::concurrency_keys(key);
+        let current_timestamp = chrono::Utc::now().timestamp_millis();
+        let lease_ms = (lease.as_millis() as i64).max(1);
+
+        let result: (i32, i64) = self
+            .execute_with_retry(|| async {
+                let conn_manager = self.conn_manager.lock().await;
+                let conn_manager = conn_manager
+                    .as_ref()
+                    .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+                let mut conn = conn_manager.clone();
+                lua_manager
+                    .execute_script(
+                        &mut conn,
+                        LuaScriptType::ConcurrencyAcquire,
+                        &[&holders_key, &permits_key],
+                        &[
+                            &max_concurrent.to_string(),
+                            &permits.to_string(),
+                            &current_timestamp.to_string(),
+                            &lease_ms.to_string(),
+                            holder_id,
+                        ],
+                    )
+                    .await
+            })
+            .await?;
+
+        Ok((result.0 == 1, result.1.max(0) as u64))
+    }
+
+    /// 释放分布式并发许可
+    ///
+    /// # 返回
+    /// - 释放的许可数（持有者已不存在时为0）
+    pub async fn concurrency_release(
+        &self,
+        key: &str,
+        holder_id: &str,
+    ) -> Result<u64, StorageError> {
+        validate_key(key)?;
+        let lua_manager = self
+            .lua_manager
+            .as_ref()
+            .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
+
+        let (holders_key, permits_key) = Self::concurrency_keys(key);
+
+        let released: i64 = self
+            .execute_with_retry(|| async {
+                let conn_manager = self.conn_manager.lock().await;
+                let conn_manager = conn_manager
+                    .as_ref()
+                    .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+                let mut conn = conn_manager.clone();
+                lua_manager
+                    .execute_script(
+                        &mut conn,
+                        LuaScriptType::ConcurrencyRelease,
+                        &[&holders_key, &permits_key],
+                        &[holder_id],
+                    )
+                    .await
+            })
+            .await?;
+
+        Ok(released.max(0) as u64)
+    }
+
+    /// 生成限流键
+    ///
+    /// 固定窗口脚本会在脚本内派生 `key:window` 子键，集群模式下用 hash tag
+    /// 包裹原始键，保证派生键与声明的键位于同一个 slot。
+    fn rate_limit_key(&self, key: &str) -> String {
+        if self.config.cluster_mode {
+            Self::hash_tag(key)
+        } else {
+            key.to_string()
+        }
+    }
+
+    /// 为键添加 hash tag（已含 hash tag 的键保持不变）
+    fn hash_tag(key: &str) -> String {
+        match key.find('{') {
+            Some(open) if key[open + 1..].find('}').is_some_and(|close| close > 0) => {
+                key.to_string()
+            }
+            _ => format!("{{{}}}", key),
+        }
+    }
+
+    /// 生成并发控制键
+    ///
+    /// 使用 hash tag 保证集群模式下两个键位于同一个 slot。
+    fn concurrency_keys(key: &str) -> (String, String) {
+        (
+            format!("concurrency:{{{}}}:holders", key),
+            format!("concurrency:{{{}}}:permits", key),
+        )
+    }
+
     /// 生成配额键（优化：使用用户级别的 Hash）
     ///
     /// 优化前：quota:user123:resource1 -> Hash {consumed, limit, window_start, window_end}
@@ -678,6 +1435,91 @@ impl RedisStorage {
         format!("quota:{}", user_id)
     }
 
+    /// 当前窗口中已超时的预留数量之和
+    ///
+    /// `raw` 为预留字段的 JSON（预留ID -> [cost, expires_at, window_end]），格式见
+    /// [`QUOTA_RESERVE_SCRIPT`](crate::lua_scripts::QUOTA_RESERVE_SCRIPT)。
+    fn expired_reservation_cost(raw: &str, now: i64, window_end: &str) -> u64 {
+        let Ok(serde_json::Value::Object(reservations)) = serde_json::from_str(raw) else {
+            return 0;
+        };
+        let field = |reservation: &serde_json::Value, index: usize| {
+            reservation.get(index).and_then(|value| match value {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+        reservations
+            .values()
+            .filter(|reservation| {
+                let expired = field(reservation, 1)
+                    .and_then(|expires_at| expires_at.parse::<i64>().ok())
+                    .is_some_and(|expires_at| expires_at <= now);
+                expired && field(reservation, 2).as_deref() == Some(window_end)
+            })
+            .filter_map(|reservation| field(reservation, 0)?.parse::<u64>().ok())
+            .sum()
+    }
+
+    /// 提交或回滚配额预留
+    async fn finish_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+        commit: bool,
+    ) -> Result<bool, StorageError> {
+        let lua_manager = self
+            .lua_manager
+            .as_ref()
+            .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
+
+        let key = Self::quota_key(user_id, resource);
+        let now = chrono::Utc::now().timestamp_millis();
+        let consumed_field = Self::quota_field(resource, "consumed");
+        let window_end_field = Self::quota_field(resource, "window_end");
+        let reservations_field = Self::quota_field(resource, "reservations");
+        let commit = if commit { "1" } else { "0" };
+
+        let finished: i64 = self
+            .execute_with_retry(|| async {
+                let conn_manager = self.conn_manager.lock().await;
+                let conn_manager = conn_manager
+                    .as_ref()
+                    .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+                let mut conn = conn_manager.clone();
+                lua_manager
+                    .execute_script(
+                        &mut conn,
+                        LuaScriptType::QuotaReservationFinish,
+                        &[&key],
+                        &[
+                            reservation_id,
+                            &now.to_string(),
+                            commit,
+                            &consumed_field,
+                            &window_end_field,
+                            &reservations_field,
+                        ],
+                    )
+                    .await
+            })
+            .await?;
+
+        Ok(finished == 1)
+    }
+
+    /// 生成组合配额键
+    fn composite_quota_key(user_id: &str, group: &str) -> String {
+        format!(
+            "quota_composite:{}:{}",
+            sanitize_key_component(user_id),
+            sanitize_key_component(group)
+        )
+    }
+
     /// 生成配额字段名
     fn quota_field(resource: &str, field: &str) -> String {
         format!("{}_{}", resource, field)
@@ -814,6 +1656,7 @@ impl QuotaStorage for RedisStorage {
             let limit_field = Self::quota_field(resource, "limit");
             let window_start_field = Self::quota_field(resource, "window_start");
             let window_end_field = Self::quota_field(resource, "window_end");
+            let reservations_field = Self::quota_field(resource, "reservations");
 
             // 批量获取配额信息（使用 HMGET 减少网络往返）
             let result: Vec<Option<String>> = redis::cmd("HMGET")
@@ -822,6 +1665,7 @@ impl QuotaStorage for RedisStorage {
                 .arg(&limit_field)
                 .arg(&window_start_field)
                 .arg(&window_end_field)
+                .arg(&reservations_field)
                 .query_async(&mut conn)
                 .await
                 .map_err(|e| {
@@ -837,8 +1681,16 @@ impl QuotaStorage for RedisStorage {
             if let (Some(consumed), Some(limit), Some(window_start), Some(window_end)) =
                 (consumed, limit, window_start, window_end)
             {
+                // 已超时但尚未被脚本回滚的预留不计入用量
+                let expired = result[4].as_deref().map_or(0, |raw| {
+                    Self::expired_reservation_cost(
+                        raw,
+                        chrono::Utc::now().timestamp_millis(),
+                        result[3].as_deref().unwrap_or_default(),
+                    )
+                });
                 let quota_info = QuotaInfo {
-                    consumed,
+                    consumed: consumed.saturating_sub(expired),
                     limit,
                     window_start: chrono::DateTime::from_timestamp(window_start / 1000, 0)
                         .unwrap_or_else(chrono::Utc::now),
@@ -880,6 +1732,7 @@ impl QuotaStorage for RedisStorage {
         let limit_field = Self::quota_field(resource, "limit");
         let window_start_field = Self::quota_field(resource, "window_start");
         let window_end_field = Self::quota_field(resource, "window_end");
+        let reservations_field = Self::quota_field(resource, "reservations");
 
         let result: (i32, i64, i64) = self
             .execute_with_retry(|| async {
@@ -904,6 +1757,7 @@ impl QuotaStorage for RedisStorage {
                             &limit_field,
                             &window_start_field,
                             &window_end_field,
+                            &reservations_field,
                         ],
                     )
                     .await
@@ -928,6 +1782,7 @@ impl QuotaStorage for RedisStorage {
             allowed,
             remaining,
             alert_triggered,
+            crossed_threshold: None,
         })
     }
 
@@ -957,6 +1812,155 @@ impl QuotaStorage for RedisStorage {
         })
         .await
     }
+
+    async fn reserve(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+        cost: u64,
+        limit: u64,
+        window: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Result<ConsumeResult, StorageError> {
+        let lua_manager = self
+            .lua_manager
+            .as_ref()
+            .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
+
+        let key = Self::quota_key(user_id, resource);
+        let now = chrono::Utc::now().timestamp_millis();
+        let window_end = now
+            + i64::try_from(window.as_millis())
+                .map_err(|_| StorageError::QueryError("window duration overflow".to_string()))?;
+        let expires_at = now
+            + i64::try_from(timeout.as_millis())
+                .map_err(|_| StorageError::QueryError("timeout duration overflow".to_string()))?;
+
+        let consumed_field = Self::quota_field(resource, "consumed");
+        let limit_field = Self::quota_field(resource, "limit");
+        let window_start_field = Self::quota_field(resource, "window_start");
+        let window_end_field = Self::quota_field(resource, "window_end");
+        let reservations_field = Self::quota_field(resource, "reservations");
+
+        let result: (i32, i64, i64) = self
+            .execute_with_retry(|| async {
+                let conn_manager = self.conn_manager.lock().await;
+                let conn_manager = conn_manager
+                    .as_ref()
+                    .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+                let mut conn = conn_manager.clone();
+                lua_manager
+                    .execute_script(
+                        &mut conn,
+                        LuaScriptType::QuotaReserve,
+                        &[&key],
+                        &[
+                            &cost.to_string(),
+                            &limit.to_string(),
+                            &now.to_string(),
+                            &window_end.to_string(),
+                            &consumed_field,
+                            &limit_field,
+                            &window_start_field,
+                            &window_end_field,
+                            &reservations_field,
+                            reservation_id,
+                            &expires_at.to_string(),
+                        ],
+                    )
+                    .await
+            })
+            .await?;
+
+        Ok(ConsumeResult {
+            allowed: result.0 == 1,
+            remaining: result.1.max(0) as u64,
+            alert_triggered: false,
+            crossed_threshold: None,
+        })
+    }
+
+    async fn commit_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+    ) -> Result<bool, StorageError> {
+        self.finish_reservation(user_id, resource, reservation_id, true)
+            .await
+    }
+
+    async fn rollback_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+    ) -> Result<bool, StorageError> {
+        self.finish_reservation(user_id, resource, reservation_id, false)
+            .await
+    }
+
+    async fn consume_composite(
+        &self,
+        user_id: &str,
+        group: &str,
+        resource: &str,
+        cost: u64,
+        total_limit: u64,
+        resource_limit: u64,
+        window: std::time::Duration,
+    ) -> Result<CompositeConsumeResult, StorageError> {
+        let lua_manager = self
+            .lua_manager
+            .as_ref()
+            .ok_or_else(|| StorageError::QueryError("Lua脚本未启用".to_string()))?;
+
+        let key = Self::composite_quota_key(user_id, group);
+        let resource_field = format!("r:{}", resource);
+        let now = chrono::Utc::now().timestamp_millis();
+        let window_end = now
+            + i64::try_from(window.as_millis())
+                .map_err(|_| StorageError::QueryError("window duration overflow".to_string()))?;
+
+        let result: (i32, i64, i64, i32) = self
+            .execute_with_retry(|| async {
+                let conn_manager = self.conn_manager.lock().await;
+                let conn_manager = conn_manager
+                    .as_ref()
+                    .ok_or_else(|| StorageError::ConnectionError("连接未初始化".to_string()))?;
+
+                let mut conn = conn_manager.clone();
+                lua_manager
+                    .execute_script(
+                        &mut conn,
+                        LuaScriptType::CompositeQuotaConsume,
+                        &[&key],
+                        &[
+                            &cost.to_string(),
+                            &total_limit.to_string(),
+                            &resource_limit.to_string(),
+                            &now.to_string(),
+                            &window_end.to_string(),
+                            &resource_field,
+                        ],
+                    )
+                    .await
+            })
+            .await?;
+
+        Ok(CompositeConsumeResult {
+            allowed: result.0 == 1,
+            total_remaining: result.1.max(0) as u64,
+            resource_remaining: result.2.max(0) as u64,
+            rejected_by: match result.3 {
+                1 => Some(CompositeQuotaLimit::Total),
+                2 => Some(CompositeQuotaLimit::Resource),
+                _ => None,
+            },
+        })
+    }
 }
 
 #[async_trait]
@@ -1242,10 +2246,267 @@ impl BanStorage for RedisStorage {
     }
 }
 
+/// 默认并发许可租约时长
+#[cfg(feature = "redis")]
+const DEFAULT_CONCURRENCY_LEASE: Duration = Duration::from_secs(30);
+
+/// 基于Redis的分布式并发控制器
+///
+/// 通过Lua脚本在Redis中记录所有节点的在途许可，使并发上限在多实例部署中全局生效。
+/// 每个许可带有租约，持有者崩溃后许可会在租约到期时自动释放；
+/// 存活的许可由后台任务定期续期，长时间运行的操作不会中途失去许可。
+///
+/// # 示例
+/// ```rust,no_run
+/// use limiteron::redis_storage::{RedisConcurrencyLimiter, RedisConfig, RedisStorage};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let storage = RedisStorage::new(RedisConfig::new("redis://localhost:6379")).await?;
+///     let limiter = RedisConcurrencyLimiter::new(storage, "api:export", 10);
+///
+///     let permit = limiter.acquire(1).await?;
+///     // 执行受并发控制的操作
+///     drop(permit);
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisConcurrencyLimiter {
+    /// Redis存储
+    storage: RedisStorage,
+    /// 并发控制键
+    key: String,
+    /// 最大并发数
+    max_concurrent: u64,
+    /// 许可租约时长
+    lease: Duration,
+}
+
+#[cfg(feature = "redis")]
+impl RedisConcurrencyLimiter {
+    /// 创建新的分布式并发控制器（默认租约30秒）
+    pub fn new(storage: RedisStorage, key: impl Into<String>, max_concurrent: u64) -> Self {
+        Self {
+            storage,
+            key: key.into(),
+            max_concurrent,
+            lease: DEFAULT_CONCURRENCY_LEASE,
+        }
+    }
+
+    /// 设置许可租约时长
+    ///
+    /// 许可每隔租约的三分之一自动续期一次。
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease.max(Duration::from_millis(1));
+        self
+    }
+
+    /// 获取最大并发数
+    pub fn max_concurrent(&self) -> u64 {
+        self.max_concurrent
+    }
+
+    /// 获取许可（非阻塞）
+    ///
+    /// # 参数
+    /// - `permits`: 需要获取的许可数量
+    ///
+    /// # 返回
+    /// - `Ok(permit)`: 成功获取许可，许可在 drop 时释放
+    /// - `Err(FlowGuardError::ConcurrencyLimitExceeded)`: 全局并发已满
+    /// - `Err(_)`: 参数无效或Redis操作失败
+    pub async fn acquire(&self, permits: u64) -> Result<RedisConcurrencyPermit, FlowGuardError> {
+        if permits == 0 {
+            return Err(FlowGuardError::LimitError("许可数量必须大于0".to_string()));
+        }
+        if permits > self.max_concurrent {
+            return Err(FlowGuardError::LimitError(format!(
+                "许可数量 {} 超过最大并发数 {}",
+                permits, self.max_concurrent
+            )));
+        }
+
+        let holder_id = uuid::Uuid::new_v4().to_string();
+        let (acquired, in_flight) = self
+            .storage
+            .concurrency_acquire(
+                &self.key,
+                self.max_concurrent,
+                permits,
+                self.lease,
+                &holder_id,
+            )
+            .await?;
+
+        if !acquired {
+            return Err(FlowGuardError::ConcurrencyLimitExceeded(format!(
+                "分布式并发已满: {}/{}",
+                in_flight, self.max_concurrent
+            )));
+        }
+
+        trace!(
+            "获取分布式并发许可: key={}, holder={}, in_flight={}",
+            self.key,
+            holder_id,
+            in_flight
+        );
+
+        let renewal = tokio::spawn(Self::renew_lease(self.clone(), holder_id.clone(), permits));
+
+        Ok(RedisConcurrencyPermit {
+            storage: self.storage.clone(),
+            key: self.key.clone(),
+            holder_id,
+            permits,
+            renewal: Some(renewal),
+            released: false,
+        })
+    }
+
+    /// 只读检查当前是否还有 `permits` 个可用许可，不获取许可
+    ///
+    /// 结果仅供参考：其他实例可能在检查之后、[`acquire`](Self::acquire) 之前占用许可。
+    pub async fn would_allow(&self, permits: u64) -> Result<bool, FlowGuardError> {
+        if permits > self.max_concurrent {
+            return Ok(false);
+        }
+        Ok(self
+            .storage
+            .concurrency_would_allow(&self.key, self.max_concurrent, permits)
+            .await?)
+    }
+
+    /// 后台续期租约，直到许可被释放（任务被中止）
+    async fn renew_lease(self, holder_id: String, permits: u64) {
+        let interval = self.lease / 3;
+        loop {
+            tokio::time::sleep(interval).await;
+            match self
+                .storage
+                .concurrency_acquire(
+                    &self.key,
+                    self.max_concurrent,
+                    permits,
+                    self.lease,
+                    &holder_id,
+                )
+                .await
+            {
+                Ok((true, _)) => trace!("续期并发许可: key={}, holder={}", self.key, holder_id),
+                Ok((false, in_flight)) => {
+                    warn!(
+                        "并发许可租约已丢失: key={}, holder={}, in_flight={}",
+                        self.key, holder_id, in_flight
+                    );
+                    return;
+                }
+                Err(e) => warn!("续期并发许可失败: key={}, 错误: {}", self.key, e),
+            }
+        }
+    }
+}
+
+/// 分布式并发许可
+///
+/// drop 时中止续期任务，并在后台异步释放Redis中的许可（fire-and-forget）。
+/// 需要确认释放结果时使用 [`RedisConcurrencyPermit::release`]。
+#[cfg(feature = "redis")]
+pub struct RedisConcurrencyPermit {
+    /// Redis存储
+    storage: RedisStorage,
+    /// 并发控制键
+    key: String,
+    /// 持有者ID
+    holder_id: String,
+    /// 持有的许可数
+    permits: u64,
+    /// 租约续期任务
+    renewal: Option<tokio::task::JoinHandle<()>>,
+    /// 是否已显式释放
+    released: bool,
+}
+
+#[cfg(feature = "redis")]
+impl RedisConcurrencyPermit {
+    /// 获取持有的许可数
+    pub fn permits(&self) -> u64 {
+        self.permits
+    }
+
+    /// 获取持有者ID
+    pub fn holder_id(&self) -> &str {
+        &self.holder_id
+    }
+
+    /// 显式释放许可并等待Redis确认
+    pub async fn release(mut self) -> Result<(), StorageError> {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        self.released = true;
+        self.storage
+            .concurrency_release(&self.key, &self.holder_id)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "redis")]
+impl Drop for RedisConcurrencyPermit {
+    fn drop(&mut self) {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        if self.released {
+            return;
+        }
+
+        let storage = self.storage.clone();
+        let key = std::mem::take(&mut self.key);
+        let holder_id = std::mem::take(&mut self.holder_id);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = storage.concurrency_release(&key, &holder_id).await {
+                        warn!("释放并发许可失败: key={}, 错误: {}", key, e);
+                    }
+                });
+            }
+            Err(_) => {
+                // 没有运行时无法发送释放命令，许可将在租约到期后自动释放
+                warn!("无可用运行时，并发许可将在租约到期后释放: key={}", key);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_expired_reservation_cost() {
+        let raw = r#"{"a":[30,1000,"5000"],"b":["20","3000","5000"],"c":[10,500,"4000"]}"#;
+        // 只统计已超时且属于当前窗口的预留
+        assert_eq!(
+            RedisStorage::expired_reservation_cost(raw, 2000, "5000"),
+            30
+        );
+        assert_eq!(
+            RedisStorage::expired_reservation_cost(raw, 3000, "5000"),
+            50
+        );
+        assert_eq!(
+            RedisStorage::expired_reservation_cost("{}", 3000, "5000"),
+            0
+        );
+        assert_eq!(RedisStorage::expired_reservation_cost("", 3000, "5000"), 0);
+    }
+
     #[test]
     fn test_redis_config_default() {
         let config = RedisConfig::default();
@@ -1302,6 +2563,32 @@ mod tests {
         assert_eq!(key, "ban:mac:001122334455");
     }
 
+    #[test]
+    fn test_seed_nodes() {
+        assert_eq!(
+            RedisStorage::seed_nodes("redis://10.0.0.1:7000, 10.0.0.2:7000,"),
+            vec!["redis://10.0.0.1:7000", "redis://10.0.0.2:7000"]
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_key_hash_tag() {
+        let mut storage = offline_storage();
+        assert_eq!(storage.rate_limit_key("api:user1"), "api:user1");
+
+        storage.config.cluster_mode = true;
+        assert_eq!(storage.rate_limit_key("api:user1"), "{api:user1}");
+        assert_eq!(storage.rate_limit_key("api:{user1}"), "api:{user1}");
+        assert_eq!(storage.rate_limit_key("api:{}"), "{api:{}}");
+    }
+
+    #[test]
+    fn test_concurrency_keys() {
+        let (holders, permits) = RedisStorage::concurrency_keys("api:export");
+        assert_eq!(holders, "concurrency:{api:export}:holders");
+        assert_eq!(permits, "concurrency:{api:export}:permits");
+    }
+
     #[test]
     fn test_ban_history_key() {
         let key = RedisStorage::ban_history_key(&BanTarget::UserId("user1".to_string()));
@@ -1328,18 +2615,97 @@ mod tests {
         assert_eq!(stats.total_retries(), 0);
     }
 
-    #[tokio::test]
-    async fn test_degraded_state() {
-        let config = RedisConfig::new("redis://invalid:6379");
-        // 注意：这里会尝试连接失败，仅测试降级状态切换
-        let storage = RedisStorage {
+    /// 构造未连接的存储，仅用于测试状态切换
+    fn offline_storage() -> RedisStorage {
+        RedisStorage {
             conn_manager: Arc::new(Mutex::new(None)),
-            config,
+            config: RedisConfig::new("redis://invalid:6379"),
             lua_manager: None,
             retry_stats: RetryStats::default(),
             degraded: Arc::new(Mutex::new(false)),
-            last_degraded_at: Arc::new(Mutex::new(None)),
-        };
+            degraded_since: Arc::new(Mutex::new(None)),
+            last_ping: Arc::new(Mutex::new(None)),
+            recovery_probe: None,
+        }
+    }
+
+    #[test]
+    fn test_combined_check_args_layout() {
+        let storage = offline_storage();
+        let specs = [
+            LimiterSpec::TokenBucket {
+                key: "rate:alice".to_string(),
+                capacity: 10,
+                refill_rate: 2000,
+                cost: 1,
+            },
+            LimiterSpec::FixedWindow {
+                key: "quota:alice".to_string(),
+                window_size: Duration::from_secs(60),
+                max_requests: 100,
+                cost: 3,
+            },
+        ];
+
+        let (keys, args) = storage.combined_check_args(&specs, 1_000, "req-1").unwrap();
+        assert_eq!(keys, vec!["rate:alice", "quota:alice"]);
+        assert_eq!(
+            args,
+            vec![
+                "1000",
+                "req-1",
+                "token_bucket",
+                "10",
+                "2",
+                "1",
+                "fixed_window",
+                "60000",
+                "100",
+                "3"
+            ]
+        );
+
+        let zero_window = [LimiterSpec::SlidingWindow {
+            key: "rate:bob".to_string(),
+            window_size: Duration::ZERO,
+            max_requests: 1,
+            cost: 1,
+        }];
+        assert!(storage
+            .combined_check_args(&zero_window, 1_000, "req-2")
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_combined_result() {
+        let decision = RedisStorage::parse_combined_result(&[0, 2, 9, 0], 2).unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.rejected_index, Some(1));
+        assert_eq!(decision.remaining, vec![9, 0]);
+
+        let decision = RedisStorage::parse_combined_result(&[1, 0, 8, 97], 2).unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.rejected_index, None);
+
+        assert!(RedisStorage::parse_combined_result(&[1, 0], 2).is_err());
+    }
+
+    #[test]
+    fn test_fixed_window_key_matches_script_window() {
+        let window = Duration::from_secs(60);
+        assert_eq!(
+            RedisStorage::fixed_window_key("api", window, 1_700_000_059_999),
+            "api:1700000040000"
+        );
+        assert_eq!(
+            RedisStorage::fixed_window_key("{api}", window, 1_700_000_040_000),
+            "{api}:1700000040000"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_degraded_state() {
+        let storage = offline_storage();
 
         assert!(!storage.is_degraded().await);
         storage.set_degraded(true).await;
@@ -1347,4 +2713,104 @@ mod tests {
         storage.set_degraded(false).await;
         assert!(!storage.is_degraded().await);
     }
+
+    #[tokio::test]
+    async fn test_health_tracks_degradation() {
+        let storage = offline_storage();
+
+        let health = storage.health().await;
+        assert!(!health.connected);
+        assert!(!health.degraded);
+        assert!(health.degraded_since.is_none());
+        assert!(health.last_ping_latency.is_none());
+
+        storage.set_degraded(true).await;
+        storage.retry_stats().record_failure();
+        let health = storage.health().await;
+        assert!(health.degraded);
+        assert!(health.degraded_since.is_some());
+        assert!(health.degraded_duration().is_some());
+        assert_eq!(health.failed_retries, 1);
+
+        storage.set_degraded(false).await;
+        let health = storage.health().await;
+        assert!(!health.degraded);
+        assert!(health.degraded_since.is_none());
+        assert!(health.degraded_duration().is_none());
+    }
+
+    /// 启动仅响应 PING 的最小 RESP 服务器
+    async fn spawn_fake_redis(listener: tokio::net::TcpListener) -> tokio::task::JoinHandle<()> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 1024];
+                    loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        // 每个 RESP 数组对应一条命令
+                        for _ in buf[..n].iter().filter(|b| **b == b'*') {
+                            let reply: &[u8] = if buf[..n].windows(4).any(|w| w == b"PING") {
+                                b"+PONG\r\n"
+                            } else {
+                                b"+OK\r\n"
+                            };
+                            if socket.write_all(reply).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_recovery_probe_restores_healthy_state() {
+        // 先占用端口再释放，模拟 Redis 不可用
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let mut storage = offline_storage();
+        storage.config = RedisConfig::new(format!("redis://{}", addr))
+            .enable_lua(false)
+            .io_timeout(Duration::from_millis(200))
+            .recovery_probe_interval(Duration::from_millis(20));
+        storage.set_degraded(true).await;
+        let storage = storage.with_recovery_probe();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(storage.is_degraded().await);
+
+        // Redis 恢复后，无需任何显式操作即可切回正常
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        let server = spawn_fake_redis(listener).await;
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while storage.is_degraded().await && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let health = storage.health().await;
+        assert!(!health.degraded);
+        assert!(health.connected);
+        assert!(health.degraded_since.is_none());
+        assert!(health.last_ping_latency.is_some());
+
+        // 释放存储后探测任务随之终止
+        let degraded = Arc::clone(&storage.degraded);
+        drop(storage);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(Arc::strong_count(&degraded), 1);
+
+        server.abort();
+    }
 }
@@ -0,0 +1,442 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! HTTP 限流服务
+//!
+//! 以独立服务的形式暴露 [`Governor`]，供非 Rust 服务通过 HTTP 调用：
+//!
+//! - `POST /check`：请求体为 JSON 形式的 [`CheckRequest`]，返回 [`CheckResponse`]
+//! - `POST /ban` / `DELETE /ban`：按 [`BanRequest`] 封禁或解封标识符，
+//!   由 [`router_with_admin_token`] 构建时需携带 `Authorization: Bearer <token>`
+//! - `GET /stats`：返回 [`GovernorStats`](crate::governor::GovernorStats)
+//! - `GET /healthz`：存活探针
+//!
+//! [`serve`] 在收到关闭信号后停止接收新连接并等待进行中的请求完成；
+//! [`config_watcher`] 将配置文件的变更热加载到 [`Governor`]。
+//!
+//! # 安全
+//!
+//! 服务本身不做认证：[`router`] 与 [`serve`] 暴露的 `/ban` 端点任何能访问服务的调用方都能使用，
+//! 只应监听在本机或受信任的内网地址上。需要对外暴露时使用 [`router_with_admin_token`]
+//! 与 [`serve_router`] 为封禁端点设置令牌。
+
+use crate::config::ChangeSource;
+use crate::config_watcher::{ConfigWatcher, WatchMode};
+use crate::error::{Decision, FlowGuardError};
+use crate::governor::Governor;
+use crate::matchers::{Identifier, RequestContext};
+use crate::storage::MemoryStorage;
+use ahash::AHashMap as HashMap;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// `POST /check` 的请求体，字段与 [`RequestContext`] 一一对应
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CheckRequest {
+    pub user_id: Option<String>,
+    pub ip: Option<String>,
+    pub mac: Option<String>,
+    pub device_id: Option<String>,
+    pub api_key: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub path: String,
+    pub method: String,
+    pub client_ip: Option<String>,
+    pub query_params: HashMap<String, String>,
+    /// 请求体（UTF-8 文本），供 JSON 请求体提取器使用
+    pub body: Option<String>,
+}
+
+impl From<CheckRequest> for RequestContext {
+    fn from(request: CheckRequest) -> Self {
+        let mut context = RequestContext::new()
+            .with_path(&request.path)
+            .with_method(&request.method);
+        for (name, value) in &request.headers {
+            context = context.with_header(name, value);
+        }
+        for (key, value) in &request.query_params {
+            context = context.with_query_param(key, value);
+        }
+        if let Some(body) = request.body {
+            context = context.with_body(body.into_bytes());
+        }
+        context.user_id = request.user_id;
+        context.ip = request.ip;
+        context.mac = request.mac;
+        context.device_id = request.device_id;
+        context.api_key = request.api_key;
+        context.client_ip = request.client_ip;
+        context
+    }
+}
+
+/// `POST /check` 的响应体
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckResponse {
+    /// 决策类型：`allowed`、`rejected`、`banned` 或 `delayed`
+    pub decision: String,
+    /// 拒绝原因（见 [`RejectReason::as_str`](crate::error::RejectReason::as_str)）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// 允许时的附带信息、拒绝或封禁时的可读消息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// 封禁到期时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banned_until: Option<DateTime<Utc>>,
+    /// 累计封禁次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ban_times: Option<u32>,
+    /// 软限流要求的等待时长（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+impl CheckResponse {
+    fn new(decision: &str) -> Self {
+        Self {
+            decision: decision.to_string(),
+            reason: None,
+            message: None,
+            banned_until: None,
+            ban_times: None,
+            delay_ms: None,
+        }
+    }
+}
+
+impl From<Decision> for CheckResponse {
+    fn from(decision: Decision) -> Self {
+        match decision {
+            Decision::Allowed(message) => CheckResponse {
+                message,
+                ..CheckResponse::new("allowed")
+            },
+            Decision::Rejected(rejection) => CheckResponse {
+                reason: Some(rejection.reason.as_str().to_string()),
+                message: Some(rejection.message),
+                ..CheckResponse::new("rejected")
+            },
+            Decision::Banned(info) => CheckResponse {
+                message: Some(info.reason),
+                banned_until: Some(info.banned_until),
+                ban_times: Some(info.ban_times),
+                ..CheckResponse::new("banned")
+            },
+            Decision::Delayed(delay) => CheckResponse {
+                delay_ms: Some(delay.as_millis() as u64),
+                ..CheckResponse::new("delayed")
+            },
+        }
+    }
+}
+
+/// `POST /ban` 与 `DELETE /ban` 的请求体
+#[derive(Debug, Clone, Deserialize)]
+pub struct BanRequest {
+    /// 标识符类型：`user_id`、`ip` 或 `mac`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// 标识符的值
+    pub value: String,
+    /// 封禁原因（仅 `POST /ban` 使用）
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// 操作人（仅 `POST /ban` 使用）
+    #[serde(default)]
+    pub operator: Option<String>,
+}
+
+impl BanRequest {
+    fn identifier(&self) -> Result<Identifier, FlowGuardError> {
+        match self.kind.as_str() {
+            "user_id" => Ok(Identifier::UserId(self.value.clone())),
+            "ip" => Ok(Identifier::Ip(self.value.clone())),
+            "mac" => Ok(Identifier::Mac(self.value.clone())),
+            other => Err(FlowGuardError::ValidationError(format!(
+                "不支持封禁的标识符类型: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 处理函数错误，序列化为 `{"error": "..."}`
+enum ServerError {
+    /// 限流器返回的错误
+    Governor(FlowGuardError),
+    /// 封禁端点缺少或携带了无效的管理令牌
+    Unauthorized,
+}
+
+impl From<FlowGuardError> for ServerError {
+    fn from(error: FlowGuardError) -> Self {
+        Self::Governor(error)
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::Governor(error) => {
+                let status = match error {
+                    FlowGuardError::ValidationError(_) | FlowGuardError::ConfigError(_) => {
+                        StatusCode::BAD_REQUEST
+                    }
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, error.to_string())
+            }
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "缺少或无效的管理令牌".to_string()),
+        };
+        let body = serde_json::json!({ "error": message });
+        (status, Json(body)).into_response()
+    }
+}
+
+/// 处理函数共享的状态
+#[derive(Clone)]
+struct AppState {
+    governor: Arc<Governor>,
+    /// 封禁端点的访问令牌，`None` 表示不校验
+    admin_token: Option<Arc<str>>,
+}
+
+impl AppState {
+    /// 校验 `Authorization: Bearer <token>` 请求头
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), ServerError> {
+        let Some(expected) = &self.admin_token else {
+            return Ok(());
+        };
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            Ok(())
+        } else {
+            Err(ServerError::Unauthorized)
+        }
+    }
+}
+
+/// 比较耗时与内容无关的相等判断，避免通过响应时间猜测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 构建挂载全部端点的路由
+///
+/// 封禁端点不做认证，只应在本机或受信任的网络中使用，
+/// 否则使用 [`router_with_admin_token`]。
+pub fn router(governor: Arc<Governor>) -> Router {
+    build_router(AppState {
+        governor,
+        admin_token: None,
+    })
+}
+
+/// 构建挂载全部端点的路由，`POST /ban` 与 `DELETE /ban` 需携带
+/// `Authorization: Bearer <admin_token>`，否则返回 401
+///
+/// 其余端点不受影响。
+pub fn router_with_admin_token(governor: Arc<Governor>, admin_token: impl Into<String>) -> Router {
+    build_router(AppState {
+        governor,
+        admin_token: Some(Arc::from(admin_token.into())),
+    })
+}
+
+fn build_router(state: AppState) -> Router {
+    Router::new()
+        .route("/check", post(check))
+        .route("/ban", post(ban).delete(unban))
+        .route("/stats", get(stats))
+        .route("/healthz", get(healthz))
+        .with_state(state)
+}
+
+async fn check(
+    State(state): State<AppState>,
+    Json(request): Json<CheckRequest>,
+) -> Result<Json<CheckResponse>, ServerError> {
+    let context = RequestContext::from(request);
+    let decision = state.governor.check(&context).await?;
+    Ok(Json(decision.into()))
+}
+
+async fn ban(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<BanRequest>,
+) -> Result<StatusCode, ServerError> {
+    state.authorize(&headers)?;
+    let identifier = request.identifier()?;
+    let reason = request.reason.as_deref().unwrap_or("manual ban");
+    let operator = request
+        .operator
+        .clone()
+        .unwrap_or_else(|| "http".to_string());
+    state
+        .governor
+        .ban_identifier(&identifier, reason, Some(ChangeSource::Manual { operator }))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn unban(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<BanRequest>,
+) -> Result<StatusCode, ServerError> {
+    state.authorize(&headers)?;
+    let identifier = request.identifier()?;
+    state.governor.unban_identifier(&identifier).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.governor.stats().await)
+}
+
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// 在给定监听器上运行服务，`shutdown` 完成后优雅退出
+///
+/// 关闭时不再接收新连接，等待进行中的请求处理完毕后返回。
+/// 使用不带认证的 [`router`]，需要校验封禁端点时使用 [`serve_router`]。
+pub async fn serve<F>(
+    listener: TcpListener,
+    governor: Arc<Governor>,
+    shutdown: F,
+) -> Result<(), FlowGuardError>
+where
+    F: Future<Output = ()>,
+{
+    serve_router(listener, router(governor), shutdown).await
+}
+
+/// 在给定监听器上运行指定的路由，`shutdown` 完成后优雅退出
+pub async fn serve_router<F>(
+    listener: TcpListener,
+    router: Router,
+    shutdown: F,
+) -> Result<(), FlowGuardError>
+where
+    F: Future<Output = ()>,
+{
+    listener.set_nonblocking(true)?;
+    info!("限流服务监听于 {}", listener.local_addr()?);
+
+    axum::Server::from_tcp(listener)
+        .map_err(|e| FlowGuardError::IoError(std::io::Error::other(e)))?
+        .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(|e| FlowGuardError::IoError(std::io::Error::other(e)))?;
+
+    info!("限流服务已停止");
+    Ok(())
+}
+
+/// 创建将配置文件变更热加载到 `governor` 的监视器
+///
+/// 监视器需调用 [`ConfigWatcher::start`] 启动；解析失败或校验失败的配置不会被应用。
+pub fn config_watcher(
+    governor: Arc<Governor>,
+    path: PathBuf,
+    poll_interval: Duration,
+) -> ConfigWatcher {
+    ConfigWatcher::new(
+        Arc::new(MemoryStorage::new()),
+        Some(path),
+        poll_interval,
+        Arc::new(move |config, source| {
+            let governor = governor.clone();
+            Box::pin(async move { governor.update_config_with_source(config, source).await })
+        }),
+        WatchMode::Poll,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{BanInfo, RejectReason};
+
+    #[test]
+    fn test_check_request_into_context() {
+        let request: CheckRequest = serde_json::from_str(
+            r#"{"ip": "10.0.0.1", "path": "/api", "method": "POST", "headers": {"X-User-Id": "u1"}}"#,
+        )
+        .unwrap();
+        let context = RequestContext::from(request);
+
+        assert_eq!(context.ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(context.path, "/api");
+        assert_eq!(context.method, "POST");
+        assert_eq!(
+            context.headers.get("x-user-id").map(String::as_str),
+            Some("u1")
+        );
+    }
+
+    #[test]
+    fn test_check_response_from_decision() {
+        let allowed = CheckResponse::from(Decision::Allowed(None));
+        assert_eq!(allowed.decision, "allowed");
+        assert_eq!(
+            serde_json::to_value(&allowed).unwrap(),
+            serde_json::json!({"decision": "allowed"})
+        );
+
+        let rejected =
+            CheckResponse::from(Decision::rejected(RejectReason::RateLimit, "slow down"));
+        assert_eq!(rejected.decision, "rejected");
+        assert_eq!(rejected.reason.as_deref(), Some("rate_limit"));
+        assert_eq!(rejected.message.as_deref(), Some("slow down"));
+
+        let banned = CheckResponse::from(Decision::Banned(BanInfo {
+            reason: "abuse".to_string(),
+            banned_until: Utc::now(),
+            ban_times: 2,
+        }));
+        assert_eq!(banned.decision, "banned");
+        assert_eq!(banned.ban_times, Some(2));
+    }
+
+    #[test]
+    fn test_ban_request_identifier() {
+        let request: BanRequest =
+            serde_json::from_str(r#"{"type": "ip", "value": "10.0.0.1"}"#).unwrap();
+        assert_eq!(
+            request.identifier().unwrap(),
+            Identifier::Ip("10.0.0.1".to_string())
+        );
+
+        let request: BanRequest =
+            serde_json::from_str(r#"{"type": "api_key", "value": "k"}"#).unwrap();
+        assert!(matches!(
+            request.identifier(),
+            Err(FlowGuardError::ValidationError(_))
+        ));
+    }
+}
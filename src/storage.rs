@@ -8,6 +8,11 @@
 
 use crate::error::{ConsumeResult, StorageError};
 use async_trait::async_trait;
+use dashmap::DashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 /// 存储接口
 #[async_trait]
@@ -50,6 +55,98 @@ pub trait QuotaStorage: Send + Sync {
         limit: u64,
         window: std::time::Duration,
     ) -> Result<(), StorageError>;
+
+    /// 在组合配额下原子地消费配额
+    ///
+    /// 同一 `group` 内的所有资源共享 `total_limit`，`resource` 另受 `resource_limit` 约束；
+    /// 任一额度不足时整体拒绝，两个计数都不变。两个额度共用一个窗口。
+    ///
+    /// 默认实现返回 [`StorageError::Unsupported`]。
+    #[allow(clippy::too_many_arguments)]
+    async fn consume_composite(
+        &self,
+        user_id: &str,
+        group: &str,
+        resource: &str,
+        cost: u64,
+        total_limit: u64,
+        resource_limit: u64,
+        window: std::time::Duration,
+    ) -> Result<CompositeConsumeResult, StorageError> {
+        let _ = (
+            user_id,
+            group,
+            resource,
+            cost,
+            total_limit,
+            resource_limit,
+            window,
+        );
+        Err(StorageError::Unsupported(
+            "该存储后端不支持组合配额".to_string(),
+        ))
+    }
+
+    /// 预留配额
+    ///
+    /// 与 [`consume`](Self::consume) 一样立即计入已消费量，同时以 `reservation_id` 记录一笔预留。
+    /// 预留须在 `timeout` 内提交或回滚，超时未处理的预留在下次访问同一配额时自动回滚。
+    ///
+    /// 默认实现返回 [`StorageError::Unsupported`]。
+    #[allow(clippy::too_many_arguments)]
+    async fn reserve(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+        cost: u64,
+        limit: u64,
+        window: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Result<ConsumeResult, StorageError> {
+        let _ = (
+            user_id,
+            resource,
+            reservation_id,
+            cost,
+            limit,
+            window,
+            timeout,
+        );
+        Err(StorageError::Unsupported(
+            "该存储后端不支持配额预留".to_string(),
+        ))
+    }
+
+    /// 提交预留，保留已扣减的配额
+    ///
+    /// 返回 `false` 表示预留不存在或已超时（超时的预留已被回滚）。
+    async fn commit_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+    ) -> Result<bool, StorageError> {
+        let _ = (user_id, resource, reservation_id);
+        Err(StorageError::Unsupported(
+            "该存储后端不支持配额预留".to_string(),
+        ))
+    }
+
+    /// 回滚预留，退还已扣减的配额
+    ///
+    /// 返回 `false` 表示预留不存在或已超时。预留所在窗口已结束时不退还。
+    async fn rollback_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+    ) -> Result<bool, StorageError> {
+        let _ = (user_id, resource, reservation_id);
+        Err(StorageError::Unsupported(
+            "该存储后端不支持配额预留".to_string(),
+        ))
+    }
 }
 
 /// 封禁存储接口
@@ -86,12 +183,41 @@ pub trait BanStorage: Send + Sync {
     /// 清理过期封禁
     async fn cleanup_expired_bans(&self) -> Result<u64, StorageError>;
 
+    /// 列出满足过滤条件的封禁记录
+    ///
+    /// 结果按封禁时间倒序并应用过滤器的分页参数；默认实现返回 [`StorageError::Unsupported`]。
+    #[cfg(feature = "ban-manager")]
+    async fn list_active(
+        &self,
+        _filter: &crate::ban_manager::BanFilter,
+    ) -> Result<Vec<BanRecord>, StorageError> {
+        Err(StorageError::Unsupported(
+            "当前存储后端不支持列出封禁记录".to_string(),
+        ))
+    }
+
+    /// 按键集分页读取满足过滤条件的封禁记录
+    ///
+    /// 记录按 `(banned_at 倒序, 目标排序键正序)` 排列，返回 `after` 之后的至多 `limit` 条，
+    /// 忽略过滤器的分页参数；默认实现返回 [`StorageError::Unsupported`]。
+    #[cfg(feature = "ban-manager")]
+    async fn list_page(
+        &self,
+        _filter: &crate::ban_manager::BanFilter,
+        _after: Option<&crate::ban_manager::BanCursor>,
+        _limit: usize,
+    ) -> Result<Vec<BanRecord>, StorageError> {
+        Err(StorageError::Unsupported(
+            "当前存储后端不支持分页读取封禁记录".to_string(),
+        ))
+    }
+
     /// 获取Any引用（用于类型转换）
     fn as_any(&self) -> &dyn std::any::Any;
 }
 
 /// 配额信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct QuotaInfo {
     pub consumed: u64,
     pub limit: u64,
@@ -99,6 +225,85 @@ pub struct QuotaInfo {
     pub window_end: chrono::DateTime<chrono::Utc>,
 }
 
+/// 组合配额中导致拒绝的额度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositeQuotaLimit {
+    /// 共享总额度
+    Total,
+    /// 资源子额度
+    Resource,
+}
+
+/// 组合配额消费结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeConsumeResult {
+    /// 是否允许
+    pub allowed: bool,
+    /// 共享总额度剩余量
+    pub total_remaining: u64,
+    /// 资源子额度剩余量
+    pub resource_remaining: u64,
+    /// 拒绝时导致拒绝的额度（两者都不足时为 `Total`）
+    pub rejected_by: Option<CompositeQuotaLimit>,
+}
+
+impl CompositeConsumeResult {
+    /// 根据当前用量判断并计算消费结果，返回结果与是否应扣减
+    pub(crate) fn evaluate(
+        total_used: u64,
+        resource_used: u64,
+        cost: u64,
+        total_limit: u64,
+        resource_limit: u64,
+    ) -> Self {
+        let rejected_by = if total_used.saturating_add(cost) > total_limit {
+            Some(CompositeQuotaLimit::Total)
+        } else if resource_used.saturating_add(cost) > resource_limit {
+            Some(CompositeQuotaLimit::Resource)
+        } else {
+            None
+        };
+        let charged = if rejected_by.is_none() { cost } else { 0 };
+        Self {
+            allowed: rejected_by.is_none(),
+            total_remaining: total_limit.saturating_sub(total_used + charged),
+            resource_remaining: resource_limit.saturating_sub(resource_used + charged),
+            rejected_by,
+        }
+    }
+}
+
+/// 分页读取时按排序键比较的封禁记录
+#[cfg(feature = "ban-manager")]
+struct PageEntry {
+    order: (std::cmp::Reverse<chrono::DateTime<chrono::Utc>>, String),
+    record: BanRecord,
+}
+
+#[cfg(feature = "ban-manager")]
+impl PartialEq for PageEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.order == other.order
+    }
+}
+
+#[cfg(feature = "ban-manager")]
+impl Eq for PageEntry {}
+
+#[cfg(feature = "ban-manager")]
+impl PartialOrd for PageEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ban-manager")]
+impl Ord for PageEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.order.cmp(&other.order)
+    }
+}
+
 /// 封禁目标
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum BanTarget {
@@ -116,7 +321,7 @@ pub enum BanScope {
 }
 
 /// 封禁记录
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BanRecord {
     pub target: BanTarget,
     pub ban_times: u32,
@@ -128,7 +333,7 @@ pub struct BanRecord {
 }
 
 /// 封禁历史
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BanHistory {
     pub ban_times: u32,
     pub last_banned_at: chrono::DateTime<chrono::Utc>,
@@ -143,11 +348,27 @@ pub struct BanConfig {
 }
 
 /// 内存存储实现
+///
+/// 默认为纯内存存储；通过 [`MemoryStorage::with_snapshot`] 可选地将封禁与配额状态
+/// 周期性持久化到磁盘，并在重启时恢复。
 pub struct MemoryStorage {
-    data: dashmap::DashMap<String, (String, Option<u64>)>,
-    quota_data: dashmap::DashMap<String, QuotaEntry>,
-    bans: dashmap::DashMap<BanTarget, BanRecord>,
-    history: dashmap::DashMap<BanTarget, BanHistory>,
+    data: DashMap<String, (String, Option<u64>)>,
+    quota_data: Arc<DashMap<String, QuotaEntry>>,
+    composite_quota_data: DashMap<String, CompositeQuotaEntry>,
+    /// 配额预留（key: 配额键, value: 预留ID -> 预留）
+    quota_reservations: DashMap<String, ahash::AHashMap<String, QuotaReservationEntry>>,
+    bans: Arc<DashMap<BanTarget, BanRecord>>,
+    history: Arc<DashMap<BanTarget, BanHistory>>,
+    /// 快照文件路径（未启用快照时为 None）
+    snapshot_path: Option<PathBuf>,
+}
+
+/// 内存存储快照文件内容
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct MemorySnapshot {
+    bans: Vec<BanRecord>,
+    history: Vec<(BanTarget, BanHistory)>,
+    quotas: Vec<(String, QuotaInfo)>,
 }
 
 /// 配额条目（包含配额信息和TTL）
@@ -159,14 +380,31 @@ struct QuotaEntry {
     _ttl: Option<u64>,
 }
 
+/// 配额预留条目
+#[derive(Debug, Clone)]
+struct QuotaReservationEntry {
+    /// 预留数量
+    cost: u64,
+    /// 超时时间
+    expires_at: chrono::DateTime<chrono::Utc>,
+    /// 预留所在窗口的结束时间，窗口已切换时不再退还
+    window_end: chrono::DateTime<chrono::Utc>,
+}
+
+/// 组合配额条目（共享总用量与各资源用量）
+#[derive(Debug, Clone)]
+struct CompositeQuotaEntry {
+    /// 窗口结束时间
+    window_end: chrono::DateTime<chrono::Utc>,
+    /// 共享总用量
+    total: u64,
+    /// 各资源用量
+    resources: ahash::AHashMap<String, u64>,
+}
+
 impl Clone for MemoryStorage {
     fn clone(&self) -> Self {
-        Self {
-            data: dashmap::DashMap::new(),
-            quota_data: dashmap::DashMap::new(),
-            bans: dashmap::DashMap::new(),
-            history: dashmap::DashMap::new(),
-        }
+        Self::new()
     }
 }
 
@@ -174,14 +412,166 @@ impl MemoryStorage {
     /// 创建新的内存存储
     pub fn new() -> Self {
         Self {
-            data: dashmap::DashMap::new(),
-            quota_data: dashmap::DashMap::new(),
-            bans: dashmap::DashMap::new(),
-            history: dashmap::DashMap::new(),
+            data: DashMap::new(),
+            quota_data: Arc::new(DashMap::new()),
+            composite_quota_data: DashMap::new(),
+            quota_reservations: DashMap::new(),
+            bans: Arc::new(DashMap::new()),
+            history: Arc::new(DashMap::new()),
+            snapshot_path: None,
+        }
+    }
+
+    /// 创建带持久化快照的内存存储
+    ///
+    /// 若 `path` 处已有快照则先加载（丢弃已过期的自动封禁和配额窗口），
+    /// 之后每隔 `interval` 将封禁与配额状态写入快照；存储释放时再写入一次。
+    /// 周期写入需要在 Tokio 运行时中创建。
+    pub fn with_snapshot(
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Result<Self, StorageError> {
+        let path = path.into();
+        let mut storage = Self::new();
+        storage.load_snapshot(&path)?;
+        storage.snapshot_path = Some(path.clone());
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let bans = Arc::downgrade(&storage.bans);
+                let history = Arc::downgrade(&storage.history);
+                let quotas = Arc::downgrade(&storage.quota_data);
+                handle.spawn(Self::run_snapshotter(
+                    path,
+                    interval.max(Duration::from_millis(1)),
+                    bans,
+                    history,
+                    quotas,
+                ));
+            }
+            Err(_) => warn!("未在 Tokio 运行时中创建，内存存储快照仅在释放时写入"),
+        }
+
+        Ok(storage)
+    }
+
+    /// 立即将当前状态写入快照文件
+    pub fn save_snapshot(&self) -> Result<(), StorageError> {
+        let path = self
+            .snapshot_path
+            .as_deref()
+            .ok_or_else(|| StorageError::InvalidConfig("未启用内存存储快照".to_string()))?;
+        write_snapshot(path, &self.bans, &self.history, &self.quota_data)
+    }
+
+    /// 从快照文件加载状态，文件不存在时保持为空
+    fn load_snapshot(&self, path: &Path) -> Result<(), StorageError> {
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(StorageError::QueryError(format!("读取快照文件失败: {}", e))),
+        };
+
+        let snapshot: MemorySnapshot = serde_json::from_slice(&content)
+            .map_err(|e| StorageError::QueryError(format!("解析快照文件失败: {}", e)))?;
+
+        let now = chrono::Utc::now();
+        let mut expired = 0usize;
+        for record in snapshot.bans {
+            if record.is_manual || record.expires_at > now {
+                self.bans.insert(record.target.clone(), record);
+            } else {
+                expired += 1;
+            }
+        }
+        for (target, history) in snapshot.history {
+            self.history.insert(target, history);
+        }
+        for (key, info) in snapshot.quotas {
+            if info.window_end > now {
+                self.quota_data.insert(key, QuotaEntry { info, _ttl: None });
+            } else {
+                expired += 1;
+            }
+        }
+
+        info!(
+            "已从快照恢复内存存储: {} 条封禁, {} 条配额, 丢弃 {} 条过期记录",
+            self.bans.len(),
+            self.quota_data.len(),
+            expired
+        );
+        Ok(())
+    }
+
+    /// 周期写入快照，存储释放后退出
+    async fn run_snapshotter(
+        path: PathBuf,
+        interval: Duration,
+        bans: Weak<DashMap<BanTarget, BanRecord>>,
+        history: Weak<DashMap<BanTarget, BanHistory>>,
+        quotas: Weak<DashMap<String, QuotaEntry>>,
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let (Some(bans), Some(history), Some(quotas)) =
+                (bans.upgrade(), history.upgrade(), quotas.upgrade())
+            else {
+                debug!("内存存储已释放，快照任务退出");
+                return;
+            };
+            if let Err(e) = write_snapshot(&path, &bans, &history, &quotas) {
+                warn!("写入内存存储快照失败: {}", e);
+            }
+        }
+    }
+}
+
+impl Drop for MemoryStorage {
+    fn drop(&mut self) {
+        if self.snapshot_path.is_some() {
+            if let Err(e) = self.save_snapshot() {
+                warn!("释放时写入内存存储快照失败: {}", e);
+            }
         }
     }
 }
 
+/// 将封禁与配额状态写入快照文件（先写临时文件再重命名，避免写入中断导致文件损坏）
+fn write_snapshot(
+    path: &Path,
+    bans: &DashMap<BanTarget, BanRecord>,
+    history: &DashMap<BanTarget, BanHistory>,
+    quotas: &DashMap<String, QuotaEntry>,
+) -> Result<(), StorageError> {
+    let snapshot = MemorySnapshot {
+        bans: bans.iter().map(|entry| entry.value().clone()).collect(),
+        history: history
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect(),
+        quotas: quotas
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().info.clone()))
+            .collect(),
+    };
+
+    let content = serde_json::to_vec(&snapshot)
+        .map_err(|e| StorageError::QueryError(format!("序列化快照失败: {}", e)))?;
+
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, content)
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|e| StorageError::QueryError(format!("写入快照文件失败: {}", e)))?;
+
+    debug!("内存存储快照已写入: {}", path.display());
+    Ok(())
+}
+
 #[async_trait]
 impl BanStorage for MemoryStorage {
     async fn is_banned(&self, target: &BanTarget) -> Result<Option<BanRecord>, StorageError> {
@@ -244,6 +634,67 @@ impl BanStorage for MemoryStorage {
         Ok(count)
     }
 
+    #[cfg(feature = "ban-manager")]
+    async fn list_active(
+        &self,
+        filter: &crate::ban_manager::BanFilter,
+    ) -> Result<Vec<BanRecord>, StorageError> {
+        let now = chrono::Utc::now();
+        let mut records: Vec<BanRecord> = self
+            .bans
+            .iter()
+            .filter(|entry| filter.matches(entry.value(), now))
+            .map(|entry| entry.value().clone())
+            .collect();
+        records.sort_by_key(|record| std::cmp::Reverse(record.banned_at));
+
+        let (offset, limit) = filter.pagination();
+        Ok(records.into_iter().skip(offset).take(limit).collect())
+    }
+
+    #[cfg(feature = "ban-manager")]
+    async fn list_page(
+        &self,
+        filter: &crate::ban_manager::BanFilter,
+        after: Option<&crate::ban_manager::BanCursor>,
+        limit: usize,
+    ) -> Result<Vec<BanRecord>, StorageError> {
+        use crate::ban_manager::BanCursor;
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        // 只保留排序最靠前的 limit 条，内存占用与页大小相关而与封禁总数无关
+        let now = chrono::Utc::now();
+        let mut page = BinaryHeap::with_capacity(limit + 1);
+        for entry in self.bans.iter() {
+            let record = entry.value();
+            if !filter.matches(record, now) {
+                continue;
+            }
+            let key = BanCursor::target_key(&record.target);
+            if after.is_some_and(|cursor| !cursor.precedes(record.banned_at, &key)) {
+                continue;
+            }
+            page.push(PageEntry {
+                order: (Reverse(record.banned_at), key),
+                record: record.clone(),
+            });
+            if page.len() > limit {
+                page.pop();
+            }
+        }
+
+        Ok(page
+            .into_sorted_vec()
+            .into_iter()
+            .map(|entry| entry.record)
+            .collect())
+    }
+
     async fn save(&self, record: &BanRecord) -> Result<(), StorageError> {
         self.bans.insert(record.target.clone(), record.clone());
 
@@ -285,6 +736,99 @@ impl Storage for MemoryStorage {
     }
 }
 
+impl MemoryStorage {
+    /// 生成组合配额键
+    ///
+    /// 用户ID带长度前缀，含 `:` 的用户ID与组名拼接后不会与其他组合冲突。
+    fn composite_quota_key(user_id: &str, group: &str) -> String {
+        format!("quota_composite:{}:{}:{}", user_id.len(), user_id, group)
+    }
+
+    /// 获取当前窗口的配额条目，窗口过期时开启新窗口
+    ///
+    /// 返回的条目持有该键的锁，调用方在锁内完成检查与扣减。
+    fn current_quota_entry(
+        &self,
+        key: String,
+        limit: u64,
+        window: std::time::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> dashmap::mapref::one::RefMut<'_, String, QuotaEntry> {
+        let window_end =
+            now + chrono::Duration::from_std(window).unwrap_or(chrono::Duration::hours(24));
+        let mut entry = self.quota_data.entry(key).or_insert_with(|| QuotaEntry {
+            info: QuotaInfo {
+                consumed: 0,
+                limit,
+                window_start: now,
+                window_end,
+            },
+            _ttl: None,
+        });
+
+        // 检查窗口是否过期
+        if now >= entry.info.window_end {
+            entry.info.consumed = 0;
+            entry.info.window_start = now;
+            entry.info.window_end = window_end;
+            entry.info.limit = limit; // 更新 limit
+        }
+        entry
+    }
+
+    /// 回滚已超时的预留
+    fn release_expired_reservations(
+        &self,
+        key: &str,
+        quota: &mut QuotaEntry,
+        now: chrono::DateTime<chrono::Utc>,
+    ) {
+        if let Some(mut reservations) = self.quota_reservations.get_mut(key) {
+            reservations.retain(|_, reservation| {
+                if reservation.expires_at > now {
+                    return true;
+                }
+                if reservation.window_end == quota.info.window_end {
+                    quota.info.consumed = quota.info.consumed.saturating_sub(reservation.cost);
+                }
+                false
+            });
+        }
+    }
+
+    /// 结束预留：提交时保留扣减，回滚时退还
+    fn finish_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+        commit: bool,
+    ) -> bool {
+        let key = format!("quota:{}:{}", user_id, resource);
+        let now = chrono::Utc::now();
+
+        // 与 reserve 保持相同的加锁顺序：先配额条目，再预留表
+        let mut quota = self.quota_data.get_mut(&key);
+        let Some(reservation) = self
+            .quota_reservations
+            .get_mut(&key)
+            .and_then(|mut reservations| reservations.remove(reservation_id))
+        else {
+            return false;
+        };
+
+        let expired = reservation.expires_at <= now;
+        if let Some(quota) = quota.as_mut() {
+            if (expired || !commit) && quota.info.window_end == reservation.window_end {
+                quota.info.consumed = quota.info.consumed.saturating_sub(reservation.cost);
+            }
+            // 顺带回滚同一配额下其他已超时的预留
+            self.release_expired_reservations(&key, quota, now);
+        }
+        !expired
+    }
+}
+
 #[async_trait]
 impl QuotaStorage for MemoryStorage {
     async fn get_quota(
@@ -293,7 +837,8 @@ impl QuotaStorage for MemoryStorage {
         resource: &str,
     ) -> Result<Option<QuotaInfo>, StorageError> {
         let key = format!("quota:{}:{}", user_id, resource);
-        if let Some(entry) = self.quota_data.get(&key) {
+        if let Some(mut entry) = self.quota_data.get_mut(&key) {
+            self.release_expired_reservations(&key, &mut entry, chrono::Utc::now());
             return Ok(Some(entry.info.clone()));
         }
         Ok(None)
@@ -309,31 +854,8 @@ impl QuotaStorage for MemoryStorage {
     ) -> Result<ConsumeResult, StorageError> {
         let key = format!("quota:{}:{}", user_id, resource);
         let now = chrono::Utc::now();
-
-        // 使用 DashMap 的 entry API 进行原子操作 (虽然 DashMap 本身不是事务性的，但在锁期间是安全的)
-        // 注意：DashMap 的 entry 锁住的是单个 key
-        let mut entry = self.quota_data.entry(key.clone()).or_insert_with(|| {
-            let window_end =
-                now + chrono::Duration::from_std(window).unwrap_or(chrono::Duration::hours(24));
-            QuotaEntry {
-                info: QuotaInfo {
-                    consumed: 0,
-                    limit,
-                    window_start: now,
-                    window_end,
-                },
-                _ttl: None,
-            }
-        });
-
-        // 检查窗口是否过期
-        if now >= entry.info.window_end {
-            entry.info.consumed = 0;
-            entry.info.window_start = now;
-            entry.info.window_end =
-                now + chrono::Duration::from_std(window).unwrap_or(chrono::Duration::hours(24));
-            entry.info.limit = limit; // 更新 limit
-        }
+        let mut entry = self.current_quota_entry(key.clone(), limit, window, now);
+        self.release_expired_reservations(&key, &mut entry, now);
 
         // 计算剩余配额
         let current_consumed = entry.info.consumed;
@@ -349,6 +871,7 @@ impl QuotaStorage for MemoryStorage {
             allowed,
             remaining: limit.saturating_sub(entry.info.consumed),
             alert_triggered: entry.info.consumed > limit, // 简单告警逻辑，实际上可能需要更复杂的判断
+            crossed_threshold: None,
         })
     }
 
@@ -379,6 +902,109 @@ impl QuotaStorage for MemoryStorage {
 
         Ok(())
     }
+
+    async fn consume_composite(
+        &self,
+        user_id: &str,
+        group: &str,
+        resource: &str,
+        cost: u64,
+        total_limit: u64,
+        resource_limit: u64,
+        window: std::time::Duration,
+    ) -> Result<CompositeConsumeResult, StorageError> {
+        let key = Self::composite_quota_key(user_id, group);
+        let now = chrono::Utc::now();
+        let next_window_end =
+            now + chrono::Duration::from_std(window).unwrap_or(chrono::Duration::hours(24));
+
+        // entry 持有该键的锁，总用量与资源用量在同一临界区内检查和更新
+        let mut entry =
+            self.composite_quota_data
+                .entry(key)
+                .or_insert_with(|| CompositeQuotaEntry {
+                    window_end: next_window_end,
+                    total: 0,
+                    resources: ahash::AHashMap::new(),
+                });
+
+        if now >= entry.window_end {
+            entry.window_end = next_window_end;
+            entry.total = 0;
+            entry.resources.clear();
+        }
+
+        let resource_used = entry.resources.get(resource).copied().unwrap_or(0);
+        let result = CompositeConsumeResult::evaluate(
+            entry.total,
+            resource_used,
+            cost,
+            total_limit,
+            resource_limit,
+        );
+        if result.allowed {
+            entry.total += cost;
+            *entry.resources.entry(resource.to_string()).or_insert(0) += cost;
+        }
+
+        Ok(result)
+    }
+
+    async fn reserve(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+        cost: u64,
+        limit: u64,
+        window: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Result<ConsumeResult, StorageError> {
+        let key = format!("quota:{}:{}", user_id, resource);
+        let now = chrono::Utc::now();
+        let mut entry = self.current_quota_entry(key.clone(), limit, window, now);
+        self.release_expired_reservations(&key, &mut entry, now);
+
+        let allowed = entry.info.consumed + cost <= limit;
+        if allowed {
+            entry.info.consumed += cost;
+            self.quota_reservations.entry(key).or_default().insert(
+                reservation_id.to_string(),
+                QuotaReservationEntry {
+                    cost,
+                    expires_at: now
+                        + chrono::Duration::from_std(timeout)
+                            .unwrap_or(chrono::Duration::hours(24)),
+                    window_end: entry.info.window_end,
+                },
+            );
+        }
+
+        Ok(ConsumeResult {
+            allowed,
+            remaining: limit.saturating_sub(entry.info.consumed),
+            alert_triggered: false,
+            crossed_threshold: None,
+        })
+    }
+
+    async fn commit_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+    ) -> Result<bool, StorageError> {
+        Ok(self.finish_reservation(user_id, resource, reservation_id, true))
+    }
+
+    async fn rollback_reservation(
+        &self,
+        user_id: &str,
+        resource: &str,
+        reservation_id: &str,
+    ) -> Result<bool, StorageError> {
+        Ok(self.finish_reservation(user_id, resource, reservation_id, false))
+    }
 }
 
 /// Mock配额存储
@@ -406,6 +1032,7 @@ impl QuotaStorage for MockQuotaStorage {
             allowed: true,
             remaining: 1000,
             alert_triggered: false,
+            crossed_threshold: None,
         })
     }
 
@@ -457,6 +1084,26 @@ impl BanStorage for MockBanStorage {
         Ok(0)
     }
 
+    /// 列出封禁记录
+    #[cfg(feature = "ban-manager")]
+    async fn list_active(
+        &self,
+        _filter: &crate::ban_manager::BanFilter,
+    ) -> Result<Vec<BanRecord>, StorageError> {
+        Ok(Vec::new())
+    }
+
+    /// 分页读取封禁记录
+    #[cfg(feature = "ban-manager")]
+    async fn list_page(
+        &self,
+        _filter: &crate::ban_manager::BanFilter,
+        _after: Option<&crate::ban_manager::BanCursor>,
+        _limit: usize,
+    ) -> Result<Vec<BanRecord>, StorageError> {
+        Ok(Vec::new())
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -490,6 +1137,25 @@ mod tests {
         assert_eq!(value, None);
     }
 
+    #[tokio::test]
+    async fn test_memory_storage_composite_keys_do_not_collide() {
+        let storage = MemoryStorage::new();
+        let window = std::time::Duration::from_secs(60);
+
+        let result = storage
+            .consume_composite("a:b", "c", "api", 10, 10, 10, window)
+            .await
+            .unwrap();
+        assert!(result.allowed);
+
+        // 拼接后同为 "a:b:c" 的另一组合不共享用量
+        let result = storage
+            .consume_composite("a", "b:c", "api", 10, 10, 10, window)
+            .await
+            .unwrap();
+        assert!(result.allowed);
+    }
+
     #[tokio::test]
     async fn test_mock_quota_storage() {
         let storage = MockQuotaStorage;
@@ -578,4 +1244,93 @@ mod tests {
         target2.hash(&mut hasher2);
         assert_eq!(hasher1.finish(), hasher2.finish());
     }
+
+    fn ban_record(target: BanTarget, expires_in_secs: i64, is_manual: bool) -> BanRecord {
+        let now = chrono::Utc::now();
+        BanRecord {
+            target,
+            ban_times: 1,
+            duration: std::time::Duration::from_secs(300),
+            banned_at: now - chrono::Duration::seconds(600),
+            expires_at: now + chrono::Duration::seconds(expires_in_secs),
+            is_manual,
+            reason: "test".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_storage_snapshot_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("limiteron.json");
+
+        let active = BanTarget::Ip("10.0.0.1".to_string());
+        let expired = BanTarget::Ip("10.0.0.2".to_string());
+        let manual = BanTarget::UserId("manual".to_string());
+        {
+            let storage = MemoryStorage::with_snapshot(&path, Duration::from_secs(3600)).unwrap();
+            storage
+                .save(&ban_record(active.clone(), 300, false))
+                .await
+                .unwrap();
+            storage
+                .save(&ban_record(expired.clone(), -60, false))
+                .await
+                .unwrap();
+            storage
+                .save(&ban_record(manual.clone(), -60, true))
+                .await
+                .unwrap();
+            storage
+                .consume("user1", "api", 10, 100, Duration::from_secs(3600))
+                .await
+                .unwrap();
+            storage
+                .consume("user2", "api", 10, 100, Duration::from_millis(1))
+                .await
+                .unwrap();
+            storage.save_snapshot().unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let restored = MemoryStorage::with_snapshot(&path, Duration::from_secs(3600)).unwrap();
+
+        assert!(restored.is_banned(&active).await.unwrap().is_some());
+        assert!(restored.is_banned(&manual).await.unwrap().is_some());
+        assert!(restored.bans.get(&expired).is_none());
+        // 过期封禁的历史仍保留，用于后续升级封禁时长
+        assert!(restored.get_history(&expired).await.unwrap().is_some());
+
+        let quota = restored.get_quota("user1", "api").await.unwrap().unwrap();
+        assert_eq!(quota.consumed, 10);
+        assert!(restored.get_quota("user2", "api").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_storage_snapshot_periodic_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("limiteron.json");
+
+        let storage = MemoryStorage::with_snapshot(&path, Duration::from_millis(20)).unwrap();
+        let target = BanTarget::UserId("user1".to_string());
+        storage
+            .save(&ban_record(target.clone(), 300, false))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(path.exists());
+
+        let fresh = MemoryStorage::new();
+        fresh.load_snapshot(&path).unwrap();
+        assert!(fresh.is_banned(&target).await.unwrap().is_some());
+    }
+
+    #[test]
+    fn test_memory_storage_snapshot_disabled_by_default() {
+        let storage = MemoryStorage::new();
+        assert!(matches!(
+            storage.save_snapshot(),
+            Err(StorageError::InvalidConfig(_))
+        ));
+    }
 }
@@ -10,6 +10,7 @@
 //!
 //! - Prometheus指标：Counter、Gauge、Histogram
 //! - OpenTelemetry分布式追踪
+//! - W3C Trace Context 传播（`traceparent` / `tracestate`）
 //! - Jaeger导出器
 //! - 指标采集和导出
 //!
@@ -32,8 +33,21 @@
 //! }
 //! ```
 
+use crate::constants::DEFAULT_MAX_LABEL_CARDINALITY;
+use crate::matchers::RequestContext;
 #[cfg(feature = "monitoring")]
-use prometheus::{Counter, Encoder, Gauge, Histogram, HistogramOpts, Registry, TextEncoder};
+use ahash::{AHashMap, AHashSet};
+#[cfg(feature = "telemetry")]
+use opentelemetry::global::{BoxedSpan, BoxedTracer};
+#[cfg(feature = "telemetry")]
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+#[cfg(feature = "telemetry")]
+use opentelemetry::trace::{Span as _, Tracer as _};
+#[cfg(feature = "monitoring")]
+use prometheus::{
+    Counter, CounterVec, Encoder, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, Opts,
+    Registry, TextEncoder,
+};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
@@ -48,6 +62,10 @@ impl Metrics {
         Self
     }
 
+    pub fn with_max_label_cardinality(_max_label_cardinality: usize) -> Self {
+        Self
+    }
+
     pub fn gather(&self) -> String {
         String::new()
     }
@@ -58,10 +76,22 @@ impl Metrics {
 
     pub fn record_ban(&self) {}
 
+    pub fn record_allowlist_bypass(&self) {}
+
+    pub fn record_decision(&self, _rule_id: &str, _limiter_type: &str, _outcome: &str) {}
+
+    pub fn record_rule_check(&self, _rule_id: &str, _duration: Duration) {}
+
+    pub fn record_storage_error(&self, _component: &str, _policy: &str) {}
+
+    pub fn record_circuit_open(&self, _component: &str) {}
+
     pub fn update_quota_usage(&self, _usage: f64) {}
 
     pub fn update_concurrent_connections(&self, _count: i64) {}
 
+    pub fn update_concurrency(&self, _limiter: &str, _in_flight: u64, _waiters: u64) {}
+
     pub fn update_token_bucket_tokens(&self, _tokens: f64) {}
 
     pub fn update_sliding_window_requests(&self, _count: f64) {}
@@ -85,24 +115,95 @@ pub struct Metrics {
     pub requests_banned: Counter,
     /// 错误数
     pub errors_total: Counter,
+    /// 白名单放行的请求数
+    pub allowlist_bypass_total: Counter,
+    /// 按规则、限流器类型与结果划分的决策数
+    pub decisions_total: CounterVec,
     /// 检查延迟分布
     pub check_duration: Histogram,
+    /// 按规则划分的检查延迟分布
+    pub rule_check_duration: HistogramVec,
+    /// 按组件与失败策略划分的存储/限流器故障数
+    pub storage_errors_total: CounterVec,
+    /// 按组件划分的熔断短路次数
+    pub circuit_open_total: CounterVec,
     /// 限流器延迟分布
     pub limiter_duration: Histogram,
     /// 配额使用率
     pub quota_usage: Gauge,
     /// 并发连接数
     pub concurrent_connections: Gauge,
+    /// 按并发控制器划分的已持有许可数
+    pub concurrency_in_flight: GaugeVec,
+    /// 按并发控制器划分的等待许可的调用方数
+    pub concurrency_waiters: GaugeVec,
     /// 令牌桶令牌数
     pub token_bucket_tokens: Gauge,
     /// 滑动窗口请求数
     pub sliding_window_requests: Gauge,
     /// 固定窗口请求数
     pub fixed_window_requests: Gauge,
+    /// 因标签基数超限而折叠为 `__other__` 的观测次数（按维度划分）
+    pub cardinality_capped_total: CounterVec,
+    /// 标签基数限制
+    labels: Arc<LabelCardinality>,
     /// 指标注册表
     registry: Registry,
 }
 
+/// 超出基数上限的标签值统一折叠为此值
+#[cfg(feature = "monitoring")]
+pub const OVERFLOW_LABEL: &str = "__other__";
+
+/// 按维度（标签名）限制标签取值数量
+///
+/// 每个维度最多记录 `max` 个不同取值，之后出现的新取值折叠为 [`OVERFLOW_LABEL`]，
+/// 已记录的取值不受影响。`max` 为 0 时不限制。
+#[cfg(feature = "monitoring")]
+#[derive(Debug)]
+struct LabelCardinality {
+    /// 每个维度允许的最大取值数量
+    max: usize,
+    /// 各维度已记录的取值
+    seen: parking_lot::RwLock<AHashMap<&'static str, AHashSet<String>>>,
+}
+
+#[cfg(feature = "monitoring")]
+impl LabelCardinality {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            seen: parking_lot::RwLock::new(AHashMap::new()),
+        }
+    }
+
+    /// 返回 `value` 在 `dimension` 下实际使用的标签值，超限时返回 `None`
+    fn admit<'a>(&self, dimension: &'static str, value: &'a str) -> Option<&'a str> {
+        if self.max == 0 {
+            return Some(value);
+        }
+        if self
+            .seen
+            .read()
+            .get(dimension)
+            .is_some_and(|values| values.contains(value))
+        {
+            return Some(value);
+        }
+
+        let mut seen = self.seen.write();
+        let values = seen.entry(dimension).or_default();
+        if values.contains(value) {
+            Some(value)
+        } else if values.len() < self.max {
+            values.insert(value.to_string());
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
 /// 全局指标实例
 static GLOBAL_METRICS: std::sync::OnceLock<Arc<Metrics>> = std::sync::OnceLock::new();
 
@@ -131,6 +232,17 @@ impl Metrics {
     /// # 返回
     /// - 包含所有指标的Metrics实例
     pub fn new() -> Self {
+        Self::with_max_label_cardinality(DEFAULT_MAX_LABEL_CARDINALITY)
+    }
+
+    /// 创建新的监控指标，并限制每个标签维度的取值数量
+    ///
+    /// 规则ID等标签可能随配置无限增长，超过 `max_label_cardinality` 个不同取值后，
+    /// 新取值统一记录为 [`OVERFLOW_LABEL`]，并累加 `metrics_cardinality_capped_total`。
+    ///
+    /// # 参数
+    /// - `max_label_cardinality`: 每个维度的最大取值数量，0 表示不限制
+    pub fn with_max_label_cardinality(max_label_cardinality: usize) -> Self {
         let registry = Registry::new();
 
         let register_counter = |name: &str, help: &str| -> Counter {
@@ -185,6 +297,25 @@ impl Metrics {
         // 错误数
         let errors_total = register_counter("flowguard_errors_total", "Total number of errors");
 
+        // 白名单放行的请求数
+        let allowlist_bypass_total = register_counter(
+            "flowguard_allowlist_bypass_total",
+            "Total number of requests bypassing checks via allowlist",
+        );
+
+        // 按规则、限流器类型与结果划分的决策数
+        let decisions_total = CounterVec::new(
+            Opts::new(
+                "limiteron_decisions_total",
+                "Total number of limiter decisions by rule, limiter type and outcome",
+            ),
+            &["rule_id", "limiter_type", "outcome"],
+        )
+        .expect("Failed to create counter vec");
+        registry
+            .register(Box::new(decisions_total.clone()))
+            .expect("Failed to register counter vec");
+
         // 检查延迟分布
         let check_duration = register_histogram(
             "flowguard_check_duration_seconds",
@@ -192,6 +323,48 @@ impl Metrics {
             vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0],
         );
 
+        // 按规则划分的检查延迟分布
+        let rule_check_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "limiteron_check_duration_seconds",
+                "Duration of rule checks in seconds by rule",
+            )
+            .buckets(vec![
+                0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0,
+            ]),
+            &["rule_id"],
+        )
+        .expect("Failed to create histogram vec");
+        registry
+            .register(Box::new(rule_check_duration.clone()))
+            .expect("Failed to register histogram vec");
+
+        // 按组件与失败策略划分的存储/限流器故障数
+        let storage_errors_total = CounterVec::new(
+            Opts::new(
+                "limiteron_storage_error_total",
+                "Total number of storage or limiter errors by component and failure policy",
+            ),
+            &["component", "policy"],
+        )
+        .expect("Failed to create counter vec");
+        registry
+            .register(Box::new(storage_errors_total.clone()))
+            .expect("Failed to register counter vec");
+
+        // 按组件划分的熔断短路次数
+        let circuit_open_total = CounterVec::new(
+            Opts::new(
+                "limiteron_circuit_open_total",
+                "Total number of calls short-circuited by an open circuit breaker by component",
+            ),
+            &["component"],
+        )
+        .expect("Failed to create counter vec");
+        registry
+            .register(Box::new(circuit_open_total.clone()))
+            .expect("Failed to register counter vec");
+
         // 限流器延迟分布
         let limiter_duration = register_histogram(
             "flowguard_limiter_duration_seconds",
@@ -211,6 +384,24 @@ impl Metrics {
             "Current number of concurrent connections",
         );
 
+        // 按并发控制器划分的已持有许可数与等待数
+        let register_gauge_vec = |name: &str, help: &str| -> GaugeVec {
+            let g = GaugeVec::new(Opts::new(name, help), &["limiter"])
+                .expect("Failed to create gauge vec");
+            registry
+                .register(Box::new(g.clone()))
+                .expect("Failed to register gauge vec");
+            g
+        };
+        let concurrency_in_flight = register_gauge_vec(
+            "limiteron_concurrency_in_flight",
+            "Current number of permits held by concurrency limiter",
+        );
+        let concurrency_waiters = register_gauge_vec(
+            "limiteron_concurrency_waiters",
+            "Current number of callers waiting for permits by concurrency limiter",
+        );
+
         // 令牌桶令牌数
         let token_bucket_tokens = register_gauge(
             "flowguard_token_bucket_tokens",
@@ -229,19 +420,41 @@ impl Metrics {
             "Current number of requests in fixed window",
         );
 
+        // 标签基数超限次数
+        let cardinality_capped_total = CounterVec::new(
+            Opts::new(
+                "metrics_cardinality_capped_total",
+                "Total number of observations whose label value was collapsed into __other__",
+            ),
+            &["dimension"],
+        )
+        .expect("Failed to create counter vec");
+        registry
+            .register(Box::new(cardinality_capped_total.clone()))
+            .expect("Failed to register counter vec");
+
         Self {
             requests_total,
             requests_allowed,
             requests_rejected,
             requests_banned,
             errors_total,
+            allowlist_bypass_total,
+            decisions_total,
             check_duration,
+            rule_check_duration,
+            storage_errors_total,
+            circuit_open_total,
             limiter_duration,
             quota_usage,
             concurrent_connections,
+            concurrency_in_flight,
+            concurrency_waiters,
             token_bucket_tokens,
             sliding_window_requests,
             fixed_window_requests,
+            cardinality_capped_total,
+            labels: Arc::new(LabelCardinality::new(max_label_cardinality)),
             registry,
         }
     }
@@ -260,13 +473,21 @@ impl Metrics {
         registry.register(Box::new(self.requests_rejected.clone()))?;
         registry.register(Box::new(self.requests_banned.clone()))?;
         registry.register(Box::new(self.errors_total.clone()))?;
+        registry.register(Box::new(self.allowlist_bypass_total.clone()))?;
+        registry.register(Box::new(self.decisions_total.clone()))?;
         registry.register(Box::new(self.check_duration.clone()))?;
+        registry.register(Box::new(self.rule_check_duration.clone()))?;
+        registry.register(Box::new(self.storage_errors_total.clone()))?;
+        registry.register(Box::new(self.circuit_open_total.clone()))?;
         registry.register(Box::new(self.limiter_duration.clone()))?;
         registry.register(Box::new(self.quota_usage.clone()))?;
         registry.register(Box::new(self.concurrent_connections.clone()))?;
+        registry.register(Box::new(self.concurrency_in_flight.clone()))?;
+        registry.register(Box::new(self.concurrency_waiters.clone()))?;
         registry.register(Box::new(self.token_bucket_tokens.clone()))?;
         registry.register(Box::new(self.sliding_window_requests.clone()))?;
         registry.register(Box::new(self.fixed_window_requests.clone()))?;
+        registry.register(Box::new(self.cardinality_capped_total.clone()))?;
         Ok(())
     }
 
@@ -285,6 +506,16 @@ impl Metrics {
         String::from_utf8(buffer).unwrap_or_else(|_| String::new())
     }
 
+    /// 按基数限制得出标签值，超限时折叠为 [`OVERFLOW_LABEL`] 并计数
+    fn label<'a>(&self, dimension: &'static str, value: &'a str) -> &'a str {
+        self.labels.admit(dimension, value).unwrap_or_else(|| {
+            self.cardinality_capped_total
+                .with_label_values(&[dimension])
+                .inc();
+            OVERFLOW_LABEL
+        })
+    }
+
     /// 记录检查操作
     ///
     /// # 参数
@@ -313,6 +544,62 @@ impl Metrics {
         self.requests_banned.inc();
     }
 
+    /// 记录白名单放行
+    pub fn record_allowlist_bypass(&self) {
+        self.allowlist_bypass_total.inc();
+    }
+
+    /// 记录单个限流器的决策
+    ///
+    /// # 参数
+    /// - `rule_id`: 规则ID
+    /// - `limiter_type`: 限流器类型（如 `token_bucket`）
+    /// - `outcome`: 结果（`allowed` / `rejected` / `error`）
+    pub fn record_decision(&self, rule_id: &str, limiter_type: &str, outcome: &str) {
+        self.decisions_total
+            .with_label_values(&[
+                self.label("rule_id", rule_id),
+                self.label("limiter_type", limiter_type),
+                self.label("outcome", outcome),
+            ])
+            .inc();
+    }
+
+    /// 记录规则检查耗时
+    ///
+    /// # 参数
+    /// - `rule_id`: 规则ID
+    /// - `duration`: 检查耗时
+    pub fn record_rule_check(&self, rule_id: &str, duration: Duration) {
+        self.rule_check_duration
+            .with_label_values(&[self.label("rule_id", rule_id)])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// 记录存储或限流器故障
+    ///
+    /// # 参数
+    /// - `component`: 故障组件（`ban` / `limiter`）
+    /// - `policy`: 生效的失败策略（`propagate` / `open` / `closed`）
+    pub fn record_storage_error(&self, component: &str, policy: &str) {
+        self.storage_errors_total
+            .with_label_values(&[
+                self.label("component", component),
+                self.label("policy", policy),
+            ])
+            .inc();
+    }
+
+    /// 记录熔断短路
+    ///
+    /// # 参数
+    /// - `component`: 被熔断的组件（`ban` / `limiter`）
+    pub fn record_circuit_open(&self, component: &str) {
+        self.circuit_open_total
+            .with_label_values(&[self.label("component", component)])
+            .inc();
+    }
+
     /// 更新配额使用率
     ///
     /// # 参数
@@ -329,6 +616,22 @@ impl Metrics {
         self.concurrent_connections.set(count as f64);
     }
 
+    /// 更新并发控制器的已持有许可数与等待数
+    ///
+    /// # 参数
+    /// - `limiter`: 并发控制器名称
+    /// - `in_flight`: 已持有的许可数
+    /// - `waiters`: 等待许可的调用方数
+    pub fn update_concurrency(&self, limiter: &str, in_flight: u64, waiters: u64) {
+        let limiter = self.label("limiter", limiter);
+        self.concurrency_in_flight
+            .with_label_values(&[limiter])
+            .set(in_flight as f64);
+        self.concurrency_waiters
+            .with_label_values(&[limiter])
+            .set(waiters as f64);
+    }
+
     /// 更新令牌桶令牌数
     ///
     /// # 参数
@@ -368,6 +671,9 @@ impl Default for Metrics {
 pub struct Tracer {
     /// 是否启用
     enabled: bool,
+    /// OpenTelemetry 追踪器，未设置时使用全局 TracerProvider
+    #[cfg(feature = "telemetry")]
+    otel: Option<Arc<BoxedTracer>>,
 }
 
 impl Tracer {
@@ -379,7 +685,23 @@ impl Tracer {
     /// # 返回
     /// - Tracer实例
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            #[cfg(feature = "telemetry")]
+            otel: None,
+        }
+    }
+
+    /// 使用指定的 OpenTelemetry 追踪器创建启用的追踪器
+    ///
+    /// # 参数
+    /// - `tracer`: OpenTelemetry 追踪器，如 `BoxedTracer::new(Box::new(provider.tracer("app")))`
+    #[cfg(feature = "telemetry")]
+    pub fn with_otel_tracer(tracer: BoxedTracer) -> Self {
+        Self {
+            enabled: true,
+            otel: Some(Arc::new(tracer)),
+        }
     }
 
     /// 开始追踪
@@ -389,12 +711,55 @@ impl Tracer {
     ///
     /// # 返回
     /// - Span实例
-    pub fn start_span(&self, _name: &str) -> Span {
+    pub fn start_span(&self, name: &str) -> Span {
         if !self.enabled {
             return Span::new_disabled();
         }
 
-        Span::new()
+        #[cfg(feature = "telemetry")]
+        return self.start_otel_span(name, &opentelemetry::Context::current());
+
+        #[cfg(not(feature = "telemetry"))]
+        {
+            let _ = name;
+            Span::new()
+        }
+    }
+
+    /// 开始追踪，并延续请求头中传入的 W3C Trace Context
+    ///
+    /// 从 `RequestContext` 的 `traceparent` / `tracestate` 请求头中提取父 Span。
+    /// 请求头缺失或无效时，等同于 [`start_span`](Self::start_span)。
+    ///
+    /// # 参数
+    /// - `name`: Span名称
+    /// - `context`: 请求上下文
+    ///
+    /// # 返回
+    /// - Span实例
+    pub fn start_span_with_context(&self, name: &str, context: &RequestContext) -> Span {
+        if !self.enabled {
+            return Span::new_disabled();
+        }
+
+        #[cfg(feature = "telemetry")]
+        return self.start_otel_span(name, &extract_trace_context(context));
+
+        #[cfg(not(feature = "telemetry"))]
+        {
+            let _ = context;
+            self.start_span(name)
+        }
+    }
+
+    #[cfg(feature = "telemetry")]
+    fn start_otel_span(&self, name: &str, parent: &opentelemetry::Context) -> Span {
+        let otel_span = match &self.otel {
+            Some(tracer) => tracer.start_with_context(name.to_string(), parent),
+            None => opentelemetry::global::tracer("limiteron")
+                .start_with_context(name.to_string(), parent),
+        };
+        Span::new().with_otel(otel_span)
     }
 
     /// 检查是否启用
@@ -403,6 +768,30 @@ impl Tracer {
     }
 }
 
+/// 从请求头读取 W3C Trace Context 的适配器
+#[cfg(feature = "telemetry")]
+struct HeaderExtractor<'a>(&'a RequestContext);
+
+#[cfg(feature = "telemetry")]
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get_header(key).map(String::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.headers.keys().map(String::as_str).collect()
+    }
+}
+
+/// 从请求头中提取 W3C Trace Context
+///
+/// 解析 `traceparent` / `tracestate` 请求头，返回以远程 Span 为父级的上下文；
+/// 请求头缺失或无效时返回不含 Span 的空上下文。
+#[cfg(feature = "telemetry")]
+pub fn extract_trace_context(context: &RequestContext) -> opentelemetry::Context {
+    opentelemetry_sdk::propagation::TraceContextPropagator::new().extract(&HeaderExtractor(context))
+}
+
 impl Default for Tracer {
     fn default() -> Self {
         Self::new(true)
@@ -424,6 +813,9 @@ pub struct Span {
     events: std::sync::Arc<tokio::sync::Mutex<Vec<(String, Vec<(String, String)>)>>>,
     /// 错误（使用 Mutex 代替 RwLock，简化并发控制）
     error: std::sync::Arc<tokio::sync::Mutex<Option<String>>>,
+    /// OpenTelemetry Span，释放时结束并导出
+    #[cfg(feature = "telemetry")]
+    otel: Option<parking_lot::Mutex<BoxedSpan>>,
 }
 
 impl Span {
@@ -435,6 +827,8 @@ impl Span {
             attributes: std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new())),
             events: std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new())),
             error: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+            #[cfg(feature = "telemetry")]
+            otel: None,
         }
     }
 
@@ -446,9 +840,26 @@ impl Span {
             attributes: std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new())),
             events: std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new())),
             error: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+            #[cfg(feature = "telemetry")]
+            otel: None,
         }
     }
 
+    /// 关联 OpenTelemetry Span
+    #[cfg(feature = "telemetry")]
+    fn with_otel(mut self, span: BoxedSpan) -> Self {
+        self.otel = Some(parking_lot::Mutex::new(span));
+        self
+    }
+
+    /// 获取 OpenTelemetry Span 上下文（未关联时返回 `None`）
+    #[cfg(feature = "telemetry")]
+    pub fn span_context(&self) -> Option<opentelemetry::trace::SpanContext> {
+        self.otel
+            .as_ref()
+            .map(|span| span.lock().span_context().clone())
+    }
+
     /// 添加属性
     ///
     /// # 参数
@@ -459,6 +870,13 @@ impl Span {
             if let Ok(mut attrs) = self.attributes.try_lock() {
                 attrs.push((key.to_string(), value.to_string()));
             }
+            #[cfg(feature = "telemetry")]
+            if let Some(span) = &self.otel {
+                span.lock().set_attribute(opentelemetry::KeyValue::new(
+                    key.to_string(),
+                    value.to_string(),
+                ));
+            }
         }
     }
 
@@ -469,6 +887,16 @@ impl Span {
     /// - `attributes`: 事件属性
     pub fn add_event(&self, name: &str, attributes: Vec<(String, String)>) {
         if self.enabled {
+            #[cfg(feature = "telemetry")]
+            if let Some(span) = &self.otel {
+                span.lock().add_event(
+                    name.to_string(),
+                    attributes
+                        .iter()
+                        .map(|(k, v)| opentelemetry::KeyValue::new(k.clone(), v.clone()))
+                        .collect(),
+                );
+            }
             if let Ok(mut evts) = self.events.try_lock() {
                 evts.push((name.to_string(), attributes));
             }
@@ -484,6 +912,11 @@ impl Span {
             if let Ok(mut err) = self.error.try_lock() {
                 *err = Some(error.to_string());
             }
+            #[cfg(feature = "telemetry")]
+            if let Some(span) = &self.otel {
+                span.lock()
+                    .set_status(opentelemetry::trace::Status::error(error.to_string()));
+            }
         }
     }
 
@@ -555,6 +988,8 @@ pub struct TelemetryConfig {
     pub prometheus_port: u16,
     /// 采样率 (0.0 - 1.0)
     pub sampling_rate: f64,
+    /// 每个指标标签维度的最大取值数量，超出后折叠为 `__other__`（0 表示不限制）
+    pub max_label_cardinality: usize,
 }
 
 impl Default for TelemetryConfig {
@@ -566,6 +1001,7 @@ impl Default for TelemetryConfig {
             enable_tracing: false,
             prometheus_port: 9090,
             sampling_rate: 1.0,
+            max_label_cardinality: DEFAULT_MAX_LABEL_CARDINALITY,
         }
     }
 }
@@ -598,6 +1034,12 @@ impl TelemetryConfig {
         self.sampling_rate = rate.clamp(0.0, 1.0);
         self
     }
+
+    /// 设置每个指标标签维度的最大取值数量
+    pub fn with_max_label_cardinality(mut self, max: usize) -> Self {
+        self.max_label_cardinality = max;
+        self
+    }
 }
 
 /// 初始化遥测系统
@@ -631,10 +1073,10 @@ pub async fn init_telemetry(config: &TelemetryConfig) -> Result<(Metrics, Tracer
             "Prometheus metrics initialized (configured port: {})",
             config.prometheus_port
         );
-        Metrics::new()
+        Metrics::with_max_label_cardinality(config.max_label_cardinality)
     } else {
         info!("Prometheus metrics disabled");
-        Metrics::new()
+        Metrics::with_max_label_cardinality(config.max_label_cardinality)
     };
 
     // 初始化OpenTelemetry追踪
@@ -856,6 +1298,66 @@ mod tests {
         span.finish();
     }
 
+    /// 收集导出 Span 的测试导出器
+    #[cfg(feature = "telemetry")]
+    #[derive(Debug, Clone, Default)]
+    struct CollectingExporter(
+        std::sync::Arc<parking_lot::Mutex<Vec<opentelemetry_sdk::export::trace::SpanData>>>,
+    );
+
+    #[cfg(feature = "telemetry")]
+    impl opentelemetry_sdk::export::trace::SpanExporter for CollectingExporter {
+        fn export(
+            &mut self,
+            batch: Vec<opentelemetry_sdk::export::trace::SpanData>,
+        ) -> futures::future::BoxFuture<'static, opentelemetry_sdk::export::trace::ExportResult>
+        {
+            self.0.lock().extend(batch);
+            Box::pin(std::future::ready(Ok(())))
+        }
+    }
+
+    #[cfg(feature = "telemetry")]
+    #[test]
+    fn test_start_span_with_context_continues_traceparent() {
+        use opentelemetry::trace::{SpanId, TraceId, TracerProvider as _};
+
+        let exporter = CollectingExporter::default();
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = Tracer::with_otel_tracer(BoxedTracer::new(Box::new(provider.tracer("test"))));
+
+        let context = RequestContext::new().with_header(
+            "Traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        );
+        let span = tracer.start_span_with_context("governor.check", &context);
+        span.set_attribute("decision", "allowed");
+        span.finish();
+        let _ = provider.force_flush();
+
+        let spans = exporter.0.lock().clone();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "governor.check");
+        assert_eq!(
+            spans[0].span_context.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+        assert_eq!(
+            spans[0].parent_span_id,
+            SpanId::from_hex("00f067aa0ba902b7").unwrap()
+        );
+
+        // 无 traceparent 时开启新的 trace
+        let span = tracer.start_span_with_context("governor.check", &RequestContext::new());
+        let span_context = span.span_context().unwrap();
+        assert_ne!(
+            span_context.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+    }
+
     #[test]
     fn test_span_elapsed() {
         let span = Span::new_disabled();
@@ -1015,6 +1517,14 @@ mod tests_monitoring {
         assert_eq!(metrics.requests_banned.get(), 1.0);
     }
 
+    #[test]
+    fn test_metrics_record_allowlist_bypass() {
+        let metrics = Metrics::new();
+        metrics.record_allowlist_bypass();
+
+        assert_eq!(metrics.allowlist_bypass_total.get(), 1.0);
+    }
+
     #[test]
     fn test_metrics_update_quota_usage() {
         let metrics = Metrics::new();
@@ -1098,6 +1608,48 @@ mod tests_monitoring {
         assert_eq!(metrics.requests_total.get(), 5.0);
     }
 
+    #[test]
+    fn test_metrics_label_cardinality_capped() {
+        let metrics = Metrics::with_max_label_cardinality(3);
+
+        for i in 0..10 {
+            metrics.record_decision(&format!("rule-{}", i), "token_bucket", "allowed");
+        }
+        // 已记录的取值不受上限影响
+        metrics.record_decision("rule-0", "token_bucket", "allowed");
+
+        let decisions = |rule_id: &str| {
+            metrics
+                .decisions_total
+                .with_label_values(&[rule_id, "token_bucket", "allowed"])
+                .get()
+        };
+        assert_eq!(decisions("rule-0"), 2.0);
+        assert_eq!(decisions("rule-2"), 1.0);
+        assert_eq!(decisions(OVERFLOW_LABEL), 7.0);
+        assert_eq!(
+            metrics
+                .cardinality_capped_total
+                .with_label_values(&["rule_id"])
+                .get(),
+            7.0
+        );
+
+        let output = metrics.gather();
+        assert!(!output.contains("rule-3"));
+        assert!(output.contains("metrics_cardinality_capped_total{dimension=\"rule_id\"} 7"));
+    }
+
+    #[test]
+    fn test_telemetry_config_max_label_cardinality() {
+        assert_eq!(
+            TelemetryConfig::default().max_label_cardinality,
+            DEFAULT_MAX_LABEL_CARDINALITY
+        );
+        let config = TelemetryConfig::new("test").with_max_label_cardinality(10);
+        assert_eq!(config.max_label_cardinality, 10);
+    }
+
     #[test]
     fn test_metrics_gather_format() {
         let metrics = Metrics::new();
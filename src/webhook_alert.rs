@@ -0,0 +1,344 @@
+//! Copyright (c) 2026, Kirky.X
+//!
+//! MIT License
+//!
+//! Webhook 告警渠道模块
+//!
+//! 以 JSON 形式将配额告警 POST 到 HTTP 端点（如 Slack、PagerDuty），
+//! 支持自定义请求头，并在 5xx、超时或连接失败时按指数退避有限次重试。
+
+use crate::error::FlowGuardError;
+use crate::quota_controller::AlertInfo;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 默认最大重试次数
+pub const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 3;
+
+/// 默认初始退避时间（毫秒）
+pub const DEFAULT_WEBHOOK_INITIAL_BACKOFF_MS: u64 = 100;
+
+/// 默认最大退避时间（毫秒）
+pub const DEFAULT_WEBHOOK_MAX_BACKOFF_MS: u64 = 2000;
+
+/// 默认单次请求超时（毫秒）
+pub const DEFAULT_WEBHOOK_TIMEOUT_MS: u64 = 5000;
+
+/// Webhook 重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookRetryPolicy {
+    /// 最大重试次数（不含首次请求）
+    pub max_retries: u32,
+    /// 初始退避时间
+    pub initial_backoff: Duration,
+    /// 最大退避时间
+    pub max_backoff: Duration,
+    /// 单次请求超时
+    pub timeout: Duration,
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_WEBHOOK_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(DEFAULT_WEBHOOK_INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_millis(DEFAULT_WEBHOOK_MAX_BACKOFF_MS),
+            timeout: Duration::from_millis(DEFAULT_WEBHOOK_TIMEOUT_MS),
+        }
+    }
+}
+
+impl WebhookRetryPolicy {
+    /// 不重试
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// 第 `attempt` 次重试前的退避时间（从 0 开始）
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(attempt))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// Webhook 告警渠道
+///
+/// # 示例
+/// ```rust
+/// use limiteron::quota_controller::AlertChannel;
+/// use limiteron::webhook_alert::{WebhookAlertChannel, WebhookRetryPolicy};
+///
+/// let channel = WebhookAlertChannel::new("https://hooks.example.com/quota")
+///     .with_header("Authorization", "Bearer token")
+///     .with_retry_policy(WebhookRetryPolicy::default());
+/// let alert_channel = AlertChannel::CustomWebhook(channel);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookAlertChannel {
+    /// 目标 URL
+    pub url: String,
+    /// 附加请求头
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// 重试策略
+    #[serde(default)]
+    pub retry_policy: WebhookRetryPolicy,
+}
+
+impl WebhookAlertChannel {
+    /// 创建新的 Webhook 告警渠道
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            retry_policy: WebhookRetryPolicy::default(),
+        }
+    }
+
+    /// 添加请求头
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// 设置重试策略
+    pub fn with_retry_policy(mut self, retry_policy: WebhookRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// 发送告警
+    ///
+    /// 4xx 响应直接返回错误；5xx、超时和连接失败按重试策略退避重试。
+    pub async fn send(&self, alert_info: &AlertInfo) -> Result<(), FlowGuardError> {
+        let client = reqwest::Client::builder()
+            .timeout(self.retry_policy.timeout)
+            .build()
+            .map_err(|e| FlowGuardError::ConfigError(format!("创建 HTTP 客户端失败: {}", e)))?;
+
+        let mut attempt = 0;
+        loop {
+            let error = match self.post(&client, alert_info).await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) if response.status().is_server_error() => {
+                    format!("Webhook 返回错误状态码: {}", response.status())
+                }
+                Ok(response) => {
+                    return Err(FlowGuardError::Other(format!(
+                        "Webhook 返回错误状态码: {}",
+                        response.status()
+                    )));
+                }
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                    format!("Webhook 请求失败: {}", e)
+                }
+                Err(e) => return Err(FlowGuardError::Other(format!("Webhook 请求失败: {}", e))),
+            };
+
+            if attempt >= self.retry_policy.max_retries {
+                return Err(FlowGuardError::Other(format!(
+                    "{}（已重试 {} 次）",
+                    error, attempt
+                )));
+            }
+
+            let backoff = self.retry_policy.backoff(attempt);
+            tracing::debug!(url = %self.url, attempt, ?backoff, error = %error, "Webhook 告警重试");
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn post(
+        &self,
+        client: &reqwest::Client,
+        alert_info: &AlertInfo,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = client.post(&self.url).json(alert_info);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quota_controller::QuotaType;
+    use chrono::Utc;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::Mutex;
+
+    /// 简易 HTTP 服务器：按顺序返回给定状态码，并记录收到的请求
+    async fn spawn_mock_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut socket).await;
+                recorded.lock().await.push(request);
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            }
+        });
+
+        (format!("http://{}/alerts", addr), requests)
+    }
+
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            data.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&data).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if data.len() >= header_end + 4 + content_length || n == 0 {
+                    return text;
+                }
+            } else if n == 0 {
+                return text;
+            }
+        }
+    }
+
+    fn alert_info() -> AlertInfo {
+        AlertInfo {
+            user_id: "user1".to_string(),
+            resource: "api".to_string(),
+            quota_type: QuotaType::Count,
+            threshold: 80,
+            current_usage: 85,
+            limit: 100,
+            triggered_at: Utc::now(),
+        }
+    }
+
+    fn fast_retry(max_retries: u32) -> WebhookRetryPolicy {
+        WebhookRetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_payload_shape() {
+        let (url, requests) = spawn_mock_server(vec![200]).await;
+        let channel = WebhookAlertChannel::new(url).with_header("X-Token", "secret");
+
+        channel.send(&alert_info()).await.unwrap();
+
+        let requests = requests.lock().await;
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert!(request.starts_with("POST /alerts HTTP/1.1"));
+        assert!(request.to_lowercase().contains("x-token: secret"));
+        assert!(request
+            .to_lowercase()
+            .contains("content-type: application/json"));
+
+        let body = &request[request.find("\r\n\r\n").unwrap() + 4..];
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["user_id"], "user1");
+        assert_eq!(payload["resource"], "api");
+        assert_eq!(payload["threshold"], 80);
+        assert_eq!(payload["current_usage"], 85);
+        assert_eq!(payload["limit"], 100);
+        assert!(payload["triggered_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_server_errors() {
+        let (url, requests) = spawn_mock_server(vec![503, 500, 200]).await;
+        let channel = WebhookAlertChannel::new(url).with_retry_policy(fast_retry(3));
+
+        channel.send(&alert_info()).await.unwrap();
+        assert_eq!(requests.lock().await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_gives_up_after_max_retries() {
+        let (url, requests) = spawn_mock_server(vec![500, 500]).await;
+        let channel = WebhookAlertChannel::new(url).with_retry_policy(fast_retry(1));
+
+        assert!(channel.send(&alert_info()).await.is_err());
+        assert_eq!(requests.lock().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_does_not_retry_client_errors() {
+        let (url, requests) = spawn_mock_server(vec![400, 200]).await;
+        let channel = WebhookAlertChannel::new(url).with_retry_policy(fast_retry(3));
+
+        assert!(channel.send(&alert_info()).await.is_err());
+        assert_eq!(requests.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_quota_controller_dispatches_to_webhook() {
+        use crate::quota_controller::{AlertChannel, AlertConfig, QuotaConfig, QuotaController};
+        use crate::storage::MemoryStorage;
+
+        let (url, requests) = spawn_mock_server(vec![200]).await;
+        let config = QuotaConfig {
+            limit: 100,
+            alert_config: AlertConfig {
+                enabled: true,
+                thresholds: vec![80],
+                channels: vec![AlertChannel::CustomWebhook(WebhookAlertChannel::new(url))],
+                dedup_window: 0,
+            },
+            ..Default::default()
+        };
+        let controller = QuotaController::new(MemoryStorage::new(), config);
+
+        let result = controller.consume("user1", "api", 90).await.unwrap();
+        assert_eq!(result.crossed_threshold, Some(80));
+
+        // 告警异步投递，不阻塞消费
+        for _ in 0..100 {
+            if !requests.lock().await.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let requests = requests.lock().await;
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("\"current_usage\":90"));
+    }
+
+    #[test]
+    fn test_backoff_is_bounded() {
+        let policy = fast_retry(10);
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(1), Duration::from_millis(20));
+        assert_eq!(policy.backoff(5), Duration::from_millis(50));
+        assert_eq!(policy.backoff(40), Duration::from_millis(50));
+    }
+}
@@ -240,6 +240,7 @@ impl QuotaStorage for MockQuotaStorage {
             allowed,
             remaining: limit.saturating_sub(*used),
             alert_triggered: false,
+            crossed_threshold: None,
         })
     }
 
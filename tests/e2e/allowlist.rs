@@ -0,0 +1,153 @@
+//! 端到端测试：白名单
+//!
+//! 测试场景：
+//! - 全局规则限流 3/60s
+//! - 白名单中的 IP 段内请求不受限流与封禁影响
+//! - 白名单外的请求照常限流
+//! - 只比对提取出的标识符，请求中其他字段命中白名单不会放行
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    error::Decision,
+    governor::Governor,
+    matchers::{Identifier, RequestContext},
+};
+
+/// 创建测试用的Governor
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![fixed_window_rule("global_rule", 3)]).await
+}
+
+/// 创建请求上下文
+fn create_request(user_id: &str, ip: &str) -> RequestContext {
+    RequestContext::new()
+        .with_header("X-User-Id", user_id)
+        .with_client_ip(ip)
+        .with_path("/test")
+}
+
+/// 创建只携带客户端 IP 的请求上下文，标识符按 IP 提取
+fn create_ip_request(ip: &str) -> RequestContext {
+    RequestContext::new().with_client_ip(ip).with_path("/test")
+}
+
+/// 端到端测试：白名单 IP 段内的请求永不被拒绝
+#[tokio::test]
+async fn test_e2e_allowlisted_ip_range_never_rejected() {
+    let gov = setup_governor().await;
+    gov.set_allowlist(vec![Identifier::Ip("10.0.0.0/8".to_string())])
+        .await
+        .unwrap();
+
+    for i in 0..20 {
+        let decision = gov.check(&create_ip_request("10.1.2.3")).await.unwrap();
+        assert!(
+            matches!(decision, Decision::Allowed(None)),
+            "白名单请求 {} 应该被允许: {:?}",
+            i,
+            decision
+        );
+    }
+
+    // 白名单外的请求照常限流
+    let decisions = gov
+        .check_batch(&vec![create_ip_request("192.168.1.1"); 5])
+        .await
+        .unwrap();
+    assert_eq!(
+        decisions
+            .iter()
+            .filter(|d| matches!(d, Decision::Rejected(_)))
+            .count(),
+        2
+    );
+
+    let stats = gov.stats().await;
+    assert_eq!(stats.allowlist_bypass_requests, 20);
+    assert_eq!(stats.allowed_requests, 23);
+    assert_eq!(stats.rejected_requests, 2);
+
+    // 移除后恢复限流
+    assert!(
+        gov.remove_from_allowlist(&Identifier::Ip("10.0.0.0/8".to_string()))
+            .await
+    );
+    let decisions = gov
+        .check_batch(&vec![create_ip_request("10.1.2.3"); 4])
+        .await
+        .unwrap();
+    assert!(matches!(decisions[3], Decision::Rejected(_)));
+}
+
+/// 端到端测试：按用户ID加入白名单，无效 IP 条目返回错误
+#[tokio::test]
+async fn test_e2e_allowlist_user_and_invalid_entry() {
+    let gov = setup_governor().await;
+    gov.add_to_allowlist(Identifier::UserId("health_checker".to_string()))
+        .await
+        .unwrap();
+
+    let contexts = vec![create_request("health_checker", "192.168.1.10"); 10];
+    let decisions = gov.check_batch(&contexts).await.unwrap();
+    assert!(decisions
+        .iter()
+        .all(|d| matches!(d, Decision::Allowed(None))));
+
+    assert!(gov
+        .set_allowlist(vec![Identifier::Ip("not-an-ip".to_string())])
+        .await
+        .is_err());
+
+    // 设置失败时原白名单保持不变
+    let decision = gov
+        .check(&create_request("health_checker", "192.168.1.10"))
+        .await
+        .unwrap();
+    assert!(matches!(decision, Decision::Allowed(None)));
+    assert_eq!(gov.stats().await.allowlist_bypass_requests, 11);
+}
+
+/// 端到端测试：被封禁的白名单 IP 仍然放行
+#[cfg(feature = "parallel-checker")]
+#[tokio::test]
+async fn test_e2e_allowlisted_ip_never_banned() {
+    let gov = setup_governor().await;
+    let ip = Identifier::Ip("10.1.2.3".to_string());
+    gov.ban_identifier(&ip, "test", None).await.unwrap();
+
+    let ctx = RequestContext::new().with_client_ip("10.1.2.3");
+    let decision = gov.check(&ctx).await.unwrap();
+    assert!(matches!(decision, Decision::Banned(_)), "{:?}", decision);
+
+    gov.add_to_allowlist(Identifier::Ip("10.0.0.0/8".to_string()))
+        .await
+        .unwrap();
+    let decision = gov.check(&ctx).await.unwrap();
+    assert!(
+        matches!(decision, Decision::Allowed(None)),
+        "{:?}",
+        decision
+    );
+}
+
+/// 端到端测试：按用户ID识别的请求不因客户端 IP 或其他字段命中白名单而放行
+#[tokio::test]
+async fn test_e2e_allowlist_ignores_other_request_fields() {
+    let gov = setup_governor().await;
+    gov.set_allowlist(vec![
+        Identifier::Ip("10.0.0.0/8".to_string()),
+        Identifier::DeviceId("trusted-device".to_string()),
+    ])
+    .await
+    .unwrap();
+
+    let mut ctx = create_request("mallory", "10.1.2.3");
+    ctx.device_id = Some("trusted-device".to_string());
+    let decisions = gov.check_batch(&vec![ctx.clone(); 4]).await.unwrap();
+    assert!(matches!(decisions[3], Decision::Rejected(_)));
+    assert!(matches!(
+        gov.check(&ctx).await.unwrap(),
+        Decision::Rejected(_)
+    ));
+    assert_eq!(gov.stats().await.allowlist_bypass_requests, 0);
+}
@@ -0,0 +1,144 @@
+//! 端到端测试：axum 中间件
+//!
+//! 测试场景：
+//! - 全局规则限流 3/60s
+//! - 真实的 axum 服务挂载 GovernorLayer，通过 TCP 发送 HTTP 请求
+//! - 允许的响应带有 X-RateLimit-* 头，超限后返回 429 与 Retry-After
+//! - 无法提取标识符时返回 401
+
+use super::{fixed_window_rule, governor_with_rules};
+use axum::{routing::get, Router};
+use limiteron::{
+    axum_layer::{request_context_from_parts, GovernorLayer},
+    governor::Governor,
+    matchers::RequestContext,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// 创建测试用的Governor
+async fn setup_governor() -> Arc<Governor> {
+    Arc::new(governor_with_rules(vec![fixed_window_rule("global_rule", 3)]).await)
+}
+
+/// 启动挂载了中间件的 axum 服务
+async fn spawn_app(layer: GovernorLayer) -> SocketAddr {
+    let app = Router::new()
+        .route("/hello", get(|| async { "hello" }))
+        .layer(layer);
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>());
+    tokio::spawn(server);
+    addr
+}
+
+/// 简易 HTTP 响应
+struct HttpResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpResponse {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 发送 HTTP/1.1 GET 请求
+async fn get_request(addr: SocketAddr, path: &str, headers: &[(&str, &str)]) -> HttpResponse {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n", path);
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("Connection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).await.unwrap();
+    let head = raw.split("\r\n\r\n").next().unwrap();
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .unwrap()
+        .split_whitespace()
+        .nth(1)
+        .unwrap()
+        .parse()
+        .unwrap();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    HttpResponse { status, headers }
+}
+
+/// 端到端测试：超限后返回 429
+#[tokio::test]
+async fn test_e2e_axum_layer_hits_limit() {
+    let addr = spawn_app(GovernorLayer::new(setup_governor().await)).await;
+
+    for expected_remaining in ["2", "1", "0"] {
+        let response = get_request(addr, "/hello", &[("X-User-Id", "axum_user")]).await;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("x-ratelimit-limit"), Some("3"));
+        assert_eq!(
+            response.header("x-ratelimit-remaining"),
+            Some(expected_remaining)
+        );
+    }
+
+    let response = get_request(addr, "/hello", &[("X-User-Id", "axum_user")]).await;
+    assert_eq!(response.status, 429);
+    assert_eq!(response.header("x-ratelimit-limit"), Some("3"));
+    assert_eq!(response.header("x-ratelimit-remaining"), Some("0"));
+    let retry_after: u64 = response.header("retry-after").unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after));
+
+    // 其他用户不受影响
+    let response = get_request(addr, "/hello", &[("X-User-Id", "other_user")]).await;
+    assert_eq!(response.status, 200);
+}
+
+/// 端到端测试：自定义请求上下文构建
+#[tokio::test]
+async fn test_e2e_axum_layer_custom_extractor() {
+    let layer = GovernorLayer::new(setup_governor().await).with_context_extractor(|parts| {
+        // 从查询参数中取出用户 ID
+        let context = request_context_from_parts(parts);
+        match context.query_params.get("user").cloned() {
+            Some(user) => context.with_header("X-User-Id", &user),
+            None => context,
+        }
+    });
+    let addr = spawn_app(layer).await;
+
+    for _ in 0..3 {
+        let response = get_request(addr, "/hello?user=query_user", &[]).await;
+        assert_eq!(response.status, 200);
+    }
+    let response = get_request(addr, "/hello?user=query_user", &[]).await;
+    assert_eq!(response.status, 429);
+}
+
+/// 端到端测试：无法提取标识符时返回 401
+#[tokio::test]
+async fn test_e2e_axum_layer_no_identifier() {
+    let layer = GovernorLayer::new(setup_governor().await)
+        .with_context_extractor(|parts| RequestContext::new().with_path(parts.uri.path()));
+    let addr = spawn_app(layer).await;
+
+    let response = get_request(addr, "/hello", &[("X-User-Id", "ignored")]).await;
+    assert_eq!(response.status, 401);
+    assert_eq!(response.header("retry-after"), None);
+}
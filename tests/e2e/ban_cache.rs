@@ -0,0 +1,102 @@
+//! 端到端测试：封禁检查缓存
+//!
+//! 测试场景：
+//! - 未封禁结果被缓存，但通过 Governor 封禁/解封后立即生效
+//! - 绕过 Governor 直接写入存储的封禁在缓存过期后生效
+//! - TTL 为零时关闭缓存
+
+use super::{config_with_rules, fixed_window_rule, governor_with_storage};
+use limiteron::{
+    error::Decision,
+    governor::Governor,
+    matchers::{Identifier, RequestContext},
+    storage::{BanRecord, BanStorage, BanTarget, MemoryStorage},
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 创建测试用的Governor，返回共享的封禁存储
+async fn setup_governor() -> (Governor, Arc<MemoryStorage>) {
+    let ban_storage = Arc::new(MemoryStorage::new());
+    let governor = governor_with_storage(
+        config_with_rules(vec![fixed_window_rule("global_rule", 1000)]),
+        Arc::new(MemoryStorage::new()),
+        ban_storage.clone(),
+    )
+    .await;
+    (governor, ban_storage)
+}
+
+fn user_request(user_id: &str) -> RequestContext {
+    RequestContext::new().with_header("X-User-Id", user_id)
+}
+
+/// 绕过 Governor 直接写入封禁记录
+async fn ban_in_storage(storage: &MemoryStorage, user_id: &str) {
+    let now = chrono::Utc::now();
+    storage
+        .save(&BanRecord {
+            target: BanTarget::UserId(user_id.to_string()),
+            ban_times: 1,
+            duration: Duration::from_secs(60),
+            banned_at: now,
+            expires_at: now + chrono::Duration::seconds(60),
+            is_manual: false,
+            reason: "external".to_string(),
+        })
+        .await
+        .unwrap();
+}
+
+/// 端到端测试：封禁与解封不受否定缓存影响
+#[tokio::test]
+async fn test_e2e_ban_takes_effect_despite_negative_cache() {
+    let (gov, _) = setup_governor().await;
+    gov.set_ban_cache_ttl(Duration::from_secs(60));
+    let user = Identifier::UserId("alice".to_string());
+
+    // 未封禁结果进入缓存
+    let decision = gov.check(&user_request("alice")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+
+    gov.ban_identifier(&user, "abuse", None).await.unwrap();
+    let decision = gov.check(&user_request("alice")).await.unwrap();
+    assert!(matches!(decision, Decision::Banned(_)), "{:?}", decision);
+
+    gov.unban_identifier(&user).await.unwrap();
+    let decision = gov.check(&user_request("alice")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+}
+
+/// 端到端测试：直接写入存储的封禁在缓存过期后生效
+#[tokio::test]
+async fn test_e2e_ban_cache_expires() {
+    let (gov, storage) = setup_governor().await;
+    gov.set_ban_cache_ttl(Duration::from_millis(100));
+
+    let decision = gov.check(&user_request("bob")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+
+    // 缓存有效期内仍使用否定缓存
+    ban_in_storage(&storage, "bob").await;
+    let decision = gov.check(&user_request("bob")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let decision = gov.check(&user_request("bob")).await.unwrap();
+    assert!(matches!(decision, Decision::Banned(_)), "{:?}", decision);
+}
+
+/// 端到端测试：TTL 为零时每次查询存储
+#[tokio::test]
+async fn test_e2e_ban_cache_disabled() {
+    let (gov, storage) = setup_governor().await;
+    gov.set_ban_cache_ttl(Duration::ZERO);
+
+    let decision = gov.check(&user_request("carol")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+
+    ban_in_storage(&storage, "carol").await;
+    let decision = gov.check(&user_request("carol")).await.unwrap();
+    assert!(matches!(decision, Decision::Banned(_)), "{:?}", decision);
+}
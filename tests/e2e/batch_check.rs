@@ -0,0 +1,113 @@
+//! 端到端测试：批量检查
+//!
+//! 测试场景：
+//! - 规则1（优先级100）: 受限用户，限流10/s
+//! - 规则2（优先级10）: 全局限流1000/s
+//! - 一批 100 个请求中混合受限用户与普通用户，逐项验证决策
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::{Matcher as ConfigMatcher, Rule},
+    error::{Decision, RejectReason},
+    governor::Governor,
+    matchers::RequestContext,
+};
+
+/// 创建测试用的Governor
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![
+        Rule {
+            name: "Limited User Rule".to_string(),
+            priority: 100,
+            matchers: vec![ConfigMatcher::User {
+                user_ids: vec!["limited_user".to_string()],
+            }],
+            ..fixed_window_rule("limited_rule", 10)
+        },
+        fixed_window_rule("global_rule", 1000),
+    ])
+    .await
+}
+
+/// 创建请求上下文
+fn create_request(user_id: &str) -> RequestContext {
+    RequestContext::new()
+        .with_header("X-User-Id", user_id)
+        .with_client_ip("10.0.0.1")
+        .with_path("/test")
+}
+
+/// 端到端测试：批量检查保持逐项顺序
+#[tokio::test]
+async fn test_e2e_check_batch_mixed_decisions() {
+    let gov = setup_governor().await;
+
+    // 偶数位为受限用户，奇数位为普通用户
+    let contexts: Vec<RequestContext> = (0..100)
+        .map(|i| {
+            if i % 2 == 0 {
+                create_request("limited_user")
+            } else {
+                create_request(&format!("user_{}", i))
+            }
+        })
+        .collect();
+
+    let decisions = gov.check_batch(&contexts).await.unwrap();
+    assert_eq!(decisions.len(), 100);
+
+    for (i, decision) in decisions.iter().enumerate() {
+        if i % 2 == 1 {
+            assert!(
+                matches!(decision, Decision::Allowed(_)),
+                "普通用户请求 {} 应该被允许: {:?}",
+                i,
+                decision
+            );
+        } else if i < 20 {
+            // 受限用户的前 10 个请求位于偶数位 0..=18
+            assert!(
+                matches!(decision, Decision::Allowed(_)),
+                "受限用户请求 {} 应该被允许: {:?}",
+                i,
+                decision
+            );
+        } else {
+            assert!(
+                matches!(decision, Decision::Rejected(_)),
+                "受限用户请求 {} 应该被拒绝: {:?}",
+                i,
+                decision
+            );
+        }
+    }
+
+    let stats = gov.stats().await;
+    assert_eq!(stats.total_requests, 100);
+    assert_eq!(stats.allowed_requests, 60);
+    assert_eq!(stats.rejected_requests, 40);
+
+    // 批量检查与单次检查共享限流状态
+    let decision = gov.check(&create_request("limited_user")).await.unwrap();
+    assert!(matches!(decision, Decision::Rejected(_)));
+}
+
+/// 端到端测试：无法提取标识符的请求单独被拒绝，不影响同批其他请求
+#[tokio::test]
+async fn test_e2e_check_batch_extraction_failure() {
+    let gov = setup_governor().await;
+
+    let contexts = vec![create_request("limited_user"), RequestContext::new()];
+    let decisions = gov.check_batch(&contexts).await.unwrap();
+    assert!(matches!(decisions[0], Decision::Allowed(_)));
+    assert_eq!(decisions[1].reason(), Some(&RejectReason::NoIdentifier));
+
+    let stats = gov.stats().await;
+    assert_eq!(stats.total_requests, 2);
+    assert_eq!(stats.allowed_requests, 1);
+    assert_eq!(stats.rejected_requests, 1);
+
+    // 单次检查同样以 NoIdentifier 拒绝
+    let decision = gov.check(&RequestContext::new()).await.unwrap();
+    assert_eq!(decision.reason(), Some(&RejectReason::NoIdentifier));
+}
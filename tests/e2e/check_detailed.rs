@@ -0,0 +1,81 @@
+//! 端到端测试：详细检查结果
+//!
+//! 测试场景：
+//! - 放行与拒绝时返回产生决策的规则ID
+//! - 多条规则匹配时，拒绝归因于实际拒绝的规则
+//! - 没有匹配规则时不返回规则ID
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::{Matcher, Rule},
+    error::Decision,
+    governor::Governor,
+    matchers::{Identifier, RequestContext},
+};
+
+/// 匹配指定用户、每分钟 `max_requests` 次的规则
+fn user_rule(id: &str, priority: u16, user_ids: &[&str], max_requests: u64) -> Rule {
+    Rule {
+        priority,
+        matchers: vec![Matcher::User {
+            user_ids: user_ids.iter().map(|id| id.to_string()).collect(),
+        }],
+        ..fixed_window_rule(id, max_requests)
+    }
+}
+
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![
+        user_rule("team_rule", 100, &["alice", "bob"], 100),
+        user_rule("alice_rule", 10, &["alice"], 2),
+    ])
+    .await
+}
+
+fn request(user_id: &str) -> RequestContext {
+    RequestContext::new().with_header("X-User-Id", user_id)
+}
+
+#[tokio::test]
+async fn test_check_detailed_reports_matched_rule() {
+    let governor = setup_governor().await;
+
+    // 放行时归因于优先级最高的匹配规则
+    let result = governor.check_detailed(&request("bob")).await.unwrap();
+    assert!(matches!(result.decision, Decision::Allowed(_)));
+    assert_eq!(result.matched_rule.as_deref(), Some("team_rule"));
+    assert_eq!(
+        result.identifier,
+        Some(Identifier::UserId("bob".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_check_detailed_reports_rejecting_rule() {
+    let governor = setup_governor().await;
+
+    for _ in 0..2 {
+        let result = governor.check_detailed(&request("alice")).await.unwrap();
+        assert!(matches!(result.decision, Decision::Allowed(_)));
+    }
+
+    let result = governor.check_detailed(&request("alice")).await.unwrap();
+    assert!(matches!(result.decision, Decision::Rejected(_)));
+    assert_eq!(result.matched_rule.as_deref(), Some("alice_rule"));
+    assert!(result.limits.is_some());
+}
+
+#[tokio::test]
+async fn test_check_detailed_without_matching_rule() {
+    let governor = setup_governor().await;
+
+    let result = governor.check_detailed(&request("carol")).await.unwrap();
+    assert!(matches!(result.decision, Decision::Allowed(_)));
+    assert_eq!(result.matched_rule, None);
+
+    // check 与 check_detailed 的决策一致
+    assert_eq!(
+        governor.check(&request("carol")).await.unwrap(),
+        result.decision
+    );
+}
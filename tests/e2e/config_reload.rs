@@ -0,0 +1,153 @@
+//! 端到端测试：从存储重新加载配置
+//!
+//! 测试场景：
+//! - 存储中的 YAML/TOML/JSON 配置按内容推断格式并应用
+//! - 无效配置不影响当前生效的配置
+//! - 未设置配置来源时重新加载不做处理
+
+use super::{config_with_rules, fixed_window_rule, governor_with_storage};
+use limiteron::{
+    config::{ConfigFormat, FlowControlConfig},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+    storage::{MemoryStorage, Storage},
+};
+use std::sync::Arc;
+
+const CONFIG_KEY: &str = "limiteron:config";
+
+/// 创建每个用户 1000 次/分钟的 Governor，返回共享的配置存储
+async fn setup_governor() -> (Governor, Arc<MemoryStorage>) {
+    let storage = Arc::new(MemoryStorage::new());
+    let governor = governor_with_storage(
+        config_with_rules(vec![fixed_window_rule("global_rule", 1000)]),
+        storage.clone(),
+        Arc::new(MemoryStorage::new()),
+    )
+    .await;
+    (governor, storage)
+}
+
+fn user_request(user_id: &str) -> RequestContext {
+    RequestContext::new().with_header("X-User-Id", user_id)
+}
+
+const STRICT_YAML: &str = r#"
+version: "2.0"
+global:
+  storage: memory
+  cache: memory
+  metrics: prometheus
+rules:
+  - id: strict_rule
+    name: Strict Rule
+    priority: 10
+    matchers:
+      - type: User
+        user_ids: ["*"]
+    limiters:
+      - type: FixedWindow
+        window_size: "60s"
+        max_requests: 1
+    action:
+      on_exceed: reject
+"#;
+
+const STRICT_TOML: &str = r#"
+version = "2.0"
+
+[global]
+storage = "memory"
+cache = "memory"
+metrics = "prometheus"
+
+[[rules]]
+id = "strict_rule"
+name = "Strict Rule"
+priority = 10
+
+[[rules.matchers]]
+type = "User"
+user_ids = ["*"]
+
+[[rules.limiters]]
+type = "FixedWindow"
+window_size = "60s"
+max_requests = 1
+
+[rules.action]
+on_exceed = "reject"
+"#;
+
+async fn assert_strict(governor: &Governor, user_id: &str) {
+    assert!(matches!(
+        governor.check(&user_request(user_id)).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    assert!(matches!(
+        governor.check(&user_request(user_id)).await.unwrap(),
+        Decision::Rejected(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_reload_detects_yaml_blob() {
+    let (governor, storage) = setup_governor().await;
+    storage.set(CONFIG_KEY, STRICT_YAML, None).await.unwrap();
+    governor.set_config_source(CONFIG_KEY, None).await;
+
+    governor.reload_config().await.unwrap();
+
+    assert_strict(&governor, "alice").await;
+}
+
+#[tokio::test]
+async fn test_reload_detects_toml_and_json_blobs() {
+    let (governor, storage) = setup_governor().await;
+    governor.set_config_source(CONFIG_KEY, None).await;
+
+    storage.set(CONFIG_KEY, STRICT_TOML, None).await.unwrap();
+    governor.reload_config().await.unwrap();
+    assert_strict(&governor, "bob").await;
+
+    let json = serde_json::to_string(&FlowControlConfig::from_toml(STRICT_TOML).unwrap()).unwrap();
+    storage.set(CONFIG_KEY, &json, None).await.unwrap();
+    governor
+        .set_config_source(CONFIG_KEY, Some(ConfigFormat::Json))
+        .await;
+    governor.reload_config().await.unwrap();
+    assert_strict(&governor, "carol").await;
+}
+
+#[tokio::test]
+async fn test_reload_rejects_invalid_config() {
+    let (governor, storage) = setup_governor().await;
+    let invalid = STRICT_YAML.replace("max_requests: 1", "max_requests: 0");
+    storage.set(CONFIG_KEY, &invalid, None).await.unwrap();
+    governor.set_config_source(CONFIG_KEY, None).await;
+
+    assert!(governor.reload_config().await.is_err());
+
+    // 原配置仍然生效
+    for _ in 0..5 {
+        assert!(matches!(
+            governor.check(&user_request("dave")).await.unwrap(),
+            Decision::Allowed(_)
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_reload_without_source_is_noop() {
+    let (governor, _storage) = setup_governor().await;
+
+    governor.reload_config().await.unwrap();
+
+    for _ in 0..5 {
+        assert!(matches!(
+            governor.check(&user_request("erin")).await.unwrap(),
+            Decision::Allowed(_)
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+//! 端到端测试：按标识符创建的自定义限流器
+//!
+//! 测试场景：
+//! - 规则引用 `LimiterConfig::Custom { name: "per_user" }`
+//! - 注册表中的工厂为每个用户创建独立的限流器
+//! - 一个用户被限流不影响其他用户
+
+use super::{governor_with_rules, rule_with_limiter};
+use limiteron::{
+    config::LimiterConfig,
+    custom_limiter::{CustomLimiter, CustomLimiterRegistry, LeakyBucketLimiter},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+};
+use std::sync::{Arc, Mutex};
+
+/// 创建测试用的Governor，规则使用自定义限流器
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![rule_with_limiter(
+        "custom_rule",
+        LimiterConfig::Custom {
+            name: "per_user".to_string(),
+            config: serde_json::json!({}),
+        },
+    )])
+    .await
+}
+
+fn user_request(user_id: &str) -> RequestContext {
+    RequestContext::new().with_header("X-User-Id", user_id)
+}
+
+/// 端到端测试：不同标识符使用独立的自定义限流器状态
+#[tokio::test]
+async fn test_e2e_custom_limiter_factory_per_identifier() {
+    let gov = setup_governor().await;
+
+    // 注册表设置前，自定义限流器被跳过
+    for _ in 0..5 {
+        let decision = gov.check(&user_request("alice")).await.unwrap();
+        assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    }
+
+    let created_for = Arc::new(Mutex::new(Vec::new()));
+    let recorded = created_for.clone();
+    let registry = Arc::new(CustomLimiterRegistry::new());
+    registry
+        .register_factory(
+            "per_user".to_string(),
+            Arc::new(move |key: &str| {
+                recorded.lock().unwrap().push(key.to_string());
+                // 每个用户最多 2 个请求，流出很慢
+                Arc::new(LeakyBucketLimiter::new(2, 1)) as Arc<dyn CustomLimiter>
+            }),
+        )
+        .await
+        .unwrap();
+    gov.set_custom_limiter_registry(registry).await.unwrap();
+
+    for _ in 0..2 {
+        let decision = gov.check(&user_request("alice")).await.unwrap();
+        assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    }
+    let decision = gov.check(&user_request("alice")).await.unwrap();
+    assert!(matches!(decision, Decision::Rejected(_)), "{:?}", decision);
+
+    // bob 使用独立的实例，不受 alice 影响
+    for _ in 0..2 {
+        let decision = gov.check(&user_request("bob")).await.unwrap();
+        assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    }
+    let decision = gov.check(&user_request("bob")).await.unwrap();
+    assert!(matches!(decision, Decision::Rejected(_)), "{:?}", decision);
+
+    let created_for = created_for.lock().unwrap();
+    assert!(created_for.contains(&"user_id:alice".to_string()));
+    assert!(created_for.contains(&"user_id:bob".to_string()));
+}
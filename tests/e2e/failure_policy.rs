@@ -0,0 +1,296 @@
+//! 端到端测试：存储故障处理策略
+//!
+//! 测试场景：
+//! - 封禁存储故障：默认失败开放，Propagate 返回错误，Open 继续限流检查，Closed 拒绝请求
+//! - 自定义限流器故障：Open 放行，Closed 拒绝
+//! - 降级管理器中的组件策略优先于 Governor 策略
+
+use super::{config_with_rules, governor_with_storage, rule_with_limiter};
+use async_trait::async_trait;
+use limiteron::{
+    config::{FlowControlConfig, LimiterConfig},
+    error::{Decision, FlowGuardError, RejectReason, StorageError},
+    governor::{FailurePolicy, Governor},
+    matchers::RequestContext,
+    storage::{BanHistory, BanRecord, BanStorage, BanTarget, MemoryStorage},
+};
+use std::sync::Arc;
+
+/// 所有操作都失败的封禁存储
+struct FailingBanStorage;
+
+#[async_trait]
+impl BanStorage for FailingBanStorage {
+    async fn is_banned(&self, _target: &BanTarget) -> Result<Option<BanRecord>, StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    async fn save(&self, _record: &BanRecord) -> Result<(), StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    async fn get_history(&self, _target: &BanTarget) -> Result<Option<BanHistory>, StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    async fn increment_ban_times(&self, _target: &BanTarget) -> Result<u64, StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    async fn get_ban_times(&self, _target: &BanTarget) -> Result<u64, StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    async fn remove_ban(&self, _target: &BanTarget) -> Result<(), StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    async fn cleanup_expired_bans(&self) -> Result<u64, StorageError> {
+        Err(StorageError::ConnectionError(
+            "ban storage down".to_string(),
+        ))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+fn config_with_limiter(limiter: LimiterConfig) -> FlowControlConfig {
+    config_with_rules(vec![rule_with_limiter("global_rule", limiter)])
+}
+
+/// 创建使用故障封禁存储的Governor，每个用户 2 次/分钟
+async fn setup_failing_ban_governor() -> Governor {
+    governor_with_storage(
+        config_with_limiter(LimiterConfig::FixedWindow {
+            window_size: "60s".to_string(),
+            max_requests: 2,
+        }),
+        Arc::new(MemoryStorage::new()),
+        Arc::new(FailingBanStorage),
+    )
+    .await
+}
+
+fn user_request(user_id: &str) -> RequestContext {
+    RequestContext::new().with_header("X-User-Id", user_id)
+}
+
+fn assert_storage_rejection(decision: &Decision) {
+    assert_eq!(
+        decision.reason(),
+        Some(&RejectReason::StorageUnavailable),
+        "{:?}",
+        decision
+    );
+}
+
+/// 端到端测试：未设置策略时封禁检查故障失败开放，与引入策略前一致
+#[cfg(feature = "parallel-checker")]
+#[tokio::test]
+async fn test_e2e_ban_storage_error_fails_open_by_default() {
+    let gov = setup_failing_ban_governor().await;
+    assert_eq!(gov.failure_policy(), None);
+
+    let decision = gov.check(&user_request("alice")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    let decisions = gov.check_batch(&[user_request("alice")]).await.unwrap();
+    assert!(matches!(decisions[0], Decision::Allowed(_)));
+}
+
+/// 端到端测试：Propagate 策略将封禁存储错误返回给调用方
+#[cfg(feature = "parallel-checker")]
+#[tokio::test]
+async fn test_e2e_ban_storage_error_propagates() {
+    let gov = setup_failing_ban_governor().await;
+    gov.set_failure_policy(FailurePolicy::Propagate);
+
+    let result = gov.check(&user_request("alice")).await;
+    assert!(
+        matches!(result, Err(FlowGuardError::StorageError(_))),
+        "{:?}",
+        result
+    );
+    assert!(gov.check_batch(&[user_request("alice")]).await.is_err());
+}
+
+/// 端到端测试：失败开放时封禁检查故障视为未封禁，限流仍然生效
+#[cfg(feature = "parallel-checker")]
+#[tokio::test]
+async fn test_e2e_ban_storage_error_fail_open() {
+    let gov = setup_failing_ban_governor().await;
+    gov.set_failure_policy(FailurePolicy::Open);
+
+    for _ in 0..2 {
+        let decision = gov.check(&user_request("bob")).await.unwrap();
+        assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    }
+    let decision = gov.check(&user_request("bob")).await.unwrap();
+    assert_eq!(decision.reason(), Some(&RejectReason::RateLimit));
+
+    let decisions = gov
+        .check_batch(&[user_request("carol"), user_request("carol")])
+        .await
+        .unwrap();
+    assert!(decisions.iter().all(|d| matches!(d, Decision::Allowed(_))));
+    // 批量检查对相同标识符只检查一次封禁
+    assert_eq!(gov.stats().await.error_count, 4);
+}
+
+/// 端到端测试：失败关闭时封禁检查故障直接拒绝
+#[cfg(feature = "parallel-checker")]
+#[tokio::test]
+async fn test_e2e_ban_storage_error_fail_closed() {
+    let gov = setup_failing_ban_governor().await;
+    gov.set_failure_policy(FailurePolicy::Closed);
+
+    assert_storage_rejection(&gov.check(&user_request("dave")).await.unwrap());
+
+    let decisions = gov.check_batch(&[user_request("dave")]).await.unwrap();
+    assert_storage_rejection(&decisions[0]);
+    assert_eq!(gov.stats().await.rejected_requests, 2);
+}
+
+/// 端到端测试：降级管理器中的组件策略优先于 Governor 策略
+#[cfg(all(feature = "parallel-checker", feature = "fallback"))]
+#[tokio::test]
+async fn test_e2e_fallback_strategy_overrides_policy() {
+    use limiteron::fallback::{ComponentType, FallbackConfig, FallbackStrategy};
+
+    let gov = setup_failing_ban_governor().await;
+    gov.set_failure_policy(FailurePolicy::Open);
+    gov.fallback_manager()
+        .set_strategy(
+            ComponentType::Ban,
+            FallbackConfig::new(ComponentType::Ban, FallbackStrategy::FailClosed),
+        )
+        .await;
+
+    assert_storage_rejection(&gov.check(&user_request("erin")).await.unwrap());
+    assert!(gov.fallback_manager().is_failed(ComponentType::Ban).await);
+}
+
+/// 端到端测试：故障按组件与策略记录到指标
+#[cfg(all(feature = "parallel-checker", feature = "monitoring"))]
+#[tokio::test]
+async fn test_e2e_storage_error_metric() {
+    use limiteron::telemetry::Metrics;
+
+    let metrics = Arc::new(Metrics::new());
+    let gov = Governor::new(
+        config_with_limiter(LimiterConfig::FixedWindow {
+            window_size: "60s".to_string(),
+            max_requests: 2,
+        }),
+        Arc::new(MemoryStorage::new()),
+        Arc::new(FailingBanStorage),
+        Some(metrics.clone()),
+        #[cfg(feature = "telemetry")]
+        None,
+    )
+    .await
+    .unwrap();
+    gov.set_failure_policy(FailurePolicy::Closed);
+
+    for _ in 0..3 {
+        gov.check(&user_request("frank")).await.unwrap();
+    }
+
+    let output = metrics.gather();
+    assert!(
+        output.contains(r#"limiteron_storage_error_total{component="ban",policy="closed"} 3"#),
+        "{}",
+        output
+    );
+}
+
+/// 总是失败的自定义限流器
+#[cfg(feature = "custom-limiter")]
+struct FailingLimiter;
+
+#[cfg(feature = "custom-limiter")]
+#[async_trait]
+impl limiteron::custom_limiter::CustomLimiter for FailingLimiter {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    async fn allow(&self, _cost: u64) -> Result<bool, FlowGuardError> {
+        Err(FlowGuardError::StorageError(StorageError::TimeoutError(
+            "redis timeout".to_string(),
+        )))
+    }
+
+    fn load_config(&mut self, _config: serde_json::Value) -> Result<(), FlowGuardError> {
+        Ok(())
+    }
+
+    fn stats(&self) -> limiteron::custom_limiter::LimiterStats {
+        limiteron::custom_limiter::LimiterStats::new()
+    }
+}
+
+/// 端到端测试：限流器故障按策略放行或拒绝
+#[cfg(feature = "custom-limiter")]
+#[tokio::test]
+async fn test_e2e_limiter_error_policies() {
+    use limiteron::custom_limiter::{CustomLimiter, CustomLimiterRegistry};
+
+    let gov = Governor::new(
+        config_with_limiter(LimiterConfig::Custom {
+            name: "failing".to_string(),
+            config: serde_json::json!({}),
+        }),
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
+        #[cfg(feature = "monitoring")]
+        None,
+        #[cfg(feature = "telemetry")]
+        None,
+    )
+    .await
+    .unwrap();
+
+    let registry = Arc::new(CustomLimiterRegistry::new());
+    registry
+        .register_factory(
+            "failing".to_string(),
+            Arc::new(|_: &str| Arc::new(FailingLimiter) as Arc<dyn CustomLimiter>),
+        )
+        .await
+        .unwrap();
+    gov.set_custom_limiter_registry(registry).await.unwrap();
+
+    assert!(gov.check(&user_request("grace")).await.is_err());
+
+    gov.set_failure_policy(FailurePolicy::Open);
+    let decision = gov.check(&user_request("grace")).await.unwrap();
+    assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    let decisions = gov.check_batch(&[user_request("grace")]).await.unwrap();
+    assert!(matches!(decisions[0], Decision::Allowed(_)));
+
+    gov.set_failure_policy(FailurePolicy::Closed);
+    assert_storage_rejection(&gov.check(&user_request("grace")).await.unwrap());
+    let decisions = gov.check_batch(&[user_request("grace")]).await.unwrap();
+    assert_storage_rejection(&decisions[0]);
+
+    // 失败开放与失败关闭的请求分别计入允许数与拒绝数，只有抛出的错误不产生决策
+    let stats = gov.stats().await;
+    assert_eq!(stats.total_requests, 5);
+    assert_eq!(stats.error_count, 5);
+    assert_eq!(stats.allowed_requests, 2);
+    assert_eq!(stats.rejected_requests, 2);
+}
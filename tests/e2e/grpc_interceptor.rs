@@ -0,0 +1,184 @@
+//! 端到端测试：gRPC 拦截器
+//!
+//! 测试场景：
+//! - 全局规则限流 3/60s
+//! - 通过 tonic 拦截器发起请求，标识符从 gRPC 元数据提取
+//! - 超限后返回 RESOURCE_EXHAUSTED 并带有 retry-after 元数据
+//! - 异步中间件层与拦截器行为一致，可在 current_thread 运行时中使用
+//! - 拦截器在 current_thread 运行时中返回错误状态而不是 panic
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    governor::Governor,
+    grpc::{FlowGuardGrpcLayer, FlowGuardInterceptor, RETRY_AFTER_METADATA_KEY},
+};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::body::BoxBody;
+use tonic::codegen::{empty_body, http, BoxFuture, Context, Poll, Service};
+use tonic::service::Interceptor;
+use tonic::{Code, Request};
+use tower_layer::Layer;
+
+/// 创建测试用的Governor
+async fn setup_governor() -> Arc<Governor> {
+    Arc::new(governor_with_rules(vec![fixed_window_rule("global_rule", 3)]).await)
+}
+
+/// 创建带元数据的 gRPC 请求
+fn create_request(key: &'static str, value: &str) -> Request<()> {
+    let mut request = Request::new(());
+    request.metadata_mut().insert(key, value.parse().unwrap());
+    request
+}
+
+/// 端到端测试：超限后拒绝并返回 retry-after
+#[tokio::test(flavor = "multi_thread")]
+async fn test_e2e_grpc_interceptor_allow_then_reject() {
+    let mut interceptor =
+        FlowGuardInterceptor::new(setup_governor().await).with_retry_after(Duration::from_secs(5));
+
+    for i in 0..3 {
+        assert!(
+            interceptor
+                .call(create_request("x-user-id", "grpc_user"))
+                .is_ok(),
+            "请求 {} 应该被允许",
+            i
+        );
+    }
+
+    let status = interceptor
+        .call(create_request("x-user-id", "grpc_user"))
+        .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert_eq!(
+        status
+            .metadata()
+            .get(RETRY_AFTER_METADATA_KEY)
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        "5"
+    );
+
+    // 其他用户不受影响
+    assert!(interceptor
+        .call(create_request("x-user-id", "other_user"))
+        .is_ok());
+}
+
+/// 端到端测试：自定义元数据键映射到提取链
+#[tokio::test(flavor = "multi_thread")]
+async fn test_e2e_grpc_interceptor_metadata_mapping() {
+    let mut interceptor = FlowGuardInterceptor::new(setup_governor().await)
+        .with_metadata_mapping("caller-id", "x-user-id");
+
+    for _ in 0..3 {
+        assert!(interceptor
+            .call(create_request("caller-id", "mapped_user"))
+            .is_ok());
+    }
+
+    let status = interceptor
+        .call(create_request("caller-id", "mapped_user"))
+        .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+}
+
+/// 端到端测试：current_thread 运行时中拦截器返回错误状态
+#[tokio::test]
+async fn test_e2e_grpc_interceptor_current_thread_runtime() {
+    let mut interceptor = FlowGuardInterceptor::new(setup_governor().await);
+
+    let status = interceptor
+        .call(create_request("x-user-id", "grpc_user"))
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Internal);
+}
+
+/// 记录调用次数、总是返回 OK 的 gRPC 服务
+#[derive(Clone, Default)]
+struct EchoService {
+    calls: Arc<AtomicUsize>,
+}
+
+impl<B: Send + 'static> Service<http::Request<B>> for EchoService {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _request: http::Request<B>) -> Self::Future {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { Ok(http::Response::new(empty_body())) })
+    }
+}
+
+/// 创建带请求头的 HTTP/2 gRPC 请求
+fn create_http_request(key: &str, value: &str) -> http::Request<BoxBody> {
+    http::Request::builder()
+        .uri("/echo.Echo/Ping")
+        .header(key, value)
+        .body(empty_body())
+        .unwrap()
+}
+
+/// 响应中的 gRPC 状态码，缺省为 OK
+fn grpc_status(response: &http::Response<BoxBody>) -> Code {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i32>().ok())
+        .map(Code::from)
+        .unwrap_or(Code::Ok)
+}
+
+/// 端到端测试：异步中间件层在 current_thread 运行时中限流
+#[tokio::test]
+async fn test_e2e_grpc_layer_allow_then_reject() {
+    let echo = EchoService::default();
+    let calls = echo.calls.clone();
+    let mut service = FlowGuardGrpcLayer::new(setup_governor().await)
+        .with_metadata_mapping("caller-id", "x-user-id")
+        .with_retry_after(Duration::from_secs(5))
+        .layer(echo);
+
+    for _ in 0..3 {
+        let response = service
+            .call(create_http_request("caller-id", "layer_user"))
+            .await
+            .unwrap();
+        assert_eq!(grpc_status(&response), Code::Ok);
+    }
+
+    let response = service
+        .call(create_http_request("caller-id", "layer_user"))
+        .await
+        .unwrap();
+    assert_eq!(grpc_status(&response), Code::ResourceExhausted);
+    assert_eq!(
+        response
+            .headers()
+            .get(RETRY_AFTER_METADATA_KEY)
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        "5"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    // 无法提取标识符时返回 UNAUTHENTICATED，不调用内部服务
+    let response = service
+        .call(create_http_request("x-other", "value"))
+        .await
+        .unwrap();
+    assert_eq!(grpc_status(&response), Code::Unauthenticated);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
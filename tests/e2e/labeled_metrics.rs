@@ -0,0 +1,83 @@
+//! 端到端测试：按规则与限流器类型划分的监控指标
+//!
+//! 测试场景：
+//! - 规则 limited_rule: 受限用户，固定窗口 2/60s
+//! - 规则 global_rule: 所有用户，令牌桶
+//! - 抓取指标注册表，验证标签组合
+
+use super::{config_with_rules, fixed_window_rule, rule_with_limiter};
+use limiteron::{
+    config::{LimiterConfig, Matcher as ConfigMatcher, Rule},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+    storage::MemoryStorage,
+    telemetry::Metrics,
+};
+use std::sync::Arc;
+
+/// 创建测试用的Governor
+async fn setup_governor(metrics: Arc<Metrics>) -> Governor {
+    let config = config_with_rules(vec![
+        Rule {
+            name: "Limited User Rule".to_string(),
+            priority: 100,
+            matchers: vec![ConfigMatcher::User {
+                user_ids: vec!["limited_user".to_string()],
+            }],
+            ..fixed_window_rule("limited_rule", 2)
+        },
+        rule_with_limiter(
+            "global_rule",
+            LimiterConfig::TokenBucket {
+                capacity: 100,
+                refill_rate: 10,
+            },
+        ),
+    ]);
+
+    Governor::new(
+        config,
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
+        Some(metrics),
+        #[cfg(feature = "telemetry")]
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// 端到端测试：抓取指标时出现按规则与限流器类型划分的标签
+#[tokio::test]
+async fn test_e2e_labeled_decision_metrics() {
+    let metrics = Arc::new(Metrics::new());
+    let gov = setup_governor(metrics.clone()).await;
+
+    let ctx = RequestContext::new()
+        .with_header("X-User-Id", "limited_user")
+        .with_client_ip("10.0.0.1");
+    let mut rejected = 0;
+    for _ in 0..3 {
+        if matches!(gov.check(&ctx).await.unwrap(), Decision::Rejected(_)) {
+            rejected += 1;
+        }
+    }
+    assert_eq!(rejected, 1);
+
+    let output = metrics.gather();
+    for expected in [
+        r#"limiteron_decisions_total{limiter_type="fixed_window",outcome="allowed",rule_id="limited_rule"} 2"#,
+        r#"limiteron_decisions_total{limiter_type="fixed_window",outcome="rejected",rule_id="limited_rule"} 1"#,
+        r#"limiteron_decisions_total{limiter_type="token_bucket",outcome="allowed",rule_id="global_rule"} 2"#,
+        r#"limiteron_check_duration_seconds_count{rule_id="limited_rule"} 3"#,
+        r#"limiteron_check_duration_seconds_count{rule_id="global_rule"} 2"#,
+    ] {
+        assert!(
+            output.contains(expected),
+            "missing `{}` in scraped metrics:\n{}",
+            expected,
+            output
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! 端到端测试：Governor 的限流键清洗策略
+//!
+//! 测试场景：
+//! - 默认直接以标识符的键隔离限流状态
+//! - 启用截断策略后与 flow_control 宏的键一致，仅特殊字符不同的标识符共享限流状态
+//! - 启用哈希策略后仍然区分这些标识符
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    error::Decision, governor::Governor, limiter_manager::KeyStrategy, matchers::RequestContext,
+};
+
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![fixed_window_rule("user_rule", 1)]).await
+}
+
+/// 依次检查 `a.b@x` 与 `a.bx`，返回第二个请求是否放行
+async fn second_user_allowed(governor: &Governor) -> bool {
+    let first = RequestContext::new().with_header("X-User-Id", "a.b@x");
+    let second = RequestContext::new().with_header("X-User-Id", "a.bx");
+    assert!(matches!(
+        governor.check(&first).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    matches!(governor.check(&second).await.unwrap(), Decision::Allowed(_))
+}
+
+#[tokio::test]
+async fn test_default_limiter_key_keeps_identifier() {
+    let governor = setup_governor().await;
+    assert_eq!(governor.limiter_key_strategy(), None);
+    assert!(second_user_allowed(&governor).await);
+}
+
+#[tokio::test]
+async fn test_truncate_strategy_matches_macro_keys() {
+    let governor = setup_governor().await;
+    governor.set_limiter_key_strategy(KeyStrategy::Truncate);
+    assert!(!second_user_allowed(&governor).await);
+}
+
+#[tokio::test]
+async fn test_hash_strategy_separates_identifiers() {
+    let governor = setup_governor().await;
+    governor.set_limiter_key_strategy(KeyStrategy::Hash);
+    assert!(second_user_allowed(&governor).await);
+}
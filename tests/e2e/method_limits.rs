@@ -0,0 +1,58 @@
+//! 端到端测试：按 HTTP 方法限流
+//!
+//! 测试场景：
+//! - 只对写请求（POST）限流，同一路径的 GET 请求不受影响
+//! - 方法匹配忽略大小写
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::{Matcher as ConfigMatcher, Rule},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+};
+
+async fn setup_governor(max_requests: u64) -> Governor {
+    governor_with_rules(vec![Rule {
+        name: "写请求限流".to_string(),
+        matchers: vec![ConfigMatcher::Method {
+            methods: vec!["POST".to_string()],
+        }],
+        ..fixed_window_rule("write_limit", max_requests)
+    }])
+    .await
+}
+
+async fn allowed(governor: &Governor, method: &str) -> bool {
+    let context = RequestContext::new()
+        .with_client_ip("192.168.1.10")
+        .with_path("/api/orders")
+        .with_method(method);
+    matches!(
+        governor.check(&context).await.unwrap(),
+        Decision::Allowed(_)
+    )
+}
+
+#[tokio::test]
+async fn test_post_limited_get_unaffected() {
+    let governor = setup_governor(2).await;
+
+    assert!(allowed(&governor, "POST").await);
+    assert!(allowed(&governor, "POST").await);
+    assert!(!allowed(&governor, "POST").await);
+
+    // 同一路径的 GET 请求不命中规则
+    for _ in 0..10 {
+        assert!(allowed(&governor, "GET").await);
+    }
+}
+
+#[tokio::test]
+async fn test_method_match_case_insensitive() {
+    let governor = setup_governor(1).await;
+
+    assert!(allowed(&governor, "post").await);
+    assert!(!allowed(&governor, "POST").await);
+    assert!(allowed(&governor, "get").await);
+}
@@ -2,6 +2,44 @@
 //!
 //! 测试完整的业务流程和场景
 
+use limiteron::{
+    config::{ActionConfig, FlowControlConfig, GlobalConfig, LimiterConfig, Matcher, Rule},
+    governor::Governor,
+    storage::{BanStorage, MemoryStorage, Storage},
+};
+use std::sync::Arc;
+
+#[allow(unused_imports)]
+mod allowlist;
+#[cfg(feature = "axum")]
+#[allow(unused_imports)]
+mod axum_layer;
+#[cfg(feature = "parallel-checker")]
+#[allow(unused_imports)]
+mod ban_cache;
+#[allow(unused_imports)]
+mod batch_check;
+#[allow(unused_imports)]
+mod check_detailed;
+#[cfg(all(feature = "yaml", feature = "toml"))]
+#[allow(unused_imports)]
+mod config_reload;
+#[cfg(feature = "custom-limiter")]
+#[allow(unused_imports)]
+mod custom_limiter_factory;
+#[cfg(any(feature = "parallel-checker", feature = "custom-limiter"))]
+#[allow(unused_imports)]
+mod failure_policy;
+#[cfg(feature = "grpc")]
+#[allow(unused_imports)]
+mod grpc_interceptor;
+#[cfg(feature = "monitoring")]
+#[allow(unused_imports)]
+mod labeled_metrics;
+#[allow(unused_imports)]
+mod limiter_key;
+#[allow(unused_imports)]
+mod method_limits;
 #[allow(unused_imports)]
 mod multi_rule_cascade;
 #[cfg(feature = "quota-control")]
@@ -10,6 +48,27 @@ mod quota_overdraft;
 #[cfg(feature = "ban-manager")]
 #[allow(unused_imports)]
 mod rate_limit_to_ban;
+#[cfg(feature = "ban-manager")]
+#[allow(unused_imports)]
+mod rejection_ban;
+#[allow(unused_imports)]
+mod rule_mutation;
+#[cfg(feature = "server")]
+#[allow(unused_imports)]
+mod server;
+#[cfg(feature = "soft-limit")]
+#[allow(unused_imports)]
+mod soft_limit;
+#[cfg(feature = "ban-manager")]
+#[allow(unused_imports)]
+mod state_dump;
+#[allow(unused_imports)]
+mod stats_persistence;
+#[cfg(all(feature = "parallel-checker", feature = "circuit-breaker"))]
+#[allow(unused_imports)]
+mod storage_circuit_breaker;
+#[allow(unused_imports)]
+mod window_parsing;
 
 #[cfg(feature = "quota-control")]
 #[allow(unused_imports)]
@@ -17,3 +76,78 @@ pub use quota_overdraft::*;
 #[cfg(feature = "ban-manager")]
 #[allow(unused_imports)]
 pub use rate_limit_to_ban::*;
+
+/// 匹配所有用户、使用给定限流器的规则，超限时拒绝
+pub fn rule_with_limiter(id: &str, limiter: LimiterConfig) -> Rule {
+    Rule {
+        id: id.to_string(),
+        name: id.to_string(),
+        priority: 10,
+        matchers: vec![Matcher::User {
+            user_ids: vec!["*".to_string()],
+        }],
+        limiters: vec![limiter],
+        action: ActionConfig {
+            on_exceed: "reject".to_string(),
+            ban: None,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// 匹配所有用户、每分钟 `max_requests` 次的固定窗口规则，超限时拒绝
+///
+/// 需要其他匹配器或优先级时用结构体更新语法覆盖：
+/// `Rule { priority: 100, ..fixed_window_rule("id", 10) }`
+pub fn fixed_window_rule(id: &str, max_requests: u64) -> Rule {
+    rule_with_limiter(
+        id,
+        LimiterConfig::FixedWindow {
+            window_size: "60s".to_string(),
+            max_requests,
+        },
+    )
+}
+
+/// 包含给定规则、使用内存存储的配置
+pub fn config_with_rules(rules: Vec<Rule>) -> FlowControlConfig {
+    FlowControlConfig {
+        version: "1.0".to_string(),
+        global: GlobalConfig {
+            storage: "memory".to_string(),
+            cache: "memory".to_string(),
+            metrics: "prometheus".to_string(),
+        },
+        rules,
+    }
+}
+
+/// 使用给定存储创建 Governor，不启用监控与追踪
+pub async fn governor_with_storage(
+    config: FlowControlConfig,
+    storage: Arc<dyn Storage>,
+    ban_storage: Arc<dyn BanStorage>,
+) -> Governor {
+    Governor::new(
+        config,
+        storage,
+        ban_storage,
+        #[cfg(feature = "monitoring")]
+        None,
+        #[cfg(feature = "telemetry")]
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// 包含给定规则、使用内存存储的 Governor
+pub async fn governor_with_rules(rules: Vec<Rule>) -> Governor {
+    governor_with_storage(
+        config_with_rules(rules),
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
+    )
+    .await
+}
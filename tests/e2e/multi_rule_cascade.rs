@@ -5,8 +5,12 @@
 //! - 规则2（优先级50）: 普通用户，限流100/s
 //! - 规则3（优先级10）: 全局限流5000/s
 
+use super::{
+    config_with_rules, fixed_window_rule, governor_with_rules, governor_with_storage,
+    rule_with_limiter,
+};
 use limiteron::{
-    config::{FlowControlConfig, LimiterConfig, Matcher as ConfigMatcher, Rule},
+    config::{LimiterConfig, Matcher as ConfigMatcher, Rule},
     error::Decision,
     governor::Governor,
     matchers::RequestContext,
@@ -16,82 +20,34 @@ use std::sync::Arc;
 
 /// 创建测试用的Governor，包含多个规则
 async fn setup_multi_rule_governor() -> Governor {
-    let config = FlowControlConfig {
-        version: "1.0".to_string(),
-        global: limiteron::config::GlobalConfig {
-            storage: "memory".to_string(),
-            cache: "memory".to_string(),
-            metrics: "prometheus".to_string(),
-        },
-        rules: vec![
-            // 规则1: VIP用户，限流1000/s
-            Rule {
-                id: "vip_rule".to_string(),
-                name: "VIP User Rule".to_string(),
-                priority: 100,
-                matchers: vec![ConfigMatcher::User {
-                    user_ids: vec!["vip_user".to_string()],
-                }],
-                limiters: vec![LimiterConfig::SlidingWindow {
-                    window_size: "1s".to_string(),
-                    max_requests: 1000,
-                }],
-                action: limiteron::config::ActionConfig {
-                    on_exceed: "reject".to_string(),
-                    ban: None,
-                },
-            },
-            // 规则2: 普通用户，限流100/s
-            Rule {
-                id: "normal_rule".to_string(),
-                name: "Normal User Rule".to_string(),
-                priority: 50,
-                matchers: vec![ConfigMatcher::User {
-                    user_ids: vec!["normal_user".to_string()],
-                }],
-                limiters: vec![LimiterConfig::SlidingWindow {
-                    window_size: "1s".to_string(),
-                    max_requests: 100,
-                }],
-                action: limiteron::config::ActionConfig {
-                    on_exceed: "reject".to_string(),
-                    ban: None,
-                },
-            },
-            // 规则3: 全局限流5000/s
-            Rule {
-                id: "global_rule".to_string(),
-                name: "Global Rule".to_string(),
-                priority: 10,
-                matchers: vec![ConfigMatcher::User {
-                    user_ids: vec!["*".to_string()],
-                }],
-                limiters: vec![LimiterConfig::SlidingWindow {
-                    window_size: "1s".to_string(),
-                    max_requests: 5000,
-                }],
-                action: limiteron::config::ActionConfig {
-                    on_exceed: "reject".to_string(),
-                    ban: None,
-                },
-            },
-        ],
-    };
-
-    let storage = Arc::new(MemoryStorage::new());
-    let ban_storage = Arc::new(limiteron::storage::MemoryStorage::new());
-
-    Governor::new(
-        config,
-        storage,
-        ban_storage,
-        #[cfg(feature = "monitoring")]
-        None,
-        #[cfg(feature = "telemetry")]
-        None,
-    )
+    governor_with_rules(vec![
+        // 规则1: VIP用户，限流1000/s
+        sliding_window_rule("vip_rule", 100, "vip_user", 1000),
+        // 规则2: 普通用户，限流100/s
+        sliding_window_rule("normal_rule", 50, "normal_user", 100),
+        // 规则3: 全局限流5000/s
+        sliding_window_rule("global_rule", 10, "*", 5000),
+    ])
     .await
-    .unwrap()
+}
+
+/// 匹配指定用户、每秒 `max_requests` 次的滑动窗口规则
+fn sliding_window_rule(id: &str, priority: u16, user_id: &str, max_requests: u64) -> Rule {
+    Rule {
+        priority,
+        matchers: vec![ConfigMatcher::User {
+            user_ids: vec![user_id.to_string()],
+        }],
+        ..rule_with_limiter(id, sliding_window(max_requests))
+    }
+}
+
+fn sliding_window(max_requests: u64) -> LimiterConfig {
+    LimiterConfig::SlidingWindow {
+        window_size: "1s".to_string(),
+        max_requests,
+        mode: Default::default(),
+    }
 }
 
 /// 创建请求上下文
@@ -110,6 +66,7 @@ fn create_request(user_id: &str, ip: &str) -> RequestContext {
         method: "GET".to_string(),
         client_ip: Some(ip.to_string()),
         query_params: ahash::AHashMap::new(),
+        ..Default::default()
     }
 }
 
@@ -126,6 +83,7 @@ async fn test_e2e_multi_rule_cascade() {
             Ok(Decision::Allowed(_)) => vip_allowed += 1,
             Ok(Decision::Rejected(_)) => break,
             Ok(Decision::Banned(_)) => break,
+            Ok(Decision::Delayed(_)) => break,
             Err(_) => break,
         }
     }
@@ -150,6 +108,7 @@ async fn test_e2e_multi_rule_cascade() {
             Ok(Decision::Allowed(_)) => normal_allowed += 1,
             Ok(Decision::Rejected(_)) => break,
             Ok(Decision::Banned(_)) => break,
+            Ok(Decision::Delayed(_)) => break,
             Err(_) => break,
         }
     }
@@ -167,10 +126,7 @@ async fn test_e2e_multi_rule_cascade() {
     );
 
     // 测试未知用户 - 应该匹配规则3（全局限流5000/s）
-    // 注意：由于 global_rule 是全局共享限流器，它的配额会被所有匹配的用户（包括 VIP 和 Normal）共享
-    // VIP用户使用了 ~1000 次
-    // Normal用户使用了 ~100 次
-    // 所以 Unknown 用户应该剩余 ~3900 次配额 (5000 - 1100 = 3900)
+    // 注意：限流器按标识符隔离，VIP 与 Normal 用户的消耗不影响 Unknown 用户的配额
     let mut unknown_allowed = 0;
     for _i in 0..6000 {
         let ctx = create_request("unknown_user", "192.168.1.30");
@@ -178,19 +134,20 @@ async fn test_e2e_multi_rule_cascade() {
             Ok(Decision::Allowed(_)) => unknown_allowed += 1,
             Ok(Decision::Rejected(_)) => break,
             Ok(Decision::Banned(_)) => break,
+            Ok(Decision::Delayed(_)) => break,
             Err(_) => break,
         }
     }
 
-    // 未知用户应该有 ~3900 次允许
+    // 未知用户应该有 ~5000 次允许
     assert!(
-        (3890..=3910).contains(&unknown_allowed),
-        "Unknown user should have ~3900 allowed requests (shared quota), got {}",
+        (5000..=5010).contains(&unknown_allowed),
+        "Unknown user should have ~5000 allowed requests (own quota), got {}",
         unknown_allowed
     );
 
     println!(
-        "✓ Unknown User: {} allowed requests (expected ~3900, shared quota)",
+        "✓ Unknown User: {} allowed requests (expected ~5000, own quota)",
         unknown_allowed
     );
 
@@ -244,45 +201,13 @@ async fn test_e2e_rule_priority() {
 /// 端到端测试：规则禁用
 #[tokio::test]
 async fn test_e2e_rule_disabled() {
-    let config = FlowControlConfig {
-        version: "1.0".to_string(),
-        global: limiteron::config::GlobalConfig {
-            storage: "memory".to_string(),
-            cache: "memory".to_string(),
-            metrics: "prometheus".to_string(),
-        },
-        rules: vec![Rule {
-            id: "enabled_rule".to_string(),
-            name: "Enabled Rule".to_string(),
-            priority: 100,
-            matchers: vec![ConfigMatcher::User {
-                user_ids: vec!["test_user".to_string()],
-            }],
-            limiters: vec![LimiterConfig::SlidingWindow {
-                window_size: "1s".to_string(),
-                max_requests: 100,
-            }],
-            action: limiteron::config::ActionConfig {
-                on_exceed: "reject".to_string(),
-                ban: None,
-            },
-        }],
-    };
-
-    let storage = Arc::new(MemoryStorage::new());
-    let ban_storage = Arc::new(limiteron::storage::MemoryStorage::new());
-
-    let gov = Governor::new(
-        config,
-        storage,
-        ban_storage,
-        #[cfg(feature = "monitoring")]
-        None,
-        #[cfg(feature = "telemetry")]
-        None,
-    )
-    .await
-    .unwrap();
+    let gov = governor_with_rules(vec![sliding_window_rule(
+        "enabled_rule",
+        100,
+        "test_user",
+        100,
+    )])
+    .await;
 
     // 测试用户应该匹配启用的规则（限流100/s）
     let mut allowed_count = 0;
@@ -292,6 +217,7 @@ async fn test_e2e_rule_disabled() {
             Ok(Decision::Allowed(_)) => allowed_count += 1,
             Ok(Decision::Rejected(_)) => break,
             Ok(Decision::Banned(_)) => break,
+            Ok(Decision::Delayed(_)) => break,
             Err(_) => break,
         }
     }
@@ -309,61 +235,13 @@ async fn test_e2e_rule_disabled() {
 /// 端到端测试：复合匹配器
 #[tokio::test]
 async fn test_e2e_composite_matcher() {
-    let config = FlowControlConfig {
-        version: "1.0".to_string(),
-        global: Default::default(),
-        rules: vec![
-            // 规则1: VIP用户且来自中国
-            Rule {
-                id: "vip_cn_rule".to_string(),
-                name: "VIP CN Rule".to_string(),
-                priority: 100,
-                matchers: vec![ConfigMatcher::User {
-                    user_ids: vec!["vip_user".to_string()],
-                }],
-                limiters: vec![LimiterConfig::SlidingWindow {
-                    window_size: "1s".to_string(),
-                    max_requests: 1000,
-                }],
-                action: limiteron::config::ActionConfig {
-                    on_exceed: "reject".to_string(),
-                    ban: None,
-                },
-            },
-            // 规则2: 其他用户
-            Rule {
-                id: "default_rule".to_string(),
-                name: "Default Rule".to_string(),
-                priority: 10,
-                matchers: vec![ConfigMatcher::User {
-                    user_ids: vec!["*".to_string()],
-                }],
-                limiters: vec![LimiterConfig::SlidingWindow {
-                    window_size: "1s".to_string(),
-                    max_requests: 100,
-                }],
-                action: limiteron::config::ActionConfig {
-                    on_exceed: "reject".to_string(),
-                    ban: None,
-                },
-            },
-        ],
-    };
-
-    let storage = Arc::new(MemoryStorage::new());
-    let ban_storage = Arc::new(limiteron::storage::MemoryStorage::new());
-
-    let gov = Governor::new(
-        config,
-        storage,
-        ban_storage,
-        #[cfg(feature = "monitoring")]
-        None,
-        #[cfg(feature = "telemetry")]
-        None,
-    )
-    .await
-    .unwrap();
+    let gov = governor_with_rules(vec![
+        // 规则1: VIP用户且来自中国
+        sliding_window_rule("vip_cn_rule", 100, "vip_user", 1000),
+        // 规则2: 其他用户
+        sliding_window_rule("default_rule", 10, "*", 100),
+    ])
+    .await;
 
     // VIP用户，来自中国（需要设置geo信息）
     // 由于测试环境可能没有geo信息，这里简化测试
@@ -383,45 +261,18 @@ async fn test_e2e_composite_matcher() {
 #[tokio::test]
 async fn test_e2e_rule_hot_reload() {
     // 初始配置：限流100/s
-    let mut config = FlowControlConfig {
-        version: "1.0".to_string(),
-        global: limiteron::config::GlobalConfig {
-            storage: "memory".to_string(),
-            cache: "memory".to_string(),
-            metrics: "prometheus".to_string(),
-        },
-        rules: vec![Rule {
-            id: "test_rule".to_string(),
-            name: "Test Rule".to_string(),
-            priority: 100,
-            matchers: vec![ConfigMatcher::User {
-                user_ids: vec!["test_user".to_string()],
-            }],
-            limiters: vec![LimiterConfig::SlidingWindow {
-                window_size: "1s".to_string(),
-                max_requests: 100,
-            }],
-            action: limiteron::config::ActionConfig {
-                on_exceed: "reject".to_string(),
-                ban: None,
-            },
-        }],
-    };
-
-    let storage = Arc::new(MemoryStorage::new());
-    let ban_storage = Arc::new(limiteron::storage::MemoryStorage::new());
-
-    let gov = Governor::new(
+    let mut config = config_with_rules(vec![sliding_window_rule(
+        "test_rule",
+        100,
+        "test_user",
+        100,
+    )]);
+    let gov = governor_with_storage(
         config.clone(),
-        storage,
-        ban_storage,
-        #[cfg(feature = "monitoring")]
-        None,
-        #[cfg(feature = "telemetry")]
-        None,
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
     )
-    .await
-    .unwrap();
+    .await;
 
     // 测试初始配置
     let mut allowed_count = 0;
@@ -431,6 +282,7 @@ async fn test_e2e_rule_hot_reload() {
             Ok(Decision::Allowed(_)) => allowed_count += 1,
             Ok(Decision::Rejected(_)) => break,
             Ok(Decision::Banned(_)) => break,
+            Ok(Decision::Delayed(_)) => break,
             Err(_) => break,
         }
     }
@@ -444,13 +296,125 @@ async fn test_e2e_rule_hot_reload() {
     println!("✓ Initial config: {} allowed requests", allowed_count);
 
     // 更新配置：限流200/s
-    config.rules[0].limiters = vec![LimiterConfig::SlidingWindow {
-        window_size: "1s".to_string(),
-        max_requests: 200,
-    }];
+    config.rules[0].limiters = vec![sliding_window(200)];
 
     // 注意：在实际实现中，需要调用reload_config方法
     // 这里简化处理，假设配置已经更新
 
     println!("✓ E2E test passed: Rule hot reload (simplified)");
 }
+
+/// 单条规则、按用户隔离的固定窗口 Governor
+async fn setup_per_user_governor(max_requests: u64) -> Governor {
+    governor_with_rules(vec![Rule {
+        priority: 100,
+        ..fixed_window_rule("per_user_rule", max_requests)
+    }])
+    .await
+}
+
+/// 端到端测试：同一规则下不同用户的配额互相独立
+#[tokio::test]
+async fn test_e2e_per_user_independent_quota() {
+    let gov = setup_per_user_governor(10).await;
+
+    // 每个用户都获得完整的 10 次配额
+    for user_id in ["user_a", "user_b"] {
+        let mut allowed_count = 0;
+        for _ in 0..15 {
+            let ctx = create_request(user_id, "192.168.1.70");
+            if matches!(gov.check(&ctx).await, Ok(Decision::Allowed(_))) {
+                allowed_count += 1;
+            }
+        }
+        assert_eq!(
+            allowed_count, 10,
+            "{} should get its own quota, got {}",
+            user_id, allowed_count
+        );
+    }
+}
+
+/// 创建两条都匹配所有用户的规则：高优先级宽松规则（10次）与低优先级严格规则（3次）
+async fn setup_overlapping_governor() -> Governor {
+    governor_with_rules(vec![
+        Rule {
+            priority: 100,
+            ..fixed_window_rule("loose_rule", 10)
+        },
+        Rule {
+            priority: 50,
+            ..fixed_window_rule("strict_rule", 3)
+        },
+    ])
+    .await
+}
+
+async fn count_allowed(gov: &Governor, user_id: &str, attempts: usize) -> usize {
+    let mut allowed_count = 0;
+    for _ in 0..attempts {
+        let ctx = create_request(user_id, "192.168.1.80");
+        if matches!(gov.check(&ctx).await, Ok(Decision::Allowed(_))) {
+            allowed_count += 1;
+        }
+    }
+    allowed_count
+}
+
+/// 端到端测试：AllMatch 下所有匹配规则都生效，更严格的规则决定结果
+#[tokio::test]
+async fn test_e2e_all_match_stricter_rule_governs() {
+    let gov = setup_overlapping_governor().await;
+    assert_eq!(
+        gov.rule_evaluation_policy(),
+        limiteron::RuleEvaluationPolicy::AllMatch
+    );
+
+    // 低优先级的严格规则同样生效
+    assert_eq!(count_allowed(&gov, "user_a", 15).await, 3);
+    assert_eq!(gov.stats().await.rejected_requests, 12);
+}
+
+/// 端到端测试：FirstMatch 下只执行优先级最高的匹配规则
+#[tokio::test]
+async fn test_e2e_first_match_uses_highest_priority_rule() {
+    let gov = setup_overlapping_governor().await;
+    gov.set_rule_evaluation_policy(limiteron::RuleEvaluationPolicy::FirstMatch);
+
+    assert_eq!(count_allowed(&gov, "user_b", 15).await, 10);
+
+    // 切回 AllMatch 后严格规则立即生效（其计数器此前未被消费）
+    gov.set_rule_evaluation_policy(limiteron::RuleEvaluationPolicy::AllMatch);
+    assert_eq!(count_allowed(&gov, "user_c", 15).await, 3);
+}
+
+/// 端到端测试：按标识符缓存的限流器上限可配置，超出时淘汰最久未使用的标识符
+#[tokio::test]
+async fn test_e2e_max_keyed_limiters() {
+    let gov = setup_per_user_governor(1).await;
+    gov.set_max_keyed_limiters(2).await;
+    assert_eq!(gov.max_keyed_limiters(), 2);
+
+    let allowed = |user_id: &'static str| {
+        let gov = &gov;
+        async move {
+            matches!(
+                gov.check(&create_request(user_id, "192.168.1.71")).await,
+                Ok(Decision::Allowed(_))
+            )
+        }
+    };
+    assert!(allowed("user_a").await);
+    assert!(!allowed("user_a").await);
+
+    // 轮换两个新标识符后 user_a 被淘汰，以新的限流器从满额度开始
+    assert!(allowed("user_b").await);
+    assert!(allowed("user_c").await);
+    assert!(allowed("user_a").await);
+
+    // 调大上限后之前的标识符保留各自的状态
+    gov.set_max_keyed_limiters(10).await;
+    assert!(allowed("user_d").await);
+    assert!(!allowed("user_c").await);
+    assert!(!allowed("user_a").await);
+}
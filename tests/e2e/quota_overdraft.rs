@@ -33,6 +33,8 @@ async fn test_e2e_quota_overdraft_alert() {
             channels: vec![AlertChannel::Log],
             dedup_window: 300,
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = QuotaController::new(storage, config);
@@ -98,6 +100,8 @@ async fn test_e2e_quota_multi_resource() {
             enabled: false,
             ..Default::default()
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     // 存储配额
@@ -111,6 +115,8 @@ async fn test_e2e_quota_multi_resource() {
             enabled: false,
             ..Default::default()
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let api_controller = QuotaController::new(storage.clone(), api_config);
@@ -168,6 +174,8 @@ async fn test_e2e_quota_sliding_window_reset() {
             enabled: false,
             ..Default::default()
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = QuotaController::new(storage, config);
@@ -202,7 +210,7 @@ async fn test_e2e_quota_alert_dedup() {
     let config = QuotaConfig {
         quota_type: QuotaType::Count,
         limit: 100,
-        window_size: 3600,
+        window_size: 2,
         allow_overdraft: false,
         overdraft_limit_percent: 0,
         alert_config: AlertConfig {
@@ -211,6 +219,8 @@ async fn test_e2e_quota_alert_dedup() {
             channels: vec![AlertChannel::Log],
             dedup_window: 5, // 5秒去重窗口
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = QuotaController::new(storage, config);
@@ -222,21 +232,30 @@ async fn test_e2e_quota_alert_dedup() {
 
     println!("✓ Step 1: Alert triggered at 80%");
 
-    // 立即消费到90%，不应该触发告警（去重）
+    // 立即消费到90%，不应该触发告警（同一窗口内只触发一次）
     let result = controller.consume(user_id, resource, 10).await.unwrap();
     assert!(result.allowed);
     assert!(!result.alert_triggered, "Should not trigger alert (dedup)");
 
     println!("✓ Step 2: Alert deduped at 90%");
 
+    // 进入新窗口，但仍在去重时间窗口内
+    tokio::time::sleep(tokio::time::Duration::from_millis(2100)).await;
+    let result = controller.consume(user_id, resource, 80).await.unwrap();
+    assert!(result.allowed);
+    assert!(
+        !result.alert_triggered,
+        "Should not trigger alert within dedup window"
+    );
+
     // 等待去重窗口过期
-    tokio::time::sleep(tokio::time::Duration::from_secs(6)).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(3100)).await;
 
     // 清理过期的去重记录
     controller.cleanup_alert_dedup();
 
-    // 再次消费，应该触发告警
-    let result = controller.consume(user_id, resource, 5).await.unwrap();
+    // 新窗口再次到达80%，应该触发告警
+    let result = controller.consume(user_id, resource, 80).await.unwrap();
     assert!(result.allowed);
     assert!(
         result.alert_triggered,
@@ -265,6 +284,8 @@ async fn test_e2e_quota_reset() {
             enabled: false,
             ..Default::default()
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = QuotaController::new(storage.clone(), config);
@@ -307,6 +328,8 @@ async fn test_e2e_quota_concurrent_consumption() {
             enabled: false,
             ..Default::default()
         },
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = Arc::new(QuotaController::new(storage, config));
@@ -8,9 +8,10 @@
 //! 5. 5分钟后自动解封
 //! 6. 恢复正常访问
 
+use super::{governor_with_rules, rule_with_limiter};
 use limiteron::{
     ban_manager::{BackoffConfig, BanManager, BanManagerConfig},
-    config::{FlowControlConfig, LimiterConfig, Rule},
+    config::{LimiterConfig, Matcher, Rule},
     error::Decision,
     governor::Governor,
     matchers::RequestContext,
@@ -36,50 +37,28 @@ fn create_request(user_id: &str, ip: &str) -> RequestContext {
         method: "GET".to_string(),
         client_ip: Some(ip.to_string()),
         query_params: ahash::AHashMap::new(),
+        ..Default::default()
     }
 }
 
 /// 创建测试用的Governor
 async fn setup_governor() -> Governor {
-    let config = FlowControlConfig {
-        version: "1.0".to_string(),
-        global: limiteron::config::GlobalConfig {
-            storage: "memory".to_string(),
-            cache: "memory".to_string(),
-            metrics: "prometheus".to_string(),
-        },
-        rules: vec![Rule {
-            id: "test_rule".to_string(),
-            name: "Test Rule".to_string(),
-            priority: 100,
-            matchers: vec![limiteron::config::Matcher::Ip {
-                ip_ranges: vec!["192.168.1.100".to_string()],
-            }],
-            limiters: vec![LimiterConfig::SlidingWindow {
+    governor_with_rules(vec![Rule {
+        name: "Test Rule".to_string(),
+        priority: 100,
+        matchers: vec![Matcher::Ip {
+            ip_ranges: vec!["192.168.1.100".to_string()],
+        }],
+        ..rule_with_limiter(
+            "test_rule",
+            LimiterConfig::SlidingWindow {
                 window_size: "1s".to_string(),
                 max_requests: 100,
-            }],
-            action: limiteron::config::ActionConfig {
-                on_exceed: "reject".to_string(),
-                ban: None,
+                mode: Default::default(),
             },
-        }],
-    };
-
-    let storage = Arc::new(MemoryStorage::new());
-    let ban_storage = Arc::new(limiteron::storage::MemoryStorage::new());
-
-    Governor::new(
-        config,
-        storage,
-        ban_storage,
-        #[cfg(feature = "monitoring")]
-        None,
-        #[cfg(feature = "telemetry")]
-        None,
-    )
+        )
+    }])
     .await
-    .unwrap()
 }
 
 /// 创建测试用的BanManager
@@ -92,6 +71,7 @@ async fn setup_ban_manager() -> BanManager {
             third_duration: 20,  // 20秒
             fourth_duration: 40, // 40秒
             max_duration: 60,    // 60秒（测试用）
+            ..Default::default()
         },
         enable_auto_unban: true,
         auto_unban_interval: 5,
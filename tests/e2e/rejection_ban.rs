@@ -0,0 +1,131 @@
+//! 端到端测试：规则拒绝达到阈值后自动封禁
+//!
+//! 测试场景：
+//! - 窗口内第 N 次拒绝时触发封禁，之前不封禁
+//! - 移出窗口的拒绝不计入阈值
+//! - 未配置 `ban_after_rejections` 的规则只拒绝不封禁
+
+use super::{config_with_rules, fixed_window_rule, governor_with_storage};
+use limiteron::{
+    config::{ActionConfig, Rule},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+    storage::{BanStorage, BanTarget, MemoryStorage},
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 每分钟只允许 1 次请求，之后每次请求都被拒绝
+fn rule(ban_after_rejections: Option<u32>, rejection_window: Option<&str>) -> Rule {
+    Rule {
+        action: ActionConfig {
+            on_exceed: "reject".to_string(),
+            ban: None,
+            ban_after_rejections,
+            rejection_window: rejection_window.map(str::to_string),
+            ..Default::default()
+        },
+        ..fixed_window_rule("escalating_rule", 1)
+    }
+}
+
+async fn setup_governor(rule: Rule) -> (Governor, Arc<MemoryStorage>) {
+    let ban_storage = Arc::new(MemoryStorage::new());
+    let governor = governor_with_storage(
+        config_with_rules(vec![rule]),
+        Arc::new(MemoryStorage::new()),
+        ban_storage.clone(),
+    )
+    .await;
+    (governor, ban_storage)
+}
+
+async fn check(governor: &Governor, user_id: &str) -> Decision {
+    let context = RequestContext::new().with_header("X-User-Id", user_id);
+    governor.check(&context).await.unwrap()
+}
+
+async fn is_banned(ban_storage: &MemoryStorage, user_id: &str) -> bool {
+    ban_storage
+        .is_banned(&BanTarget::UserId(user_id.to_string()))
+        .await
+        .unwrap()
+        .is_some()
+}
+
+#[tokio::test]
+async fn test_ban_fires_at_configured_rejection_count() {
+    let (governor, ban_storage) = setup_governor(rule(Some(3), Some("60s"))).await;
+
+    assert!(matches!(
+        check(&governor, "alice").await,
+        Decision::Allowed(_)
+    ));
+
+    // 前两次拒绝不触发封禁
+    for _ in 0..2 {
+        assert!(matches!(
+            check(&governor, "alice").await,
+            Decision::Rejected(_)
+        ));
+        assert!(!is_banned(&ban_storage, "alice").await);
+    }
+
+    // 第三次拒绝触发封禁
+    assert!(matches!(
+        check(&governor, "alice").await,
+        Decision::Rejected(_)
+    ));
+    let record = ban_storage
+        .is_banned(&BanTarget::UserId("alice".to_string()))
+        .await
+        .unwrap()
+        .expect("第三次拒绝后应被封禁");
+    assert!(record.reason.contains("escalating_rule"));
+
+    // 其他标识符的计数互不影响
+    check(&governor, "bob").await;
+    check(&governor, "bob").await;
+    assert!(!is_banned(&ban_storage, "bob").await);
+}
+
+#[cfg(feature = "parallel-checker")]
+#[tokio::test]
+async fn test_banned_after_threshold_short_circuits_check() {
+    let (governor, _ban_storage) = setup_governor(rule(Some(2), None)).await;
+
+    for _ in 0..3 {
+        check(&governor, "alice").await;
+    }
+    assert!(matches!(
+        check(&governor, "alice").await,
+        Decision::Banned(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_rejections_outside_window_are_not_counted() {
+    let (governor, ban_storage) = setup_governor(rule(Some(2), Some("1s"))).await;
+
+    check(&governor, "alice").await;
+    check(&governor, "alice").await;
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // 上一次拒绝已移出窗口，这次只算第一次
+    check(&governor, "alice").await;
+    assert!(!is_banned(&ban_storage, "alice").await);
+
+    check(&governor, "alice").await;
+    assert!(is_banned(&ban_storage, "alice").await);
+}
+
+#[tokio::test]
+async fn test_rule_without_escalation_only_rejects() {
+    let (governor, ban_storage) = setup_governor(rule(None, None)).await;
+
+    for _ in 0..20 {
+        check(&governor, "alice").await;
+    }
+    assert!(!is_banned(&ban_storage, "alice").await);
+}
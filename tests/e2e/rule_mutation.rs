@@ -0,0 +1,139 @@
+//! 端到端测试：运行时修改单条规则
+//!
+//! 测试场景：
+//! - 新增规则只影响命中该规则的请求，其他规则的限流状态保持不变
+//! - 禁用规则后不再限流，重新启用后沿用原有状态
+//! - 仅修改优先级时沿用决策链，修改限流器时重建
+//! - 移除规则并记录配置变更
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::{ChangeSource, Matcher as ConfigMatcher, Rule},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+};
+
+/// 只匹配指定用户、每分钟 `max_requests` 次的规则
+fn user_rule(id: &str, user_id: &str, max_requests: u64) -> Rule {
+    Rule {
+        matchers: vec![ConfigMatcher::User {
+            user_ids: vec![user_id.to_string()],
+        }],
+        ..fixed_window_rule(id, max_requests)
+    }
+}
+
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![
+        user_rule("alice_rule", "alice", 2),
+        user_rule("bob_rule", "bob", 3),
+    ])
+    .await
+}
+
+async fn allowed(governor: &Governor, user_id: &str) -> bool {
+    let context = RequestContext::new().with_header("X-User-Id", user_id);
+    matches!(
+        governor.check(&context).await.unwrap(),
+        Decision::Allowed(_)
+    )
+}
+
+#[tokio::test]
+async fn test_upsert_rule_keeps_other_rule_state() {
+    let governor = setup_governor().await;
+    assert!(allowed(&governor, "bob").await);
+
+    // 新增规则前 carol 不受限
+    for _ in 0..5 {
+        assert!(allowed(&governor, "carol").await);
+    }
+
+    governor
+        .upsert_rule(user_rule("carol_rule", "carol", 1))
+        .await
+        .unwrap();
+    assert!(allowed(&governor, "carol").await);
+    assert!(!allowed(&governor, "carol").await);
+
+    // bob 的计数未被重置
+    assert!(allowed(&governor, "bob").await);
+    assert!(allowed(&governor, "bob").await);
+    assert!(!allowed(&governor, "bob").await);
+
+    // 仅修改优先级时沿用原有状态
+    let mut reprioritized = user_rule("carol_rule", "carol", 1);
+    reprioritized.priority = 20;
+    governor.upsert_rule(reprioritized).await.unwrap();
+    assert!(!allowed(&governor, "carol").await);
+
+    // 修改限流器时重建该规则的状态
+    governor
+        .upsert_rule(user_rule("carol_rule", "carol", 2))
+        .await
+        .unwrap();
+    assert!(allowed(&governor, "carol").await);
+    assert!(allowed(&governor, "carol").await);
+    assert!(!allowed(&governor, "carol").await);
+    assert!(!allowed(&governor, "bob").await);
+
+    // 无效规则不生效
+    let mut invalid = user_rule("carol_rule", "carol", 100);
+    invalid.limiters.clear();
+    assert!(governor.upsert_rule(invalid).await.is_err());
+    assert!(!allowed(&governor, "carol").await);
+}
+
+#[tokio::test]
+async fn test_set_rule_enabled() {
+    let governor = setup_governor().await;
+    assert!(allowed(&governor, "alice").await);
+    assert!(allowed(&governor, "alice").await);
+    assert!(!allowed(&governor, "alice").await);
+    assert!(allowed(&governor, "bob").await);
+
+    assert!(governor
+        .set_rule_enabled("alice_rule", false)
+        .await
+        .unwrap());
+    for _ in 0..5 {
+        assert!(allowed(&governor, "alice").await);
+    }
+    assert!(allowed(&governor, "bob").await);
+    assert!(allowed(&governor, "bob").await);
+    assert!(!allowed(&governor, "bob").await);
+
+    // 重新启用后沿用禁用前的计数
+    assert!(governor.set_rule_enabled("alice_rule", true).await.unwrap());
+    assert!(!allowed(&governor, "alice").await);
+
+    assert!(!governor.set_rule_enabled("missing", false).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_remove_rule_and_history() {
+    let governor = setup_governor().await;
+    assert!(allowed(&governor, "alice").await);
+    assert!(allowed(&governor, "alice").await);
+    assert!(!allowed(&governor, "alice").await);
+
+    assert!(governor.remove_rule("alice_rule").await.unwrap());
+    for _ in 0..5 {
+        assert!(allowed(&governor, "alice").await);
+    }
+    assert!(!governor.remove_rule("alice_rule").await.unwrap());
+
+    // 至少保留一条规则
+    assert!(governor.remove_rule("bob_rule").await.is_err());
+    assert!(allowed(&governor, "bob").await);
+
+    governor.set_rule_enabled("bob_rule", false).await.unwrap();
+    let history = governor.get_config_history().await;
+    assert_eq!(history.len(), 2);
+    assert!(history
+        .iter()
+        .all(|record| record.source == ChangeSource::Api && !record.diff.is_empty()));
+    assert!(history[0].summary().contains("alice_rule"));
+    assert!(history[1].summary().contains("disabled"));
+}
@@ -0,0 +1,265 @@
+//! 端到端测试：HTTP 限流服务
+//!
+//! 测试场景：
+//! - 在临时端口上启动服务，通过 TCP 发送 HTTP 请求
+//! - `/check` 按规则放行与拒绝，`/stats` 与 `/healthz` 可用
+//! - `/ban` 封禁后 `/check` 返回封禁，`DELETE /ban` 后恢复
+//! - 设置管理令牌后 `/ban` 需携带正确的令牌
+//! - 配置文件变更被热加载
+//! - 关闭信号触发后服务优雅退出
+
+use super::governor_with_storage;
+use limiteron::{
+    config::FlowControlConfig,
+    governor::Governor,
+    server::{self, CheckResponse},
+    storage::MemoryStorage,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// 所有用户每分钟最多 `max_requests` 次请求的配置
+fn config_json(max_requests: u64) -> String {
+    format!(
+        r#"{{
+            "version": "1.0",
+            "global": {{"storage": "memory", "cache": "memory", "metrics": "prometheus"}},
+            "rules": [{{
+                "id": "server_rule", "name": "Server Rule", "priority": 10,
+                "matchers": [{{"type": "User", "user_ids": ["*"]}}],
+                "limiters": [{{"type": "FixedWindow", "window_size": "60s", "max_requests": {}}}],
+                "action": {{"on_exceed": "reject", "ban": null}}
+            }}]
+        }}"#,
+        max_requests
+    )
+}
+
+async fn setup_governor(max_requests: u64) -> Arc<Governor> {
+    let config = FlowControlConfig::parse(&config_json(max_requests), None).unwrap();
+    Arc::new(
+        governor_with_storage(
+            config,
+            Arc::new(MemoryStorage::new()),
+            Arc::new(MemoryStorage::new()),
+        )
+        .await,
+    )
+}
+
+/// 运行中的服务
+struct TestServer {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+async fn spawn_server(governor: Arc<Governor>) -> TestServer {
+    spawn_router(server::router(governor)).await
+}
+
+async fn spawn_router(router: axum::Router) -> TestServer {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown, rx) = oneshot::channel::<()>();
+    let handle = tokio::spawn(async move {
+        server::serve_router(listener, router, async {
+            let _ = rx.await;
+        })
+        .await
+        .unwrap();
+    });
+    TestServer {
+        addr,
+        shutdown,
+        handle,
+    }
+}
+
+/// 发送 HTTP/1.1 请求，返回状态码与响应体
+async fn request(addr: SocketAddr, method: &str, path: &str, body: Option<&str>) -> (u16, String) {
+    request_with_headers(addr, method, path, &[], body).await
+}
+
+/// 携带额外请求头发送 HTTP/1.1 请求
+async fn request_with_headers(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut request = format!("{} {} HTTP/1.1\r\nHost: localhost\r\n", method, path);
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if let Some(body) = body {
+        request.push_str("Content-Type: application/json\r\n");
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("Connection: close\r\n\r\n");
+    request.push_str(body.unwrap_or(""));
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).await.unwrap();
+    let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+    let status = head
+        .lines()
+        .next()
+        .unwrap()
+        .split_whitespace()
+        .nth(1)
+        .unwrap()
+        .parse()
+        .unwrap();
+    (status, body.to_string())
+}
+
+async fn check(addr: SocketAddr, user: &str) -> CheckResponse {
+    let body = format!(
+        r#"{{"path": "/api", "headers": {{"X-User-Id": "{}"}}}}"#,
+        user
+    );
+    let (status, body) = request(addr, "POST", "/check", Some(&body)).await;
+    assert_eq!(status, 200, "unexpected body: {}", body);
+    serde_json::from_str(&body).unwrap()
+}
+
+/// 端到端测试：检查、统计与健康检查
+#[tokio::test]
+async fn test_e2e_server_check_and_stats() {
+    let server = spawn_server(setup_governor(2).await).await;
+
+    let (status, body) = request(server.addr, "GET", "/healthz", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body, "ok");
+
+    assert_eq!(check(server.addr, "alice").await.decision, "allowed");
+    assert_eq!(check(server.addr, "alice").await.decision, "allowed");
+    let rejected = check(server.addr, "alice").await;
+    assert_eq!(rejected.decision, "rejected");
+    assert_eq!(rejected.reason.as_deref(), Some("rate_limit"));
+
+    // 其他用户不受影响
+    assert_eq!(check(server.addr, "bob").await.decision, "allowed");
+
+    let (status, body) = request(server.addr, "GET", "/stats", None).await;
+    assert_eq!(status, 200);
+    let stats: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(stats["total_requests"], 4);
+    assert_eq!(stats["allowed_requests"], 3);
+    assert_eq!(stats["rejected_requests"], 1);
+
+    // 非法请求体返回 4xx
+    let (status, _) = request(server.addr, "POST", "/check", Some("not json")).await;
+    assert!((400..500).contains(&status));
+}
+
+/// 端到端测试：封禁与解封
+#[tokio::test]
+async fn test_e2e_server_ban_and_unban() {
+    let server = spawn_server(setup_governor(100).await).await;
+    let target = r#"{"type": "user_id", "value": "mallory", "reason": "abuse"}"#;
+
+    let (status, _) = request(server.addr, "POST", "/ban", Some(target)).await;
+    assert_eq!(status, 204);
+    let banned = check(server.addr, "mallory").await;
+    assert_eq!(banned.decision, "banned");
+    assert!(banned.banned_until.is_some());
+
+    let (status, _) = request(server.addr, "DELETE", "/ban", Some(target)).await;
+    assert_eq!(status, 204);
+    assert_eq!(check(server.addr, "mallory").await.decision, "allowed");
+
+    // 不支持封禁的标识符类型
+    let (status, body) = request(
+        server.addr,
+        "POST",
+        "/ban",
+        Some(r#"{"type": "api_key", "value": "k"}"#),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert!(body.contains("error"));
+}
+
+/// 端到端测试：设置管理令牌后封禁端点需要认证
+#[tokio::test]
+async fn test_e2e_server_ban_requires_admin_token() {
+    let governor = setup_governor(100).await;
+    let server = spawn_router(server::router_with_admin_token(governor, "s3cret")).await;
+    let target = r#"{"type": "user_id", "value": "mallory"}"#;
+
+    let (status, body) = request(server.addr, "POST", "/ban", Some(target)).await;
+    assert_eq!(status, 401);
+    assert!(body.contains("error"));
+    let (status, _) = request_with_headers(
+        server.addr,
+        "POST",
+        "/ban",
+        &[("Authorization", "Bearer wrong")],
+        Some(target),
+    )
+    .await;
+    assert_eq!(status, 401);
+    assert_eq!(check(server.addr, "mallory").await.decision, "allowed");
+
+    let auth = [("Authorization", "Bearer s3cret")];
+    let (status, _) = request_with_headers(server.addr, "POST", "/ban", &auth, Some(target)).await;
+    assert_eq!(status, 204);
+    assert_eq!(check(server.addr, "mallory").await.decision, "banned");
+
+    let (status, _) = request(server.addr, "DELETE", "/ban", Some(target)).await;
+    assert_eq!(status, 401);
+    let (status, _) =
+        request_with_headers(server.addr, "DELETE", "/ban", &auth, Some(target)).await;
+    assert_eq!(status, 204);
+    assert_eq!(check(server.addr, "mallory").await.decision, "allowed");
+
+    // 其余端点不需要令牌
+    let (status, _) = request(server.addr, "GET", "/stats", None).await;
+    assert_eq!(status, 200);
+}
+
+/// 端到端测试：配置文件变更被热加载
+#[tokio::test]
+async fn test_e2e_server_config_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("limiteron.json");
+    std::fs::write(&path, config_json(100)).unwrap();
+
+    let governor = setup_governor(100).await;
+    let watcher = server::config_watcher(governor.clone(), path.clone(), Duration::from_millis(50));
+    watcher.start().await.unwrap();
+    let server = spawn_server(governor).await;
+
+    std::fs::write(&path, config_json(1)).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert_eq!(check(server.addr, "carol").await.decision, "allowed");
+    assert_eq!(check(server.addr, "carol").await.decision, "rejected");
+
+    watcher.stop().await.unwrap();
+}
+
+/// 端到端测试：关闭信号触发后服务退出并释放端口
+#[tokio::test]
+async fn test_e2e_server_graceful_shutdown() {
+    let server = spawn_server(setup_governor(100).await).await;
+    assert_eq!(check(server.addr, "dave").await.decision, "allowed");
+
+    server.shutdown.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server.handle)
+        .await
+        .expect("server did not shut down")
+        .unwrap();
+
+    assert!(TcpStream::connect(server.addr).await.is_err());
+}
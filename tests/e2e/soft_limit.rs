@@ -0,0 +1,159 @@
+//! 端到端测试：软限流以延迟代替拒绝
+//!
+//! 测试场景：
+//! - 超限请求返回 `Decision::Delayed`，延迟随窗口内超限次数增长
+//! - 延迟不超过配置的上限
+//! - 超限次数移出统计窗口后延迟重新计算
+//! - 同时匹配的硬限流规则拒绝时优先于软限流
+//! - gRPC 拦截器不等待，把延迟写入请求扩展交给处理函数
+
+use super::{config_with_rules, fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::{ActionConfig, Rule},
+    error::Decision,
+    governor::Governor,
+    matchers::RequestContext,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn rule(id: &str, priority: u16, max_requests: u64, action: ActionConfig) -> Rule {
+    Rule {
+        priority,
+        action,
+        ..fixed_window_rule(id, max_requests)
+    }
+}
+
+fn delay_action(delay_ms: u64, max_delay_ms: u64, rejection_window: Option<&str>) -> ActionConfig {
+    ActionConfig {
+        on_exceed: "delay".to_string(),
+        delay_ms: Some(delay_ms),
+        max_delay_ms: Some(max_delay_ms),
+        rejection_window: rejection_window.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+async fn setup_governor(rules: Vec<Rule>) -> Governor {
+    config_with_rules(rules.clone()).validate().unwrap();
+    governor_with_rules(rules).await
+}
+
+async fn check(governor: &Governor, user_id: &str) -> Decision {
+    let context = RequestContext::new().with_header("X-User-Id", user_id);
+    governor.check(&context).await.unwrap()
+}
+
+async fn collect_delays(governor: &Governor, user_id: &str, times: usize) -> Vec<Option<Duration>> {
+    let mut delays = Vec::with_capacity(times);
+    for _ in 0..times {
+        delays.push(check(governor, user_id).await.delay());
+    }
+    delays
+}
+
+#[tokio::test]
+async fn test_delay_grows_with_overage_and_is_capped() {
+    let governor = setup_governor(vec![rule("soft", 10, 1, delay_action(100, 350, None))]).await;
+
+    assert_eq!(check(&governor, "alice").await, Decision::Allowed(None));
+
+    let delays: Vec<Option<Duration>> = collect_delays(&governor, "alice", 5).await;
+    assert_eq!(
+        delays,
+        [100, 200, 300, 350, 350]
+            .into_iter()
+            .map(|ms| Some(Duration::from_millis(ms)))
+            .collect::<Vec<_>>()
+    );
+
+    // 其他标识符的超限次数单独计算
+    check(&governor, "bob").await;
+    assert_eq!(
+        check(&governor, "bob").await,
+        Decision::Delayed(Duration::from_millis(100))
+    );
+
+    // 延迟放行的请求不计入拒绝数
+    let stats = governor.stats().await;
+    assert_eq!(stats.rejected_requests, 0);
+    assert_eq!(stats.allowed_requests, stats.total_requests);
+}
+
+#[tokio::test]
+async fn test_delay_resets_after_rejection_window() {
+    let governor = setup_governor(vec![rule(
+        "soft",
+        10,
+        1,
+        delay_action(100, 1000, Some("1s")),
+    )])
+    .await;
+
+    check(&governor, "alice").await;
+    assert_eq!(
+        check(&governor, "alice").await.delay(),
+        Some(Duration::from_millis(100))
+    );
+    assert_eq!(
+        check(&governor, "alice").await.delay(),
+        Some(Duration::from_millis(200))
+    );
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(
+        check(&governor, "alice").await.delay(),
+        Some(Duration::from_millis(100))
+    );
+}
+
+#[tokio::test]
+async fn test_hard_rule_rejection_wins_over_delay() {
+    let governor = setup_governor(vec![
+        rule("soft", 20, 1, delay_action(100, 1000, None)),
+        rule("hard", 10, 2, ActionConfig::default()),
+    ])
+    .await;
+
+    assert_eq!(check(&governor, "alice").await, Decision::Allowed(None));
+    // 软限流规则超限，硬限流规则仍允许
+    assert_eq!(
+        check(&governor, "alice").await,
+        Decision::Delayed(Duration::from_millis(100))
+    );
+    // 两条规则都超限时拒绝
+    assert!(matches!(
+        check(&governor, "alice").await,
+        Decision::Rejected(_)
+    ));
+}
+
+#[cfg(feature = "grpc")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_grpc_interceptor_hands_delay_to_handler() {
+    use limiteron::grpc::{FlowGuardInterceptor, SoftLimitDelay};
+    use std::time::Instant;
+    use tonic::service::Interceptor;
+
+    let governor = setup_governor(vec![rule("soft", 10, 1, delay_action(500, 1000, None))]).await;
+    let mut interceptor = FlowGuardInterceptor::new(Arc::new(governor));
+    let request = || {
+        let mut request = tonic::Request::new(());
+        request
+            .metadata_mut()
+            .insert("x-user-id", "alice".parse().unwrap());
+        request
+    };
+
+    let allowed = interceptor.call(request()).unwrap();
+    assert!(allowed.extensions().get::<SoftLimitDelay>().is_none());
+
+    let started = Instant::now();
+    let delayed = interceptor.call(request()).unwrap();
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert_eq!(
+        delayed.extensions().get::<SoftLimitDelay>(),
+        Some(&SoftLimitDelay(Duration::from_millis(500)))
+    );
+}
@@ -0,0 +1,91 @@
+//! 端到端测试：转储 Governor 运行状态
+//!
+//! 测试场景：
+//! - 转储包含配置、统计、限流器快照、封禁与配置历史
+//! - 限流器与封禁中的标识符已脱敏
+//! - 配置与配置历史中的匹配器取值已脱敏
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::Matcher,
+    governor::Governor,
+    limiters::LimiterSnapshot,
+    matchers::{Identifier, RequestContext},
+};
+
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![fixed_window_rule("global_rule", 10)]).await
+}
+
+#[tokio::test]
+async fn test_dump_state_sections_and_redaction() {
+    let governor = setup_governor().await;
+    let context = RequestContext::new().with_header("X-User-Id", "alice-secret");
+    for _ in 0..3 {
+        governor.check(&context).await.unwrap();
+    }
+    governor
+        .ban_identifier(
+            &Identifier::UserId("mallory-secret".to_string()),
+            "abuse",
+            None,
+        )
+        .await
+        .unwrap();
+    let mut strict_rule = fixed_window_rule("strict_rule", 1);
+    strict_rule.matchers = vec![
+        Matcher::User {
+            user_ids: vec!["vip-secret".to_string()],
+        },
+        Matcher::Ip {
+            ip_ranges: vec!["203.0.113.0/24".to_string()],
+        },
+    ];
+    governor.upsert_rule(strict_rule).await.unwrap();
+
+    let dump = governor.dump_state().await;
+    assert_eq!(dump.config.rules.len(), 2);
+    assert_eq!(dump.stats.total_requests, 3);
+    assert_eq!(dump.config_history.len(), 1);
+
+    assert_eq!(dump.limiters.len(), 1);
+    let limiter = &dump.limiters[0];
+    assert_eq!(limiter.rule_id, "global_rule");
+    assert_eq!(limiter.key.as_deref(), Some("user_id:***"));
+    assert!(matches!(
+        limiter.snapshot,
+        LimiterSnapshot::Window {
+            used: 3,
+            limit: 10,
+            ..
+        }
+    ));
+
+    assert_eq!(dump.bans.len(), 1);
+    assert_eq!(dump.bans[0].target, "user_id:***");
+    assert_eq!(dump.bans[0].reason, "abuse");
+
+    let json = serde_json::to_value(&dump).unwrap();
+    for section in [
+        "generated_at",
+        "config",
+        "stats",
+        "limiters",
+        "bans",
+        "config_history",
+    ] {
+        assert!(json.get(section).is_some(), "missing section {}", section);
+    }
+    assert_eq!(json["limiters"][0]["snapshot"]["type"], "window");
+
+    let text = json.to_string();
+    assert!(!text.contains("alice-secret"));
+    assert!(!text.contains("mallory-secret"));
+    assert!(!text.contains("vip-secret"));
+    assert!(!text.contains("203.0.113.0"));
+    assert_eq!(json["config"]["rules"][1]["matchers"][1]["type"], "Ip");
+    assert_eq!(
+        json["config_history"][0]["diff"][0]["value"]["matchers"][0]["user_ids"][0],
+        "***"
+    );
+}
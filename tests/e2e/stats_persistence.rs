@@ -0,0 +1,116 @@
+//! 端到端测试：统计计数器跨重启持久化
+//!
+//! 测试场景：
+//! - 导出的快照可序列化为 JSON 并还原
+//! - 导入是累加的，新实例启动后已处理的请求不会被覆盖
+//! - 导入后计数器在快照基础上继续增长
+
+use super::{fixed_window_rule, governor_with_rules};
+use limiteron::{
+    config::{Matcher as ConfigMatcher, Rule},
+    governor::{Governor, StatsSnapshot},
+    matchers::RequestContext,
+};
+
+async fn setup_governor() -> Governor {
+    governor_with_rules(vec![Rule {
+        matchers: vec![ConfigMatcher::User {
+            user_ids: vec!["alice".to_string()],
+        }],
+        ..fixed_window_rule("user_rule", 2)
+    }])
+    .await
+}
+
+async fn send(governor: &Governor, user_id: &str, times: usize) {
+    let context = RequestContext::new().with_header("X-User-Id", user_id);
+    for _ in 0..times {
+        governor.check(&context).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_stats_snapshot_round_trip() {
+    let governor = setup_governor().await;
+    send(&governor, "alice", 3).await;
+
+    let snapshot = governor.export_stats().await;
+    assert_eq!(snapshot.governor.total_requests, 3);
+    assert_eq!(snapshot.governor.allowed_requests, 2);
+    assert_eq!(snapshot.governor.rejected_requests, 1);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored: StatsSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, snapshot);
+}
+
+#[tokio::test]
+async fn test_import_stats_continues_counters() {
+    let before_restart = setup_governor().await;
+    send(&before_restart, "alice", 3).await;
+    send(&before_restart, "bob", 1).await;
+    let saved = before_restart.export_stats().await;
+    // alice 的 3 次请求经过规则决策链，bob 的 1 次经过默认决策链
+    assert_eq!(saved.chain.total_checks, 4);
+    assert_eq!(saved.chain.rejected_count, 1);
+
+    // 重启后先处理请求再导入，导入不能覆盖新实例的计数
+    let after_restart = setup_governor().await;
+    send(&after_restart, "alice", 1).await;
+    let fresh = after_restart.export_stats().await;
+    after_restart.import_stats(saved.clone()).await;
+
+    let stats = after_restart.stats().await;
+    assert_eq!(stats.total_requests, 5);
+    assert_eq!(stats.allowed_requests, 4);
+    assert_eq!(stats.rejected_requests, 1);
+
+    let matcher = after_restart.rule_matcher_stats().await;
+    assert_eq!(
+        matcher.total_matches,
+        saved.matcher.total_matches + fresh.matcher.total_matches
+    );
+    assert_eq!(
+        matcher.total_mismatches,
+        saved.matcher.total_mismatches + fresh.matcher.total_mismatches
+    );
+
+    let chain = after_restart.decision_chain_stats().await;
+    assert_eq!(
+        chain.total_checks,
+        saved.chain.total_checks + fresh.chain.total_checks
+    );
+    assert_eq!(
+        chain.rejected_count,
+        saved.chain.rejected_count + fresh.chain.rejected_count
+    );
+
+    // 导入后计数器继续增长
+    send(&after_restart, "bob", 1).await;
+    assert_eq!(after_restart.stats().await.total_requests, 6);
+}
+
+#[tokio::test]
+async fn test_update_config_keeps_counters() {
+    let governor = setup_governor().await;
+    send(&governor, "alice", 3).await;
+    send(&governor, "bob", 1).await;
+    let mut saved = governor.export_stats().await;
+    saved.matcher.total_matches = 7;
+    saved.matcher.total_mismatches = 2;
+
+    let reloaded = setup_governor().await;
+    reloaded.import_stats(saved.clone()).await;
+    let config = reloaded.get_config().await;
+    reloaded.update_config(config).await.unwrap();
+
+    // 重建匹配器和决策链后，导入的和已累计的计数都保留
+    let after = reloaded.export_stats().await;
+    assert_eq!(after.matcher, saved.matcher);
+    assert_eq!(after.chain.total_checks, saved.chain.total_checks);
+    assert_eq!(after.chain.rejected_count, saved.chain.rejected_count);
+
+    send(&reloaded, "alice", 1).await;
+    let after = reloaded.export_stats().await;
+    assert_eq!(after.chain.total_checks, saved.chain.total_checks + 1);
+}
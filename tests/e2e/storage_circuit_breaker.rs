@@ -0,0 +1,496 @@
+//! 端到端测试：封禁存储熔断
+//!
+//! 测试场景：
+//! - 封禁存储持续故障时熔断器打开，之后的检查不再访问存储
+//! - 熔断期间封禁检查使用封禁缓存中的结果（即使已过 TTL）
+//! - 熔断短路按组件记录到指标
+//! - 熔断期间按组件降级策略决策：放行、拒绝、使用缓存、使用最近一次结果
+
+use super::{config_with_rules, fixed_window_rule, governor_with_storage};
+use async_trait::async_trait;
+use limiteron::{
+    config::{FlowControlConfig, LimiterConfig},
+    error::{Decision, FlowGuardError, StorageError},
+    governor::{FailurePolicy, Governor},
+    matchers::RequestContext,
+    storage::{BanHistory, BanRecord, BanStorage, BanTarget, MemoryStorage},
+};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 可切换为故障状态的封禁存储，记录查询次数
+#[derive(Default)]
+struct FlakyBanStorage {
+    inner: MemoryStorage,
+    down: AtomicBool,
+    lookups: AtomicUsize,
+}
+
+impl FlakyBanStorage {
+    fn check(&self) -> Result<(), StorageError> {
+        if self.down.load(Ordering::SeqCst) {
+            return Err(StorageError::ConnectionError(
+                "ban storage down".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn set_down(&self, down: bool) {
+        self.down.store(down, Ordering::SeqCst);
+    }
+
+    fn lookups(&self) -> usize {
+        self.lookups.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl BanStorage for FlakyBanStorage {
+    async fn is_banned(&self, target: &BanTarget) -> Result<Option<BanRecord>, StorageError> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        self.check()?;
+        self.inner.is_banned(target).await
+    }
+
+    async fn save(&self, record: &BanRecord) -> Result<(), StorageError> {
+        self.check()?;
+        self.inner.save(record).await
+    }
+
+    async fn get_history(&self, target: &BanTarget) -> Result<Option<BanHistory>, StorageError> {
+        self.check()?;
+        self.inner.get_history(target).await
+    }
+
+    async fn increment_ban_times(&self, target: &BanTarget) -> Result<u64, StorageError> {
+        self.check()?;
+        self.inner.increment_ban_times(target).await
+    }
+
+    async fn get_ban_times(&self, target: &BanTarget) -> Result<u64, StorageError> {
+        self.check()?;
+        self.inner.get_ban_times(target).await
+    }
+
+    async fn remove_ban(&self, target: &BanTarget) -> Result<(), StorageError> {
+        self.check()?;
+        self.inner.remove_ban(target).await
+    }
+
+    async fn cleanup_expired_bans(&self) -> Result<u64, StorageError> {
+        self.check()?;
+        self.inner.cleanup_expired_bans().await
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+fn config() -> FlowControlConfig {
+    config_with_rules(vec![fixed_window_rule("global_rule", 1000)])
+}
+
+async fn setup_governor(
+    #[cfg(feature = "monitoring")] metrics: Option<Arc<limiteron::telemetry::Metrics>>,
+) -> (Governor, Arc<FlakyBanStorage>) {
+    let ban_storage = Arc::new(FlakyBanStorage::default());
+    let governor = Governor::new(
+        config(),
+        Arc::new(MemoryStorage::new()),
+        ban_storage.clone(),
+        #[cfg(feature = "monitoring")]
+        metrics,
+        #[cfg(feature = "telemetry")]
+        None,
+    )
+    .await
+    .unwrap();
+    (governor, ban_storage)
+}
+
+fn user_request(user_id: &str) -> RequestContext {
+    RequestContext::new().with_header("X-User-Id", user_id)
+}
+
+/// 绕过 Governor 直接写入封禁记录
+async fn ban_in_storage(storage: &FlakyBanStorage, user_id: &str) {
+    let now = chrono::Utc::now();
+    storage
+        .save(&BanRecord {
+            target: BanTarget::UserId(user_id.to_string()),
+            ban_times: 1,
+            duration: Duration::from_secs(600),
+            banned_at: now,
+            expires_at: now + chrono::Duration::seconds(600),
+            is_manual: true,
+            reason: "abuse".to_string(),
+        })
+        .await
+        .unwrap();
+}
+
+/// 端到端测试：封禁存储持续故障时熔断器打开，不再访问存储
+#[tokio::test]
+async fn test_e2e_ban_storage_failures_open_circuit() {
+    #[cfg(feature = "monitoring")]
+    let (gov, storage) = setup_governor(None).await;
+    #[cfg(not(feature = "monitoring"))]
+    let (gov, storage) = setup_governor().await;
+    gov.set_failure_policy(FailurePolicy::Open);
+    storage.set_down(true);
+
+    for i in 0..5 {
+        let decision = gov.check(&user_request(&format!("u{}", i))).await.unwrap();
+        assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    }
+    assert!(gov.circuit_breaker().is_open().await);
+    assert!(gov.limiter_circuit_breaker().is_closed().await);
+    let lookups = storage.lookups();
+
+    // 熔断期间按失败开放策略放行，限流仍然生效，且不再访问存储
+    for i in 5..10 {
+        let decision = gov.check(&user_request(&format!("u{}", i))).await.unwrap();
+        assert!(matches!(decision, Decision::Allowed(_)), "{:?}", decision);
+    }
+    assert_eq!(storage.lookups(), lookups);
+    assert_eq!(gov.stats().await.error_count, 10);
+
+    // Propagate 策略下熔断错误返回给调用方
+    gov.set_failure_policy(FailurePolicy::Propagate);
+    let result = gov.check(&user_request("u10")).await;
+    assert!(
+        matches!(result, Err(FlowGuardError::CircuitBreakerError(_))),
+        "{:?}",
+        result
+    );
+}
+
+/// 端到端测试：熔断期间封禁检查使用已过期的缓存结果
+#[tokio::test]
+async fn test_e2e_open_circuit_serves_ban_cache() {
+    #[cfg(feature = "monitoring")]
+    let (gov, storage) = setup_governor(None).await;
+    #[cfg(not(feature = "monitoring"))]
+    let (gov, storage) = setup_governor().await;
+    gov.set_ban_cache_ttl(Duration::from_millis(20));
+    // 未命中缓存的封禁检查故障返回给调用方，便于区分缓存结果
+    gov.set_failure_policy(FailurePolicy::Propagate);
+
+    ban_in_storage(&storage, "mallory").await;
+    assert!(matches!(
+        gov.check(&user_request("mallory")).await.unwrap(),
+        Decision::Banned(_)
+    ));
+    assert!(matches!(
+        gov.check(&user_request("alice")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+
+    // 缓存过期后存储故障，连续失败使熔断器打开
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    storage.set_down(true);
+    for i in 0..5 {
+        assert!(gov.check(&user_request(&format!("u{}", i))).await.is_err());
+    }
+    assert!(gov.circuit_breaker().is_open().await);
+    let lookups = storage.lookups();
+
+    let decision = gov.check(&user_request("mallory")).await.unwrap();
+    match decision {
+        Decision::Banned(info) => assert_eq!(info.reason, "abuse"),
+        other => panic!("expected Banned, got {:?}", other),
+    }
+    assert!(matches!(
+        gov.check(&user_request("alice")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    assert_eq!(storage.lookups(), lookups);
+
+    // 缓存中没有的标识符按故障处理策略处理
+    assert!(gov.check(&user_request("bob")).await.is_err());
+    gov.set_failure_policy(FailurePolicy::Closed);
+    let decision = gov.check(&user_request("bob")).await.unwrap();
+    assert!(matches!(decision, Decision::Rejected(_)), "{:?}", decision);
+
+    #[cfg(feature = "fallback")]
+    assert!(
+        gov.fallback_manager()
+            .is_failed(limiteron::fallback::ComponentType::Ban)
+            .await
+    );
+}
+
+/// 端到端测试：熔断短路按组件记录到指标
+#[cfg(feature = "monitoring")]
+#[tokio::test]
+async fn test_e2e_circuit_open_metric() {
+    use limiteron::telemetry::Metrics;
+
+    let metrics = Arc::new(Metrics::new());
+    let (gov, storage) = setup_governor(Some(metrics.clone())).await;
+    gov.set_failure_policy(FailurePolicy::Open);
+    storage.set_down(true);
+
+    for i in 0..8 {
+        gov.check(&user_request(&format!("u{}", i))).await.unwrap();
+    }
+
+    let output = metrics.gather();
+    assert!(
+        output.contains(r#"limiteron_circuit_open_total{component="ban"} 3"#),
+        "{}",
+        output
+    );
+}
+
+/// 存储故障并使封禁存储熔断器打开
+#[cfg(feature = "fallback")]
+async fn trip_ban_circuit(gov: &Governor, storage: &FlakyBanStorage) {
+    storage.set_down(true);
+    for i in 0..5 {
+        let _ = gov.check(&user_request(&format!("trip{}", i))).await;
+    }
+    assert!(gov.circuit_breaker().is_open().await);
+}
+
+/// 端到端测试：封禁存储熔断期间按运行时设置的降级策略决策
+#[cfg(feature = "fallback")]
+#[tokio::test]
+async fn test_e2e_ban_fallback_strategies() {
+    use limiteron::error::RejectReason;
+    use limiteron::fallback::{ComponentType, FallbackConfig, FallbackStrategy};
+
+    #[cfg(feature = "monitoring")]
+    let (gov, storage) = setup_governor(None).await;
+    #[cfg(not(feature = "monitoring"))]
+    let (gov, storage) = setup_governor().await;
+    gov.set_ban_cache_ttl(Duration::from_millis(20));
+    // 未命中缓存的封禁检查故障返回给调用方，便于区分缓存结果
+    gov.set_failure_policy(FailurePolicy::Propagate);
+
+    ban_in_storage(&storage, "mallory").await;
+    assert!(matches!(
+        gov.check(&user_request("mallory")).await.unwrap(),
+        Decision::Banned(_)
+    ));
+    assert!(matches!(
+        gov.check(&user_request("alice")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    trip_ban_circuit(&gov, &storage).await;
+
+    let set = |strategy: FallbackStrategy| {
+        gov.fallback_manager()
+            .set_strategy(ComponentType::Ban, strategy)
+    };
+
+    // 全部放行：跳过封禁检查，被封禁的用户也能通过
+    set(FallbackStrategy::FailOpen).await;
+    for user in ["mallory", "alice", "bob"] {
+        let decision = gov.check(&user_request(user)).await.unwrap();
+        assert!(
+            matches!(decision, Decision::Allowed(_)),
+            "{}: {:?}",
+            user,
+            decision
+        );
+    }
+
+    // 全部拒绝
+    set(FallbackStrategy::FailClosed).await;
+    for user in ["mallory", "alice", "bob"] {
+        let decision = gov.check(&user_request(user)).await.unwrap();
+        assert_eq!(
+            decision.reason(),
+            Some(&RejectReason::StorageUnavailable),
+            "{}: {:?}",
+            user,
+            decision
+        );
+    }
+
+    // 使用缓存：过期的封禁仍然生效，未缓存的标识符按故障处理策略处理
+    set(FallbackStrategy::Degraded).await;
+    assert!(matches!(
+        gov.check(&user_request("mallory")).await.unwrap(),
+        Decision::Banned(_)
+    ));
+    assert!(matches!(
+        gov.check(&user_request("alice")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    assert!(gov.check(&user_request("bob")).await.is_err());
+
+    // 清空封禁缓存后，使用缓存策略取不到结果，最近一次结果仍然可用
+    gov.set_ban_cache_ttl(Duration::from_millis(20));
+    assert!(gov.check(&user_request("mallory")).await.is_err());
+    set(FallbackStrategy::UseLastKnown).await;
+    assert!(matches!(
+        gov.check(&user_request("mallory")).await.unwrap(),
+        Decision::Banned(_)
+    ));
+    assert!(matches!(
+        gov.check(&user_request("alice")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+
+    // 禁用组件策略后回到 Governor 的故障处理策略
+    gov.fallback_manager()
+        .set_strategy(
+            ComponentType::Ban,
+            FallbackConfig::new(ComponentType::Ban, FallbackStrategy::UseLastKnown).enabled(false),
+        )
+        .await;
+    assert!(gov.check(&user_request("mallory")).await.is_err());
+    gov.set_failure_policy(FailurePolicy::Open);
+    assert!(matches!(
+        gov.check(&user_request("mallory")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+}
+
+/// 可切换为故障状态的自定义限流器，正常时全部放行
+#[cfg(all(feature = "fallback", feature = "custom-limiter"))]
+#[derive(Default)]
+struct FlakyLimiter {
+    down: Arc<AtomicBool>,
+}
+
+#[cfg(all(feature = "fallback", feature = "custom-limiter"))]
+#[async_trait]
+impl limiteron::custom_limiter::CustomLimiter for FlakyLimiter {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    async fn allow(&self, _cost: u64) -> Result<bool, FlowGuardError> {
+        if self.down.load(Ordering::SeqCst) {
+            return Err(FlowGuardError::StorageError(StorageError::TimeoutError(
+                "redis timeout".to_string(),
+            )));
+        }
+        Ok(true)
+    }
+
+    fn load_config(&mut self, _config: serde_json::Value) -> Result<(), FlowGuardError> {
+        Ok(())
+    }
+
+    fn stats(&self) -> limiteron::custom_limiter::LimiterStats {
+        limiteron::custom_limiter::LimiterStats::new()
+    }
+}
+
+/// 端到端测试：限流器熔断期间按 `UseLastKnown` 复用最近一次决策
+#[cfg(all(feature = "fallback", feature = "custom-limiter"))]
+#[tokio::test]
+async fn test_e2e_limiter_use_last_known() {
+    use limiteron::custom_limiter::{CustomLimiter, CustomLimiterRegistry};
+    use limiteron::fallback::{ComponentType, FallbackStrategy};
+
+    let mut config = config();
+    config.rules[0].limiters = vec![LimiterConfig::Custom {
+        name: "flaky".to_string(),
+        config: serde_json::json!({}),
+    }];
+    let gov = governor_with_storage(
+        config,
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
+    )
+    .await;
+
+    let down = Arc::new(AtomicBool::new(false));
+    let registry = Arc::new(CustomLimiterRegistry::new());
+    let shared = down.clone();
+    registry
+        .register_factory(
+            "flaky".to_string(),
+            Arc::new(move |_: &str| {
+                Arc::new(FlakyLimiter {
+                    down: shared.clone(),
+                }) as Arc<dyn CustomLimiter>
+            }),
+        )
+        .await
+        .unwrap();
+    gov.set_custom_limiter_registry(registry).await.unwrap();
+    gov.fallback_manager()
+        .set_strategy(ComponentType::Limiter, FallbackStrategy::UseLastKnown)
+        .await;
+
+    assert!(matches!(
+        gov.check(&user_request("grace")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+
+    down.store(true, Ordering::SeqCst);
+    for i in 0..5 {
+        assert!(gov.check(&user_request(&format!("u{}", i))).await.is_err());
+    }
+    assert!(gov.limiter_circuit_breaker().is_open().await);
+    assert!(gov.circuit_breaker().is_closed().await);
+
+    assert!(matches!(
+        gov.check(&user_request("grace")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    // 没有历史决策的标识符按故障处理策略处理
+    assert!(gov.check(&user_request("heidi")).await.is_err());
+}
+
+/// 端到端测试：未启用 `UseLastKnown` 时不记录决策，熔断后再启用也无可复用的决策
+#[cfg(all(feature = "fallback", feature = "custom-limiter"))]
+#[tokio::test]
+async fn test_e2e_limiter_last_known_recorded_only_when_enabled() {
+    use limiteron::custom_limiter::{CustomLimiter, CustomLimiterRegistry};
+    use limiteron::fallback::{ComponentType, FallbackStrategy};
+
+    let mut config = config();
+    config.rules[0].limiters = vec![LimiterConfig::Custom {
+        name: "flaky".to_string(),
+        config: serde_json::json!({}),
+    }];
+    let gov = governor_with_storage(
+        config,
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
+    )
+    .await;
+
+    let down = Arc::new(AtomicBool::new(false));
+    let registry = Arc::new(CustomLimiterRegistry::new());
+    let shared = down.clone();
+    registry
+        .register_factory(
+            "flaky".to_string(),
+            Arc::new(move |_: &str| {
+                Arc::new(FlakyLimiter {
+                    down: shared.clone(),
+                }) as Arc<dyn CustomLimiter>
+            }),
+        )
+        .await
+        .unwrap();
+    gov.set_custom_limiter_registry(registry).await.unwrap();
+
+    assert!(matches!(
+        gov.check(&user_request("grace")).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+
+    down.store(true, Ordering::SeqCst);
+    for i in 0..5 {
+        assert!(gov.check(&user_request(&format!("u{}", i))).await.is_err());
+    }
+    assert!(gov.limiter_circuit_breaker().is_open().await);
+
+    gov.fallback_manager()
+        .set_strategy(ComponentType::Limiter, FallbackStrategy::UseLastKnown)
+        .await;
+    assert!(gov.check(&user_request("grace")).await.is_err());
+}
@@ -0,0 +1,99 @@
+//! 端到端测试：窗口时长解析一致性
+//!
+//! 测试场景：
+//! - 限流器工厂与 Governor 对同一窗口大小的接受与拒绝结果一致
+//! - 毫秒级窗口在 Governor 中按预期限流
+
+use super::{config_with_rules, rule_with_limiter};
+use limiteron::{
+    config::LimiterConfig, error::Decision, factory::LimiterFactory, governor::Governor,
+    matchers::RequestContext, storage::MemoryStorage,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn window_limiter(window_size: &str, max_requests: u64) -> LimiterConfig {
+    LimiterConfig::FixedWindow {
+        window_size: window_size.to_string(),
+        max_requests,
+    }
+}
+
+async fn governor_with(limiter: LimiterConfig) -> Result<Governor, limiteron::FlowGuardError> {
+    let config = config_with_rules(vec![rule_with_limiter("window_rule", limiter)]);
+
+    Governor::new(
+        config,
+        Arc::new(MemoryStorage::new()),
+        Arc::new(MemoryStorage::new()),
+        #[cfg(feature = "monitoring")]
+        None,
+        #[cfg(feature = "telemetry")]
+        None,
+    )
+    .await
+}
+
+/// 端到端测试：工厂与 Governor 接受相同的窗口大小
+#[tokio::test]
+async fn test_e2e_factory_and_governor_agree_on_window_sizes() {
+    let cases = [
+        ("250ms", true),
+        ("10s", true),
+        ("5m", true),
+        ("2h", true),
+        ("1d", true),
+        ("30 seconds", true),
+        ("0s", false),
+        ("0ms", false),
+        ("", false),
+        ("ms", false),
+        ("10", false),
+        ("10w", false),
+        ("-5s", false),
+        // 换算溢出与超过 30 天上限时返回错误，不会 panic 或回绕
+        ("30d", true),
+        ("31d", false),
+        ("999999999999999999d", false),
+        ("18446744073709551615h", false),
+        ("99999999999999999999s", false),
+    ];
+
+    for (window_size, expected) in cases {
+        let limiter = window_limiter(window_size, 10);
+        let factory_ok = LimiterFactory::create(&limiter).is_ok();
+        let governor_ok = governor_with(limiter).await.is_ok();
+        assert_eq!(factory_ok, expected, "factory: {:?}", window_size);
+        assert_eq!(governor_ok, expected, "governor: {:?}", window_size);
+    }
+
+    // GCRA 周期使用同一解析规则
+    let gcra = LimiterConfig::Gcra {
+        period: "500ms".to_string(),
+        burst: 5,
+    };
+    assert!(LimiterFactory::create(&gcra).is_ok());
+    assert!(governor_with(gcra).await.is_ok());
+}
+
+/// 端到端测试：毫秒级窗口
+#[tokio::test]
+async fn test_e2e_millisecond_window() {
+    let governor = governor_with(window_limiter("100ms", 1)).await.unwrap();
+    let context = RequestContext::new().with_header("X-User-Id", "ms_user");
+
+    assert!(matches!(
+        governor.check(&context).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+    assert!(matches!(
+        governor.check(&context).await.unwrap(),
+        Decision::Rejected(_)
+    ));
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(matches!(
+        governor.check(&context).await.unwrap(),
+        Decision::Allowed(_)
+    ));
+}
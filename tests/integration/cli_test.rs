@@ -0,0 +1,82 @@
+//! 集成测试：配置检查命令行工具
+//!
+//! 测试场景：
+//! - 有效配置通过 `validate` 与 `lint`
+//! - 无效配置逐条报告带路径的错误并以非零码退出
+//! - 可疑配置在 `lint` 中报告警告，`--deny-warnings` 时以非零码退出
+
+use limiteron::cli::{self, EXIT_INVALID, EXIT_OK};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/cli")
+        .join(name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// 执行命令行，返回退出码、标准输出与标准错误
+fn run(args: &[&str]) -> (i32, String, String) {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let code = cli::run(&args, &mut out, &mut err);
+    (
+        code,
+        String::from_utf8(out).unwrap(),
+        String::from_utf8(err).unwrap(),
+    )
+}
+
+#[test]
+fn test_cli_valid_config() {
+    let file = fixture("valid.toml");
+    for command in ["validate", "lint"] {
+        let (code, out, err) = run(&[command, &file]);
+        assert_eq!(code, EXIT_OK, "stderr: {}", err);
+        assert!(out.contains("检查通过"));
+        assert!(err.is_empty());
+    }
+}
+
+#[test]
+fn test_cli_invalid_config() {
+    let (code, out, err) = run(&["validate", &fixture("invalid.json")]);
+    assert_eq!(code, EXIT_INVALID);
+    assert!(out.is_empty());
+
+    let errors: Vec<&str> = err
+        .lines()
+        .filter(|line| line.starts_with("error: "))
+        .collect();
+    assert_eq!(errors.len(), 3, "stderr: {}", err);
+    assert!(errors[0].starts_with("error: version: "));
+    assert!(err.contains("error: rules[0].limiters[0].max_requests: "));
+    assert!(err.contains("error: rules[0].limiters[0].window_size: "));
+    assert!(err.contains("发现 3 个错误"));
+}
+
+#[test]
+fn test_cli_lint_suspicious_config() {
+    let file = fixture("suspicious.yaml");
+
+    // 配置本身有效
+    let (code, _, _) = run(&["validate", &file]);
+    assert_eq!(code, EXIT_OK);
+
+    let (code, out, err) = run(&["lint", &file]);
+    assert_eq!(code, EXIT_OK);
+    assert!(out.contains("检查通过"));
+    let warnings: Vec<&str> = err
+        .lines()
+        .filter(|line| line.starts_with("warning: "))
+        .collect();
+    assert_eq!(warnings.len(), 3, "stderr: {}", err);
+    assert!(warnings[0].starts_with("warning: rules[0].matchers: "));
+    assert!(warnings[1].starts_with("warning: rules[1].limiters[0].max_requests: "));
+    assert!(warnings[2].starts_with("warning: rules[2].matchers: "));
+
+    let (code, out, _) = run(&["lint", &file, "--deny-warnings"]);
+    assert_eq!(code, EXIT_INVALID);
+    assert!(out.is_empty());
+}
@@ -2,6 +2,9 @@
 //!
 //! 测试各组件之间的集成和交互
 
+#[cfg(feature = "cli")]
+#[allow(unused_imports)]
+mod cli_test;
 #[cfg(feature = "postgres")]
 #[allow(unused_imports)]
 mod postgres_test;
@@ -154,6 +154,8 @@ async fn test_redis_lua_atomicity() {
         allow_overdraft: false,
         overdraft_limit_percent: 0,
         alert_config: Default::default(),
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = QuotaController::new(storage, quota_config);
@@ -337,3 +339,206 @@ async fn test_redis_high_concurrency() {
     println!("Success: {}, Fail: {}", success_count, fail_count);
     assert!(success_count + fail_count == 1000);
 }
+
+/// 测试Redis分布式并发控制
+#[tokio::test]
+#[ignore]
+async fn test_redis_concurrency_limiter() {
+    use limiteron::error::FlowGuardError;
+    use limiteron::redis_storage::RedisConcurrencyLimiter;
+
+    let config = RedisConfig::new("redis://localhost:6379").password("limiteron123");
+    let storage = RedisStorage::new(config).await.unwrap();
+
+    // 两个实例共享同一个键，模拟多节点部署
+    let node_a = RedisConcurrencyLimiter::new(storage.clone(), "test_concurrency", 3);
+    let node_b = RedisConcurrencyLimiter::new(storage.clone(), "test_concurrency", 3);
+
+    let permit_a = node_a.acquire(2).await.unwrap();
+    let permit_b = node_b.acquire(1).await.unwrap();
+    assert_eq!(permit_a.permits(), 2);
+
+    // 全局并发已满
+    let result = node_b.acquire(1).await;
+    assert!(matches!(
+        result,
+        Err(FlowGuardError::ConcurrencyLimitExceeded(_))
+    ));
+
+    // 显式释放后可以再次获取
+    permit_b.release().await.unwrap();
+    let permit_c = node_a.acquire(1).await.unwrap();
+
+    // drop 时异步释放
+    drop(permit_a);
+    drop(permit_c);
+    sleep(Duration::from_millis(100)).await;
+    let permit = node_b.acquire(3).await.unwrap();
+    permit.release().await.unwrap();
+}
+
+/// 测试组合检查：后面的限流器拒绝时不消费前面限流器的额度
+#[tokio::test]
+#[ignore]
+async fn test_redis_check_all_no_partial_consumption() {
+    use limiteron::redis_storage::LimiterSpec;
+
+    let config = RedisConfig::new("redis://localhost:6379").password("limiteron123");
+    let storage = RedisStorage::new(config).await.unwrap();
+
+    let rate_key = "test_check_all_rate";
+    let quota_key = "test_check_all_quota";
+    storage.reset_token_bucket(rate_key).await.unwrap();
+    storage
+        .reset_fixed_window(quota_key, Duration::from_secs(3600))
+        .await
+        .unwrap();
+
+    let specs = [
+        LimiterSpec::TokenBucket {
+            key: rate_key.to_string(),
+            capacity: 5,
+            refill_rate: 1,
+            cost: 1,
+        },
+        LimiterSpec::FixedWindow {
+            key: quota_key.to_string(),
+            window_size: Duration::from_secs(3600),
+            max_requests: 1,
+            cost: 1,
+        },
+    ];
+
+    let decision = storage.check_all(&specs).await.unwrap();
+    assert!(decision.allowed);
+    assert_eq!(decision.remaining, vec![4, 0]);
+
+    // 配额已用完：请求被拒绝，令牌桶的令牌没有被扣除
+    for _ in 0..3 {
+        let decision = storage.check_all(&specs).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.rejected_index, Some(1));
+        assert_eq!(decision.remaining[0], 4);
+    }
+
+    let (allowed, remaining, _) = storage.token_bucket(rate_key, 5, 1, 1).await.unwrap();
+    assert!(allowed);
+    assert_eq!(remaining, 3);
+}
+
+/// 测试并发许可租约过期与续期
+#[tokio::test]
+#[ignore]
+async fn test_redis_concurrency_lease() {
+    use limiteron::redis_storage::RedisConcurrencyLimiter;
+
+    let config = RedisConfig::new("redis://localhost:6379").password("limiteron123");
+    let storage = RedisStorage::new(config).await.unwrap();
+
+    let limiter = RedisConcurrencyLimiter::new(storage.clone(), "test_concurrency_lease", 1)
+        .with_lease(Duration::from_millis(300));
+
+    // 长时间持有的许可会被续期，不会在租约到期后被他人获取
+    let permit = limiter.acquire(1).await.unwrap();
+    sleep(Duration::from_millis(700)).await;
+    assert!(limiter.acquire(1).await.is_err());
+
+    // 模拟持有者崩溃：直接登记一个不会续期的持有者，租约到期后自动释放
+    permit.release().await.unwrap();
+    let (acquired, _) = storage
+        .concurrency_acquire(
+            "test_concurrency_lease",
+            1,
+            1,
+            Duration::from_millis(200),
+            "crashed-holder",
+        )
+        .await
+        .unwrap();
+    assert!(acquired);
+    assert!(limiter.acquire(1).await.is_err());
+
+    sleep(Duration::from_millis(300)).await;
+    let permit = limiter.acquire(1).await.unwrap();
+    permit.release().await.unwrap();
+}
+
+/// 测试Redis集群模式（需设置 LIMITERON_REDIS_CLUSTER_URL，例如
+/// `redis://127.0.0.1:7000,redis://127.0.0.1:7001,redis://127.0.0.1:7002`）
+#[tokio::test]
+async fn test_redis_cluster_mode() {
+    let Ok(url) = std::env::var("LIMITERON_REDIS_CLUSTER_URL") else {
+        eprintln!("跳过集群测试: 未设置 LIMITERON_REDIS_CLUSTER_URL");
+        return;
+    };
+
+    let config = RedisConfig::new(url).cluster_mode(true);
+    let storage = RedisStorage::new(config).await.unwrap();
+    storage.ping().await.unwrap();
+
+    // 配额：同一用户的字段位于同一个 Hash
+    let user_id = "cluster_user";
+    let resource = "cluster_resource";
+    let _ = storage
+        .reset(user_id, resource, DEFAULT_LIMIT, DEFAULT_WINDOW)
+        .await;
+    let result = storage
+        .consume(user_id, resource, 10, DEFAULT_LIMIT, DEFAULT_WINDOW)
+        .await
+        .unwrap();
+    assert!(result.allowed);
+    assert_eq!(result.remaining, DEFAULT_LIMIT - 10);
+
+    // 固定窗口脚本派生的窗口键需与原始键位于同一个 slot
+    let key = format!("cluster_fixed_{}", chrono::Utc::now().timestamp_millis());
+    for i in 1..=3 {
+        let (allowed, count, _) = storage.fixed_window(&key, DEFAULT_WINDOW, 3).await.unwrap();
+        assert!(allowed);
+        assert_eq!(count, i);
+    }
+    let (allowed, _, _) = storage.fixed_window(&key, DEFAULT_WINDOW, 3).await.unwrap();
+    assert!(!allowed);
+
+    let key = format!("cluster_sliding_{}", chrono::Utc::now().timestamp_millis());
+    let (allowed, count, _) = storage
+        .sliding_window(&key, DEFAULT_WINDOW, 1)
+        .await
+        .unwrap();
+    assert!(allowed);
+    assert_eq!(count, 1);
+
+    storage
+        .reset(user_id, resource, DEFAULT_LIMIT, DEFAULT_WINDOW)
+        .await
+        .unwrap();
+}
+
+/// 测试脚本缓存被清空后自动重新加载
+#[tokio::test]
+#[ignore]
+async fn test_redis_script_flush_recovery() {
+    let config = RedisConfig::new("redis://localhost:6379").password("limiteron123");
+    let storage = RedisStorage::new(config).await.unwrap();
+
+    let client = redis::Client::open("redis://:limiteron123@localhost:6379").unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+
+    let key = "test_script_flush_recovery";
+    let window = Duration::from_secs(60);
+    storage.sliding_window(key, window, 100).await.unwrap();
+
+    // 运行中清空脚本缓存，下一次调用应透明地重新加载
+    let _: () = redis::cmd("SCRIPT")
+        .arg("FLUSH")
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    let (allowed, count, _) = storage.sliding_window(key, window, 100).await.unwrap();
+    assert!(allowed);
+    assert!(count >= 1);
+
+    // 本地SHA缓存被清除时按需加载
+    storage.lua_manager().unwrap().clear_sha_cache();
+    let (allowed, _, _) = storage.sliding_window(key, window, 100).await.unwrap();
+    assert!(allowed);
+}
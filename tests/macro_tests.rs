@@ -0,0 +1,306 @@
+//! flow_control 宏运行时行为测试
+//!
+//! 运行：
+//! ```bash
+//! cargo test --test macro_tests --features macros
+//! ```
+
+#![cfg(feature = "macros")]
+
+use limiteron::{flow_control, FlowGuardError};
+
+#[flow_control(rate = "2/s", runtime = "current_thread")]
+fn sync_rate_limited() -> Result<u32, FlowGuardError> {
+    Ok(42)
+}
+
+#[flow_control(rate = "100/s")]
+async fn async_rate_limited() -> Result<u32, FlowGuardError> {
+    Ok(7)
+}
+
+#[flow_control(rate = "1/s", identifiers = [user_id, tenant])]
+async fn per_user_handler(user_id: &str, tenant: u32) -> Result<String, FlowGuardError> {
+    Ok(format!("{}@{}", user_id, tenant))
+}
+
+#[derive(Debug, PartialEq)]
+enum AppError {
+    Throttled(String),
+}
+
+fn to_app_error(err: FlowGuardError) -> AppError {
+    AppError::Throttled(err.to_string())
+}
+
+#[flow_control(concurrency = 1, acquire_timeout = "500ms")]
+async fn bounded_wait_handler(hold: std::time::Duration) -> Result<(), FlowGuardError> {
+    tokio::time::sleep(hold).await;
+    Ok(())
+}
+
+#[flow_control(
+    concurrency = 1,
+    acquire_timeout = "10ms",
+    identifiers = [user_id],
+    fallback = "uuid"
+)]
+async fn fallback_concurrency_handler(
+    user_id: &str,
+    hold: std::time::Duration,
+) -> Result<(), FlowGuardError> {
+    tokio::time::sleep(hold).await;
+    Ok(())
+}
+
+#[flow_control(rate = "1/s", on_exceed = "delay", delay_ms = 200)]
+async fn soft_limited_handler() -> Result<u32, FlowGuardError> {
+    Ok(7)
+}
+
+#[flow_control(rate = "1/s", on_reject = "to_app_error")]
+async fn custom_error_handler() -> Result<(), AppError> {
+    Ok(())
+}
+
+#[flow_control(rate = "1/s", methods = ["POST"], method = method)]
+async fn method_scoped_handler(method: &str) -> Result<(), FlowGuardError> {
+    Ok(())
+}
+
+#[flow_control(rate = "1/s", identifiers = [token], key_strategy = "hash")]
+async fn hashed_key_handler(token: &str) -> Result<(), FlowGuardError> {
+    Ok(())
+}
+
+#[flow_control(rate = "3/s", scope = "global")]
+async fn globally_limited_handler(user_id: &str) -> Result<String, FlowGuardError> {
+    Ok(user_id.to_string())
+}
+
+#[flow_control(rate = "1/s", fallback = "ip", client_ip = addr)]
+async fn ip_fallback_handler(addr: &str) -> Result<(), FlowGuardError> {
+    Ok(())
+}
+
+#[flow_control(
+    rate = "1/s",
+    identifiers = [user_id],
+    fallback = "ip",
+    client_ip = addr
+)]
+async fn user_or_ip_handler(user_id: &str, addr: &str) -> Result<(), FlowGuardError> {
+    Ok(())
+}
+
+#[flow_control(rate = "1/s", identifiers = [user_id], fallback = "uuid")]
+async fn uuid_fallback_handler(user_id: &str) -> Result<(), FlowGuardError> {
+    Ok(())
+}
+
+#[test]
+fn test_sync_fn_enforces_rate_limit() {
+    assert_eq!(sync_rate_limited().unwrap(), 42);
+    assert_eq!(sync_rate_limited().unwrap(), 42);
+    assert!(matches!(
+        sync_rate_limited(),
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_sync_fn_inside_runtime_is_error() {
+    // 在 Tokio 运行时内部调用同步路径会返回错误，而不是跳过检查
+    assert!(matches!(sync_rate_limited(), Err(FlowGuardError::Other(_))));
+}
+
+#[tokio::test]
+async fn test_async_fn_enforces_rate_limit() {
+    assert_eq!(async_rate_limited().await.unwrap(), 7);
+}
+
+#[tokio::test]
+async fn test_on_reject_maps_error_type() {
+    assert!(custom_error_handler().await.is_ok());
+    assert!(matches!(
+        custom_error_handler().await,
+        Err(AppError::Throttled(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_identifiers_use_runtime_values() {
+    assert_eq!(per_user_handler("alice", 1).await.unwrap(), "alice@1");
+    // 同一用户与租户共享限流键
+    assert!(matches!(
+        per_user_handler("alice", 1).await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+    // 不同的参数值生成不同的限流键
+    assert!(per_user_handler("bob", 1).await.is_ok());
+    assert!(per_user_handler("alice", 2).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_methods_only_limit_listed_methods() {
+    assert!(method_scoped_handler("POST").await.is_ok());
+    assert!(matches!(
+        method_scoped_handler("post").await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+    // 未列出的方法不受限流
+    for _ in 0..5 {
+        assert!(method_scoped_handler("GET").await.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_hash_key_strategy_separates_long_identifiers() {
+    let prefix = "t".repeat(200);
+    let first = format!("{}-first", prefix);
+    let second = format!("{}-second", prefix);
+
+    assert!(hashed_key_handler(&first).await.is_ok());
+    // 共享前缀的另一个长标识符使用独立的限流键
+    assert!(hashed_key_handler(&second).await.is_ok());
+    assert!(matches!(
+        hashed_key_handler(&first).await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_global_scope_shares_one_bucket() {
+    // 不同调用方共享同一个全局额度
+    for user_id in ["alice", "bob", "carol"] {
+        assert_eq!(globally_limited_handler(user_id).await.unwrap(), user_id);
+    }
+    for user_id in ["dave", "erin"] {
+        assert!(matches!(
+            globally_limited_handler(user_id).await,
+            Err(FlowGuardError::RateLimitExceeded(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_ip_fallback_separates_callers() {
+    // 没有主标识符时按客户端 IP 区分限流桶
+    assert!(ip_fallback_handler("10.0.0.1").await.is_ok());
+    assert!(ip_fallback_handler("10.0.0.2").await.is_ok());
+    assert!(matches!(
+        ip_fallback_handler("10.0.0.1").await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+    // IP 也缺失时按请求回退，使用不缓存的一次性限流器
+    assert!(ip_fallback_handler("").await.is_ok());
+    assert!(ip_fallback_handler("").await.is_ok());
+    assert_eq!(limiter_count("rate:ip_fallback_handler:"), 2);
+}
+
+#[tokio::test]
+async fn test_ip_fallback_separates_ipv6_callers() {
+    // 截断策略下两者都会被清洗为 "2001db81"
+    assert!(ip_fallback_handler("2001:db8::1").await.is_ok());
+    assert!(ip_fallback_handler("2001:db::81").await.is_ok());
+    assert!(matches!(
+        ip_fallback_handler("2001:db8::1").await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+    assert!(matches!(
+        ip_fallback_handler("2001:db::81").await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_ip_fallback_key_does_not_collide_with_identifier() {
+    assert!(user_or_ip_handler("", "1.2.3.4").await.is_ok());
+    // 取值与 IP 键清洗结果相同的标识符使用自己的桶
+    assert!(user_or_ip_handler("ip1.2.3.4", "").await.is_ok());
+    assert!(matches!(
+        user_or_ip_handler("", "1.2.3.4").await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_uuid_fallback_only_when_identifier_missing() {
+    assert!(uuid_fallback_handler("alice").await.is_ok());
+    assert!(matches!(
+        uuid_fallback_handler("alice").await,
+        Err(FlowGuardError::RateLimitExceeded(_))
+    ));
+    for _ in 0..10 {
+        assert!(uuid_fallback_handler("").await.is_ok());
+    }
+    assert_eq!(limiter_count("rate:uuid_fallback_handler:"), 1);
+}
+
+#[tokio::test]
+async fn test_fallback_concurrency_per_request_without_identifier() {
+    use std::time::Duration;
+
+    let holder = tokio::spawn(fallback_concurrency_handler(
+        "bob",
+        Duration::from_millis(200),
+    ));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(matches!(
+        fallback_concurrency_handler("bob", Duration::ZERO).await,
+        Err(FlowGuardError::ConcurrencyLimitExceeded(_))
+    ));
+
+    // 没有标识符的调用各自使用一次性限制器，互不占用并发额度
+    let anonymous = tokio::spawn(fallback_concurrency_handler("", Duration::from_millis(100)));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(fallback_concurrency_handler("", Duration::ZERO)
+        .await
+        .is_ok());
+    anonymous.await.unwrap().unwrap();
+    holder.await.unwrap().unwrap();
+}
+
+/// 全局限流器管理器中键带有指定前缀的速率与配额限流器数量
+fn limiter_count(prefix: &str) -> usize {
+    limiteron::GLOBAL_LIMITER_MANAGER
+        .snapshots()
+        .iter()
+        .filter(|(key, _)| key.starts_with(prefix))
+        .count()
+}
+
+#[tokio::test]
+async fn test_soft_limit_delays_instead_of_rejecting() {
+    use std::time::{Duration, Instant};
+
+    let started = Instant::now();
+    assert_eq!(soft_limited_handler().await.unwrap(), 7);
+    assert!(started.elapsed() < Duration::from_millis(200));
+
+    // 超出速率限制的调用等待后继续执行
+    let started = Instant::now();
+    assert_eq!(soft_limited_handler().await.unwrap(), 7);
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn test_concurrency_acquire_timeout() {
+    use std::time::Duration;
+
+    // 占用许可的调用在超时前结束，等待者获得许可
+    let holder = tokio::spawn(bounded_wait_handler(Duration::from_millis(50)));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(bounded_wait_handler(Duration::ZERO).await.is_ok());
+    holder.await.unwrap().unwrap();
+
+    // 许可一直被占用，等待者超时
+    let holder = tokio::spawn(bounded_wait_handler(Duration::from_secs(2)));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(matches!(
+        bounded_wait_handler(Duration::ZERO).await,
+        Err(FlowGuardError::ConcurrencyLimitExceeded(_))
+    ));
+    holder.await.unwrap().unwrap();
+    assert!(bounded_wait_handler(Duration::ZERO).await.is_ok());
+}
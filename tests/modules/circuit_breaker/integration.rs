@@ -3,7 +3,7 @@
 //! 测试熔断器模块的基本功能
 
 #[cfg(feature = "circuit-breaker")]
-use limiteron::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use limiteron::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, TripPolicy};
 use std::time::Duration;
 
 /// 测试熔断器模块导入
@@ -16,6 +16,7 @@ async fn test_circuit_breaker_module_import() {
         success_threshold: 2,
         timeout: Duration::from_secs(5),
         half_open_max_calls: 3,
+        trip_policy: TripPolicy::ConsecutiveFailures,
     };
 
     #[allow(unused_variables)]
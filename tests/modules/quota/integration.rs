@@ -19,6 +19,8 @@ async fn test_quota_controller_module_import() {
         allow_overdraft: false,
         overdraft_limit_percent: 0,
         alert_config: Default::default(),
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     #[allow(unused_variables)]
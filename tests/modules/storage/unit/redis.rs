@@ -167,6 +167,8 @@ async fn test_redis_lua_atomicity() {
         allow_overdraft: false,
         overdraft_limit_percent: 0,
         alert_config: Default::default(),
+        rollover: None,
+        cost_weights: Default::default(),
     };
 
     let controller = QuotaController::new(storage, quota_config);